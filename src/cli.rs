@@ -79,13 +79,47 @@ pub enum ListWhat {
     Commit,
 }
 
+#[derive(Subcommand, Debug, Clone)]
+pub enum BaseCmd {
+    /// Move the stack onto REF: rebase it, save REF as the repo config `base`, and republish
+    Set {
+        /// New base, for example `origin/release-2.0`
+        #[arg(value_name = "REF")]
+        base: String,
+        /// Create a local backup tag at current HEAD before rebasing
+        #[arg(long)]
+        safe: bool,
+        #[command(flatten)]
+        dry_run: DryRunArgs,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum BackupCmd {
+    /// List backup tags and branches with the command that created them, age, and whether HEAD
+    /// contains them
+    #[command(alias = "ls")]
+    List,
+    /// Delete backup refs older than a number of days or already contained in HEAD
+    Prune {
+        /// Delete backups at least this many days old
+        #[arg(long, value_name = "DAYS", required_unless_present = "in_head")]
+        older_than: Option<u32>,
+        /// Delete backups whose tip is an ancestor of the current HEAD
+        #[arg(long)]
+        in_head: bool,
+        #[command(flatten)]
+        dry_run: DryRunArgs,
+    },
+}
+
 #[derive(Subcommand, Debug)]
 pub enum Cmd {
     /// Build/refresh stacked PRs
     #[command(alias = "u")]
     Update {
         /// Source ref to read commits from (if building from tags)
-        #[arg(long, default_value = "HEAD")]
+        #[arg(long, visible_alias = "from-ref", default_value = "HEAD")]
         from: String,
 
         /// Don’t create PRs, only (re)create branches
@@ -110,6 +144,29 @@ pub enum Cmd {
         #[arg(long)]
         allow_branch_reuse: bool,
 
+        /// Open newly created PRs as drafts (existing PRs keep their stage); to draft a single
+        /// group, put `spr-draft` in its first commit message instead
+        #[arg(long, conflicts_with_all = ["no_pr", "offline"])]
+        draft: bool,
+
+        /// Skip GitHub and queue the PR updates for `spr flush` (for when GitHub is unreachable)
+        #[arg(long, conflicts_with = "no_pr")]
+        offline: bool,
+
+        /// With --dry-run, also write the plan (pushes, PR creations and edits, warnings) to FILE
+        /// as Markdown
+        #[arg(
+            long,
+            value_name = "FILE",
+            requires = "dry_run",
+            conflicts_with = "offline"
+        )]
+        plan_out: Option<PathBuf>,
+
+        /// Lift a `spr freeze` of the stack before updating
+        #[arg(long)]
+        thaw: bool,
+
         #[command(flatten)]
         dry_run: DryRunArgs,
 
@@ -118,6 +175,12 @@ pub enum Cmd {
         extent: Option<Extent>,
     },
 
+    /// Push branches and update PRs queued by `spr update --offline` for the current stack
+    Flush {
+        #[command(flatten)]
+        dry_run: DryRunArgs,
+    },
+
     /// Restack PRs by rebasing the top commits after the bottom N PR groups onto the latest base
     #[command(
         long_about = "Restack PRs by rebasing the top commits after the bottom N PR groups onto the latest base.\n\nWhen `restack_conflict` is `halt`, `spr restack` leaves the temp rewrite worktree in place on conflict, writes a resume file under the repository common Git directory, and prints `spr resume <path>`. Resolve conflicts in that temp worktree, stage the resolution, and hand control back to `spr` with the printed resume command.\n\nWhen `restack_conflict` is `rollback`, `spr restack` preserves the historical cleanup-on-conflict behavior and removes the temp rewrite state instead."
@@ -126,9 +189,24 @@ pub enum Cmd {
         /// Keep groups through this selector in place and rebuild only the groups above it
         #[arg(
             long,
+            required_unless_present = "interactive",
             value_name = "N|0|bottom|top|last|all|name|pr:<label>|branch:<branch-name>"
         )]
-        after: crate::selectors::AfterSelector,
+        after: Option<crate::selectors::AfterSelector>,
+
+        /// Choose `--after` from a numbered list of groups and their PR states, then confirm the
+        /// printed plan before rebasing
+        #[arg(
+            short = 'i',
+            long,
+            conflicts_with_all = ["after", "preview", "check"]
+        )]
+        interactive: bool,
+
+        /// Restack the stack on this local branch instead of the checked-out one, moving the
+        /// branch by ref without touching the worktree
+        #[arg(long, visible_alias = "from-ref", value_name = "REF")]
+        from: Option<String>,
 
         /// Create a local backup tag at current HEAD before rebasing
         #[arg(long)]
@@ -138,10 +216,40 @@ pub enum Cmd {
         #[arg(long)]
         preview: bool,
 
+        /// After a completed restack, push the rebuilt branches and relink PR bases like `spr update`
+        #[arg(long, conflicts_with = "preview")]
+        update: bool,
+
+        /// Trial-run the replay in a throwaway worktree and report the first conflict without touching the branch
+        #[arg(long, conflicts_with_all = ["preview", "update", "safe"])]
+        check: bool,
+
+        #[command(flatten)]
+        dry_run: DryRunArgs,
+    },
+
+    /// Fetch, restack the whole stack onto the latest base, and publish it like `spr update`
+    #[command(
+        long_about = "Fetch, restack the whole stack onto the latest base, and publish it like `spr update`.\n\n`spr sync` is `spr restack --after 0 --update` in one command: it fetches `origin`, rebuilds every group onto the latest base, then pushes the rebuilt branches and refreshes PR bases and stack blocks. Conflicts follow `restack_conflict` like `spr restack`; a suspended sync publishes nothing, so run `spr update` after `spr resume` completes."
+    )]
+    Sync {
+        /// Create a local backup tag at current HEAD before rebasing
+        #[arg(long)]
+        safe: bool,
+
         #[command(flatten)]
         dry_run: DryRunArgs,
     },
 
+    /// Change the stack's base branch
+    #[command(
+        long_about = "Change the stack's base branch.\n\n`spr base set <REF>` rebuilds every group on top of REF (commits that exist only on the old base stay behind), writes REF as `base` in the repository `.spr_multicommit_cfg.yml`, then publishes the stack like `spr update`, which retargets the bottom PR to REF and refreshes every PR's stack block. Conflicts follow `restack_conflict` like `spr restack`; after `spr resume`, run `spr update` to publish."
+    )]
+    Base {
+        #[command(subcommand)]
+        action: BaseCmd,
+    },
+
     /// Adopt rewritten lower-stack history as the new prefix of its live stack
     #[command(
         long_about = "Adopt rewritten lower-stack history as the new prefix of its existing live stack.\n\nRun this from a checkout whose committed history through `HEAD` is the rewritten bottom prefix you want to keep. `spr adopt-prefix` resolves the owning live stack from explicit `pr:` and `branch:` selectors, verifies that the candidate still represents the same live bottom sequence, then rebuilds the remaining raw history above the verified stack branch. Adoption preserves the verified live stack merge base and rejects candidates that would change the publishable selector sequence.\n\nThe candidate checkout is the prefix source, not the rebuilt destination branch. The verified owning full-stack branch must not be checked out in another worktree while adoption runs. `spr adopt-prefix` is local-only and does not update GitHub; run `spr update` after inspecting the result."
@@ -159,6 +267,21 @@ pub enum Cmd {
         dry_run: DryRunArgs,
     },
 
+    /// Move every remote branch under the prefix to a new prefix, reopening their PRs
+    #[command(
+        long_about = "Move every remote branch under the prefix to a new prefix, reopening their PRs.\n\nEach branch under the current prefix is pushed under `<NEW_PREFIX>`. GitHub cannot change a PR's head branch, so each open PR from a moved branch is opened again from the new branch with the same title, body, draft state, and renamed base, and the old PR is closed with a comment linking its replacement. Open PRs from other branches whose base moves are retargeted. The old branches are deleted last, and the new prefix is written to the repository config unless `--keep-config` is given."
+    )]
+    RenamePrefix {
+        /// Prefix to move the branches to, e.g. `team/dank/`
+        #[arg(value_name = "NEW_PREFIX")]
+        new_prefix: String,
+        /// Do not write the new prefix to the repository config
+        #[arg(long)]
+        keep_config: bool,
+        #[command(flatten)]
+        dry_run: DryRunArgs,
+    },
+
     /// Drop bottom PR groups whose GitHub PRs already merged, without landing or mutating PRs
     #[command(
         long_about = "Drop bottom PR groups whose GitHub PRs already merged, without landing or mutating PRs.\n\n`spr drop-merged-prefix` is local post-merge maintenance. It reads GitHub PR state, verifies each dropped PR's GitHub merge commit is contained in the configured SPR base, and then rewrites only the checked-out local stack.\n\nIt does not merge, close, retarget, comment on, or push GitHub PRs. After inspecting the rewritten stack, run `spr update` to publish remaining PR branch updates."
@@ -174,7 +297,7 @@ pub enum Cmd {
 
     /// Absorb commits appended to canonical local per-PR branches back into the owning stack branch
     #[command(
-        long_about = "Absorb commits appended to canonical local per-PR branches back into the owning stack branch.\n\nIf you append commits to the end of a local PR branch such as `user-spr/alpha`, run `spr absorb` from either that branch or the owning stack branch. When the invoking checkout's selector sequence identifies one verified live stack, `spr` rebuilds that owning stack so new commits from every absorbable local PR branch become part of their matching PR groups. The PR-group order stays the same.\n\nThis command is local-only: it rewrites the owning stack branch, creates a backup tag, and does not update GitHub. After checking the result, run `spr update`.\n\nOnly each group's exact resolved local branch is considered. If one of those branches still points at rewritten-equivalent stack commits, `spr absorb` accepts that prefix only when the branch still descends from the same stack merge-base and the matched pre-tail commit ends at the same tree as the canonical stack prefix. A no-op rewritten match is reported as `skip (rewritten-equivalent prefix)`, and only commits appended above that proven prefix are absorbed. `spr absorb` also refuses to operate when two live PR groups would derive concrete branch names that differ only by case.\n\nUse `--from <N|name|pr:<label>|branch:<branch-name>>` to constrain absorb to one PR group and every group above it. For example, `spr absorb --from pr:beta` considers only the `pr:beta..top` suffix and leaves unrelated lower-group branch tails out of scope.\n\nExample:\n- The owning stack has three PR groups: `pr:alpha`, `pr:beta`, and `pr:gamma`.\n- Check out `user-spr/alpha` and append 2 commits.\n- Run `spr absorb` from `user-spr/alpha`.\n- Result: the 2 new commits are folded into the `pr:alpha` group on the owning stack branch, and absorb still scans `pr:beta` and `pr:gamma` for their own append-only tails.\n- Then run `spr update`.\n\nOn cherry-pick conflict, `spr absorb` leaves the temp rewrite worktree in place, writes a resume file under the repository common Git directory, and prints `spr resume <path>`. Resolve conflicts in that temp worktree, stage the resolution, and run the printed resume command.\n\nAdvanced:\n- By default, absorb blocks copied later commits when replaying the stack would become empty or ambiguous.\n- `--allow-replayed-duplicates` allows an earlier copied non-seed follow-up commit to coexist with its later replayed copy by keeping both commits in the rewritten stack."
+        long_about = "Absorb commits appended to canonical local per-PR branches back into the owning stack branch.\n\nIf you append commits to the end of a local PR branch such as `user-spr/alpha`, run `spr absorb` from either that branch or the owning stack branch. When the invoking checkout's selector sequence identifies one verified live stack, `spr` rebuilds that owning stack so new commits from every absorbable local PR branch become part of their matching PR groups. The PR-group order stays the same.\n\nThis command is local-only: it rewrites the owning stack branch, creates a backup tag, and does not update GitHub. After checking the result, run `spr update`.\n\nOnly each group's exact resolved local branch is considered. If one of those branches still points at rewritten-equivalent stack commits, `spr absorb` accepts that prefix only when the branch still descends from the same stack merge-base and the matched pre-tail commit ends at the same tree as the canonical stack prefix. A no-op rewritten match is reported as `skip (rewritten-equivalent prefix)`, and only commits appended above that proven prefix are absorbed. `spr absorb` also refuses to operate when two live PR groups would derive concrete branch names that differ only by case.\n\nUse `--from <N|name|pr:<label>|branch:<branch-name>>` to constrain absorb to one PR group and every group above it. For example, `spr absorb --from pr:beta` considers only the `pr:beta..top` suffix and leaves unrelated lower-group branch tails out of scope.\n\nExample:\n- The owning stack has three PR groups: `pr:alpha`, `pr:beta`, and `pr:gamma`.\n- Check out `user-spr/alpha` and append 2 commits.\n- Run `spr absorb` from `user-spr/alpha`.\n- Result: the 2 new commits are folded into the `pr:alpha` group on the owning stack branch, and absorb still scans `pr:beta` and `pr:gamma` for their own append-only tails.\n- Then run `spr update`.\n\nOn cherry-pick conflict, `spr absorb` leaves the temp rewrite worktree in place, writes a resume file under the repository common Git directory, and prints `spr resume <path>`. Resolve conflicts in that temp worktree, stage the resolution, and run the printed resume command.\n\nAdvanced:\n- By default, absorb blocks copied later commits when replaying the stack would become empty or ambiguous.\n- `--allow-replayed-duplicates` allows an earlier copied non-seed follow-up commit to coexist with its later replayed copy by keeping both commits in the rewritten stack.\n\nWith `--staged`, `spr absorb` instead folds staged hunks into the PR groups whose commits last touched their lines (by `git blame`), committing them as `fixup!` commits at each group's tail. Hunks that do not belong to exactly one group stay staged."
     )]
     Absorb {
         /// Absorb only this PR group and every group above it
@@ -189,6 +312,15 @@ pub enum Cmd {
         #[arg(long, conflicts_with = "dry_run")]
         query_changed_branches: bool,
 
+        /// Fold staged hunks into the PR groups whose commits last touched their lines (by blame)
+        /// instead of absorbing local PR branch tails
+        #[arg(long, conflicts_with_all = ["from", "allow_replayed_duplicates", "query_changed_branches"])]
+        staged: bool,
+
+        /// With --staged, create a local backup tag at current HEAD before rewriting
+        #[arg(long, requires = "staged")]
+        safe: bool,
+
         #[command(flatten)]
         dry_run: DryRunArgs,
     },
@@ -199,6 +331,15 @@ pub enum Cmd {
         #[arg(long, value_name = "N|name|pr:<label>|branch:<branch-name>")]
         from: Option<crate::selectors::GroupSelector>,
 
+        /// Prepare the stack on this local branch instead of the checked-out one (`--from`
+        /// already selects a PR group here)
+        #[arg(long = "from-ref", value_name = "REF")]
+        from_ref: Option<String>,
+
+        /// Keep intentionally empty groups as empty commits instead of dropping them
+        #[arg(long)]
+        allow_empty: bool,
+
         // Additional selection is provided via global --until/--exact flags.
         #[command(flatten)]
         dry_run: DryRunArgs,
@@ -206,12 +347,43 @@ pub enum Cmd {
 
     /// Resume a suspended local rewrite from a resume-state file
     #[command(
-        long_about = "Resume a suspended local rewrite from a resume-state file.\n\nRun `spr resume <path>` with the exact path printed by `spr restack`, `spr adopt-prefix`, `spr absorb`, `spr move`, or `spr fix-pr` after a cherry-pick conflict. The supported workflow is: resolve the conflict in the printed temp rewrite worktree, stage the resolution, and then run the printed `spr resume <path>` command from any worktree in the same repository.\n\nThe resume file lives under the repository common Git directory, usually `.git/spr/resume/`. `spr resume` tolerates one accidental manual `git cherry-pick --continue` for the paused step, but broader manual replay edits are rejected."
+        visible_alias = "continue",
+        long_about = "Resume a suspended local rewrite from a resume-state file.\n\nRun `spr resume <path>` with the exact path printed by `spr restack`, `spr adopt-prefix`, `spr absorb`, `spr move`, or `spr fix-pr` after a cherry-pick conflict. The supported workflow is: resolve the conflict in the printed temp rewrite worktree, stage the resolution, and then run the printed `spr resume <path>` command from any worktree in the same repository.\n\nThe resume file lives under the repository common Git directory, usually `.git/spr/resume/`. Without a path, `spr resume` (alias `spr continue`) picks the only suspended rewrite in the repository. `spr resume` tolerates one accidental manual `git cherry-pick --continue` for the paused step, but broader manual replay edits are rejected."
     )]
     Resume {
-        /// Explicit path to the suspended rewrite's resume-state JSON file
+        /// Explicit path to the suspended rewrite's resume-state JSON file (default: the only
+        /// suspended rewrite in the repository)
         #[arg(value_name = "PATH")]
-        path: PathBuf,
+        path: Option<PathBuf>,
+    },
+
+    /// Discard a suspended local rewrite, leaving the original branch as it was
+    #[command(
+        long_about = "Discard a suspended local rewrite, leaving the original branch as it was.\n\n`spr abort` aborts the paused cherry-pick, removes the temp rewrite worktree, its temp branch, and the resume file, and restores any local changes the rewrite auto-stashed. The original branch only moves when a rewrite finishes, so nothing else needs undoing. Without a path it picks the only suspended rewrite in the repository."
+    )]
+    Abort {
+        /// Explicit path to the suspended rewrite's resume-state JSON file (default: the only
+        /// suspended rewrite in the repository)
+        #[arg(value_name = "PATH")]
+        path: Option<PathBuf>,
+    },
+
+    /// Write a starter `.spr_multicommit_cfg.yml` from the detected default branch and `$USER`
+    #[command(
+        long_about = "Write a starter `.spr_multicommit_cfg.yml` from the detected default branch and `$USER`.\n\nThe base is proposed from the default branch GitHub reports for the repository (falling back to `<remote>/HEAD`), the prefix as `<user>-spr/`, and the land mode as `flatten`. From a terminal each proposal can be accepted with Enter or replaced. The file is written to the repository root, or with `--global` to `$HOME`, and lists the other common keys commented out."
+    )]
+    Init {
+        /// Write `$HOME/.spr_multicommit_cfg.yml` instead of the repository's config file
+        #[arg(long)]
+        global: bool,
+
+        /// Accept the proposed values without prompting
+        #[arg(long, short = 'y')]
+        yes: bool,
+
+        /// Replace an existing config file
+        #[arg(long)]
+        force: bool,
     },
 
     /// List entities and halt early on case-colliding concrete branch names
@@ -219,15 +391,45 @@ pub enum Cmd {
     List {
         #[command(subcommand)]
         what: ListWhat,
+
+        /// List the stack at this ref instead of `HEAD`
+        #[arg(long, global = true, visible_alias = "from-ref", value_name = "REF")]
+        from: Option<String>,
+
+        /// Only show groups with commits newer than this date or not contained in this ref
+        #[arg(long, global = true, value_name = "REF|DATE")]
+        since: Option<String>,
     },
 
-    /// Status overview (alias for `list pr`) with the same early concrete branch-collision guard
+    /// Status overview: `list pr` plus branch drift, PR base chaining, and how far the stack is
+    /// behind its base (same early concrete branch-collision guard)
     #[command(alias = "stat")]
-    Status,
+    Status {
+        /// Keep polling until every open PR's checks pass or any check fails
+        #[arg(long)]
+        watch: bool,
+        /// Seconds between polls in `--watch` mode
+        #[arg(long, value_name = "SECONDS", default_value_t = 30, requires = "watch")]
+        interval: u64,
+        /// Send a desktop notification (or ring the terminal bell) when `--watch` finishes
+        #[arg(long, requires = "watch")]
+        notify: bool,
+    },
 
     /// Reconcile local per-PR branches with the current stack using the configured sync policy
     SyncLocalBranches,
 
+    /// Check a published stack's PR base chain, stack blocks, and group markers (for CI)
+    Verify {
+        /// Running in GitHub Actions: read the PR from the workflow event and print problems as
+        /// error annotations
+        #[arg(long)]
+        ci: bool,
+        /// PR whose stack to verify (required without `--ci`)
+        #[arg(long, value_name = "N")]
+        pr: Option<u64>,
+    },
+
     /// Find the owning stack branch for a PR branch or report that the target is already a stack branch
     #[command(
         long_about = "Find the owning stack branch for a PR branch using repo-local stack metadata.\n\nTargets may be omitted (use the current branch), a local branch name, a remote-qualified branch name such as `origin/dank-spr/alpha`, or a GitHub PR URL. This command is strict and metadata-backed: it does not scan unrelated branches or guess a likely owner."
@@ -248,6 +450,38 @@ pub enum Cmd {
         /// Skip automatic restack after landing (default: restack remaining commits with `--after N`)
         #[arg(long = "no-restack")]
         no_restack: bool,
+        /// After the follow-up restack completes, push the remaining groups and relink their PRs
+        /// like `spr update`, so the stack reflects the land right away
+        #[arg(
+            long,
+            visible_alias = "restack",
+            conflicts_with_all = ["no_restack", "local_only"]
+        )]
+        update: bool,
+        /// Skip this PR in the landed range (repeatable); skipped groups move to the top of the stack first
+        #[arg(long, value_name = "N|name|pr:<label>|branch:<branch-name>")]
+        except: Vec<crate::selectors::GroupSelector>,
+        /// Land the stack on this local branch instead of the checked-out one; the follow-up
+        /// restack moves that branch by ref
+        #[arg(long, global = true, visible_alias = "from-ref", value_name = "REF")]
+        from: Option<String>,
+        /// Squash each landed group onto a local copy of base on branch `spr-land-local` instead
+        /// of merging on GitHub, so the result can be built and tested first
+        #[arg(long = "local-only", conflicts_with_all = ["unsafe", "except"])]
+        local_only: bool,
+        /// Poll CI and review status with backoff and land PRs bottom-up as each passes and is
+        /// approved; stops early when a check fails or changes are requested
+        #[arg(long, conflicts_with_all = ["unsafe", "local_only"])]
+        wait: bool,
+        /// With `--wait`, give up after this many minutes; PRs that landed by then stay landed
+        #[arg(long, value_name = "MINUTES", default_value_t = 120, requires = "wait")]
+        timeout: u64,
+        /// Land without the confirmation prompt that `confirm_land` config enables
+        #[arg(long, short = 'y')]
+        yes: bool,
+        /// Lift a `spr freeze` of the stack before landing
+        #[arg(long, conflicts_with = "local_only")]
+        thaw: bool,
         #[command(flatten)]
         dry_run: DryRunArgs,
     },
@@ -258,18 +492,126 @@ pub enum Cmd {
         dry_run: DryRunArgs,
     },
 
+    /// Create (or refresh) a GitHub issue with a checklist of every PR in the stack
+    TrackIssue {
+        /// Issue title when creating (default: `Stack: <branch>`)
+        #[arg(long)]
+        title: Option<String>,
+        #[command(flatten)]
+        dry_run: DryRunArgs,
+    },
+
     /// Delete remote branches with the configured prefix whose PRs are all closed
     #[command(alias = "clean")]
     Cleanup {
+        /// Also close open PRs under the prefix whose branches match no group in the local stack
+        #[arg(long = "close-orphaned-prs")]
+        close_orphaned_prs: bool,
+        /// Close the orphaned PRs without the confirmation prompt
+        #[arg(long, short = 'y', requires = "close_orphaned_prs")]
+        yes: bool,
+        #[command(flatten)]
+        dry_run: DryRunArgs,
+    },
+
+    /// List files changed by one PR group, or by every group when no target is given
+    Files {
+        /// Target local PR number or group selector (default: every group)
+        target: Option<crate::selectors::GroupSelector>,
+        /// Include added/deleted line counts per file
+        #[arg(long)]
+        stat: bool,
+    },
+
+    /// Show the diff of one PR group, from its first commit's parent to its last commit
+    Diff {
+        /// Target local PR number or group selector
+        target: crate::selectors::GroupSelector,
+        /// Show a diffstat instead of the full diff
+        #[arg(long)]
+        stat: bool,
+    },
+
+    /// Record the stack's remote branch SHAs and refuse `spr update`/`spr land` until `spr thaw`
+    Freeze {
+        /// Why the stack is frozen, shown when an update or land is refused
+        #[arg(long, value_name = "TEXT")]
+        reason: Option<String>,
+        #[command(flatten)]
+        dry_run: DryRunArgs,
+    },
+
+    /// Lift a `spr freeze`, reporting group branches that moved on the remote meanwhile
+    Thaw {
         #[command(flatten)]
         dry_run: DryRunArgs,
     },
 
+    /// Create or reset a local branch at a PR group's tip and check it out
+    Checkout {
+        /// Target local PR number or group selector
+        target: crate::selectors::GroupSelector,
+        /// Branch to use instead of the group's PR branch name
+        #[arg(long, value_name = "NAME")]
+        branch: Option<String>,
+        /// Reset the branch even when it holds commits that are not in the group
+        #[arg(long)]
+        force: bool,
+        #[command(flatten)]
+        dry_run: DryRunArgs,
+    },
+
+    /// Compare a PR group's local commits with its last pushed version using `git range-diff`
+    RangeDiff {
+        /// Target local PR number or group selector
+        target: crate::selectors::GroupSelector,
+    },
+
+    /// List or prune the backup refs left by `--safe` rewrites and local PR branch sync
+    Backup {
+        #[command(subcommand)]
+        action: BackupCmd,
+    },
+
+    /// Show how the local range parses into PR groups and ignore blocks (`--json` for scripts)
+    Parse,
+
+    /// Show the PR a commit landed in, as recorded by `spr land`
+    Landed {
+        /// Commit to look up: a commit landed by `spr land`, or the merge commit it created
+        #[arg(value_name = "SHA")]
+        sha: String,
+    },
+
+    /// Report per-PR review metrics: time to merge, review rounds, and force pushes
+    Stats {
+        /// Report recently merged PRs under the prefix instead of the current stack
+        #[arg(long)]
+        history: bool,
+        /// Number of most recently merged PRs to scan with --history
+        #[arg(long, default_value_t = 50, requires = "history")]
+        limit: usize,
+        /// Only report groups with commits newer than this date or not contained in this ref
+        #[arg(long, value_name = "REF|DATE", conflicts_with = "history")]
+        since: Option<String>,
+    },
+
     /// Move the last M commits (top of stack) to the tail of a selected PR group
     #[command(visible_alias = "fix")]
     FixPr {
         /// Target local PR number or group selector
-        target: crate::selectors::GroupSelector,
+        #[arg(required_unless_present_any = ["after", "before"], conflicts_with_all = ["after", "before"])]
+        target: Option<crate::selectors::GroupSelector>,
+        /// Same as the positional target: move the commits to the tail of this PR group
+        #[arg(long, value_name = "C|name|pr:<label>|branch:<branch-name>")]
+        after: Option<crate::selectors::GroupSelector>,
+        /// Move the commits to the tail of the PR group directly below this one
+        #[arg(
+            long,
+            value_name = "C|name|pr:<label>|branch:<branch-name>",
+            conflicts_with = "after"
+        )]
+        before: Option<crate::selectors::GroupSelector>,
         /// Number of top commits to move to the selected PR group's tail
         #[arg(short = 't', long = "tail", default_value_t = 1)]
         tail: usize,
@@ -280,7 +622,202 @@ pub enum Cmd {
         dry_run: DryRunArgs,
     },
 
-    /// Reorder local PR groups by moving one or a range to come after a target PR, halting early on case-colliding concrete branch names
+    /// Import an existing open PR's commits into the stack as a new group
+    ImportPr {
+        /// Number of the PR to import
+        number: u64,
+        /// Insert the imported group after this PR position (default: top of the stack)
+        #[arg(
+            long,
+            value_name = "C|bottom|top|last|all|name|pr:<label>|branch:<branch-name>"
+        )]
+        after: Option<crate::selectors::AfterSelector>,
+        /// Seed the group with `pr:<TAG>` and publish a new prefix-branch PR instead of adopting
+        /// the PR's own branch
+        #[arg(long)]
+        tag: Option<String>,
+        /// Create a local backup tag at current HEAD before rewriting
+        #[arg(long)]
+        safe: bool,
+        #[command(flatten)]
+        dry_run: DryRunArgs,
+    },
+
+    /// Commit staged changes as a fixup of a PR group and move the commit to that group's tail
+    Fixup {
+        /// Target local PR number or group selector
+        target: crate::selectors::GroupSelector,
+        /// Commit message (default: `fixup! <PR title>`)
+        #[arg(short = 'm', long)]
+        message: Option<String>,
+        /// After relocating the fix, publish branches and PRs up to and including the target group
+        #[arg(long)]
+        update: bool,
+        /// Create a local backup tag at current HEAD before rewriting
+        #[arg(long)]
+        safe: bool,
+        #[command(flatten)]
+        dry_run: DryRunArgs,
+    },
+
+    /// Amend working-tree changes (or a commit) into a PR group's tip commit and replay the stack
+    /// above it
+    Amend {
+        /// Target local PR number or group selector
+        target: crate::selectors::GroupSelector,
+        /// Amend this commit (above the target in the stack) instead of the working tree's tracked
+        /// changes
+        #[arg(long, value_name = "REV")]
+        commit: Option<String>,
+        /// After amending, publish branches and PRs up to and including the target group
+        #[arg(long)]
+        update: bool,
+        /// Create a local backup tag at current HEAD before rewriting
+        #[arg(long)]
+        safe: bool,
+        #[command(flatten)]
+        dry_run: DryRunArgs,
+    },
+
+    /// Squash consecutive commits inside one PR group into one commit and replay the stack above
+    /// it
+    SquashRange {
+        /// Inclusive range of commit indices from `spr list commit`, such as `3..5`
+        #[arg(value_name = "A..B")]
+        range: crate::commands::squash_range::CommitIndexRange,
+        /// After squashing, publish branches and PRs up to and including the owning group
+        #[arg(long)]
+        update: bool,
+        /// Create a local backup tag at current HEAD before rewriting
+        #[arg(long)]
+        safe: bool,
+        #[command(flatten)]
+        dry_run: DryRunArgs,
+    },
+
+    /// Fast-forward local groups with commits pushed to their branches from another machine
+    Pull {
+        /// Pull every group of the current stack from its pushed branch
+        #[arg(long, required = true)]
+        stack: bool,
+        /// After pulling, publish branches and PRs for the whole stack
+        #[arg(long)]
+        update: bool,
+        /// Create a local backup tag at current HEAD before rewriting
+        #[arg(long)]
+        safe: bool,
+        #[command(flatten)]
+        dry_run: DryRunArgs,
+    },
+
+    /// Edit a PR group's first commit message in the Git editor and replay the stack above it
+    Edit {
+        /// Target local PR number or group selector
+        target: crate::selectors::GroupSelector,
+        /// After rewording, publish branches and PRs up to and including the target group and
+        /// retitle its PR, so the PR title and description follow the new message
+        #[arg(long)]
+        update: bool,
+        /// Create a local backup tag at current HEAD before rewriting
+        #[arg(long)]
+        safe: bool,
+        #[command(flatten)]
+        dry_run: DryRunArgs,
+    },
+
+    /// Split a PR group into two groups at a commit boundary and replay the stack above it
+    Split {
+        /// Target local PR number or group selector
+        target: crate::selectors::GroupSelector,
+        /// Keep this many commits in the target group; the rest start the new group (prompted
+        /// when omitted)
+        #[arg(long, value_name = "K")]
+        after: Option<usize>,
+        /// Tag for the new group's `pr:<tag>` marker (default: `<target tag>-2`)
+        #[arg(long)]
+        tag: Option<String>,
+        /// After splitting, publish the stack so the new group gets its own PR
+        #[arg(long)]
+        update: bool,
+        /// Create a local backup tag at current HEAD before rewriting
+        #[arg(long)]
+        safe: bool,
+        #[command(flatten)]
+        dry_run: DryRunArgs,
+    },
+
+    /// Fold the PR group above a target into the target and replay the stack above it
+    Fold {
+        /// Target local PR number or group selector; the group above it is folded in
+        target: crate::selectors::GroupSelector,
+        /// Close the folded group's open PR with a comment pointing at the target's PR
+        #[arg(long)]
+        close: bool,
+        /// After folding, publish the stack so the target PR carries the folded commits and the
+        /// PRs above are retargeted
+        #[arg(long)]
+        update: bool,
+        /// Create a local backup tag at current HEAD before rewriting
+        #[arg(long)]
+        safe: bool,
+        #[command(flatten)]
+        dry_run: DryRunArgs,
+    },
+
+    /// Revert a landed PR as a new PR group at the top of the stack and open its PR
+    RollbackPr {
+        /// GitHub PR number of the landed PR
+        number: u64,
+        /// Tag for the revert group's `pr:<tag>` marker (default: `revert-<number>`)
+        #[arg(long)]
+        tag: Option<String>,
+        /// Only create the revert commit; do not publish the stack
+        #[arg(long)]
+        no_update: bool,
+        #[command(flatten)]
+        dry_run: DryRunArgs,
+    },
+
+    /// Backport a PR group onto another branch and open a PR that links the original
+    Cherry {
+        /// Target local PR number or group selector
+        target: crate::selectors::GroupSelector,
+        /// Branch to backport onto, e.g. `release-2.0`
+        #[arg(long, value_name = "BRANCH")]
+        onto: String,
+        /// Open the backport PR as a draft
+        #[arg(long)]
+        draft: bool,
+        #[command(flatten)]
+        dry_run: DryRunArgs,
+    },
+
+    /// Replace a PR group's first commit subject (keeping its marker) and retitle its open PR
+    Title {
+        /// Target local PR number or group selector
+        target: crate::selectors::GroupSelector,
+        /// New title
+        title: String,
+        /// Create a local backup tag at current HEAD before rewriting
+        #[arg(long)]
+        safe: bool,
+        #[command(flatten)]
+        dry_run: DryRunArgs,
+    },
+
+    /// Edit a PR group's description in the Git editor and write it to the first commit body and
+    /// the open PR
+    Describe {
+        /// Target local PR number or group selector
+        target: crate::selectors::GroupSelector,
+        /// Create a local backup tag at current HEAD before rewriting
+        #[arg(long)]
+        safe: bool,
+        #[command(flatten)]
+        dry_run: DryRunArgs,
+    },
+
+    /// Reorder local PR groups by moving one or a range to come after (or before) a target PR, halting early on case-colliding concrete branch names
     #[command(alias = "mv")]
     Move {
         /// Position or range to move: ordinal, bare selector, explicit selector, or selector range
@@ -288,15 +825,170 @@ pub enum Cmd {
         /// Target PR position to come after: number, group selector, or one of bottom/top/last/all
         #[arg(
             long,
-            value_name = "C|bottom|top|last|all|name|pr:<label>|branch:<branch-name>"
+            value_name = "C|bottom|top|last|all|name|pr:<label>|branch:<branch-name>",
+            required_unless_present = "before"
+        )]
+        after: Option<crate::selectors::AfterSelector>,
+        /// Target PR to come directly below: number or group selector
+        #[arg(
+            long,
+            value_name = "C|name|pr:<label>|branch:<branch-name>",
+            conflicts_with = "after"
         )]
-        after: crate::selectors::AfterSelector,
+        before: Option<crate::selectors::GroupSelector>,
+        /// Create a local backup tag at current HEAD before rewriting
+        #[arg(long)]
+        safe: bool,
+        #[command(flatten)]
+        dry_run: DryRunArgs,
+    },
+
+    /// Apply `/spr move-after`/`/spr move-before` comments and `spr:move-*` labels that reviewers left on the stack's PRs
+    ApplyRemoteDirectives {
         /// Create a local backup tag at current HEAD before rewriting
         #[arg(long)]
         safe: bool,
         #[command(flatten)]
         dry_run: DryRunArgs,
     },
+
+    /// Mark a stack PR (or every stack PR with --all) ready for review
+    Ready {
+        #[command(flatten)]
+        target: PrStageTargetArgs,
+        #[command(flatten)]
+        dry_run: DryRunArgs,
+    },
+
+    /// Convert a stack PR (or every stack PR with --all) back to draft
+    Draft {
+        #[command(flatten)]
+        target: PrStageTargetArgs,
+        #[command(flatten)]
+        dry_run: DryRunArgs,
+    },
+
+    /// Open a stack PR (or every stack PR with --all) in the browser
+    Open {
+        #[command(flatten)]
+        target: PrStageTargetArgs,
+        #[command(flatten)]
+        dry_run: DryRunArgs,
+    },
+
+    /// Add or remove labels on every open PR in the stack in one batched mutation
+    Label {
+        /// Label to add (repeatable); it must already exist in the repository
+        #[arg(long, value_name = "LABEL", required_unless_present = "remove")]
+        add: Vec<String>,
+        /// Label to remove (repeatable)
+        #[arg(long, value_name = "LABEL")]
+        remove: Vec<String>,
+        #[command(flatten)]
+        dry_run: DryRunArgs,
+    },
+
+    /// Set the milestone of every open PR in the stack in one batched mutation
+    Milestone {
+        /// Title of an open milestone
+        #[arg(required_unless_present = "clear", conflicts_with = "clear")]
+        name: Option<String>,
+        /// Remove the milestone from every open PR in the stack instead
+        #[arg(long)]
+        clear: bool,
+        #[command(flatten)]
+        dry_run: DryRunArgs,
+    },
+
+    /// Add or remove assignees on one stack PR, or on every stack PR with target 0
+    Assign {
+        /// Local PR number or group selector; 0 means every open PR in the stack
+        target: crate::selectors::InclusiveSelector,
+        /// GitHub login to assign (repeatable; a leading `@` is ignored)
+        #[arg(value_name = "USER", required_unless_present = "remove")]
+        users: Vec<String>,
+        /// GitHub login to unassign (repeatable)
+        #[arg(long, value_name = "USER")]
+        remove: Vec<String>,
+        #[command(flatten)]
+        dry_run: DryRunArgs,
+    },
+
+    /// Enable GitHub auto-merge on the bottom PR, or on one segment's bottom PR
+    Automerge {
+        /// Local PR number or group selector of a group whose PR targets a base branch (default:
+        /// the bottom group)
+        target: Option<crate::selectors::GroupSelector>,
+        /// Merge method GitHub uses once the PR is mergeable (default `merge_method` config, else
+        /// squash)
+        #[arg(long, value_enum)]
+        method: Option<crate::commands::MergeMethod>,
+        #[command(flatten)]
+        dry_run: DryRunArgs,
+    },
+}
+
+impl Cmd {
+    /// The `--dry-run` mode of the command, or `Apply` for commands without one.
+    pub fn execution_mode(&self) -> ExecutionMode {
+        match self {
+            Self::Update { dry_run, .. }
+            | Self::Flush { dry_run, .. }
+            | Self::Restack { dry_run, .. }
+            | Self::Sync { dry_run, .. }
+            | Self::AdoptPrefix { dry_run, .. }
+            | Self::RenamePrefix { dry_run, .. }
+            | Self::DropMergedPrefix { dry_run, .. }
+            | Self::Absorb { dry_run, .. }
+            | Self::Prep { dry_run, .. }
+            | Self::Land { dry_run, .. }
+            | Self::RelinkPrs { dry_run, .. }
+            | Self::TrackIssue { dry_run, .. }
+            | Self::Cleanup { dry_run, .. }
+            | Self::Freeze { dry_run, .. }
+            | Self::Thaw { dry_run, .. }
+            | Self::Checkout { dry_run, .. }
+            | Self::FixPr { dry_run, .. }
+            | Self::ImportPr { dry_run, .. }
+            | Self::Fixup { dry_run, .. }
+            | Self::Amend { dry_run, .. }
+            | Self::SquashRange { dry_run, .. }
+            | Self::Pull { dry_run, .. }
+            | Self::Edit { dry_run, .. }
+            | Self::Split { dry_run, .. }
+            | Self::Fold { dry_run, .. }
+            | Self::RollbackPr { dry_run, .. }
+            | Self::Cherry { dry_run, .. }
+            | Self::Title { dry_run, .. }
+            | Self::Describe { dry_run, .. }
+            | Self::Move { dry_run, .. }
+            | Self::ApplyRemoteDirectives { dry_run, .. }
+            | Self::Ready { dry_run, .. }
+            | Self::Draft { dry_run, .. }
+            | Self::Open { dry_run, .. }
+            | Self::Label { dry_run, .. }
+            | Self::Milestone { dry_run, .. }
+            | Self::Assign { dry_run, .. }
+            | Self::Automerge { dry_run, .. }
+            | Self::Base {
+                action: BaseCmd::Set { dry_run, .. },
+            }
+            | Self::Backup {
+                action: BackupCmd::Prune { dry_run, .. },
+            } => ExecutionMode::from(*dry_run),
+            _ => ExecutionMode::Apply,
+        }
+    }
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct PrStageTargetArgs {
+    /// Target local PR number or group selector
+    #[arg(required_unless_present = "all", conflicts_with = "all")]
+    pub target: Option<crate::selectors::GroupSelector>,
+    /// Apply to every PR in the local stack
+    #[arg(long)]
+    pub all: bool,
 }
 
 #[derive(Subcommand, Debug, Clone, Copy)]
@@ -317,15 +1009,27 @@ pub struct Cli {
     /// Verbose output for underlying git/gh commands
     #[arg(long, global = true)]
     pub verbose: bool,
+    /// Print a per-phase breakdown of git/gh command time (parsing, ls-remote, pushes, GraphQL
+    /// batches) to stderr when the command finishes
+    #[arg(long, global = true)]
+    pub timing: bool,
     /// Change to PATH before loading repo config or running git/gh commands
     #[arg(long, global = true, value_name = "PATH")]
     pub cd: Option<PathBuf>,
     /// Global base branch (root of stack)
     #[arg(short = 'b', long, global = true)]
     pub base: Option<String>,
+    /// Branch the stack's PRs target when `--base` is a tag or commit (default
+    /// `landing_branch` config)
+    #[arg(long, global = true, value_name = "BRANCH")]
+    pub landing_branch: Option<String>,
     /// Global branch prefix for per-PR branches
     #[arg(long, global = true)]
     pub prefix: Option<String>,
+    /// Tag whose `pr:<tag>` marker starts a block of commits kept out of every PR (default
+    /// `ignore_tag` config)
+    #[arg(long, global = true, value_name = "TAG")]
+    pub ignore_tag: Option<String>,
     /// Sync local per-PR branches named like each group's resolved concrete branch
     #[arg(long, global = true, value_enum)]
     pub local_pr_branches: Option<crate::config::LocalPrBranchSyncPolicy>,
@@ -343,6 +1047,31 @@ pub struct Cli {
         value_name = "I|name|pr:<label>|branch:<branch-name>"
     )]
     pub exact: Option<crate::selectors::GroupSelector>,
+    /// Only treat commits touching these path globs as stack commits (repeatable; overrides
+    /// `path_scope` config). Other commits act like ignore blocks
+    #[arg(long = "path-scope", global = true, value_name = "GLOB")]
+    pub path_scope: Vec<String>,
+    /// Only treat `pr:<NAME>/<tag>` groups as the stack; other groups act like ignore blocks
+    #[arg(long, global = true, value_name = "NAME")]
+    pub stack: Option<String>,
+    /// Derive groups from the first-parent chain only (also `first_parent` config), so commits
+    /// of side branches merged into the stack are not assigned to groups
+    #[arg(long, global = true)]
+    pub first_parent: bool,
+    /// Rebase merge commits out of the stack (backing the branch up first) before running the
+    /// command
+    #[arg(long, global = true)]
+    pub linearize: bool,
+    /// Skip `git fetch origin` before restacking or landing, trusting the local remote-tracking
+    /// base
+    #[arg(long, global = true)]
+    pub no_fetch: bool,
+    /// Git remote hosting the PR branches and base (default `remote` config, then `origin`)
+    #[arg(long, global = true, value_name = "NAME")]
+    pub remote: Option<String>,
+    /// Allow a base outside the `allowed_bases` config for this run
+    #[arg(long, global = true)]
+    pub allow_base: bool,
     #[command(flatten)]
     pub output: OutputArgs,
     #[command(subcommand)]
@@ -351,7 +1080,7 @@ pub struct Cli {
 
 #[cfg(test)]
 mod tests {
-    use super::{Cli, Cmd, OutputFormat};
+    use super::{BackupCmd, Cli, Cmd, OutputFormat};
     use crate::config::LocalPrBranchSyncPolicy;
     use crate::execution::ExecutionMode;
     use clap::{CommandFactory, Parser};
@@ -371,6 +1100,157 @@ mod tests {
         }
     }
 
+    #[test]
+    fn cleanup_close_orphaned_prs_flag_parses() {
+        let cli = Cli::try_parse_from(["spr", "clean", "--close-orphaned-prs", "--dr"]).unwrap();
+
+        match cli.cmd {
+            Cmd::Cleanup {
+                close_orphaned_prs,
+                dry_run,
+                ..
+            } => {
+                assert!(close_orphaned_prs);
+                assert_eq!(ExecutionMode::from(dry_run), ExecutionMode::DryRun);
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn backup_prune_needs_an_age_or_in_head() {
+        let cli =
+            Cli::try_parse_from(["spr", "backup", "prune", "--older-than", "30", "--dry-run"])
+                .unwrap();
+        match cli.cmd {
+            Cmd::Backup {
+                action:
+                    BackupCmd::Prune {
+                        older_than,
+                        in_head,
+                        dry_run,
+                    },
+            } => {
+                assert_eq!(older_than, Some(30));
+                assert!(!in_head);
+                assert_eq!(ExecutionMode::from(dry_run), ExecutionMode::DryRun);
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+        assert!(Cli::try_parse_from(["spr", "backup", "prune", "--in-head"]).is_ok());
+        assert!(Cli::try_parse_from(["spr", "backup", "prune"]).is_err());
+    }
+
+    #[test]
+    fn assign_takes_a_target_then_users() {
+        let cli = Cli::try_parse_from(["spr", "assign", "0", "@bob", "--remove", "alice"]).unwrap();
+        match cli.cmd {
+            Cmd::Assign {
+                target,
+                users,
+                remove,
+                ..
+            } => {
+                assert_eq!(target, crate::selectors::InclusiveSelector::All);
+                assert_eq!(users, vec!["@bob".to_string()]);
+                assert_eq!(remove, vec!["alice".to_string()]);
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+        assert!(Cli::try_parse_from(["spr", "assign", "2"]).is_err());
+    }
+
+    #[test]
+    fn ready_and_draft_accept_target_or_all() {
+        let cli = Cli::try_parse_from(["spr", "ready", "2"]).unwrap();
+        match cli.cmd {
+            Cmd::Ready { target, .. } => {
+                assert_eq!(
+                    target.target,
+                    Some(crate::selectors::GroupSelector::LocalPr(2))
+                );
+                assert!(!target.all);
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+
+        let cli = Cli::try_parse_from(["spr", "draft", "--all"]).unwrap();
+        match cli.cmd {
+            Cmd::Draft { target, .. } => {
+                assert_eq!(target.target, None);
+                assert!(target.all);
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+
+        assert!(Cli::try_parse_from(["spr", "ready"]).is_err());
+        assert!(Cli::try_parse_from(["spr", "draft", "1", "--all"]).is_err());
+    }
+
+    #[test]
+    fn status_watch_options_require_watch() {
+        let cli = Cli::try_parse_from(["spr", "status", "--watch", "--interval", "5", "--notify"])
+            .unwrap();
+        match cli.cmd {
+            Cmd::Status {
+                watch,
+                interval,
+                notify,
+            } => {
+                assert!(watch);
+                assert_eq!(interval, 5);
+                assert!(notify);
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+        assert!(Cli::try_parse_from(["spr", "status", "--notify"]).is_err());
+    }
+
+    #[test]
+    fn move_and_fix_pr_accept_before_targets() {
+        let cli = Cli::try_parse_from(["spr", "move", "delta", "--before", "alpha"]).unwrap();
+        match cli.cmd {
+            Cmd::Move { after, before, .. } => {
+                assert_eq!(after, None);
+                assert_eq!(
+                    before,
+                    Some(crate::selectors::GroupSelector::Bare("alpha".to_string()))
+                );
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+        assert!(Cli::try_parse_from(["spr", "move", "2"]).is_err());
+        assert!(
+            Cli::try_parse_from(["spr", "move", "2", "--after", "1", "--before", "3"]).is_err()
+        );
+
+        let cli = Cli::try_parse_from(["spr", "fix-pr", "--before", "2"]).unwrap();
+        match cli.cmd {
+            Cmd::FixPr { target, before, .. } => {
+                assert_eq!(target, None);
+                assert_eq!(before, Some(crate::selectors::GroupSelector::LocalPr(2)));
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+        assert!(Cli::try_parse_from(["spr", "fix-pr", "--after", "1"]).is_ok());
+        assert!(Cli::try_parse_from(["spr", "fix-pr"]).is_err());
+        assert!(Cli::try_parse_from(["spr", "fix-pr", "1", "--before", "2"]).is_err());
+    }
+
+    #[test]
+    fn restack_update_flag_parses_and_rejects_preview() {
+        let cli = Cli::try_parse_from(["spr", "restack", "--after", "1", "--update"]).unwrap();
+        match cli.cmd {
+            Cmd::Restack { update, .. } => assert!(update),
+            other => panic!("unexpected command: {:?}", other),
+        }
+
+        assert!(
+            Cli::try_parse_from(["spr", "restack", "--after", "1", "--update", "--preview"])
+                .is_err()
+        );
+    }
+
     #[test]
     fn update_commit_extent_is_rejected() {
         let err = Cli::try_parse_from(["spr", "update", "commits", "2"]).unwrap_err();
@@ -393,6 +1273,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn absorb_staged_flag_parses_and_rejects_branch_tail_options() {
+        let cli = Cli::try_parse_from(["spr", "absorb", "--staged", "--safe"]).unwrap();
+        match cli.cmd {
+            Cmd::Absorb { staged, safe, .. } => assert!(staged && safe),
+            other => panic!("unexpected command: {:?}", other),
+        }
+
+        assert!(Cli::try_parse_from(["spr", "absorb", "--staged", "--from", "1"]).is_err());
+        assert!(Cli::try_parse_from(["spr", "absorb", "--safe"]).is_err());
+    }
+
     #[test]
     fn absorb_from_selector_parses() {
         let cli = Cli::try_parse_from(["spr", "absorb", "--from", "pr:beta"]).unwrap();
@@ -425,6 +1317,57 @@ mod tests {
         }
     }
 
+    #[test]
+    fn land_restack_alias_enables_update_and_conflicts_with_no_restack() {
+        let cli = Cli::try_parse_from(["spr", "land", "--restack"]).unwrap();
+        match cli.cmd {
+            Cmd::Land { update, .. } => assert!(update),
+            other => panic!("unexpected command: {:?}", other),
+        }
+        assert!(Cli::try_parse_from(["spr", "land", "--update", "--no-restack"]).is_err());
+    }
+
+    #[test]
+    fn stack_from_ref_parses_for_list_prep_land_and_restack() {
+        let cli = Cli::try_parse_from(["spr", "list", "pr", "--from", "feature/stack"]).unwrap();
+        match cli.cmd {
+            Cmd::List { from, .. } => assert_eq!(from.as_deref(), Some("feature/stack")),
+            other => panic!("unexpected command: {:?}", other),
+        }
+
+        let cli =
+            Cli::try_parse_from(["spr", "land", "per-pr", "--from", "feature/stack"]).unwrap();
+        match cli.cmd {
+            Cmd::Land { from, .. } => assert_eq!(from.as_deref(), Some("feature/stack")),
+            other => panic!("unexpected command: {:?}", other),
+        }
+
+        let cli = Cli::try_parse_from([
+            "spr",
+            "restack",
+            "--after",
+            "0",
+            "--from-ref",
+            "feature/stack",
+        ])
+        .unwrap();
+        match cli.cmd {
+            Cmd::Restack { from, .. } => assert_eq!(from.as_deref(), Some("feature/stack")),
+            other => panic!("unexpected command: {:?}", other),
+        }
+
+        let cli =
+            Cli::try_parse_from(["spr", "prep", "--from", "2", "--from-ref", "feature/stack"])
+                .unwrap();
+        match cli.cmd {
+            Cmd::Prep { from, from_ref, .. } => {
+                assert_eq!(from, Some(crate::selectors::GroupSelector::LocalPr(2)));
+                assert_eq!(from_ref.as_deref(), Some("feature/stack"));
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+    }
+
     #[test]
     fn absorb_help_text_mentions_pr_groups_and_example_flow() {
         let mut cli = Cli::command();
@@ -467,7 +1410,10 @@ mod tests {
 
         match cli.cmd {
             Cmd::Resume { path } => {
-                assert_eq!(path, PathBuf::from(".git/spr/resume/restack-example.json"));
+                assert_eq!(
+                    path,
+                    Some(PathBuf::from(".git/spr/resume/restack-example.json"))
+                );
                 assert_eq!(cli.output.format(), OutputFormat::Json);
             }
             other => panic!("unexpected command: {:?}", other),
@@ -519,9 +1465,10 @@ mod tests {
             assert!(scan.requested, "case did not request JSON: {case:?}");
             assert!(matches!(
                 cli.cmd,
-                Cmd::Status
+                Cmd::Status { .. }
                     | Cmd::List {
-                        what: super::ListWhat::Commit
+                        what: super::ListWhat::Commit,
+                        ..
                     }
             ));
         }
@@ -545,11 +1492,16 @@ mod tests {
                 after,
                 safe,
                 preview,
+                update,
+                check,
                 dry_run,
+                ..
             } => {
-                assert_eq!(after.to_string(), "pr:alpha");
+                assert_eq!(after.unwrap().to_string(), "pr:alpha");
                 assert!(safe);
                 assert!(preview);
+                assert!(!update);
+                assert!(!check);
                 assert_eq!(ExecutionMode::from(dry_run), ExecutionMode::Apply);
                 assert_eq!(cli.output.format(), OutputFormat::Json);
             }
@@ -557,6 +1509,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn restack_check_conflicts_with_preview_and_update() {
+        assert!(Cli::try_parse_from(["spr", "restack", "--after", "0", "--check"]).is_ok());
+        assert!(
+            Cli::try_parse_from(["spr", "restack", "--after", "0", "--check", "--preview"])
+                .is_err()
+        );
+        assert!(
+            Cli::try_parse_from(["spr", "restack", "--after", "0", "--check", "--update"]).is_err()
+        );
+    }
+
+    #[test]
+    fn restack_interactive_replaces_after_and_excludes_preview_and_check() {
+        let cli = Cli::try_parse_from(["spr", "restack", "-i", "--update"]).unwrap();
+        match cli.cmd {
+            Cmd::Restack {
+                after, interactive, ..
+            } => {
+                assert!(after.is_none());
+                assert!(interactive);
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+        assert!(Cli::try_parse_from(["spr", "restack"]).is_err());
+        assert!(Cli::try_parse_from(["spr", "restack", "-i", "--after", "1"]).is_err());
+        assert!(Cli::try_parse_from(["spr", "restack", "-i", "--preview"]).is_err());
+        assert!(Cli::try_parse_from(["spr", "restack", "-i", "--check"]).is_err());
+    }
+
     #[test]
     fn adopt_prefix_preview_flag_parses_with_json_and_safe() {
         let cli =
@@ -589,6 +1571,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn update_plan_out_requires_dry_run() {
+        assert!(Cli::try_parse_from(["spr", "update", "--plan-out", "plan.md"]).is_err());
+        let cli =
+            Cli::try_parse_from(["spr", "update", "--dry-run", "--plan-out", "plan.md"]).unwrap();
+
+        match cli.cmd {
+            Cmd::Update { plan_out, .. } => {
+                assert_eq!(plan_out, Some(PathBuf::from("plan.md")));
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+    }
+
     #[test]
     fn update_dry_run_alias_parses_after_command() {
         let cli = Cli::try_parse_from(["spr", "update", "--dr"]).unwrap();
@@ -668,6 +1664,7 @@ mod tests {
         match cli.cmd {
             Cmd::List {
                 what: super::ListWhat::Pr,
+                ..
             } => {
                 assert_eq!(cli.output.format(), OutputFormat::Json);
             }
@@ -682,6 +1679,7 @@ mod tests {
         match cli.cmd {
             Cmd::List {
                 what: super::ListWhat::Commit,
+                ..
             } => {
                 assert_eq!(cli.output.format(), OutputFormat::Json);
             }
@@ -696,7 +1694,8 @@ mod tests {
         assert!(matches!(
             cli.cmd,
             Cmd::List {
-                what: super::ListWhat::Pr
+                what: super::ListWhat::Pr,
+                ..
             }
         ));
         assert_eq!(cli.output.format(), OutputFormat::Json);
@@ -707,7 +1706,7 @@ mod tests {
         let cli = Cli::try_parse_from(["spr", "status", "--json"]).unwrap();
 
         match cli.cmd {
-            Cmd::Status => assert_eq!(cli.output.format(), OutputFormat::Json),
+            Cmd::Status { .. } => assert_eq!(cli.output.format(), OutputFormat::Json),
             other => panic!("unexpected command: {:?}", other),
         }
     }
@@ -746,7 +1745,7 @@ mod tests {
         let cli = Cli::try_parse_from(["spr", "status", "--cd", "/tmp/example"]).unwrap();
 
         assert_eq!(cli.cd, Some(PathBuf::from("/tmp/example")));
-        assert!(matches!(cli.cmd, Cmd::Status));
+        assert!(matches!(cli.cmd, Cmd::Status { .. }));
         assert_eq!(cli.output.format(), OutputFormat::Human);
     }
 
@@ -755,7 +1754,25 @@ mod tests {
         let cli = Cli::try_parse_from(["spr", "--cd", "/tmp/example", "status"]).unwrap();
 
         assert_eq!(cli.cd, Some(PathBuf::from("/tmp/example")));
-        assert!(matches!(cli.cmd, Cmd::Status));
+        assert!(matches!(cli.cmd, Cmd::Status { .. }));
         assert_eq!(cli.output.format(), OutputFormat::Human);
     }
+
+    #[test]
+    fn command_execution_mode_follows_its_dry_run_flag() {
+        let parse = |args: &[&str]| Cli::try_parse_from(args).unwrap().cmd.execution_mode();
+        assert_eq!(
+            parse(&["spr", "--linearize", "update", "--dry-run"]),
+            ExecutionMode::DryRun
+        );
+        assert_eq!(
+            parse(&["spr", "--linearize", "update"]),
+            ExecutionMode::Apply
+        );
+        assert_eq!(
+            parse(&["spr", "base", "set", "origin/release", "--dr"]),
+            ExecutionMode::DryRun
+        );
+        assert_eq!(parse(&["spr", "status"]), ExecutionMode::Apply);
+    }
 }
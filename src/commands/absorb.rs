@@ -2452,7 +2452,10 @@ mod tests {
         assert_ne!(rewritten_head, original_head, "absorb should rewrite HEAD");
         let backup_tags = tags_with_pattern(&repo.repo, "backup/absorb/*");
         assert_eq!(backup_tags.len(), 1, "absorb should create one backup tag");
-        let backup_target = rev_parse(&repo.repo, &format!("refs/tags/{}", backup_tags[0]));
+        let backup_target = rev_parse(
+            &repo.repo,
+            &format!("refs/tags/{}^{{commit}}", backup_tags[0]),
+        );
         assert_eq!(
             backup_target, original_head,
             "backup tag should point at the pre-absorb HEAD"
@@ -0,0 +1,533 @@
+//! Fold staged hunks into the PR groups that last touched their lines.
+//!
+//! `spr absorb --staged` is `git absorb` for stacks: every staged hunk is blamed against `HEAD`,
+//! and a hunk whose lines (or, for pure insertions, neighbouring lines) all come from one group's
+//! commits becomes part of a `fixup! <PR title>` commit for that group. The fixup commits are
+//! written with a private index, the branch is soft-reset onto them so hunks that could not be
+//! attributed stay staged, and one rewrite moves each fixup to its group's tail.
+
+use anyhow::{anyhow, bail, Result};
+use std::collections::{BTreeMap, HashMap};
+use tracing::info;
+
+use crate::commands::common::{self, git_in_index, CherryPickOp};
+use crate::commands::rewrite_resume::{
+    self, RewriteCommandKind, RewriteCommandOutcome, RewriteConflictPolicy, RewriteDestinationKind,
+    RewriteSession,
+};
+use crate::config::DirtyWorktreePolicy;
+use crate::execution::ExecutionMode;
+use crate::git::{git_rev_parse, git_ro, git_rw};
+use crate::parsing::{derive_local_groups_with_ignored, Group};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct StagedHunk {
+    old_start: usize,
+    old_count: usize,
+    new_count: usize,
+    body: Vec<String>,
+}
+
+impl StagedHunk {
+    fn delta(&self) -> isize {
+        self.new_count as isize - self.old_count as isize
+    }
+
+    /// HEAD line numbers whose blame decides the owning group.
+    fn blamed_lines(&self, file_lines: usize) -> Vec<usize> {
+        if self.old_count > 0 {
+            (self.old_start..self.old_start + self.old_count).collect()
+        } else {
+            [self.old_start, self.old_start + 1]
+                .into_iter()
+                .filter(|line| (1..=file_lines).contains(line))
+                .collect()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct StagedFile {
+    path: String,
+    header: Vec<String>,
+    hunks: Vec<StagedHunk>,
+    /// Only plain content edits of existing text files are absorbed; adds, deletes, renames,
+    /// mode changes, and binary diffs stay staged.
+    absorbable: bool,
+}
+
+fn parse_range(range: &str) -> Option<(usize, usize)> {
+    match range.split_once(',') {
+        Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+        None => Some((range.parse().ok()?, 1)),
+    }
+}
+
+fn parse_hunk_header(line: &str) -> Option<(usize, usize, usize)> {
+    let mut parts = line.strip_prefix("@@ ")?.split(' ');
+    let (old_start, old_count) = parse_range(parts.next()?.strip_prefix('-')?)?;
+    let (_new_start, new_count) = parse_range(parts.next()?.strip_prefix('+')?)?;
+    Some((old_start, old_count, new_count))
+}
+
+/// Parse `git diff --cached -U0` output into per-file hunks.
+fn parse_staged_diff(diff: &str) -> Result<Vec<StagedFile>> {
+    let mut files: Vec<StagedFile> = Vec::new();
+    for line in diff.lines() {
+        if line.starts_with("diff --git ") {
+            files.push(StagedFile {
+                path: String::new(),
+                header: vec![line.to_string()],
+                hunks: Vec::new(),
+                absorbable: true,
+            });
+            continue;
+        }
+        let file = files
+            .last_mut()
+            .ok_or_else(|| anyhow!("Unexpected staged diff line before a file header: {line}"))?;
+        if line.starts_with("@@ ") {
+            let (old_start, old_count, new_count) = parse_hunk_header(line)
+                .ok_or_else(|| anyhow!("Could not parse staged hunk header: {line}"))?;
+            file.hunks.push(StagedHunk {
+                old_start,
+                old_count,
+                new_count,
+                body: Vec::new(),
+            });
+        } else if let Some(hunk) = file.hunks.last_mut() {
+            hunk.body.push(line.to_string());
+        } else {
+            if let Some(path) = line.strip_prefix("--- a/") {
+                file.path = path.to_string();
+            } else if line.starts_with("new file")
+                || line.starts_with("deleted file")
+                || line.starts_with("old mode")
+                || line.starts_with("rename ")
+                || line.starts_with("copy ")
+                || line.starts_with("Binary files")
+                || line.starts_with("--- \"")
+            {
+                file.absorbable = false;
+            }
+            if !line.starts_with("index ") {
+                file.header.push(line.to_string());
+            }
+        }
+    }
+    for file in &mut files {
+        if file.path.is_empty() || file.hunks.is_empty() {
+            file.absorbable = false;
+        }
+    }
+    Ok(files)
+}
+
+/// Map each line of `path` at HEAD to the commit that last changed it.
+fn blame_lines(path: &str) -> Result<Vec<String>> {
+    let porcelain = git_ro(["blame", "--porcelain", "HEAD", "--", path].as_slice())?;
+    let mut lines: Vec<String> = Vec::new();
+    for line in porcelain.lines() {
+        let mut parts = line.split(' ');
+        let (Some(sha), Some(_orig), Some(final_line)) = (parts.next(), parts.next(), parts.next())
+        else {
+            continue;
+        };
+        if sha.len() < 40 || !sha.chars().all(|ch| ch.is_ascii_hexdigit()) {
+            continue;
+        }
+        let Ok(final_line) = final_line.parse::<usize>() else {
+            continue;
+        };
+        if final_line == lines.len() + 1 {
+            lines.push(sha.to_string());
+        }
+    }
+    Ok(lines)
+}
+
+/// The 1-based group whose commits own every blamed line, if exactly one does.
+fn owning_group(
+    blamed: &[usize],
+    line_commits: &[String],
+    commit_groups: &HashMap<String, usize>,
+) -> Option<usize> {
+    let mut owner = None;
+    for line in blamed {
+        let group = commit_groups.get(line_commits.get(line - 1)?).copied()?;
+        if owner.is_some_and(|owner| owner != group) {
+            return None;
+        }
+        owner = Some(group);
+    }
+    owner
+}
+
+/// Build the patch for one group's hunks, shifting HEAD line numbers past hunks that earlier
+/// fixup commits already applied to the same file.
+fn build_group_patch(
+    files: &[StagedFile],
+    hunk_indexes: &[(usize, usize)],
+    applied: &mut HashMap<usize, Vec<(usize, isize)>>,
+) -> String {
+    let mut patch = String::new();
+    let mut by_file: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for (file_index, hunk_index) in hunk_indexes {
+        by_file.entry(*file_index).or_default().push(*hunk_index);
+    }
+    for (file_index, hunks) in by_file {
+        let file = &files[file_index];
+        for line in &file.header {
+            patch.push_str(line);
+            patch.push('\n');
+        }
+        let earlier = applied.entry(file_index).or_default();
+        let mut running: isize = 0;
+        let mut newly_applied = Vec::new();
+        for hunk_index in hunks {
+            let hunk = &file.hunks[hunk_index];
+            let shift: isize = earlier
+                .iter()
+                .filter(|(start, _)| *start < hunk.old_start)
+                .map(|(_, delta)| delta)
+                .sum();
+            let old_start = (hunk.old_start as isize + shift) as usize;
+            let new_start = old_start as isize
+                + running
+                + match (hunk.old_count, hunk.new_count) {
+                    (0, _) => 1,
+                    (_, 0) => -1,
+                    _ => 0,
+                };
+            patch.push_str(&format!(
+                "@@ -{},{} +{},{} @@\n",
+                old_start, hunk.old_count, new_start, hunk.new_count
+            ));
+            for line in &hunk.body {
+                patch.push_str(line);
+                patch.push('\n');
+            }
+            running += hunk.delta();
+            newly_applied.push((hunk.old_start, hunk.delta()));
+        }
+        earlier.extend(newly_applied);
+    }
+    patch
+}
+
+/// Write one fixup commit per group on top of HEAD without touching the real index.
+fn commit_group_fixups(patches: &[(usize, String, String)]) -> Result<Vec<(usize, String)>> {
+    common::with_temp_index("absorb", |index| {
+        let mut parent = git_rev_parse("HEAD")?;
+        git_in_index(index, ["read-tree", &parent].as_slice(), None)?;
+        let mut commits = Vec::new();
+        for (group, message, patch) in patches {
+            git_in_index(
+                index,
+                ["apply", "--cached", "--unidiff-zero", "-"].as_slice(),
+                Some(patch),
+            )?;
+            let tree = git_in_index(index, ["write-tree"].as_slice(), None)?;
+            parent = git_in_index(
+                index,
+                ["commit-tree", &tree, "-p", &parent, "-m", message].as_slice(),
+                None,
+            )?;
+            commits.push((*group, parent.clone()));
+        }
+        Ok(commits)
+    })
+}
+
+/// Replay order with each group's fixup commits appended after the group and its ignore block.
+fn build_absorb_operations(
+    leading_ignored: &[String],
+    groups: &[Group],
+    fixups: &[(usize, String)],
+) -> Vec<CherryPickOp> {
+    let mut operations = Vec::new();
+    let mut run: Vec<String> = leading_ignored.to_vec();
+    for (index, group) in groups.iter().enumerate() {
+        run.extend(group.commits.iter().cloned());
+        run.extend(group.ignored_after.iter().cloned());
+        let group_fixups: Vec<String> = fixups
+            .iter()
+            .filter(|(ordinal, _)| *ordinal == index + 1)
+            .map(|(_, sha)| sha.clone())
+            .collect();
+        if !group_fixups.is_empty() {
+            operations.extend(CherryPickOp::from_commits(&run));
+            run.clear();
+            operations.extend(CherryPickOp::from_commits(&group_fixups));
+        }
+    }
+    operations.extend(CherryPickOp::from_commits(&run));
+    operations
+}
+
+/// Whether the worktree has changes beyond the staged diff (unstaged edits or untracked files).
+fn has_unstaged_changes() -> Result<bool> {
+    let status = git_ro(["status", "--porcelain"].as_slice())?;
+    Ok(status
+        .lines()
+        .any(|line| line.starts_with("??") || line.chars().nth(1).is_some_and(|ch| ch != ' ')))
+}
+
+/// Fold staged hunks into the groups that own their lines and move the fixups to those groups.
+///
+/// Hunks that touch lines from more than one group, from commits below the stack, or files that
+/// are added, deleted, renamed, or binary are left staged.
+///
+/// # Errors
+///
+/// Returns errors when nothing is staged, no hunk can be attributed to a single group,
+/// `dirty_worktree=halt` would refuse the rewrite because changes remain, or the rewrite fails.
+pub fn absorb_staged(
+    metadata_context: &crate::stack_metadata::RefreshMetadataContext,
+    safe: bool,
+    execution_mode: ExecutionMode,
+    dirty_worktree_policy: DirtyWorktreePolicy,
+) -> Result<RewriteCommandOutcome> {
+    let (merge_base, leading_ignored, groups) =
+        derive_local_groups_with_ignored(&metadata_context.base, &metadata_context.ignore_tag)?;
+    if groups.is_empty() {
+        bail!("No local PR groups found; nothing to absorb into.");
+    }
+    let commit_groups: HashMap<String, usize> = groups
+        .iter()
+        .enumerate()
+        .flat_map(|(index, group)| {
+            group
+                .commits
+                .iter()
+                .map(move |sha| (sha.clone(), index + 1))
+        })
+        .collect();
+
+    let diff = git_ro(
+        [
+            "-c",
+            "core.quotePath=false",
+            "diff",
+            "--cached",
+            "--no-color",
+            "--no-ext-diff",
+            "--no-renames",
+            "--unified=0",
+        ]
+        .as_slice(),
+    )?;
+    let files = parse_staged_diff(&diff)?;
+    if files.is_empty() {
+        bail!("No staged changes; stage hunks with `git add -p` before running `spr absorb --staged`.");
+    }
+
+    let mut assigned: BTreeMap<usize, Vec<(usize, usize)>> = BTreeMap::new();
+    let mut left_staged = 0usize;
+    for (file_index, file) in files.iter().enumerate() {
+        if !file.absorbable {
+            left_staged += file.hunks.len().max(1);
+            continue;
+        }
+        let line_commits = blame_lines(&file.path)?;
+        for (hunk_index, hunk) in file.hunks.iter().enumerate() {
+            let blamed = hunk.blamed_lines(line_commits.len());
+            match owning_group(&blamed, &line_commits, &commit_groups) {
+                Some(group) => assigned
+                    .entry(group)
+                    .or_default()
+                    .push((file_index, hunk_index)),
+                None => left_staged += 1,
+            }
+        }
+    }
+    if assigned.is_empty() {
+        bail!("No staged hunk could be attributed to a single PR group; use `spr fixup <target>` to choose one.");
+    }
+    for (group, hunks) in &assigned {
+        info!(
+            "Absorbing {} hunk(s) into {} (LPR #{})",
+            hunks.len(),
+            groups[group - 1].selector_text(),
+            group
+        );
+    }
+    if left_staged > 0 {
+        info!(
+            "Leaving {} hunk(s) staged that do not belong to a single PR group",
+            left_staged
+        );
+    }
+    if dirty_worktree_policy == DirtyWorktreePolicy::Halt
+        && (left_staged > 0 || has_unstaged_changes()?)
+    {
+        bail!("spr absorb --staged rewrites the checked-out branch and dirty_worktree=halt would refuse the remaining local changes. Commit or stash unstaged changes and unattributed hunks first, or set dirty_worktree=stash.");
+    }
+    if execution_mode == ExecutionMode::DryRun {
+        info!("DRY-RUN: would commit the fixups and move each to its group's tail");
+        return Ok(RewriteCommandOutcome::Completed);
+    }
+
+    let mut applied = HashMap::new();
+    let mut patches = Vec::new();
+    for (group, hunks) in &assigned {
+        let message = format!("fixup! {}", groups[group - 1].pr_title()?);
+        patches.push((
+            *group,
+            message,
+            build_group_patch(&files, hunks, &mut applied),
+        ));
+    }
+    let fixups = commit_group_fixups(&patches)?;
+    let tip = fixups
+        .last()
+        .map(|(_, sha)| sha.clone())
+        .ok_or_else(|| anyhow!("No fixup commits were created"))?;
+    // Hunks now committed drop out of the index diff; unattributed hunks stay staged.
+    git_rw(execution_mode, ["reset", "--soft", &tip].as_slice())?;
+
+    let top = groups.len();
+    if fixups.iter().all(|(group, _)| *group == top) && groups[top - 1].ignored_after.is_empty() {
+        info!("All absorbed hunks belong to the top group; the fixups are already its tail.");
+        return Ok(RewriteCommandOutcome::Completed);
+    }
+
+    common::with_dirty_worktree_policy(
+        execution_mode,
+        "spr absorb --staged",
+        dirty_worktree_policy,
+        |deferred_dirty_worktree_restore| {
+            let (cur_branch, short) = common::get_current_branch_and_short()?;
+            let original_head = git_rev_parse("HEAD")?;
+            let original_worktree_root = rewrite_resume::current_repo_root()?;
+            let resume_path = rewrite_resume::prepare_resume_path_for_new_session(
+                execution_mode,
+                RewriteCommandKind::Absorb,
+                &cur_branch,
+                &original_head,
+            )?;
+            let backup_tag = if safe {
+                Some(common::create_backup_tag(
+                    execution_mode,
+                    "absorb",
+                    &cur_branch,
+                    &short,
+                )?)
+            } else {
+                None
+            };
+            let (tmp_path, tmp_branch) =
+                common::create_temp_worktree(execution_mode, "absorb", &merge_base, &short)?;
+            rewrite_resume::run_rewrite_session(
+                execution_mode,
+                RewriteSession {
+                    command_kind: RewriteCommandKind::Absorb,
+                    conflict_policy: RewriteConflictPolicy::Suspend,
+                    original_worktree_root,
+                    original_branch: cur_branch,
+                    original_head,
+                    destination_kind: RewriteDestinationKind::CheckedOutBranch,
+                    resume_path,
+                    temp_branch: tmp_branch,
+                    temp_worktree_path: tmp_path,
+                    backup_tag,
+                    operations: build_absorb_operations(&leading_ignored, &groups, &fixups),
+                    deferred_dirty_worktree_restore,
+                    post_success_hint: Some(
+                        "No GitHub changes were made. Run `spr update` after inspecting the rewritten stack."
+                            .to_string(),
+                    ),
+                    metadata_refresh_context: Some(metadata_context.clone()),
+                },
+            )
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{absorb_staged, parse_staged_diff};
+    use crate::commands::RewriteCommandOutcome;
+    use crate::config::DirtyWorktreePolicy;
+    use crate::execution::ExecutionMode;
+    use crate::test_support::{commit_file, git, init_repo, lock_cwd, log_subjects, DirGuard};
+    use std::fs;
+
+    #[test]
+    fn parse_staged_diff_marks_added_files_unabsorbable() {
+        let diff = "diff --git a/a.txt b/a.txt\nindex 1..2 100644\n--- a/a.txt\n+++ b/a.txt\n@@ -2 +2,2 @@\n-old\n+new\n+more\ndiff --git a/b.txt b/b.txt\nnew file mode 100644\nindex 0..1\n--- /dev/null\n+++ b/b.txt\n@@ -0,0 +1 @@\n+b\n";
+        let files = parse_staged_diff(diff).unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path, "a.txt");
+        assert!(files[0].absorbable);
+        assert_eq!(
+            (
+                files[0].hunks[0].old_start,
+                files[0].hunks[0].old_count,
+                files[0].hunks[0].new_count
+            ),
+            (2, 1, 2)
+        );
+        assert!(!files[0]
+            .header
+            .iter()
+            .any(|line| line.starts_with("index ")));
+        assert!(!files[1].absorbable);
+    }
+
+    #[test]
+    fn absorb_staged_splits_hunks_between_owning_groups() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path().to_path_buf();
+        git(&repo, ["checkout", "-b", "stack"].as_slice());
+        commit_file(&repo, "story.txt", "a1\na2\na3\n", "feat: alpha pr:alpha");
+        commit_file(
+            &repo,
+            "story.txt",
+            "a1\na2\na3\nb1\nb2\n",
+            "feat: beta pr:beta",
+        );
+        commit_file(&repo, "gamma.txt", "g\n", "feat: gamma pr:gamma");
+        let _guard = DirGuard::change_to(&repo);
+        fs::write(repo.join("story.txt"), "a1\nA2\nextra\na3\nb1\nB2\n").unwrap();
+        fs::write(repo.join("new.txt"), "new\n").unwrap();
+        git(&repo, ["add", "story.txt", "new.txt"].as_slice());
+
+        let outcome = absorb_staged(
+            &crate::stack_metadata::RefreshMetadataContext {
+                base: "main".to_string(),
+                prefix: "dank-spr/".to_string(),
+                ignore_tag: "ignore".to_string(),
+            },
+            false,
+            ExecutionMode::Apply,
+            DirtyWorktreePolicy::Stash,
+        )
+        .unwrap();
+
+        assert_eq!(outcome, RewriteCommandOutcome::Completed);
+        assert_eq!(
+            log_subjects(&repo, 5),
+            vec![
+                "feat: gamma pr:gamma".to_string(),
+                "fixup! feat: beta".to_string(),
+                "feat: beta pr:beta".to_string(),
+                "fixup! feat: alpha".to_string(),
+                "feat: alpha pr:alpha".to_string(),
+            ]
+        );
+        assert_eq!(
+            git(&repo, ["show", "HEAD~3:story.txt"].as_slice()),
+            "a1\nA2\nextra\na3\n"
+        );
+        assert_eq!(
+            git(&repo, ["show", "HEAD:story.txt"].as_slice()),
+            "a1\nA2\nextra\na3\nb1\nB2\n"
+        );
+        assert_eq!(
+            git(&repo, ["diff", "--cached", "--name-only"].as_slice()),
+            "new.txt\n"
+        );
+    }
+}
@@ -0,0 +1,243 @@
+//! Amend a change into the tip commit of one PR group.
+//!
+//! `spr amend <target>` folds a change into the target group's last commit instead of adding a
+//! commit like `spr fixup`. The change is either the working tree's tracked modifications, which
+//! are committed on top of the stack first, or an existing commit above the group given with
+//! `--commit`. The amended tip is built with a private index, so the change must apply cleanly to
+//! it; the commits above are then replayed through the usual rewrite session, minus the change.
+
+use anyhow::{bail, Context, Result};
+use tracing::info;
+
+use crate::commands::common::{apply_commit_diff, commit_with_tree, git_in_index, with_temp_index};
+use crate::commands::reword::replay_with_replacement;
+use crate::commands::rewrite_resume::{RewriteCommandKind, RewriteCommandOutcome};
+use crate::config::DirtyWorktreePolicy;
+use crate::execution::ExecutionMode;
+use crate::git::{git_is_ancestor, git_ro, git_rw};
+use crate::parsing::derive_local_groups;
+use crate::selectors::{resolve_group_ordinal, GroupSelector};
+
+/// Result of `spr amend`, including the resolved target so callers can scope a follow-up update.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AmendOutcome {
+    /// 1-based local PR number of the group whose tip was amended.
+    pub local_pr_number: usize,
+    pub rewrite: RewriteCommandOutcome,
+}
+
+/// Tree of `tip` with the first-parent diff of `change` applied.
+fn amended_tree(tip: &str, change: &str) -> Result<String> {
+    with_temp_index("amend", |index| {
+        git_in_index(index, ["read-tree", tip].as_slice(), None)?;
+        apply_commit_diff(index, change)?;
+        git_in_index(index, ["write-tree"].as_slice(), None)
+    })
+}
+
+fn rev_parse_commit(rev: &str) -> Result<String> {
+    Ok(
+        git_ro(["rev-parse", "--verify", &format!("{rev}^{{commit}}")].as_slice())
+            .with_context(|| format!("`{rev}` does not name a commit"))?
+            .trim()
+            .to_string(),
+    )
+}
+
+/// Amend the working tree's tracked changes, or `commit`, into the tip of `target`.
+///
+/// # Errors
+///
+/// Returns errors when the target does not resolve, there is nothing to amend, `commit` is not a
+/// single-parent commit above the target's tip, the change does not apply cleanly to the tip, or
+/// the rewrite fails. If the rewrite fails after the working tree was committed, the error says
+/// how to finish with `--commit HEAD`.
+pub fn amend_group(
+    metadata_context: &crate::stack_metadata::RefreshMetadataContext,
+    target: &GroupSelector,
+    commit: Option<&str>,
+    safe: bool,
+    execution_mode: ExecutionMode,
+    dirty_worktree_policy: DirtyWorktreePolicy,
+) -> Result<AmendOutcome> {
+    let (_merge_base, groups) =
+        derive_local_groups(&metadata_context.base, &metadata_context.ignore_tag)?;
+    if groups.is_empty() {
+        bail!("No local PR groups found; nothing to amend.");
+    }
+    let local_pr_number = resolve_group_ordinal(&groups, target)?;
+    let group = &groups[local_pr_number - 1];
+    let selector = group.selector_text();
+    let Some(tip) = group.commits.last() else {
+        bail!("{selector} has no commits");
+    };
+
+    let change = match commit {
+        Some(rev) => rev_parse_commit(rev)?,
+        None => {
+            let changed = git_ro(["diff", "HEAD", "--name-only"].as_slice())?;
+            let changed_count = changed
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .count();
+            if changed_count == 0 {
+                bail!("No tracked changes in the working tree; nothing to amend into {selector}.");
+            }
+            // Without the marker, a leftover commit (if the rewrite stops) cannot start a group.
+            let subject = crate::group_markers::strip_valid_group_markers(
+                group.subjects.last().map_or("", String::as_str),
+            );
+            git_rw(
+                execution_mode,
+                ["commit", "-a", "-m", &format!("amend! {}", subject.trim())].as_slice(),
+            )?;
+            if execution_mode == ExecutionMode::DryRun {
+                info!(
+                    "DRY-RUN: would amend {} changed file(s) into the tip of {} (LPR #{}).",
+                    changed_count, selector, local_pr_number
+                );
+                return Ok(AmendOutcome {
+                    local_pr_number,
+                    rewrite: RewriteCommandOutcome::Completed,
+                });
+            }
+            rev_parse_commit("HEAD")?
+        }
+    };
+    if &change == tip || !git_is_ancestor(tip, &change)? || !git_is_ancestor(&change, "HEAD")? {
+        bail!(
+            "{} must be a commit above the tip of {} in the checked-out stack",
+            commit.unwrap_or("HEAD"),
+            selector
+        );
+    }
+    let parents = git_ro(["rev-list", "--parents", "-n", "1", &change].as_slice())?;
+    if parents.split_whitespace().count() != 2 {
+        bail!(
+            "{} is a merge commit; only single-parent commits can be amended in",
+            change
+        );
+    }
+
+    let finish_hint = if commit.is_none() {
+        format!(
+            "The changes were committed at HEAD but not amended; run `spr amend {local_pr_number} --commit HEAD` to finish."
+        )
+    } else {
+        format!("Could not amend {change} into {selector}.")
+    };
+    let tree = amended_tree(tip, &change)
+        .with_context(|| {
+            format!(
+                "{} does not apply cleanly to the tip of {}; use `spr fixup` to add it as a separate commit",
+                &change[..change.len().min(8)],
+                selector
+            )
+        })
+        .with_context(|| finish_hint.clone())?;
+    let message = git_ro(["log", "-n", "1", "--format=%B", tip].as_slice())?;
+    let amended = commit_with_tree(tip, &tree, message.trim_end())?;
+    let rewrite = replay_with_replacement(
+        metadata_context,
+        tip,
+        &amended,
+        std::slice::from_ref(&change),
+        RewriteCommandKind::Amend,
+        &format!(
+            "Amending {} into the tip of {}",
+            &change[..change.len().min(8)],
+            selector
+        ),
+        safe,
+        execution_mode,
+        dirty_worktree_policy,
+    )
+    .with_context(|| finish_hint)?;
+    Ok(AmendOutcome {
+        local_pr_number,
+        rewrite,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::amend_group;
+    use crate::commands::RewriteCommandOutcome;
+    use crate::config::DirtyWorktreePolicy;
+    use crate::execution::ExecutionMode;
+    use crate::selectors::GroupSelector;
+    use crate::test_support::{
+        commit_file, git, init_repo, lock_cwd, log_subjects, write_file, DirGuard,
+    };
+
+    fn metadata_context() -> crate::stack_metadata::RefreshMetadataContext {
+        crate::stack_metadata::RefreshMetadataContext {
+            base: "main".to_string(),
+            prefix: "dank-spr/".to_string(),
+            ignore_tag: "ignore".to_string(),
+        }
+    }
+
+    #[test]
+    fn amend_folds_working_tree_changes_into_a_mid_stack_tip() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path().to_path_buf();
+        git(&repo, ["checkout", "-b", "stack"].as_slice());
+        commit_file(&repo, "alpha.txt", "a\n", "feat: alpha pr:alpha");
+        commit_file(&repo, "alpha.txt", "a\nb\n", "feat: alpha more");
+        commit_file(&repo, "beta.txt", "b\n", "feat: beta pr:beta");
+        write_file(&repo, "alpha.txt", "a\nb fixed\n");
+        let _guard = DirGuard::change_to(&repo);
+
+        let outcome = amend_group(
+            &metadata_context(),
+            &GroupSelector::LocalPr(1),
+            None,
+            false,
+            ExecutionMode::Apply,
+            DirtyWorktreePolicy::Halt,
+        )
+        .unwrap();
+
+        assert_eq!(outcome.local_pr_number, 1);
+        assert_eq!(outcome.rewrite, RewriteCommandOutcome::Completed);
+        assert_eq!(
+            log_subjects(&repo, 3),
+            vec![
+                "feat: beta pr:beta".to_string(),
+                "feat: alpha more".to_string(),
+                "feat: alpha pr:alpha".to_string(),
+            ]
+        );
+        assert_eq!(
+            git(&repo, ["show", "HEAD~1:alpha.txt"].as_slice()),
+            "a\nb fixed\n"
+        );
+        assert!(git(&repo, ["status", "--porcelain"].as_slice())
+            .trim()
+            .is_empty());
+    }
+
+    #[test]
+    fn amend_rejects_commits_below_the_target_tip() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path().to_path_buf();
+        git(&repo, ["checkout", "-b", "stack"].as_slice());
+        commit_file(&repo, "alpha.txt", "a\n", "feat: alpha pr:alpha");
+        commit_file(&repo, "beta.txt", "b\n", "feat: beta pr:beta");
+        let _guard = DirGuard::change_to(&repo);
+
+        let err = amend_group(
+            &metadata_context(),
+            &GroupSelector::LocalPr(2),
+            Some("HEAD~1"),
+            false,
+            ExecutionMode::Apply,
+            DirtyWorktreePolicy::Halt,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("must be a commit above"), "{err}");
+    }
+}
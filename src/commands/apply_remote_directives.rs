@@ -0,0 +1,266 @@
+//! Apply stack reordering that reviewers request on GitHub with `spr apply-remote-directives`.
+//!
+//! Reviewers leave a directive on a stack PR, either as a comment line or as a label:
+//!
+//! - `/spr move-after <selector>` or the label `spr:move-after:<selector>`
+//! - `/spr move-before <selector>` or the label `spr:move-before:<selector>`
+//! - `/spr retag <tag>`
+//!
+//! Comment directives count only from the repository's owner, members, and collaborators; labels
+//! need triage access to set anyway. The newest move directive of each PR wins (labels count as
+//! older than every comment) and is applied to that PR's group with the same rewrite as
+//! `spr move`, bottom PR first. A move the stack already satisfies is a no-op, so rerunning is
+//! safe and directives do not need to be removed. A move whose target has since landed or been
+//! retagged no longer resolves and is skipped with a warning. Retags are only reported: a new tag publishes
+//! to a new branch and PR, which is not a change to make on someone else's word.
+
+use anyhow::{anyhow, Result};
+use tracing::{info, warn};
+
+use crate::branch_names::group_branch_identities;
+use crate::commands::r#move::{
+    move_groups_after, resolve_move_targets, MoveDestination, MoveExecutionOptions,
+};
+use crate::commands::rewrite_resume::RewriteCommandOutcome;
+use crate::github::{fetch_pr_comments_and_labels, list_open_prs_for_heads, PrComment};
+use crate::parsing::{derive_local_groups, Group};
+use crate::selectors::{AfterSelector, GroupRangeSelector, GroupSelector};
+
+const TRUSTED_ASSOCIATIONS: &[&str] = &["OWNER", "MEMBER", "COLLABORATOR"];
+
+/// One reviewer request for a stack PR.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemoteDirective {
+    Move(MoveDestination),
+    Retag(String),
+}
+
+/// Parse `move-after <selector>`, `move-before <selector>`, or `retag <tag>`.
+fn parse_directive(verb: &str, argument: &str) -> Option<Result<RemoteDirective, String>> {
+    let argument = argument.trim();
+    match verb {
+        "move-after" => Some(
+            argument
+                .parse::<AfterSelector>()
+                .map(|after| RemoteDirective::Move(MoveDestination::After(after))),
+        ),
+        "move-before" => Some(
+            argument
+                .parse::<GroupSelector>()
+                .map(|before| RemoteDirective::Move(MoveDestination::Before(before))),
+        ),
+        "retag" => Some(
+            crate::pr_labels::validate_label(argument)
+                .map(|()| RemoteDirective::Retag(argument.to_string()))
+                .map_err(|err| format!("`{argument}` {err}")),
+        ),
+        _ => None,
+    }
+}
+
+/// The directives of one PR, oldest first: labels, then trusted comments by creation time.
+///
+/// Malformed directives are returned as errors so they can be reported next to the PR.
+pub fn collect_remote_directives(
+    comments: &[PrComment],
+    labels: &[String],
+) -> Vec<Result<RemoteDirective, String>> {
+    let mut out = Vec::new();
+    for label in labels {
+        let Some(rest) = label.strip_prefix("spr:") else {
+            continue;
+        };
+        let (verb, argument) = rest.split_once(':').unwrap_or((rest, ""));
+        if let Some(directive) = parse_directive(verb, argument) {
+            out.push(directive);
+        }
+    }
+    let mut trusted: Vec<&PrComment> = comments
+        .iter()
+        .filter(|comment| TRUSTED_ASSOCIATIONS.contains(&comment.author_association.as_str()))
+        .collect();
+    trusted.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+    for comment in trusted {
+        for line in comment.body.lines() {
+            let Some(rest) = line.trim().strip_prefix("/spr ") else {
+                continue;
+            };
+            let rest = rest.trim();
+            let (verb, argument) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+            if let Some(directive) = parse_directive(verb, argument) {
+                out.push(directive);
+            }
+        }
+    }
+    out
+}
+
+/// The group `handle` names as a range for `spr move`, if it and the directive's destination both
+/// still resolve in `groups`.
+fn resolve_directive_move(
+    groups: &[Group],
+    handle: &str,
+    destination: &MoveDestination,
+) -> Result<GroupRangeSelector> {
+    let selector = handle
+        .parse::<GroupSelector>()
+        .map_err(|err| anyhow!("{handle}: {err}"))?;
+    let range = GroupRangeSelector::Single(selector);
+    resolve_move_targets(groups, &range, destination)?;
+    Ok(range)
+}
+
+/// Read directives from the open PRs of the local stack and apply the moves they request.
+///
+/// Moves whose group or target no longer resolves are skipped with a warning. Stops at the first
+/// move that suspends on a conflict and returns that outcome; rerun after `spr resume` to apply
+/// the rest.
+///
+/// # Errors
+///
+/// Returns errors from group derivation, GitHub lookups, or the move rewrites.
+pub fn apply_remote_directives(
+    base: &str,
+    prefix: &str,
+    ignore_tag: &str,
+    options: MoveExecutionOptions,
+) -> Result<RewriteCommandOutcome> {
+    let (_merge_base, groups) = derive_local_groups(base, ignore_tag)?;
+    let heads: Vec<String> = group_branch_identities(&groups, prefix)?
+        .into_iter()
+        .map(|identity| identity.exact)
+        .collect();
+    let prs = list_open_prs_for_heads(&heads)?;
+    let mut moves: Vec<(u64, String, MoveDestination)> = Vec::new();
+    for (group, head) in groups.iter().zip(&heads) {
+        let Some(pr) = prs.iter().find(|pr| &pr.head == head) else {
+            continue;
+        };
+        let (comments, labels) = fetch_pr_comments_and_labels(pr.number)?;
+        let mut latest_move = None;
+        for directive in collect_remote_directives(&comments, &labels) {
+            match directive {
+                Ok(RemoteDirective::Move(destination)) => latest_move = Some(destination),
+                Ok(RemoteDirective::Retag(tag)) => warn!(
+                    "PR #{} asks to retag {} as pr:{}; retag it by hand, since a new tag publishes a new PR",
+                    pr.number,
+                    group.selector_text(),
+                    tag
+                ),
+                Err(err) => warn!("Ignoring a directive on PR #{}: {}", pr.number, err),
+            }
+        }
+        if let Some(destination) = latest_move {
+            moves.push((pr.number, group.selector_text(), destination));
+        }
+    }
+    if moves.is_empty() {
+        info!("No move directives on the stack's open PRs.");
+        return Ok(RewriteCommandOutcome::Completed);
+    }
+    for (number, handle, destination) in moves {
+        let (kind, target) = match &destination {
+            MoveDestination::After(after) => ("after", after.to_string()),
+            MoveDestination::Before(before) => ("before", before.to_string()),
+        };
+        // Earlier moves reorder the stack, so resolve against the stack as it is now.
+        let (_merge_base, groups) = derive_local_groups(base, ignore_tag)?;
+        let range = match resolve_directive_move(&groups, &handle, &destination) {
+            Ok(range) => range,
+            Err(err) => {
+                warn!("Skipping PR #{number}'s move of {handle} {kind} {target}: {err:#}");
+                continue;
+            }
+        };
+        info!("PR #{number}: moving {handle} {kind} {target}");
+        let outcome = move_groups_after(base, prefix, ignore_tag, &range, &destination, options)?;
+        if outcome != RewriteCommandOutcome::Completed {
+            return Ok(outcome);
+        }
+    }
+    Ok(RewriteCommandOutcome::Completed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{collect_remote_directives, resolve_directive_move, RemoteDirective};
+    use crate::commands::r#move::MoveDestination;
+    use crate::github::PrComment;
+    use crate::parsing::Group;
+    use crate::selectors::{AfterSelector, ExplicitGroupSelector, GroupSelector};
+
+    fn comment(body: &str, association: &str, created_at: &str) -> PrComment {
+        PrComment {
+            body: body.to_string(),
+            author_association: association.to_string(),
+            created_at: created_at.to_string(),
+        }
+    }
+
+    #[test]
+    fn collect_remote_directives_reads_labels_then_trusted_comments_in_order() {
+        let comments = vec![
+            comment(
+                "Looks good.\n/spr move-before pr:beta\n",
+                "MEMBER",
+                "2026-01-02T00:00:00Z",
+            ),
+            comment("/spr move-after top", "NONE", "2026-01-03T00:00:00Z"),
+            comment(
+                "/spr move-after api-core",
+                "COLLABORATOR",
+                "2026-01-01T00:00:00Z",
+            ),
+            comment("/spr retag 9lives", "OWNER", "2026-01-04T00:00:00Z"),
+        ];
+        let labels = vec!["spr:move-after:bottom".to_string(), "bug".to_string()];
+
+        let directives = collect_remote_directives(&comments, &labels);
+
+        assert_eq!(directives.len(), 4);
+        assert_eq!(
+            directives[0],
+            Ok(RemoteDirective::Move(MoveDestination::After(
+                AfterSelector::Bottom
+            )))
+        );
+        assert_eq!(
+            directives[1],
+            Ok(RemoteDirective::Move(MoveDestination::After(
+                AfterSelector::Group(GroupSelector::Bare("api-core".to_string()))
+            )))
+        );
+        assert_eq!(
+            directives[2],
+            Ok(RemoteDirective::Move(MoveDestination::Before(
+                GroupSelector::Explicit(ExplicitGroupSelector::PrLabel("beta".to_string()))
+            )))
+        );
+        assert!(directives[3].is_err());
+    }
+    #[test]
+    fn directive_moves_that_no_longer_resolve_are_reported_not_applied() {
+        let groups: Vec<Group> = ["alpha", "gamma"]
+            .iter()
+            .map(|tag| Group {
+                marker: crate::group_markers::GroupMarker::PrLabel(tag.to_string()),
+                subjects: vec![format!("feat: {tag}")],
+                commits: vec![format!("{tag}1")],
+                first_message: Some(format!("feat: {tag} pr:{tag}")),
+                ignored_after: Vec::new(),
+            })
+            .collect();
+        let after = |label: &str| {
+            MoveDestination::After(AfterSelector::Group(GroupSelector::Explicit(
+                ExplicitGroupSelector::PrLabel(label.to_string()),
+            )))
+        };
+
+        assert!(resolve_directive_move(&groups, "pr:alpha", &after("gamma")).is_ok());
+        // `beta` landed since the directive was left.
+        let err = resolve_directive_move(&groups, "pr:alpha", &after("beta")).unwrap_err();
+        assert!(err.to_string().contains("beta"), "unexpected error: {err}");
+        // The PR's own group was retagged.
+        assert!(resolve_directive_move(&groups, "pr:delta", &after("gamma")).is_err());
+    }
+}
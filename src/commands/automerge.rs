@@ -0,0 +1,157 @@
+//! Enable GitHub auto-merge on stack PRs.
+//!
+//! `spr automerge` turns on auto-merge (squash unless `--method` or `merge_method` says otherwise)
+//! for the bottom PR of the stack, or for one selected group that starts a segment. GitHub then
+//! merges the PR once its required checks and approvals arrive. Only PRs that target a real base
+//! branch qualify: a PR higher in a chain targets the branch of the group below it, and GitHub
+//! would merge it into that branch instead of the base.
+
+use anyhow::{bail, Result};
+use clap::ValueEnum;
+use serde::Deserialize;
+use tracing::info;
+
+use crate::commands::stack_labels::{open_group_prs, pr_list_text};
+use crate::execution::ExecutionMode;
+use crate::github::GraphqlMutation;
+use crate::parsing::Group;
+use crate::selectors::{resolve_group_index, GroupSelector};
+use crate::stack_segments::group_segment_base;
+
+/// How GitHub merges a PR once auto-merge fires.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[value(rename_all = "snake_case")]
+pub enum MergeMethod {
+    Squash,
+    Merge,
+    Rebase,
+}
+
+impl MergeMethod {
+    fn graphql_value(self) -> &'static str {
+        match self {
+            Self::Squash => "SQUASH",
+            Self::Merge => "MERGE",
+            Self::Rebase => "REBASE",
+        }
+    }
+}
+
+/// Whether the group at `idx` has a PR that targets a base branch rather than another stack
+/// branch: the bottom group, or a group that starts its own segment with `pr:base:`.
+fn targets_base_branch(groups: &[Group], idx: usize) -> bool {
+    idx == 0 || group_segment_base(&groups[idx]).is_some()
+}
+
+/// Resolve the group to auto-merge: `target`, or the bottom group when `None`.
+fn automerge_group_index(groups: &[Group], target: Option<&GroupSelector>) -> Result<usize> {
+    let Some(target) = target else {
+        return Ok(0);
+    };
+    let idx = resolve_group_index(groups, target)?;
+    if !targets_base_branch(groups, idx) {
+        bail!(
+            "LPR #{} {} targets the branch of the group below it, so GitHub would merge it there; \
+             enable auto-merge on the bottom PR (`spr automerge`) and re-run once it has landed",
+            idx + 1,
+            groups[idx].selector_text()
+        );
+    }
+    Ok(idx)
+}
+
+fn automerge_mutation(pr_ids: &[String], method: MergeMethod) -> GraphqlMutation {
+    let mut mutation = GraphqlMutation::new();
+    for (i, pr_id) in pr_ids.iter().enumerate() {
+        let pr = mutation.var("ID!", pr_id);
+        mutation.field(format!(
+            "a{i}: enablePullRequestAutoMerge(input:{{pullRequestId:{pr}, mergeMethod:{}}}){{ clientMutationId }}",
+            method.graphql_value()
+        ));
+    }
+    mutation
+}
+
+/// Enable auto-merge on the open PR of `target`, or on the bottom PR when `target` is `None`.
+///
+/// Returns the number of PRs changed.
+///
+/// # Errors
+///
+/// Returns errors when there are no groups, the target does not resolve or its PR targets another
+/// stack branch, or GitHub refuses auto-merge (for example when it is disabled for the
+/// repository).
+pub fn enable_automerge(
+    base: &str,
+    prefix: &str,
+    ignore_tag: &str,
+    target: Option<&GroupSelector>,
+    method: MergeMethod,
+    execution_mode: ExecutionMode,
+) -> Result<usize> {
+    let prs = open_group_prs(base, prefix, ignore_tag, |groups| {
+        Ok(vec![automerge_group_index(groups, target)?])
+    })?;
+    if prs.is_empty() {
+        info!("No open PRs to auto-merge; nothing to do.");
+        return Ok(0);
+    }
+    info!(
+        "Enabling {} auto-merge on {}",
+        method.graphql_value().to_lowercase(),
+        pr_list_text(&prs)
+    );
+    let pr_ids: Vec<String> = prs.iter().map(|(_, id)| id.clone()).collect();
+    automerge_mutation(&pr_ids, method).run(execution_mode)?;
+    Ok(prs.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{automerge_group_index, automerge_mutation, MergeMethod};
+    use crate::parsing::Group;
+    use crate::selectors::GroupSelector;
+
+    fn group(tag: &str, message: &str) -> Group {
+        Group {
+            marker: crate::group_markers::GroupMarker::PrLabel(tag.to_string()),
+            subjects: vec![message.to_string()],
+            commits: vec![format!("{tag}1")],
+            first_message: Some(message.to_string()),
+            ignored_after: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn automerge_only_selects_prs_that_target_a_base_branch() {
+        let groups = vec![
+            group("alpha", "feat: alpha pr:alpha"),
+            group("beta", "feat: beta pr:beta"),
+            group("gamma", "feat: gamma pr:gamma pr:base:release"),
+        ];
+        assert_eq!(automerge_group_index(&groups, None).unwrap(), 0);
+        assert_eq!(
+            automerge_group_index(&groups, Some(&GroupSelector::LocalPr(3))).unwrap(),
+            2
+        );
+        let err = automerge_group_index(&groups, Some(&GroupSelector::LocalPr(2)))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("LPR #2 pr:beta targets the branch"), "{err}");
+    }
+
+    #[test]
+    fn automerge_mutation_enables_every_pr_with_the_method() {
+        let mutation = automerge_mutation(
+            &["PR_1".to_string(), "PR_2".to_string()],
+            MergeMethod::Squash,
+        );
+        assert_eq!(
+            mutation.query(),
+            "mutation($v0:ID!,$v1:ID!) { \
+             a0: enablePullRequestAutoMerge(input:{pullRequestId:$v0, mergeMethod:SQUASH}){ clientMutationId } \
+             a1: enablePullRequestAutoMerge(input:{pullRequestId:$v1, mergeMethod:SQUASH}){ clientMutationId } }"
+        );
+    }
+}
@@ -0,0 +1,258 @@
+//! List and prune the backup refs that rewriting commands leave behind.
+//!
+//! `--safe` rewrites, local PR branch sync, and `spr linearize` keep the previous tip under a
+//! `backup/<kind>/<branch>-<short-sha>` tag, which is never removed automatically. `spr backup list`
+//! shows every one with the command kind that created it, its age, and whether the current `HEAD`
+//! already contains its tip; `spr backup prune` deletes those older than a number of days or
+//! already contained in `HEAD`. Only these tags are touched; branches are never considered backups.
+//!
+//! Backups are annotated tags, so their age is the tagger date, the moment the backup was taken.
+//! Lightweight backup tags from older versions have no date of their own; their age is unknown and
+//! `--older-than` leaves them alone.
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+use time::OffsetDateTime;
+use tracing::info;
+
+use crate::execution::ExecutionMode;
+use crate::git::{git_is_ancestor, git_ro, git_rw};
+
+/// Ref namespace holding the backup tags spr creates.
+const BACKUP_REF_PATTERN: &str = "refs/tags/backup/";
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BackupEntryData {
+    /// Tag name, such as `backup/restack/feature-1a2b3c4`.
+    pub name: String,
+    /// Command that created the backup, such as `restack` or `local-pr-branches`.
+    pub created_by: String,
+    /// Backed-up tip commit.
+    pub sha: String,
+    /// Unix time the backup was taken; `None` for lightweight tags from older versions.
+    pub created_at: Option<i64>,
+    pub age_days: Option<i64>,
+    /// True when the current `HEAD` already contains the tip.
+    pub in_head: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BackupsData {
+    pub backups: Vec<BackupEntryData>,
+}
+
+/// Parse one `for-each-ref` line of NUL-separated refname, tip, and tagger date (empty for
+/// lightweight tags).
+fn parse_backup_ref(line: &str, now: i64) -> Option<BackupEntryData> {
+    let mut fields = line.split('\0');
+    let name = fields.next()?.strip_prefix("refs/tags/")?;
+    let sha = fields.next()?.to_string();
+    let created_at: Option<i64> = fields.next()?.trim().parse().ok();
+    let created_by = name
+        .split('/')
+        .nth(1)
+        .filter(|_| name.matches('/').count() > 1)
+        .unwrap_or("unknown")
+        .to_string();
+    Some(BackupEntryData {
+        name: name.to_string(),
+        created_by,
+        sha,
+        created_at,
+        age_days: created_at.map(|created_at| (now - created_at).max(0) / SECONDS_PER_DAY),
+        in_head: false,
+    })
+}
+
+/// Every backup tag, oldest first.
+///
+/// # Errors
+///
+/// Returns errors from `git for-each-ref` or when `HEAD` cannot be resolved.
+pub fn collect_backups_data() -> Result<BackupsData> {
+    let args = [
+        "for-each-ref",
+        "--sort=creatordate",
+        "--format=%(refname)%00%(if)%(*objectname)%(then)%(*objectname)%(else)%(objectname)%(end)%00%(taggerdate:unix)",
+        BACKUP_REF_PATTERN,
+    ];
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+    let mut backups = Vec::new();
+    for line in git_ro(&args)?.lines() {
+        if let Some(mut backup) = parse_backup_ref(line, now) {
+            backup.in_head = git_is_ancestor(&backup.sha, "HEAD")?;
+            backups.push(backup);
+        }
+    }
+    Ok(BackupsData { backups })
+}
+
+/// Print every backup ref with its creator, age, and whether `HEAD` contains it.
+///
+/// # Errors
+///
+/// Returns the errors of [`collect_backups_data`].
+pub fn backup_list_display() -> Result<()> {
+    let data = collect_backups_data()?;
+    if data.backups.is_empty() {
+        info!("No backup refs found.");
+        return Ok(());
+    }
+    for backup in &data.backups {
+        let age = backup
+            .age_days
+            .map(|days| format!("{days}d old"))
+            .unwrap_or_else(|| "age unknown".to_string());
+        info!(
+            "{} {} ({}, {}){}",
+            &backup.sha[..backup.sha.len().min(8)],
+            backup.name,
+            backup.created_by,
+            age,
+            if backup.in_head { " in HEAD" } else { "" }
+        );
+    }
+    Ok(())
+}
+
+/// Backups older than `older_than_days` or, with `in_head`, already contained in `HEAD`.
+fn prunable(
+    backups: &[BackupEntryData],
+    older_than_days: Option<i64>,
+    in_head: bool,
+) -> Vec<&BackupEntryData> {
+    backups
+        .iter()
+        .filter(|backup| {
+            older_than_days
+                .zip(backup.age_days)
+                .is_some_and(|(days, age_days)| age_days >= days)
+                || (in_head && backup.in_head)
+        })
+        .collect()
+}
+
+/// Delete backups older than `older_than_days`, and with `in_head` those whose tip `HEAD`
+/// contains. Returns the number of refs deleted.
+///
+/// # Errors
+///
+/// Returns errors when neither criterion is given, or from `git tag -d`.
+pub fn prune_backups(
+    older_than_days: Option<u32>,
+    in_head: bool,
+    execution_mode: ExecutionMode,
+) -> Result<usize> {
+    if older_than_days.is_none() && !in_head {
+        bail!("spr backup prune needs --older-than <DAYS> or --in-head");
+    }
+    let data = collect_backups_data()?;
+    let selected = prunable(&data.backups, older_than_days.map(i64::from), in_head);
+    if selected.is_empty() {
+        info!("No backup refs to prune.");
+        return Ok(0);
+    }
+    for backup in &selected {
+        info!("Deleting backup {} ({})", backup.name, backup.created_by);
+        git_rw(execution_mode, ["tag", "-d", &backup.name].as_slice())?;
+    }
+    Ok(selected.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{collect_backups_data, prune_backups, SECONDS_PER_DAY};
+    use crate::commands::common::create_backup_tag_at;
+    use crate::execution::ExecutionMode;
+    use crate::test_support::{commit_file, git, init_repo, lock_cwd, write_file, DirGuard};
+    use std::process::Command;
+
+    #[test]
+    fn parse_backup_ref_reads_creator_and_age() {
+        let now = 10 * SECONDS_PER_DAY;
+        let tag = super::parse_backup_ref(
+            &format!(
+                "refs/tags/backup/restack/feature-1a2b3c4\0abc\0{}",
+                7 * SECONDS_PER_DAY
+            ),
+            now,
+        )
+        .unwrap();
+        assert_eq!(tag.name, "backup/restack/feature-1a2b3c4");
+        assert_eq!(tag.created_by, "restack");
+        assert_eq!(tag.age_days, Some(3));
+        let lightweight = super::parse_backup_ref("refs/tags/backup/old\0def\0", now).unwrap();
+        assert_eq!(lightweight.created_by, "unknown");
+        assert_eq!(lightweight.age_days, None);
+        assert!(super::parse_backup_ref("refs/heads/backup/old\0def\x00100", now).is_none());
+    }
+
+    #[test]
+    fn prune_in_head_deletes_only_backups_contained_in_head() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path();
+        commit_file(repo, "alpha.txt", "a\n", "feat: alpha");
+        git(repo, ["tag", "backup/restack/main-old"].as_slice());
+        git(repo, ["checkout", "-q", "-b", "side"].as_slice());
+        commit_file(repo, "beta.txt", "b\n", "feat: beta");
+        git(repo, ["tag", "backup/move/side-new"].as_slice());
+        git(repo, ["branch", "backup/mine"].as_slice());
+        git(repo, ["checkout", "-q", "main"].as_slice());
+        let _guard = DirGuard::change_to(repo);
+
+        let data = collect_backups_data().unwrap();
+        let names: Vec<(&str, bool)> = data
+            .backups
+            .iter()
+            .map(|backup| (backup.name.as_str(), backup.in_head))
+            .collect();
+        assert_eq!(names.len(), 2);
+        assert!(names.contains(&("backup/restack/main-old", true)));
+        assert!(names.contains(&("backup/move/side-new", false)));
+
+        assert_eq!(prune_backups(None, true, ExecutionMode::Apply).unwrap(), 1);
+        assert_eq!(
+            git(repo, ["tag", "--list", "backup/*"].as_slice()).trim(),
+            "backup/move/side-new"
+        );
+        assert_eq!(
+            git(repo, ["branch", "--list", "backup/*"].as_slice()).trim(),
+            "backup/mine"
+        );
+        assert!(prune_backups(None, false, ExecutionMode::Apply).is_err());
+    }
+
+    #[test]
+    fn backup_age_counts_from_when_it_was_taken_not_from_the_commit() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path();
+        write_file(repo, "old.txt", "old\n");
+        git(repo, ["add", "old.txt"].as_slice());
+        let status = Command::new("git")
+            .current_dir(repo)
+            .args(["commit", "-q", "-m", "feat: old"])
+            .env("GIT_AUTHOR_DATE", "2020-01-01T00:00:00Z")
+            .env("GIT_COMMITTER_DATE", "2020-01-01T00:00:00Z")
+            .status()
+            .unwrap();
+        assert!(status.success());
+        let _guard = DirGuard::change_to(repo);
+        create_backup_tag_at(ExecutionMode::Apply, "restack", "main", "abc1234", "HEAD").unwrap();
+
+        let data = collect_backups_data().unwrap();
+        assert_eq!(data.backups.len(), 1);
+        assert_eq!(data.backups[0].age_days, Some(0));
+        assert_eq!(
+            prune_backups(Some(30), false, ExecutionMode::Apply).unwrap(),
+            0
+        );
+        assert_eq!(
+            git(repo, ["tag", "--list", "backup/*"].as_slice()).trim(),
+            "backup/restack/main-abc1234"
+        );
+    }
+}
@@ -0,0 +1,233 @@
+//! Check out exactly what one PR group contains with `spr checkout <n>`.
+//!
+//! The local branch named like the group's PR branch (`<prefix><tag>`, or `--branch`) is created
+//! or moved to the group's last commit and checked out, so the group can be built and tested as
+//! its PR would merge it. A branch left behind by a restack or amend is moved freely: it is safe
+//! when it sits at the SHA spr last published for the group, or when each of its commits has a
+//! patch-equivalent commit in the group. A branch that holds other commits (for example work
+//! committed on it after an earlier checkout) is only moved with `--force`. Return to the stack
+//! with `git checkout -`.
+
+use anyhow::{bail, Result};
+use tracing::info;
+
+use crate::branch_names::group_branch_identities;
+use crate::execution::ExecutionMode;
+use crate::git::{git_is_ancestor, git_ref_exists_at, git_rev_parse, git_ro, git_rw};
+use crate::parsing::derive_local_groups;
+use crate::selectors::{resolve_group_ordinal, GroupSelector};
+
+/// Commits on `current` that `tip` has no patch-equivalent of, oldest first, as `<sha> <subject>`.
+///
+/// Empty when moving from `current` to `tip` loses no work: `current` is an ancestor of `tip`, or
+/// is the SHA spr last published for `head_branch` (what an earlier checkout or update left
+/// behind), or every commit only on `current` was rewritten into `tip` unchanged.
+fn commits_lost_by_reset(
+    prefix: &str,
+    head_branch: &str,
+    current: &str,
+    tip: &str,
+) -> Result<Vec<String>> {
+    if current == tip || git_is_ancestor(current, tip)? {
+        return Ok(Vec::new());
+    }
+    if crate::remote_state::recorded_push_sha(prefix, head_branch)?.as_deref() == Some(current) {
+        return Ok(Vec::new());
+    }
+    Ok(git_ro(["cherry", "-v", tip, current].as_slice())?
+        .lines()
+        .filter_map(|line| line.strip_prefix("+ "))
+        .map(str::to_string)
+        .collect())
+}
+
+/// Point `branch` (default: the group's PR branch name) at the tip of `target` and check it out.
+///
+/// Returns the branch checked out.
+///
+/// # Errors
+///
+/// Returns errors when `target` does not resolve, the branch holds commits outside the group and
+/// `force` is not set, or `git checkout` fails (for example over conflicting local changes).
+pub fn checkout_group(
+    base: &str,
+    prefix: &str,
+    ignore_tag: &str,
+    target: &GroupSelector,
+    branch: Option<&str>,
+    force: bool,
+    execution_mode: ExecutionMode,
+) -> Result<String> {
+    let (_merge_base, groups) = derive_local_groups(base, ignore_tag)?;
+    if groups.is_empty() {
+        bail!("No local PR groups found; nothing to check out.");
+    }
+    let local_pr_number = resolve_group_ordinal(&groups, target)?;
+    let group = &groups[local_pr_number - 1];
+    let head_branch = group_branch_identities(&groups, prefix)?[local_pr_number - 1]
+        .exact
+        .clone();
+    let branch = branch.map_or_else(|| head_branch.clone(), str::to_string);
+    let Some(tip) = group.commits.last() else {
+        bail!("{} has no commits", group.selector_text());
+    };
+    if git_ref_exists_at(".", &format!("refs/heads/{branch}"))? {
+        let current = git_rev_parse(&format!("refs/heads/{branch}"))?;
+        let lost = commits_lost_by_reset(prefix, &head_branch, &current, tip)?;
+        if !lost.is_empty() && !force {
+            bail!(
+                "{branch} has commits that are not in {}:\n  {}\nCherry-pick them onto the stack to keep them, or pass --force to reset {branch} and drop them",
+                group.selector_text(),
+                lost.join("\n  ")
+            );
+        }
+    }
+    git_rw(execution_mode, ["checkout", "-B", &branch, tip].as_slice())?;
+    if execution_mode == ExecutionMode::DryRun {
+        info!(
+            "DRY-RUN: would check out {} at LPR #{} {} ({})",
+            branch,
+            local_pr_number,
+            group.selector_text(),
+            &tip[..tip.len().min(8)]
+        );
+    } else {
+        info!(
+            "Checked out {} at LPR #{} {} ({}); `git checkout -` returns to the stack",
+            branch,
+            local_pr_number,
+            group.selector_text(),
+            &tip[..tip.len().min(8)]
+        );
+    }
+    Ok(branch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::checkout_group;
+    use crate::execution::ExecutionMode;
+    use crate::selectors::GroupSelector;
+    use crate::test_support::{commit_file, git, init_repo, lock_cwd, DirGuard};
+
+    #[test]
+    fn checkout_group_moves_the_group_branch_and_refuses_to_drop_extra_commits() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path().to_path_buf();
+        git(&repo, ["checkout", "-b", "stack"].as_slice());
+        commit_file(&repo, "alpha.txt", "a\n", "feat: alpha pr:alpha");
+        let alpha_tip = commit_file(&repo, "alpha.txt", "a2\n", "feat: alpha again");
+        commit_file(&repo, "beta.txt", "b\n", "feat: beta pr:beta");
+        let _guard = DirGuard::change_to(&repo);
+
+        let branch = checkout_group(
+            "main",
+            "dank-spr/",
+            "ignore",
+            &GroupSelector::LocalPr(1),
+            None,
+            false,
+            ExecutionMode::Apply,
+        )
+        .unwrap();
+
+        assert_eq!(branch, "dank-spr/alpha");
+        assert_eq!(
+            git(&repo, ["symbolic-ref", "--short", "HEAD"].as_slice()).trim(),
+            "dank-spr/alpha"
+        );
+        assert_eq!(
+            git(&repo, ["rev-parse", "HEAD"].as_slice()).trim(),
+            alpha_tip
+        );
+
+        commit_file(&repo, "extra.txt", "x\n", "wip: appended");
+        git(&repo, ["checkout", "stack"].as_slice());
+        let err = checkout_group(
+            "main",
+            "dank-spr/",
+            "ignore",
+            &GroupSelector::Bare("alpha".to_string()),
+            None,
+            false,
+            ExecutionMode::Apply,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("wip: appended"), "{err:#}");
+        assert!(err.to_string().contains("--force"), "{err:#}");
+        assert_eq!(
+            git(&repo, ["symbolic-ref", "--short", "HEAD"].as_slice()).trim(),
+            "stack"
+        );
+    }
+
+    #[test]
+    fn checkout_group_moves_branches_left_behind_by_rewrites() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path().to_path_buf();
+        git(&repo, ["checkout", "-b", "stack"].as_slice());
+        commit_file(&repo, "alpha.txt", "a\n", "feat: alpha pr:alpha");
+        let _guard = DirGuard::change_to(&repo);
+        let checkout = || {
+            checkout_group(
+                "main",
+                "dank-spr/",
+                "ignore",
+                &GroupSelector::LocalPr(1),
+                None,
+                false,
+                ExecutionMode::Apply,
+            )
+        };
+        checkout().unwrap();
+        git(&repo, ["checkout", "-q", "stack"].as_slice());
+
+        // Restack: main moves on and the group is replayed onto it unchanged
+        git(&repo, ["checkout", "-q", "main"].as_slice());
+        commit_file(&repo, "main.txt", "m\n", "chore: main moves on");
+        git(&repo, ["checkout", "-q", "stack"].as_slice());
+        git(&repo, ["rebase", "-q", "main"].as_slice());
+        checkout().unwrap();
+        let restacked = git(&repo, ["rev-parse", "HEAD"].as_slice())
+            .trim()
+            .to_string();
+        git(&repo, ["checkout", "-q", "stack"].as_slice());
+
+        // Amend: the content changes, but the branch is still the SHA spr last published
+        let state = repo.join("state.json");
+        std::fs::write(
+            &state,
+            format!(
+                r#"{{"schema_version":1,"prefix":"dank-spr/","entries":[{{"head_branch":"dank-spr/alpha","selector":"pr:alpha","pr_number":1,"sha":"{restacked}","stack_branch":"stack","updated_at":"2026-01-01T00:00:00Z"}}]}}"#
+            ),
+        )
+        .unwrap();
+        let blob = git(&repo, ["hash-object", "-w", "state.json"].as_slice());
+        let tree = crate::git::git_with_stdin(
+            ["mktree"].as_slice(),
+            &format!("100644 blob {}\tstate.json\n", blob.trim()),
+        )
+        .unwrap();
+        let commit = git(
+            &repo,
+            ["commit-tree", tree.trim(), "-m", "state"].as_slice(),
+        );
+        git(
+            &repo,
+            ["update-ref", "refs/spr/state/dank-spr", commit.trim()].as_slice(),
+        );
+        std::fs::remove_file(&state).unwrap();
+        std::fs::write(repo.join("alpha.txt"), "a amended\n").unwrap();
+        git(
+            &repo,
+            ["commit", "-q", "-a", "--amend", "--no-edit"].as_slice(),
+        );
+        checkout().unwrap();
+        assert_eq!(
+            git(&repo, ["show", "HEAD:alpha.txt"].as_slice()),
+            "a amended\n"
+        );
+    }
+}
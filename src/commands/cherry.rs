@@ -0,0 +1,277 @@
+//! Backport one PR group onto another branch with `spr cherry <n> --onto <branch>`.
+//!
+//! The group's commits are cherry-picked with `-x` onto the remote-tracking `<branch>` in a temp
+//! worktree, so the checkout is never touched. The result is pushed as
+//! `backport/<branch>/<head-branch>`, outside the stack prefix so `spr cleanup` leaves it alone,
+//! and opened as a PR against `<branch>` whose body links the group's PR. The group's PR gets a
+//! comment linking back to the backport.
+
+use anyhow::{bail, Result};
+use std::collections::HashMap;
+use tracing::{info, warn};
+
+use crate::branch_names::group_branch_identities;
+use crate::commands::common::{cleanup_temp_worktree, create_temp_worktree, tip_of_tmp};
+use crate::execution::ExecutionMode;
+use crate::git::{
+    base_remote, fetch_remote, gh_rw, git_ref_exists_at, git_ro, git_rw, remote,
+    sanitize_gh_base_ref,
+};
+use crate::github::{list_open_or_merged_prs_for_heads, list_open_prs_for_heads, upsert_pr_cached};
+use crate::parsing::derive_local_groups;
+use crate::selectors::{resolve_group_ordinal, GroupSelector};
+
+/// The ref to cherry-pick onto: `<base-remote>/<onto>` when that remote-tracking ref exists,
+/// else `onto` as given (for example `upstream/release-2.0` or a local branch).
+fn resolve_onto(onto: &str) -> Result<String> {
+    let tracking = format!("{}/{onto}", base_remote());
+    if git_ref_exists_at(".", &format!("refs/remotes/{tracking}"))? {
+        return Ok(tracking);
+    }
+    if git_ro(
+        [
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{onto}^{{commit}}"),
+        ]
+        .as_slice(),
+    )
+    .is_err()
+    {
+        bail!(
+            "--onto `{onto}` is neither a branch of `{}` nor a local ref",
+            base_remote()
+        );
+    }
+    Ok(onto.to_string())
+}
+
+fn backport_branch(target_branch: &str, head_branch: &str) -> String {
+    format!("backport/{target_branch}/{head_branch}")
+}
+
+fn backport_title(target_branch: &str, subject: &str) -> String {
+    format!("[{target_branch}] {subject}")
+}
+
+fn backport_body(
+    target_branch: &str,
+    original_pr: Option<u64>,
+    head_branch: &str,
+    commits: &[(String, String)],
+) -> String {
+    let source = match original_pr {
+        Some(number) => format!("#{number}"),
+        None => format!("`{head_branch}`"),
+    };
+    let mut body = format!("Backport of {source} to `{target_branch}`.\n\nCherry-picked:\n");
+    for (sha, subject) in commits {
+        body.push_str(&format!("- {} {subject}\n", &sha[..sha.len().min(12)]));
+    }
+    body
+}
+
+/// Cherry-pick `commits` onto `onto` in a temp worktree and return the new tip.
+///
+/// The temp worktree is removed afterwards, also when a pick conflicts.
+fn pick_onto(onto: &str, commits: &[String]) -> Result<String> {
+    let (Some(first), Some(last)) = (commits.first(), commits.last()) else {
+        bail!("Nothing to cherry-pick");
+    };
+    let short = &last[..last.len().min(8)];
+    let (tmp_path, tmp_branch) = create_temp_worktree(ExecutionMode::Apply, "cherry", onto, short)?;
+    let picked = git_rw(
+        ExecutionMode::Apply,
+        [
+            "-C",
+            &tmp_path,
+            "cherry-pick",
+            "-x",
+            &format!("{first}^..{last}"),
+        ]
+        .as_slice(),
+    )
+    .and_then(|_| tip_of_tmp(&tmp_path));
+    if picked.is_err() {
+        let conflicted =
+            git_ro(["-C", &tmp_path, "diff", "--name-only", "--diff-filter=U"].as_slice())
+                .unwrap_or_default();
+        let _ = git_ro(["-C", &tmp_path, "cherry-pick", "--abort"].as_slice());
+        cleanup_temp_worktree(ExecutionMode::Apply, &tmp_path, &tmp_branch)?;
+        let conflicted: Vec<&str> = conflicted.lines().collect();
+        if !conflicted.is_empty() {
+            bail!(
+                "Cherry-picking onto {onto} conflicts in {}; backport these commits by hand",
+                conflicted.join(", ")
+            );
+        }
+        return picked;
+    }
+    cleanup_temp_worktree(ExecutionMode::Apply, &tmp_path, &tmp_branch)?;
+    picked
+}
+
+/// Backport group `target` onto `onto`, push it, and open a PR that links the group's PR.
+///
+/// # Errors
+///
+/// Returns errors when the target or `onto` does not resolve, a commit does not apply, or the
+/// push or PR creation fails.
+pub fn cherry_group(
+    base: &str,
+    prefix: &str,
+    ignore_tag: &str,
+    target: &GroupSelector,
+    onto: &str,
+    draft: bool,
+    execution_mode: ExecutionMode,
+) -> Result<()> {
+    let (_merge_base, groups) = derive_local_groups(base, ignore_tag)?;
+    if groups.is_empty() {
+        bail!("No local PR groups found; nothing to cherry-pick.");
+    }
+    let local_pr_number = resolve_group_ordinal(&groups, target)?;
+    let group = &groups[local_pr_number - 1];
+    let head_branch = group_branch_identities(&groups, prefix)?[local_pr_number - 1]
+        .exact
+        .clone();
+    fetch_remote(execution_mode)?;
+    let onto_ref = resolve_onto(onto)?;
+    let target_branch = sanitize_gh_base_ref(&onto_ref);
+    let branch = backport_branch(&target_branch, &head_branch);
+    let original_pr = match list_open_or_merged_prs_for_heads(std::slice::from_ref(&head_branch)) {
+        Ok(prs) => prs.first().map(|pr| pr.number),
+        Err(err) => {
+            warn!("Could not look up the PR of {head_branch}: {err:#}");
+            None
+        }
+    };
+    if execution_mode == ExecutionMode::DryRun {
+        info!(
+            "DRY-RUN: would cherry-pick {} commit(s) of {} onto {}, push {}, and open a PR against {}",
+            group.commits.len(),
+            group.selector_text(),
+            onto_ref,
+            branch,
+            target_branch
+        );
+        return Ok(());
+    }
+
+    info!(
+        "Cherry-picking {} commit(s) of {} onto {}…",
+        group.commits.len(),
+        group.selector_text(),
+        onto_ref
+    );
+    let tip = pick_onto(&onto_ref, &group.commits)?;
+    git_rw(
+        execution_mode,
+        [
+            "push",
+            "-f",
+            &remote(),
+            &format!("{tip}:refs/heads/{branch}"),
+        ]
+        .as_slice(),
+    )?;
+
+    match list_open_prs_for_heads(std::slice::from_ref(&branch))?.first() {
+        Some(existing) => {
+            info!("Updated {} of backport PR #{}", branch, existing.number);
+        }
+        None => {
+            let commits: Vec<(String, String)> = group
+                .commits
+                .iter()
+                .cloned()
+                .zip(group.subjects.iter().cloned())
+                .collect();
+            let subject = group.subjects.first().map(String::as_str).unwrap_or("");
+            let number = upsert_pr_cached(
+                &branch,
+                &target_branch,
+                &backport_title(&target_branch, subject),
+                &backport_body(&target_branch, original_pr, &head_branch, &commits),
+                draft,
+                execution_mode,
+                &mut HashMap::new(),
+            )?;
+            info!("Opened backport PR #{} against {}", number, target_branch);
+            if let Some(original_pr) = original_pr {
+                if let Err(err) = gh_rw(
+                    execution_mode,
+                    [
+                        "pr",
+                        "comment",
+                        &original_pr.to_string(),
+                        "--body",
+                        &format!("Backported to `{target_branch}` in #{number}."),
+                    ]
+                    .as_slice(),
+                ) {
+                    warn!("Could not link backport #{number} from #{original_pr}: {err:#}");
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{backport_body, pick_onto};
+    use crate::test_support::{commit_file, git, init_repo, lock_cwd, DirGuard};
+
+    #[test]
+    fn backport_body_links_the_original_pr_and_lists_commits() {
+        let body = backport_body(
+            "release-2.0",
+            Some(42),
+            "jane-spr/api",
+            &[("0123456789abcdef".to_string(), "feat: api".to_string())],
+        );
+        assert_eq!(
+            body,
+            "Backport of #42 to `release-2.0`.\n\nCherry-picked:\n- 0123456789ab feat: api\n"
+        );
+        assert!(backport_body("release-2.0", None, "jane-spr/api", &[])
+            .starts_with("Backport of `jane-spr/api` to"));
+    }
+
+    #[test]
+    fn pick_onto_replays_commits_without_touching_the_checkout() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path();
+        git(repo, ["branch", "release"].as_slice());
+        commit_file(repo, "other.txt", "main only\n", "chore: main only");
+        let alpha = commit_file(repo, "alpha.txt", "a\n", "feat: alpha pr:alpha");
+        let alpha2 = commit_file(repo, "alpha.txt", "a2\n", "feat: alpha again");
+        let head = git(repo, ["rev-parse", "HEAD"].as_slice());
+        let _guard = DirGuard::change_to(repo);
+
+        let tip = pick_onto("release", &[alpha, alpha2.clone()]).unwrap();
+
+        assert_eq!(git(repo, ["rev-parse", "HEAD"].as_slice()), head);
+        assert_eq!(
+            git(repo, ["show", &format!("{tip}:alpha.txt")].as_slice()),
+            "a2\n"
+        );
+        assert_eq!(
+            git(repo, ["ls-tree", "--name-only", &tip].as_slice()),
+            git(repo, ["ls-tree", "--name-only", "release"].as_slice()) + "alpha.txt\n"
+        );
+        assert!(git(repo, ["log", "-1", "--format=%B", &tip].as_slice())
+            .contains(&format!("cherry picked from commit {alpha2}")));
+        assert_eq!(
+            git(
+                repo,
+                ["rev-list", "--count", &format!("release..{tip}")].as_slice()
+            )
+            .trim(),
+            "2"
+        );
+    }
+}
@@ -1,12 +1,30 @@
 use anyhow::Result;
-use tracing::info;
+use std::collections::HashSet;
+use std::io::{BufRead, IsTerminal, Write};
+use tracing::{info, warn};
 
+use crate::branch_names::{canonical_branch_conflict_key, group_branch_identities};
 use crate::execution::ExecutionMode;
-use crate::git::{git_rw, list_remote_branches_with_prefix};
-use crate::github::list_open_pr_heads;
+use crate::git::{gh_rw, git_current_branch, git_rw, list_remote_branches_with_prefix, remote};
+use crate::github::{list_open_pr_heads, list_open_pr_numbers_by_head};
 use crate::maintenance_output::{
-    CleanupAction, CleanupDecisionData, CleanupRepoContext, CleanupSummaryData, MaintenanceOptions,
+    CleanupAction, CleanupDecisionData, CleanupOrphanedPrAction, CleanupOrphanedPrData,
+    CleanupRepoContext, CleanupSummaryData, MaintenanceOptions,
 };
+use crate::parsing::derive_local_groups;
+use crate::remote_state::branches_published_from;
+
+/// The local stack that `spr cleanup --close-orphaned-prs` treats as the source of truth.
+#[derive(Debug, Clone, Copy)]
+pub struct OrphanedPrScope<'a> {
+    pub base: &'a str,
+    pub ignore_tag: &'a str,
+    /// Close without asking; otherwise the candidates are listed and confirmed on the terminal.
+    pub assume_yes: bool,
+}
+
+const ORPHANED_PR_CLOSE_COMMENT: &str =
+    "Closed by `spr cleanup`: this branch no longer matches any group in the local stack.";
 
 fn render_cleanup_action(action: CleanupAction) -> &'static str {
     match action {
@@ -16,7 +34,22 @@ fn render_cleanup_action(action: CleanupAction) -> &'static str {
     }
 }
 
+fn render_orphaned_pr_action(action: CleanupOrphanedPrAction) -> &'static str {
+    match action {
+        CleanupOrphanedPrAction::Close => "closed orphaned pr",
+        CleanupOrphanedPrAction::DryRunClose => "would close orphaned pr",
+    }
+}
+
 pub fn print_cleanup_summary(summary: &CleanupSummaryData) {
+    for orphan in &summary.orphaned_prs {
+        info!(
+            "#{} {} ({})",
+            orphan.number,
+            orphan.head_branch,
+            render_orphaned_pr_action(orphan.action)
+        );
+    }
     if summary.remote_candidates.is_empty() {
         info!(
             "No remote branches found with prefix {}",
@@ -33,9 +66,71 @@ pub fn print_cleanup_summary(summary: &CleanupSummaryData) {
     }
 }
 
+/// Select open PRs on prefixed remote branches that this stack published (`owned`) and whose heads
+/// match no live local group.
+///
+/// Branches published from other stack branches under the same prefix are never candidates.
+/// Matching uses the case-insensitive branch conflict key so a case-only rename on GitHub does not
+/// make a live PR look abandoned. The result is sorted by branch name.
+fn select_orphaned_prs(
+    branches: &[String],
+    open_prs_by_head: &std::collections::HashMap<String, u64>,
+    owned: &HashSet<String>,
+    local_heads: &[String],
+) -> Vec<(String, u64)> {
+    let local_keys: HashSet<_> = local_heads
+        .iter()
+        .map(|head| canonical_branch_conflict_key(head))
+        .collect();
+    branches
+        .iter()
+        .filter(|branch| owned.contains(*branch))
+        .filter(|branch| !local_keys.contains(&canonical_branch_conflict_key(branch)))
+        .filter_map(|branch| {
+            open_prs_by_head
+                .get(branch)
+                .map(|number| (branch.clone(), *number))
+        })
+        .collect()
+}
+
+/// List the orphaned PRs and ask whether to close them.
+///
+/// Without a terminal to ask on, nothing is closed; `--yes` skips the prompt.
+fn confirm_closing_orphans(orphans: &[(String, u64)]) -> Result<bool> {
+    if !std::io::stdin().is_terminal() {
+        warn!(
+            "Not closing {} orphaned PR(s) without confirmation; pass --yes to close them",
+            orphans.len()
+        );
+        return Ok(false);
+    }
+    let mut stderr = std::io::stderr();
+    writeln!(stderr, "Orphaned PRs published from this stack:")?;
+    for (head_branch, number) in orphans {
+        writeln!(stderr, "  #{number} {head_branch}")?;
+    }
+    write!(
+        stderr,
+        "Close {} PR(s) and delete their branches? [y/N] ",
+        orphans.len()
+    )?;
+    stderr.flush()?;
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
 /// Delete remote branches that start with the configured prefix and have only closed PRs (or no PRs)
+///
+/// When `orphaned_pr_scope` is set, open PRs whose head branches the current stack branch published
+/// (per the shared state in `refs/spr/state/<prefix>`) but that no longer correspond to any group
+/// in that local stack are first closed with an explanatory comment, which then makes their
+/// branches eligible for deletion in the same run. Closing needs `assume_yes` or a confirmation on
+/// the terminal; declined PRs stay open and keep their branches.
 pub fn cleanup_remote_branches(
     prefix: &str,
+    orphaned_pr_scope: Option<OrphanedPrScope<'_>>,
     execution_mode: ExecutionMode,
 ) -> Result<CleanupSummaryData> {
     let dry_run = execution_mode == ExecutionMode::DryRun;
@@ -51,9 +146,57 @@ pub fn cleanup_remote_branches(
             open_pr_heads: Vec::new(),
             decisions: Vec::new(),
             delete_batch: Vec::new(),
+            orphaned_prs: Vec::new(),
         });
     }
-    let mut open_heads: Vec<String> = list_open_pr_heads()?.into_iter().collect();
+
+    let mut orphaned_prs = Vec::new();
+    let mut open_heads: Vec<String> = if let Some(scope) = orphaned_pr_scope {
+        let (_merge_base, groups) = derive_local_groups(scope.base, scope.ignore_tag)?;
+        let local_heads: Vec<String> = group_branch_identities(&groups, prefix)?
+            .into_iter()
+            .map(|identity| identity.exact)
+            .collect();
+        let owned = branches_published_from(prefix, &git_current_branch()?)?;
+        let open_prs_by_head = list_open_pr_numbers_by_head()?;
+        let mut orphans = select_orphaned_prs(&branches, &open_prs_by_head, &owned, &local_heads);
+        if !orphans.is_empty()
+            && !dry_run
+            && !scope.assume_yes
+            && !confirm_closing_orphans(&orphans)?
+        {
+            orphans.clear();
+        }
+        for (head_branch, number) in &orphans {
+            gh_rw(
+                execution_mode,
+                [
+                    "pr",
+                    "close",
+                    &format!("#{}", number),
+                    "--comment",
+                    ORPHANED_PR_CLOSE_COMMENT,
+                ]
+                .as_slice(),
+            )?;
+            orphaned_prs.push(CleanupOrphanedPrData {
+                number: *number,
+                head_branch: head_branch.clone(),
+                action: if dry_run {
+                    CleanupOrphanedPrAction::DryRunClose
+                } else {
+                    CleanupOrphanedPrAction::Close
+                },
+            });
+        }
+        let closed: HashSet<&String> = orphans.iter().map(|(head, _)| head).collect();
+        open_prs_by_head
+            .into_keys()
+            .filter(|head| !closed.contains(head))
+            .collect()
+    } else {
+        list_open_pr_heads()?.into_iter().collect()
+    };
     open_heads.sort();
 
     let decisions: Vec<CleanupDecisionData> = branches
@@ -81,7 +224,7 @@ pub fn cleanup_remote_branches(
         .collect();
 
     if !delete_batch.is_empty() {
-        let mut owned_args: Vec<String> = vec!["push".into(), "origin".into(), "--delete".into()];
+        let mut owned_args: Vec<String> = vec!["push".into(), remote(), "--delete".into()];
         owned_args.extend(delete_batch.iter().cloned());
         let args: Vec<&str> = owned_args.iter().map(String::as_str).collect();
         let _ = git_rw(execution_mode, &args)?;
@@ -96,15 +239,21 @@ pub fn cleanup_remote_branches(
         open_pr_heads: open_heads,
         decisions,
         delete_batch,
+        orphaned_prs,
     })
 }
 
 #[cfg(test)]
 mod tests {
-    use super::cleanup_remote_branches;
+    use super::{cleanup_remote_branches, select_orphaned_prs, OrphanedPrScope};
     use crate::execution::ExecutionMode;
-    use crate::maintenance_output::CleanupAction;
+    use crate::maintenance_output::{CleanupAction, CleanupOrphanedPrAction};
+    use crate::remote_state::publish_update_state;
     use crate::test_support::{commit_file, git, lock_cwd, write_file, DirGuard};
+    use crate::update_output::{
+        UpdateEditAction, UpdateGroupData, UpdatePrAction, UpdatePushAction,
+    };
+    use std::collections::{HashMap, HashSet};
     use std::env;
     use std::fs;
     use std::os::unix::fs::PermissionsExt;
@@ -190,6 +339,23 @@ mod tests {
         dir
     }
 
+    fn published(head_branch: &str, number: u64) -> UpdateGroupData {
+        UpdateGroupData {
+            local_pr_number: 1,
+            stable_handle: format!("pr:{}", head_branch.rsplit('/').next().unwrap()),
+            head_branch: head_branch.to_string(),
+            base_ref: "main".to_string(),
+            title: "feat".to_string(),
+            target_sha: "0".repeat(40),
+            push_action: UpdatePushAction::CreateBranch,
+            pr_action: UpdatePrAction::Created,
+            base_ref_action: UpdateEditAction::Unchanged,
+            description_action: UpdateEditAction::Updated,
+            remote_pr_number: Some(number),
+            remote_pr_url: None,
+        }
+    }
+
     fn log_contents(path: &Path) -> String {
         fs::read_to_string(path).unwrap_or_default()
     }
@@ -207,7 +373,7 @@ mod tests {
         );
         let (_wrapper_dir, _path_guard) = install_gh_wrapper(&script);
 
-        let summary = cleanup_remote_branches("skilltest/", ExecutionMode::DryRun).unwrap();
+        let summary = cleanup_remote_branches("skilltest/", None, ExecutionMode::DryRun).unwrap();
 
         assert_eq!(
             summary.remote_candidates,
@@ -235,7 +401,7 @@ mod tests {
         );
         let (_wrapper_dir, _path_guard) = install_gh_wrapper(&script);
 
-        let summary = cleanup_remote_branches("missing/", ExecutionMode::DryRun).unwrap();
+        let summary = cleanup_remote_branches("missing/", None, ExecutionMode::DryRun).unwrap();
 
         assert!(summary.remote_candidates.is_empty());
         assert!(summary.open_pr_heads.is_empty());
@@ -243,4 +409,99 @@ mod tests {
         assert!(summary.delete_batch.is_empty());
         assert!(log_contents(&log_path).is_empty());
     }
+
+    #[test]
+    fn select_orphaned_prs_ignores_live_heads_case_insensitively() {
+        let branches = vec![
+            "skilltest/Alpha".to_string(),
+            "skilltest/beta".to_string(),
+            "skilltest/gamma".to_string(),
+            "skilltest/delta".to_string(),
+        ];
+        let open_prs_by_head = HashMap::from([
+            ("skilltest/Alpha".to_string(), 11),
+            ("skilltest/beta".to_string(), 12),
+            ("skilltest/delta".to_string(), 14),
+        ]);
+        let owned: HashSet<String> = ["skilltest/Alpha", "skilltest/beta", "skilltest/gamma"]
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+
+        let orphans = select_orphaned_prs(
+            &branches,
+            &open_prs_by_head,
+            &owned,
+            &["skilltest/alpha".to_string()],
+        );
+
+        assert_eq!(orphans, vec![("skilltest/beta".to_string(), 12)]);
+    }
+
+    #[test]
+    fn cleanup_close_orphaned_prs_closes_and_deletes_abandoned_branches() {
+        let _lock = lock_cwd();
+        let dir = init_cleanup_repo();
+        let repo = dir.path().join("repo");
+        let _guard = DirGuard::change_to(&repo);
+        commit_file(&repo, "alpha.txt", "alpha\n", "feat: alpha pr:alpha");
+        git(
+            &repo,
+            ["push", "origin", "main:refs/heads/skilltest/gamma"].as_slice(),
+        );
+        publish_update_state(
+            "skilltest/",
+            "main",
+            &[
+                published("skilltest/alpha", 11),
+                published("skilltest/beta", 12),
+            ],
+            ExecutionMode::Apply,
+        )
+        .unwrap();
+        publish_update_state(
+            "skilltest/",
+            "other-stack",
+            &[published("skilltest/gamma", 13)],
+            ExecutionMode::Apply,
+        )
+        .unwrap();
+        let log_path = repo.join("gh.log");
+        let script = format!(
+            "#!/bin/sh\nprintf '%s\\n' \"$*\" >> \"{}\"\nif [ \"$1\" = \"pr\" ] && [ \"$2\" = \"list\" ]; then\n  echo '[{{\"number\":11,\"headRefName\":\"skilltest/alpha\"}},{{\"number\":12,\"headRefName\":\"skilltest/beta\"}},{{\"number\":13,\"headRefName\":\"skilltest/gamma\"}}]'\n  exit 0\nfi\nif [ \"$1\" = \"pr\" ] && [ \"$2\" = \"close\" ]; then\n  exit 0\nfi\necho \"unexpected gh invocation: $*\" >&2\nexit 1\n",
+            log_path.display()
+        );
+        let (_wrapper_dir, _path_guard) = install_gh_wrapper(&script);
+
+        let summary = cleanup_remote_branches(
+            "skilltest/",
+            Some(OrphanedPrScope {
+                base: "origin/main",
+                ignore_tag: "ignore",
+                assume_yes: true,
+            }),
+            ExecutionMode::Apply,
+        )
+        .unwrap();
+
+        assert_eq!(summary.orphaned_prs.len(), 1);
+        assert_eq!(summary.orphaned_prs[0].number, 12);
+        assert_eq!(summary.orphaned_prs[0].head_branch, "skilltest/beta");
+        assert_eq!(
+            summary.orphaned_prs[0].action,
+            CleanupOrphanedPrAction::Close
+        );
+        assert_eq!(
+            summary.open_pr_heads,
+            vec!["skilltest/alpha".to_string(), "skilltest/gamma".to_string()]
+        );
+        assert_eq!(summary.delete_batch, vec!["skilltest/beta".to_string()]);
+        let log = log_contents(&log_path);
+        assert!(log.contains("pr close #12 --comment"));
+        assert!(!log.contains("pr close #11"));
+        assert!(!log.contains("pr close #13"));
+        let remote = git(&repo, ["ls-remote", "--heads", "origin"].as_slice());
+        assert!(remote.contains("skilltest/alpha"));
+        assert!(!remote.contains("skilltest/beta"));
+    }
 }
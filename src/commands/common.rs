@@ -30,12 +30,14 @@ use anyhow::{bail, Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::fs;
+use std::io::Write;
 use std::path::Path;
+use std::process::{Command, Stdio};
 use tracing::{info, warn};
 
 use crate::config::DirtyWorktreePolicy;
 use crate::execution::ExecutionMode;
-use crate::git::{git_ro, git_rw, repo_root, worktree_entries};
+use crate::git::{git_common_dir, git_ro, git_rw, repo_root, worktree_entries};
 use crate::parsing::Group;
 
 /// Returns the current branch name and the short SHA of `HEAD`.
@@ -90,10 +92,133 @@ pub fn create_backup_tag_at(
     }
     // Use `-f` to make backup creation idempotent. When the name already
     // exists, we explicitly move it to the current target.
-    let _ = git_rw(execution_mode, ["tag", "-f", &backup, target].as_slice())?;
+    // Annotated, so the tagger date records when the backup was taken for `spr backup prune`.
+    let message = format!("spr backup ({kind}) of {cur_branch}");
+    let _ = git_rw(
+        execution_mode,
+        ["tag", "-f", "-a", "-m", &message, &backup, target].as_slice(),
+    )?;
     Ok(backup)
 }
 
+/// Writes a copy of `sha` with `message`, keeping its tree, first parent, and author.
+///
+/// The new commit is not referenced by any branch; rewrites replay it in place of `sha`.
+pub fn commit_with_message(sha: &str, message: &str) -> Result<String> {
+    commit_with_tree(sha, &format!("{sha}^{{tree}}"), message)
+}
+
+/// Writes a copy of `sha` with `tree` and `message`, keeping its first parent and author.
+pub fn commit_with_tree(sha: &str, tree: &str, message: &str) -> Result<String> {
+    let author = git_ro(
+        [
+            "log",
+            "-n",
+            "1",
+            "--format=%an%x00%ae%x00%ad",
+            "--date=raw",
+            sha,
+        ]
+        .as_slice(),
+    )?;
+    let mut fields = author.trim_end_matches('\n').splitn(3, '\0');
+    let (Some(name), Some(email), Some(date)) = (fields.next(), fields.next(), fields.next())
+    else {
+        bail!("Could not read the author of commit {}", sha);
+    };
+    let output = std::process::Command::new("git")
+        .args(["commit-tree", tree, "-p", &format!("{sha}^"), "-m", message])
+        .env("GIT_AUTHOR_NAME", name)
+        .env("GIT_AUTHOR_EMAIL", email)
+        .env("GIT_AUTHOR_DATE", date)
+        .output()?;
+    if !output.status.success() {
+        bail!(
+            "git commit-tree failed for {}: {}",
+            sha,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Runs `f` with a private index file under the common Git directory, then removes the file.
+///
+/// The file is named `spr/<kind>-<pid>.index`, so concurrent commands never share an index, and
+/// neither the worktree nor the repository index is touched.
+pub fn with_temp_index<T>(kind: &str, f: impl FnOnce(&Path) -> Result<T>) -> Result<T> {
+    let dir = git_common_dir()?.join("spr");
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let index = dir.join(format!("{kind}-{}.index", std::process::id()));
+    let result = f(&index);
+    let _ = fs::remove_file(&index);
+    result
+}
+
+/// Runs `git` against `index` instead of the repository index, writing `input` to its stdin.
+pub fn git_in_index(index: &Path, args: &[&str], input: Option<&str>) -> Result<String> {
+    let mut child = Command::new("git")
+        .args(args)
+        .env("GIT_INDEX_FILE", index)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("failed to spawn git {}", args.join(" ")))?;
+    let mut stdin = child
+        .stdin
+        .take()
+        .with_context(|| format!("failed to open git {} stdin", args.join(" ")))?;
+    if let Some(input) = input {
+        stdin
+            .write_all(input.as_bytes())
+            .with_context(|| format!("failed to write git {} stdin", args.join(" ")))?;
+    }
+    drop(stdin);
+    let out = child
+        .wait_with_output()
+        .with_context(|| format!("failed to collect git {} output", args.join(" ")))?;
+    if !out.status.success() {
+        bail!(
+            "command failed: git {}\nstderr:\n{}",
+            args.join(" "),
+            String::from_utf8_lossy(&out.stderr)
+        );
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).trim().to_string())
+}
+
+/// Applies the first-parent diff of `commit` to `index`, streaming the patch as raw bytes.
+pub fn apply_commit_diff(index: &Path, commit: &str) -> Result<()> {
+    let parent = format!("{commit}^");
+    let mut diff = Command::new("git")
+        .args(["diff", "--binary", "--full-index", &parent, commit])
+        .stdout(Stdio::piped())
+        .spawn()
+        .context("failed to spawn git diff")?;
+    let patch = diff
+        .stdout
+        .take()
+        .context("failed to open git diff stdout")?;
+    let apply = Command::new("git")
+        .args(["apply", "--cached", "--allow-empty"])
+        .env("GIT_INDEX_FILE", index)
+        .stdin(Stdio::from(patch))
+        .output()
+        .context("failed to spawn git apply")?;
+    let diff_status = diff.wait().context("failed to wait for git diff")?;
+    if !diff_status.success() {
+        bail!("git diff failed for {}", commit);
+    }
+    if !apply.status.success() {
+        bail!(
+            "{}",
+            String::from_utf8_lossy(&apply.stderr).trim().to_string()
+        );
+    }
+    Ok(())
+}
+
 /// Creates a temporary worktree/branch off `merge_base` for stack rewrites.
 ///
 /// The temp branch and path names are derived from `(kind, short)` and are
@@ -239,6 +364,49 @@ pub fn checked_out_worktree_for_branch(branch: &str) -> Result<Option<String>> {
         .map(|entry| entry.path))
 }
 
+/// Local branch named by `--from` when it is not the checked-out branch.
+///
+/// Rewrites of such a stack move the branch ref instead of resetting a worktree, so the branch
+/// must not be checked out anywhere else either. Returns `None` for the checked-out stack.
+///
+/// # Errors
+///
+/// Returns errors when `--from` names something other than a local branch, or a branch that is
+/// checked out in another worktree.
+pub fn unchecked_out_stack_branch() -> Result<Option<String>> {
+    let from = crate::parsing::stack_from();
+    if from == "HEAD" {
+        return Ok(None);
+    }
+    let branch = from
+        .strip_prefix("refs/heads/")
+        .unwrap_or(&from)
+        .to_string();
+    if git_ro(
+        [
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("refs/heads/{branch}"),
+        ]
+        .as_slice(),
+    )
+    .is_err()
+    {
+        bail!("--from {from} must name a local branch to rewrite the stack on it");
+    }
+    let current = git_ro(["symbolic-ref", "--quiet", "--short", "HEAD"].as_slice())
+        .map(|out| out.trim().to_string())
+        .unwrap_or_default();
+    if current == branch {
+        return Ok(None);
+    }
+    if let Some(worktree) = checked_out_worktree_for_branch(&branch)? {
+        bail!("--from {branch} is checked out in worktree {worktree}; run spr from there instead");
+    }
+    Ok(Some(branch))
+}
+
 /// Returns true when a local branch with the given name exists.
 fn branch_exists(branch: &str) -> Result<bool> {
     let out = git_ro(["branch", "--list", branch].as_slice())?;
@@ -395,6 +563,25 @@ impl DeferredDirtyWorktreeRestore {
         }
     }
 
+    /// Put the stashed changes back after a suspended rewrite was discarded with `spr abort`.
+    pub fn restore_after_abort(
+        self,
+        execution_mode: ExecutionMode,
+        command_name: &str,
+        worktree_root: &str,
+    ) -> Result<()> {
+        match self {
+            Self::Noop => Ok(()),
+            Self::Stash { stash_commit } => restore_stash_in_worktree(
+                execution_mode,
+                command_name,
+                worktree_root,
+                &stash_commit,
+                RewriteOutcome::Failed,
+            ),
+        }
+    }
+
     pub fn discard_instruction_lines(&self, worktree_root: &str) -> Vec<String> {
         match self {
             Self::Noop => Vec::new(),
@@ -761,8 +948,8 @@ mod tests {
     use super::{
         build_head_base_chain, can_fallback_after_failed_native_rebase, cherry_pick_args,
         cleanup_temp_worktree, create_backup_tag, create_temp_worktree,
-        get_current_branch_and_short, run_native_rebase_with_abort, CherryPickEmptyPolicy,
-        NativeRebaseOutcome, NativeRebaseSnapshot,
+        get_current_branch_and_short, git_in_index, run_native_rebase_with_abort, with_temp_index,
+        CherryPickEmptyPolicy, NativeRebaseOutcome, NativeRebaseSnapshot,
     };
     use crate::execution::ExecutionMode;
     use crate::group_markers::GroupMarker;
@@ -840,6 +1027,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn temp_index_leaves_the_repository_index_alone_and_is_removed() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path().to_path_buf();
+        let _guard = DirGuard::change_to(&repo);
+        let mut used_index = None;
+
+        let tree = with_temp_index("test", |index| {
+            used_index = Some(index.to_path_buf());
+            git_in_index(index, ["read-tree", "HEAD"].as_slice(), None)?;
+            git_in_index(
+                index,
+                ["update-index", "--force-remove", "file.txt"].as_slice(),
+                None,
+            )?;
+            git_in_index(index, ["write-tree"].as_slice(), None)
+        })
+        .unwrap();
+
+        assert_eq!(tree, "4b825dc642cb6eb9a060e54bf8d69288fbee4904");
+        assert!(!used_index.unwrap().exists());
+        assert_eq!(
+            git(&repo, ["diff", "--cached", "--name-only"].as_slice()),
+            ""
+        );
+    }
+
     #[test]
     fn failed_native_rebase_fallback_requires_unchanged_repository_snapshot() {
         let before = native_rebase_snapshot("head", "");
@@ -924,7 +1139,7 @@ mod tests {
         assert_eq!(backup, backup_again, "backup name should be stable");
 
         let head = git(&repo, ["rev-parse", "HEAD"].as_slice());
-        let backup_ref = format!("refs/tags/{}", backup);
+        let backup_ref = format!("refs/tags/{}^{{commit}}", backup);
         let backup_head = git(&repo, ["rev-parse", backup_ref.as_str()].as_slice());
         assert_eq!(head.trim(), backup_head.trim(), "backup should match HEAD");
         let branch_out = git(&repo, ["branch", "--list", backup.as_str()].as_slice());
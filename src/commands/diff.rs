@@ -0,0 +1,118 @@
+//! Show what one PR group changes with `spr diff <target>`.
+//!
+//! The diff runs from the group's first parent to its last commit, the same boundaries
+//! `spr files` uses, so it matches what the group's PR shows on GitHub without looking up SHAs.
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+use tracing::info;
+
+use crate::git::git_ro;
+use crate::parsing::derive_local_groups;
+use crate::selectors::{resolve_group_ordinal, GroupSelector};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiffData {
+    pub local_pr_number: usize,
+    pub stable_handle: String,
+    /// Diffed range, `<parent>..<tip>`.
+    pub range: String,
+    pub stat: bool,
+    /// `git diff` output; a diffstat with `--stat`.
+    pub output: String,
+}
+
+fn diff_args(range: &str, stat: bool) -> Vec<&str> {
+    let mut args = vec!["diff"];
+    if stat {
+        args.push("--stat");
+    }
+    args.push(range);
+    args
+}
+
+/// Collect the diff of `target` from its parent to its tip.
+///
+/// # Errors
+///
+/// Returns errors when `target` does not resolve or Git cannot diff the group.
+pub fn collect_diff_data(
+    base: &str,
+    ignore_tag: &str,
+    target: &GroupSelector,
+    stat: bool,
+) -> Result<DiffData> {
+    let (_merge_base, groups) = derive_local_groups(base, ignore_tag)?;
+    if groups.is_empty() {
+        bail!("No local PR groups found; nothing to diff.");
+    }
+    let local_pr_number = resolve_group_ordinal(&groups, target)?;
+    let group = &groups[local_pr_number - 1];
+    let (Some(first), Some(last)) = (group.commits.first(), group.commits.last()) else {
+        bail!("{} has no commits", group.selector_text());
+    };
+    let range = format!("{first}^..{last}");
+    let output = git_ro(&diff_args(&range, stat))?;
+    Ok(DiffData {
+        local_pr_number,
+        stable_handle: group.selector_text(),
+        range,
+        stat,
+        output,
+    })
+}
+
+/// Print the diff of `target` from its parent to its tip.
+pub fn diff_display(
+    base: &str,
+    ignore_tag: &str,
+    target: &GroupSelector,
+    stat: bool,
+) -> Result<()> {
+    let data = collect_diff_data(base, ignore_tag, target, stat)?;
+    info!(
+        "LPR #{} / {}: {}",
+        data.local_pr_number, data.stable_handle, data.range
+    );
+    for line in data.output.lines() {
+        info!("{line}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::collect_diff_data;
+    use crate::selectors::GroupSelector;
+    use crate::test_support::{commit_file, git, init_repo, lock_cwd, DirGuard};
+
+    #[test]
+    fn collect_diff_data_covers_only_the_selected_group() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path().to_path_buf();
+        git(&repo, ["checkout", "-b", "stack"].as_slice());
+        commit_file(&repo, "alpha.txt", "a\n", "feat: alpha pr:alpha");
+        commit_file(&repo, "beta.txt", "b\n", "feat: beta pr:beta");
+        commit_file(&repo, "beta.txt", "b2\n", "feat: beta again");
+        commit_file(&repo, "gamma.txt", "g\n", "feat: gamma pr:gamma");
+        let _guard = DirGuard::change_to(&repo);
+
+        let data = collect_diff_data("main", "ignore", &GroupSelector::LocalPr(2), false).unwrap();
+
+        assert_eq!(data.stable_handle, "pr:beta");
+        assert!(data.output.contains("+++ b/beta.txt"), "{}", data.output);
+        assert!(data.output.contains("+b2"), "{}", data.output);
+        assert!(!data.output.contains("alpha.txt"), "{}", data.output);
+        assert!(!data.output.contains("gamma.txt"), "{}", data.output);
+
+        let stat = collect_diff_data(
+            "main",
+            "ignore",
+            &GroupSelector::Bare("beta".to_string()),
+            true,
+        )
+        .unwrap();
+        assert!(stat.output.contains("beta.txt | 1 +"), "{}", stat.output);
+    }
+}
@@ -15,7 +15,7 @@ use crate::commands::restack_after_count;
 use crate::commands::rewrite_resume::RewriteCommandOutcome;
 use crate::config::{DirtyWorktreePolicy, RestackConflictPolicy};
 use crate::execution::ExecutionMode;
-use crate::git::{git_is_ancestor, git_rw};
+use crate::git::{fetch_remote, git_is_ancestor};
 use crate::github::{
     fetch_merged_pr_merge_commit_oids, list_open_or_merged_prs_for_heads, PrInfoWithState, PrState,
 };
@@ -258,7 +258,7 @@ pub fn drop_merged_prefix(
     restack_conflict_policy: RestackConflictPolicy,
     dirty_worktree_policy: DirtyWorktreePolicy,
 ) -> Result<RewriteCommandOutcome> {
-    git_rw(execution_mode, ["fetch", "origin"].as_slice())?;
+    fetch_remote(execution_mode)?;
 
     let (_merge_base, leading_ignored, groups) =
         derive_local_groups_with_ignored(&metadata_context.base, &metadata_context.ignore_tag)?;
@@ -657,7 +657,7 @@ mod tests {
                 &repo,
                 [
                     "rev-parse",
-                    &format!("backup/drop-merged-prefix/stack-{original_short}")
+                    &format!("backup/drop-merged-prefix/stack-{original_short}^{{commit}}")
                 ]
                 .as_slice()
             )
@@ -0,0 +1,270 @@
+//! List the files each local PR group changes.
+//!
+//! `spr files` is read-only: it diffs each group's first parent against its last commit, so the
+//! listing matches what the group's PR would show on GitHub without consulting GitHub. Files that
+//! `.gitattributes` marks `linguist-generated`, `binary`, or `-diff` are labeled, and line counts
+//! go through any configured `textconv` driver.
+
+use anyhow::Result;
+use serde::Serialize;
+use tracing::info;
+
+use crate::git::git_ro;
+use crate::parsing::{derive_local_groups, Group};
+use crate::path_attributes::path_attributes;
+use crate::selectors::{resolve_group_ordinal, GroupSelector};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileChangeData {
+    /// Git name-status letter: `A`, `M`, `D`, or `T`.
+    pub status: String,
+    pub path: String,
+    /// Added/deleted line counts; present only with `--stat`, and omitted for binary files.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub insertions: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deletions: Option<usize>,
+    /// Marked `linguist-generated` in `.gitattributes`.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub generated: bool,
+    /// Marked `binary` or `-diff` in `.gitattributes`; such files get no line counts.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub binary: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GroupFilesData {
+    pub local_pr_number: usize,
+    pub stable_handle: String,
+    pub files: Vec<FileChangeData>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FilesData {
+    pub groups: Vec<GroupFilesData>,
+}
+
+fn group_diff_args<'a>(first_parent: &'a str, last: &'a str, mode: &'a str) -> Vec<&'a str> {
+    vec!["diff", mode, "--no-renames", first_parent, last]
+}
+
+fn parse_name_status(output: &str) -> Vec<FileChangeData> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (status, path) = line.split_once('\t')?;
+            Some(FileChangeData {
+                status: status.trim().to_string(),
+                path: path.to_string(),
+                insertions: None,
+                deletions: None,
+                generated: false,
+                binary: false,
+            })
+        })
+        .collect()
+}
+
+fn apply_numstat(files: &mut [FileChangeData], output: &str) {
+    for line in output.lines() {
+        let mut fields = line.splitn(3, '\t');
+        let (Some(added), Some(deleted), Some(path)) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        if let Some(file) = files
+            .iter_mut()
+            .find(|file| file.path == path && !file.binary)
+        {
+            file.insertions = added.parse().ok();
+            file.deletions = deleted.parse().ok();
+        }
+    }
+}
+
+fn collect_group_files(group: &Group, stat: bool) -> Result<Vec<FileChangeData>> {
+    let (Some(first), Some(last)) = (group.commits.first(), group.commits.last()) else {
+        return Ok(Vec::new());
+    };
+    let first_parent = format!("{first}^");
+    let mut files = parse_name_status(&git_ro(&group_diff_args(
+        &first_parent,
+        last,
+        "--name-status",
+    ))?);
+    let paths: Vec<String> = files.iter().map(|file| file.path.clone()).collect();
+    let attributes = path_attributes(&first_parent, last, &paths)?;
+    for file in &mut files {
+        if let Some(attributes) = attributes.get(&file.path) {
+            file.generated = attributes.generated;
+            file.binary = attributes.binary;
+        }
+    }
+    if stat {
+        let mut args = group_diff_args(&first_parent, last, "--numstat");
+        args.insert(1, "--textconv");
+        apply_numstat(&mut files, &git_ro(&args)?);
+    }
+    Ok(files)
+}
+
+/// Collect changed files for one group (`target`) or for every local group.
+///
+/// # Errors
+///
+/// Returns errors when `target` does not resolve or Git cannot diff a group.
+pub fn collect_files_data(
+    base: &str,
+    ignore_tag: &str,
+    target: Option<&GroupSelector>,
+    stat: bool,
+) -> Result<FilesData> {
+    let (_merge_base, groups) = derive_local_groups(base, ignore_tag)?;
+    let ordinals = match target {
+        Some(target) => vec![resolve_group_ordinal(&groups, target)?],
+        None => (1..=groups.len()).collect(),
+    };
+    let mut data = FilesData { groups: Vec::new() };
+    for ordinal in ordinals {
+        let group = &groups[ordinal - 1];
+        data.groups.push(GroupFilesData {
+            local_pr_number: ordinal,
+            stable_handle: crate::commands::common::group_selector_text(group),
+            files: collect_group_files(group, stat)?,
+        });
+    }
+    Ok(data)
+}
+
+fn render_files(data: &FilesData) -> Vec<String> {
+    if data.groups.is_empty() {
+        return vec!["No groups discovered; nothing to list.".to_string()];
+    }
+    let mut lines = Vec::new();
+    for group in &data.groups {
+        lines.push(format!(
+            "LPR #{} {} ({} file(s))",
+            group.local_pr_number,
+            group.stable_handle,
+            group.files.len()
+        ));
+        for file in &group.files {
+            let stat = match (file.insertions, file.deletions) {
+                (Some(added), Some(deleted)) => format!("  +{added} -{deleted}"),
+                _ => String::new(),
+            };
+            let label = match (file.generated, file.binary) {
+                (true, true) => " (generated, binary)",
+                (true, false) => " (generated)",
+                (false, true) => " (binary)",
+                (false, false) => "",
+            };
+            lines.push(format!("  {}  {}{}{}", file.status, file.path, stat, label));
+        }
+    }
+    lines
+}
+
+/// Print the files changed by one group or by every group (bottom→top).
+pub fn list_files_display(
+    base: &str,
+    ignore_tag: &str,
+    target: Option<&GroupSelector>,
+    stat: bool,
+) -> Result<()> {
+    let data = collect_files_data(base, ignore_tag, target, stat)?;
+    for line in render_files(&data) {
+        info!("{line}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{collect_files_data, render_files, FileChangeData};
+    use crate::selectors::{ExplicitGroupSelector, GroupSelector};
+    use crate::test_support::{commit_file, git, init_repo, lock_cwd, DirGuard};
+
+    #[test]
+    fn files_lists_each_groups_changes_with_stat() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path().to_path_buf();
+        git(&repo, ["checkout", "-b", "stack"].as_slice());
+        commit_file(&repo, "alpha.txt", "a\nb\n", "feat: alpha pr:alpha");
+        commit_file(&repo, "shared.txt", "alpha\n", "feat: alpha follow-up");
+        commit_file(&repo, "beta.txt", "beta\n", "feat: beta pr:beta");
+        let _guard = DirGuard::change_to(&repo);
+
+        let all = collect_files_data("main", "ignore", None, false).unwrap();
+        assert_eq!(all.groups.len(), 2);
+        assert_eq!(
+            all.groups[0]
+                .files
+                .iter()
+                .map(|file| file.path.as_str())
+                .collect::<Vec<_>>(),
+            vec!["alpha.txt", "shared.txt"]
+        );
+        assert_eq!(all.groups[1].files[0].path, "beta.txt");
+
+        let beta = collect_files_data(
+            "main",
+            "ignore",
+            Some(&GroupSelector::Explicit(ExplicitGroupSelector::PrLabel(
+                "beta".to_string(),
+            ))),
+            true,
+        )
+        .unwrap();
+        assert_eq!(beta.groups.len(), 1);
+        assert_eq!(beta.groups[0].local_pr_number, 2);
+        assert_eq!(
+            beta.groups[0].files,
+            vec![FileChangeData {
+                status: "A".to_string(),
+                path: "beta.txt".to_string(),
+                insertions: Some(1),
+                deletions: Some(0),
+                generated: false,
+                binary: false,
+            }]
+        );
+        assert_eq!(
+            render_files(&beta),
+            vec![
+                "LPR #2 pr:beta (1 file(s))".to_string(),
+                "  A  beta.txt  +1 -0".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn files_labels_generated_and_binary_paths_from_gitattributes() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path().to_path_buf();
+        commit_file(
+            &repo,
+            ".gitattributes",
+            "*.gen.rs linguist-generated\n*.dat -diff\n",
+            "chore: attributes",
+        );
+        git(&repo, ["checkout", "-b", "stack"].as_slice());
+        commit_file(&repo, "api.gen.rs", "a\nb\n", "feat: alpha pr:alpha");
+        commit_file(&repo, "blob.dat", "x\n", "feat: alpha data");
+        let _guard = DirGuard::change_to(&repo);
+
+        let data = collect_files_data("main", "ignore", None, true).unwrap();
+
+        assert_eq!(
+            render_files(&data),
+            vec![
+                "LPR #1 pr:alpha (2 file(s))".to_string(),
+                "  A  api.gen.rs  +2 -0 (generated)".to_string(),
+                "  A  blob.dat (binary)".to_string(),
+            ]
+        );
+    }
+}
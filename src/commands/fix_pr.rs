@@ -24,6 +24,31 @@ fn resolve_fix_pr_target(
     resolve_group_ordinal(groups, target)
 }
 
+fn group_below(groups: &[crate::parsing::Group], before: &GroupSelector) -> Result<usize> {
+    let ordinal = resolve_group_ordinal(groups, before)?;
+    if ordinal == 1 {
+        bail!(
+            "--before {} names the bottom PR; there is no group below it to receive the commits",
+            before
+        );
+    }
+    Ok(ordinal - 1)
+}
+
+/// Resolve `spr fix-pr --before <C>` to the group directly below `C`.
+///
+/// # Errors
+///
+/// Returns errors when `before` does not resolve or names the bottom PR.
+pub fn resolve_fix_pr_before(
+    metadata_context: &crate::stack_metadata::RefreshMetadataContext,
+    before: &GroupSelector,
+) -> Result<GroupSelector> {
+    let (_merge_base, groups) =
+        crate::parsing::derive_local_groups(&metadata_context.base, &metadata_context.ignore_tag)?;
+    group_below(&groups, before).map(GroupSelector::LocalPr)
+}
+
 fn build_fix_pr_operations(
     all_commits: &[String],
     top_commits: &[String],
@@ -203,7 +228,7 @@ pub fn fix_pr_tail(
 
 #[cfg(test)]
 mod tests {
-    use super::{fix_pr_tail, resolve_fix_pr_target};
+    use super::{fix_pr_tail, group_below, resolve_fix_pr_target};
     use crate::commands::rewrite_resume::{resume_rewrite, RewriteResumeState};
     use crate::commands::RewriteCommandOutcome;
     use crate::config::DirtyWorktreePolicy;
@@ -244,6 +269,17 @@ mod tests {
         assert_eq!(resolve_fix_pr_target(&groups, &target).unwrap(), 2);
     }
 
+    #[test]
+    fn fix_pr_before_targets_group_below() {
+        let groups = groups(&["alpha", "beta", "gamma"]);
+        let label =
+            |tag: &str| GroupSelector::Explicit(ExplicitGroupSelector::PrLabel(tag.to_string()));
+
+        assert_eq!(group_below(&groups, &label("gamma")).unwrap(), 2);
+        let err = group_below(&groups, &label("alpha")).unwrap_err();
+        assert!(err.to_string().contains("bottom PR"), "{err}");
+    }
+
     fn git(repo: &Path, args: &[&str]) -> String {
         let out = Command::new("git")
             .current_dir(repo)
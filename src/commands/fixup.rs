@@ -0,0 +1,218 @@
+//! Commit staged changes as a follow-up fix for one PR group.
+//!
+//! `spr fixup` is `git commit` followed by `spr fix-pr <target> --tail 1`: the new commit is
+//! created on top of the stack and then relocated to the selected group's tail with the same
+//! rewrite (and resume) machinery as `spr fix-pr`.
+
+use anyhow::{bail, Context, Result};
+use tracing::info;
+
+use crate::commands::fix_pr_tail;
+use crate::commands::RewriteCommandOutcome;
+use crate::config::DirtyWorktreePolicy;
+use crate::execution::ExecutionMode;
+use crate::git::{git_ro, git_rw};
+use crate::parsing::derive_local_groups;
+use crate::selectors::{resolve_group_ordinal, GroupSelector};
+
+/// Result of `spr fixup`, including the resolved target so callers can scope a follow-up update.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixupOutcome {
+    /// 1-based local PR number of the group that received the fix.
+    pub local_pr_number: usize,
+    pub rewrite: RewriteCommandOutcome,
+}
+
+fn default_fixup_message(title: &str) -> String {
+    format!("fixup! {title}")
+}
+
+/// Commit the index as a fixup of `target` and move that commit to the group's tail.
+///
+/// The commit message defaults to `fixup! <PR title>`. Messages containing group markers are
+/// rejected before committing, because a marker would start a new group instead of joining
+/// `target`.
+///
+/// # Errors
+///
+/// Returns errors when there are no local groups, the target does not resolve, nothing is
+/// staged, the message carries a group marker, or the commit/relocation fails. If relocation
+/// fails after the commit was created, the error says how to finish with `spr fix-pr`.
+pub fn fixup_staged(
+    metadata_context: &crate::stack_metadata::RefreshMetadataContext,
+    target: &GroupSelector,
+    message: Option<&str>,
+    safe: bool,
+    execution_mode: ExecutionMode,
+    dirty_worktree_policy: DirtyWorktreePolicy,
+) -> Result<FixupOutcome> {
+    let (_merge_base, groups) =
+        derive_local_groups(&metadata_context.base, &metadata_context.ignore_tag)?;
+    if groups.is_empty() {
+        bail!("No local PR groups found; nothing to fix up.");
+    }
+    let local_pr_number = resolve_group_ordinal(&groups, target)?;
+    let group = &groups[local_pr_number - 1];
+
+    let staged = git_ro(["diff", "--cached", "--name-only"].as_slice())?;
+    let staged_count = staged
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .count();
+    if staged_count == 0 {
+        bail!("No staged changes; stage the fix with `git add` before running `spr fixup`.");
+    }
+
+    let message = match message {
+        Some(message) => message.to_string(),
+        None => default_fixup_message(&group.pr_title()?),
+    };
+    if !crate::group_markers::candidate_group_markers(&message).is_empty() {
+        bail!(
+            "Fixup message must not contain group markers; it would start a new PR group instead of joining {}",
+            group.selector_text()
+        );
+    }
+
+    git_rw(execution_mode, ["commit", "-m", &message].as_slice())?;
+    if execution_mode == ExecutionMode::DryRun {
+        info!(
+            "DRY-RUN: would move the new commit ({} staged file(s)) to the tail of {} (LPR #{}).",
+            staged_count,
+            group.selector_text(),
+            local_pr_number
+        );
+        return Ok(FixupOutcome {
+            local_pr_number,
+            rewrite: RewriteCommandOutcome::Completed,
+        });
+    }
+
+    let already_at_tail = local_pr_number == groups.len() && group.ignored_after.is_empty();
+    if already_at_tail {
+        info!(
+            "Committed fixup on top of {}; it is already the group's tail.",
+            group.selector_text()
+        );
+        return Ok(FixupOutcome {
+            local_pr_number,
+            rewrite: RewriteCommandOutcome::Completed,
+        });
+    }
+
+    let rewrite = fix_pr_tail(
+        metadata_context,
+        &GroupSelector::LocalPr(local_pr_number),
+        1,
+        safe,
+        execution_mode,
+        dirty_worktree_policy,
+    )
+    .with_context(|| {
+        format!(
+            "The fixup commit was created at HEAD but not moved; run `spr fix-pr {local_pr_number}` to finish."
+        )
+    })?;
+    Ok(FixupOutcome {
+        local_pr_number,
+        rewrite,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{default_fixup_message, fixup_staged};
+    use crate::commands::RewriteCommandOutcome;
+    use crate::config::DirtyWorktreePolicy;
+    use crate::execution::ExecutionMode;
+    use crate::selectors::{ExplicitGroupSelector, GroupSelector};
+    use crate::test_support::{commit_file, git, init_repo, lock_cwd, log_subjects, DirGuard};
+    use std::fs;
+
+    fn metadata_context() -> crate::stack_metadata::RefreshMetadataContext {
+        crate::stack_metadata::RefreshMetadataContext {
+            base: "main".to_string(),
+            prefix: "dank-spr/".to_string(),
+            ignore_tag: "ignore".to_string(),
+        }
+    }
+
+    fn init_fixup_repo() -> tempfile::TempDir {
+        let dir = init_repo();
+        let repo = dir.path();
+        git(repo, ["checkout", "-b", "stack"].as_slice());
+        commit_file(repo, "alpha.txt", "alpha 1\n", "feat: alpha pr:alpha");
+        commit_file(repo, "beta.txt", "beta 1\n", "feat: beta pr:beta");
+        dir
+    }
+
+    #[test]
+    fn default_message_marks_the_pr_title() {
+        assert_eq!(default_fixup_message("feat: alpha"), "fixup! feat: alpha");
+    }
+
+    #[test]
+    fn fixup_commits_staged_changes_into_target_group_tail() {
+        let _lock = lock_cwd();
+        let dir = init_fixup_repo();
+        let repo = dir.path().to_path_buf();
+        let _guard = DirGuard::change_to(&repo);
+        fs::write(repo.join("alpha.txt"), "alpha 1\nalpha fix\n").unwrap();
+        git(&repo, ["add", "alpha.txt"].as_slice());
+
+        let outcome = fixup_staged(
+            &metadata_context(),
+            &GroupSelector::Explicit(ExplicitGroupSelector::PrLabel("alpha".to_string())),
+            None,
+            false,
+            ExecutionMode::Apply,
+            DirtyWorktreePolicy::Halt,
+        )
+        .unwrap();
+
+        assert_eq!(outcome.local_pr_number, 1);
+        assert_eq!(outcome.rewrite, RewriteCommandOutcome::Completed);
+        assert_eq!(
+            log_subjects(&repo, 3),
+            vec![
+                "feat: beta pr:beta".to_string(),
+                "fixup! feat: alpha".to_string(),
+                "feat: alpha pr:alpha".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn fixup_rejects_empty_index_and_marker_messages() {
+        let _lock = lock_cwd();
+        let dir = init_fixup_repo();
+        let repo = dir.path().to_path_buf();
+        let _guard = DirGuard::change_to(&repo);
+        let head = git(&repo, ["rev-parse", "HEAD"].as_slice());
+
+        let err = fixup_staged(
+            &metadata_context(),
+            &GroupSelector::LocalPr(1),
+            None,
+            false,
+            ExecutionMode::Apply,
+            DirtyWorktreePolicy::Halt,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("No staged changes"), "{err}");
+
+        fs::write(repo.join("alpha.txt"), "alpha 1\nalpha fix\n").unwrap();
+        git(&repo, ["add", "alpha.txt"].as_slice());
+        let err = fixup_staged(
+            &metadata_context(),
+            &GroupSelector::LocalPr(1),
+            Some("fix pr:gamma"),
+            false,
+            ExecutionMode::Apply,
+            DirtyWorktreePolicy::Halt,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("group markers"), "{err}");
+        assert_eq!(git(&repo, ["rev-parse", "HEAD"].as_slice()), head);
+    }
+}
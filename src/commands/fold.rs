@@ -0,0 +1,224 @@
+//! Fold one PR group into the group below it.
+//!
+//! `spr fold <target>` merges the target group and the group above it into one PR by removing the
+//! upper group's `pr:<tag>`/`branch:<name>` marker from its first commit. Everything above it is
+//! replayed through the usual rewrite session, like `spr split`, of which this is the inverse.
+//! With `--close`, the upper group's open PR is closed with a comment pointing at the PR it was
+//! folded into.
+
+use anyhow::{bail, Result};
+use tracing::info;
+
+use crate::branch_names::group_branch_identities;
+use crate::commands::reword::replay_with_new_message;
+use crate::commands::rewrite_resume::{RewriteCommandKind, RewriteCommandOutcome};
+use crate::config::DirtyWorktreePolicy;
+use crate::execution::ExecutionMode;
+use crate::git::{gh_rw, git_ro};
+use crate::github::list_open_prs_for_heads;
+use crate::group_markers::strip_valid_group_markers;
+use crate::parsing::derive_local_groups;
+use crate::selectors::{resolve_group_ordinal, GroupSelector};
+
+/// Result of `spr fold`, including the resolved target so callers can scope a follow-up update.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FoldOutcome {
+    /// 1-based local PR number of the group that absorbed the one above it.
+    pub local_pr_number: usize,
+    pub rewrite: RewriteCommandOutcome,
+}
+
+/// `message` without its group markers, keeping the rest of each line.
+fn unmarked_message(message: &str) -> String {
+    let stripped = strip_valid_group_markers(message);
+    let lines: Vec<&str> = stripped.lines().map(str::trim_end).collect();
+    lines.join("\n").trim_end().to_string()
+}
+
+fn close_folded_pr(
+    folded: u64,
+    into: Option<u64>,
+    into_selector: &str,
+    execution_mode: ExecutionMode,
+) -> Result<()> {
+    let comment = match into {
+        Some(number) => format!("Folded into #{number} by `spr fold`."),
+        None => format!("Folded into {into_selector} by `spr fold`."),
+    };
+    info!("Closing PR #{} (folded into {})", folded, into_selector);
+    gh_rw(
+        execution_mode,
+        ["pr", "close", &format!("#{folded}"), "--comment", &comment].as_slice(),
+    )?;
+    Ok(())
+}
+
+/// Fold the group above `target` into `target`, optionally closing the upper group's open PR.
+///
+/// # Errors
+///
+/// Returns errors when the target does not resolve, is the top group, the upper group starts a
+/// `pr:base:` segment, or the rewrite or PR close fails.
+pub fn fold_groups(
+    metadata_context: &crate::stack_metadata::RefreshMetadataContext,
+    target: &GroupSelector,
+    close: bool,
+    safe: bool,
+    execution_mode: ExecutionMode,
+    dirty_worktree_policy: DirtyWorktreePolicy,
+) -> Result<FoldOutcome> {
+    let (_merge_base, groups) =
+        derive_local_groups(&metadata_context.base, &metadata_context.ignore_tag)?;
+    if groups.is_empty() {
+        bail!("No local PR groups found; nothing to fold.");
+    }
+    let local_pr_number = resolve_group_ordinal(&groups, target)?;
+    let Some(upper) = groups.get(local_pr_number) else {
+        bail!(
+            "{} is the top group; there is no group above it to fold in",
+            groups[local_pr_number - 1].selector_text()
+        );
+    };
+    let lower = &groups[local_pr_number - 1];
+    if crate::stack_segments::group_segment_base(upper).is_some() {
+        bail!(
+            "{} starts a `pr:base:` segment; folding it would change its base",
+            upper.selector_text()
+        );
+    }
+    let Some(first) = upper.commits.first() else {
+        bail!("{} has no commits", upper.selector_text());
+    };
+
+    let open_prs = if close {
+        let heads: Vec<String> = group_branch_identities(
+            &groups[local_pr_number - 1..=local_pr_number],
+            &metadata_context.prefix,
+        )?
+        .into_iter()
+        .map(|identity| identity.exact)
+        .collect();
+        let prs = list_open_prs_for_heads(&heads)?;
+        let number_for = |head: &str| {
+            prs.iter()
+                .find(|pr| {
+                    crate::branch_names::canonical_branch_conflict_key(&pr.head)
+                        == crate::branch_names::canonical_branch_conflict_key(head)
+                })
+                .map(|pr| pr.number)
+        };
+        Some((number_for(&heads[0]), number_for(&heads[1])))
+    } else {
+        None
+    };
+
+    let message = git_ro(["log", "-n", "1", "--format=%B", first].as_slice())?;
+    info!(
+        "Folding {} ({} commit(s)) into {}",
+        upper.selector_text(),
+        upper.commits.len(),
+        lower.selector_text()
+    );
+    let rewrite = replay_with_new_message(
+        metadata_context,
+        first,
+        &unmarked_message(&message),
+        RewriteCommandKind::Fold,
+        &format!("Folding into {}", lower.selector_text()),
+        safe,
+        execution_mode,
+        dirty_worktree_policy,
+    )?;
+
+    if rewrite == RewriteCommandOutcome::Completed {
+        match open_prs {
+            Some((into, Some(folded))) => {
+                close_folded_pr(folded, into, &lower.selector_text(), execution_mode)?
+            }
+            Some((_, None)) => info!("{} has no open PR; nothing to close", upper.selector_text()),
+            None => {}
+        }
+    }
+    Ok(FoldOutcome {
+        local_pr_number,
+        rewrite,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fold_groups, unmarked_message};
+    use crate::commands::RewriteCommandOutcome;
+    use crate::config::DirtyWorktreePolicy;
+    use crate::execution::ExecutionMode;
+    use crate::selectors::GroupSelector;
+    use crate::test_support::{commit_file, git, init_repo, lock_cwd, log_subjects, DirGuard};
+
+    fn metadata_context() -> crate::stack_metadata::RefreshMetadataContext {
+        crate::stack_metadata::RefreshMetadataContext {
+            base: "main".to_string(),
+            prefix: "dank-spr/".to_string(),
+            ignore_tag: "ignore".to_string(),
+        }
+    }
+
+    #[test]
+    fn unmarked_message_drops_markers_and_trailing_marker_lines() {
+        assert_eq!(
+            unmarked_message("feat: beta pr:beta\n\nBody text\n\npr:beta\n"),
+            "feat: beta\n\nBody text"
+        );
+        assert_eq!(
+            unmarked_message("feat: login branch:feature/login"),
+            "feat: login"
+        );
+    }
+
+    #[test]
+    fn fold_merges_the_group_above_into_the_target() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path().to_path_buf();
+        git(&repo, ["checkout", "-b", "stack"].as_slice());
+        commit_file(&repo, "alpha.txt", "a\n", "feat: alpha pr:alpha");
+        commit_file(&repo, "beta.txt", "b\n", "feat: beta pr:beta");
+        commit_file(&repo, "gamma.txt", "g\n", "feat: gamma pr:gamma");
+        let _guard = DirGuard::change_to(&repo);
+
+        let outcome = fold_groups(
+            &metadata_context(),
+            &GroupSelector::LocalPr(1),
+            false,
+            false,
+            ExecutionMode::Apply,
+            DirtyWorktreePolicy::Halt,
+        )
+        .unwrap();
+
+        assert_eq!(outcome.local_pr_number, 1);
+        assert_eq!(outcome.rewrite, RewriteCommandOutcome::Completed);
+        assert_eq!(
+            log_subjects(&repo, 3),
+            vec![
+                "feat: gamma pr:gamma".to_string(),
+                "feat: beta".to_string(),
+                "feat: alpha pr:alpha".to_string(),
+            ]
+        );
+        let (_merge_base, groups) = crate::parsing::derive_local_groups("main", "ignore").unwrap();
+        let selectors: Vec<String> = groups.iter().map(|group| group.selector_text()).collect();
+        assert_eq!(selectors, vec!["pr:alpha", "pr:gamma"]);
+        assert_eq!(groups[0].commits.len(), 2);
+
+        let err = fold_groups(
+            &metadata_context(),
+            &GroupSelector::LocalPr(2),
+            false,
+            false,
+            ExecutionMode::Apply,
+            DirtyWorktreePolicy::Halt,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("is the top group"), "{err}");
+    }
+}
@@ -0,0 +1,220 @@
+//! Lock a stack against publishing and landing with `spr freeze`, and unlock it with `spr thaw`.
+//!
+//! A freeze is recorded in the shared stack state (`refs/spr/state/<prefix>` on the remote)
+//! together with the remote SHA of every group branch, so it holds for every collaborator and
+//! machine, not only the checkout that froze it. While it is in place, `spr update` (including
+//! the publish after `--update` rewrites) and `spr land` refuse unless `--thaw` is passed, which
+//! lifts the freeze first. `spr thaw` reports group branches that moved on the remote anyway.
+
+use anyhow::{bail, Result};
+use std::collections::BTreeMap;
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+use tracing::{info, warn};
+
+use crate::branch_names::group_branch_identities;
+use crate::execution::ExecutionMode;
+use crate::git::{get_remote_branches_sha, git_current_branch};
+use crate::parsing::derive_local_groups;
+use crate::remote_state::{frozen_record, publish_freeze, FreezeRecord};
+
+/// Record a freeze of the stack's remote branches under `prefix`.
+///
+/// # Errors
+///
+/// Returns errors from group derivation, `ls-remote`, or pushing the state ref.
+pub fn freeze_stack(
+    base: &str,
+    prefix: &str,
+    ignore_tag: &str,
+    reason: Option<&str>,
+    execution_mode: ExecutionMode,
+) -> Result<()> {
+    let (_merge_base, groups) = derive_local_groups(base, ignore_tag)?;
+    let heads: Vec<String> = group_branch_identities(&groups, prefix)?
+        .into_iter()
+        .map(|identity| identity.exact)
+        .collect();
+    let shas: BTreeMap<String, String> = get_remote_branches_sha(&heads)?
+        .into_iter()
+        .filter(|(head, _)| heads.contains(head))
+        .collect();
+    if let Some(existing) = frozen_record(prefix)? {
+        info!(
+            "The stack under {} is already frozen since {}; recording the current SHAs.",
+            prefix, existing.frozen_at
+        );
+    }
+    let record = FreezeRecord {
+        frozen_at: OffsetDateTime::now_utc().format(&Rfc3339)?,
+        stack_branch: git_current_branch()?,
+        reason: reason
+            .map(str::trim)
+            .filter(|reason| !reason.is_empty())
+            .map(str::to_string),
+        shas,
+    };
+    let count = record.shas.len();
+    publish_freeze(prefix, Some(record), execution_mode)?;
+    if execution_mode == ExecutionMode::Apply {
+        info!(
+            "Froze the stack under {} ({} published branch(es)); `spr update` and `spr land` refuse until `spr thaw`.",
+            prefix, count
+        );
+    }
+    Ok(())
+}
+
+/// Group branches whose remote SHA differs from the one recorded at freeze time.
+fn moved_since_freeze(record: &FreezeRecord, current: &BTreeMap<String, String>) -> Vec<String> {
+    record
+        .shas
+        .iter()
+        .filter(|(head, sha)| current.get(*head) != Some(*sha))
+        .map(|(head, _)| head.clone())
+        .collect()
+}
+
+/// Lift the freeze recorded for `prefix`, if any.
+///
+/// # Errors
+///
+/// Returns errors from `ls-remote` or pushing the state ref.
+pub fn thaw_stack(prefix: &str, execution_mode: ExecutionMode) -> Result<()> {
+    let Some(record) = frozen_record(prefix)? else {
+        info!("The stack under {} is not frozen.", prefix);
+        return Ok(());
+    };
+    let heads: Vec<String> = record.shas.keys().cloned().collect();
+    let current: BTreeMap<String, String> = get_remote_branches_sha(&heads)?
+        .into_iter()
+        .filter(|(head, _)| heads.contains(head))
+        .collect();
+    let moved = moved_since_freeze(&record, &current);
+    if !moved.is_empty() {
+        warn!("Moved on the remote while frozen: {}", moved.join(", "));
+    }
+    publish_freeze(prefix, None, execution_mode)?;
+    if execution_mode == ExecutionMode::Apply {
+        info!(
+            "Thawed the stack under {} (frozen since {}).",
+            prefix, record.frozen_at
+        );
+    }
+    Ok(())
+}
+
+/// Refuse to publish or land a frozen stack, or lift the freeze first when `thaw` is set.
+///
+/// A dry run only warns, so the plan can still be previewed.
+///
+/// # Errors
+///
+/// Returns an error when the stack is frozen and `thaw` is not set, or when thawing fails.
+pub fn ensure_not_frozen(
+    prefix: &str,
+    thaw: bool,
+    command: &str,
+    execution_mode: ExecutionMode,
+) -> Result<()> {
+    let Some(record) = frozen_record(prefix)? else {
+        return Ok(());
+    };
+    let reason = record
+        .reason
+        .as_deref()
+        .map(|reason| format!(": {reason}"))
+        .unwrap_or_default();
+    if thaw {
+        return thaw_stack(prefix, execution_mode);
+    }
+    if execution_mode == ExecutionMode::DryRun {
+        warn!(
+            "The stack under {} is frozen since {}{}; {} would refuse without --thaw.",
+            prefix, record.frozen_at, reason, command
+        );
+        return Ok(());
+    }
+    bail!(
+        "The stack under {} is frozen since {}{}. Run `spr thaw` or pass --thaw to {}.",
+        prefix,
+        record.frozen_at,
+        reason,
+        command
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ensure_not_frozen, freeze_stack, moved_since_freeze, thaw_stack};
+    use crate::execution::ExecutionMode;
+    use crate::remote_state::{frozen_record, FreezeRecord};
+    use crate::test_support::{commit_file, git, init_repo, lock_cwd, DirGuard};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn freeze_blocks_land_until_thawed() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path().to_path_buf();
+        let origin_dir = tempfile::tempdir().unwrap();
+        let origin = origin_dir.path().to_str().unwrap().to_string();
+        git(&repo, ["init", "--bare", &origin].as_slice());
+        git(&repo, ["remote", "add", "origin", &origin].as_slice());
+        git(&repo, ["checkout", "-b", "stack"].as_slice());
+        let alpha = commit_file(&repo, "alpha.txt", "a\n", "feat: alpha pr:alpha");
+        git(
+            &repo,
+            [
+                "push",
+                "origin",
+                &format!("{alpha}:refs/heads/dank-spr/alpha"),
+            ]
+            .as_slice(),
+        );
+        let _guard = DirGuard::change_to(&repo);
+
+        freeze_stack(
+            "main",
+            "dank-spr/",
+            "ignore",
+            Some("release cut"),
+            ExecutionMode::Apply,
+        )
+        .unwrap();
+
+        let record = frozen_record("dank-spr/").unwrap().unwrap();
+        assert_eq!(record.stack_branch, "stack");
+        assert_eq!(record.reason.as_deref(), Some("release cut"));
+        assert_eq!(record.shas.get("dank-spr/alpha"), Some(&alpha));
+        let err =
+            ensure_not_frozen("dank-spr/", false, "spr land", ExecutionMode::Apply).unwrap_err();
+        assert!(err.to_string().contains("release cut"), "{err:#}");
+        ensure_not_frozen("dank-spr/", false, "spr land", ExecutionMode::DryRun).unwrap();
+
+        thaw_stack("dank-spr/", ExecutionMode::Apply).unwrap();
+        assert!(frozen_record("dank-spr/").unwrap().is_none());
+        ensure_not_frozen("dank-spr/", false, "spr land", ExecutionMode::Apply).unwrap();
+    }
+
+    #[test]
+    fn moved_since_freeze_lists_changed_and_deleted_branches() {
+        let record = FreezeRecord {
+            frozen_at: "2026-01-01T00:00:00Z".to_string(),
+            stack_branch: "stack".to_string(),
+            reason: None,
+            shas: BTreeMap::from([
+                ("dank-spr/a".to_string(), "a1".to_string()),
+                ("dank-spr/b".to_string(), "b1".to_string()),
+                ("dank-spr/c".to_string(), "c1".to_string()),
+            ]),
+        };
+        let current = BTreeMap::from([
+            ("dank-spr/a".to_string(), "a1".to_string()),
+            ("dank-spr/b".to_string(), "b2".to_string()),
+        ]);
+        assert_eq!(
+            moved_since_freeze(&record, &current),
+            vec!["dank-spr/b".to_string(), "dank-spr/c".to_string()]
+        );
+    }
+}
@@ -0,0 +1,319 @@
+//! Import an existing (non-spr) PR into the local stack as a new group.
+//!
+//! The PR's commits are fetched from `refs/pull/<n>/head` and replayed into the stack at the
+//! chosen position. The first imported commit gets a group marker appended to its message:
+//! `branch:<head>` by default, so the next `spr update` force-pushes the group to the PR's own
+//! branch and the existing PR joins the stack block; or `pr:<tag>` with `--tag`, which publishes
+//! the group as a new prefix-branch PR instead.
+
+use anyhow::{anyhow, bail, Result};
+use tracing::info;
+
+use crate::commands::common;
+use crate::commands::common::CherryPickOp;
+use crate::commands::rewrite_resume::{
+    self, RewriteCommandKind, RewriteCommandOutcome, RewriteConflictPolicy, RewriteDestinationKind,
+    RewriteSession,
+};
+use crate::config::DirtyWorktreePolicy;
+use crate::execution::ExecutionMode;
+use crate::git::{git_rev_parse, git_ro, remote};
+use crate::github::PrImportInfo;
+use crate::group_markers::GroupMarker;
+use crate::parsing::derive_local_groups_with_ignored;
+use crate::selectors::{resolve_after_count, AfterSelector};
+
+fn import_marker(info: &PrImportInfo, tag: Option<&str>) -> Result<GroupMarker> {
+    if let Some(tag) = tag {
+        crate::pr_labels::validate_label(tag)
+            .map_err(|err| anyhow!("invalid --tag `{}`: {}", tag, err))?;
+        return Ok(GroupMarker::PrLabel(tag.to_string()));
+    }
+    if info.is_cross_repository {
+        bail!(
+            "PR #{} comes from a fork, so `spr update` cannot push to its branch; pass --tag <label> to import it as a new prefix-branch PR",
+            info.number
+        );
+    }
+    Ok(GroupMarker::BranchName(info.head.clone()))
+}
+
+/// Replay `existing[..insert_at]`, then the imported commits, then the rest of the stack.
+///
+/// `imported[0]` is the marker seed, which is not the parent of `imported[1]`, so it is picked on
+/// its own instead of opening a range.
+fn build_import_operations(
+    existing: &[String],
+    imported: &[String],
+    insert_at: usize,
+) -> Vec<CherryPickOp> {
+    let mut operations = Vec::new();
+    operations.extend(CherryPickOp::from_commits(&existing[..insert_at]));
+    operations.extend(CherryPickOp::from_commits(
+        &imported[..imported.len().min(1)],
+    ));
+    operations.extend(CherryPickOp::from_commits(
+        &imported[imported.len().min(1)..],
+    ));
+    operations.extend(CherryPickOp::from_commits(&existing[insert_at..]));
+    operations
+}
+
+/// Create a copy of `sha` whose message ends with `marker`, keeping tree, parent, and author.
+///
+/// This only writes an unreferenced commit object, so it also runs under `--dry-run`.
+fn seed_commit_with_marker(sha: &str, marker: &GroupMarker) -> Result<String> {
+    let message = git_ro(["log", "-n", "1", "--format=%B", sha].as_slice())?;
+    common::commit_with_message(
+        sha,
+        &format!(
+            "{}\n\n{}",
+            message.trim_end(),
+            marker.explicit_selector_text()
+        ),
+    )
+}
+
+fn ensure_importable_commits(info: &PrImportInfo) -> Result<()> {
+    if info.commit_oids.is_empty() {
+        bail!("PR #{} has no commits to import", info.number);
+    }
+    for sha in &info.commit_oids {
+        let parents = git_ro(["rev-list", "--parents", "-n", "1", sha].as_slice())?;
+        if parents.split_whitespace().count() != 2 {
+            bail!(
+                "PR #{} contains merge commit {}; linearize the PR before importing it",
+                info.number,
+                &sha[..sha.len().min(8)]
+            );
+        }
+        let message = git_ro(["log", "-n", "1", "--format=%B", sha].as_slice())?;
+        if !crate::group_markers::candidate_group_markers(&message).is_empty() {
+            bail!(
+                "PR #{} commit {} already contains a group marker; reword it before importing",
+                info.number,
+                &sha[..sha.len().min(8)]
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Import open PR `number` as a new group after `after` (bottom→top ordinals).
+///
+/// # Errors
+///
+/// Returns errors when the PR is not open, comes from a fork without `tag`, contains merge
+/// commits or group markers, would duplicate an existing group marker, or when Git operations
+/// fail.
+pub fn import_pr(
+    metadata_context: &crate::stack_metadata::RefreshMetadataContext,
+    number: u64,
+    after: &AfterSelector,
+    tag: Option<&str>,
+    safe: bool,
+    execution_mode: ExecutionMode,
+    dirty_worktree_policy: DirtyWorktreePolicy,
+) -> Result<RewriteCommandOutcome> {
+    let info = crate::github::fetch_pr_import_info(number)?;
+    if info.state != "OPEN" {
+        bail!(
+            "PR #{} is {}; only open PRs can be imported",
+            number,
+            info.state.to_ascii_lowercase()
+        );
+    }
+    let marker = import_marker(&info, tag)?;
+
+    let (merge_base, leading_ignored, groups) =
+        derive_local_groups_with_ignored(&metadata_context.base, &metadata_context.ignore_tag)?;
+    if groups.iter().any(|group| group.marker == marker) {
+        bail!("The stack already has a group marked `{}`", marker);
+    }
+    let after_count = resolve_after_count(&groups, after)?;
+
+    let mut existing: Vec<String> = leading_ignored.clone();
+    let mut insert_at = existing.len();
+    for (idx, group) in groups.iter().enumerate() {
+        existing.extend(group.commits.iter().cloned());
+        existing.extend(group.ignored_after.iter().cloned());
+        if idx + 1 == after_count {
+            insert_at = existing.len();
+        }
+    }
+
+    // Fetching only updates FETCH_HEAD, so it runs under --dry-run too.
+    git_ro(["fetch", &remote(), &format!("refs/pull/{number}/head")].as_slice())?;
+    ensure_importable_commits(&info)?;
+    let mut imported = info.commit_oids.clone();
+    imported[0] = seed_commit_with_marker(&imported[0], &marker)?;
+
+    let post_success_hint = match &marker {
+        GroupMarker::BranchName(head) => format!(
+            "Imported PR #{number} as `{marker}`. Run `spr update` to force-push `{head}` and add PR #{number} to the stack."
+        ),
+        GroupMarker::PrLabel(_) => format!(
+            "Imported PR #{number} as `{marker}`. Run `spr update` to publish it as a new PR, then close PR #{number}."
+        ),
+    };
+
+    common::with_dirty_worktree_policy(
+        execution_mode,
+        "spr import-pr",
+        dirty_worktree_policy,
+        |deferred_dirty_worktree_restore| {
+            let (cur_branch, short) = common::get_current_branch_and_short()?;
+            let original_head = git_rev_parse("HEAD")?;
+            let original_worktree_root = rewrite_resume::current_repo_root()?;
+            let resume_path = rewrite_resume::prepare_resume_path_for_new_session(
+                execution_mode,
+                RewriteCommandKind::ImportPr,
+                &cur_branch,
+                &original_head,
+            )?;
+            let backup_tag = if safe {
+                Some(common::create_backup_tag(
+                    execution_mode,
+                    "import-pr",
+                    &cur_branch,
+                    &short,
+                )?)
+            } else {
+                None
+            };
+
+            let (tmp_path, tmp_branch) =
+                common::create_temp_worktree(execution_mode, "import", &merge_base, &short)?;
+            info!(
+                "Importing {} commit(s) from PR #{} after {} group(s).",
+                imported.len(),
+                number,
+                after_count
+            );
+            rewrite_resume::run_rewrite_session(
+                execution_mode,
+                RewriteSession {
+                    command_kind: RewriteCommandKind::ImportPr,
+                    conflict_policy: RewriteConflictPolicy::Suspend,
+                    original_worktree_root,
+                    original_branch: cur_branch,
+                    original_head,
+                    destination_kind: RewriteDestinationKind::CheckedOutBranch,
+                    resume_path,
+                    temp_branch: tmp_branch,
+                    temp_worktree_path: tmp_path,
+                    backup_tag,
+                    operations: build_import_operations(&existing, &imported, insert_at),
+                    deferred_dirty_worktree_restore,
+                    post_success_hint: Some(post_success_hint.clone()),
+                    metadata_refresh_context: Some(metadata_context.clone()),
+                },
+            )
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_import_operations, import_marker, seed_commit_with_marker};
+    use crate::commands::common::{CherryPickEmptyPolicy, CherryPickOp};
+    use crate::github::PrImportInfo;
+    use crate::group_markers::GroupMarker;
+    use crate::test_support::{commit_file, git, init_repo, lock_cwd, DirGuard};
+
+    fn shas(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn import_operations_splice_pr_commits_at_insert_point() {
+        let ops = build_import_operations(&shas(&["a1", "a2", "b1"]), &shas(&["p1", "p2"]), 2);
+        assert_eq!(
+            ops,
+            vec![
+                CherryPickOp::Range {
+                    first: "a1".to_string(),
+                    last: "a2".to_string(),
+                    empty_policy: CherryPickEmptyPolicy::StopOnEmpty,
+                },
+                CherryPickOp::Commit {
+                    sha: "p1".to_string(),
+                    empty_policy: CherryPickEmptyPolicy::StopOnEmpty,
+                },
+                CherryPickOp::Commit {
+                    sha: "p2".to_string(),
+                    empty_policy: CherryPickEmptyPolicy::StopOnEmpty,
+                },
+                CherryPickOp::Commit {
+                    sha: "b1".to_string(),
+                    empty_policy: CherryPickEmptyPolicy::StopOnEmpty,
+                },
+            ]
+        );
+        assert_eq!(
+            build_import_operations(&shas(&["a1"]), &shas(&["p1"]), 1).len(),
+            2
+        );
+    }
+
+    #[test]
+    fn import_marker_adopts_branch_unless_tagged_or_forked() {
+        let mut info = PrImportInfo {
+            number: 7,
+            title: "Fix".to_string(),
+            state: "OPEN".to_string(),
+            head: "alice/fix".to_string(),
+            is_cross_repository: false,
+            commit_oids: shas(&["p1"]),
+        };
+        assert_eq!(
+            import_marker(&info, None).unwrap(),
+            GroupMarker::BranchName("alice/fix".to_string())
+        );
+        assert_eq!(
+            import_marker(&info, Some("fix")).unwrap(),
+            GroupMarker::PrLabel("fix".to_string())
+        );
+        info.is_cross_repository = true;
+        assert!(import_marker(&info, None).is_err());
+        assert!(import_marker(&info, Some("1bad")).is_err());
+    }
+
+    #[test]
+    fn seed_commit_keeps_tree_and_author_and_appends_marker() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path().to_path_buf();
+        git(
+            &repo,
+            ["config", "user.email", "importer@example.com"].as_slice(),
+        );
+        let original = commit_file(&repo, "fix.txt", "fix\n", "Fix login\n\nDetails.");
+        git(
+            &repo,
+            ["config", "user.email", "other@example.com"].as_slice(),
+        );
+        let _guard = DirGuard::change_to(&repo);
+
+        let seed =
+            seed_commit_with_marker(&original, &GroupMarker::BranchName("alice/fix".to_string()))
+                .unwrap();
+
+        assert_ne!(seed, original);
+        assert_eq!(
+            git(&repo, ["log", "-n", "1", "--format=%B", &seed].as_slice()).trim_end(),
+            "Fix login\n\nDetails.\n\nbranch:alice/fix"
+        );
+        assert_eq!(
+            git(&repo, ["log", "-n", "1", "--format=%ae", &seed].as_slice()).trim(),
+            "importer@example.com"
+        );
+        assert_eq!(
+            git(&repo, ["rev-parse", &format!("{seed}^{{tree}}")].as_slice()),
+            git(
+                &repo,
+                ["rev-parse", &format!("{original}^{{tree}}")].as_slice()
+            )
+        );
+    }
+}
@@ -0,0 +1,199 @@
+//! Scaffold `.spr_multicommit_cfg.yml` with `spr init`.
+//!
+//! The base is proposed from the repository's default branch as GitHub reports it (`gh repo view`),
+//! falling back to `<remote>/HEAD`, and the prefix from `$USER`. From a terminal each proposal can
+//! be accepted with Enter or replaced; otherwise, or with `--yes`, the proposals are written as is.
+//! The file goes to the repository root, or with `--global` to `$HOME`, and an existing file is
+//! only replaced with `--force`. The written file lists the most common keys with comments, so
+//! the format does not have to be read from the source.
+
+use anyhow::{anyhow, bail, Context, Result};
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::PathBuf;
+use tracing::{info, warn};
+
+use crate::config::{normalize_prefix, FileConfig};
+use crate::git::{discover_remote_head_base, gh_ro, remote};
+
+const CONFIG_FILE_NAME: &str = ".spr_multicommit_cfg.yml";
+
+/// Values `spr init` writes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InitAnswers {
+    pub base: String,
+    pub prefix: String,
+    pub land: String,
+}
+
+/// `<remote>/<default branch>`, from GitHub when reachable, else from `<remote>/HEAD`.
+fn propose_base() -> String {
+    let from_github = gh_ro(
+        [
+            "repo",
+            "view",
+            "--json",
+            "defaultBranchRef",
+            "-q",
+            ".defaultBranchRef.name",
+        ]
+        .as_slice(),
+    )
+    .ok()
+    .map(|branch| branch.trim().to_string())
+    .filter(|branch| !branch.is_empty());
+    match from_github {
+        Some(branch) => format!("{}/{branch}", remote()),
+        None => discover_remote_head_base().unwrap_or_else(|err| {
+            warn!("Could not detect the default branch: {err:#}");
+            format!("{}/main", remote())
+        }),
+    }
+}
+
+/// `<user>-spr/`, with characters that do not belong in a branch name replaced by `-`.
+fn propose_prefix(user: &str) -> String {
+    let user: String = user
+        .trim()
+        .to_ascii_lowercase()
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || ch == '-' || ch == '_' || ch == '.' {
+                ch
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let user = user.trim_matches('-');
+    if user.is_empty() {
+        "spr/".to_string()
+    } else {
+        format!("{user}-spr/")
+    }
+}
+
+fn ask(question: &str, proposal: &str) -> Result<String> {
+    let mut stderr = std::io::stderr();
+    write!(stderr, "{question} [{proposal}]: ")?;
+    stderr.flush()?;
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+    let answer = answer.trim();
+    Ok(if answer.is_empty() {
+        proposal.to_string()
+    } else {
+        answer.to_string()
+    })
+}
+
+/// The config file text for `answers`, with the other common keys commented out.
+pub fn render_config(answers: &InitAnswers) -> String {
+    format!(
+        "# spr-multicommit configuration, written by `spr init`.\n\
+         # Repository config overrides `$HOME/{CONFIG_FILE_NAME}`; see the README for every key.\n\
+         \n\
+         # Root of the stack: the branch PRs are opened against\n\
+         base: {}\n\
+         \n\
+         # Prefix of the per-PR branches `spr update` pushes\n\
+         prefix: {}\n\
+         \n\
+         # Default `spr land` mode: flatten or per-pr\n\
+         land: {}\n\
+         \n\
+         # `pr:<tag>` that starts a block of commits kept out of every PR\n\
+         # ignore_tag: ignore\n\
+         \n\
+         # overwrite (default) or stack_only: whether commit bodies replace PR descriptions\n\
+         # pr_description_mode: overwrite\n\
+         \n\
+         # halt (default) or rollback: what `spr restack` does on a conflict\n\
+         # restack_conflict: halt\n\
+         \n\
+         # halt (default), stash, or discard: how rewrites treat uncommitted changes\n\
+         # dirty_worktree: halt\n",
+        answers.base, answers.prefix, answers.land
+    )
+}
+
+fn config_path(global: bool) -> Result<PathBuf> {
+    let dir = if global {
+        PathBuf::from(std::env::var_os("HOME").ok_or_else(|| anyhow!("HOME is not set"))?)
+    } else {
+        PathBuf::from(
+            crate::git::main_worktree_root()?
+                .ok_or_else(|| anyhow!("`spr init` must run inside a git worktree"))?,
+        )
+    };
+    Ok(dir.join(CONFIG_FILE_NAME))
+}
+
+/// Write a config file from detected defaults, asking to confirm each from a terminal.
+///
+/// Returns the path written.
+///
+/// # Errors
+///
+/// Returns errors when the file exists without `force`, an answer is invalid, or writing fails.
+pub fn init_config(global: bool, yes: bool, force: bool) -> Result<PathBuf> {
+    let path = config_path(global)?;
+    if path.exists() && !force {
+        bail!(
+            "{} already exists; pass --force to replace it",
+            path.display()
+        );
+    }
+    let mut answers = InitAnswers {
+        base: propose_base(),
+        prefix: propose_prefix(&std::env::var("USER").unwrap_or_default()),
+        land: "flatten".to_string(),
+    };
+    if !yes && std::io::stdin().is_terminal() {
+        answers.base = ask("Base branch", &answers.base)?;
+        answers.prefix = ask("Branch prefix", &answers.prefix)?;
+        answers.land = ask("Land mode (flatten or per-pr)", &answers.land)?;
+    }
+    answers.prefix = normalize_prefix(&answers.prefix)?;
+    if answers.land != "flatten" && answers.land != "per-pr" {
+        bail!(
+            "Land mode must be `flatten` or `per-pr`, not `{}`",
+            answers.land
+        );
+    }
+    let text = render_config(&answers);
+    serde_yaml::from_str::<FileConfig>(&text)
+        .with_context(|| format!("Refusing to write an unparsable {}", path.display()))?;
+    std::fs::write(&path, text)
+        .with_context(|| format!("Failed to write config file: {}", path.display()))?;
+    info!(
+        "Wrote {} (base {}, prefix {}, land {})",
+        path.display(),
+        answers.base,
+        answers.prefix,
+        answers.land
+    );
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{propose_prefix, render_config, InitAnswers};
+    use crate::config::FileConfig;
+
+    #[test]
+    fn init_proposes_a_user_prefix_and_renders_parsable_config() {
+        assert_eq!(propose_prefix("Jane Doe"), "jane-doe-spr/");
+        assert_eq!(propose_prefix(""), "spr/");
+
+        let text = render_config(&InitAnswers {
+            base: "origin/main".to_string(),
+            prefix: "jane-spr/".to_string(),
+            land: "per-pr".to_string(),
+        });
+        let cfg: FileConfig = serde_yaml::from_str(&text).unwrap();
+        assert_eq!(cfg.base.as_deref(), Some("origin/main"));
+        assert_eq!(cfg.prefix.as_deref(), Some("jane-spr/"));
+        assert_eq!(cfg.land.as_deref(), Some("per-pr"));
+        assert_eq!(cfg.ignore_tag, None);
+    }
+}
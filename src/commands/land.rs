@@ -1,24 +1,81 @@
 use anyhow::{bail, Result};
 use std::collections::{HashMap, HashSet};
-use tracing::warn;
+use std::io::{BufRead, IsTerminal, Write};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
 
 use crate::branch_names::{canonical_branch_conflict_key, group_branch_identities};
 use crate::cli::LandCmd;
+use crate::commands::landed::note_landed_commits;
+use crate::commands::update::STACK_BLOCK_START;
 use crate::execution::ExecutionMode;
-use crate::git::{gh_rw, git_ro, git_rw, sanitize_gh_base_ref, to_remote_ref};
+use crate::git::{fetch_remote, git_ro, sanitize_gh_base_ref, to_remote_ref};
 use crate::github::{
-    fetch_pr_bodies_graphql, fetch_pr_ci_review_status, fetch_pr_issue_comment_bodies_graphql,
-    graphql_escape, list_open_or_merged_prs_for_heads, PrCiState, PrInfoWithState,
-    PrReviewDecision, PrState,
+    fetch_pr_bodies_with_node_ids_graphql, fetch_pr_ci_review_status,
+    fetch_pr_issue_comment_bodies_graphql, fetch_pr_node_ids_graphql, fetch_repo_merge_methods,
+    list_open_or_merged_prs_for_heads, GraphqlMutation, PrCiReviewStatus, PrCiState,
+    PrInfoWithState, PrReviewDecision, PrState, RepoMergeMethods,
 };
 use crate::parsing::derive_local_groups;
+use crate::project_status::ProjectsConfig;
 use crate::selectors::{resolve_inclusive_count, InclusiveSelector};
+use crate::stack_segments::group_segment_base;
 
+/// Resolve how many groups `until` lands. Landing stops at the first segment boundary: a higher
+/// group with a `pr:base:` directive heads its own PR chain onto another base, so merging it into
+/// the stack base would be wrong.
 fn resolve_land_take_count(
     groups: &[crate::parsing::Group],
     until: &InclusiveSelector,
 ) -> Result<usize> {
-    resolve_inclusive_count(groups, until)
+    let take_n = resolve_inclusive_count(groups, until)?;
+    if let Some(offset) = groups[1..take_n]
+        .iter()
+        .position(|group| group_segment_base(group).is_some())
+    {
+        let boundary = &groups[offset + 1];
+        bail!(
+            "LPR #{} {} starts a segment onto '{}'; land at most the {} group(s) below it",
+            offset + 2,
+            boundary.selector_text(),
+            group_segment_base(boundary).unwrap_or_default(),
+            offset + 1
+        );
+    }
+    Ok(take_n)
+}
+
+/// With `confirm_land` set, list the groups `until` selects and ask from a terminal whether to
+/// land them onto `base`. Off a terminal there is nobody to ask, so landing proceeds.
+///
+/// # Errors
+///
+/// Returns errors when `until` does not resolve or the terminal cannot be read.
+pub fn confirm_landing(
+    groups: &[crate::parsing::Group],
+    until: &InclusiveSelector,
+    base: &str,
+) -> Result<bool> {
+    if !std::io::stdin().is_terminal() {
+        return Ok(true);
+    }
+    let count = resolve_land_take_count(groups, until)?;
+    let mut stderr = std::io::stderr();
+    writeln!(stderr, "Landing onto {base}:")?;
+    for (idx, group) in groups.iter().take(count).enumerate() {
+        writeln!(
+            stderr,
+            "  {}. {} {}",
+            idx + 1,
+            group.selector_text(),
+            group.subjects.first().map(String::as_str).unwrap_or("")
+        )?;
+    }
+    write!(stderr, "Land {count} PR(s)? [y/N] ")?;
+    stderr.flush()?;
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
 }
 
 #[derive(Debug)]
@@ -84,7 +141,11 @@ fn resolve_land_plan<'a>(
     Ok((take_n, LandPlan::Fresh { segment }))
 }
 
-fn format_land_safety_failures(ci_bad: &[u64], review_bad: &[u64]) -> String {
+fn format_land_safety_failures(
+    ci_bad: &[u64],
+    status_bad: &[String],
+    review_bad: &[u64],
+) -> String {
     let format_numbers = |numbers: &[u64]| {
         numbers
             .iter()
@@ -96,6 +157,12 @@ fn format_land_safety_failures(ci_bad: &[u64], review_bad: &[u64]) -> String {
     if !ci_bad.is_empty() {
         failures.push(format!("CI not passing: {}", format_numbers(ci_bad)));
     }
+    if !status_bad.is_empty() {
+        failures.push(format!(
+            "Commit statuses not passing: {}",
+            status_bad.join(", ")
+        ));
+    }
     if !review_bad.is_empty() {
         failures.push(format!(
             "Reviews not approved: {}",
@@ -105,22 +172,335 @@ fn format_land_safety_failures(ci_bad: &[u64], review_bad: &[u64]) -> String {
     failures.join("; ")
 }
 
+/// PRs that fail the land safety checks, by kind.
+#[derive(Debug, Default)]
+struct LandSafetyFailures {
+    /// PRs whose check runs are not passing.
+    ci: Vec<u64>,
+    /// `#N (context, ...)` for PRs with commit statuses that are not passing.
+    statuses: Vec<String>,
+    /// PRs whose review is not approved.
+    reviews: Vec<u64>,
+}
+
+impl LandSafetyFailures {
+    /// Collect the PRs among `numbers` whose check runs, commit statuses, or review decision
+    /// `ci_failing`/`review_failing` reject. A PR without a status is judged as `Unknown`.
+    fn collect(
+        numbers: &[u64],
+        status_map: &HashMap<u64, PrCiReviewStatus>,
+        ci_failing: impl Fn(PrCiState) -> bool,
+        review_failing: impl Fn(PrReviewDecision) -> bool,
+    ) -> Self {
+        let mut failures = Self::default();
+        for number in numbers {
+            let status = status_map.get(number);
+            if ci_failing(status.map_or(PrCiState::Unknown, |status| status.checks_state)) {
+                failures.ci.push(*number);
+            }
+            let contexts: Vec<&str> = status
+                .into_iter()
+                .flat_map(|status| &status.status_contexts)
+                .filter(|context| ci_failing(context.state))
+                .map(|context| context.context.as_str())
+                .collect();
+            if !contexts.is_empty() {
+                failures
+                    .statuses
+                    .push(format!("#{number} ({})", contexts.join(", ")));
+            }
+            if review_failing(
+                status.map_or(PrReviewDecision::Unknown, |status| status.review_decision),
+            ) {
+                failures.reviews.push(*number);
+            }
+        }
+        failures
+    }
+
+    fn is_empty(&self) -> bool {
+        self.ci.is_empty() && self.statuses.is_empty() && self.reviews.is_empty()
+    }
+
+    fn describe(&self) -> String {
+        format_land_safety_failures(&self.ci, &self.statuses, &self.reviews)
+    }
+}
+
+/// PRs whose CI or commit statuses are not passing and PRs whose review is not approved; a PR
+/// without a status counts as failing CI and review.
+fn land_safety_failures(
+    numbers: &[u64],
+    status_map: &HashMap<u64, PrCiReviewStatus>,
+) -> LandSafetyFailures {
+    LandSafetyFailures::collect(
+        numbers,
+        status_map,
+        |state| state != PrCiState::Success,
+        |decision| decision != PrReviewDecision::Approved,
+    )
+}
+
+/// Refuse to land unless every PR in `numbers` passes CI and is approved, or warn when
+/// `bypass_safety` (`--unsafe`) is set. A status that could not be fetched fails the check rather
+/// than skipping it.
+fn enforce_land_safety(
+    numbers: &[u64],
+    status_map: Result<HashMap<u64, PrCiReviewStatus>>,
+    bypass_safety: bool,
+) -> Result<()> {
+    let failures = match status_map {
+        Ok(status_map) => {
+            let failures = land_safety_failures(numbers, &status_map);
+            if failures.is_empty() {
+                return Ok(());
+            }
+            failures.describe()
+        }
+        Err(err) => format!("could not fetch CI/review status ({err:#})"),
+    };
+    if bypass_safety {
+        warn!("Bypassing safety checks (--unsafe). {}", failures);
+        Ok(())
+    } else {
+        bail!("Refusing to land: {}. Use --unsafe to override.", failures)
+    }
+}
+
+/// First delay between `spr land --wait` polls; it doubles up to [`MAX_WAIT_POLL_INTERVAL`].
+const INITIAL_WAIT_POLL_INTERVAL: Duration = Duration::from_secs(15);
+const MAX_WAIT_POLL_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Where the PRs being landed stand for `spr land --wait`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum LandWaitState {
+    /// CI passes and reviews are approved on every PR.
+    Ready,
+    /// Some PR is still running CI or awaiting review; the text says which.
+    Pending(String),
+    /// Some PR failed CI or had changes requested, so waiting will not help.
+    Blocked(String),
+}
+
+fn land_wait_state(numbers: &[u64], status_map: &HashMap<u64, PrCiReviewStatus>) -> LandWaitState {
+    let blocked = LandSafetyFailures::collect(
+        numbers,
+        status_map,
+        |state| matches!(state, PrCiState::Failure | PrCiState::Error),
+        |decision| decision == PrReviewDecision::ChangesRequested,
+    );
+    if !blocked.is_empty() {
+        return LandWaitState::Blocked(blocked.describe());
+    }
+    let failures = land_safety_failures(numbers, status_map);
+    if failures.is_empty() {
+        LandWaitState::Ready
+    } else {
+        LandWaitState::Pending(failures.describe())
+    }
+}
+
+/// How many of `numbers`, from the bottom, pass CI and are approved. A PR lands only together
+/// with the PRs below it, so readiness stops at the first PR that is not.
+fn ready_prefix_len(numbers: &[u64], status_map: &HashMap<u64, PrCiReviewStatus>) -> usize {
+    numbers
+        .iter()
+        .take_while(|number| {
+            land_wait_state(std::slice::from_ref(*number), status_map) == LandWaitState::Ready
+        })
+        .count()
+}
+
+/// The head commit whose checks and reviews `status_map` holds for PR `number`, to pin its merge.
+fn checked_head_oid(status_map: &HashMap<u64, PrCiReviewStatus>, number: u64) -> Option<String> {
+    status_map
+        .get(&number)
+        .map(|status| status.head_oid.clone())
+        .filter(|oid| !oid.is_empty())
+}
+
+/// Give up on `spr land --wait`: an error if nothing landed yet, else a warning, so the caller
+/// still restacks onto what did land.
+fn stop_waiting(landed: usize, total: usize, reason: String) -> Result<usize> {
+    if landed == 0 {
+        bail!("{reason}");
+    }
+    warn!("{reason}; landed {landed} of {total} PR(s)");
+    Ok(landed)
+}
+
+/// Land `numbers` bottom-up as they become landable: poll CI and review status with backoff and,
+/// whenever the lowest PRs pass and are approved, call `land` with how many PRs are landed after
+/// it and the checked head commit of the top one. Returns how many PRs landed.
+///
+/// Stops when a check fails, changes are requested, or `timeout` passes. Dry runs check once,
+/// report what they would land and wait for, and count every PR as landed.
+fn land_as_ready<F, L>(
+    numbers: &[u64],
+    timeout: Duration,
+    execution_mode: ExecutionMode,
+    mut fetch_status: F,
+    mut land: L,
+) -> Result<usize>
+where
+    F: FnMut(&[u64]) -> Result<HashMap<u64, PrCiReviewStatus>>,
+    L: FnMut(usize, Option<&str>) -> Result<()>,
+{
+    let deadline = Instant::now() + timeout;
+    let mut interval = INITIAL_WAIT_POLL_INTERVAL;
+    let mut landed = 0;
+    while landed < numbers.len() {
+        let remaining = &numbers[landed..];
+        let pending = match fetch_status(remaining) {
+            Ok(status_map) => {
+                let ready = ready_prefix_len(remaining, &status_map);
+                if ready > 0 {
+                    let head_oid = checked_head_oid(&status_map, remaining[ready - 1]);
+                    land(landed + ready, head_oid.as_deref())?;
+                    landed += ready;
+                    continue;
+                }
+                match land_wait_state(remaining, &status_map) {
+                    LandWaitState::Blocked(failures) => {
+                        return stop_waiting(
+                            landed,
+                            numbers.len(),
+                            format!("Stopped waiting to land: {failures}"),
+                        );
+                    }
+                    LandWaitState::Pending(failures) => failures,
+                    LandWaitState::Ready => unreachable!("a ready PR lands above"),
+                }
+            }
+            Err(err) => format!("could not fetch CI/review status ({err:#})"),
+        };
+        if execution_mode == ExecutionMode::DryRun {
+            info!("Would wait to land: {}", pending);
+            return Ok(numbers.len());
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return stop_waiting(
+                landed,
+                numbers.len(),
+                format!(
+                    "Timed out after {}s waiting to land: {pending}",
+                    timeout.as_secs()
+                ),
+            );
+        }
+        let delay = interval.min(deadline - now);
+        info!(
+            "Waiting to land: {}; checking again in {}s",
+            pending,
+            delay.as_secs()
+        );
+        std::thread::sleep(delay);
+        interval = (interval * 2).min(MAX_WAIT_POLL_INTERVAL);
+    }
+    Ok(landed)
+}
+
 // Each older PR adds two mutative aliases: one comment and one close. GitHub does not publish a
 // safe alias count for this shape, so keep each write request deliberately small.
 const MAX_CLOSE_COMMENT_PRS_PER_MUTATION: usize = 3;
 
-fn build_land_merge_mutation(nth_id: &str, base: &str, mode: LandCmd) -> String {
-    let merge_method = match mode {
-        LandCmd::PerPr => "REBASE",
-        LandCmd::Flatten => "SQUASH",
+/// Explicit squash-merge commit message for a flatten land.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SquashMergeMessage {
+    headline: String,
+    body: String,
+}
+
+/// Compose the landed commit message from PR metadata, mirroring GitHub's `Title (#N)` headline
+/// but keeping only the description above the spr stack block.
+fn squash_merge_message(number: u64, title: &str, body: &str) -> SquashMergeMessage {
+    let description = body
+        .find(STACK_BLOCK_START)
+        .map_or(body, |start| &body[..start]);
+    SquashMergeMessage {
+        headline: format!("{} (#{number})", title.trim()),
+        body: description.trim().to_string(),
+    }
+}
+
+/// GitHub merge method used for the landed PR.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MergeMethod {
+    Squash,
+    Rebase,
+    Merge,
+}
+
+impl MergeMethod {
+    fn preferred(mode: LandCmd) -> Self {
+        match mode {
+            LandCmd::Flatten => MergeMethod::Squash,
+            LandCmd::PerPr => MergeMethod::Rebase,
+        }
+    }
+
+    fn graphql_name(self) -> &'static str {
+        match self {
+            MergeMethod::Squash => "SQUASH",
+            MergeMethod::Rebase => "REBASE",
+            MergeMethod::Merge => "MERGE",
+        }
+    }
+}
+
+/// Pick a merge method the repository allows for `mode`.
+///
+/// Flatten needs a squash merge to land one commit. Per-PR prefers a rebase merge and falls back
+/// to a merge commit, which keeps each PR's commit on the base as well.
+fn choose_merge_method(mode: LandCmd, allowed: RepoMergeMethods) -> Result<MergeMethod> {
+    match mode {
+        LandCmd::Flatten if allowed.squash => Ok(MergeMethod::Squash),
+        LandCmd::Flatten if allowed.rebase || allowed.merge_commit => bail!(
+            "This repository does not allow squash merging, which `spr land flatten` needs; use `spr land per-pr` or enable squash merging"
+        ),
+        LandCmd::PerPr if allowed.rebase => Ok(MergeMethod::Rebase),
+        LandCmd::PerPr if allowed.merge_commit => {
+            warn!("This repository does not allow rebase merging; landing with a merge commit");
+            Ok(MergeMethod::Merge)
+        }
+        LandCmd::PerPr if allowed.squash => bail!(
+            "This repository does not allow rebase merging or merge commits, which `spr land per-pr` needs; use `spr land flatten`"
+        ),
+        _ => bail!("This repository allows no merge method that `spr land` can use"),
+    }
+}
+
+fn build_land_merge_mutation(
+    nth_id: &str,
+    base: &str,
+    merge_method: MergeMethod,
+    squash_message: Option<&SquashMergeMessage>,
+    expected_head_oid: Option<&str>,
+) -> GraphqlMutation {
+    let mut mutation = GraphqlMutation::new();
+    let id = mutation.var("ID!", nth_id);
+    let base_ref = mutation.var("String!", &sanitize_gh_base_ref(base));
+    mutation.field(format!(
+        "b0: updatePullRequest(input:{{pullRequestId:{id}, baseRefName:{base_ref}}}){{ clientMutationId }}"
+    ));
+    let commit_message = match (merge_method, squash_message) {
+        (MergeMethod::Squash, Some(message)) => format!(
+            ", commitHeadline:{}, commitBody:{}",
+            mutation.var("String!", &message.headline),
+            mutation.var("String!", &message.body),
+        ),
+        _ => String::new(),
     };
-    format!(
-        "mutation {{b0: updatePullRequest(input:{{pullRequestId:\"{}\", baseRefName:\"{}\"}}){{ clientMutationId }} m0: mergePullRequest(input:{{pullRequestId:\"{}\", mergeMethod:{}}}){{ clientMutationId }} }}",
-        nth_id,
-        graphql_escape(&sanitize_gh_base_ref(base)),
-        nth_id,
-        merge_method,
-    )
+    // GitHub refuses the merge if the head moved since its checks and reviews were read.
+    let expected_head = expected_head_oid.map_or_else(String::new, |oid| {
+        format!(", expectedHeadOid:{}", mutation.var("GitObjectID!", oid))
+    });
+    mutation.field(format!(
+        "m0: mergePullRequest(input:{{pullRequestId:{id}, mergeMethod:{}{commit_message}{expected_head}}}){{ clientMutationId }}",
+        merge_method.graphql_name(),
+    ));
+    mutation
 }
 
 fn cleanup_comment(merged_pr_number: u64) -> String {
@@ -132,36 +512,33 @@ fn build_close_comment_mutation(
     ids_by_number: &HashMap<u64, String>,
     merged_pr_number: u64,
     add_comment_numbers: &HashSet<u64>,
-) -> Option<String> {
-    let mut mutation = String::from("mutation {");
-    let mut has_operations = false;
+) -> Option<GraphqlMutation> {
+    let mut mutation = GraphqlMutation::new();
     for (i, pr) in prs.iter().enumerate() {
         let Some(id) = ids_by_number.get(&pr.number).filter(|id| !id.is_empty()) else {
             continue;
         };
-        has_operations = true;
+        let id = mutation.var("ID!", id);
         if add_comment_numbers.contains(&pr.number) {
-            mutation.push_str(&format!(
-                "c{}: addComment(input:{{subjectId:\"{}\", body:\"{}\"}}){{ clientMutationId }} ",
-                i,
-                id,
-                graphql_escape(&cleanup_comment(merged_pr_number))
+            let body = mutation.var("String!", &cleanup_comment(merged_pr_number));
+            mutation.field(format!(
+                "c{i}: addComment(input:{{subjectId:{id}, body:{body}}}){{ clientMutationId }}"
             ));
         }
-        mutation.push_str(&format!(
-            "x{}: closePullRequest(input:{{pullRequestId:\"{}\"}}){{ clientMutationId }} ",
-            i, id
+        mutation.field(format!(
+            "x{i}: closePullRequest(input:{{pullRequestId:{id}}}){{ clientMutationId }}"
         ));
     }
-    mutation.push('}');
-    has_operations.then_some(mutation)
+    (!mutation.is_empty()).then_some(mutation)
 }
 
 struct LandMutationPlan<'a> {
     base: &'a str,
-    mode: LandCmd,
+    merge_method: MergeMethod,
     target: &'a PrInfoWithState,
     target_id: Option<&'a str>,
+    squash_message: Option<&'a SquashMergeMessage>,
+    expected_head_oid: Option<&'a str>,
     open_older_prs: &'a [&'a PrInfoWithState],
     ids_by_number: &'a HashMap<u64, String>,
     add_comment_numbers: &'a HashSet<u64>,
@@ -169,10 +546,16 @@ struct LandMutationPlan<'a> {
 
 fn run_land_mutations<F>(plan: LandMutationPlan<'_>, mut run: F) -> Result<()>
 where
-    F: FnMut(String) -> Result<()>,
+    F: FnMut(GraphqlMutation) -> Result<()>,
 {
     if let Some(target_id) = plan.target_id {
-        run(build_land_merge_mutation(target_id, plan.base, plan.mode))?;
+        run(build_land_merge_mutation(
+            target_id,
+            plan.base,
+            plan.merge_method,
+            plan.squash_message,
+            plan.expected_head_oid,
+        ))?;
     }
     for chunk in plan
         .open_older_prs
@@ -190,6 +573,28 @@ where
     Ok(())
 }
 
+/// Best-effort Projects (v2) update after GitHub landing succeeded; failures only warn.
+fn update_landed_project_items(
+    projects: &ProjectsConfig,
+    landed_numbers: &[u64],
+    execution_mode: ExecutionMode,
+) {
+    if let Err(err) =
+        crate::project_status::mark_landed_prs(projects, landed_numbers, execution_mode)
+    {
+        warn!("Landing succeeded, but updating GitHub Projects status failed: {err:#}");
+    }
+}
+
+/// Local commits of the landed groups, bottom to top.
+fn landed_commits(groups: &[crate::parsing::Group]) -> Vec<String> {
+    groups
+        .iter()
+        .flat_map(|group| group.commits.iter().cloned())
+        .collect()
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn land_until(
     base: &str,
     prefix: &str,
@@ -198,6 +603,8 @@ pub fn land_until(
     execution_mode: ExecutionMode,
     mode: LandCmd,
     bypass_safety: bool,
+    wait: Option<Duration>,
+    projects: &ProjectsConfig,
 ) -> Result<usize> {
     // Local stack is the source of truth: derive order from local groups
     let (_merge_base, groups) = derive_local_groups(base, ignore_tag)?;
@@ -235,11 +642,7 @@ pub fn land_until(
             .iter()
             .map(|pr| pr.number)
             .collect::<Vec<_>>();
-        let bodies = fetch_pr_bodies_graphql(&numbers)?;
-        let ids_by_number = bodies
-            .iter()
-            .map(|(number, info)| (*number, info.id.clone()))
-            .collect::<HashMap<_, _>>();
+        let ids_by_number = fetch_pr_node_ids_graphql(&numbers)?;
         let expected_comment = cleanup_comment(target.number);
         let mut add_comment_numbers = std::collections::HashSet::new();
         for pr in open_older_prs {
@@ -253,104 +656,149 @@ pub fn land_until(
             target.number,
             open_older_prs.len()
         );
+        let mut landed_numbers = numbers;
+        landed_numbers.push(target.number);
         return run_land_mutations(
             LandMutationPlan {
                 base,
-                mode,
+                merge_method: MergeMethod::preferred(mode),
                 target,
                 target_id: None,
+                squash_message: None,
+                expected_head_oid: None,
                 open_older_prs,
                 ids_by_number: &ids_by_number,
                 add_comment_numbers: &add_comment_numbers,
             },
             |mutation| {
-                gh_rw(
-                    execution_mode,
-                    ["api", "graphql", "-f", &format!("query={mutation}")].as_slice(),
-                )?;
+                mutation.run(execution_mode)?;
                 Ok(())
             },
         )
-        .map(|()| take_n);
+        .map(|()| {
+            update_landed_project_items(projects, &landed_numbers, execution_mode);
+            note_landed_commits(
+                &landed_commits(&groups[..take_n]),
+                target.number,
+                execution_mode,
+            );
+            take_n
+        });
     };
     let segment = segment.as_slice();
-
-    // Safety validation: CI and Reviews must be passing/approved for all PRs being landed
+    let merge_method = choose_merge_method(mode, fetch_repo_merge_methods()?)?;
     let numbers: Vec<u64> = segment.iter().map(|p| p.number).collect();
-    if !numbers.is_empty() {
-        if let Ok(status_map) = fetch_pr_ci_review_status(&numbers) {
-            let mut ci_bad: Vec<u64> = vec![];
-            let mut rv_bad: Vec<u64> = vec![];
-            for n in &numbers {
-                if let Some(st) = status_map.get(n) {
-                    if st.ci_state != PrCiState::Success {
-                        ci_bad.push(*n);
-                    }
-                    if st.review_decision != PrReviewDecision::Approved {
-                        rv_bad.push(*n);
-                    }
-                } else {
-                    // Unknown status → treat as failing both
-                    ci_bad.push(*n);
-                    rv_bad.push(*n);
-                }
-            }
-            if !ci_bad.is_empty() || !rv_bad.is_empty() {
-                let failures = format_land_safety_failures(&ci_bad, &rv_bad);
-                if bypass_safety {
-                    warn!("Bypassing safety checks (--unsafe). {}", failures);
-                } else {
-                    bail!("Refusing to land: {}. Use --unsafe to override.", failures);
-                }
-            }
+    let land_prefix = |from: usize, to: usize, expected_head_oid: Option<&str>| -> Result<()> {
+        merge_top_and_close_rest(
+            base,
+            merge_method,
+            &segment[from..to],
+            expected_head_oid,
+            execution_mode,
+        )?;
+        update_landed_project_items(projects, &numbers[from..to], execution_mode);
+        note_landed_commits(
+            &landed_commits(&groups[from..to]),
+            segment[to - 1].number,
+            execution_mode,
+        );
+        Ok(())
+    };
+
+    if let Some(timeout) = wait {
+        if let LandCmd::PerPr = mode {
+            ensure_single_commit_prs(base, segment, execution_mode)?;
         }
+        let mut landed = 0;
+        return land_as_ready(
+            &numbers,
+            timeout,
+            execution_mode,
+            fetch_pr_ci_review_status,
+            |through, expected_head_oid| {
+                land_prefix(landed, through, expected_head_oid)?;
+                landed = through;
+                Ok(())
+            },
+        );
     }
 
+    // Safety validation: CI and Reviews must be passing/approved for all PRs being landed
+    let status_map = fetch_pr_ci_review_status(&numbers);
+    let expected_head_oid = status_map
+        .as_ref()
+        .ok()
+        .and_then(|status_map| checked_head_oid(status_map, segment[take_n - 1].number));
+    enforce_land_safety(&numbers, status_map, bypass_safety)?;
+
     if let LandCmd::PerPr = mode {
-        // Verify each has exactly one unique commit over its parent
-        git_rw(execution_mode, ["fetch", "origin"].as_slice())?; // ensure remotes up to date
-        let mut offenders: Vec<u64> = vec![];
-        for (i, pr) in segment.iter().enumerate() {
-            let parent = if i == 0 {
-                base.to_string()
-            } else {
-                segment[i - 1].head.clone()
-            };
-            let parent_ref = to_remote_ref(&parent);
-            let child_ref = to_remote_ref(&pr.head);
-            let cnt_s = git_ro(
-                [
-                    "rev-list",
-                    "--count",
-                    &format!("{}..{}", parent_ref, child_ref),
-                ]
-                .as_slice(),
-            )?;
-            let cnt: usize = cnt_s.trim().parse().unwrap_or(0);
-            if cnt != 1 {
-                offenders.push(pr.number);
-            }
-        }
-        if !offenders.is_empty() {
-            warn!(
-                "The following PRs have != 1 commit: {}",
-                offenders
-                    .iter()
-                    .map(|x| format!("#{}", x))
-                    .collect::<Vec<_>>()
-                    .join(", ")
-            );
-            bail!("Run `spr prep` to squash them first");
+        ensure_single_commit_prs(base, segment, execution_mode)?;
+    }
+
+    land_prefix(0, take_n, expected_head_oid.as_deref())?;
+    Ok(take_n)
+}
+
+/// Per-PR landing rebases each PR as one commit, so each must have exactly one commit over its
+/// parent.
+fn ensure_single_commit_prs(
+    base: &str,
+    segment: &[&PrInfoWithState],
+    execution_mode: ExecutionMode,
+) -> Result<()> {
+    fetch_remote(execution_mode)?; // ensure remotes up to date
+    let mut offenders: Vec<u64> = vec![];
+    for (i, pr) in segment.iter().enumerate() {
+        let parent = if i == 0 {
+            base.to_string()
+        } else {
+            segment[i - 1].head.clone()
+        };
+        let parent_ref = to_remote_ref(&parent);
+        let child_ref = to_remote_ref(&pr.head);
+        let cnt_s = git_ro(
+            [
+                "rev-list",
+                "--count",
+                &format!("{}..{}", parent_ref, child_ref),
+            ]
+            .as_slice(),
+        )?;
+        let cnt: usize = cnt_s.trim().parse().unwrap_or(0);
+        if cnt != 1 {
+            offenders.push(pr.number);
         }
     }
+    if !offenders.is_empty() {
+        warn!(
+            "The following PRs have != 1 commit: {}",
+            offenders
+                .iter()
+                .map(|x| format!("#{}", x))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        bail!("Run `spr prep` to squash them first");
+    }
+    Ok(())
+}
 
-    // Set base of Nth PR, merge it (rebase or squash), then close older PRs in bounded chunks.
-    let nth = segment[take_n - 1];
+/// Set the base of the top PR in `prs` to `base`, merge it (rebase or squash), then close the PRs
+/// below it in bounded chunks. With `expected_head_oid`, the merge only goes through while the PR
+/// head is still that commit.
+fn merge_top_and_close_rest(
+    base: &str,
+    merge_method: MergeMethod,
+    prs: &[&PrInfoWithState],
+    expected_head_oid: Option<&str>,
+    execution_mode: ExecutionMode,
+) -> Result<()> {
+    let (nth, older) = prs.split_last().expect("landing at least one PR");
     let mut nums: Vec<u64> = vec![nth.number];
-    for pr in &segment[..take_n - 1] {
-        nums.push(pr.number);
-    }
-    let bodies = fetch_pr_bodies_graphql(&nums)?;
+    nums.extend(older.iter().map(|pr| pr.number));
+    // Only the landed PR's title and body matter; older PRs just need their node IDs, which
+    // share the same read unless this run already saw them.
+    let (bodies, ids_by_number) = fetch_pr_bodies_with_node_ids_graphql(&[nth.number], &nums)?;
     let nth_id = bodies
         .get(&nth.number)
         .map(|x| x.id.clone())
@@ -358,44 +806,42 @@ pub fn land_until(
     if nth_id.is_empty() {
         bail!("Failed to fetch GraphQL id for PR #{}", nth.number);
     }
+    let squash_message = bodies
+        .get(&nth.number)
+        .filter(|info| !info.title.trim().is_empty())
+        .map(|info| squash_merge_message(nth.number, &info.title, &info.body));
 
-    let ids_by_number: HashMap<u64, String> = bodies
-        .iter()
-        .map(|(number, info)| (*number, info.id.clone()))
-        .collect();
     tracing::info!(
         "Merging PR #{} and closing {} other PR(s) on GitHub... this might take a few seconds.",
         nth.number,
-        take_n - 1
+        older.len()
     );
-    let add_comment_numbers = segment[..take_n - 1]
+    let add_comment_numbers = older
         .iter()
         .map(|pr| pr.number)
         .collect::<std::collections::HashSet<_>>();
     run_land_mutations(
         LandMutationPlan {
             base,
-            mode,
+            merge_method,
             target: nth,
             target_id: Some(&nth_id),
-            open_older_prs: &segment[..take_n - 1],
+            squash_message: squash_message.as_ref(),
+            expected_head_oid,
+            open_older_prs: older,
             ids_by_number: &ids_by_number,
             add_comment_numbers: &add_comment_numbers,
         },
         |mutation| {
-            gh_rw(
-                execution_mode,
-                ["api", "graphql", "-f", &format!("query={mutation}")].as_slice(),
-            )?;
+            mutation.run(execution_mode)?;
             Ok(())
         },
-    )?;
-
-    Ok(take_n)
+    )
 }
 
 /// Per-PR: land N PRs bottom-up, each PR as its own commit using rebase merge.
 /// Each PR must have exactly one commit over its parent.
+#[allow(clippy::too_many_arguments)]
 pub fn land_per_pr_until(
     base: &str,
     prefix: &str,
@@ -403,6 +849,8 @@ pub fn land_per_pr_until(
     until: &InclusiveSelector,
     execution_mode: ExecutionMode,
     bypass_safety: bool,
+    wait: Option<Duration>,
+    projects: &ProjectsConfig,
 ) -> Result<usize> {
     land_until(
         base,
@@ -412,10 +860,13 @@ pub fn land_per_pr_until(
         execution_mode,
         LandCmd::PerPr,
         bypass_safety,
+        wait,
+        projects,
     )
 }
 
 /// Flatten: behave like per-pr landing but squash-merge the Nth PR and set its base to the actual base.
+#[allow(clippy::too_many_arguments)]
 pub fn land_flatten_until(
     base: &str,
     prefix: &str,
@@ -423,6 +874,8 @@ pub fn land_flatten_until(
     until: &InclusiveSelector,
     execution_mode: ExecutionMode,
     bypass_safety: bool,
+    wait: Option<Duration>,
+    projects: &ProjectsConfig,
 ) -> Result<usize> {
     land_until(
         base,
@@ -432,24 +885,32 @@ pub fn land_flatten_until(
         execution_mode,
         LandCmd::Flatten,
         bypass_safety,
+        wait,
+        projects,
     )
 }
 
 #[cfg(test)]
 mod tests {
     use super::{
-        build_close_comment_mutation, build_land_merge_mutation, format_land_safety_failures,
-        land_until, resolve_land_plan, resolve_land_take_count, run_land_mutations,
-        LandMutationPlan, LandPlan,
+        build_close_comment_mutation, build_land_merge_mutation, choose_merge_method,
+        enforce_land_safety, format_land_safety_failures, land_as_ready, land_safety_failures,
+        land_until, land_wait_state, resolve_land_plan, resolve_land_take_count,
+        run_land_mutations, squash_merge_message, LandMutationPlan, LandPlan, LandWaitState,
+        MergeMethod, SquashMergeMessage,
     };
     use crate::branch_names::canonical_branch_conflict_key;
     use crate::cli::LandCmd;
     use crate::execution::ExecutionMode;
-    use crate::github::{PrInfoWithState, PrState};
+    use crate::github::{
+        GraphqlMutation, PrCiReviewStatus, PrCiState, PrInfoWithState, PrReviewDecision, PrState,
+        PrStatusContext, RepoMergeMethods,
+    };
     use crate::parsing::Group;
     use crate::selectors::{ExplicitGroupSelector, GroupSelector, InclusiveSelector};
     use crate::test_support::{init_case_conflicting_stack_repo, lock_cwd, DirGuard};
     use std::collections::HashMap;
+    use std::time::Duration;
 
     fn groups(tags: &[&str]) -> Vec<Group> {
         tags.iter()
@@ -463,6 +924,10 @@ mod tests {
             .collect()
     }
 
+    fn rendered(mutation: GraphqlMutation) -> String {
+        mutation.gh_args().join(" ")
+    }
+
     fn pr(number: u64, head: &str) -> PrInfoWithState {
         PrInfoWithState {
             number,
@@ -490,6 +955,28 @@ mod tests {
         assert_eq!(resolve_land_take_count(&groups, &until).unwrap(), 2);
     }
 
+    #[test]
+    fn land_until_stops_at_the_next_segment_boundary() {
+        let mut groups = groups(&["alpha", "beta", "gamma"]);
+        groups[1].first_message = Some("feat: beta pr:beta pr:base:release".to_string());
+
+        assert_eq!(
+            resolve_land_take_count(
+                &groups,
+                &InclusiveSelector::Group(GroupSelector::LocalPr(1))
+            )
+            .unwrap(),
+            1
+        );
+        let err = resolve_land_take_count(&groups, &InclusiveSelector::All)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("LPR #2 pr:beta starts a segment onto 'release'"),
+            "{err}"
+        );
+    }
+
     #[test]
     fn land_until_only_requires_open_prs_for_landed_prefix() {
         let groups = groups(&["rho", "sigma"]);
@@ -559,6 +1046,8 @@ mod tests {
             ExecutionMode::DryRun,
             LandCmd::Flatten,
             false,
+            None,
+            &crate::project_status::ProjectsConfig::default(),
         )
         .unwrap_err();
 
@@ -571,14 +1060,91 @@ mod tests {
 
     #[test]
     fn land_merge_mutation_only_updates_and_merges_target_pr() {
-        let mutation = build_land_merge_mutation("PR_target", "origin/main", LandCmd::Flatten);
+        let mutation = rendered(build_land_merge_mutation(
+            "PR_target",
+            "origin/main",
+            MergeMethod::Squash,
+            None,
+            Some("0123abcd"),
+        ));
 
         assert!(mutation.contains("updatePullRequest"));
         assert!(mutation.contains("mergePullRequest"));
         assert!(!mutation.contains("closePullRequest"));
         assert!(!mutation.contains("addComment"));
-        assert!(mutation.contains("baseRefName:\"main\""));
+        assert!(mutation.contains("baseRefName:$v1"));
+        assert!(mutation.contains("v1=main"));
         assert!(mutation.contains("mergeMethod:SQUASH"));
+        assert!(!mutation.contains("commitHeadline"));
+        assert!(mutation.contains("expectedHeadOid:$v2"));
+        assert!(mutation.contains("v2=0123abcd"));
+    }
+
+    #[test]
+    fn merge_method_follows_repository_restrictions() {
+        let allowed = |merge_commit, squash, rebase| RepoMergeMethods {
+            merge_commit,
+            squash,
+            rebase,
+        };
+
+        assert_eq!(
+            choose_merge_method(LandCmd::Flatten, allowed(true, true, true)).unwrap(),
+            MergeMethod::Squash
+        );
+        assert_eq!(
+            choose_merge_method(LandCmd::PerPr, allowed(true, true, true)).unwrap(),
+            MergeMethod::Rebase
+        );
+        assert_eq!(
+            choose_merge_method(LandCmd::PerPr, allowed(true, false, false)).unwrap(),
+            MergeMethod::Merge
+        );
+        let err = choose_merge_method(LandCmd::Flatten, allowed(false, false, true)).unwrap_err();
+        assert!(err.to_string().contains("use `spr land per-pr`"), "{err}");
+        let err = choose_merge_method(LandCmd::PerPr, allowed(false, true, false)).unwrap_err();
+        assert!(err.to_string().contains("use `spr land flatten`"), "{err}");
+
+        let mutation =
+            build_land_merge_mutation("PR_target", "main", MergeMethod::Merge, None, None);
+        assert!(mutation.query().contains("mergeMethod:MERGE"));
+    }
+
+    #[test]
+    fn flatten_merge_mutation_uses_pr_metadata_without_stack_block() {
+        let message = squash_merge_message(
+            7,
+            "feat: alpha ",
+            "Alpha \"details\".\n\n<!-- spr-stack:start -->\n**Stack**:\n- #7\n<!-- spr-stack:end -->",
+        );
+        assert_eq!(
+            message,
+            SquashMergeMessage {
+                headline: "feat: alpha (#7)".to_string(),
+                body: "Alpha \"details\".".to_string(),
+            }
+        );
+
+        let mutation = rendered(build_land_merge_mutation(
+            "PR_target",
+            "main",
+            MergeMethod::Squash,
+            Some(&message),
+            None,
+        ));
+        assert!(mutation.contains("commitHeadline:$v2, commitBody:$v3"));
+        assert!(mutation.contains("v2=feat: alpha (#7)"));
+        assert!(mutation.contains("v3=Alpha \"details\"."));
+        assert!(!mutation.contains("spr-stack"));
+
+        let per_pr = build_land_merge_mutation(
+            "PR_target",
+            "main",
+            MergeMethod::Rebase,
+            Some(&message),
+            None,
+        );
+        assert!(!per_pr.query().contains("commitHeadline"));
     }
 
     #[test]
@@ -586,13 +1152,15 @@ mod tests {
         let prs = [pr(1, "skilltest/alpha"), pr(2, "skilltest/beta")];
         let ids = HashMap::from([(1, "PR_alpha".to_string()), (2, "PR_beta".to_string())]);
 
-        let mutation = build_close_comment_mutation(
-            &[&prs[0]],
-            &ids,
-            3,
-            &std::collections::HashSet::from([1]),
-        )
-        .unwrap();
+        let mutation = rendered(
+            build_close_comment_mutation(
+                &[&prs[0]],
+                &ids,
+                3,
+                &std::collections::HashSet::from([1]),
+            )
+            .unwrap(),
+        );
 
         assert!(mutation.contains("PR_alpha"));
         assert!(!mutation.contains("PR_beta"));
@@ -665,15 +1233,17 @@ mod tests {
         let err = run_land_mutations(
             LandMutationPlan {
                 base: "main",
-                mode: LandCmd::Flatten,
+                merge_method: MergeMethod::Squash,
                 target: &target,
                 target_id: Some("PR_gamma"),
+                squash_message: None,
+                expected_head_oid: None,
                 open_older_prs: &[&older],
                 ids_by_number: &ids,
                 add_comment_numbers: &comments,
             },
             |mutation| {
-                first_calls.push(mutation);
+                first_calls.push(rendered(mutation));
                 if first_calls.len() == 2 {
                     anyhow::bail!("transient cleanup failure");
                 }
@@ -688,15 +1258,17 @@ mod tests {
         run_land_mutations(
             LandMutationPlan {
                 base: "main",
-                mode: LandCmd::Flatten,
+                merge_method: MergeMethod::Squash,
                 target: &target,
                 target_id: None,
+                squash_message: None,
+                expected_head_oid: None,
                 open_older_prs: &[&older],
                 ids_by_number: &ids,
                 add_comment_numbers: &std::collections::HashSet::new(),
             },
             |mutation| {
-                retry_calls.push(mutation);
+                retry_calls.push(rendered(mutation));
                 Ok(())
             },
         )
@@ -711,16 +1283,205 @@ mod tests {
     #[test]
     fn land_safety_failure_message_only_reports_failed_checks() {
         assert_eq!(
-            format_land_safety_failures(&[17], &[]),
+            format_land_safety_failures(&[17], &[], &[]),
             "CI not passing: #17"
         );
         assert_eq!(
-            format_land_safety_failures(&[], &[18]),
+            format_land_safety_failures(&[], &[], &[18]),
             "Reviews not approved: #18"
         );
         assert_eq!(
-            format_land_safety_failures(&[17], &[18, 19]),
+            format_land_safety_failures(&[17], &[], &[18, 19]),
             "CI not passing: #17; Reviews not approved: #18, #19"
         );
     }
+
+    #[test]
+    fn land_wait_state_keeps_waiting_only_on_pending_checks_and_reviews() {
+        let status = |ci_state, review_decision| PrCiReviewStatus {
+            ci_state,
+            checks_state: ci_state,
+            status_contexts: Vec::new(),
+            review_decision,
+            is_draft: false,
+            awaiting_review_since: None,
+            requested_reviewers: Vec::new(),
+            head_oid: String::new(),
+        };
+        let mut status_map = HashMap::from([
+            (17, status(PrCiState::Success, PrReviewDecision::Approved)),
+            (
+                18,
+                status(PrCiState::Pending, PrReviewDecision::ReviewRequired),
+            ),
+        ]);
+
+        assert_eq!(land_wait_state(&[17], &status_map), LandWaitState::Ready);
+        assert_eq!(
+            land_wait_state(&[17, 18, 19], &status_map),
+            LandWaitState::Pending(
+                "CI not passing: #18, #19; Reviews not approved: #18, #19".to_string()
+            )
+        );
+
+        status_map.insert(
+            19,
+            status(PrCiState::Failure, PrReviewDecision::ChangesRequested),
+        );
+        assert_eq!(
+            land_wait_state(&[17, 18, 19], &status_map),
+            LandWaitState::Blocked("CI not passing: #19; Reviews not approved: #19".to_string())
+        );
+    }
+
+    fn ready_status(ready: bool, head_oid: &str) -> PrCiReviewStatus {
+        PrCiReviewStatus {
+            ci_state: PrCiState::Success,
+            checks_state: PrCiState::Success,
+            status_contexts: Vec::new(),
+            review_decision: if ready {
+                PrReviewDecision::Approved
+            } else {
+                PrReviewDecision::ReviewRequired
+            },
+            is_draft: false,
+            awaiting_review_since: None,
+            requested_reviewers: Vec::new(),
+            head_oid: head_oid.to_string(),
+        }
+    }
+
+    #[test]
+    fn land_as_ready_lands_bottom_up_pinned_to_the_checked_heads() {
+        let mut polls = vec![
+            HashMap::from([(18, ready_status(true, "bbb"))]),
+            HashMap::from([
+                (17, ready_status(true, "aaa")),
+                (18, ready_status(false, "bbb")),
+            ]),
+        ];
+        let mut landed = Vec::new();
+        let count = land_as_ready(
+            &[17, 18],
+            Duration::from_secs(3600),
+            ExecutionMode::Apply,
+            |_| Ok(polls.pop().unwrap()),
+            |through, head_oid| {
+                landed.push((through, head_oid.map(str::to_string)));
+                Ok(())
+            },
+        )
+        .unwrap();
+
+        assert_eq!(count, 2);
+        assert_eq!(
+            landed,
+            vec![(1, Some("aaa".to_string())), (2, Some("bbb".to_string()))]
+        );
+    }
+
+    #[test]
+    fn land_as_ready_gives_up_at_the_timeout_keeping_what_landed() {
+        let statuses = HashMap::from([
+            (17, ready_status(true, "aaa")),
+            (18, ready_status(false, "bbb")),
+        ]);
+        let mut landed = Vec::new();
+        let count = land_as_ready(
+            &[17, 18],
+            Duration::ZERO,
+            ExecutionMode::Apply,
+            |numbers| {
+                Ok(statuses
+                    .iter()
+                    .filter(|(number, _)| numbers.contains(number))
+                    .map(|(number, status)| (*number, status.clone()))
+                    .collect())
+            },
+            |through, _| {
+                landed.push(through);
+                Ok(())
+            },
+        )
+        .unwrap();
+        assert_eq!((count, landed), (1, vec![1]));
+
+        let err = land_as_ready(
+            &[18],
+            Duration::ZERO,
+            ExecutionMode::Apply,
+            |_| Ok(HashMap::from([(18, ready_status(false, "bbb"))])),
+            |_, _| panic!("nothing is ready to land"),
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("Timed out after 0s waiting to land: Reviews not approved: #18"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn enforce_land_safety_refuses_failing_or_unfetchable_status_without_unsafe() {
+        let approved = PrCiReviewStatus {
+            ci_state: PrCiState::Success,
+            checks_state: PrCiState::Success,
+            status_contexts: Vec::new(),
+            review_decision: PrReviewDecision::Approved,
+            is_draft: false,
+            awaiting_review_since: None,
+            requested_reviewers: Vec::new(),
+            head_oid: String::new(),
+        };
+        let status_map = HashMap::from([(17, approved)]);
+
+        enforce_land_safety(&[17], Ok(status_map.clone()), false).unwrap();
+        assert_eq!(
+            enforce_land_safety(&[17, 18], Ok(status_map.clone()), false)
+                .unwrap_err()
+                .to_string(),
+            "Refusing to land: CI not passing: #18; Reviews not approved: #18. Use --unsafe to override."
+        );
+        assert_eq!(
+            enforce_land_safety(&[17], Err(anyhow::anyhow!("gh: rate limited")), false)
+                .unwrap_err()
+                .to_string(),
+            "Refusing to land: could not fetch CI/review status (gh: rate limited). Use --unsafe to override."
+        );
+        enforce_land_safety(&[17, 18], Ok(status_map), true).unwrap();
+        enforce_land_safety(&[17], Err(anyhow::anyhow!("gh: rate limited")), true).unwrap();
+    }
+
+    #[test]
+    fn land_safety_failures_name_commit_statuses_separately_from_checks() {
+        let status = PrCiReviewStatus {
+            ci_state: PrCiState::Pending,
+            checks_state: PrCiState::Success,
+            status_contexts: vec![
+                PrStatusContext {
+                    context: "deploy/staging".to_string(),
+                    state: PrCiState::Pending,
+                },
+                PrStatusContext {
+                    context: "ci/jenkins".to_string(),
+                    state: PrCiState::Success,
+                },
+            ],
+            review_decision: PrReviewDecision::Approved,
+            is_draft: false,
+            awaiting_review_since: None,
+            requested_reviewers: Vec::new(),
+            head_oid: String::new(),
+        };
+        let status_map = HashMap::from([(18, status)]);
+
+        assert_eq!(
+            land_safety_failures(&[17, 18], &status_map).describe(),
+            "CI not passing: #17; Commit statuses not passing: #18 (deploy/staging); Reviews not approved: #17"
+        );
+        assert_eq!(
+            land_wait_state(&[18], &status_map),
+            LandWaitState::Pending("Commit statuses not passing: #18 (deploy/staging)".to_string())
+        );
+    }
 }
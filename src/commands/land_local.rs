@@ -0,0 +1,164 @@
+//! Simulate `spr land` on a local copy of the base branch.
+//!
+//! `spr land --local-only` squashes each selected group into one commit on top of the base, in
+//! stack order, and points the local branch `spr-land-local` at the result. GitHub is never
+//! contacted and the checked-out worktree is untouched, so the branch can be built and tested in
+//! a separate worktree before the real merge. Groups are applied with a private index, so a group
+//! that no longer applies cleanly to the base is reported instead of landing with conflicts.
+
+use anyhow::{bail, Context, Result};
+use tracing::info;
+
+use crate::commands::common::{apply_commit_diff, git_in_index, with_temp_index};
+use crate::execution::ExecutionMode;
+use crate::git::{git_ro, git_rw, worktree_entries};
+use crate::group_markers::strip_valid_group_markers;
+use crate::parsing::{derive_local_groups, Group};
+use crate::selectors::{resolve_inclusive_count, InclusiveSelector};
+
+/// Local branch that receives the simulated landing.
+pub const LAND_LOCAL_BRANCH: &str = "spr-land-local";
+
+/// Result of `spr land --local-only`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LandLocalOutcome {
+    /// Number of groups squashed onto the base.
+    pub landed: usize,
+    /// Tip of the simulated landing; `None` in dry-run mode.
+    pub tip: Option<String>,
+}
+
+/// Squash commit message for `group`: its first commit's message without group markers.
+fn squash_message(group: &Group) -> Result<String> {
+    let message = match group.commits.first() {
+        Some(first) => git_ro(["log", "-n", "1", "--format=%B", first].as_slice())?,
+        None => group.subjects.first().cloned().unwrap_or_default(),
+    };
+    let stripped = strip_valid_group_markers(&message);
+    let lines: Vec<&str> = stripped.lines().map(str::trim_end).collect();
+    Ok(lines.join("\n").trim().to_string())
+}
+
+/// Squash `groups` one by one on top of `base_sha`, returning the last squash commit.
+fn squash_groups_onto(base_sha: &str, groups: &[Group]) -> Result<String> {
+    with_temp_index("land-local", |index| {
+        git_in_index(index, ["read-tree", base_sha].as_slice(), None)?;
+        let mut parent = base_sha.to_string();
+        for group in groups {
+            for commit in &group.commits {
+                apply_commit_diff(index, commit).with_context(|| {
+                    format!(
+                        "{} does not apply cleanly on top of the base; run `spr restack --after 0` first",
+                        group.selector_text()
+                    )
+                })?;
+            }
+            let tree = git_in_index(index, ["write-tree"].as_slice(), None)?;
+            let message = squash_message(group)?;
+            parent = git_ro(["commit-tree", &tree, "-p", &parent, "-m", &message].as_slice())?
+                .trim()
+                .to_string();
+        }
+        Ok(parent)
+    })
+}
+
+/// Squash the groups up to `until` onto `base` and point [`LAND_LOCAL_BRANCH`] at the result.
+///
+/// # Errors
+///
+/// Returns errors when there are no groups, `until` does not resolve, the branch is checked out,
+/// or a group does not apply cleanly on top of the groups below it.
+pub fn land_local_only(
+    base: &str,
+    ignore_tag: &str,
+    until: &InclusiveSelector,
+    execution_mode: ExecutionMode,
+) -> Result<LandLocalOutcome> {
+    let (_merge_base, groups) = derive_local_groups(base, ignore_tag)?;
+    if groups.is_empty() {
+        bail!("No local PR groups found; nothing to land.");
+    }
+    let landed = resolve_inclusive_count(&groups, until)?;
+    let branch_ref = format!("refs/heads/{LAND_LOCAL_BRANCH}");
+    if worktree_entries()?
+        .iter()
+        .any(|entry| entry.branch.as_deref() == Some(LAND_LOCAL_BRANCH))
+    {
+        bail!(
+            "{LAND_LOCAL_BRANCH} is checked out in a worktree; switch away from it before rerunning `spr land --local-only`"
+        );
+    }
+    let base_sha = git_ro(["rev-parse", "--verify", &format!("{base}^{{commit}}")].as_slice())?
+        .trim()
+        .to_string();
+    let tip = squash_groups_onto(&base_sha, &groups[..landed])?;
+    if execution_mode == ExecutionMode::DryRun {
+        info!(
+            "DRY-RUN: would point {} at {} PR(s) squashed onto {}",
+            LAND_LOCAL_BRANCH, landed, base
+        );
+        return Ok(LandLocalOutcome { landed, tip: None });
+    }
+    git_rw(execution_mode, ["update-ref", &branch_ref, &tip].as_slice())?;
+    info!(
+        "Simulated landing {} PR(s) onto {}: {} is at {}. Build and test it with `git worktree add <path> {}`.",
+        landed,
+        base,
+        LAND_LOCAL_BRANCH,
+        &tip[..tip.len().min(8)],
+        LAND_LOCAL_BRANCH
+    );
+    Ok(LandLocalOutcome {
+        landed,
+        tip: Some(tip),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{land_local_only, LAND_LOCAL_BRANCH};
+    use crate::execution::ExecutionMode;
+    use crate::selectors::{GroupSelector, InclusiveSelector};
+    use crate::test_support::{commit_file, git, init_repo, lock_cwd, DirGuard};
+
+    #[test]
+    fn land_local_only_squashes_each_group_onto_base() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path().to_path_buf();
+        git(&repo, ["checkout", "-b", "stack"].as_slice());
+        commit_file(&repo, "alpha.txt", "a\n", "feat: alpha pr:alpha");
+        commit_file(&repo, "alpha.txt", "a\nb\n", "feat: alpha more");
+        commit_file(&repo, "beta.txt", "b\n", "feat: beta pr:beta");
+        commit_file(&repo, "gamma.txt", "g\n", "feat: gamma pr:gamma");
+        let head = git(&repo, ["rev-parse", "HEAD"].as_slice());
+        let _guard = DirGuard::change_to(&repo);
+
+        let outcome = land_local_only(
+            "main",
+            "ignore",
+            &InclusiveSelector::Group(GroupSelector::LocalPr(2)),
+            ExecutionMode::Apply,
+        )
+        .unwrap();
+
+        assert_eq!(outcome.landed, 2);
+        let subjects = git(
+            &repo,
+            ["log", "--format=%s", &format!("main..{LAND_LOCAL_BRANCH}")].as_slice(),
+        );
+        assert_eq!(
+            subjects.lines().collect::<Vec<_>>(),
+            ["feat: beta", "feat: alpha"]
+        );
+        assert_eq!(
+            git(
+                &repo,
+                ["show", &format!("{LAND_LOCAL_BRANCH}:alpha.txt")].as_slice()
+            ),
+            "a\nb\n"
+        );
+        assert_eq!(git(&repo, ["rev-parse", "HEAD"].as_slice()), head);
+    }
+}
@@ -0,0 +1,211 @@
+//! Record the PR each landed commit shipped in, and look it up with `spr landed <sha>`.
+//!
+//! After `spr land` merges on GitHub, every local commit of the landed groups gets a Git note
+//! under `refs/notes/spr-landed` naming the PR that landed it and the merge commit GitHub created
+//! on the base. The notes stay attached to the original commits after the follow-up restack drops
+//! them, so `spr landed` still answers for SHAs from old logs, reviews, or bug reports. Looking up
+//! the merge commit itself works too.
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::execution::ExecutionMode;
+use crate::git::{git_ro, git_rw};
+
+const LANDED_NOTES_REF: &str = "refs/notes/spr-landed";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LandedData {
+    /// The looked-up commit, fully resolved.
+    pub commit: String,
+    /// The PR the commit landed through.
+    pub pr_number: u64,
+    /// The commit GitHub created on the base, when known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub merge_commit: Option<String>,
+}
+
+fn note_text(pr_number: u64, merge_commit: Option<&str>) -> String {
+    match merge_commit {
+        Some(merge_commit) => format!("landed-pr: {pr_number}\nmerge-commit: {merge_commit}\n"),
+        None => format!("landed-pr: {pr_number}\n"),
+    }
+}
+
+/// The PR number and merge commit recorded in a note.
+fn parse_note(text: &str) -> Option<(u64, Option<String>)> {
+    let mut pr_number = None;
+    let mut merge_commit = None;
+    for line in text.lines() {
+        if let Some(value) = line.strip_prefix("landed-pr:") {
+            pr_number = value.trim().parse().ok();
+        } else if let Some(value) = line.strip_prefix("merge-commit:") {
+            merge_commit = Some(value.trim().to_string()).filter(|value| !value.is_empty());
+        }
+    }
+    Some((pr_number?, merge_commit))
+}
+
+/// Note `commits` as landed through PR `pr_number`, replacing any earlier note.
+///
+/// # Errors
+///
+/// Returns errors from `git notes`.
+pub fn record_landed_commits(
+    commits: &[String],
+    pr_number: u64,
+    merge_commit: Option<&str>,
+    execution_mode: ExecutionMode,
+) -> Result<()> {
+    let text = note_text(pr_number, merge_commit);
+    for commit in commits {
+        git_rw(
+            execution_mode,
+            [
+                "notes",
+                "--ref",
+                LANDED_NOTES_REF,
+                "add",
+                "-f",
+                "-m",
+                text.trim_end(),
+                commit,
+            ]
+            .as_slice(),
+        )?;
+    }
+    Ok(())
+}
+
+/// Best-effort [`record_landed_commits`] after GitHub landing succeeded; failures only warn.
+pub(crate) fn note_landed_commits(
+    commits: &[String],
+    pr_number: u64,
+    execution_mode: ExecutionMode,
+) {
+    let merge_commit = if execution_mode == ExecutionMode::Apply {
+        match crate::github::fetch_merged_pr_merge_commit_oids(&[pr_number]) {
+            Ok(oids) => oids.get(&pr_number).cloned(),
+            Err(err) => {
+                warn!("Could not read the merge commit of PR #{pr_number}: {err:#}");
+                None
+            }
+        }
+    } else {
+        None
+    };
+    if let Err(err) =
+        record_landed_commits(commits, pr_number, merge_commit.as_deref(), execution_mode)
+    {
+        warn!("Landing succeeded, but recording `spr landed` notes failed: {err:#}");
+    }
+}
+
+/// Find the PR that landed `sha`, from its own note or from a note naming it as merge commit.
+///
+/// # Errors
+///
+/// Returns errors when `sha` is not a commit or no landed PR was recorded for it.
+pub fn collect_landed_data(sha: &str) -> Result<LandedData> {
+    let commit = git_ro(["rev-parse", "--verify", &format!("{sha}^{{commit}}")].as_slice())
+        .map_err(|_| anyhow::anyhow!("`{sha}` is not a commit"))?
+        .trim()
+        .to_string();
+    let own_note = git_ro(["notes", "--ref", LANDED_NOTES_REF, "show", &commit].as_slice()).ok();
+    if let Some((pr_number, merge_commit)) = own_note.as_deref().and_then(parse_note) {
+        return Ok(LandedData {
+            commit,
+            pr_number,
+            merge_commit,
+        });
+    }
+    let listed =
+        git_ro(["notes", "--ref", LANDED_NOTES_REF, "list"].as_slice()).unwrap_or_default();
+    for note_blob in listed
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+    {
+        let text = git_ro(["cat-file", "-p", note_blob].as_slice())?;
+        if let Some((pr_number, Some(merge_commit))) = parse_note(&text) {
+            if merge_commit == commit {
+                return Ok(LandedData {
+                    commit,
+                    pr_number,
+                    merge_commit: Some(merge_commit),
+                });
+            }
+        }
+    }
+    bail!("No landed PR recorded for {commit}; only commits landed by `spr land` are noted")
+}
+
+/// Print the PR that landed `sha`.
+///
+/// # Errors
+///
+/// Returns the errors of [`collect_landed_data`].
+pub fn landed_display(sha: &str) -> Result<()> {
+    let data = collect_landed_data(sha)?;
+    match &data.merge_commit {
+        Some(merge_commit) => info!(
+            "{} landed in PR #{} as {}",
+            &data.commit[..data.commit.len().min(8)],
+            data.pr_number,
+            merge_commit
+        ),
+        None => info!(
+            "{} landed in PR #{}",
+            &data.commit[..data.commit.len().min(8)],
+            data.pr_number
+        ),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{collect_landed_data, record_landed_commits, LandedData};
+    use crate::execution::ExecutionMode;
+    use crate::test_support::{commit_file, git, init_repo, lock_cwd, DirGuard};
+
+    #[test]
+    fn landed_commits_resolve_by_their_own_sha_or_the_merge_commit() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path();
+        let alpha = commit_file(repo, "alpha.txt", "a\n", "feat: alpha pr:alpha");
+        let beta = commit_file(repo, "beta.txt", "b\n", "feat: beta pr:beta");
+        let merge = git(repo, ["rev-parse", "HEAD~2"].as_slice())
+            .trim()
+            .to_string();
+        let _guard = DirGuard::change_to(repo);
+
+        record_landed_commits(
+            &[alpha.clone(), beta.clone()],
+            17,
+            Some(&merge),
+            ExecutionMode::Apply,
+        )
+        .unwrap();
+
+        assert_eq!(
+            collect_landed_data(&beta[..10]).unwrap(),
+            LandedData {
+                commit: beta,
+                pr_number: 17,
+                merge_commit: Some(merge.clone()),
+            }
+        );
+        assert_eq!(
+            collect_landed_data("HEAD~2").unwrap(),
+            LandedData {
+                commit: merge.clone(),
+                pr_number: 17,
+                merge_commit: Some(merge),
+            }
+        );
+        let err = collect_landed_data("no-such-ref").unwrap_err();
+        assert_eq!(err.to_string(), "`no-such-ref` is not a commit");
+    }
+}
@@ -0,0 +1,115 @@
+//! Flatten merge commits out of the stack before groups are derived.
+//!
+//! `spr --linearize <command>` rebases `merge-base(base, HEAD)..HEAD` onto its own merge base
+//! with a plain `git rebase`, which drops merge commits and replays every non-merge commit from
+//! both sides in topological order. The command then runs against the now-linear stack.
+
+use anyhow::{bail, Result};
+use tracing::info;
+
+use crate::commands::common::{self, DirtyWorktreeOutcome, NativeRebaseOutcome};
+use crate::config::DirtyWorktreePolicy;
+use crate::execution::ExecutionMode;
+use crate::git::git_ro;
+use crate::parsing::merge_commits_in_range;
+
+impl DirtyWorktreeOutcome for NativeRebaseOutcome {
+    fn keeps_dirty_worktree_restore_deferred(&self) -> bool {
+        false
+    }
+}
+
+/// Rebase the current stack into a linear history when it contains merge commits.
+///
+/// The branch is always backed up under `backup/linearize/...` first. Returns whether the branch
+/// was rewritten.
+///
+/// # Errors
+///
+/// Returns errors when the rebase conflicts (it is aborted, leaving the branch untouched) or
+/// when Git commands fail.
+pub fn linearize_stack(
+    base: &str,
+    execution_mode: ExecutionMode,
+    dirty_worktree_policy: DirtyWorktreePolicy,
+) -> Result<bool> {
+    let merge_base = git_ro(["merge-base", base, "HEAD"].as_slice())?
+        .trim()
+        .to_string();
+    let merges = merge_commits_in_range(&format!("{merge_base}..HEAD"))?;
+    if merges.is_empty() {
+        info!("Stack is already linear; nothing to linearize.");
+        return Ok(false);
+    }
+    let outcome = common::with_dirty_worktree_policy(
+        execution_mode,
+        "spr --linearize",
+        dirty_worktree_policy,
+        |_deferred_dirty_worktree_restore| {
+            let (cur_branch, short) = common::get_current_branch_and_short()?;
+            if cur_branch == "HEAD" {
+                bail!("HEAD is detached; check out the stack branch before running `spr --linearize`.");
+            }
+            let backup =
+                common::create_backup_tag(execution_mode, "linearize", &cur_branch, &short)?;
+            info!(
+                "Linearizing {} merge commit(s) out of {} (backup: {}).",
+                merges.len(),
+                cur_branch,
+                backup
+            );
+            common::run_native_rebase_with_abort(
+                execution_mode,
+                ["rebase", &merge_base].as_slice(),
+                "linearize",
+            )
+        },
+    )?;
+    if outcome == NativeRebaseOutcome::Aborted {
+        bail!(
+            "Linearizing the stack hit conflicts and was aborted; rebase it manually with `git rebase {}` and resolve the conflicts.",
+            merge_base
+        );
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::linearize_stack;
+    use crate::config::DirtyWorktreePolicy;
+    use crate::execution::ExecutionMode;
+    use crate::parsing::derive_local_groups;
+    use crate::test_support::{commit_file, git, init_repo, lock_cwd, DirGuard};
+
+    #[test]
+    fn linearize_drops_merge_commits_so_groups_derive() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path().to_path_buf();
+        git(&repo, ["branch", "base"].as_slice());
+        commit_file(&repo, "a.txt", "a\n", "feat: a pr:alpha");
+        git(&repo, ["checkout", "-q", "-b", "side", "base"].as_slice());
+        commit_file(&repo, "side.txt", "s\n", "side work");
+        git(&repo, ["checkout", "-q", "-"].as_slice());
+        git(
+            &repo,
+            ["merge", "-q", "--no-ff", "-m", "merge side", "side"].as_slice(),
+        );
+        commit_file(&repo, "b.txt", "b\n", "feat: b pr:beta");
+        let _guard = DirGuard::change_to(&repo);
+
+        let err = derive_local_groups("base", "ignore").unwrap_err();
+        assert!(err.to_string().contains("1 merge commit(s)"));
+
+        assert!(linearize_stack("base", ExecutionMode::Apply, DirtyWorktreePolicy::Halt).unwrap());
+        let (_, groups) = derive_local_groups("base", "ignore").unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].commits.len(), 2);
+        assert!(
+            git(&repo, ["tag", "--list", "backup/linearize/*"].as_slice())
+                .contains("backup/linearize/")
+        );
+        assert!(!linearize_stack("base", ExecutionMode::Apply, DirtyWorktreePolicy::Halt).unwrap());
+    }
+}
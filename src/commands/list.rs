@@ -8,17 +8,29 @@
 //! - `CI` + `Review` symbols for open PRs
 //! - `⑃M` for merged PRs
 //! - `??` when no matching PR metadata is available
+//!
+//! Open PRs still waiting for review end their line with how long they have waited since their
+//! last push or ready-for-review event. Groups whose head branch the next `spr update` would push
+//! are marked with the same fast-forward or force classification `update` uses, so the list doubles
+//! as a preview of the update.
+//!
+//! `--since <ref|date>` keeps only groups with a commit newer than the date (by committer date) or
+//! not reachable from the ref. Hidden groups keep their numbers, so the shown `LPR #N` and commit
+//! indices still match the full stack.
 
 use anyhow::Result;
 use serde::Serialize;
-use std::collections::HashMap;
-use tracing::info;
+use std::collections::{HashMap, HashSet};
+use time::OffsetDateTime;
+use tracing::{info, warn};
 
 use crate::branch_names::{
     canonical_branch_conflict_key, find_group_branch_name_collision, group_branch_identities,
     CanonicalBranchConflictKey, GroupBranchIdentity, GroupBranchNameCollision,
 };
+use crate::commands::PushKind;
 use crate::config::{ListOrder, LocalPrBranchSyncPolicy};
+use crate::git::{get_remote_branches_sha, git_ro};
 use crate::github::{
     fetch_pr_ci_review_status, list_open_or_merged_prs_for_heads, PrCiReviewStatus, PrCiState,
     PrInfoWithState, PrReviewDecision, PrState,
@@ -82,6 +94,9 @@ pub struct PrGroupData {
     pub commit_count: usize,
     pub first_subject: String,
     pub remote: RemotePrMetadata,
+    /// What the next `spr update` would do with the head branch; `None` when the remote branches
+    /// could not be read.
+    pub planned_push: Option<PushKind>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
@@ -112,6 +127,19 @@ pub struct CommitListData {
     pub local_pr_branch_drift: Vec<crate::local_pr_branches::LocalPrBranchAction>,
 }
 
+/// An open PR that GitHub retargeted after the merged PR group below it.
+///
+/// When a bottom PR is squash-merged and its head branch deleted, GitHub moves the PR above it
+/// onto the merged PR's base. Until the local stack drops the merged group, that PR's head branch
+/// still carries the merged commits, so its diff against the new base repeats the parent's changes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AutoRetargetedPr {
+    pub local_pr_number: usize,
+    pub pr_number: u64,
+    pub merged_parent_pr_number: u64,
+    pub base_branch: String,
+}
+
 /// Maps remote PR state into the two-character status slot used by `spr list pr`.
 ///
 /// Open PRs show CI and review icons independently, while merged PRs intentionally use the
@@ -192,18 +220,31 @@ struct PrSummaryLine<'a> {
     stable_handle: &'a str,
     short: &'a str,
     pr_number: Option<u64>,
+    draft: bool,
     count: usize,
+    planned_push: Option<PushKind>,
+    review_wait: Option<String>,
 }
 
 fn format_pr_summary_line(line: PrSummaryLine<'_>) -> String {
-    let remote_pr_num = if let Some(pr_number) = line.pr_number {
-        format!(" (#{pr_number})")
+    let remote_pr_num = match (line.pr_number, line.draft) {
+        (Some(pr_number), true) => format!(" (#{pr_number}, draft)"),
+        (Some(pr_number), false) => format!(" (#{pr_number})"),
+        (None, _) => String::new(),
+    };
+    let plural = if line.count == 1 { "commit" } else { "commits" };
+    let planned_push = match line.planned_push {
+        Some(PushKind::FastForward) => " - update pushes",
+        Some(PushKind::Force) => " - update force-pushes",
+        Some(PushKind::Skip) | None => "",
+    };
+    let review_wait = if let Some(wait) = line.review_wait {
+        format!(" - awaiting review {wait}")
     } else {
         String::new()
     };
-    let plural = if line.count == 1 { "commit" } else { "commits" };
     format!(
-        "{}{} LPR #{} / {} - {}{} - {} {}",
+        "{}{} LPR #{} / {} - {}{} - {} {}{}{}",
         line.ci_icon,
         line.rv_icon,
         line.local_pr_num,
@@ -211,10 +252,43 @@ fn format_pr_summary_line(line: PrSummaryLine<'_>) -> String {
         line.short,
         remote_pr_num,
         line.count,
-        plural
+        plural,
+        planned_push,
+        review_wait
+    )
+}
+
+fn is_open_draft(remote: &RemotePrMetadata) -> bool {
+    matches!(
+        &remote.state,
+        RemotePrState::RemoteWithCiReview {
+            state: PrState::Open,
+            ci_review_status,
+            ..
+        } if ci_review_status.is_draft
     )
 }
 
+/// Compact review wait such as `45m`, `7h`, or `3d` for an open PR awaiting review.
+fn review_wait_text(remote: &RemotePrMetadata, now: OffsetDateTime) -> Option<String> {
+    let RemotePrState::RemoteWithCiReview {
+        state: PrState::Open,
+        ci_review_status,
+        ..
+    } = &remote.state
+    else {
+        return None;
+    };
+    let wait = ci_review_status.awaiting_review_for(now)?;
+    Some(if wait.whole_hours() < 1 {
+        format!("{}m", wait.whole_minutes())
+    } else if wait.whole_hours() < 48 {
+        format!("{}h", wait.whole_hours())
+    } else {
+        format!("{}d", wait.whole_days())
+    })
+}
+
 fn format_commit_group_header(
     local_pr_num: usize,
     stable_handle: &str,
@@ -244,6 +318,51 @@ fn derive_groups_and_identities(
     }
 }
 
+/// Which groups have a commit newer than `since`, a commit-ish or a date `git log --since` accepts.
+///
+/// # Errors
+///
+/// Returns errors when `since` is neither a commit nor a date, or Git fails.
+fn groups_changed_since(groups: &[Group], since: &str) -> Result<Vec<bool>> {
+    let commits: Vec<&str> = groups
+        .iter()
+        .flat_map(|group| group.commits.iter().map(String::as_str))
+        .collect();
+    if commits.is_empty() {
+        return Ok(Vec::new());
+    }
+    let since_commit = format!("{since}^{{commit}}");
+    let recent: HashSet<String> = if let Ok(since_sha) =
+        git_ro(["rev-parse", "--verify", "--quiet", &since_commit].as_slice())
+    {
+        let mut args = vec!["rev-list"];
+        args.extend(&commits);
+        args.extend(["--not", since_sha.trim()]);
+        git_ro(&args)?.lines().map(str::to_string).collect()
+    } else {
+        let max_age = git_ro(["rev-parse", &format!("--since={since}")].as_slice())?;
+        let Some(cutoff) = max_age
+            .trim()
+            .strip_prefix("--max-age=")
+            .and_then(|secs| secs.parse::<i64>().ok())
+        else {
+            anyhow::bail!("`{since}` is neither a commit nor a date");
+        };
+        let mut args = vec!["show", "-s", "--format=%H %ct"];
+        args.extend(&commits);
+        git_ro(&args)?
+            .lines()
+            .filter_map(|line| line.split_once(' '))
+            .filter(|(_, time)| time.parse::<i64>().is_ok_and(|time| time >= cutoff))
+            .map(|(sha, _)| sha.to_string())
+            .collect()
+    };
+    Ok(groups
+        .iter()
+        .map(|group| group.commits.iter().any(|sha| recent.contains(sha)))
+        .collect())
+}
+
 fn fetch_remote_pr_metadata(
     branch_identities: &[GroupBranchIdentity],
 ) -> Result<HashMap<CanonicalBranchConflictKey, RemotePrMetadata>> {
@@ -285,10 +404,39 @@ fn build_remote_pr_metadata(
         .collect()
 }
 
+/// Classify each group's push the way `spr update` would, from one `ls-remote` of the heads.
+///
+/// Best-effort: when the remote cannot be read (offline, no remote) every entry is `None`.
+fn planned_pushes(
+    groups: &[Group],
+    branch_identities: &[GroupBranchIdentity],
+) -> Vec<Option<PushKind>> {
+    let heads: Vec<String> = branch_identities
+        .iter()
+        .map(|identity| identity.exact.clone())
+        .collect();
+    let remote_heads = match get_remote_branches_sha(&heads) {
+        Ok(remote_heads) => remote_heads,
+        Err(err) => {
+            warn!("Could not read remote branches to preview pushes: {err:#}");
+            return vec![None; groups.len()];
+        }
+    };
+    groups
+        .iter()
+        .zip(&heads)
+        .map(|(group, head)| {
+            let tip = group.commits.last()?;
+            PushKind::classify(remote_heads.get(head).map(String::as_str), tip).ok()
+        })
+        .collect()
+}
+
 fn build_pr_list_data(
     groups: &[Group],
     branch_identities: &[GroupBranchIdentity],
     remote_by_head: &HashMap<CanonicalBranchConflictKey, RemotePrMetadata>,
+    planned_pushes: &[Option<PushKind>],
     local_pr_branch_drift: Vec<crate::local_pr_branches::LocalPrBranchAction>,
 ) -> PrListData {
     let groups = groups
@@ -309,6 +457,7 @@ fn build_pr_list_data(
                     .unwrap_or(RemotePrMetadata {
                         state: RemotePrState::NoRemote,
                     }),
+                planned_push: planned_pushes.get(group_idx).copied().flatten(),
             }
         })
         .collect();
@@ -376,8 +525,13 @@ pub fn collect_pr_list_data_for_json(
     prefix: &str,
     ignore_tag: &str,
     local_pr_branch_policy: LocalPrBranchSyncPolicy,
+    since: Option<&str>,
 ) -> std::result::Result<PrListData, ReadOnlyQueryError> {
     let (groups, branch_identities) = derive_groups_and_identities(base, prefix, ignore_tag)?;
+    let changed = since
+        .map(|since| groups_changed_since(&groups, since))
+        .transpose()
+        .map_err(ReadOnlyQueryError::Internal)?;
     let remote_by_head =
         fetch_remote_pr_metadata(&branch_identities).map_err(ReadOnlyQueryError::Internal)?;
     let targets = crate::local_pr_branches::targets_from_groups(prefix, &groups)
@@ -385,12 +539,19 @@ pub fn collect_pr_list_data_for_json(
     let local_pr_branch_drift =
         crate::local_pr_branches::plan_local_pr_branch_drift(local_pr_branch_policy, &targets)
             .map_err(ReadOnlyQueryError::Internal)?;
-    Ok(build_pr_list_data(
+    let planned_pushes = planned_pushes(&groups, &branch_identities);
+    let mut data = build_pr_list_data(
         &groups,
         &branch_identities,
         &remote_by_head,
+        &planned_pushes,
         local_pr_branch_drift,
-    ))
+    );
+    if let Some(changed) = changed {
+        data.groups
+            .retain(|group| changed[group.local_pr_number - 1]);
+    }
+    Ok(data)
 }
 
 pub fn collect_pr_list_data(
@@ -399,7 +560,7 @@ pub fn collect_pr_list_data(
     ignore_tag: &str,
     local_pr_branch_policy: LocalPrBranchSyncPolicy,
 ) -> Result<PrListData> {
-    collect_pr_list_data_for_json(base, prefix, ignore_tag, local_pr_branch_policy)
+    collect_pr_list_data_for_json(base, prefix, ignore_tag, local_pr_branch_policy, None)
         .map_err(anyhow::Error::from)
 }
 
@@ -408,8 +569,13 @@ pub fn collect_commit_list_data_for_json(
     prefix: &str,
     ignore_tag: &str,
     local_pr_branch_policy: LocalPrBranchSyncPolicy,
+    since: Option<&str>,
 ) -> std::result::Result<CommitListData, ReadOnlyQueryError> {
     let (groups, branch_identities) = derive_groups_and_identities(base, prefix, ignore_tag)?;
+    let changed = since
+        .map(|since| groups_changed_since(&groups, since))
+        .transpose()
+        .map_err(ReadOnlyQueryError::Internal)?;
     let remote_by_head =
         fetch_remote_pr_metadata(&branch_identities).map_err(ReadOnlyQueryError::Internal)?;
     let targets = crate::local_pr_branches::targets_from_groups(prefix, &groups)
@@ -417,12 +583,17 @@ pub fn collect_commit_list_data_for_json(
     let local_pr_branch_drift =
         crate::local_pr_branches::plan_local_pr_branch_drift(local_pr_branch_policy, &targets)
             .map_err(ReadOnlyQueryError::Internal)?;
-    Ok(build_commit_list_data(
+    let mut data = build_commit_list_data(
         &groups,
         &branch_identities,
         &remote_by_head,
         local_pr_branch_drift,
-    ))
+    );
+    if let Some(changed) = changed {
+        data.groups
+            .retain(|group| changed[group.local_pr_number - 1]);
+    }
+    Ok(data)
 }
 
 pub fn collect_commit_list_data(
@@ -430,8 +601,9 @@ pub fn collect_commit_list_data(
     prefix: &str,
     ignore_tag: &str,
     local_pr_branch_policy: LocalPrBranchSyncPolicy,
+    since: Option<&str>,
 ) -> Result<CommitListData> {
-    collect_commit_list_data_for_json(base, prefix, ignore_tag, local_pr_branch_policy)
+    collect_commit_list_data_for_json(base, prefix, ignore_tag, local_pr_branch_policy, since)
         .map_err(anyhow::Error::from)
 }
 
@@ -443,6 +615,7 @@ fn render_pr_list(data: &PrListData, list_order: ListOrder) -> Vec<String> {
             format!("┏━━{}CI status", crate::format::EM_SPACE),
             format!("┃┏━{}review status", crate::format::EM_SPACE),
         ];
+        let now = OffsetDateTime::now_utc();
         for group_idx in list_order.display_indices(data.groups.len()) {
             let group = &data.groups[group_idx];
             let (ci_icon, rv_icon) = status_icons(&group.remote);
@@ -458,7 +631,10 @@ fn render_pr_list(data: &PrListData, list_order: ListOrder) -> Vec<String> {
                 stable_handle: &group.stable_handle,
                 short: short_sha(&group.first_commit_sha),
                 pr_number,
+                draft: is_open_draft(&group.remote),
                 count: group.commit_count,
+                planned_push: group.planned_push,
+                review_wait: review_wait_text(&group.remote, now),
             }));
             lines.push(format!(
                 "{s}{s}{s}{s}{s}{subject}",
@@ -540,6 +716,106 @@ fn render_local_pr_branch_drift(
         .collect()
 }
 
+fn remote_pr_number_base_state(remote: &RemotePrMetadata) -> Option<(u64, &str, PrState)> {
+    match &remote.state {
+        RemotePrState::NoRemote => None,
+        RemotePrState::RemoteWithoutCiReview {
+            pr_number,
+            base_branch,
+            state,
+            ..
+        }
+        | RemotePrState::RemoteWithCiReview {
+            pr_number,
+            base_branch,
+            state,
+            ..
+        } => Some((*pr_number, base_branch.as_str(), *state)),
+    }
+}
+
+/// Open PRs sitting directly above the merged bottom prefix whose base is no longer the merged
+/// parent's head branch.
+///
+/// Only the bottom prefix is considered: a PR above a still-open group keeps a correct diff even
+/// when GitHub retargets it, and the bottom prefix is what `spr update` drops before republishing.
+pub fn auto_retargeted_prs(groups: &[PrGroupData]) -> Vec<AutoRetargetedPr> {
+    let mut retargeted = Vec::new();
+    for pair in groups.windows(2) {
+        let (parent, child) = (&pair[0], &pair[1]);
+        let Some((parent_number, _, PrState::Merged)) = remote_pr_number_base_state(&parent.remote)
+        else {
+            break;
+        };
+        if let Some((pr_number, base_branch, PrState::Open)) =
+            remote_pr_number_base_state(&child.remote)
+        {
+            if crate::git::normalize_branch_name(base_branch)
+                != crate::git::normalize_branch_name(&parent.head_branch)
+            {
+                retargeted.push(AutoRetargetedPr {
+                    local_pr_number: child.local_pr_number,
+                    pr_number,
+                    merged_parent_pr_number: parent_number,
+                    base_branch: base_branch.to_string(),
+                });
+            }
+        }
+    }
+    retargeted
+}
+
+/// Explain retargeted PRs and how `spr update` repairs them; empty when nothing was retargeted.
+pub fn auto_retarget_warnings(retargeted: &[AutoRetargetedPr]) -> Vec<String> {
+    let mut lines: Vec<String> = retargeted
+        .iter()
+        .map(|pr| {
+            format!(
+                "PR #{} (LPR #{}) was retargeted to {} by GitHub after #{} merged; its diff repeats #{}'s changes",
+                pr.pr_number,
+                pr.local_pr_number,
+                pr.base_branch,
+                pr.merged_parent_pr_number,
+                pr.merged_parent_pr_number
+            )
+        })
+        .collect();
+    if !lines.is_empty() {
+        lines.push(
+            "run `spr update` to drop the merged group(s), restack, and force-push the correct diffs"
+                .to_string(),
+        );
+    }
+    lines
+}
+
+/// Detect retargeted PRs for the current stack without fetching CI or review status.
+///
+/// # Errors
+///
+/// Returns errors from group discovery or the GitHub PR lookup.
+pub fn collect_auto_retargeted_prs(
+    base: &str,
+    prefix: &str,
+    ignore_tag: &str,
+) -> Result<Vec<AutoRetargetedPr>> {
+    let (groups, branch_identities) =
+        derive_groups_and_identities(base, prefix, ignore_tag).map_err(anyhow::Error::from)?;
+    let heads: Vec<String> = branch_identities
+        .iter()
+        .map(|identity| identity.exact.clone())
+        .collect();
+    let remote_by_head = build_remote_pr_metadata(list_open_or_merged_prs_for_heads(&heads)?, None);
+    let data = build_pr_list_data(
+        &groups,
+        &branch_identities,
+        &remote_by_head,
+        &[],
+        Vec::new(),
+    );
+    Ok(auto_retargeted_prs(&data.groups))
+}
+
 /// Print a per-PR summary for the current local stack.
 ///
 /// The local stack order is derived bottom-up from commits, so local PR numbers are based
@@ -552,15 +828,25 @@ pub fn list_prs_display(
     ignore_tag: &str,
     list_order: ListOrder,
     local_pr_branch_policy: LocalPrBranchSyncPolicy,
+    since: Option<&str>,
 ) -> Result<()> {
-    let data = collect_pr_list_data(base, prefix, ignore_tag, local_pr_branch_policy)?;
-    for line in render_pr_list(&data, list_order) {
+    let data =
+        collect_pr_list_data_for_json(base, prefix, ignore_tag, local_pr_branch_policy, since)?;
+    print_pr_list(&data, list_order);
+    Ok(())
+}
+
+/// Print already-collected PR list data in the `spr list pr` format.
+pub fn print_pr_list(data: &PrListData, list_order: ListOrder) {
+    for line in render_pr_list(data, list_order) {
         info!("{line}");
     }
     for line in render_local_pr_branch_drift(&data.local_pr_branch_drift) {
         info!("{line}");
     }
-    Ok(())
+    for line in auto_retarget_warnings(&auto_retargeted_prs(&data.groups)) {
+        warn!("{line}");
+    }
 }
 
 /// Print commits grouped by local PR, keeping commit indices in bottom-up order.
@@ -575,8 +861,9 @@ pub fn list_commits_display(
     ignore_tag: &str,
     list_order: ListOrder,
     local_pr_branch_policy: LocalPrBranchSyncPolicy,
+    since: Option<&str>,
 ) -> Result<()> {
-    let data = collect_commit_list_data(base, prefix, ignore_tag, local_pr_branch_policy)?;
+    let data = collect_commit_list_data(base, prefix, ignore_tag, local_pr_branch_policy, since)?;
     for line in render_commit_list(&data, list_order) {
         info!("{line}");
     }
@@ -590,7 +877,9 @@ pub fn list_commits_display(
 mod tests {
     use super::*;
     use crate::config::ListOrder;
-    use crate::test_support::{init_case_conflicting_stack_repo, lock_cwd, DirGuard};
+    use crate::test_support::{
+        commit_file, git, init_case_conflicting_stack_repo, init_repo, lock_cwd, DirGuard,
+    };
 
     #[test]
     fn status_icons_uses_merged_marker() {
@@ -616,7 +905,13 @@ mod tests {
                 PrState::Open,
                 Some(PrCiReviewStatus {
                     ci_state: PrCiState::Success,
+                    checks_state: PrCiState::Success,
+                    status_contexts: Vec::new(),
                     review_decision: PrReviewDecision::Approved,
+                    is_draft: false,
+                    awaiting_review_since: None,
+                    requested_reviewers: Vec::new(),
+                    head_oid: String::new(),
                 }),
             )),
             ("✓", "✓")
@@ -652,12 +947,123 @@ mod tests {
             stable_handle: "pr:beta",
             short: "abcdef12",
             pr_number: Some(17),
+            draft: false,
             count: 3,
+            planned_push: None,
+            review_wait: None,
         });
 
         assert_eq!(line, "✓✓ LPR #2 / pr:beta - abcdef12 (#17) - 3 commits");
     }
 
+    #[test]
+    fn pr_summary_line_marks_draft_prs() {
+        let line = format_pr_summary_line(PrSummaryLine {
+            ci_icon: "✓",
+            rv_icon: "?",
+            local_pr_num: 1,
+            stable_handle: "pr:alpha",
+            short: "abcdef12",
+            pr_number: Some(16),
+            draft: true,
+            count: 2,
+            planned_push: None,
+            review_wait: None,
+        });
+
+        assert_eq!(
+            line,
+            "✓? LPR #1 / pr:alpha - abcdef12 (#16, draft) - 2 commits"
+        );
+    }
+
+    #[test]
+    fn pr_summary_line_previews_the_planned_push() {
+        let line = |planned_push| {
+            format_pr_summary_line(PrSummaryLine {
+                ci_icon: "✓",
+                rv_icon: "✓",
+                local_pr_num: 2,
+                stable_handle: "pr:beta",
+                short: "abcdef12",
+                pr_number: Some(17),
+                draft: false,
+                count: 1,
+                planned_push,
+                review_wait: None,
+            })
+        };
+
+        assert_eq!(
+            line(Some(PushKind::Force)),
+            "✓✓ LPR #2 / pr:beta - abcdef12 (#17) - 1 commit - update force-pushes"
+        );
+        assert_eq!(
+            line(Some(PushKind::FastForward)),
+            "✓✓ LPR #2 / pr:beta - abcdef12 (#17) - 1 commit - update pushes"
+        );
+        assert_eq!(
+            line(Some(PushKind::Skip)),
+            "✓✓ LPR #2 / pr:beta - abcdef12 (#17) - 1 commit"
+        );
+    }
+
+    #[test]
+    fn pr_summary_line_shows_how_long_an_open_pr_awaits_review() {
+        let now = OffsetDateTime::parse(
+            "2026-03-04T12:00:00Z",
+            &time::format_description::well_known::Rfc3339,
+        )
+        .unwrap();
+        let waiting = |since: &str| {
+            remote_pr_metadata(
+                17,
+                String::new(),
+                "main".to_string(),
+                PrState::Open,
+                Some(PrCiReviewStatus {
+                    ci_state: PrCiState::Success,
+                    checks_state: PrCiState::Success,
+                    status_contexts: Vec::new(),
+                    review_decision: PrReviewDecision::ReviewRequired,
+                    is_draft: false,
+                    awaiting_review_since: Some(since.to_string()),
+                    requested_reviewers: vec!["octocat".to_string()],
+                    head_oid: String::new(),
+                }),
+            )
+        };
+        assert_eq!(
+            review_wait_text(&waiting("2026-03-04T11:15:00Z"), now).as_deref(),
+            Some("45m")
+        );
+        assert_eq!(
+            review_wait_text(&waiting("2026-03-03T05:00:00Z"), now).as_deref(),
+            Some("31h")
+        );
+        assert_eq!(
+            review_wait_text(&waiting("2026-03-01T10:00:00Z"), now).as_deref(),
+            Some("3d")
+        );
+
+        let line = format_pr_summary_line(PrSummaryLine {
+            ci_icon: "✓",
+            rv_icon: "◐",
+            local_pr_num: 2,
+            stable_handle: "pr:beta",
+            short: "abcdef12",
+            pr_number: Some(17),
+            draft: false,
+            count: 1,
+            planned_push: None,
+            review_wait: Some("3d".to_string()),
+        });
+        assert_eq!(
+            line,
+            "✓◐ LPR #2 / pr:beta - abcdef12 (#17) - 1 commit - awaiting review 3d"
+        );
+    }
+
     #[test]
     fn commit_group_header_includes_stable_handle_for_any_display_order() {
         assert_eq!(
@@ -679,7 +1085,10 @@ mod tests {
             stable_handle: "branch:feature/login",
             short: "abcdef12",
             pr_number: None,
+            draft: false,
             count: 1,
+            planned_push: None,
+            review_wait: None,
         });
 
         assert_eq!(
@@ -737,12 +1146,24 @@ mod tests {
                 PrState::Open,
                 Some(PrCiReviewStatus {
                     ci_state: PrCiState::Success,
+                    checks_state: PrCiState::Success,
+                    status_contexts: Vec::new(),
                     review_decision: PrReviewDecision::Approved,
+                    is_draft: false,
+                    awaiting_review_since: None,
+                    requested_reviewers: Vec::new(),
+                    head_oid: String::new(),
                 }),
             ),
         )]);
 
-        let data = build_pr_list_data(&groups, &branch_identities, &remote_by_head, Vec::new());
+        let data = build_pr_list_data(
+            &groups,
+            &branch_identities,
+            &remote_by_head,
+            &[],
+            Vec::new(),
+        );
         assert_eq!(data.groups[0].local_pr_number, 1);
         assert_eq!(data.groups[0].stable_handle, "pr:alpha");
         assert_eq!(data.groups[1].local_pr_number, 2);
@@ -769,7 +1190,13 @@ mod tests {
             GroupBranchIdentity::new("dank-spr/beta".to_string()),
         ];
 
-        let data = build_pr_list_data(&groups, &branch_identities, &HashMap::new(), Vec::new());
+        let data = build_pr_list_data(
+            &groups,
+            &branch_identities,
+            &HashMap::new(),
+            &[],
+            Vec::new(),
+        );
 
         assert_eq!(data.groups[0].stable_handle, "branch:feature/login");
         assert_eq!(data.groups[0].head_branch, "feature/login");
@@ -822,6 +1249,59 @@ mod tests {
         );
     }
 
+    #[test]
+    fn auto_retargeted_prs_flags_open_prs_above_the_merged_bottom_prefix() {
+        let pr = |number: u64, head: &str, base: &str, state: PrState| PrGroupData {
+            local_pr_number: number as usize - 10,
+            stable_handle: format!("pr:{head}"),
+            head_branch: format!("dank-spr/{head}"),
+            first_commit_sha: String::new(),
+            commit_count: 1,
+            first_subject: String::new(),
+            planned_push: None,
+            remote: remote_pr_metadata(
+                number,
+                format!("https://github.com/o/r/pull/{number}"),
+                base.to_string(),
+                state,
+                None,
+            ),
+        };
+        let groups = vec![
+            pr(11, "alpha", "main", PrState::Merged),
+            pr(12, "beta", "main", PrState::Open),
+            pr(13, "gamma", "dank-spr/beta", PrState::Open),
+        ];
+
+        let retargeted = auto_retargeted_prs(&groups);
+        assert_eq!(
+            retargeted,
+            vec![AutoRetargetedPr {
+                local_pr_number: 2,
+                pr_number: 12,
+                merged_parent_pr_number: 11,
+                base_branch: "main".to_string(),
+            }]
+        );
+        assert_eq!(
+            auto_retarget_warnings(&retargeted)[0],
+            "PR #12 (LPR #2) was retargeted to main by GitHub after #11 merged; its diff repeats #11's changes"
+        );
+
+        let not_yet_retargeted = vec![
+            pr(11, "alpha", "main", PrState::Merged),
+            pr(12, "beta", "dank-spr/alpha", PrState::Open),
+        ];
+        assert!(auto_retargeted_prs(&not_yet_retargeted).is_empty());
+        let open_bottom = vec![
+            pr(11, "alpha", "main", PrState::Open),
+            pr(12, "beta", "main", PrState::Merged),
+            pr(13, "gamma", "main", PrState::Open),
+        ];
+        assert!(auto_retargeted_prs(&open_bottom).is_empty());
+        assert!(auto_retarget_warnings(&[]).is_empty());
+    }
+
     #[test]
     fn build_commit_list_data_uses_canonical_group_and_commit_order() {
         let groups = vec![
@@ -875,6 +1355,7 @@ mod tests {
                     first_commit_sha: "aaaaaaaa1".to_string(),
                     commit_count: 1,
                     first_subject: "feat: alpha".to_string(),
+                    planned_push: None,
                     remote: RemotePrMetadata {
                         state: RemotePrState::NoRemote,
                     },
@@ -886,6 +1367,7 @@ mod tests {
                     first_commit_sha: "bbbbbbbb1".to_string(),
                     commit_count: 1,
                     first_subject: "feat: beta".to_string(),
+                    planned_push: None,
                     remote: RemotePrMetadata {
                         state: RemotePrState::NoRemote,
                     },
@@ -997,6 +1479,7 @@ mod tests {
             "dank-spr/",
             "ignore",
             LocalPrBranchSyncPolicy::Off,
+            None,
         )
         .expect_err("collision");
 
@@ -1008,4 +1491,30 @@ mod tests {
             other => panic!("unexpected error: {:?}", other),
         }
     }
+
+    #[test]
+    fn groups_changed_since_accepts_a_ref_or_a_date() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path();
+        git(repo, ["checkout", "-b", "stack"].as_slice());
+        commit_file(repo, "alpha.txt", "a\n", "feat: alpha pr:alpha");
+        git(repo, ["tag", "reviewed"].as_slice());
+        commit_file(repo, "beta.txt", "b\n", "feat: beta pr:beta");
+        let _guard = DirGuard::change_to(repo);
+        let (_merge_base, groups) = derive_local_groups("main", "ignore").unwrap();
+
+        assert_eq!(
+            groups_changed_since(&groups, "reviewed").unwrap(),
+            vec![false, true]
+        );
+        assert_eq!(
+            groups_changed_since(&groups, "1 day ago").unwrap(),
+            vec![true, true]
+        );
+        assert_eq!(
+            groups_changed_since(&groups, "2099-01-01").unwrap(),
+            vec![false, false]
+        );
+    }
 }
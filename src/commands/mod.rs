@@ -1,42 +1,120 @@
 pub mod absorb;
+pub mod absorb_staged;
 pub mod adopt_prefix;
+pub mod amend;
+pub mod apply_remote_directives;
+pub mod automerge;
+pub mod backup;
+pub mod checkout;
+pub mod cherry;
 pub mod cleanup;
 pub mod common;
+pub mod diff;
 pub mod drop_merged_prefix;
+pub mod files;
 pub mod fix_pr;
+pub mod fixup;
+pub mod fold;
+pub mod freeze;
+pub mod import_pr;
+pub mod init;
 pub mod land;
+pub mod land_local;
+pub mod landed;
+pub mod linearize;
 pub mod list;
 pub mod r#move;
+pub mod open;
 pub mod owning_stack;
+pub mod parse;
+pub mod pr_stage;
 pub mod prep;
+pub mod pull;
+pub mod range_diff;
 pub mod relink_prs;
+pub mod rename_prefix;
 pub mod resolve_stack;
 pub mod restack;
+pub mod review_ping;
+pub mod reword;
 pub mod rewrite_resume;
+pub mod rollback_pr;
+pub mod split;
+pub mod squash_range;
+pub mod stack_labels;
+pub mod stats;
+pub mod status;
+pub mod status_watch;
+pub mod track_issue;
 pub mod update;
+pub mod verify;
 
 pub use absorb::{
     absorb_branch_tails, query_absorb_changed_branches, AbsorbOptions, CopiedLaterStackCommitPolicy,
 };
+pub use absorb_staged::absorb_staged;
 pub use adopt_prefix::{adopt_prefix, preview_adopt_prefix};
-pub use cleanup::{cleanup_remote_branches, print_cleanup_summary};
+pub use amend::amend_group;
+pub use apply_remote_directives::apply_remote_directives;
+pub use automerge::{enable_automerge, MergeMethod};
+pub use backup::{backup_list_display, collect_backups_data, prune_backups, BackupsData};
+pub use checkout::checkout_group;
+pub use cherry::cherry_group;
+pub use cleanup::{cleanup_remote_branches, print_cleanup_summary, OrphanedPrScope};
+pub use diff::{collect_diff_data, diff_display, DiffData};
 pub use drop_merged_prefix::drop_merged_prefix;
-pub use fix_pr::fix_pr_tail;
-pub use land::{land_flatten_until, land_per_pr_until};
+pub use files::{collect_files_data, list_files_display, FilesData};
+pub use fix_pr::{fix_pr_tail, resolve_fix_pr_before};
+pub use fixup::fixup_staged;
+pub use fold::fold_groups;
+pub use freeze::{ensure_not_frozen, freeze_stack, thaw_stack};
+pub use import_pr::import_pr;
+pub use init::init_config;
+pub use land::{confirm_landing, land_flatten_until, land_per_pr_until};
+pub use land_local::land_local_only;
+pub use landed::{collect_landed_data, landed_display, LandedData};
+pub use linearize::linearize_stack;
 #[allow(unused_imports)]
 pub use list::{
-    collect_commit_list_data, collect_commit_list_data_for_json, collect_pr_list_data,
-    collect_pr_list_data_for_json, list_commits_display, list_prs_display, CommitEntryData,
-    CommitGroupData, CommitListData, PrGroupData, PrListData, ReadOnlyQueryError, RemotePrMetadata,
-    RemotePrState,
+    auto_retarget_warnings, collect_auto_retargeted_prs, collect_commit_list_data,
+    collect_commit_list_data_for_json, collect_pr_list_data, collect_pr_list_data_for_json,
+    list_commits_display, list_prs_display, print_pr_list, CommitEntryData, CommitGroupData,
+    CommitListData, PrGroupData, PrListData, ReadOnlyQueryError, RemotePrMetadata, RemotePrState,
 };
+pub use open::open_prs;
+pub use parse::{collect_parse_data, parse_display, ParseData};
+pub use pr_stage::{print_pr_stage_summary, set_pr_stage};
 pub use prep::{prep_squash, print_prep_summary, PrepExecutionOptions};
-pub use r#move::{move_groups_after, MoveExecutionOptions};
+pub use pull::{pull_stack, pushed_ahead_warnings};
+pub use r#move::{
+    move_excepted_groups_to_top, move_groups_after, MoveDestination, MoveExecutionOptions,
+};
+pub use range_diff::{collect_range_diff_data, range_diff_display, RangeDiffData};
 pub use relink_prs::{print_relink_prs_summary, relink_prs};
+pub use rename_prefix::rename_prefix;
 pub use resolve_stack::{looks_like_pr_url, resolve_stack, ResolveStackOutput};
-pub use restack::{preview_restack_after, restack_after, restack_after_count};
+pub use restack::{
+    check_restack_after, choose_restack_after_interactively, preview_restack_after, restack_after,
+    restack_after_count, restack_onto_base,
+};
+pub use review_ping::ping_stale_reviews_if_configured;
+pub use reword::{describe_group, edit_group_message, retitle_group, sync_pr_title};
 pub use rewrite_resume::{
-    resume_context, resume_rewrite, RewriteCommandKind, RewriteCommandOutcome,
-    RewriteDestinationKind, RewriteSuspendedState,
+    abort_rewrite, pending_resume_path, resume_context, resume_rewrite, RewriteCommandKind,
+    RewriteCommandOutcome, RewriteDestinationKind, RewriteSuspendedState,
+};
+pub use rollback_pr::rollback_pr;
+pub use split::split_group;
+pub use squash_range::squash_commit_range;
+pub use stack_labels::{
+    assign_stack, attach_stack_prs_if_configured, label_stack, set_configured_attachments,
+    set_stack_milestone,
+};
+pub use stats::{collect_stats_data, print_stats, StatsData};
+pub use status::{collect_status_data_for_json, status_display, StatusData};
+pub use status_watch::watch_status;
+pub use track_issue::{
+    print_track_issue_summary, refresh_tracking_issue_if_configured, track_issue,
 };
-pub use update::{build_from_groups, build_from_groups_with_summary};
+pub use update::{build_from_groups, build_from_groups_with_summary, PushKind};
+pub use verify::{collect_verify_data, report_verify, resolve_verify_pr, VerifyData};
@@ -16,9 +16,19 @@ use crate::git::git_rev_parse;
 use crate::github::get_open_pr_automerge_for_head;
 use crate::parsing::derive_local_groups_with_ignored;
 use crate::selectors::{
-    resolve_after_count, resolve_group_range, AfterSelector, GroupRangeSelector,
+    resolve_after_count, resolve_group_ordinal, resolve_group_range, resolve_inclusive_count,
+    AfterSelector, GroupRangeSelector, GroupSelector, InclusiveSelector,
 };
 
+/// Where `spr move` places the selected group or range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoveDestination {
+    /// `--after <C>`: directly above the target position.
+    After(AfterSelector),
+    /// `--before <C>`: directly below the target group.
+    Before(GroupSelector),
+}
+
 /// Execution controls for `spr move`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct MoveExecutionOptions {
@@ -63,13 +73,33 @@ fn build_move_operations(
     operations
 }
 
-fn resolve_move_targets(
+pub(crate) fn resolve_move_targets(
     groups: &[crate::parsing::Group],
     range: &GroupRangeSelector,
-    after: &AfterSelector,
+    destination: &MoveDestination,
 ) -> Result<(usize, usize, usize)> {
     let (a, b) = resolve_group_range(groups, range)?;
-    let c = resolve_after_count(groups, after)?;
+    let c = match destination {
+        MoveDestination::After(after) => resolve_after_count(groups, after)?,
+        MoveDestination::Before(before) => {
+            let target = resolve_group_ordinal(groups, before)?;
+            if target >= a && target <= b {
+                return Err(anyhow!(
+                    "--before target {} must not be within [{}..{}]",
+                    target,
+                    a,
+                    b
+                ));
+            }
+            // Directly below the range's upper neighbour is where the range already sits;
+            // express that as "after A-1" so it is reported as unchanged instead of overlapping.
+            if target == b + 1 {
+                a - 1
+            } else {
+                target - 1
+            }
+        }
+    };
     Ok((a, b, c))
 }
 
@@ -118,7 +148,8 @@ fn enforce_bottom_pr_automerge_guard(
     }
 }
 
-/// Move a group (or group range) to come after a target group index.
+/// Move a group (or group range) to come after a target group index, or directly
+/// below a target group with [`MoveDestination::Before`].
 ///
 /// Ignore blocks (`pr:ignore` and its configured alias) remain attached to the
 /// group that precedes them, so local-only commits move with their owning group.
@@ -127,14 +158,14 @@ fn enforce_bottom_pr_automerge_guard(
 ///
 /// # Errors
 ///
-/// Returns errors for invalid ranges, invalid `--after` positions, or when Git
+/// Returns errors for invalid ranges, invalid `--after`/`--before` positions, or when Git
 /// operations (worktree creation, cherry-picks, reset) fail.
 pub fn move_groups_after(
     base: &str,
     prefix: &str,
     ignore_tag: &str,
     range: &GroupRangeSelector,
-    after: &AfterSelector,
+    destination: &MoveDestination,
     options: MoveExecutionOptions,
 ) -> Result<RewriteCommandOutcome> {
     // Discover groups from local commits bottom→top
@@ -146,7 +177,7 @@ pub fn move_groups_after(
     }
     group_branch_identities(&groups, prefix)?;
 
-    let (a, b, c) = resolve_move_targets(&groups, range, after)?;
+    let (a, b, c) = resolve_move_targets(&groups, range, destination)?;
     if a == 0 || b == 0 || a > n || b > n {
         return Err(anyhow!(
             "Range out of bounds: {}..{} with N={} groups",
@@ -211,6 +242,32 @@ pub fn move_groups_after(
         return Ok(RewriteCommandOutcome::Completed);
     }
 
+    rewrite_in_order(
+        base,
+        prefix,
+        ignore_tag,
+        &merge_base,
+        &leading_ignored,
+        &groups,
+        &new_order,
+        options,
+        "No GitHub changes were made. Run `spr update` after inspecting the rewritten stack.",
+    )
+}
+
+/// Replay the stack onto `merge_base` with groups in `new_order` (1-based local ordinals).
+#[allow(clippy::too_many_arguments)]
+fn rewrite_in_order(
+    base: &str,
+    prefix: &str,
+    ignore_tag: &str,
+    merge_base: &str,
+    leading_ignored: &[String],
+    groups: &[crate::parsing::Group],
+    new_order: &[usize],
+    options: MoveExecutionOptions,
+    post_success_hint: &str,
+) -> Result<RewriteCommandOutcome> {
     common::with_dirty_worktree_policy(
         options.execution_mode,
         "spr move",
@@ -237,8 +294,8 @@ pub fn move_groups_after(
             };
 
             let (tmp_path, tmp_branch) =
-                common::create_temp_worktree(options.execution_mode, "move", &merge_base, &short)?;
-            let operations = build_move_operations(&leading_ignored, &groups, &new_order);
+                common::create_temp_worktree(options.execution_mode, "move", merge_base, &short)?;
+            let operations = build_move_operations(leading_ignored, groups, new_order);
             rewrite_resume::run_rewrite_session(
                 options.execution_mode,
                 RewriteSession {
@@ -254,31 +311,111 @@ pub fn move_groups_after(
                     backup_tag,
                     operations,
                     deferred_dirty_worktree_restore,
-                    post_success_hint: Some(
-                        "No GitHub changes were made. Run `spr update` after inspecting the rewritten stack."
-                            .to_string(),
-                    ),
-                    metadata_refresh_context: Some(
-                        crate::stack_metadata::RefreshMetadataContext {
-                            base: base.to_string(),
-                            prefix: prefix.to_string(),
-                            ignore_tag: ignore_tag.to_string(),
-                        },
-                    ),
+                    post_success_hint: Some(post_success_hint.to_string()),
+                    metadata_refresh_context: Some(crate::stack_metadata::RefreshMetadataContext {
+                        base: base.to_string(),
+                        prefix: prefix.to_string(),
+                        ignore_tag: ignore_tag.to_string(),
+                    }),
                 },
             )
         },
     )
 }
 
+/// Order for `spr land --except`: the groups to land, then the rest of the stack, then `excepted`.
+fn land_except_order(take_n: usize, total: usize, excepted: &[usize]) -> Vec<usize> {
+    (1..=take_n)
+        .filter(|ordinal| !excepted.contains(ordinal))
+        .chain(take_n + 1..=total)
+        .chain(excepted.iter().copied())
+        .collect()
+}
+
+/// Move the `excepted` groups from the bottom `take_n` to the top of the stack for
+/// `spr land --except`, so the remaining bottom groups can land without them.
+///
+/// Returns how many bottom groups remain to land and the rewrite outcome.
+///
+/// # Errors
+///
+/// Returns an error when a selector does not resolve to a group within the bottom `take_n`,
+/// when every group in the range is excepted, or when the rewrite fails.
+pub fn move_excepted_groups_to_top(
+    base: &str,
+    prefix: &str,
+    ignore_tag: &str,
+    until: &InclusiveSelector,
+    except: &[GroupSelector],
+    options: MoveExecutionOptions,
+) -> Result<(usize, RewriteCommandOutcome)> {
+    let (merge_base, leading_ignored, groups) = derive_local_groups_with_ignored(base, ignore_tag)?;
+    group_branch_identities(&groups, prefix)?;
+    let take_n = resolve_inclusive_count(&groups, until)?;
+    let mut excepted: Vec<usize> = Vec::new();
+    for selector in except {
+        let ordinal = resolve_group_ordinal(&groups, selector)?;
+        if ordinal > take_n {
+            return Err(anyhow!(
+                "--except {} is LPR #{}, above the landed range 1..={}",
+                selector,
+                ordinal,
+                take_n
+            ));
+        }
+        if !excepted.contains(&ordinal) {
+            excepted.push(ordinal);
+        }
+    }
+    excepted.sort_unstable();
+    if excepted.len() == take_n {
+        return Err(anyhow!(
+            "--except skips every PR in the landed range; nothing to land"
+        ));
+    }
+    let landable = take_n - excepted.len();
+    let new_order = land_except_order(take_n, groups.len(), &excepted);
+    if new_order == (1..=groups.len()).collect::<Vec<_>>() {
+        return Ok((landable, RewriteCommandOutcome::Completed));
+    }
+    enforce_bottom_pr_automerge_guard(prefix, &groups, &new_order)?;
+    let skipped = excepted
+        .iter()
+        .map(|ordinal| groups[*ordinal - 1].selector_text())
+        .collect::<Vec<_>>()
+        .join(", ");
+    if options.execution_mode == ExecutionMode::DryRun {
+        info!(
+            "DRY-RUN: would move {} to the top of the stack and land the bottom {} PR(s)",
+            skipped, landable
+        );
+        return Ok((landable, RewriteCommandOutcome::Completed));
+    }
+    info!("Moving {} to the top of the stack before landing", skipped);
+    let outcome = rewrite_in_order(
+        base,
+        prefix,
+        ignore_tag,
+        &merge_base,
+        &leading_ignored,
+        &groups,
+        &new_order,
+        options,
+        "Nothing was landed. Run `spr update`, then rerun `spr land` without the already-moved groups in --except.",
+    )?;
+    Ok((landable, outcome))
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
-        changes_stack_bottom, enforce_bottom_pr_automerge_guard, resolve_move_targets,
-        should_block_for_bottom_pr_automerge,
+        changes_stack_bottom, enforce_bottom_pr_automerge_guard, land_except_order,
+        resolve_move_targets, should_block_for_bottom_pr_automerge,
     };
     use crate::commands::rewrite_resume::{resume_rewrite, RewriteResumeState};
-    use crate::commands::{move_groups_after, MoveExecutionOptions, RewriteCommandOutcome};
+    use crate::commands::{
+        move_groups_after, MoveDestination, MoveExecutionOptions, RewriteCommandOutcome,
+    };
     use crate::config::DirtyWorktreePolicy;
     use crate::execution::ExecutionMode;
     use crate::parsing::Group;
@@ -339,17 +476,65 @@ mod tests {
         )));
 
         assert_eq!(
-            resolve_move_targets(&groups, &range, &after).unwrap(),
+            resolve_move_targets(&groups, &range, &MoveDestination::After(after)).unwrap(),
             (2, 3, 1)
         );
     }
 
+    #[test]
+    fn move_before_resolves_to_position_below_target() {
+        let groups = groups(&["alpha", "beta", "gamma", "delta"]);
+        let label =
+            |tag: &str| GroupSelector::Explicit(ExplicitGroupSelector::PrLabel(tag.to_string()));
+        let range = GroupRangeSelector::Inclusive {
+            start: label("gamma"),
+            end: label("delta"),
+        };
+
+        assert_eq!(
+            resolve_move_targets(&groups, &range, &MoveDestination::Before(label("alpha")))
+                .unwrap(),
+            (3, 4, 0)
+        );
+        assert_eq!(
+            resolve_move_targets(
+                &groups,
+                &GroupRangeSelector::Single(label("alpha")),
+                &MoveDestination::Before(label("delta")),
+            )
+            .unwrap(),
+            (1, 1, 3)
+        );
+        assert_eq!(
+            resolve_move_targets(
+                &groups,
+                &GroupRangeSelector::Inclusive {
+                    start: label("alpha"),
+                    end: label("beta"),
+                },
+                &MoveDestination::Before(label("gamma")),
+            )
+            .unwrap(),
+            (1, 2, 0)
+        );
+        let err = resolve_move_targets(&groups, &range, &MoveDestination::Before(label("delta")))
+            .unwrap_err();
+        assert!(err.to_string().contains("--before target 4"), "{err}");
+    }
+
     #[test]
     fn changes_stack_bottom_detects_bottom_replacement() {
         assert!(!changes_stack_bottom(&[1, 3, 2]));
         assert!(changes_stack_bottom(&[2, 1, 3]));
     }
 
+    #[test]
+    fn land_except_order_moves_excepted_groups_to_the_top() {
+        assert_eq!(land_except_order(3, 5, &[2]), vec![1, 3, 4, 5, 2]);
+        assert_eq!(land_except_order(4, 4, &[1, 3]), vec![2, 4, 1, 3]);
+        assert_eq!(land_except_order(2, 2, &[2]), vec![1, 2]);
+    }
+
     #[test]
     fn bottom_pr_automerge_only_blocks_when_bottom_would_change() {
         assert!(should_block_for_bottom_pr_automerge(true, &[2, 1, 3]));
@@ -425,7 +610,7 @@ mod tests {
             "dank-spr/",
             "ignore",
             &GroupRangeSelector::Single(GroupSelector::LocalPr(3)),
-            &AfterSelector::Group(GroupSelector::LocalPr(1)),
+            &MoveDestination::After(AfterSelector::Group(GroupSelector::LocalPr(1))),
             MoveExecutionOptions {
                 safe: false,
                 execution_mode: ExecutionMode::Apply,
@@ -0,0 +1,112 @@
+//! Open stack PRs in the browser with `spr open <n>` or `spr open --all`.
+//!
+//! Each selected group's open PR is found by its concrete branch name, like `spr ready`, and is
+//! opened with `gh pr view --web`, which honors `BROWSER` and falls back to the platform opener.
+
+use anyhow::{bail, Result};
+use tracing::{info, warn};
+
+use crate::branch_names::group_branch_identities;
+use crate::execution::ExecutionMode;
+use crate::git::gh_rw;
+use crate::github::{list_open_prs_for_heads, PrInfo};
+use crate::parsing::derive_local_groups;
+use crate::selectors::{resolve_group_index, GroupSelector};
+
+/// Pair each selected head with its open PR number, or `None` when it has no open PR.
+fn match_open_prs(heads: &[String], prs: &[PrInfo]) -> Vec<Option<u64>> {
+    heads
+        .iter()
+        .map(|head| prs.iter().find(|pr| &pr.head == head).map(|pr| pr.number))
+        .collect()
+}
+
+/// Open the PR of `target` (or of every group when `None`) in the browser.
+///
+/// Returns the PR numbers opened, bottom first.
+///
+/// # Errors
+///
+/// Returns errors when `target` does not resolve, no selected group has an open PR, or `gh`
+/// fails to open one.
+pub fn open_prs(
+    base: &str,
+    prefix: &str,
+    ignore_tag: &str,
+    target: Option<&GroupSelector>,
+    execution_mode: ExecutionMode,
+) -> Result<Vec<u64>> {
+    let (_merge_base, groups) = derive_local_groups(base, ignore_tag)?;
+    if groups.is_empty() {
+        bail!("No local PR groups found; nothing to open.");
+    }
+    let heads: Vec<String> = group_branch_identities(&groups, prefix)?
+        .into_iter()
+        .map(|identity| identity.exact)
+        .collect();
+    let selected: Vec<usize> = match target {
+        Some(selector) => vec![resolve_group_index(&groups, selector)?],
+        None => (0..groups.len()).collect(),
+    };
+    let selected_heads: Vec<String> = selected.iter().map(|&idx| heads[idx].clone()).collect();
+    let prs = list_open_prs_for_heads(&selected_heads)?;
+    let mut opened = Vec::new();
+    for (&idx, number) in selected.iter().zip(match_open_prs(&selected_heads, &prs)) {
+        let Some(number) = number else {
+            warn!(
+                "LPR #{} {} has no open PR; run `spr update` to create it",
+                idx + 1,
+                groups[idx].selector_text()
+            );
+            continue;
+        };
+        gh_rw(
+            execution_mode,
+            ["pr", "view", &number.to_string(), "--web"].as_slice(),
+        )?;
+        info!(
+            "{} PR #{} for LPR #{} {}",
+            if execution_mode == ExecutionMode::DryRun {
+                "DRY-RUN: would open"
+            } else {
+                "Opened"
+            },
+            number,
+            idx + 1,
+            groups[idx].selector_text()
+        );
+        opened.push(number);
+    }
+    if opened.is_empty() {
+        bail!("No open PRs for the selected groups; run `spr update` first.");
+    }
+    Ok(opened)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::match_open_prs;
+    use crate::github::PrInfo;
+
+    #[test]
+    fn match_open_prs_keeps_selection_order_and_reports_missing() {
+        let heads = vec![
+            "dank-spr/alpha".to_string(),
+            "dank-spr/beta".to_string(),
+            "dank-spr/gamma".to_string(),
+        ];
+        let prs = vec![
+            PrInfo {
+                number: 12,
+                head: "dank-spr/gamma".to_string(),
+                base: "dank-spr/beta".to_string(),
+            },
+            PrInfo {
+                number: 10,
+                head: "dank-spr/alpha".to_string(),
+                base: "main".to_string(),
+            },
+        ];
+        assert_eq!(match_open_prs(&heads, &prs), vec![Some(10), None, Some(12)]);
+    }
+}
@@ -0,0 +1,207 @@
+//! Print how the current range parses into PR groups with `spr parse`.
+//!
+//! `spr parse` is read-only and never consults GitHub. It runs the same parser every other
+//! command uses (group markers, ignore blocks, path scope, `--first-parent`), so scripts, hooks,
+//! and CI jobs can reuse spr's grouping with `spr parse --json` instead of reimplementing it.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashMap;
+use tracing::info;
+
+use crate::branch_names::group_branch_identities;
+use crate::git::git_ro;
+use crate::parsing::{derive_local_groups_with_leading_commits, stack_from};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ParsedCommitData {
+    pub sha: String,
+    pub subject: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ParsedGroupData {
+    pub local_pr_number: usize,
+    pub stable_handle: String,
+    /// The marker without its `pr:` or `branch:` kind.
+    pub tag: String,
+    pub head_branch: String,
+    /// The group's commits, oldest→newest.
+    pub commits: Vec<ParsedCommitData>,
+    /// The ignore block that follows the group, kept out of every PR.
+    pub ignored_after: Vec<ParsedCommitData>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ParseData {
+    pub merge_base: String,
+    /// Commits before the first group marker that are in no group and no ignore block.
+    pub leading_ungrouped: Vec<ParsedCommitData>,
+    /// An ignore block before the first group marker.
+    pub leading_ignored: Vec<ParsedCommitData>,
+    pub groups: Vec<ParsedGroupData>,
+}
+
+fn commit_subjects(range: &str) -> Result<HashMap<String, String>> {
+    Ok(git_ro(["log", "--format=%H%x00%s", range].as_slice())?
+        .lines()
+        .filter_map(|line| {
+            let (sha, subject) = line.split_once('\0')?;
+            Some((sha.to_string(), subject.to_string()))
+        })
+        .collect())
+}
+
+/// Parse `merge-base(base, tip)..tip` into groups and ignore blocks.
+///
+/// # Errors
+///
+/// Returns errors from git commands, group parsing, or branch name collisions.
+pub fn collect_parse_data(base: &str, prefix: &str, ignore_tag: &str) -> Result<ParseData> {
+    let (merge_base, parsed) = derive_local_groups_with_leading_commits(base, ignore_tag)?;
+    let subjects = commit_subjects(&format!("{merge_base}..{}", stack_from()))?;
+    let commits = |shas: &[String]| -> Vec<ParsedCommitData> {
+        shas.iter()
+            .map(|sha| ParsedCommitData {
+                sha: sha.clone(),
+                subject: subjects.get(sha).cloned().unwrap_or_default(),
+            })
+            .collect()
+    };
+    let identities = group_branch_identities(&parsed.groups, prefix)?;
+    let groups = parsed
+        .groups
+        .iter()
+        .zip(identities)
+        .enumerate()
+        .map(|(idx, (group, identity))| ParsedGroupData {
+            local_pr_number: idx + 1,
+            stable_handle: group.selector_text(),
+            tag: group.bare_selector_text().to_string(),
+            head_branch: identity.exact,
+            commits: group
+                .commits
+                .iter()
+                .zip(&group.subjects)
+                .map(|(sha, subject)| ParsedCommitData {
+                    sha: sha.clone(),
+                    subject: subject.clone(),
+                })
+                .collect(),
+            ignored_after: commits(&group.ignored_after),
+        })
+        .collect();
+    Ok(ParseData {
+        merge_base,
+        leading_ungrouped: commits(&parsed.leading_ungrouped),
+        leading_ignored: commits(&parsed.leading_ignored),
+        groups,
+    })
+}
+
+fn short(sha: &str) -> &str {
+    &sha[..sha.len().min(8)]
+}
+
+fn render_parse(data: &ParseData) -> Vec<String> {
+    let mut lines = vec![format!("Merge base {}", short(&data.merge_base))];
+    for commit in &data.leading_ungrouped {
+        lines.push(format!(
+            "  ungrouped  {}  {}",
+            short(&commit.sha),
+            commit.subject
+        ));
+    }
+    for commit in &data.leading_ignored {
+        lines.push(format!(
+            "  ignored  {}  {}",
+            short(&commit.sha),
+            commit.subject
+        ));
+    }
+    if data.groups.is_empty() {
+        lines.push("No groups discovered.".to_string());
+    }
+    for group in &data.groups {
+        lines.push(format!(
+            "LPR #{} {} -> {} ({} commit(s))",
+            group.local_pr_number,
+            group.stable_handle,
+            group.head_branch,
+            group.commits.len()
+        ));
+        for commit in &group.commits {
+            lines.push(format!("  {}  {}", short(&commit.sha), commit.subject));
+        }
+        for commit in &group.ignored_after {
+            lines.push(format!(
+                "  ignored  {}  {}",
+                short(&commit.sha),
+                commit.subject
+            ));
+        }
+    }
+    lines
+}
+
+/// Print the parsed groups and ignore blocks, bottom→top.
+pub fn parse_display(base: &str, prefix: &str, ignore_tag: &str) -> Result<()> {
+    for line in render_parse(&collect_parse_data(base, prefix, ignore_tag)?) {
+        info!("{line}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{collect_parse_data, render_parse};
+    use crate::test_support::{commit_file, git, init_repo, lock_cwd, DirGuard};
+
+    #[test]
+    fn parse_reports_groups_and_ignore_blocks() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path().to_path_buf();
+        git(&repo, ["checkout", "-b", "stack"].as_slice());
+        let stray = commit_file(&repo, "stray.txt", "s\n", "chore: stray");
+        let alpha = commit_file(&repo, "alpha.txt", "a\n", "feat: alpha pr:alpha");
+        let local = commit_file(&repo, "local.txt", "l\n", "wip: local pr:ignore");
+        let beta = commit_file(&repo, "beta.txt", "b\n", "feat: beta pr:beta");
+        let _guard = DirGuard::change_to(&repo);
+
+        let data = collect_parse_data("main", "dank-spr/", "ignore").unwrap();
+
+        assert_eq!(
+            data.merge_base,
+            git(&repo, ["rev-parse", "main"].as_slice()).trim()
+        );
+        assert_eq!(data.leading_ungrouped[0].sha, stray);
+        assert_eq!(data.leading_ungrouped[0].subject, "chore: stray");
+        assert!(data.leading_ignored.is_empty());
+        assert_eq!(data.groups.len(), 2);
+        assert_eq!(data.groups[0].stable_handle, "pr:alpha");
+        assert_eq!(data.groups[0].tag, "alpha");
+        assert_eq!(data.groups[0].head_branch, "dank-spr/alpha");
+        assert_eq!(data.groups[0].commits[0].sha, alpha);
+        assert_eq!(data.groups[0].ignored_after[0].sha, local);
+        assert_eq!(
+            data.groups[0].ignored_after[0].subject,
+            "wip: local pr:ignore"
+        );
+        assert_eq!(data.groups[1].local_pr_number, 2);
+        assert_eq!(data.groups[1].commits[0].sha, beta);
+
+        let lines = render_parse(&data);
+        assert_eq!(lines[2], "LPR #1 pr:alpha -> dank-spr/alpha (1 commit(s))");
+        assert_eq!(
+            lines[4],
+            format!("  ignored  {}  wip: local pr:ignore", &local[..8])
+        );
+
+        let json = serde_json::to_value(&data).unwrap();
+        assert_eq!(
+            json["groups"][1]["commits"][0]["subject"],
+            "feat: beta pr:beta"
+        );
+    }
+}
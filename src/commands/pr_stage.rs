@@ -0,0 +1,237 @@
+use anyhow::{bail, Result};
+use std::collections::HashMap;
+use tracing::info;
+
+use crate::branch_names::canonical_branch_conflict_key;
+use crate::execution::ExecutionMode;
+use crate::git::normalize_branch_name;
+use crate::github::{
+    convert_pull_requests_to_draft, fetch_pr_stage_info_graphql, list_open_prs_for_heads,
+    mark_pull_requests_ready_for_review, PrInfo, PrStageInfo,
+};
+use crate::maintenance_output::{
+    MaintenanceOptions, MaintenanceRepoContext, PrStage, PrStageAction, PrStageDecisionData,
+    PrStageSummaryData,
+};
+use crate::parsing::derive_local_groups;
+use crate::pr_base_chain::{build_desired_pr_base_chain, DesiredPrBase};
+use crate::selectors::{resolve_group_index, GroupSelector};
+
+fn render_pr_stage_action(action: PrStageAction) -> &'static str {
+    match action {
+        PrStageAction::Converted => "converted",
+        PrStageAction::DryRunConvert => "would convert",
+        PrStageAction::AlreadyInStage => "already in stage",
+        PrStageAction::MissingOpenPr => "missing open pr",
+    }
+}
+
+pub fn print_pr_stage_summary(summary: &PrStageSummaryData) {
+    if summary.decisions.is_empty() {
+        info!("No local groups found; nothing to change.");
+    }
+    for decision in &summary.decisions {
+        match decision.remote_pr_number {
+            Some(number) => info!(
+                "#{} {} -> {} ({})",
+                number,
+                decision.head_branch,
+                summary.stage.as_str(),
+                render_pr_stage_action(decision.action)
+            ),
+            None => info!(
+                "{} ({})",
+                decision.head_branch,
+                render_pr_stage_action(decision.action)
+            ),
+        }
+    }
+}
+
+/// Decide, per selected local group, whether its open PR needs a stage transition.
+fn plan_pr_stage_decisions(
+    selected: &[DesiredPrBase],
+    open_prs: &[PrInfo],
+    stage_by_number: &HashMap<u64, PrStageInfo>,
+    stage: PrStage,
+    execution_mode: ExecutionMode,
+) -> Vec<PrStageDecisionData> {
+    let open_by_head: HashMap<_, _> = open_prs
+        .iter()
+        .map(|pr| (canonical_branch_conflict_key(&pr.head), pr.number))
+        .collect();
+    selected
+        .iter()
+        .map(|desired| {
+            let remote_pr_number = open_by_head
+                .get(&canonical_branch_conflict_key(&desired.head_branch))
+                .copied();
+            let action = match remote_pr_number.and_then(|n| stage_by_number.get(&n)) {
+                None => PrStageAction::MissingOpenPr,
+                Some(info) if info.is_draft == (stage == PrStage::Draft) => {
+                    PrStageAction::AlreadyInStage
+                }
+                Some(_) if execution_mode == ExecutionMode::DryRun => PrStageAction::DryRunConvert,
+                Some(_) => PrStageAction::Converted,
+            };
+            PrStageDecisionData {
+                local_pr_number: desired.local_pr_number,
+                stable_handle: desired.stable_handle.clone(),
+                head_branch: desired.head_branch.clone(),
+                remote_pr_number,
+                action,
+            }
+        })
+        .collect()
+}
+
+/// Move one stack PR (or every stack PR when `target` is `None`) into draft or ready-for-review.
+///
+/// Local groups are the source of truth for selection; PRs already in the requested stage are
+/// left alone so repeated runs are no-ops.
+pub fn set_pr_stage(
+    base: &str,
+    prefix: &str,
+    ignore_tag: &str,
+    target: Option<&GroupSelector>,
+    stage: PrStage,
+    execution_mode: ExecutionMode,
+) -> Result<PrStageSummaryData> {
+    let dry_run = execution_mode == ExecutionMode::DryRun;
+    let normalized_base = normalize_branch_name(base);
+    let (_merge_base, groups) = derive_local_groups(base, ignore_tag)?;
+    if groups.is_empty() && target.is_some() {
+        bail!("No local groups found; nothing to change.");
+    }
+    let desired_chain = build_desired_pr_base_chain(&normalized_base, &groups, prefix)?;
+    let selected: Vec<DesiredPrBase> = match target {
+        Some(selector) => vec![desired_chain[resolve_group_index(&groups, selector)?].clone()],
+        None => desired_chain,
+    };
+    let heads: Vec<String> = selected
+        .iter()
+        .map(|desired| desired.head_branch.clone())
+        .collect();
+    let open_prs = list_open_prs_for_heads(&heads)?;
+    let numbers: Vec<u64> = open_prs.iter().map(|pr| pr.number).collect();
+    let stage_by_number = fetch_pr_stage_info_graphql(&numbers)?;
+    let decisions = plan_pr_stage_decisions(
+        &selected,
+        &open_prs,
+        &stage_by_number,
+        stage,
+        execution_mode,
+    );
+
+    let pull_request_ids: Vec<String> = decisions
+        .iter()
+        .filter(|decision| {
+            matches!(
+                decision.action,
+                PrStageAction::Converted | PrStageAction::DryRunConvert
+            )
+        })
+        .filter_map(|decision| decision.remote_pr_number)
+        .filter_map(|number| stage_by_number.get(&number))
+        .map(|info| info.id.clone())
+        .collect();
+    match stage {
+        PrStage::Draft => convert_pull_requests_to_draft(&pull_request_ids, execution_mode)?,
+        PrStage::Ready => mark_pull_requests_ready_for_review(&pull_request_ids, execution_mode)?,
+    }
+
+    Ok(PrStageSummaryData {
+        repo: MaintenanceRepoContext {
+            base: normalized_base,
+            prefix: prefix.to_string(),
+        },
+        options: MaintenanceOptions { dry_run },
+        stage,
+        decisions,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::plan_pr_stage_decisions;
+    use crate::execution::ExecutionMode;
+    use crate::github::{PrInfo, PrStageInfo};
+    use crate::maintenance_output::{PrStage, PrStageAction};
+    use crate::pr_base_chain::DesiredPrBase;
+    use std::collections::HashMap;
+
+    fn desired(local_pr_number: usize, head_branch: &str) -> DesiredPrBase {
+        DesiredPrBase {
+            local_pr_number,
+            stable_handle: format!("pr:{local_pr_number}"),
+            head_branch: head_branch.to_string(),
+            expected_base_ref: "main".to_string(),
+        }
+    }
+
+    fn open_pr(number: u64, head: &str) -> PrInfo {
+        PrInfo {
+            number,
+            head: head.to_string(),
+            base: "main".to_string(),
+        }
+    }
+
+    fn stage_info(id: &str, is_draft: bool) -> PrStageInfo {
+        PrStageInfo {
+            id: id.to_string(),
+            is_draft,
+        }
+    }
+
+    #[test]
+    fn plan_pr_stage_decisions_skips_prs_already_in_requested_stage() {
+        let selected = vec![
+            desired(1, "dank-spr/alpha"),
+            desired(2, "dank-spr/beta"),
+            desired(3, "dank-spr/gamma"),
+        ];
+        let open_prs = vec![open_pr(11, "dank-spr/alpha"), open_pr(12, "dank-spr/Beta")];
+        let stage_by_number = HashMap::from([
+            (11, stage_info("PR_11", true)),
+            (12, stage_info("PR_12", false)),
+        ]);
+
+        let decisions = plan_pr_stage_decisions(
+            &selected,
+            &open_prs,
+            &stage_by_number,
+            PrStage::Ready,
+            ExecutionMode::Apply,
+        );
+
+        let actions: Vec<_> = decisions.iter().map(|decision| decision.action).collect();
+        assert_eq!(
+            actions,
+            vec![
+                PrStageAction::Converted,
+                PrStageAction::AlreadyInStage,
+                PrStageAction::MissingOpenPr,
+            ]
+        );
+        assert_eq!(decisions[1].remote_pr_number, Some(12));
+        assert_eq!(decisions[2].remote_pr_number, None);
+    }
+
+    #[test]
+    fn plan_pr_stage_decisions_reports_dry_run_conversions() {
+        let selected = vec![desired(1, "dank-spr/alpha")];
+        let open_prs = vec![open_pr(11, "dank-spr/alpha")];
+        let stage_by_number = HashMap::from([(11, stage_info("PR_11", false))]);
+
+        let decisions = plan_pr_stage_decisions(
+            &selected,
+            &open_prs,
+            &stage_by_number,
+            PrStage::Draft,
+            ExecutionMode::DryRun,
+        );
+
+        assert_eq!(decisions[0].action, PrStageAction::DryRunConvert);
+    }
+}
@@ -3,7 +3,7 @@ use tracing::info;
 
 use crate::branch_names::{canonical_branch_conflict_key, group_branch_identities};
 use crate::execution::ExecutionMode;
-use crate::git::{git_ro, git_rw};
+use crate::git::{git_commit_tree, git_ro, git_rw};
 use crate::github::{append_warning_to_pr, list_open_prs_for_heads};
 use crate::limit::Limit;
 use crate::maintenance_output::{
@@ -26,6 +26,9 @@ pub struct PrepExecutionOptions {
     pub local_pr_branch_policy: crate::config::LocalPrBranchSyncPolicy,
     pub selection: crate::cli::PrepSelection,
     pub execution_mode: ExecutionMode,
+    /// Keep groups (and replayed commits) whose tree matches their parent as empty commits.
+    pub allow_empty: bool,
+    pub title_transforms: crate::title_transforms::TitleTransformConfig,
 }
 
 fn resolve_prep_window(
@@ -112,6 +115,7 @@ fn render_prepared_group_action(action: PreparedGroupAction) -> &'static str {
     match action {
         PreparedGroupAction::Squashed => "squashed",
         PreparedGroupAction::PreservedSingleCommit => "preserved single commit",
+        PreparedGroupAction::PreservedEmpty => "preserved empty group",
         PreparedGroupAction::SkippedEmpty => "skipped empty rewrite",
     }
 }
@@ -203,8 +207,17 @@ pub fn prep_squash(
         local_pr_branch_policy,
         selection,
         execution_mode,
+        allow_empty,
+        title_transforms,
     } = options;
     let dry_run = execution_mode == ExecutionMode::DryRun;
+    // With `--from-ref`, the squashed stack replaces that branch instead of the checked-out one
+    let stack_branch = match crate::commands::common::unchecked_out_stack_branch()? {
+        Some(branch) => branch,
+        None => git_ro(["symbolic-ref", "--quiet", "--short", "HEAD"].as_slice())?
+            .trim()
+            .to_string(),
+    };
     let (merge_base, groups) = derive_local_groups(base, ignore_tag)?;
     if groups.is_empty() {
         return Ok(PrepSummaryData {
@@ -216,6 +229,7 @@ pub fn prep_squash(
             options: PrepOptions {
                 dry_run,
                 pr_description_mode,
+                allow_empty,
             },
             selection: ResolvedPrepSelection::All,
             selected_groups: Vec::new(),
@@ -290,20 +304,17 @@ pub fn prep_squash(
                 single_idx += 1;
                 message.to_string()
             };
-            let parent_tree =
-                git_ro(["rev-parse", &format!("{}^{{tree}}", parent_sha)].as_slice())?
-                    .lines()
-                    .next()
-                    .unwrap_or("")
-                    .to_string();
-            if tree != parent_tree {
+            let parent_tree = git_commit_tree(&parent_sha)?;
+            if tree != parent_tree || allow_empty {
                 let new_commit = git_rw(
                     execution_mode,
                     ["commit-tree", tree, "-p", &parent_sha, "-m", &message].as_slice(),
                 )?
                 .trim()
                 .to_string();
-                let action = if group.commits.len() > 1 {
+                let action = if tree == parent_tree {
+                    PreparedGroupAction::PreservedEmpty
+                } else if group.commits.len() > 1 {
                     PreparedGroupAction::Squashed
                 } else {
                     PreparedGroupAction::PreservedSingleCommit
@@ -354,13 +365,8 @@ pub fn prep_squash(
         for index in 0..remainder.len() {
             let tree = trees.get(index).copied().unwrap_or("");
             let message = bodies.get(index).copied().unwrap_or("");
-            let parent_tree =
-                git_ro(["rev-parse", &format!("{}^{{tree}}", parent_sha)].as_slice())?
-                    .lines()
-                    .next()
-                    .unwrap_or("")
-                    .to_string();
-            if tree == parent_tree {
+            let parent_tree = git_commit_tree(&parent_sha)?;
+            if tree == parent_tree && !allow_empty {
                 skipped_replay_commit_count += 1;
             } else {
                 let new_commit = git_rw(
@@ -375,14 +381,11 @@ pub fn prep_squash(
         }
     }
 
-    let current_branch = git_ro(["symbolic-ref", "--quiet", "--short", "HEAD"].as_slice())?
-        .trim()
-        .to_string();
     git_rw(
         execution_mode,
         [
             "update-ref",
-            &format!("refs/heads/{}", current_branch),
+            &format!("refs/heads/{}", stack_branch),
             &parent_sha,
         ]
         .as_slice(),
@@ -396,6 +399,7 @@ pub fn prep_squash(
     let update_execution = crate::commands::build_from_groups_with_summary(
         base,
         prefix,
+        &crate::git::stack_ref_name(&crate::parsing::stack_from())?,
         &skipped_handles,
         false,
         execution_mode,
@@ -406,11 +410,13 @@ pub fn prep_squash(
         true,
         0,
         local_pr_branch_policy,
+        &title_transforms,
+        false,
     )?;
     let update_summary = UpdateSummaryData::from_execution(
         UpdateRepoContext {
             base: base.to_string(),
-            from: "HEAD".to_string(),
+            from: crate::parsing::stack_from(),
             prefix: prefix.to_string(),
             ignore_tag: ignore_tag.to_string(),
         },
@@ -485,6 +491,7 @@ pub fn prep_squash(
         options: PrepOptions {
             dry_run,
             pr_description_mode,
+            allow_empty,
         },
         selection: resolved_selection,
         selected_groups,
@@ -566,6 +573,7 @@ mod tests {
             options: crate::maintenance_output::PrepOptions {
                 dry_run: true,
                 pr_description_mode: PrDescriptionMode::Overwrite,
+                allow_empty: false,
             },
             selection: ResolvedPrepSelection::All,
             selected_groups: vec![crate::maintenance_output::PreparedGroupData {
@@ -606,6 +614,8 @@ mod tests {
                 local_pr_branch_policy: crate::config::LocalPrBranchSyncPolicy::Off,
                 selection: PrepSelection::All,
                 execution_mode: ExecutionMode::DryRun,
+                allow_empty: false,
+                title_transforms: Default::default(),
             },
         )
         .unwrap_err();
@@ -0,0 +1,291 @@
+//! Bring group commits pushed from another machine into the local stack.
+//!
+//! `spr pull --stack` fetches the remote and compares every group with its pushed branch. When
+//! the pushed group holds the local group's commits followed by new ones, the new commits are
+//! replayed on top of the local group and everything above it is rebuilt, so the next
+//! `spr update` does not force-push them away. Commits are matched by patch ID, so upper branches
+//! that the other machine's `spr update` rebased onto a grown lower group still match. A pushed
+//! group that does not extend the local commits (local rewrites not pushed yet, or history
+//! rewritten elsewhere) cannot be fast-forwarded and is left as is with a warning.
+
+use anyhow::{bail, Result};
+use std::collections::HashMap;
+use tracing::{info, warn};
+
+use crate::branch_names::group_branch_identities;
+use crate::commands::common::CherryPickOp;
+use crate::commands::reword::replay_operations;
+use crate::commands::rewrite_resume::{RewriteCommandKind, RewriteCommandOutcome};
+use crate::config::DirtyWorktreePolicy;
+use crate::execution::ExecutionMode;
+use crate::git::{
+    fetch_remote, git_commit_parent_count, git_is_ancestor, git_merge_base,
+    git_patch_ids_for_commits, git_ref_exists_at, git_rev_list_range, git_rev_parse, remote,
+};
+use crate::parsing::{derive_local_groups, Group};
+
+/// How a group's pushed branch relates to the local group.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PushedGroupState {
+    /// Not pushed, or the pushed group has nothing the local group lacks.
+    UpToDate,
+    /// The pushed group is the local group followed by these commits, oldest first.
+    Ahead(Vec<String>),
+    /// The pushed group rewrote or dropped local commits.
+    Diverged,
+}
+
+/// Classify a pushed group from its commits and the local group's commits, both oldest first.
+fn classify_pushed_group(
+    local: &[String],
+    pushed: &[String],
+    patch_ids: &HashMap<String, String>,
+) -> PushedGroupState {
+    let id = |sha: &String| patch_ids.get(sha).unwrap_or(sha).clone();
+    let local_ids: Vec<String> = local.iter().map(id).collect();
+    let pushed_ids: Vec<String> = pushed.iter().map(id).collect();
+    if pushed_ids
+        .iter()
+        .all(|pushed_id| local_ids.contains(pushed_id))
+    {
+        PushedGroupState::UpToDate
+    } else if pushed_ids.starts_with(&local_ids) {
+        PushedGroupState::Ahead(pushed[local.len()..].to_vec())
+    } else {
+        PushedGroupState::Diverged
+    }
+}
+
+/// Compare every group with its remote-tracking branch, bottom to top.
+///
+/// The pushed group is the range from the branch below it (when that branch is an ancestor) or
+/// from the merge base with `base` up to the group's pushed tip.
+///
+/// # Errors
+///
+/// Returns errors from Git or case-colliding concrete branch names.
+pub fn collect_pushed_group_states(
+    base: &str,
+    prefix: &str,
+    groups: &[Group],
+) -> Result<Vec<PushedGroupState>> {
+    let identities = group_branch_identities(groups, prefix)?;
+    let mut states = Vec::with_capacity(groups.len());
+    let mut lower_pushed: Option<String> = None;
+    for (group, identity) in groups.iter().zip(&identities) {
+        let remote_ref = format!("refs/remotes/{}/{}", remote(), identity.exact);
+        if !git_ref_exists_at(".", &remote_ref)? {
+            states.push(PushedGroupState::UpToDate);
+            lower_pushed = None;
+            continue;
+        }
+        let pushed_tip = git_rev_parse(&remote_ref)?;
+        let lower = match &lower_pushed {
+            Some(lower) if git_is_ancestor(lower, &pushed_tip)? => lower.clone(),
+            _ => git_merge_base(base, &pushed_tip)?,
+        };
+        let pushed = git_rev_list_range(&lower, &pushed_tip)?;
+        let mut commits = group.commits.clone();
+        commits.extend(pushed.iter().cloned());
+        let patch_ids = git_patch_ids_for_commits(&commits)?;
+        states.push(classify_pushed_group(&group.commits, &pushed, &patch_ids));
+        lower_pushed = Some(pushed_tip);
+    }
+    Ok(states)
+}
+
+/// Warnings for groups whose pushed branch holds commits missing from the local stack.
+///
+/// # Errors
+///
+/// Returns errors from Git or case-colliding concrete branch names.
+pub fn pushed_ahead_warnings(base: &str, prefix: &str, groups: &[Group]) -> Result<Vec<String>> {
+    Ok(collect_pushed_group_states(base, prefix, groups)?
+        .iter()
+        .zip(groups)
+        .filter_map(|(state, group)| match state {
+            PushedGroupState::Ahead(commits) => Some(format!(
+                "{}: its pushed branch has {} commit(s) missing from the local stack (pushed from another machine?); run `spr pull --stack` first to keep them",
+                group.selector_text(),
+                commits.len()
+            )),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Fast-forward local groups from their pushed branches and rebuild the stack above them.
+///
+/// # Errors
+///
+/// Returns errors when there are no groups, pulled commits include a merge commit, or Git
+/// operations fail.
+pub fn pull_stack(
+    metadata_context: &crate::stack_metadata::RefreshMetadataContext,
+    safe: bool,
+    execution_mode: ExecutionMode,
+    dirty_worktree_policy: DirtyWorktreePolicy,
+) -> Result<RewriteCommandOutcome> {
+    fetch_remote(execution_mode)?;
+    let (_merge_base, groups) =
+        derive_local_groups(&metadata_context.base, &metadata_context.ignore_tag)?;
+    if groups.is_empty() {
+        bail!("No local PR groups found; nothing to pull.");
+    }
+    let states =
+        collect_pushed_group_states(&metadata_context.base, &metadata_context.prefix, &groups)?;
+    let diverged: Vec<String> = states
+        .iter()
+        .zip(&groups)
+        .filter(|(state, _)| **state == PushedGroupState::Diverged)
+        .map(|(_, group)| group.selector_text())
+        .collect();
+    if !diverged.is_empty() {
+        warn!(
+            "Leaving {} as is: the pushed branch does not extend the local commits (local rewrites not pushed yet, or rewritten elsewhere); compare with `spr range-diff`",
+            diverged.join(", ")
+        );
+    }
+    let mut new_after_tip: HashMap<&str, &[String]> = HashMap::new();
+    let mut lowest_tip = None;
+    for (state, group) in states.iter().zip(&groups) {
+        if let (PushedGroupState::Ahead(commits), Some(tip)) = (state, group.commits.last()) {
+            for commit in commits {
+                if git_commit_parent_count(commit)? != 1 {
+                    bail!(
+                        "The pushed branch of {} contains merge commit {commit}; restack it instead",
+                        group.selector_text()
+                    );
+                }
+            }
+            info!(
+                "{}: pulling {} commit(s) from its pushed branch",
+                group.selector_text(),
+                commits.len()
+            );
+            new_after_tip.insert(tip.as_str(), commits.as_slice());
+            lowest_tip.get_or_insert(tip.clone());
+        }
+    }
+    let Some(lowest_tip) = lowest_tip else {
+        info!("Every group already contains its pushed commits; nothing to pull.");
+        return Ok(RewriteCommandOutcome::Completed);
+    };
+    let mut operations = Vec::new();
+    let mut run: Vec<String> = Vec::new();
+    let mut pulled = 0;
+    let mut replay_after =
+        |tip: &str, run: &mut Vec<String>, operations: &mut Vec<CherryPickOp>| {
+            if let Some(commits) = new_after_tip.get(tip) {
+                operations.extend(CherryPickOp::from_commits(run));
+                run.clear();
+                operations.extend(CherryPickOp::from_commits(commits));
+                pulled += commits.len();
+            }
+        };
+    replay_after(&lowest_tip, &mut run, &mut operations);
+    for sha in git_rev_list_range(&lowest_tip, "HEAD")? {
+        run.push(sha.clone());
+        replay_after(&sha, &mut run, &mut operations);
+    }
+    operations.extend(CherryPickOp::from_commits(&run));
+    replay_operations(
+        metadata_context,
+        &lowest_tip,
+        operations,
+        RewriteCommandKind::Pull,
+        &format!("Replaying {pulled} pulled commit(s) into the stack."),
+        safe,
+        execution_mode,
+        dirty_worktree_policy,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{classify_pushed_group, pull_stack, PushedGroupState};
+    use crate::commands::RewriteCommandOutcome;
+    use crate::config::DirtyWorktreePolicy;
+    use crate::execution::ExecutionMode;
+    use crate::test_support::{commit_file, git, init_repo, lock_cwd, log_subjects, DirGuard};
+    use std::collections::HashMap;
+
+    fn shas(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn classify_matches_rebased_commits_by_patch_id() {
+        let patch_ids: HashMap<String, String> = [("a", "p1"), ("a2", "p1"), ("b", "p2")]
+            .iter()
+            .map(|(sha, id)| (sha.to_string(), id.to_string()))
+            .collect();
+        assert_eq!(
+            classify_pushed_group(&shas(&["a"]), &shas(&["a2", "n"]), &patch_ids),
+            PushedGroupState::Ahead(shas(&["n"]))
+        );
+        assert_eq!(
+            classify_pushed_group(&shas(&["a", "b"]), &shas(&["a2"]), &patch_ids),
+            PushedGroupState::UpToDate
+        );
+        assert_eq!(
+            classify_pushed_group(&shas(&["a", "b"]), &shas(&["a", "n"]), &patch_ids),
+            PushedGroupState::Diverged
+        );
+    }
+
+    #[test]
+    fn pull_stack_inserts_commits_pushed_to_a_lower_group() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path().to_path_buf();
+        git(&repo, ["checkout", "-b", "stack"].as_slice());
+        commit_file(&repo, "alpha.txt", "a\n", "feat: alpha pr:alpha");
+        commit_file(&repo, "beta.txt", "b\n", "feat: beta pr:beta");
+        let origin = tempfile::TempDir::new().unwrap();
+        git(origin.path(), ["init", "-q", "--bare"].as_slice());
+        git(
+            &repo,
+            ["remote", "add", "origin", origin.path().to_str().unwrap()].as_slice(),
+        );
+        // Another machine added a commit to alpha and republished both branches.
+        git(
+            &repo,
+            ["checkout", "-q", "-b", "other", "HEAD~1"].as_slice(),
+        );
+        commit_file(&repo, "alpha.txt", "a\nmore\n", "fix: alpha review");
+        git(
+            &repo,
+            ["push", "-q", "origin", "HEAD:dank-spr/alpha"].as_slice(),
+        );
+        git(&repo, ["cherry-pick", "stack"].as_slice());
+        git(
+            &repo,
+            ["push", "-q", "origin", "HEAD:dank-spr/beta"].as_slice(),
+        );
+        git(&repo, ["checkout", "-q", "stack"].as_slice());
+        let _guard = DirGuard::change_to(&repo);
+
+        let outcome = pull_stack(
+            &crate::stack_metadata::RefreshMetadataContext {
+                base: "main".to_string(),
+                prefix: "dank-spr/".to_string(),
+                ignore_tag: "ignore".to_string(),
+            },
+            false,
+            ExecutionMode::Apply,
+            DirtyWorktreePolicy::Halt,
+        )
+        .unwrap();
+
+        assert_eq!(outcome, RewriteCommandOutcome::Completed);
+        assert_eq!(
+            log_subjects(&repo, 3),
+            vec![
+                "feat: beta pr:beta".to_string(),
+                "fix: alpha review".to_string(),
+                "feat: alpha pr:alpha".to_string(),
+            ]
+        );
+    }
+}
@@ -0,0 +1,191 @@
+//! Compare one PR group with the version last pushed for it.
+//!
+//! `spr range-diff <target>` finds the group's previously pushed tip (the remote-tracking ref of
+//! its head branch, else the SHA recorded in the local `refs/spr/state/<prefix>` ref), locates the
+//! same group in that pushed history by its marker, and renders `git range-diff` between the old
+//! and the local commits. After a restack this shows whether the rebase changed any content.
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+use tracing::info;
+
+use crate::branch_names::group_branch_identities;
+use crate::git::{git_ref_exists_at, git_ro, remote};
+use crate::parsing::{derive_groups_between, derive_local_groups, Group};
+use crate::selectors::{resolve_group_ordinal, GroupSelector};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RangeDiffData {
+    pub local_pr_number: usize,
+    pub stable_handle: String,
+    pub head_branch: String,
+    pub pushed_sha: String,
+    /// Commit range of the group in the pushed history, `<parent>..<tip>`.
+    pub old_range: String,
+    /// Commit range of the local group, `<parent>..<tip>`.
+    pub new_range: String,
+    /// True when the pushed tip is the local tip.
+    pub unchanged: bool,
+    /// `git range-diff` output; empty when `unchanged`.
+    pub output: String,
+}
+
+fn commit_exists(sha: &str) -> bool {
+    git_ro(["cat-file", "-e", &format!("{sha}^{{commit}}")].as_slice()).is_ok()
+}
+
+/// The last pushed tip of `head_branch`: its remote-tracking ref, else the recorded state.
+fn pushed_tip(prefix: &str, head_branch: &str) -> Result<Option<String>> {
+    let remote_ref = format!("refs/remotes/{}/{head_branch}", remote());
+    if git_ref_exists_at(".", &remote_ref)? {
+        return Ok(Some(
+            git_ro(["rev-parse", &remote_ref].as_slice())?
+                .trim()
+                .to_string(),
+        ));
+    }
+    Ok(crate::remote_state::recorded_push_sha(prefix, head_branch)?
+        .filter(|sha| commit_exists(sha)))
+}
+
+fn group_range(group: &Group) -> Option<String> {
+    let first = group.commits.first()?;
+    let last = group.commits.last()?;
+    Some(format!("{first}^..{last}"))
+}
+
+/// Range of the group with `local`'s marker in the pushed history ending at `pushed`.
+///
+/// Falls back to everything the pushed branch adds on top of `base` when the marker is not found,
+/// for example after the group's tag was renamed.
+fn pushed_group_range(base: &str, pushed: &str, ignore_tag: &str, local: &Group) -> Result<String> {
+    let (merge_base, groups) = derive_groups_between(base, pushed, ignore_tag)?;
+    Ok(groups
+        .iter()
+        .find(|group| group.marker == local.marker)
+        .and_then(group_range)
+        .unwrap_or_else(|| format!("{merge_base}..{pushed}")))
+}
+
+/// Collect the range-diff between `target`'s pushed and local commits.
+///
+/// # Errors
+///
+/// Returns errors when the target does not resolve, the group was never pushed, or Git fails.
+pub fn collect_range_diff_data(
+    base: &str,
+    prefix: &str,
+    ignore_tag: &str,
+    target: &GroupSelector,
+) -> Result<RangeDiffData> {
+    let (_merge_base, groups) = derive_local_groups(base, ignore_tag)?;
+    if groups.is_empty() {
+        bail!("No local PR groups found; nothing to compare.");
+    }
+    let local_pr_number = resolve_group_ordinal(&groups, target)?;
+    let group = &groups[local_pr_number - 1];
+    let head_branch = group_branch_identities(&groups, prefix)?[local_pr_number - 1]
+        .exact
+        .clone();
+    let Some(new_range) = group_range(group) else {
+        bail!("{} has no commits", group.selector_text());
+    };
+    let Some(pushed_sha) = pushed_tip(prefix, &head_branch)? else {
+        bail!(
+            "{} has no pushed version to compare with; run `git fetch {}` or `spr update` first",
+            head_branch,
+            remote()
+        );
+    };
+    let unchanged = group.commits.last() == Some(&pushed_sha);
+    let old_range = pushed_group_range(base, &pushed_sha, ignore_tag, group)?;
+    let output = if unchanged {
+        String::new()
+    } else {
+        git_ro(["range-diff", &old_range, &new_range].as_slice())?
+    };
+    Ok(RangeDiffData {
+        local_pr_number,
+        stable_handle: group.selector_text(),
+        head_branch,
+        pushed_sha,
+        old_range,
+        new_range,
+        unchanged,
+        output,
+    })
+}
+
+/// Print the range-diff between `target`'s pushed and local commits.
+pub fn range_diff_display(
+    base: &str,
+    prefix: &str,
+    ignore_tag: &str,
+    target: &GroupSelector,
+) -> Result<()> {
+    let data = collect_range_diff_data(base, prefix, ignore_tag, target)?;
+    if data.unchanged {
+        info!(
+            "LPR #{} / {}: local commits match the pushed {}",
+            data.local_pr_number, data.stable_handle, data.head_branch
+        );
+        return Ok(());
+    }
+    info!(
+        "LPR #{} / {}: pushed {} ({}) vs local",
+        data.local_pr_number,
+        data.stable_handle,
+        data.head_branch,
+        &data.pushed_sha[..data.pushed_sha.len().min(8)]
+    );
+    for line in data.output.lines() {
+        info!("{line}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::collect_range_diff_data;
+    use crate::selectors::GroupSelector;
+    use crate::test_support::{commit_file, git, init_repo, lock_cwd, DirGuard};
+
+    #[test]
+    fn range_diff_compares_pushed_group_with_restacked_group() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path().to_path_buf();
+        git(&repo, ["checkout", "-b", "stack"].as_slice());
+        commit_file(&repo, "alpha.txt", "a\n", "feat: alpha pr:alpha");
+        commit_file(&repo, "beta.txt", "b\n", "feat: beta pr:beta");
+        git(
+            &repo,
+            ["update-ref", "refs/remotes/origin/dank-spr/beta", "HEAD"].as_slice(),
+        );
+        let _guard = DirGuard::change_to(&repo);
+
+        let unchanged =
+            collect_range_diff_data("main", "dank-spr/", "ignore", &GroupSelector::LocalPr(2))
+                .unwrap();
+        assert!(unchanged.unchanged);
+
+        git(&repo, ["checkout", "-q", "main"].as_slice());
+        commit_file(&repo, "base.txt", "base\n", "chore: base moves");
+        git(&repo, ["checkout", "-q", "stack"].as_slice());
+        git(&repo, ["rebase", "-q", "main"].as_slice());
+        commit_file(&repo, "beta.txt", "b2\n", "feat: beta tweak");
+
+        let data =
+            collect_range_diff_data("main", "dank-spr/", "ignore", &GroupSelector::LocalPr(2))
+                .unwrap();
+        assert!(!data.unchanged);
+        let lines: Vec<&str> = data.output.lines().collect();
+        assert!(
+            lines[0].contains("= ") && lines[0].contains("feat: beta pr:beta"),
+            "{}",
+            data.output
+        );
+        assert!(data.output.contains("feat: beta tweak"), "{}", data.output);
+        assert!(!data.output.contains("feat: alpha"), "{}", data.output);
+    }
+}
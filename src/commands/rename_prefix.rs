@@ -0,0 +1,270 @@
+//! Move every remote branch under the current prefix to a new prefix with `spr rename-prefix`.
+//!
+//! GitHub cannot change a PR's head branch, so each open PR whose head moves is opened again from
+//! the new branch (same title, body, draft state, and renamed base) and the old PR is closed with
+//! a comment pointing at its replacement. Open PRs whose base moves but whose head stays are
+//! retargeted in place. Old branches are deleted only after that, because GitHub closes PRs whose
+//! base branch disappears. Finally the new prefix is written to the repository config so the next
+//! `spr update` publishes under it.
+
+use anyhow::{bail, Result};
+use std::collections::HashMap;
+use tracing::{info, warn};
+
+use crate::config::normalize_prefix;
+use crate::execution::ExecutionMode;
+use crate::git::{
+    get_remote_branches_sha, gh_rw, git_rw, list_remote_branches_with_prefix, remote,
+};
+use crate::github::{list_open_pr_snapshots, upsert_pr_cached, OpenPrSnapshot};
+
+/// Everything `spr rename-prefix` changes, in the order it changes it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RenamePrefixPlan {
+    /// `(old branch, new branch, sha)` for every remote branch under the old prefix.
+    pub branches: Vec<(String, String, String)>,
+    /// Open PRs whose head moves, with the new head and base they are opened again with.
+    pub reopen: Vec<(OpenPrSnapshot, String, String)>,
+    /// `(PR number, new base)` for open PRs whose head stays but whose base moves.
+    pub retarget: Vec<(u64, String)>,
+}
+
+fn renamed(branch: &str, old_prefix: &str, new_prefix: &str) -> Option<String> {
+    branch
+        .strip_prefix(old_prefix)
+        .map(|tail| format!("{new_prefix}{tail}"))
+}
+
+/// Plan the rename of `remote_branches` (name to sha) from `old_prefix` to `new_prefix`.
+pub fn plan_rename_prefix(
+    old_prefix: &str,
+    new_prefix: &str,
+    remote_branches: &HashMap<String, String>,
+    open_prs: &[OpenPrSnapshot],
+) -> RenamePrefixPlan {
+    let mut branches: Vec<(String, String, String)> = remote_branches
+        .iter()
+        .filter_map(|(branch, sha)| {
+            renamed(branch, old_prefix, new_prefix)
+                .map(|new_branch| (branch.clone(), new_branch, sha.clone()))
+        })
+        .collect();
+    branches.sort();
+    let moved_base = |base: &str| {
+        remote_branches
+            .contains_key(base)
+            .then(|| renamed(base, old_prefix, new_prefix))
+            .flatten()
+    };
+    let mut plan = RenamePrefixPlan {
+        branches,
+        ..RenamePrefixPlan::default()
+    };
+    for pr in open_prs {
+        let new_base = moved_base(&pr.base);
+        if remote_branches.contains_key(&pr.head) {
+            if let Some(new_head) = renamed(&pr.head, old_prefix, new_prefix) {
+                let base = new_base.unwrap_or_else(|| pr.base.clone());
+                plan.reopen.push((pr.clone(), new_head, base));
+                continue;
+            }
+        }
+        if let Some(new_base) = new_base {
+            plan.retarget.push((pr.number, new_base));
+        }
+    }
+    plan.reopen.sort_by_key(|(pr, _, _)| pr.number);
+    plan.retarget.sort();
+    plan
+}
+
+/// Move the remote branches and open PRs under `old_prefix` to `new_prefix`.
+///
+/// # Errors
+///
+/// Returns errors when the new prefix is invalid or equal to the old one, a new branch name is
+/// already taken, or a push or GitHub call fails.
+pub fn rename_prefix(
+    old_prefix: &str,
+    new_prefix: &str,
+    keep_config: bool,
+    execution_mode: ExecutionMode,
+) -> Result<()> {
+    let new_prefix = normalize_prefix(new_prefix)?;
+    if new_prefix == old_prefix {
+        bail!("The prefix is already {old_prefix}");
+    }
+    let names = list_remote_branches_with_prefix(old_prefix)?;
+    let remote_branches: HashMap<String, String> = get_remote_branches_sha(&names)?
+        .into_iter()
+        .filter(|(name, _)| names.contains(name))
+        .collect();
+    let plan = plan_rename_prefix(
+        old_prefix,
+        &new_prefix,
+        &remote_branches,
+        &list_open_pr_snapshots()?,
+    );
+    if plan.branches.is_empty() {
+        info!("No branches under {old_prefix} on {}.", remote());
+    } else {
+        let new_names: Vec<String> = plan
+            .branches
+            .iter()
+            .map(|(_, new_branch, _)| new_branch.clone())
+            .collect();
+        let taken: Vec<String> = get_remote_branches_sha(&new_names)?
+            .into_keys()
+            .filter(|name| new_names.contains(name))
+            .collect();
+        if !taken.is_empty() {
+            bail!(
+                "{} already exist on {}; choose another prefix",
+                taken.join(", "),
+                remote()
+            );
+        }
+        info!(
+            "Pushing {} branch(es) under {}…",
+            plan.branches.len(),
+            new_prefix
+        );
+        let mut args: Vec<String> = vec!["push".into(), remote()];
+        args.extend(
+            plan.branches
+                .iter()
+                .map(|(_, new_branch, sha)| format!("{sha}:refs/heads/{new_branch}")),
+        );
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        git_rw(execution_mode, &args)?;
+    }
+
+    for (pr, new_head, new_base) in &plan.reopen {
+        if execution_mode == ExecutionMode::DryRun {
+            info!(
+                "DRY-RUN: would open {} -> {} as the replacement of PR #{} and close #{}",
+                new_head, new_base, pr.number, pr.number
+            );
+            continue;
+        }
+        let number = upsert_pr_cached(
+            new_head,
+            new_base,
+            &pr.title,
+            &pr.body,
+            pr.is_draft,
+            execution_mode,
+            &mut HashMap::new(),
+        )?;
+        info!(
+            "Opened PR #{} for {} (replaces #{})",
+            number, new_head, pr.number
+        );
+        gh_rw(
+            execution_mode,
+            [
+                "pr",
+                "close",
+                &format!("#{}", pr.number),
+                "--comment",
+                &format!(
+                    "Moved to #{number}: `spr rename-prefix` renamed the branch to `{new_head}`."
+                ),
+            ]
+            .as_slice(),
+        )?;
+    }
+    for (number, new_base) in &plan.retarget {
+        info!("Retargeting PR #{} onto {}", number, new_base);
+        gh_rw(
+            execution_mode,
+            ["pr", "edit", &format!("#{number}"), "--base", new_base].as_slice(),
+        )?;
+    }
+
+    if !plan.branches.is_empty() {
+        let mut args: Vec<String> = vec!["push".into(), remote(), "--delete".into()];
+        args.extend(
+            plan.branches
+                .iter()
+                .map(|(old_branch, _, _)| old_branch.clone()),
+        );
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        if let Err(err) = git_rw(execution_mode, &args) {
+            warn!("Renamed the branches, but deleting the old ones failed: {err:#}");
+        }
+    }
+
+    if keep_config {
+        info!("Pass --prefix {new_prefix} or set `prefix: {new_prefix}` in config to use the new branches.");
+    } else if execution_mode == ExecutionMode::DryRun {
+        info!("DRY-RUN: would set `prefix: {new_prefix}` in the repository config");
+    } else {
+        let path = crate::config::set_repo_config_prefix(&new_prefix)?;
+        info!("Set `prefix: {}` in {}", new_prefix, path.display());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::plan_rename_prefix;
+    use crate::github::OpenPrSnapshot;
+    use std::collections::HashMap;
+
+    fn pr(number: u64, head: &str, base: &str) -> OpenPrSnapshot {
+        OpenPrSnapshot {
+            number,
+            title: format!("PR {number}"),
+            body: String::new(),
+            head: head.to_string(),
+            base: base.to_string(),
+            is_draft: number == 2,
+        }
+    }
+
+    #[test]
+    fn plan_rename_prefix_reopens_moved_heads_and_retargets_moved_bases() {
+        let remote_branches = HashMap::from([
+            ("dank-spr/alpha".to_string(), "a1".to_string()),
+            ("dank-spr/beta".to_string(), "b1".to_string()),
+        ]);
+        let open_prs = vec![
+            pr(1, "dank-spr/alpha", "main"),
+            pr(2, "dank-spr/beta", "dank-spr/alpha"),
+            pr(3, "backport/release/fix", "dank-spr/beta"),
+            pr(4, "jane-spr/alpha", "main"),
+        ];
+
+        let plan = plan_rename_prefix("dank-spr/", "team/dank/", &remote_branches, &open_prs);
+
+        assert_eq!(
+            plan.branches,
+            vec![
+                (
+                    "dank-spr/alpha".to_string(),
+                    "team/dank/alpha".to_string(),
+                    "a1".to_string()
+                ),
+                (
+                    "dank-spr/beta".to_string(),
+                    "team/dank/beta".to_string(),
+                    "b1".to_string()
+                ),
+            ]
+        );
+        let reopen: Vec<(u64, &str, &str, bool)> = plan
+            .reopen
+            .iter()
+            .map(|(pr, head, base)| (pr.number, head.as_str(), base.as_str(), pr.is_draft))
+            .collect();
+        assert_eq!(
+            reopen,
+            vec![
+                (1, "team/dank/alpha", "main", false),
+                (2, "team/dank/beta", "team/dank/alpha", true),
+            ]
+        );
+        assert_eq!(plan.retarget, vec![(3, "team/dank/beta".to_string())]);
+    }
+}
@@ -9,8 +9,12 @@
 //! The default `halt` behavior suspends the replay, leaves the temp worktree
 //! in place, and writes a resume file for `spr resume <path>`. The `rollback`
 //! behavior preserves the historical cleanup-on-conflict path.
+//!
+//! `spr restack --interactive` lists the groups with the state of their PRs, reads the `after`
+//! boundary from the terminal, and asks for confirmation after printing the plan.
 
 use anyhow::{bail, Context, Result};
+use std::io::{BufRead, IsTerminal, Write};
 use std::path::Path;
 use tracing::{info, warn};
 
@@ -22,15 +26,18 @@ use crate::commands::rewrite_resume::{
 };
 use crate::config::{DirtyWorktreePolicy, RestackConflictPolicy};
 use crate::execution::ExecutionMode;
+use crate::git::fetch_remote;
 use crate::git::git_rev_list_range;
 use crate::git::git_rev_parse;
 use crate::git::git_ro;
 use crate::git::git_rw;
+use crate::github::{list_open_or_merged_prs_for_heads, PrInfoWithState, PrState};
 use crate::parsing::{derive_local_groups_with_ignored, Group};
 use crate::restack_output::{
-    render_human_preview, RestackExecutorPlan, RestackPreviewData, RestackPreviewGroup,
+    render_human_preview, RestackCheckConflict, RestackCheckData, RestackExecutorPlan,
+    RestackPreviewData, RestackPreviewGroup,
 };
-use crate::selectors::{resolve_after_count, AfterSelector};
+use crate::selectors::{resolve_after_count, AfterSelector, GroupSelector};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct RestackExecutionOptions {
@@ -278,7 +285,8 @@ impl RestackPlan {
             kept_ignored_segment_count: self.kept_ignored_segments.len(),
             planned_cherry_pick_operation_count: self.operations.len(),
             planned_executor,
-            would_fetch_origin_when_executed: !self.base_ref_was_refreshed,
+            would_fetch_origin_when_executed: !self.base_ref_was_refreshed
+                && !crate::git::no_fetch(),
             would_create_backup_tag: safe_requested && would_change_branch,
             would_create_temp_worktree,
             would_reset_current_branch: would_change_branch,
@@ -288,6 +296,20 @@ impl RestackPlan {
     }
 }
 
+/// Branch and tip of the stack being restacked: the `--from` branch, or the checkout.
+fn stack_branch_and_head() -> Result<(String, String)> {
+    match common::unchecked_out_stack_branch()? {
+        Some(branch) => {
+            let head = git_rev_parse(&format!("refs/heads/{branch}"))?;
+            Ok((branch, head))
+        }
+        None => {
+            let (current_branch, _) = common::get_current_branch_and_short()?;
+            Ok((current_branch, git_rev_parse("HEAD")?))
+        }
+    }
+}
+
 fn build_restack_plan(
     metadata_context: &crate::stack_metadata::RefreshMetadataContext,
     leading_ignored: Vec<String>,
@@ -301,8 +323,7 @@ fn build_restack_plan(
     let dropped_groups = groups[..after].to_vec();
     let remaining_groups = groups[after..].to_vec();
     let operations = build_cherry_pick_plan(&kept_ignored_segments, &remaining_groups);
-    let (current_branch, _) = common::get_current_branch_and_short()?;
-    let original_head = git_rev_parse("HEAD")?;
+    let (current_branch, original_head) = stack_branch_and_head()?;
 
     Ok(RestackPlan {
         base_ref: metadata_context.base.clone(),
@@ -369,12 +390,12 @@ pub fn preview_restack_after(
     after: &AfterSelector,
     safe_requested: bool,
 ) -> Result<RestackPreviewData> {
+    let allow_native_rebase = common::unchecked_out_stack_branch()?.is_none();
     if let Some(plan) = collect_restack_plan(metadata_context, after, false)? {
-        let planned_executor = plan.planned_executor(true)?;
+        let planned_executor = plan.planned_executor(allow_native_rebase)?;
         Ok(plan.preview_data(safe_requested, planned_executor))
     } else {
-        let (current_branch, _) = common::get_current_branch_and_short()?;
-        let original_head = git_rev_parse("HEAD")?;
+        let (current_branch, original_head) = stack_branch_and_head()?;
         let plan = RestackPlan {
             base_ref: metadata_context.base.clone(),
             base_sha: git_rev_parse(&metadata_context.base).ok(),
@@ -388,11 +409,265 @@ pub fn preview_restack_after(
             kept_ignored_segments: Vec::new(),
             operations: Vec::new(),
         };
-        let planned_executor = plan.planned_executor(true)?;
+        let planned_executor = plan.planned_executor(allow_native_rebase)?;
         Ok(plan.preview_data(safe_requested, planned_executor))
     }
 }
 
+/// One numbered line of the interactive `after` menu: the group and the state of its PR.
+fn interactive_group_line(ordinal: usize, group: &Group, pr: Option<&PrInfoWithState>) -> String {
+    let state = match pr {
+        Some(pr) if pr.state == PrState::Merged => format!("#{} merged", pr.number),
+        Some(pr) => format!("#{} open", pr.number),
+        None => "no PR".to_string(),
+    };
+    format!(
+        "  {:>2}. {} ({}) {}",
+        ordinal,
+        group.selector_text(),
+        state,
+        group
+            .subjects
+            .first()
+            .map(String::as_str)
+            .unwrap_or_default()
+    )
+}
+
+/// Number of bottom groups whose PRs merged, offered as the default `after`.
+fn merged_bottom_count(prs: &[Option<PrInfoWithState>]) -> usize {
+    prs.iter()
+        .take_while(|pr| pr.as_ref().is_some_and(|pr| pr.state == PrState::Merged))
+        .count()
+}
+
+/// The PR of each group, or `None` everywhere when GitHub cannot be queried.
+fn group_prs(groups: &[Group], prefix: &str) -> Vec<Option<PrInfoWithState>> {
+    let prs = crate::branch_names::group_branch_identities(groups, prefix).and_then(|identities| {
+        let heads: Vec<String> = identities
+            .into_iter()
+            .map(|identity| identity.exact)
+            .collect();
+        let prs = list_open_or_merged_prs_for_heads(&heads)?;
+        Ok(heads
+            .iter()
+            .map(|head| prs.iter().find(|pr| &pr.head == head).cloned())
+            .collect())
+    });
+    prs.unwrap_or_else(|err| {
+        warn!("Could not read PR states: {err:#}");
+        vec![None; groups.len()]
+    })
+}
+
+fn prompt_line(prompt: &str) -> Result<String> {
+    let mut stderr = std::io::stderr();
+    write!(stderr, "{prompt}")?;
+    stderr.flush()?;
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+    Ok(answer.trim().to_string())
+}
+
+/// Choose the restack boundary from the terminal and confirm the resulting plan.
+///
+/// Returns `None` when there is nothing to restack or the plan is declined.
+///
+/// # Errors
+///
+/// Returns errors when stdin is not a terminal, the answer is not a group count, or the plan
+/// cannot be computed.
+pub fn choose_restack_after_interactively(
+    metadata_context: &crate::stack_metadata::RefreshMetadataContext,
+    safe: bool,
+) -> Result<Option<AfterSelector>> {
+    if !std::io::stdin().is_terminal() {
+        bail!("spr restack --interactive needs a terminal; pass --after instead");
+    }
+    let (_merge_base, groups) =
+        crate::parsing::derive_local_groups(&metadata_context.base, &metadata_context.ignore_tag)?;
+    if groups.is_empty() {
+        info!("No local PR groups found; nothing to restack.");
+        return Ok(None);
+    }
+    let prs = group_prs(&groups, &metadata_context.prefix);
+    let mut stderr = std::io::stderr();
+    writeln!(stderr, "Groups, bottom to top:")?;
+    writeln!(
+        stderr,
+        "   0. (keep none; rebuild every group onto the base)"
+    )?;
+    for (idx, group) in groups.iter().enumerate() {
+        writeln!(
+            stderr,
+            "{}",
+            interactive_group_line(idx + 1, group, prs[idx].as_ref())
+        )?;
+    }
+    let default_after = merged_bottom_count(&prs);
+    let answer = prompt_line(&format!(
+        "Keep groups through (0-{}) [{}]: ",
+        groups.len(),
+        default_after
+    ))?;
+    let after_count = if answer.is_empty() {
+        default_after
+    } else {
+        answer
+            .parse::<usize>()
+            .with_context(|| format!("`{answer}` is not a group number"))?
+    };
+    if after_count > groups.len() {
+        bail!("Choose a group number between 0 and {}", groups.len());
+    }
+    let after = match after_count {
+        0 => AfterSelector::Bottom,
+        count => AfterSelector::Group(GroupSelector::LocalPr(count)),
+    };
+    let preview = preview_restack_after(metadata_context, &after, safe)?;
+    writeln!(stderr, "{}", render_human_preview("Restack plan", &preview))?;
+    let confirm = prompt_line("Restack now? [y/N] ")?;
+    if confirm.eq_ignore_ascii_case("y") || confirm.eq_ignore_ascii_case("yes") {
+        Ok(Some(after))
+    } else {
+        info!("Restack cancelled; nothing changed.");
+        Ok(None)
+    }
+}
+
+impl RestackPlan {
+    /// Commits in replay order, each paired with the group or ignored block that owns it.
+    fn commits_with_owners(&self) -> Vec<(String, String)> {
+        let mut out = Vec::new();
+        for segment in &self.kept_ignored_segments {
+            out.extend(segment.iter().map(|sha| {
+                (
+                    sha.clone(),
+                    "ignored block kept below the stack".to_string(),
+                )
+            }));
+        }
+        for group in &self.remaining_groups {
+            let handle = group.selector_text();
+            out.extend(
+                group
+                    .commits
+                    .iter()
+                    .map(|sha| (sha.clone(), handle.clone())),
+            );
+            out.extend(
+                group
+                    .ignored_after
+                    .iter()
+                    .map(|sha| (sha.clone(), format!("ignored block after {handle}"))),
+            );
+        }
+        out
+    }
+}
+
+/// Replay `commits` onto `base` in a throwaway worktree and stop at the first conflict.
+///
+/// Returns the number of commits replayed cleanly and, on conflict, the conflicting commit with
+/// its unmerged paths. The temp worktree and branch are always removed; the caller's checkout is
+/// never touched.
+fn trial_replay(
+    base: &str,
+    commits: &[(String, String)],
+) -> Result<(usize, Option<RestackCheckConflict>)> {
+    let (_, short) = common::get_current_branch_and_short()?;
+    let (tmp_path, tmp_branch) =
+        common::create_temp_worktree(ExecutionMode::Apply, "restack-check", base, &short)?;
+    let result = (|| -> Result<(usize, Option<RestackCheckConflict>)> {
+        for (replayed, (sha, owner)) in commits.iter().enumerate() {
+            let status = std::process::Command::new("git")
+                .args([
+                    "-C",
+                    tmp_path.as_str(),
+                    "cherry-pick",
+                    "--allow-empty",
+                    "--keep-redundant-commits",
+                    sha.as_str(),
+                ])
+                .stdout(std::process::Stdio::null())
+                .stderr(std::process::Stdio::null())
+                .status()
+                .context("failed to spawn git cherry-pick")?;
+            if !status.success() {
+                let conflicted_paths =
+                    git_ro(["-C", &tmp_path, "diff", "--name-only", "--diff-filter=U"].as_slice())?
+                        .lines()
+                        .map(str::trim)
+                        .filter(|path| !path.is_empty())
+                        .map(str::to_string)
+                        .collect();
+                let subject = git_ro(["log", "-n", "1", "--format=%s", sha].as_slice())?
+                    .trim()
+                    .to_string();
+                let _ = git_ro(["-C", &tmp_path, "cherry-pick", "--abort"].as_slice());
+                return Ok((
+                    replayed,
+                    Some(RestackCheckConflict {
+                        owner: owner.clone(),
+                        commit: sha.clone(),
+                        subject,
+                        conflicted_paths,
+                    }),
+                ));
+            }
+        }
+        Ok((commits.len(), None))
+    })();
+    common::cleanup_temp_worktree(ExecutionMode::Apply, &tmp_path, &tmp_branch)?;
+    result
+}
+
+/// Trial-run `spr restack --after` in a throwaway worktree and report the first conflict.
+///
+/// This fetches `origin` like a real restack so the prediction matches what `spr restack` would
+/// replay onto, but it never moves the current branch, creates backup tags, or writes resume
+/// state.
+pub fn check_restack_after(
+    metadata_context: &crate::stack_metadata::RefreshMetadataContext,
+    after: &AfterSelector,
+    execution_mode: ExecutionMode,
+) -> Result<RestackCheckData> {
+    fetch_remote(execution_mode)?;
+    let plan = collect_restack_plan(metadata_context, after, true)?;
+    let (current_branch, original_head) = stack_branch_and_head()?;
+    let base_sha = git_rev_parse(&metadata_context.base).ok();
+    let Some(plan) = plan else {
+        return Ok(RestackCheckData {
+            base_ref: metadata_context.base.clone(),
+            base_sha,
+            current_branch,
+            original_head,
+            after_selector: after.to_string(),
+            resolved_after_count: 0,
+            planned_commit_count: 0,
+            replayed_commit_count: 0,
+            conflict: None,
+        });
+    };
+    let commits = plan.commits_with_owners();
+    let (replayed_commit_count, conflict) = if commits.is_empty() {
+        (0, None)
+    } else {
+        trial_replay(&metadata_context.base, &commits)?
+    };
+    Ok(RestackCheckData {
+        base_ref: plan.base_ref,
+        base_sha: plan.base_sha,
+        current_branch,
+        original_head,
+        after_selector: plan.after_selector,
+        resolved_after_count: plan.resolved_after_count,
+        planned_commit_count: commits.len(),
+        replayed_commit_count,
+        conflict,
+    })
+}
+
 fn log_human_restack_plan(
     plan: &RestackPlan,
     safe_requested: bool,
@@ -501,13 +776,116 @@ fn try_fast_suffix_rebase(
     }
 }
 
+/// Restack `branch`, a `--from` stack that is not checked out, by moving its ref.
+///
+/// No worktree is reset, so the dirty-worktree policy does not apply and the native rebase
+/// path is never taken; the replay always runs in a temp worktree.
+fn restack_unchecked_out_branch(
+    metadata_context: &crate::stack_metadata::RefreshMetadataContext,
+    plan: &RestackPlan,
+    options: RestackExecutionOptions,
+    branch: &str,
+) -> Result<RewriteCommandOutcome> {
+    let original_head = plan.original_head.clone();
+    let short = git_ro(["rev-parse", "--short", &original_head].as_slice())?
+        .trim()
+        .to_string();
+    let original_worktree_root = rewrite_resume::current_repo_root()?;
+    let backup_tag = if options.safe {
+        Some(common::create_backup_tag_at(
+            options.execution_mode,
+            "restack",
+            branch,
+            &short,
+            &original_head,
+        )?)
+    } else {
+        None
+    };
+    if plan.remaining_groups.is_empty() && plan.kept_ignored_segments.is_empty() {
+        info!(
+            "Skipping all {} PR(s); moving branch {} to {}",
+            plan.dropped_groups.len(),
+            branch,
+            metadata_context.base
+        );
+        git_rw(
+            options.execution_mode,
+            [
+                "update-ref",
+                &format!("refs/heads/{branch}"),
+                &metadata_context.base,
+                &original_head,
+            ]
+            .as_slice(),
+        )?;
+        if options.execution_mode == ExecutionMode::Apply {
+            crate::stack_metadata::refresh_metadata_for_branch(
+                &original_worktree_root,
+                branch,
+                metadata_context,
+                None,
+            )?;
+        }
+        return Ok(RewriteCommandOutcome::Completed);
+    }
+    let resume_path = rewrite_resume::prepare_resume_path_for_new_session(
+        options.execution_mode,
+        RewriteCommandKind::Restack,
+        branch,
+        &original_head,
+    )?;
+    let (tmp_path, tmp_branch) = common::create_temp_worktree(
+        options.execution_mode,
+        "restack",
+        &metadata_context.base,
+        &short,
+    )?;
+    let outcome = rewrite_resume::run_rewrite_session(
+        options.execution_mode,
+        RewriteSession {
+            command_kind: RewriteCommandKind::Restack,
+            conflict_policy: if options.conflict_policy == RestackConflictPolicy::Rollback {
+                RewriteConflictPolicy::Rollback
+            } else {
+                RewriteConflictPolicy::Suspend
+            },
+            original_worktree_root,
+            original_branch: branch.to_string(),
+            original_head,
+            destination_kind: RewriteDestinationKind::UncheckedOutBranchRef,
+            resume_path,
+            temp_branch: tmp_branch,
+            temp_worktree_path: tmp_path,
+            backup_tag,
+            operations: plan.operations.clone(),
+            deferred_dirty_worktree_restore: common::DeferredDirtyWorktreeRestore::Noop,
+            post_success_hint: None,
+            metadata_refresh_context: Some(metadata_context.clone()),
+        },
+    )?;
+    if outcome == RewriteCommandOutcome::Completed {
+        info!(
+            "Rebased commits after first {} PR(s) of {} onto {} (including ignored commits)",
+            plan.resolved_after_count, branch, metadata_context.base
+        );
+    }
+    Ok(outcome)
+}
+
 fn restack_after_resolved(
     metadata_context: &crate::stack_metadata::RefreshMetadataContext,
     plan: RestackPlan,
     options: RestackExecutionOptions,
 ) -> Result<RewriteCommandOutcome> {
-    let planned_executor = plan.planned_executor(options.execution_mode == ExecutionMode::Apply)?;
+    let unchecked_out_branch = common::unchecked_out_stack_branch()?;
+    let planned_executor = plan.planned_executor(
+        options.execution_mode == ExecutionMode::Apply && unchecked_out_branch.is_none(),
+    )?;
     log_human_restack_plan(&plan, options.safe, planned_executor.clone());
+    if let Some(branch) = unchecked_out_branch {
+        return restack_unchecked_out_branch(metadata_context, &plan, options, &branch);
+    }
 
     common::with_dirty_worktree_policy(
         options.execution_mode,
@@ -670,7 +1048,7 @@ pub fn restack_after(
     conflict_policy: RestackConflictPolicy,
     dirty_worktree_policy: DirtyWorktreePolicy,
 ) -> Result<RewriteCommandOutcome> {
-    git_rw(execution_mode, ["fetch", "origin"].as_slice())?;
+    fetch_remote(execution_mode)?;
 
     if let Some(plan) = collect_restack_plan(metadata_context, after, true)? {
         restack_after_resolved(
@@ -698,7 +1076,7 @@ pub fn restack_after_count(
     conflict_policy: RestackConflictPolicy,
     dirty_worktree_policy: DirtyWorktreePolicy,
 ) -> Result<RewriteCommandOutcome> {
-    git_rw(execution_mode, ["fetch", "origin"].as_slice())?;
+    fetch_remote(execution_mode)?;
 
     if let Some(plan) = collect_restack_plan_after_count(metadata_context, after, true)? {
         restack_after_resolved(
@@ -716,17 +1094,66 @@ pub fn restack_after_count(
     }
 }
 
+/// Rebuild the whole stack on top of `new_base`, as `spr base set` does.
+///
+/// Groups and ignored blocks are selected against the current `metadata_context.base`, so
+/// commits that exist only on the old base stay behind. The replay itself, including conflict
+/// handling and the metadata refresh, is `spr restack --after 0` against the new base.
+///
+/// # Errors
+///
+/// Returns errors from git operations (fetch, worktree creation, cherry-picks, reset).
+pub fn restack_onto_base(
+    metadata_context: &crate::stack_metadata::RefreshMetadataContext,
+    new_base: &str,
+    safe: bool,
+    execution_mode: ExecutionMode,
+    conflict_policy: RestackConflictPolicy,
+    dirty_worktree_policy: DirtyWorktreePolicy,
+) -> Result<RewriteCommandOutcome> {
+    fetch_remote(execution_mode)?;
+    let new_base_sha = git_rev_parse(&format!("{new_base}^{{commit}}"))
+        .with_context(|| format!("new base {new_base} does not resolve to a commit"))?;
+    let new_context = crate::stack_metadata::RefreshMetadataContext {
+        base: new_base.to_string(),
+        ..metadata_context.clone()
+    };
+
+    if let Some(mut plan) = collect_restack_plan_after_count(metadata_context, 0, true)? {
+        plan.base_ref = new_base.to_string();
+        plan.base_sha = Some(new_base_sha);
+        restack_after_resolved(
+            &new_context,
+            plan,
+            RestackExecutionOptions {
+                safe,
+                execution_mode,
+                conflict_policy,
+                dirty_worktree_policy,
+            },
+        )
+    } else {
+        info!(
+            "No local PR groups found; nothing to move onto {}.",
+            new_base
+        );
+        Ok(RewriteCommandOutcome::Completed)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
         build_cherry_pick_plan, build_kept_ignored_segments, build_restack_plan,
-        plan_fast_suffix_rebase, preview_restack_after, resolve_restack_after_count,
+        check_restack_after, interactive_group_line, merged_bottom_count, plan_fast_suffix_rebase,
+        preview_restack_after, resolve_restack_after_count, restack_after_count, restack_onto_base,
     };
     use crate::commands::common::{CherryPickEmptyPolicy, CherryPickOp};
     use crate::commands::rewrite_resume::{resume_rewrite, RewriteResumeState};
     use crate::commands::RewriteCommandOutcome;
     use crate::config::{DirtyWorktreePolicy, RestackConflictPolicy};
     use crate::execution::ExecutionMode;
+    use crate::github::{PrInfoWithState, PrState};
     use crate::parsing::Group;
     use crate::restack_output::RestackExecutorPlan;
     use crate::selectors::{AfterSelector, ExplicitGroupSelector, GroupSelector};
@@ -756,6 +1183,42 @@ mod tests {
             .collect()
     }
 
+    fn pr(number: u64, state: PrState) -> PrInfoWithState {
+        PrInfoWithState {
+            number,
+            head: format!("dank-spr/{number}"),
+            base: "main".to_string(),
+            state,
+            url: format!("https://github.com/o/r/pull/{number}"),
+        }
+    }
+
+    #[test]
+    fn interactive_menu_shows_pr_states_and_defaults_to_the_merged_bottom() {
+        let mut groups = groups(&["alpha", "beta", "gamma"]);
+        groups[0].first_message =
+            Some("feat: alpha pr:alpha\n\nExplain why alpha exists.\n".to_string());
+        let prs = vec![
+            Some(pr(11, PrState::Merged)),
+            Some(pr(12, PrState::Open)),
+            None,
+        ];
+        assert_eq!(
+            interactive_group_line(1, &groups[0], prs[0].as_ref()),
+            "   1. pr:alpha (#11 merged) feat: alpha"
+        );
+        assert_eq!(
+            interactive_group_line(2, &groups[1], prs[1].as_ref()),
+            "   2. pr:beta (#12 open) feat: beta"
+        );
+        assert_eq!(
+            interactive_group_line(3, &groups[2], prs[2].as_ref()),
+            "   3. pr:gamma (no PR) feat: gamma"
+        );
+        assert_eq!(merged_bottom_count(&prs), 1);
+        assert_eq!(merged_bottom_count(&prs[1..]), 0);
+    }
+
     #[test]
     fn restack_after_stable_handle_keeps_that_group_and_lower_groups() {
         let groups = groups(&["alpha", "beta", "gamma"]);
@@ -892,6 +1355,97 @@ mod tests {
         dir
     }
 
+    #[test]
+    fn restack_check_reports_conflicting_group_and_leaves_branch_untouched() {
+        let _lock = lock_cwd();
+        let dir = init_restack_conflict_repo();
+        let repo = dir.path().join("repo");
+        let _guard = DirGuard::change_to(&repo);
+        let before = read_preview_side_effect_snapshot(&repo);
+
+        let data = check_restack_after(
+            &metadata_context(),
+            &AfterSelector::Bottom,
+            ExecutionMode::Apply,
+        )
+        .unwrap();
+
+        assert_eq!(data.planned_commit_count, 2);
+        assert_eq!(data.replayed_commit_count, 1);
+        let conflict = data.conflict.expect("beta conflicts with the base update");
+        assert_eq!(conflict.owner, "pr:beta");
+        assert_eq!(conflict.subject, "feat: beta pr:beta");
+        assert_eq!(conflict.conflicted_paths, vec!["story.txt".to_string()]);
+        assert_eq!(read_preview_side_effect_snapshot(&repo), before);
+    }
+
+    #[test]
+    fn restack_onto_base_moves_stack_without_old_base_commits() {
+        let _lock = lock_cwd();
+        let dir = init_restack_conflict_repo();
+        let repo = dir.path().join("repo");
+        git(&repo, ["checkout", "-b", "release", "main~1"].as_slice());
+        commit_file(&repo, "release.txt", "2.0\n", "chore: cut release");
+        git(&repo, ["checkout", "stack"].as_slice());
+        git(&repo, ["reset", "--hard", "HEAD~1"].as_slice());
+        git(&repo, ["rebase", "main"].as_slice());
+        let _guard = DirGuard::change_to(&repo);
+
+        let outcome = restack_onto_base(
+            &metadata_context(),
+            "release",
+            false,
+            ExecutionMode::Apply,
+            RestackConflictPolicy::Halt,
+            DirtyWorktreePolicy::Halt,
+        )
+        .unwrap();
+
+        assert_eq!(outcome, RewriteCommandOutcome::Completed);
+        assert_eq!(
+            git(&repo, ["log", "--format=%s", "release..stack"].as_slice()).trim(),
+            "feat: alpha pr:alpha"
+        );
+        assert_eq!(
+            git(&repo, ["rev-parse", "stack~1"].as_slice()),
+            git(&repo, ["rev-parse", "release"].as_slice())
+        );
+    }
+
+    #[test]
+    fn restack_from_unchecked_out_branch_moves_it_by_ref() {
+        let _lock = lock_cwd();
+        let dir = init_restack_conflict_repo();
+        let repo = dir.path().join("repo");
+        git(&repo, ["reset", "--hard", "HEAD~1"].as_slice());
+        git(&repo, ["checkout", "main"].as_slice());
+        let main_head = git(&repo, ["rev-parse", "HEAD"].as_slice());
+        let _guard = DirGuard::change_to(&repo);
+        let _from = crate::parsing::STACK_FROM.scoped("stack".to_string());
+
+        let outcome = restack_after_count(
+            &metadata_context(),
+            0,
+            false,
+            ExecutionMode::Apply,
+            RestackConflictPolicy::Halt,
+            DirtyWorktreePolicy::Halt,
+        )
+        .unwrap();
+
+        assert_eq!(outcome, RewriteCommandOutcome::Completed);
+        assert_eq!(
+            git(&repo, ["log", "--format=%s", "main..stack"].as_slice()).trim(),
+            "feat: alpha pr:alpha"
+        );
+        assert_eq!(git(&repo, ["rev-parse", "stack~1"].as_slice()), main_head);
+        assert_eq!(
+            git(&repo, ["symbolic-ref", "--short", "HEAD"].as_slice()).trim(),
+            "main"
+        );
+        assert_eq!(git(&repo, ["rev-parse", "HEAD"].as_slice()), main_head);
+    }
+
     fn init_restack_preview_repo() -> TempDir {
         let dir = tempfile::tempdir().expect("create temp dir");
         let repo = dir.path().join("repo");
@@ -1237,7 +1791,7 @@ mod tests {
                 &repo,
                 [
                     "rev-parse",
-                    &format!("backup/restack/stack-{original_short}")
+                    &format!("backup/restack/stack-{original_short}^{{commit}}")
                 ]
                 .as_slice()
             )
@@ -0,0 +1,125 @@
+//! Remind requested reviewers about stack PRs that have waited too long for review.
+//!
+//! With `review_ping_after_hours` set, `spr update` comments on each open, ready PR that has been
+//! awaiting review (since its last push or ready-for-review event) for at least that long,
+//! mentioning its requested reviewers. The comment carries a hidden marker with the start of the
+//! wait, so each PR is pinged at most once per push.
+
+use anyhow::Result;
+use time::OffsetDateTime;
+use tracing::{info, warn};
+
+use crate::execution::ExecutionMode;
+use crate::git::gh_rw;
+use crate::github::{fetch_pr_ci_review_status, fetch_pr_issue_comment_bodies_graphql};
+
+fn ping_marker(since: &str) -> String {
+    format!("<!-- spr-review-ping since={since} -->")
+}
+
+fn ping_comment(reviewers: &[String], waited_hours: i64, since: &str) -> String {
+    let mentions: Vec<String> = reviewers
+        .iter()
+        .map(|reviewer| format!("@{reviewer}"))
+        .collect();
+    format!(
+        "{} this PR has been waiting for review for {} hours; could you take a look?\n\n{}",
+        mentions.join(" "),
+        waited_hours,
+        ping_marker(since)
+    )
+}
+
+/// Comment on each PR in `numbers` awaiting review for at least `after_hours`, unless this wait
+/// was already pinged. Returns how many PRs were pinged.
+///
+/// # Errors
+///
+/// Returns errors from the GitHub status or comment queries, or from posting a comment.
+pub fn ping_stale_reviews(
+    numbers: &[u64],
+    after_hours: u32,
+    execution_mode: ExecutionMode,
+) -> Result<usize> {
+    if numbers.is_empty() {
+        return Ok(0);
+    }
+    let statuses = fetch_pr_ci_review_status(numbers)?;
+    let now = OffsetDateTime::now_utc();
+    let mut pinged = 0;
+    for number in numbers {
+        let Some(status) = statuses.get(number) else {
+            continue;
+        };
+        let (Some(since), Some(waited)) = (
+            status.awaiting_review_since.as_deref(),
+            status.awaiting_review_for(now),
+        ) else {
+            continue;
+        };
+        if status.requested_reviewers.is_empty() || waited.whole_hours() < i64::from(after_hours) {
+            continue;
+        }
+        let marker = ping_marker(since);
+        if fetch_pr_issue_comment_bodies_graphql(*number)?
+            .iter()
+            .any(|body| body.contains(&marker))
+        {
+            continue;
+        }
+        info!(
+            "Pinging {} on PR #{} ({}h awaiting review)",
+            status.requested_reviewers.join(", "),
+            number,
+            waited.whole_hours()
+        );
+        gh_rw(
+            execution_mode,
+            [
+                "pr",
+                "comment",
+                &number.to_string(),
+                "--body",
+                &ping_comment(&status.requested_reviewers, waited.whole_hours(), since),
+            ]
+            .as_slice(),
+        )?;
+        pinged += 1;
+    }
+    Ok(pinged)
+}
+
+/// [`ping_stale_reviews`] after `spr update` when `review_ping_after_hours` is set.
+///
+/// Best-effort: failures are logged as warnings because the calling command already succeeded.
+pub fn ping_stale_reviews_if_configured(
+    numbers: &[u64],
+    after_hours: u32,
+    execution_mode: ExecutionMode,
+) {
+    if after_hours == 0 {
+        return;
+    }
+    if let Err(err) = ping_stale_reviews(numbers, after_hours, execution_mode) {
+        warn!("Failed to ping reviewers of stale PRs: {err:#}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ping_comment, ping_marker};
+
+    #[test]
+    fn ping_comment_mentions_reviewers_and_marks_the_wait() {
+        let comment = ping_comment(
+            &["octocat".to_string(), "acme/reviewers".to_string()],
+            50,
+            "2026-03-01T10:00:00Z",
+        );
+        assert!(comment.starts_with(
+            "@octocat @acme/reviewers this PR has been waiting for review for 50 hours"
+        ));
+        assert!(comment.ends_with(&ping_marker("2026-03-01T10:00:00Z")));
+        assert!(!comment.contains(&ping_marker("2026-03-02T10:00:00Z")));
+    }
+}
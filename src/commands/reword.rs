@@ -0,0 +1,631 @@
+//! Rewrite the first commit message of one PR group.
+//!
+//! `spr edit` opens the message in the Git editor. The group's first commit is copied with the new
+//! message (same tree, parent, and author) and everything above it is replayed through the usual
+//! rewrite session, so groups below the target keep their commit IDs. The group marker is kept:
+//! when the new message drops it, it is appended as the last line; a different marker is
+//! rejected because it would silently retarget the group to another PR.
+//!
+//! `spr title` is the one-shot variant: it swaps only the subject, keeping the marker and
+//! directive tokens the old subject carried, and then updates the open PR's title directly
+//! because `spr update` never edits titles of existing PRs. `spr describe` does the same for the
+//! body: it edits the open PR's description (or the commit body when no PR exists yet), writes it
+//! back into the commit, and updates the PR description above the untouched stack block.
+
+use anyhow::{bail, Context, Result};
+use std::process::Command;
+use tracing::info;
+
+use crate::commands::common;
+use crate::commands::common::CherryPickOp;
+use crate::commands::rewrite_resume::{
+    self, RewriteCommandKind, RewriteCommandOutcome, RewriteConflictPolicy, RewriteDestinationKind,
+    RewriteSession,
+};
+use crate::commands::update::STACK_BLOCK_START;
+use crate::config::DirtyWorktreePolicy;
+use crate::execution::ExecutionMode;
+use crate::git::{git_rev_list_range, git_rev_parse, git_ro};
+use crate::parsing::{derive_local_groups, Group};
+use crate::selectors::{resolve_group_ordinal, GroupSelector};
+
+/// Result of a reword, including the resolved target so callers can scope a follow-up update.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RewordOutcome {
+    /// 1-based local PR number of the reworded group.
+    pub local_pr_number: usize,
+    pub rewrite: RewriteCommandOutcome,
+}
+
+/// Normalize an edited message and make sure it still carries `group`'s marker.
+fn finalize_message(group: &Group, edited: &str) -> Result<String> {
+    let message = edited.trim();
+    if message.is_empty() {
+        bail!("Aborting: the new commit message is empty.");
+    }
+    match crate::group_markers::first_valid_group_marker(message) {
+        Some(found) if found == group.marker => Ok(message.to_string()),
+        Some(found) => bail!(
+            "The new message carries `{}` but the group is `{}`; keep the group marker unchanged.",
+            found,
+            group.selector_text()
+        ),
+        None => Ok(format!("{}\n\n{}", message, group.selector_text())),
+    }
+}
+
+/// Replace the first line of `message` with `title`, carrying over marker and directive tokens.
+fn retitled_message(message: &str, title: &str) -> Result<String> {
+    let title = title.trim();
+    if title.is_empty() {
+        bail!("The new title is empty.");
+    }
+    if !crate::group_markers::candidate_group_markers(title).is_empty() {
+        bail!("The new title must not contain a group marker; the existing one is kept.");
+    }
+    let (subject, rest) = message.split_once('\n').unwrap_or((message, ""));
+    let mut subject_parts = vec![title.to_string()];
+    subject_parts.extend(
+        subject
+            .split_whitespace()
+            .filter(|token| {
+                crate::group_markers::first_valid_group_marker(token).is_some()
+                    || crate::group_directives::has_draft_directive(token)
+            })
+            .map(str::to_string),
+    );
+    Ok(format!("{}\n{}", subject_parts.join(" "), rest))
+}
+
+/// Replace everything below the subject of `message` with `body`, keeping marker and directive
+/// tokens the old body carried (on their own last line) unless `body` already has them.
+pub(crate) fn described_message(message: &str, body: &str) -> String {
+    let (subject, rest) = message.split_once('\n').unwrap_or((message, ""));
+    let body = body.trim();
+    let kept: Vec<&str> = rest
+        .split_whitespace()
+        .filter(|token| {
+            crate::group_markers::first_valid_group_marker(token).is_some()
+                || crate::group_directives::has_draft_directive(token)
+        })
+        .filter(|token| !body.split_whitespace().any(|word| word == *token))
+        .collect();
+    let mut sections = vec![subject.trim_end().to_string()];
+    if !body.is_empty() {
+        sections.push(body.to_string());
+    }
+    if !kept.is_empty() {
+        sections.push(kept.join(" "));
+    }
+    sections.join("\n\n")
+}
+
+/// Split a PR body into the description and the spr stack block (with everything after it).
+fn split_pr_body(body: &str) -> (&str, &str) {
+    match body.find(STACK_BLOCK_START) {
+        Some(start) => (body[..start].trim(), &body[start..]),
+        None => (body.trim(), ""),
+    }
+}
+
+/// Open `initial` in the Git editor (`GIT_EDITOR`, `core.editor`, `VISUAL`, `EDITOR`) and return
+/// the saved text.
+pub fn edit_text_in_editor(initial: &str, file_name: &str) -> Result<String> {
+    let editor = git_ro(["var", "GIT_EDITOR"].as_slice())?.trim().to_string();
+    let path = git_ro(["rev-parse", "--git-path", file_name].as_slice())?
+        .trim()
+        .to_string();
+    std::fs::write(&path, initial).with_context(|| format!("Failed to write {path}"))?;
+    let status = Command::new("sh")
+        .args(["-c", &format!("{editor} \"$@\""), &editor, &path])
+        .status()
+        .with_context(|| format!("Failed to launch editor `{editor}`"))?;
+    if !status.success() {
+        bail!("Editor `{}` exited with {}; aborting.", editor, status);
+    }
+    let edited =
+        std::fs::read_to_string(&path).with_context(|| format!("Failed to read {path}"))?;
+    let _ = std::fs::remove_file(&path);
+    Ok(edited)
+}
+
+/// Replace the first commit message of `target` with `new_message(group)` and replay the stack
+/// above it.
+///
+/// `new_message` returns `None` to leave the stack untouched. The result is checked by
+/// [`finalize_message`] before anything is rewritten.
+///
+/// # Errors
+///
+/// Returns errors when the target does not resolve, the new message is empty or changes the
+/// group marker, or Git operations fail.
+pub fn reword_group(
+    metadata_context: &crate::stack_metadata::RefreshMetadataContext,
+    target: &GroupSelector,
+    command_kind: RewriteCommandKind,
+    new_message: impl FnOnce(&Group, &str) -> Result<Option<String>>,
+    safe: bool,
+    execution_mode: ExecutionMode,
+    dirty_worktree_policy: DirtyWorktreePolicy,
+) -> Result<RewordOutcome> {
+    let (_merge_base, groups) =
+        derive_local_groups(&metadata_context.base, &metadata_context.ignore_tag)?;
+    if groups.is_empty() {
+        bail!("No local PR groups found; nothing to edit.");
+    }
+    let local_pr_number = resolve_group_ordinal(&groups, target)?;
+    let group = &groups[local_pr_number - 1];
+    let first = group.commits[0].clone();
+    let current = git_ro(["log", "-n", "1", "--format=%B", &first].as_slice())?;
+    let unchanged = RewordOutcome {
+        local_pr_number,
+        rewrite: RewriteCommandOutcome::Completed,
+    };
+    let Some(edited) = new_message(group, &current)? else {
+        return Ok(unchanged);
+    };
+    let message = finalize_message(group, &edited)?;
+    if message == current.trim() {
+        info!(
+            "Commit message of {} is unchanged; nothing to rewrite.",
+            group.selector_text()
+        );
+        return Ok(unchanged);
+    }
+
+    let rewrite = replay_with_new_message(
+        metadata_context,
+        &first,
+        &message,
+        command_kind,
+        &format!("Rewording {}", group.selector_text()),
+        safe,
+        execution_mode,
+        dirty_worktree_policy,
+    )?;
+    Ok(RewordOutcome {
+        local_pr_number,
+        rewrite,
+    })
+}
+
+/// Copy `commit` with `message` (same tree, parent, and author) and replay everything above it
+/// onto the copy through a rewrite session.
+///
+/// # Errors
+///
+/// Returns errors from the dirty-worktree policy or Git operations.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn replay_with_new_message(
+    metadata_context: &crate::stack_metadata::RefreshMetadataContext,
+    commit: &str,
+    message: &str,
+    command_kind: RewriteCommandKind,
+    action: &str,
+    safe: bool,
+    execution_mode: ExecutionMode,
+    dirty_worktree_policy: DirtyWorktreePolicy,
+) -> Result<RewriteCommandOutcome> {
+    let reworded = common::commit_with_message(commit, message)?;
+    replay_with_replacement(
+        metadata_context,
+        commit,
+        &reworded,
+        &[],
+        command_kind,
+        action,
+        safe,
+        execution_mode,
+        dirty_worktree_policy,
+    )
+}
+
+/// Replay `replacement` in place of `commit`, then everything above `commit` except `dropped`,
+/// through a rewrite session based at `commit^`.
+///
+/// # Errors
+///
+/// Returns errors from the dirty-worktree policy or Git operations.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn replay_with_replacement(
+    metadata_context: &crate::stack_metadata::RefreshMetadataContext,
+    commit: &str,
+    replacement: &str,
+    dropped: &[String],
+    command_kind: RewriteCommandKind,
+    action: &str,
+    safe: bool,
+    execution_mode: ExecutionMode,
+    dirty_worktree_policy: DirtyWorktreePolicy,
+) -> Result<RewriteCommandOutcome> {
+    let above = git_rev_list_range(commit, "HEAD")?;
+    // The replacement is not the parent of `above[0]`, so it cannot open a range.
+    let mut operations: Vec<CherryPickOp> = CherryPickOp::from_commits(&[replacement.to_string()])
+        .into_iter()
+        .collect();
+    for run in above.split(|sha| dropped.contains(sha)) {
+        operations.extend(CherryPickOp::from_commits(run));
+    }
+    let replayed = above.iter().filter(|sha| !dropped.contains(sha)).count();
+    replay_operations(
+        metadata_context,
+        &format!("{commit}^"),
+        operations,
+        command_kind,
+        &format!("{} and replaying {} commit(s) above it.", action, replayed),
+        safe,
+        execution_mode,
+        dirty_worktree_policy,
+    )
+}
+
+/// Run `operations` through a rewrite session based at `session_base` that replaces the checked
+/// out branch, logging `message` once the temp worktree is ready.
+///
+/// # Errors
+///
+/// Returns errors from the dirty-worktree policy or Git operations.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn replay_operations(
+    metadata_context: &crate::stack_metadata::RefreshMetadataContext,
+    session_base: &str,
+    operations: Vec<CherryPickOp>,
+    command_kind: RewriteCommandKind,
+    message: &str,
+    safe: bool,
+    execution_mode: ExecutionMode,
+    dirty_worktree_policy: DirtyWorktreePolicy,
+) -> Result<RewriteCommandOutcome> {
+    let command_name = command_kind.command_name();
+    common::with_dirty_worktree_policy(
+        execution_mode,
+        command_name,
+        dirty_worktree_policy,
+        |deferred_dirty_worktree_restore| {
+            let (cur_branch, short) = common::get_current_branch_and_short()?;
+            let original_head = git_rev_parse("HEAD")?;
+            let original_worktree_root = rewrite_resume::current_repo_root()?;
+            let resume_path = rewrite_resume::prepare_resume_path_for_new_session(
+                execution_mode,
+                command_kind,
+                &cur_branch,
+                &original_head,
+            )?;
+            let backup_tag = if safe {
+                Some(common::create_backup_tag(
+                    execution_mode,
+                    command_kind.resume_slug(),
+                    &cur_branch,
+                    &short,
+                )?)
+            } else {
+                None
+            };
+            let (tmp_path, tmp_branch) = common::create_temp_worktree(
+                execution_mode,
+                command_kind.resume_slug(),
+                session_base,
+                &short,
+            )?;
+            info!("{}", message);
+            rewrite_resume::run_rewrite_session(
+                execution_mode,
+                RewriteSession {
+                    command_kind,
+                    conflict_policy: RewriteConflictPolicy::Suspend,
+                    original_worktree_root,
+                    original_branch: cur_branch,
+                    original_head,
+                    destination_kind: RewriteDestinationKind::CheckedOutBranch,
+                    resume_path,
+                    temp_branch: tmp_branch,
+                    temp_worktree_path: tmp_path,
+                    backup_tag,
+                    operations,
+                    deferred_dirty_worktree_restore,
+                    post_success_hint: None,
+                    metadata_refresh_context: Some(metadata_context.clone()),
+                },
+            )
+        },
+    )
+}
+
+/// Edit the first commit message of `target` in the Git editor.
+///
+/// # Errors
+///
+/// See [`reword_group`]; also fails when the editor exits unsuccessfully.
+pub fn edit_group_message(
+    metadata_context: &crate::stack_metadata::RefreshMetadataContext,
+    target: &GroupSelector,
+    safe: bool,
+    execution_mode: ExecutionMode,
+    dirty_worktree_policy: DirtyWorktreePolicy,
+) -> Result<RewordOutcome> {
+    reword_group(
+        metadata_context,
+        target,
+        RewriteCommandKind::Edit,
+        |_group, current| edit_text_in_editor(current, "SPR_EDITMSG").map(Some),
+        safe,
+        execution_mode,
+        dirty_worktree_policy,
+    )
+}
+
+fn open_pr_for_group(group: &Group, prefix: &str) -> Result<Option<crate::github::PrInfo>> {
+    let head = crate::branch_names::group_branch_name(prefix, group);
+    let pr = crate::github::list_open_prs_for_heads(std::slice::from_ref(&head))?
+        .into_iter()
+        .next();
+    if pr.is_none() {
+        info!("{} has no open PR yet; `spr update` will create it.", head);
+    }
+    Ok(pr)
+}
+
+/// Set the open PR title of group `local_pr_number` to `title` (or the group's derived title),
+/// with the configured title transforms applied.
+///
+/// Returns the edited PR number, or `None` when the group has no open PR yet.
+///
+/// # Errors
+///
+/// Returns errors from stack discovery or GitHub queries.
+pub fn sync_pr_title(
+    metadata_context: &crate::stack_metadata::RefreshMetadataContext,
+    local_pr_number: usize,
+    title: Option<&str>,
+    title_transforms: &crate::title_transforms::TitleTransformConfig,
+    execution_mode: ExecutionMode,
+) -> Result<Option<u64>> {
+    let (_merge_base, groups) =
+        derive_local_groups(&metadata_context.base, &metadata_context.ignore_tag)?;
+    let Some(group) = groups.get(local_pr_number - 1) else {
+        return Ok(None);
+    };
+    let Some(pr) = open_pr_for_group(group, &metadata_context.prefix)? else {
+        return Ok(None);
+    };
+    let title = match title {
+        Some(title) => title_transforms.apply(title.trim(), group.bare_selector_text())?,
+        None => group.pr_title_with(title_transforms)?,
+    };
+    crate::github::edit_pr_title(pr.number, &title, execution_mode)?;
+    if execution_mode == ExecutionMode::DryRun {
+        info!("DRY-RUN: would retitle PR #{} to \"{}\".", pr.number, title);
+    } else {
+        info!("Retitled PR #{} to \"{}\".", pr.number, title);
+    }
+    Ok(Some(pr.number))
+}
+
+/// Replace the subject of `target`'s first commit with `title`.
+///
+/// # Errors
+///
+/// See [`reword_group`]; also fails when `title` is empty or carries a group marker.
+pub fn retitle_group(
+    metadata_context: &crate::stack_metadata::RefreshMetadataContext,
+    target: &GroupSelector,
+    title: &str,
+    safe: bool,
+    execution_mode: ExecutionMode,
+    dirty_worktree_policy: DirtyWorktreePolicy,
+) -> Result<RewordOutcome> {
+    reword_group(
+        metadata_context,
+        target,
+        RewriteCommandKind::Title,
+        |_group, current| retitled_message(current, title).map(Some),
+        safe,
+        execution_mode,
+        dirty_worktree_policy,
+    )
+}
+
+/// Edit the description of `target` in the Git editor and write it to both the first commit
+/// body and the open PR.
+///
+/// The buffer starts from the open PR's description without the stack block, or from the commit
+/// body when the group has no open PR. The PR keeps its stack block below the new description.
+///
+/// # Errors
+///
+/// See [`reword_group`]; also fails when the editor exits unsuccessfully or GitHub rejects the
+/// body edit.
+pub fn describe_group(
+    metadata_context: &crate::stack_metadata::RefreshMetadataContext,
+    target: &GroupSelector,
+    safe: bool,
+    execution_mode: ExecutionMode,
+    dirty_worktree_policy: DirtyWorktreePolicy,
+) -> Result<RewordOutcome> {
+    let mut pr_edit: Option<(u64, String)> = None;
+    let outcome = reword_group(
+        metadata_context,
+        target,
+        RewriteCommandKind::Describe,
+        |group, current| {
+            let pr = open_pr_for_group(group, &metadata_context.prefix)?;
+            let (description, stack_block) = match &pr {
+                Some(pr) => {
+                    let body = crate::github::fetch_pr_bodies_graphql(&[pr.number])?
+                        .remove(&pr.number)
+                        .map(|info| info.body)
+                        .unwrap_or_default();
+                    let (description, stack_block) = split_pr_body(&body);
+                    (description.to_string(), stack_block.to_string())
+                }
+                None => (group.pr_body_base()?, String::new()),
+            };
+            let edited = edit_text_in_editor(&format!("{description}\n"), "SPR_DESCRIBE.md")?;
+            let edited = edited.trim();
+            if edited == description {
+                info!("Description is unchanged; nothing to rewrite.");
+                return Ok(None);
+            }
+            if let Some(pr) = pr {
+                let body = if edited.is_empty() {
+                    stack_block
+                } else if stack_block.is_empty() {
+                    edited.to_string()
+                } else {
+                    format!("{edited}\n\n{stack_block}")
+                };
+                pr_edit = Some((pr.number, body));
+            }
+            Ok(Some(described_message(current, edited)))
+        },
+        safe,
+        execution_mode,
+        dirty_worktree_policy,
+    )?;
+    if outcome.rewrite == RewriteCommandOutcome::Completed {
+        if let Some((number, body)) = pr_edit {
+            crate::github::edit_pr_body(number, &body, execution_mode)?;
+            if execution_mode == ExecutionMode::DryRun {
+                info!("DRY-RUN: would update the description of PR #{}.", number);
+            } else {
+                info!("Updated the description of PR #{}.", number);
+            }
+        }
+    }
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        described_message, finalize_message, retitled_message, reword_group, split_pr_body,
+    };
+    use crate::commands::{RewriteCommandKind, RewriteCommandOutcome};
+    use crate::config::DirtyWorktreePolicy;
+    use crate::execution::ExecutionMode;
+    use crate::group_markers::GroupMarker;
+    use crate::parsing::Group;
+    use crate::selectors::GroupSelector;
+    use crate::test_support::{commit_file, git, init_repo, lock_cwd, log_subjects, DirGuard};
+
+    fn group(tag: &str) -> Group {
+        Group {
+            marker: GroupMarker::PrLabel(tag.to_string()),
+            subjects: vec![format!("feat: {tag} pr:{tag}")],
+            commits: vec!["a1".to_string()],
+            first_message: None,
+            ignored_after: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn finalize_message_keeps_or_restores_the_group_marker() {
+        let alpha = group("alpha");
+        assert_eq!(
+            finalize_message(&alpha, "feat: renamed pr:alpha\n\nBody.\n").unwrap(),
+            "feat: renamed pr:alpha\n\nBody."
+        );
+        assert_eq!(
+            finalize_message(&alpha, "feat: renamed\n").unwrap(),
+            "feat: renamed\n\npr:alpha"
+        );
+        assert!(finalize_message(&alpha, "feat: renamed pr:beta").is_err());
+        assert!(finalize_message(&alpha, "  \n").is_err());
+    }
+
+    #[test]
+    fn retitled_message_keeps_marker_and_directive_tokens() {
+        assert_eq!(
+            retitled_message("feat: old pr:alpha spr-draft\n\nBody.\n", "feat: new").unwrap(),
+            "feat: new pr:alpha spr-draft\n\nBody.\n"
+        );
+        assert_eq!(
+            retitled_message("feat: old\n\npr:alpha\n", " feat: new ").unwrap(),
+            "feat: new\n\npr:alpha\n"
+        );
+        assert!(retitled_message("feat: old pr:alpha", "feat: new pr:beta").is_err());
+        assert!(retitled_message("feat: old pr:alpha", " ").is_err());
+    }
+
+    #[test]
+    fn described_message_replaces_body_and_keeps_tokens() {
+        assert_eq!(
+            described_message(
+                "feat: a\n\nOld body.\n\npr:alpha spr-draft\n",
+                "New body.\n"
+            ),
+            "feat: a\n\nNew body.\n\npr:alpha spr-draft"
+        );
+        assert_eq!(
+            described_message("feat: a pr:alpha\n\nOld body.", ""),
+            "feat: a pr:alpha"
+        );
+        assert_eq!(
+            described_message("feat: a\n\npr:alpha", "Body pr:alpha"),
+            "feat: a\n\nBody pr:alpha"
+        );
+    }
+
+    #[test]
+    fn split_pr_body_separates_stack_block() {
+        assert_eq!(
+            split_pr_body("Why.\n\n<!-- spr-stack:start -->\n- #1\n<!-- spr-stack:end -->"),
+            (
+                "Why.",
+                "<!-- spr-stack:start -->\n- #1\n<!-- spr-stack:end -->"
+            )
+        );
+        assert_eq!(split_pr_body(" Why.\n"), ("Why.", ""));
+    }
+
+    #[test]
+    fn reword_rewrites_message_and_keeps_lower_groups() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path().to_path_buf();
+        git(&repo, ["checkout", "-b", "stack"].as_slice());
+        let alpha = commit_file(&repo, "alpha.txt", "a\n", "feat: alpha pr:alpha");
+        commit_file(&repo, "beta.txt", "b\n", "feat: beta pr:beta");
+        commit_file(&repo, "beta2.txt", "b\n", "feat: beta follow-up");
+        let _guard = DirGuard::change_to(&repo);
+
+        let outcome = reword_group(
+            &crate::stack_metadata::RefreshMetadataContext {
+                base: "main".to_string(),
+                prefix: "dank-spr/".to_string(),
+                ignore_tag: "ignore".to_string(),
+            },
+            &GroupSelector::LocalPr(2),
+            RewriteCommandKind::Edit,
+            |_group, current| {
+                Ok(Some(
+                    current.replace("feat: beta pr:beta", "feat: better beta"),
+                ))
+            },
+            false,
+            ExecutionMode::Apply,
+            DirtyWorktreePolicy::Halt,
+        )
+        .unwrap();
+
+        assert_eq!(outcome.local_pr_number, 2);
+        assert_eq!(outcome.rewrite, RewriteCommandOutcome::Completed);
+        assert_eq!(
+            log_subjects(&repo, 3),
+            vec![
+                "feat: beta follow-up".to_string(),
+                "feat: better beta".to_string(),
+                "feat: alpha pr:alpha".to_string(),
+            ]
+        );
+        assert_eq!(
+            git(
+                &repo,
+                ["log", "-n", "1", "--format=%B", "HEAD~1"].as_slice()
+            )
+            .trim_end(),
+            "feat: better beta\n\npr:beta"
+        );
+        assert_eq!(git(&repo, ["rev-parse", "HEAD~2"].as_slice()).trim(), alpha);
+    }
+}
@@ -63,26 +63,53 @@ pub enum RewriteCommandKind {
     Move,
     FixPr,
     AdoptPrefix,
+    ImportPr,
+    Edit,
+    Title,
+    Describe,
+    Split,
+    Fold,
+    Amend,
+    SquashRange,
+    Pull,
 }
 
 impl RewriteCommandKind {
-    fn resume_slug(self) -> &'static str {
+    pub fn resume_slug(self) -> &'static str {
         match self {
             Self::Restack => "restack",
             Self::Absorb => "absorb",
             Self::Move => "move",
             Self::FixPr => "fix-pr",
             Self::AdoptPrefix => "adopt-prefix",
+            Self::ImportPr => "import-pr",
+            Self::Edit => "edit",
+            Self::Title => "title",
+            Self::Describe => "describe",
+            Self::Split => "split",
+            Self::Fold => "fold",
+            Self::Amend => "amend",
+            Self::SquashRange => "squash-range",
+            Self::Pull => "pull",
         }
     }
 
-    fn command_name(self) -> &'static str {
+    pub fn command_name(self) -> &'static str {
         match self {
             Self::Restack => "spr restack",
             Self::Absorb => "spr absorb",
             Self::Move => "spr move",
             Self::FixPr => "spr fix-pr",
             Self::AdoptPrefix => "spr adopt-prefix",
+            Self::ImportPr => "spr import-pr",
+            Self::Edit => "spr edit",
+            Self::Title => "spr title",
+            Self::Describe => "spr describe",
+            Self::Split => "spr split",
+            Self::Fold => "spr fold",
+            Self::Amend => "spr amend",
+            Self::SquashRange => "spr squash-range",
+            Self::Pull => "spr pull",
         }
     }
 }
@@ -330,6 +357,80 @@ pub fn resume_rewrite(path: &Path) -> Result<RewriteCommandOutcome> {
     )
 }
 
+/// The resume file of the only suspended rewrite in this repository, for `spr resume` (or
+/// `spr continue`) and `spr abort` without a path.
+pub fn pending_resume_path() -> Result<PathBuf> {
+    let resume_dir = git_common_dir()?.join("spr").join("resume");
+    let mut paths: Vec<PathBuf> = fs::read_dir(&resume_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                .collect()
+        })
+        .unwrap_or_default();
+    paths.sort();
+    match paths.as_slice() {
+        [] => bail!("no suspended rewrite found under {}", resume_dir.display()),
+        [path] => Ok(path.clone()),
+        _ => bail!(
+            "several suspended rewrites exist; pass the resume file to use:\n{}",
+            paths
+                .iter()
+                .map(|path| format!("  {}", path.display()))
+                .collect::<Vec<_>>()
+                .join("\n")
+        ),
+    }
+}
+
+/// Discard a suspended rewrite: abort its paused cherry-pick, remove its temp worktree, temp
+/// branch, and resume file, and restore any deferred auto-stash.
+///
+/// The original branch only moves when a rewrite finishes, so it is left exactly as it was before
+/// the rewrite started.
+pub fn abort_rewrite(path: &Path) -> Result<()> {
+    let resume_path = absolute_path(path)?;
+    let state = read_resume_state(&resume_path)?;
+    validate_resume_state_against_current_repo(&resume_path, &state)?;
+    if Path::new(&state.temp_worktree_path).exists() {
+        if cherry_pick_head_exists(&state.temp_worktree_path) {
+            abort_cherry_pick_best_effort(ExecutionMode::Apply, &state.temp_worktree_path);
+        }
+        cleanup_temp_state_best_effort(
+            ExecutionMode::Apply,
+            &state.temp_worktree_path,
+            &state.temp_branch,
+        );
+    } else {
+        let _ = git_rw(ExecutionMode::Apply, ["worktree", "prune"].as_slice());
+        if let Err(err) = git_rw(
+            ExecutionMode::Apply,
+            ["branch", "-D", &state.temp_branch].as_slice(),
+        ) {
+            warn!(
+                "Failed to delete temp branch {}: {}",
+                state.temp_branch, err
+            );
+        }
+    }
+    let restore_result = state
+        .deferred_dirty_worktree_restore
+        .clone()
+        .restore_after_abort(
+            ExecutionMode::Apply,
+            state.command_kind.command_name(),
+            &state.original_worktree_root,
+        );
+    remove_resume_file_if_exists(ExecutionMode::Apply, &resume_path)?;
+    info!(
+        "Discarded the suspended {}; {} is unchanged.",
+        state.command_kind.command_name(),
+        state.original_branch
+    );
+    restore_result
+}
+
 pub fn resume_context(path: &Path) -> Result<RewriteResumeContext> {
     let resume_path = absolute_path(path)?;
     let state = read_resume_state(&resume_path)?;
@@ -477,7 +578,9 @@ fn suspend_instruction_lines(resume_path: &Path, state: &RewriteResumeState) ->
         format!("Resume file: {}", resume_path.display()),
         "Resolve the conflict in the temp worktree, stage the resolution, and run:".to_string(),
         format!("  spr resume {}", resume_path.display()),
-        "To discard this suspended rewrite and clean up its temp state:".to_string(),
+        "To discard this suspended rewrite and clean up its temp state, run:".to_string(),
+        format!("  spr abort {}", resume_path.display()),
+        "or by hand:".to_string(),
         format!("  git -C {} cherry-pick --abort", state.temp_worktree_path),
     ];
     lines.extend(
@@ -1154,11 +1257,11 @@ mod tests {
     use tempfile::TempDir;
 
     use super::{
-        conflicted_paths_from_status_lines, default_resume_path,
-        prepare_resume_path_for_new_session, resume_rewrite, run_rewrite_session,
-        sanitize_branch_for_filename, suspend_instruction_lines, RewriteCommandKind,
-        RewriteCommandOutcome, RewriteConflictPolicy, RewriteDestinationKind, RewriteReplayStep,
-        RewriteResumeState, RewriteSession, REWRITE_RESUME_SCHEMA_VERSION,
+        abort_rewrite, conflicted_paths_from_status_lines, default_resume_path,
+        pending_resume_path, prepare_resume_path_for_new_session, resume_rewrite,
+        run_rewrite_session, sanitize_branch_for_filename, suspend_instruction_lines,
+        RewriteCommandKind, RewriteCommandOutcome, RewriteConflictPolicy, RewriteDestinationKind,
+        RewriteReplayStep, RewriteResumeState, RewriteSession, REWRITE_RESUME_SCHEMA_VERSION,
     };
     use crate::commands::common::{
         CherryPickEmptyPolicy, CherryPickOp, DeferredDirtyWorktreeRestore,
@@ -1573,6 +1676,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn abort_rewrite_discards_pending_session_and_keeps_original_branch() {
+        let _lock = lock_cwd();
+        let (dir, repo, resume_path) = suspended_session_repo();
+        let _keep_dir_alive = dir.path();
+        let _guard = DirGuard::change_to(&repo);
+        let original_head = git(&repo, ["rev-parse", "stack"].as_slice());
+        let resume_state: RewriteResumeState =
+            serde_json::from_str(&fs::read_to_string(&resume_path).expect("read resume state"))
+                .expect("parse resume state");
+
+        assert_eq!(
+            pending_resume_path().expect("find pending session"),
+            resume_path
+        );
+        abort_rewrite(&resume_path).expect("abort suspended rewrite");
+
+        assert!(!resume_path.exists());
+        assert!(!Path::new(&resume_state.temp_worktree_path).exists());
+        assert!(git(
+            &repo,
+            ["branch", "--list", &resume_state.temp_branch].as_slice()
+        )
+        .trim()
+        .is_empty());
+        assert_eq!(git(&repo, ["rev-parse", "stack"].as_slice()), original_head);
+        let err = pending_resume_path().expect_err("no session remains");
+        assert!(
+            format!("{err:#}").contains("no suspended rewrite found"),
+            "unexpected error: {err:#}"
+        );
+    }
+
     #[test]
     fn resume_rewrite_rejects_wrong_repository() {
         let _lock = lock_cwd();
@@ -0,0 +1,165 @@
+//! Roll back an already-landed PR as a new group at the top of the stack.
+//!
+//! `spr rollback-pr <n>` looks up the commit GitHub landed for merged PR `#n` (the squash commit
+//! `spr land` creates, or a merge commit), reverts it on top of the checked-out stack, and tags the
+//! revert commit `pr:<tag>` so the next `spr update` publishes it as its own PR.
+
+use anyhow::{bail, Context, Result};
+use tracing::info;
+
+use crate::execution::ExecutionMode;
+use crate::git::{fetch_remote, git_is_ancestor, git_ro, git_rw};
+use crate::github::{fetch_merged_pr_merge_commit_oids, fetch_pr_import_info};
+use crate::group_markers::GroupMarker;
+use crate::parsing::derive_local_groups;
+
+/// Tag for the revert group: `tag` when given, otherwise `revert-<number>`.
+fn rollback_tag(number: u64, tag: Option<&str>, markers: &[GroupMarker]) -> Result<String> {
+    let tag = match tag {
+        Some(tag) => tag.trim().trim_start_matches("pr:").to_string(),
+        None => format!("revert-{number}"),
+    };
+    crate::pr_labels::validate_label(&tag)
+        .map_err(|err| anyhow::anyhow!("invalid --tag `{tag}`: {err}"))?;
+    if markers.contains(&GroupMarker::PrLabel(tag.clone())) {
+        bail!("pr:{tag} already names a group in this stack; pass a different --tag");
+    }
+    Ok(tag)
+}
+
+fn revert_message(number: u64, title: &str, landed: &str, tag: &str) -> String {
+    format!(
+        "Revert \"{title}\" (#{number})\n\nThis reverts #{number} (commit {landed}).\n\npr:{tag}"
+    )
+}
+
+/// Revert `landed` on top of HEAD as a single commit with `message`.
+///
+/// Merge commits are reverted against their first parent, the base branch they landed on.
+fn commit_revert(landed: &str, message: &str, execution_mode: ExecutionMode) -> Result<()> {
+    let parents = git_ro(["rev-list", "--parents", "-n", "1", landed].as_slice())?;
+    let mut args = vec!["revert", "--no-commit"];
+    if parents.split_whitespace().count() > 2 {
+        args.extend(["-m", "1"]);
+    }
+    args.push(landed);
+    git_rw(execution_mode, args.as_slice()).with_context(|| {
+        format!(
+            "Reverting {} conflicts with the stack; resolve it and `git commit`, keeping the message below, or run `git revert --abort`:\n\n{}",
+            landed, message
+        )
+    })?;
+    git_rw(execution_mode, ["commit", "-m", message].as_slice())?;
+    Ok(())
+}
+
+/// Revert merged PR `number` as a new `pr:<tag>` group on top of the checked-out stack.
+///
+/// Returns the revert group's tag.
+///
+/// # Errors
+///
+/// Returns errors when the PR is not merged, its landed commit is not on the base, the worktree
+/// has uncommitted changes, the tag is invalid or already used, or the revert conflicts.
+pub fn rollback_pr(
+    metadata_context: &crate::stack_metadata::RefreshMetadataContext,
+    number: u64,
+    tag: Option<&str>,
+    execution_mode: ExecutionMode,
+) -> Result<String> {
+    let pr = fetch_pr_import_info(number)?;
+    if pr.state != "MERGED" {
+        bail!(
+            "PR #{} is {}; only landed PRs can be rolled back",
+            number,
+            pr.state.to_lowercase()
+        );
+    }
+    let (_merge_base, groups) =
+        derive_local_groups(&metadata_context.base, &metadata_context.ignore_tag)?;
+    let markers: Vec<GroupMarker> = groups.iter().map(|group| group.marker.clone()).collect();
+    let tag = rollback_tag(number, tag, &markers)?;
+    if !git_ro(["status", "--porcelain", "--untracked-files=no"].as_slice())?
+        .trim()
+        .is_empty()
+    {
+        bail!(
+            "Uncommitted changes in the worktree; commit or stash them before `spr rollback-pr`."
+        );
+    }
+
+    fetch_remote(execution_mode)?;
+    let landed = fetch_merged_pr_merge_commit_oids(&[number])?
+        .remove(&number)
+        .with_context(|| format!("GitHub did not report a landed commit for PR #{number}"))?;
+    if !git_is_ancestor(&landed, &metadata_context.base)? {
+        bail!(
+            "PR #{}'s landed commit {} is not on {}; pull the base branch first",
+            number,
+            landed,
+            metadata_context.base
+        );
+    }
+
+    info!(
+        "Reverting PR #{} ({}) as pr:{} on top of the stack",
+        number,
+        &landed[..landed.len().min(8)],
+        tag
+    );
+    commit_revert(
+        &landed,
+        &revert_message(number, &pr.title, &landed, &tag),
+        execution_mode,
+    )?;
+    Ok(tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{commit_revert, revert_message, rollback_tag};
+    use crate::execution::ExecutionMode;
+    use crate::group_markers::GroupMarker;
+    use crate::test_support::{commit_file, git, init_repo, lock_cwd, log_subjects, DirGuard};
+
+    #[test]
+    fn rollback_tag_defaults_to_revert_number_and_rejects_duplicates() {
+        assert_eq!(rollback_tag(42, None, &[]).unwrap(), "revert-42");
+        assert_eq!(
+            rollback_tag(42, Some("pr:undo-login"), &[]).unwrap(),
+            "undo-login"
+        );
+        let err =
+            rollback_tag(42, None, &[GroupMarker::PrLabel("revert-42".to_string())]).unwrap_err();
+        assert!(err.to_string().contains("already names a group"), "{err}");
+    }
+
+    #[test]
+    fn commit_revert_adds_a_tagged_revert_commit_on_top() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path().to_path_buf();
+        commit_file(&repo, "login.txt", "login\n", "feat: login (#7)");
+        let landed = git(&repo, ["rev-parse", "HEAD"].as_slice())
+            .trim()
+            .to_string();
+        git(&repo, ["checkout", "-b", "stack"].as_slice());
+        commit_file(&repo, "alpha.txt", "a\n", "feat: alpha pr:alpha");
+        let _guard = DirGuard::change_to(&repo);
+
+        let message = revert_message(7, "feat: login", &landed, "revert-7");
+        commit_revert(&landed, &message, ExecutionMode::Apply).unwrap();
+
+        assert_eq!(
+            log_subjects(&repo, 2),
+            vec![
+                "Revert \"feat: login\" (#7)".to_string(),
+                "feat: alpha pr:alpha".to_string(),
+            ]
+        );
+        assert!(!repo.join("login.txt").exists());
+        let (_merge_base, groups) = crate::parsing::derive_local_groups("main", "ignore").unwrap();
+        let selectors: Vec<String> = groups.iter().map(|group| group.selector_text()).collect();
+        assert_eq!(selectors, vec!["pr:alpha", "pr:revert-7"]);
+    }
+}
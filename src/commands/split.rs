@@ -0,0 +1,228 @@
+//! Split one PR group into two at a commit boundary.
+//!
+//! `spr split <target> --after <K>` keeps the group's first K commits in place and starts a new
+//! `pr:<tag>` group at commit K+1 by adding the marker to that commit's message. Everything above
+//! it is replayed through the usual rewrite session, like `spr edit`. Without `--after`, the
+//! group's commits are listed and the boundary is read from the terminal.
+
+use anyhow::{bail, Context, Result};
+use std::io::{BufRead, IsTerminal, Write};
+use tracing::info;
+
+use crate::commands::reword::replay_with_new_message;
+use crate::commands::rewrite_resume::{RewriteCommandKind, RewriteCommandOutcome};
+use crate::config::DirtyWorktreePolicy;
+use crate::execution::ExecutionMode;
+use crate::git::git_ro;
+use crate::group_markers::GroupMarker;
+use crate::parsing::{derive_local_groups, Group};
+use crate::selectors::{resolve_group_ordinal, GroupSelector};
+
+/// Result of `spr split`, including the resolved target so callers can scope a follow-up update.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SplitOutcome {
+    /// 1-based local PR number of the group that was split; the new group is the next one.
+    pub local_pr_number: usize,
+    pub rewrite: RewriteCommandOutcome,
+}
+
+/// Tag for the new upper group: `tag` when given, otherwise `<label>-2` for `pr:<label>` groups.
+fn new_group_tag(group: &Group, groups: &[Group], tag: Option<&str>) -> Result<String> {
+    let tag = match (tag, &group.marker) {
+        (Some(tag), _) => tag.trim().trim_start_matches("pr:").to_string(),
+        (None, GroupMarker::PrLabel(label)) => format!("{label}-2"),
+        (None, GroupMarker::BranchName(_)) => bail!(
+            "{} has no pr:<label> to derive the new group's tag from; pass --tag <label>",
+            group.selector_text()
+        ),
+    };
+    crate::pr_labels::validate_label(&tag)
+        .map_err(|err| anyhow::anyhow!("invalid --tag `{tag}`: {err}"))?;
+    let marker = GroupMarker::PrLabel(tag.clone());
+    if groups.iter().any(|existing| existing.marker == marker) {
+        bail!("pr:{tag} already names a group in this stack; pass a different --tag");
+    }
+    Ok(tag)
+}
+
+/// Validate `after` (commits kept in the lower group) against a group of `len` commits.
+fn check_boundary(after: usize, len: usize, selector: &str) -> Result<()> {
+    if after == 0 || after >= len {
+        bail!(
+            "--after must be between 1 and {} for {} ({} commits)",
+            len - 1,
+            selector,
+            len
+        );
+    }
+    Ok(())
+}
+
+/// List the group's commits on stderr and read the split boundary from the terminal.
+fn prompt_boundary(group: &Group) -> Result<usize> {
+    if !std::io::stdin().is_terminal() {
+        bail!("spr split needs --after <K> when not run from a terminal");
+    }
+    let mut stderr = std::io::stderr();
+    writeln!(stderr, "Commits of {}:", group.selector_text())?;
+    for (idx, (sha, subject)) in group.commits.iter().zip(&group.subjects).enumerate() {
+        writeln!(
+            stderr,
+            "  {:>2}. {} {}",
+            idx + 1,
+            &sha[..sha.len().min(8)],
+            subject
+        )?;
+    }
+    write!(
+        stderr,
+        "Keep how many commits in {} (1-{})? ",
+        group.selector_text(),
+        group.commits.len() - 1
+    )?;
+    stderr.flush()?;
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+    answer
+        .trim()
+        .parse()
+        .with_context(|| format!("`{}` is not a commit count", answer.trim()))
+}
+
+/// Split `target` after its first `after` commits into a new `pr:<tag>` group.
+///
+/// # Errors
+///
+/// Returns errors when the target does not resolve, has a single commit, the boundary is out of
+/// range, the tag is invalid or already used, or the rewrite fails.
+pub fn split_group(
+    metadata_context: &crate::stack_metadata::RefreshMetadataContext,
+    target: &GroupSelector,
+    after: Option<usize>,
+    tag: Option<&str>,
+    safe: bool,
+    execution_mode: ExecutionMode,
+    dirty_worktree_policy: DirtyWorktreePolicy,
+) -> Result<SplitOutcome> {
+    let (_merge_base, groups) =
+        derive_local_groups(&metadata_context.base, &metadata_context.ignore_tag)?;
+    if groups.is_empty() {
+        bail!("No local PR groups found; nothing to split.");
+    }
+    let local_pr_number = resolve_group_ordinal(&groups, target)?;
+    let group = &groups[local_pr_number - 1];
+    let selector = group.selector_text();
+    if group.commits.len() < 2 {
+        bail!("{selector} has a single commit; there is nothing to split");
+    }
+    let tag = new_group_tag(group, &groups, tag)?;
+    let after = match after {
+        Some(after) => after,
+        None => prompt_boundary(group)?,
+    };
+    check_boundary(after, group.commits.len(), &selector)?;
+
+    let boundary = &group.commits[after];
+    let message = git_ro(["log", "-n", "1", "--format=%B", boundary].as_slice())?;
+    let message = format!("{}\n\npr:{}", message.trim_end(), tag);
+    info!(
+        "Splitting {} after commit {} of {}: commits {}-{} become pr:{}",
+        selector,
+        after,
+        group.commits.len(),
+        after + 1,
+        group.commits.len(),
+        tag
+    );
+    let rewrite = replay_with_new_message(
+        metadata_context,
+        boundary,
+        &message,
+        RewriteCommandKind::Split,
+        &format!("Starting pr:{tag}"),
+        safe,
+        execution_mode,
+        dirty_worktree_policy,
+    )?;
+    Ok(SplitOutcome {
+        local_pr_number,
+        rewrite,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_boundary, split_group};
+    use crate::commands::RewriteCommandOutcome;
+    use crate::config::DirtyWorktreePolicy;
+    use crate::execution::ExecutionMode;
+    use crate::selectors::GroupSelector;
+    use crate::test_support::{commit_file, git, init_repo, lock_cwd, log_subjects, DirGuard};
+
+    fn metadata_context() -> crate::stack_metadata::RefreshMetadataContext {
+        crate::stack_metadata::RefreshMetadataContext {
+            base: "main".to_string(),
+            prefix: "dank-spr/".to_string(),
+            ignore_tag: "ignore".to_string(),
+        }
+    }
+
+    #[test]
+    fn check_boundary_rejects_out_of_range_splits() {
+        assert!(check_boundary(1, 3, "pr:alpha").is_ok());
+        assert!(check_boundary(0, 3, "pr:alpha").is_err());
+        let err = check_boundary(3, 3, "pr:alpha").unwrap_err();
+        assert!(err.to_string().contains("between 1 and 2"), "{err}");
+    }
+
+    #[test]
+    fn split_starts_a_new_group_at_the_boundary() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path().to_path_buf();
+        git(&repo, ["checkout", "-b", "stack"].as_slice());
+        commit_file(&repo, "alpha.txt", "a1\n", "feat: alpha pr:alpha");
+        commit_file(&repo, "alpha.txt", "a2\n", "feat: alpha api");
+        commit_file(&repo, "alpha.txt", "a3\n", "feat: alpha ui");
+        commit_file(&repo, "beta.txt", "b\n", "feat: beta pr:beta");
+        let _guard = DirGuard::change_to(&repo);
+
+        let outcome = split_group(
+            &metadata_context(),
+            &GroupSelector::LocalPr(1),
+            Some(2),
+            Some("alpha-ui"),
+            false,
+            ExecutionMode::Apply,
+            DirtyWorktreePolicy::Halt,
+        )
+        .unwrap();
+
+        assert_eq!(outcome.local_pr_number, 1);
+        assert_eq!(outcome.rewrite, RewriteCommandOutcome::Completed);
+        assert_eq!(
+            log_subjects(&repo, 4),
+            vec![
+                "feat: beta pr:beta".to_string(),
+                "feat: alpha ui".to_string(),
+                "feat: alpha api".to_string(),
+                "feat: alpha pr:alpha".to_string(),
+            ]
+        );
+        let (_merge_base, groups) = crate::parsing::derive_local_groups("main", "ignore").unwrap();
+        let selectors: Vec<String> = groups.iter().map(|group| group.selector_text()).collect();
+        assert_eq!(selectors, vec!["pr:alpha", "pr:alpha-ui", "pr:beta"]);
+
+        let err = split_group(
+            &metadata_context(),
+            &GroupSelector::LocalPr(1),
+            Some(1),
+            Some("beta"),
+            false,
+            ExecutionMode::Apply,
+            DirtyWorktreePolicy::Halt,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("already names a group"), "{err}");
+    }
+}
@@ -0,0 +1,224 @@
+//! Squash a run of consecutive commits inside one PR group.
+//!
+//! `spr squash-range A..B` replaces commits `A` through `B` (global commit indices, as printed by
+//! `spr list commit`) with a single commit that has `B`'s tree and `A`'s message and author. The
+//! range must stay inside one group, so the group's marker (carried by its first commit) and the
+//! group boundaries are unchanged. Everything above the range is replayed through the usual
+//! rewrite session, so tidying WIP commits does not require flattening the whole PR with
+//! `spr prep`.
+
+use anyhow::{bail, Result};
+use std::str::FromStr;
+
+use crate::commands::common::commit_with_tree;
+use crate::commands::reword::replay_with_replacement;
+use crate::commands::rewrite_resume::{RewriteCommandKind, RewriteCommandOutcome};
+use crate::config::DirtyWorktreePolicy;
+use crate::execution::ExecutionMode;
+use crate::git::git_ro;
+use crate::parsing::{derive_local_groups, Group};
+
+/// Inclusive range of global commit indices written `A..B`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommitIndexRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl FromStr for CommitIndexRange {
+    type Err = String;
+
+    fn from_str(value: &str) -> std::result::Result<Self, Self::Err> {
+        let Some((start, end)) = value.split_once("..") else {
+            return Err(format!("expected `A..B` commit indices, got `{value}`"));
+        };
+        let parse = |index: &str| {
+            index
+                .trim()
+                .parse::<usize>()
+                .ok()
+                .filter(|index| *index > 0)
+                .ok_or_else(|| format!("`{index}` is not a commit index (1-based)"))
+        };
+        let (start, end) = (parse(start)?, parse(end)?);
+        if start >= end {
+            return Err(format!(
+                "`{value}` must name at least two commits, bottom index first"
+            ));
+        }
+        Ok(Self { start, end })
+    }
+}
+
+/// Result of `spr squash-range`, including the owning group so callers can scope a follow-up
+/// update.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SquashRangeOutcome {
+    /// 1-based local PR number of the group containing the range.
+    pub local_pr_number: usize,
+    pub rewrite: RewriteCommandOutcome,
+}
+
+/// Local PR number and the commits of `range` when it lies inside a single group.
+fn resolve_range(groups: &[Group], range: CommitIndexRange) -> Result<(usize, Vec<String>)> {
+    let mut first_index = 1;
+    for (group_idx, group) in groups.iter().enumerate() {
+        let last_index = first_index + group.commits.len() - 1;
+        if range.start <= last_index {
+            if range.start < first_index || range.end > last_index {
+                bail!(
+                    "Commits {}..{} span more than one group; {} covers commits {}..{}",
+                    range.start,
+                    range.end,
+                    group.selector_text(),
+                    first_index,
+                    last_index
+                );
+            }
+            let commits =
+                group.commits[range.start - first_index..=range.end - first_index].to_vec();
+            return Ok((group_idx + 1, commits));
+        }
+        first_index = last_index + 1;
+    }
+    bail!(
+        "Commit index {} is out of range; the stack has {} commit(s)",
+        range.start,
+        first_index - 1
+    )
+}
+
+/// Squash the commits in `range` into one commit and replay the stack above it.
+///
+/// # Errors
+///
+/// Returns errors when the range is out of bounds, crosses a group boundary, contains a merge
+/// commit, or the rewrite fails.
+pub fn squash_commit_range(
+    metadata_context: &crate::stack_metadata::RefreshMetadataContext,
+    range: CommitIndexRange,
+    safe: bool,
+    execution_mode: ExecutionMode,
+    dirty_worktree_policy: DirtyWorktreePolicy,
+) -> Result<SquashRangeOutcome> {
+    let (_merge_base, groups) =
+        derive_local_groups(&metadata_context.base, &metadata_context.ignore_tag)?;
+    if groups.is_empty() {
+        bail!("No local PR groups found; nothing to squash.");
+    }
+    let (local_pr_number, commits) = resolve_range(&groups, range)?;
+    for commit in &commits {
+        let parents = git_ro(["rev-list", "--parents", "-n", "1", commit].as_slice())?;
+        if parents.split_whitespace().count() != 2 {
+            bail!("{commit} is a merge commit; only single-parent commits can be squashed");
+        }
+    }
+    let (first, last) = (&commits[0], &commits[commits.len() - 1]);
+    let tree = git_ro(["rev-parse", &format!("{last}^{{tree}}")].as_slice())?;
+    let message = git_ro(["log", "-n", "1", "--format=%B", first].as_slice())?;
+    let squashed = commit_with_tree(first, tree.trim(), message.trim_end())?;
+    let rewrite = replay_with_replacement(
+        metadata_context,
+        first,
+        &squashed,
+        &commits[1..],
+        RewriteCommandKind::SquashRange,
+        &format!(
+            "Squashing commits {}..{} of {} into one",
+            range.start,
+            range.end,
+            groups[local_pr_number - 1].selector_text()
+        ),
+        safe,
+        execution_mode,
+        dirty_worktree_policy,
+    )?;
+    Ok(SquashRangeOutcome {
+        local_pr_number,
+        rewrite,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{squash_commit_range, CommitIndexRange};
+    use crate::commands::RewriteCommandOutcome;
+    use crate::config::DirtyWorktreePolicy;
+    use crate::execution::ExecutionMode;
+    use crate::test_support::{commit_file, git, init_repo, lock_cwd, log_subjects, DirGuard};
+
+    fn metadata_context() -> crate::stack_metadata::RefreshMetadataContext {
+        crate::stack_metadata::RefreshMetadataContext {
+            base: "main".to_string(),
+            prefix: "dank-spr/".to_string(),
+            ignore_tag: "ignore".to_string(),
+        }
+    }
+
+    #[test]
+    fn commit_index_range_parses_inclusive_bounds() {
+        assert_eq!(
+            "2..4".parse::<CommitIndexRange>().unwrap(),
+            CommitIndexRange { start: 2, end: 4 }
+        );
+        assert!("4..2".parse::<CommitIndexRange>().is_err());
+        assert!("3..3".parse::<CommitIndexRange>().is_err());
+        assert!("0..2".parse::<CommitIndexRange>().is_err());
+        assert!("2-4".parse::<CommitIndexRange>().is_err());
+    }
+
+    #[test]
+    fn squash_range_keeps_marker_and_commits_above() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path().to_path_buf();
+        git(&repo, ["checkout", "-b", "stack"].as_slice());
+        commit_file(&repo, "alpha.txt", "a\n", "feat: alpha pr:alpha");
+        commit_file(&repo, "alpha.txt", "a\nb\n", "wip");
+        commit_file(&repo, "alpha.txt", "a\nb\nc\n", "wip again");
+        commit_file(&repo, "alpha.txt", "a\nb\nc\nd\n", "feat: alpha tests");
+        commit_file(&repo, "beta.txt", "b\n", "feat: beta pr:beta");
+        let _guard = DirGuard::change_to(&repo);
+
+        let err = squash_commit_range(
+            &metadata_context(),
+            CommitIndexRange { start: 4, end: 5 },
+            false,
+            ExecutionMode::Apply,
+            DirtyWorktreePolicy::Halt,
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string().contains("span more than one group"),
+            "{err}"
+        );
+
+        let outcome = squash_commit_range(
+            &metadata_context(),
+            CommitIndexRange { start: 1, end: 3 },
+            false,
+            ExecutionMode::Apply,
+            DirtyWorktreePolicy::Halt,
+        )
+        .unwrap();
+
+        assert_eq!(outcome.local_pr_number, 1);
+        assert_eq!(outcome.rewrite, RewriteCommandOutcome::Completed);
+        assert_eq!(
+            log_subjects(&repo, 3),
+            vec![
+                "feat: beta pr:beta".to_string(),
+                "feat: alpha tests".to_string(),
+                "feat: alpha pr:alpha".to_string(),
+            ]
+        );
+        assert_eq!(
+            git(&repo, ["show", "HEAD~2:alpha.txt"].as_slice()),
+            "a\nb\nc\n"
+        );
+        assert_eq!(
+            git(&repo, ["show", "HEAD:alpha.txt"].as_slice()),
+            "a\nb\nc\nd\n"
+        );
+    }
+}
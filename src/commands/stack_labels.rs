@@ -0,0 +1,468 @@
+//! Stack-wide PR metadata for `spr label`, `spr milestone`, and `spr assign`.
+//!
+//! Each command applies one change to every open PR of the local stack (or, for `spr assign`, to
+//! one selected group) in a single batched GraphQL mutation, so the PRs of a stack never disagree
+//! about stack-wide metadata. Groups without an open PR are skipped. Every publish path (`spr update`,
+//! `--update` rewrites, `spr sync`, and `spr flush`) also uses this module to put every stack PR on
+//! the configured `milestone` and `project`.
+
+use anyhow::{bail, Result};
+use tracing::{info, warn};
+
+use crate::branch_names::{canonical_branch_conflict_key, group_branch_identities};
+use crate::execution::ExecutionMode;
+use crate::github::{
+    fetch_label_ids, fetch_open_milestone_id, fetch_owner_project_id, fetch_pr_node_ids_graphql,
+    fetch_pr_tracking, fetch_user_ids, list_open_prs_for_heads, GraphqlMutation, PrTracking,
+};
+use crate::parsing::{derive_local_groups, Group};
+use crate::run_settings::Setting;
+use crate::selectors::{resolve_group_index, GroupSelector};
+use std::collections::HashMap;
+
+/// Open PRs of the stack (or of the `target` group only), bottom to top, as `(number, node id)`.
+fn open_stack_prs(
+    base: &str,
+    prefix: &str,
+    ignore_tag: &str,
+    target: Option<&GroupSelector>,
+) -> Result<Vec<(u64, String)>> {
+    open_group_prs(base, prefix, ignore_tag, |groups| match target {
+        Some(target) => Ok(vec![resolve_group_index(groups, target)?]),
+        None => Ok((0..groups.len()).collect()),
+    })
+}
+
+/// Open PRs of the groups at the indices `select` picks, bottom to top, as `(number, node id)`.
+pub(crate) fn open_group_prs(
+    base: &str,
+    prefix: &str,
+    ignore_tag: &str,
+    select: impl FnOnce(&[Group]) -> Result<Vec<usize>>,
+) -> Result<Vec<(u64, String)>> {
+    let (_merge_base, groups) = derive_local_groups(base, ignore_tag)?;
+    if groups.is_empty() {
+        bail!("No local PR groups found; nothing to change.");
+    }
+    let all_heads: Vec<String> = group_branch_identities(&groups, prefix)?
+        .into_iter()
+        .map(|identity| identity.exact)
+        .collect();
+    let heads: Vec<String> = select(&groups)?
+        .into_iter()
+        .map(|idx| all_heads[idx].clone())
+        .collect();
+    let open_prs = list_open_prs_for_heads(&heads)?;
+    let mut numbers: Vec<u64> = heads
+        .iter()
+        .filter_map(|head| {
+            open_prs
+                .iter()
+                .find(|pr| {
+                    canonical_branch_conflict_key(&pr.head) == canonical_branch_conflict_key(head)
+                })
+                .map(|pr| pr.number)
+        })
+        .collect();
+    numbers.dedup();
+    let ids = fetch_pr_node_ids_graphql(&numbers)?;
+    Ok(numbers
+        .into_iter()
+        .filter_map(|number| ids.get(&number).map(|id| (number, id.clone())))
+        .collect())
+}
+
+/// GraphQL mutation and input field names for one kind of add/remove PR metadata.
+struct AddRemoveFields {
+    add: &'static str,
+    remove: &'static str,
+    subject: &'static str,
+    ids: &'static str,
+}
+
+const LABEL_FIELDS: AddRemoveFields = AddRemoveFields {
+    add: "addLabelsToLabelable",
+    remove: "removeLabelsFromLabelable",
+    subject: "labelableId",
+    ids: "labelIds",
+};
+
+const ASSIGNEE_FIELDS: AddRemoveFields = AddRemoveFields {
+    add: "addAssigneesToAssignable",
+    remove: "removeAssigneesFromAssignable",
+    subject: "assignableId",
+    ids: "assigneeIds",
+};
+
+fn add_remove_mutation(
+    fields: &AddRemoveFields,
+    pr_ids: &[String],
+    add_ids: &[String],
+    remove_ids: &[String],
+) -> GraphqlMutation {
+    let mut mutation = GraphqlMutation::new();
+    let add_vars: Vec<String> = add_ids.iter().map(|id| mutation.var("ID!", id)).collect();
+    let remove_vars: Vec<String> = remove_ids
+        .iter()
+        .map(|id| mutation.var("ID!", id))
+        .collect();
+    for (i, pr_id) in pr_ids.iter().enumerate() {
+        let pr = mutation.var("ID!", pr_id);
+        if !add_vars.is_empty() {
+            mutation.field(format!(
+                "a{i}: {}(input:{{{}:{pr}, {}:[{}]}}){{ clientMutationId }}",
+                fields.add,
+                fields.subject,
+                fields.ids,
+                add_vars.join(",")
+            ));
+        }
+        if !remove_vars.is_empty() {
+            mutation.field(format!(
+                "r{i}: {}(input:{{{}:{pr}, {}:[{}]}}){{ clientMutationId }}",
+                fields.remove,
+                fields.subject,
+                fields.ids,
+                remove_vars.join(",")
+            ));
+        }
+    }
+    mutation
+}
+
+fn milestone_mutation(pr_ids: &[String], milestone_id: Option<&str>) -> GraphqlMutation {
+    let mut mutation = GraphqlMutation::new();
+    let milestone = match milestone_id {
+        Some(id) => mutation.var("ID!", id),
+        None => "null".to_string(),
+    };
+    for (i, pr_id) in pr_ids.iter().enumerate() {
+        let pr = mutation.var("ID!", pr_id);
+        mutation.field(format!(
+            "m{i}: updatePullRequest(input:{{pullRequestId:{pr}, milestoneId:{milestone}}}){{ clientMutationId }}"
+        ));
+    }
+    mutation
+}
+
+fn project_mutation(pr_ids: &[String], project_id: &str) -> GraphqlMutation {
+    let mut mutation = GraphqlMutation::new();
+    let project = mutation.var("ID!", project_id);
+    for (i, pr_id) in pr_ids.iter().enumerate() {
+        let pr = mutation.var("ID!", pr_id);
+        mutation.field(format!(
+            "p{i}: addProjectV2ItemById(input:{{projectId:{project}, contentId:{pr}}}){{ clientMutationId }}"
+        ));
+    }
+    mutation
+}
+
+pub(crate) fn pr_list_text(prs: &[(u64, String)]) -> String {
+    prs.iter()
+        .map(|(number, _)| format!("#{number}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Add `add` and remove `remove` labels on every open PR of the stack.
+///
+/// Returns the number of PRs changed. Labels to add must exist in the repository; labels to
+/// remove that do not exist are ignored.
+///
+/// # Errors
+///
+/// Returns errors when there are no groups, a label to add does not exist, or GitHub fails.
+pub fn label_stack(
+    base: &str,
+    prefix: &str,
+    ignore_tag: &str,
+    add: &[String],
+    remove: &[String],
+    execution_mode: ExecutionMode,
+) -> Result<usize> {
+    let names: Vec<String> = add.iter().chain(remove).cloned().collect();
+    let label_ids = fetch_label_ids(&names)?;
+    let missing: Vec<&str> = add
+        .iter()
+        .filter(|label| !label_ids.contains_key(*label))
+        .map(String::as_str)
+        .collect();
+    if !missing.is_empty() {
+        bail!(
+            "Label(s) {} do not exist in the repository; create them first",
+            missing.join(", ")
+        );
+    }
+    let add_ids: Vec<String> = add.iter().map(|label| label_ids[label].clone()).collect();
+    let remove_ids: Vec<String> = remove
+        .iter()
+        .filter_map(|label| label_ids.get(label).cloned())
+        .collect();
+    let prs = open_stack_prs(base, prefix, ignore_tag, None)?;
+    if prs.is_empty() || (add_ids.is_empty() && remove_ids.is_empty()) {
+        info!("No open stack PRs or labels to change; nothing to do.");
+        return Ok(0);
+    }
+    info!("Updating labels on {}", pr_list_text(&prs));
+    let pr_ids: Vec<String> = prs.iter().map(|(_, id)| id.clone()).collect();
+    add_remove_mutation(&LABEL_FIELDS, &pr_ids, &add_ids, &remove_ids).run(execution_mode)?;
+    Ok(prs.len())
+}
+
+/// Set the milestone titled `title` (or clear it when `None`) on every open PR of the stack.
+///
+/// Returns the number of PRs changed.
+///
+/// # Errors
+///
+/// Returns errors when there are no groups, no open milestone has that title, or GitHub fails.
+pub fn set_stack_milestone(
+    base: &str,
+    prefix: &str,
+    ignore_tag: &str,
+    title: Option<&str>,
+    execution_mode: ExecutionMode,
+) -> Result<usize> {
+    let milestone_id = match title {
+        Some(title) => match fetch_open_milestone_id(title)? {
+            Some(id) => Some(id),
+            None => bail!("No open milestone named `{title}` in the repository"),
+        },
+        None => None,
+    };
+    let prs = open_stack_prs(base, prefix, ignore_tag, None)?;
+    if prs.is_empty() {
+        info!("No open stack PRs; nothing to do.");
+        return Ok(0);
+    }
+    match title {
+        Some(title) => info!("Setting milestone `{}` on {}", title, pr_list_text(&prs)),
+        None => info!("Clearing the milestone on {}", pr_list_text(&prs)),
+    }
+    let pr_ids: Vec<String> = prs.iter().map(|(_, id)| id.clone()).collect();
+    milestone_mutation(&pr_ids, milestone_id.as_deref()).run(execution_mode)?;
+    Ok(prs.len())
+}
+
+/// Add `add` and remove `remove` assignees (GitHub logins, with or without a leading `@`) on the
+/// open PR of `target`, or on every open PR of the stack when `target` is `None`.
+///
+/// Returns the number of PRs changed.
+///
+/// # Errors
+///
+/// Returns errors when there are no groups, the target does not resolve, a login is not a GitHub
+/// user, or GitHub fails.
+pub fn assign_stack(
+    base: &str,
+    prefix: &str,
+    ignore_tag: &str,
+    target: Option<&GroupSelector>,
+    add: &[String],
+    remove: &[String],
+    execution_mode: ExecutionMode,
+) -> Result<usize> {
+    let login = |user: &String| user.trim_start_matches('@').to_string();
+    let add: Vec<String> = add.iter().map(login).collect();
+    let remove: Vec<String> = remove.iter().map(login).collect();
+    let prs = open_stack_prs(base, prefix, ignore_tag, target)?;
+    if prs.is_empty() {
+        info!("No open PRs to assign; nothing to do.");
+        return Ok(0);
+    }
+    let logins: Vec<String> = add.iter().chain(&remove).cloned().collect();
+    let user_ids = fetch_user_ids(&logins)?;
+    let add_ids: Vec<String> = add.iter().map(|user| user_ids[user].clone()).collect();
+    let remove_ids: Vec<String> = remove.iter().map(|user| user_ids[user].clone()).collect();
+    info!("Updating assignees on {}", pr_list_text(&prs));
+    let pr_ids: Vec<String> = prs.iter().map(|(_, id)| id.clone()).collect();
+    add_remove_mutation(&ASSIGNEE_FIELDS, &pr_ids, &add_ids, &remove_ids).run(execution_mode)?;
+    Ok(prs.len())
+}
+
+/// Node IDs of the PRs of `numbers` that `keep` selects, in stack order.
+fn tracked_pr_ids(
+    numbers: &[u64],
+    tracking: &HashMap<u64, PrTracking>,
+    keep: impl Fn(&PrTracking) -> bool,
+) -> Vec<String> {
+    numbers
+        .iter()
+        .filter_map(|number| tracking.get(number))
+        .filter(|pr| keep(pr))
+        .map(|pr| pr.id.clone())
+        .collect()
+}
+
+fn attach_stack_prs(
+    numbers: &[u64],
+    milestone: Option<&str>,
+    project: Option<&str>,
+    execution_mode: ExecutionMode,
+) -> Result<()> {
+    let tracking = fetch_pr_tracking(numbers)?;
+    if let Some(title) = milestone {
+        // PRs moved with `spr milestone` keep their milestone
+        let pr_ids = tracked_pr_ids(numbers, &tracking, |pr| pr.milestone_id.is_none());
+        if !pr_ids.is_empty() {
+            match fetch_open_milestone_id(title)? {
+                Some(id) => {
+                    milestone_mutation(&pr_ids, Some(&id)).run(execution_mode)?;
+                }
+                None => warn!("No open milestone named `{title}`; stack PRs were not added to it"),
+            }
+        }
+    }
+    if let Some(title) = project {
+        match fetch_owner_project_id(title)? {
+            Some(id) => {
+                let pr_ids = tracked_pr_ids(numbers, &tracking, |pr| !pr.project_ids.contains(&id));
+                if !pr_ids.is_empty() {
+                    project_mutation(&pr_ids, &id).run(execution_mode)?;
+                }
+            }
+            None => warn!("No project named `{title}`; stack PRs were not added to it"),
+        }
+    }
+    Ok(())
+}
+
+static CONFIGURED_MILESTONE: Setting<String> = Setting::new();
+static CONFIGURED_PROJECT: Setting<String> = Setting::new();
+
+/// Put every stack PR published in this process on `milestone` and the `project` board.
+pub fn set_configured_attachments(milestone: Option<&str>, project: Option<&str>) {
+    if let Some(milestone) = milestone {
+        CONFIGURED_MILESTONE.set(milestone.to_string());
+    }
+    if let Some(project) = project {
+        CONFIGURED_PROJECT.set(project.to_string());
+    }
+}
+
+/// Put the stack PRs just published on the configured `milestone` and `project` board.
+///
+/// PRs without a milestone get the configured one, and PRs not yet on the board are added to it;
+/// PRs that already have a milestone are left alone. Best-effort: failures are logged as warnings
+/// because the calling command already succeeded.
+pub fn attach_stack_prs_if_configured(numbers: &[u64], execution_mode: ExecutionMode) {
+    let milestone = CONFIGURED_MILESTONE.get();
+    let project = CONFIGURED_PROJECT.get();
+    if numbers.is_empty() || (milestone.is_none() && project.is_none()) {
+        return;
+    }
+    if let Err(err) = attach_stack_prs(
+        numbers,
+        milestone.as_deref(),
+        project.as_deref(),
+        execution_mode,
+    ) {
+        warn!("Failed to add stack PRs to the configured milestone or project: {err:#}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        add_remove_mutation, milestone_mutation, project_mutation, tracked_pr_ids, ASSIGNEE_FIELDS,
+        LABEL_FIELDS,
+    };
+    use crate::github::PrTracking;
+    use std::collections::HashMap;
+
+    #[test]
+    fn label_mutation_batches_every_pr_and_reuses_label_variables() {
+        let mutation = add_remove_mutation(
+            &LABEL_FIELDS,
+            &["PR_1".to_string(), "PR_2".to_string()],
+            &["LA_add".to_string()],
+            &["LA_rm".to_string()],
+        );
+        assert_eq!(
+            mutation.query(),
+            "mutation($v0:ID!,$v1:ID!,$v2:ID!,$v3:ID!) { \
+             a0: addLabelsToLabelable(input:{labelableId:$v2, labelIds:[$v0]}){ clientMutationId } \
+             r0: removeLabelsFromLabelable(input:{labelableId:$v2, labelIds:[$v1]}){ clientMutationId } \
+             a1: addLabelsToLabelable(input:{labelableId:$v3, labelIds:[$v0]}){ clientMutationId } \
+             r1: removeLabelsFromLabelable(input:{labelableId:$v3, labelIds:[$v1]}){ clientMutationId } }"
+        );
+        assert!(mutation.gh_args().contains(&"v3=PR_2".to_string()));
+    }
+
+    #[test]
+    fn assignee_mutation_adds_without_removing() {
+        let mutation = add_remove_mutation(
+            &ASSIGNEE_FIELDS,
+            &["PR_1".to_string()],
+            &["U_bob".to_string()],
+            &[],
+        );
+        assert_eq!(
+            mutation.query(),
+            "mutation($v0:ID!,$v1:ID!) { a0: addAssigneesToAssignable(input:{assignableId:$v1, assigneeIds:[$v0]}){ clientMutationId } }"
+        );
+    }
+
+    #[test]
+    fn milestone_mutation_sets_or_clears_the_milestone() {
+        let set = milestone_mutation(&["PR_1".to_string()], Some("MI_1"));
+        assert_eq!(
+            set.query(),
+            "mutation($v0:ID!,$v1:ID!) { m0: updatePullRequest(input:{pullRequestId:$v1, milestoneId:$v0}){ clientMutationId } }"
+        );
+        let clear = milestone_mutation(&["PR_1".to_string()], None);
+        assert!(clear.query().contains("milestoneId:null"));
+    }
+
+    #[test]
+    fn project_mutation_adds_every_pr_to_one_project() {
+        let mutation = project_mutation(&["PR_1".to_string(), "PR_2".to_string()], "PVT_1");
+        assert_eq!(
+            mutation.query(),
+            "mutation($v0:ID!,$v1:ID!,$v2:ID!) { \
+             p0: addProjectV2ItemById(input:{projectId:$v0, contentId:$v1}){ clientMutationId } \
+             p1: addProjectV2ItemById(input:{projectId:$v0, contentId:$v2}){ clientMutationId } }"
+        );
+    }
+
+    #[test]
+    fn tracked_pr_ids_keeps_stack_order_and_skips_unknown_prs() {
+        let tracking = HashMap::from([
+            (
+                1,
+                PrTracking {
+                    id: "PR_1".to_string(),
+                    milestone_id: Some("MI_1".to_string()),
+                    project_ids: vec!["PVT_1".to_string()],
+                },
+            ),
+            (
+                2,
+                PrTracking {
+                    id: "PR_2".to_string(),
+                    milestone_id: None,
+                    project_ids: Vec::new(),
+                },
+            ),
+            (
+                3,
+                PrTracking {
+                    id: "PR_3".to_string(),
+                    milestone_id: None,
+                    project_ids: vec!["PVT_1".to_string()],
+                },
+            ),
+        ]);
+
+        assert_eq!(
+            tracked_pr_ids(&[3, 4, 2, 1], &tracking, |pr| pr.milestone_id.is_none()),
+            vec!["PR_3", "PR_2"]
+        );
+        assert_eq!(
+            tracked_pr_ids(&[1, 2, 3], &tracking, |pr| !pr
+                .project_ids
+                .contains(&"PVT_1".to_string())),
+            vec!["PR_2"]
+        );
+    }
+}
@@ -0,0 +1,238 @@
+//! Per-PR review metrics for the current stack or for previously landed stack PRs.
+//!
+//! `spr stats` reads GitHub timeline data for each PR and reports time from first push to merge,
+//! review rounds, and force pushes. A review round is a run of reviews not interrupted by a force
+//! push, so "review, push, review, review, push, review" counts as three rounds. `--history`
+//! switches from the current stack to merged PRs whose head branch carries the configured prefix;
+//! `--since` narrows the current stack to groups with recent commits, as in `spr list`.
+
+use anyhow::Result;
+use serde::Serialize;
+use tracing::info;
+
+use crate::commands::RemotePrState;
+use crate::config::LocalPrBranchSyncPolicy;
+use crate::github::{PrHistoryInfo, PrTimelineEvent};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PrStatsData {
+    pub pr_number: u64,
+    pub title: String,
+    pub head_branch: String,
+    pub merged: bool,
+    /// Seconds from PR creation (the first push) to merge; absent while unmerged.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_to_merge_secs: Option<i64>,
+    pub review_rounds: usize,
+    pub force_pushes: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StatsData {
+    pub history: bool,
+    pub prs: Vec<PrStatsData>,
+    /// Median over merged PRs; absent when none merged.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub median_time_to_merge_secs: Option<i64>,
+    pub total_review_rounds: usize,
+    pub total_force_pushes: usize,
+}
+
+fn review_rounds(events: &[PrTimelineEvent]) -> usize {
+    let mut rounds = 0;
+    let mut in_round = false;
+    for event in events {
+        match event {
+            PrTimelineEvent::Review if !in_round => {
+                rounds += 1;
+                in_round = true;
+            }
+            PrTimelineEvent::Review => {}
+            PrTimelineEvent::ForcePush => in_round = false,
+        }
+    }
+    rounds
+}
+
+fn median(mut values: Vec<i64>) -> Option<i64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_unstable();
+    let mid = values.len() / 2;
+    Some(if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2
+    } else {
+        values[mid]
+    })
+}
+
+fn pr_stats(info: &PrHistoryInfo) -> Result<PrStatsData> {
+    Ok(PrStatsData {
+        pr_number: info.number,
+        title: info.title.clone(),
+        head_branch: info.head.clone(),
+        merged: info.merged_at.is_some(),
+        time_to_merge_secs: info.time_to_merge_secs()?,
+        review_rounds: review_rounds(&info.events),
+        force_pushes: info
+            .events
+            .iter()
+            .filter(|event| **event == PrTimelineEvent::ForcePush)
+            .count(),
+    })
+}
+
+fn build_stats_data(history: bool, infos: &[PrHistoryInfo]) -> Result<StatsData> {
+    let prs = infos.iter().map(pr_stats).collect::<Result<Vec<_>>>()?;
+    Ok(StatsData {
+        history,
+        median_time_to_merge_secs: median(
+            prs.iter().filter_map(|pr| pr.time_to_merge_secs).collect(),
+        ),
+        total_review_rounds: prs.iter().map(|pr| pr.review_rounds).sum(),
+        total_force_pushes: prs.iter().map(|pr| pr.force_pushes).sum(),
+        prs,
+    })
+}
+
+/// Collect review metrics for the current stack's PRs, or with `history` for up to `limit`
+/// recently merged PRs under `prefix`.
+///
+/// # Errors
+///
+/// Returns errors from stack discovery or GitHub queries.
+pub fn collect_stats_data(
+    base: &str,
+    prefix: &str,
+    ignore_tag: &str,
+    history: bool,
+    limit: usize,
+    since: Option<&str>,
+) -> Result<StatsData> {
+    let numbers = if history {
+        crate::github::list_merged_pr_numbers_with_prefix(prefix, limit)?
+    } else {
+        crate::commands::collect_pr_list_data_for_json(
+            base,
+            prefix,
+            ignore_tag,
+            LocalPrBranchSyncPolicy::Off,
+            since,
+        )?
+        .groups
+        .iter()
+        .filter_map(|group| match &group.remote.state {
+            RemotePrState::NoRemote => None,
+            RemotePrState::RemoteWithoutCiReview { pr_number, .. }
+            | RemotePrState::RemoteWithCiReview { pr_number, .. } => Some(*pr_number),
+        })
+        .collect()
+    };
+    build_stats_data(history, &crate::github::fetch_pr_history(&numbers)?)
+}
+
+fn format_duration(secs: i64) -> String {
+    let minutes = secs / 60;
+    let (days, hours, minutes) = (minutes / 1440, minutes / 60 % 24, minutes % 60);
+    if days > 0 {
+        format!("{days}d {hours}h")
+    } else if hours > 0 {
+        format!("{hours}h {minutes}m")
+    } else {
+        format!("{minutes}m")
+    }
+}
+
+fn render_stats(data: &StatsData) -> Vec<String> {
+    if data.prs.is_empty() {
+        return vec![if data.history {
+            "No merged PRs found under the prefix.".to_string()
+        } else {
+            "No published PRs in the current stack.".to_string()
+        }];
+    }
+    let mut lines = Vec::new();
+    for pr in &data.prs {
+        let merge = pr
+            .time_to_merge_secs
+            .map(|secs| format!("merged in {}", format_duration(secs)))
+            .unwrap_or_else(|| "open".to_string());
+        lines.push(format!(
+            "#{} {}: {}, {} review round(s), {} force push(es)",
+            pr.pr_number, pr.title, merge, pr.review_rounds, pr.force_pushes
+        ));
+    }
+    lines.push(format!(
+        "{} PR(s): median time to merge {}, {} review round(s), {} force push(es)",
+        data.prs.len(),
+        data.median_time_to_merge_secs
+            .map(format_duration)
+            .unwrap_or_else(|| "n/a".to_string()),
+        data.total_review_rounds,
+        data.total_force_pushes
+    ));
+    lines
+}
+
+pub fn print_stats(data: &StatsData) {
+    for line in render_stats(data) {
+        info!("{line}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_stats_data, render_stats, review_rounds};
+    use crate::github::{PrHistoryInfo, PrTimelineEvent};
+
+    #[test]
+    fn review_rounds_split_on_force_pushes() {
+        use PrTimelineEvent::{ForcePush, Review};
+        assert_eq!(review_rounds(&[]), 0);
+        assert_eq!(review_rounds(&[ForcePush, ForcePush]), 0);
+        assert_eq!(
+            review_rounds(&[Review, ForcePush, Review, Review, ForcePush, Review]),
+            3
+        );
+    }
+
+    #[test]
+    fn stats_summarize_merged_and_open_prs() {
+        let pr = |number, merged_at: Option<&str>, events| PrHistoryInfo {
+            number,
+            title: format!("feat: {number}"),
+            head: format!("dank-spr/{number}"),
+            created_at: "2026-03-01T00:00:00Z".to_string(),
+            merged_at: merged_at.map(str::to_string),
+            events,
+        };
+        let data = build_stats_data(
+            true,
+            &[
+                pr(
+                    1,
+                    Some("2026-03-03T04:00:00Z"),
+                    vec![PrTimelineEvent::Review, PrTimelineEvent::ForcePush],
+                ),
+                pr(2, Some("2026-03-01T02:30:00Z"), vec![]),
+                pr(3, None, vec![PrTimelineEvent::ForcePush]),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(data.median_time_to_merge_secs, Some((52 * 3600 + 9000) / 2));
+        assert_eq!(data.total_review_rounds, 1);
+        assert_eq!(data.total_force_pushes, 2);
+        assert_eq!(
+            render_stats(&data),
+            vec![
+                "#1 feat: 1: merged in 2d 4h, 1 review round(s), 1 force push(es)".to_string(),
+                "#2 feat: 2: merged in 2h 30m, 0 review round(s), 0 force push(es)".to_string(),
+                "#3 feat: 3: open, 0 review round(s), 1 force push(es)".to_string(),
+                "3 PR(s): median time to merge 1d 3h, 1 review round(s), 2 force push(es)"
+                    .to_string(),
+            ]
+        );
+    }
+}
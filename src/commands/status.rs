@@ -0,0 +1,511 @@
+//! Rich stack overview for `spr status`.
+//!
+//! `spr status` prints the `spr list pr` lines followed by a diagnosis of each group: whether its
+//! pushed branch (as of the last fetch) matches the local group tip, and whether its open PR's
+//! base follows the stack chain, which is what `spr relink-prs` would repair. A headline per
+//! segment reports how far it is behind its base. Groups of a `pr:base:` segment are compared as
+//! `spr update` would publish them, replayed onto the segment's base. Nothing is pushed or edited.
+
+use anyhow::Result;
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::branch_names::group_branch_identities;
+use crate::commands::{
+    collect_pr_list_data_for_json, print_pr_list, PrListData, ReadOnlyQueryError, RemotePrState,
+};
+use crate::config::{ListOrder, LocalPrBranchSyncPolicy};
+use crate::git::{git_ref_exists_at, git_ro, remote, sanitize_gh_base_ref};
+use crate::github::PrState;
+use crate::parsing::{derive_local_groups, stack_from};
+use crate::stack_segments::{replay_segment_onto_base, split_segments};
+
+/// How a group's pushed branch compares with its local tip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum BranchDrift {
+    NotPushed,
+    InSync,
+    LocalAhead { commits: usize },
+    RemoteAhead { commits: usize },
+    Diverged { local: usize, remote: usize },
+}
+
+/// Whether a group's open PR targets the branch below it in the stack, or its segment's base.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum BaseChainState {
+    Chained,
+    Misrouted { current: String, expected: String },
+    NoOpenPr,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StatusGroupData {
+    pub local_pr_number: usize,
+    pub stable_handle: String,
+    /// Base of the segment (PR chain) the group belongs to.
+    pub segment_base: String,
+    pub drift: BranchDrift,
+    pub base_chain: BaseChainState,
+}
+
+/// A run of groups published as one PR chain onto `base`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StatusSegmentData {
+    pub base: String,
+    pub first_local_pr_number: usize,
+    pub group_count: usize,
+    /// Commits on `base` that the segment does not contain yet. For the bottom segment that is
+    /// the local stack; a `pr:base:` segment is replayed onto its base on every update, so it
+    /// counts what its pushed bottom branch lacks.
+    pub behind_base: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StatusData {
+    pub base: String,
+    pub segments: Vec<StatusSegmentData>,
+    #[serde(rename = "group_status")]
+    pub groups: Vec<StatusGroupData>,
+    #[serde(flatten)]
+    pub list: PrListData,
+}
+
+/// Compare `local_tip` with the remote-tracking ref of `head_branch`.
+fn branch_drift(local_tip: &str, head_branch: &str) -> Result<BranchDrift> {
+    let remote_ref = format!("refs/remotes/{}/{head_branch}", remote());
+    if !git_ref_exists_at(".", &remote_ref)? {
+        return Ok(BranchDrift::NotPushed);
+    }
+    let counts = git_ro(
+        [
+            "rev-list",
+            "--left-right",
+            "--count",
+            &format!("{local_tip}...{remote_ref}"),
+        ]
+        .as_slice(),
+    )?;
+    let mut counts = counts
+        .split_whitespace()
+        .map(|count| count.parse::<usize>().unwrap_or(0));
+    let local = counts.next().unwrap_or(0);
+    let remote = counts.next().unwrap_or(0);
+    Ok(match (local, remote) {
+        (0, 0) => BranchDrift::InSync,
+        (commits, 0) => BranchDrift::LocalAhead { commits },
+        (0, commits) => BranchDrift::RemoteAhead { commits },
+        (local, remote) => BranchDrift::Diverged { local, remote },
+    })
+}
+
+fn base_chain_state(remote: &RemotePrState, expected: &str) -> BaseChainState {
+    match remote {
+        RemotePrState::RemoteWithoutCiReview {
+            state: PrState::Open,
+            base_branch,
+            ..
+        }
+        | RemotePrState::RemoteWithCiReview {
+            state: PrState::Open,
+            base_branch,
+            ..
+        } => {
+            if sanitize_gh_base_ref(base_branch) == sanitize_gh_base_ref(expected) {
+                BaseChainState::Chained
+            } else {
+                BaseChainState::Misrouted {
+                    current: base_branch.clone(),
+                    expected: sanitize_gh_base_ref(expected),
+                }
+            }
+        }
+        _ => BaseChainState::NoOpenPr,
+    }
+}
+
+/// Commits reachable from `base` but not from `from`.
+fn commits_behind(from: &str, base: &str) -> Result<usize> {
+    Ok(
+        git_ro(["rev-list", "--count", &format!("{from}..{base}")].as_slice())?
+            .trim()
+            .parse()
+            .unwrap_or(0),
+    )
+}
+
+/// Add branch drift, base chaining, and per-segment behind-base counts to `list`.
+fn status_data_from_list(
+    base: &str,
+    prefix: &str,
+    ignore_tag: &str,
+    list: PrListData,
+) -> Result<StatusData> {
+    let (_merge_base, groups) = derive_local_groups(base, ignore_tag)?;
+    let identities = group_branch_identities(&groups, prefix)?;
+    let mut segments = Vec::new();
+    let mut status_groups = Vec::with_capacity(groups.len());
+    let mut group_idx = 0;
+    for mut segment in split_segments(base, groups) {
+        let first = group_idx;
+        let behind_base = if segment.base == base {
+            commits_behind(&stack_from(), base)?
+        } else {
+            // Compare the way `spr update` publishes the segment: replayed onto its base
+            let mut replayed = segment.clone();
+            match replay_segment_onto_base(&mut replayed) {
+                Ok(()) => segment = replayed,
+                Err(err) => warn!("{err:#}"),
+            }
+            let bottom = format!("refs/remotes/{}/{}", remote(), identities[first].exact);
+            if git_ref_exists_at(".", &bottom)? {
+                commits_behind(&bottom, &segment.base)?
+            } else {
+                0
+            }
+        };
+        let mut expected_base = segment.base.clone();
+        for group in &segment.groups {
+            let identity = &identities[group_idx];
+            let listed = &list.groups[group_idx];
+            let tip = group.commits.last().map_or("", String::as_str);
+            status_groups.push(StatusGroupData {
+                local_pr_number: listed.local_pr_number,
+                stable_handle: listed.stable_handle.clone(),
+                segment_base: segment.base.clone(),
+                drift: branch_drift(tip, &identity.exact)?,
+                base_chain: base_chain_state(&listed.remote.state, &expected_base),
+            });
+            expected_base = identity.exact.clone();
+            group_idx += 1;
+        }
+        segments.push(StatusSegmentData {
+            base: segment.base,
+            first_local_pr_number: first + 1,
+            group_count: group_idx - first,
+            behind_base,
+        });
+    }
+    Ok(StatusData {
+        base: base.to_string(),
+        segments,
+        groups: status_groups,
+        list,
+    })
+}
+
+/// Collect the `spr list pr` data plus branch drift, base chaining, and behind-base counts.
+///
+/// # Errors
+///
+/// Returns errors from git, GitHub queries, or case-colliding concrete branch names.
+pub fn collect_status_data(
+    base: &str,
+    prefix: &str,
+    ignore_tag: &str,
+    local_pr_branch_policy: LocalPrBranchSyncPolicy,
+) -> Result<StatusData> {
+    collect_status_data_for_json(base, prefix, ignore_tag, local_pr_branch_policy)
+        .map_err(anyhow::Error::from)
+}
+
+/// [`collect_status_data`] for `spr status --json`, keeping branch-name collisions apart from
+/// other failures.
+pub fn collect_status_data_for_json(
+    base: &str,
+    prefix: &str,
+    ignore_tag: &str,
+    local_pr_branch_policy: LocalPrBranchSyncPolicy,
+) -> std::result::Result<StatusData, ReadOnlyQueryError> {
+    let list =
+        collect_pr_list_data_for_json(base, prefix, ignore_tag, local_pr_branch_policy, None)?;
+    status_data_from_list(base, prefix, ignore_tag, list).map_err(ReadOnlyQueryError::Internal)
+}
+
+fn drift_text(drift: BranchDrift) -> String {
+    match drift {
+        BranchDrift::NotPushed => "not pushed".to_string(),
+        BranchDrift::InSync => "branch in sync".to_string(),
+        BranchDrift::LocalAhead { commits } => format!("{commits} local commit(s) not pushed"),
+        BranchDrift::RemoteAhead { commits } => {
+            format!("remote branch {commits} commit(s) ahead")
+        }
+        BranchDrift::Diverged { local, remote } => {
+            format!("branch diverged ({local} local, {remote} remote)")
+        }
+    }
+}
+
+fn segment_headline(data: &StatusData, segment: &StatusSegmentData) -> String {
+    if segment.base == data.base {
+        return if segment.behind_base == 0 {
+            format!("Stack is up to date with {}", segment.base)
+        } else {
+            format!(
+                "Stack is {} commit(s) behind {}; run `spr restack --after 0`",
+                segment.behind_base, segment.base
+            )
+        };
+    }
+    let start = format!(
+        "Segment from LPR #{} onto {}",
+        segment.first_local_pr_number, segment.base
+    );
+    if segment.behind_base == 0 {
+        format!("{start} is up to date")
+    } else {
+        format!(
+            "{start} is {} commit(s) behind; `spr update` replays it onto the new base",
+            segment.behind_base
+        )
+    }
+}
+
+fn render_status_lines(data: &StatusData, list_order: ListOrder) -> Vec<String> {
+    if data.groups.is_empty() {
+        return Vec::new();
+    }
+    let mut lines: Vec<String> = data
+        .segments
+        .iter()
+        .map(|segment| segment_headline(data, segment))
+        .collect();
+    let mut needs_update = false;
+    let mut needs_relink = false;
+    for group_idx in list_order.display_indices(data.groups.len()) {
+        let group = &data.groups[group_idx];
+        needs_update |= group.drift != BranchDrift::InSync;
+        let base_text = match &group.base_chain {
+            BaseChainState::Chained => "base ok".to_string(),
+            BaseChainState::Misrouted { current, expected } => {
+                needs_relink = true;
+                format!("base {current}, expected {expected}")
+            }
+            BaseChainState::NoOpenPr => "no open PR".to_string(),
+        };
+        lines.push(format!(
+            "LPR #{} / {}: {}; {}",
+            group.local_pr_number,
+            group.stable_handle,
+            drift_text(group.drift),
+            base_text
+        ));
+    }
+    if needs_update {
+        lines.push("Run `spr update` to push local changes.".to_string());
+    }
+    if needs_relink {
+        lines.push("Run `spr relink-prs` to restore the PR base chain.".to_string());
+    }
+    lines
+}
+
+/// Print the `spr list pr` lines followed by the status diagnosis.
+pub fn status_display(
+    base: &str,
+    prefix: &str,
+    ignore_tag: &str,
+    list_order: ListOrder,
+    local_pr_branch_policy: LocalPrBranchSyncPolicy,
+) -> Result<()> {
+    let data = collect_status_data(base, prefix, ignore_tag, local_pr_branch_policy)?;
+    print_pr_list(&data.list, list_order);
+    for line in render_status_lines(&data, list_order) {
+        info!("{line}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        base_chain_state, branch_drift, render_status_lines, status_data_from_list, BaseChainState,
+        BranchDrift, StatusData, StatusGroupData, StatusSegmentData,
+    };
+    use crate::commands::{PrGroupData, PrListData, RemotePrMetadata, RemotePrState};
+    use crate::config::ListOrder;
+    use crate::github::PrState;
+    use crate::test_support::{commit_file, git, init_repo, lock_cwd, DirGuard};
+
+    #[test]
+    fn branch_drift_compares_local_tip_with_remote_tracking_ref() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path().to_path_buf();
+        commit_file(&repo, "alpha.txt", "a\n", "feat: alpha pr:alpha");
+        git(
+            &repo,
+            ["update-ref", "refs/remotes/origin/dank-spr/alpha", "HEAD"].as_slice(),
+        );
+        commit_file(&repo, "alpha.txt", "a\nb\n", "feat: alpha more");
+        let tip = git(&repo, ["rev-parse", "HEAD"].as_slice())
+            .trim()
+            .to_string();
+        let _guard = DirGuard::change_to(&repo);
+
+        assert_eq!(
+            branch_drift(&tip, "dank-spr/alpha").unwrap(),
+            BranchDrift::LocalAhead { commits: 1 }
+        );
+        assert_eq!(
+            branch_drift(&tip, "dank-spr/beta").unwrap(),
+            BranchDrift::NotPushed
+        );
+    }
+
+    #[test]
+    fn status_lines_flag_misrouted_bases_and_unpushed_branches() {
+        let open = |base: &str| RemotePrState::RemoteWithoutCiReview {
+            pr_number: 7,
+            url: String::new(),
+            base_branch: base.to_string(),
+            state: PrState::Open,
+        };
+        assert_eq!(
+            base_chain_state(&open("main"), "origin/main"),
+            BaseChainState::Chained
+        );
+        let misrouted = base_chain_state(&open("main"), "dank-spr/alpha");
+        assert_eq!(
+            misrouted,
+            BaseChainState::Misrouted {
+                current: "main".to_string(),
+                expected: "dank-spr/alpha".to_string(),
+            }
+        );
+
+        let data = StatusData {
+            base: "origin/main".to_string(),
+            segments: vec![StatusSegmentData {
+                base: "origin/main".to_string(),
+                first_local_pr_number: 1,
+                group_count: 2,
+                behind_base: 2,
+            }],
+            groups: vec![
+                StatusGroupData {
+                    local_pr_number: 1,
+                    stable_handle: "pr:alpha".to_string(),
+                    segment_base: "origin/main".to_string(),
+                    drift: BranchDrift::InSync,
+                    base_chain: BaseChainState::Chained,
+                },
+                StatusGroupData {
+                    local_pr_number: 2,
+                    stable_handle: "pr:beta".to_string(),
+                    segment_base: "origin/main".to_string(),
+                    drift: BranchDrift::LocalAhead { commits: 1 },
+                    base_chain: misrouted,
+                },
+            ],
+            list: PrListData {
+                groups: Vec::new(),
+                local_pr_branch_drift: Vec::new(),
+            },
+        };
+        assert_eq!(
+            render_status_lines(&data, ListOrder::RecentOnTop),
+            vec![
+                "Stack is 2 commit(s) behind origin/main; run `spr restack --after 0`".to_string(),
+                "LPR #2 / pr:beta: 1 local commit(s) not pushed; base main, expected dank-spr/alpha"
+                    .to_string(),
+                "LPR #1 / pr:alpha: branch in sync; base ok".to_string(),
+                "Run `spr update` to push local changes.".to_string(),
+                "Run `spr relink-prs` to restore the PR base chain.".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn status_checks_pr_base_segments_against_their_own_base() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path().to_path_buf();
+        git(&repo, ["branch", "release"].as_slice());
+        git(&repo, ["checkout", "-b", "stack"].as_slice());
+        commit_file(&repo, "alpha.txt", "alpha\n", "feat: alpha pr:alpha");
+        git(
+            &repo,
+            ["update-ref", "refs/remotes/origin/dank-spr/alpha", "HEAD"].as_slice(),
+        );
+        commit_file(
+            &repo,
+            "hotfix.txt",
+            "fix\n",
+            "fix: hotfix pr:hotfix pr:base:release",
+        );
+        let _guard = DirGuard::change_to(&repo);
+        let listed = |number: usize, handle: &str, base: &str| PrGroupData {
+            local_pr_number: number,
+            stable_handle: format!("pr:{handle}"),
+            head_branch: format!("dank-spr/{handle}"),
+            first_commit_sha: String::new(),
+            commit_count: 1,
+            first_subject: String::new(),
+            planned_push: None,
+            remote: RemotePrMetadata {
+                state: RemotePrState::RemoteWithoutCiReview {
+                    pr_number: 10 + number as u64,
+                    url: String::new(),
+                    base_branch: base.to_string(),
+                    state: PrState::Open,
+                },
+            },
+        };
+        let list = PrListData {
+            groups: vec![listed(1, "alpha", "main"), listed(2, "hotfix", "release")],
+            local_pr_branch_drift: Vec::new(),
+        };
+
+        // Publish the hotfix the way `spr update` does: replayed onto release
+        let (_merge_base, groups) = crate::parsing::derive_local_groups("main", "ignore").unwrap();
+        let mut segments = crate::stack_segments::split_segments("main", groups);
+        crate::stack_segments::replay_segment_onto_base(&mut segments[1]).unwrap();
+        git(
+            &repo,
+            [
+                "update-ref",
+                "refs/remotes/origin/dank-spr/hotfix",
+                &segments[1].groups[0].commits[0],
+            ]
+            .as_slice(),
+        );
+
+        let data = status_data_from_list("main", "dank-spr/", "ignore", list.clone()).unwrap();
+        assert_eq!(
+            data.segments,
+            vec![
+                StatusSegmentData {
+                    base: "main".to_string(),
+                    first_local_pr_number: 1,
+                    group_count: 1,
+                    behind_base: 0,
+                },
+                StatusSegmentData {
+                    base: "release".to_string(),
+                    first_local_pr_number: 2,
+                    group_count: 1,
+                    behind_base: 0,
+                },
+            ]
+        );
+        assert_eq!(data.groups[1].segment_base, "release");
+        assert_eq!(data.groups[1].drift, BranchDrift::InSync);
+        assert_eq!(data.groups[1].base_chain, BaseChainState::Chained);
+        let json = serde_json::to_value(&data).unwrap();
+        assert_eq!(json["segments"][1]["base"], "release");
+        assert_eq!(json["group_status"][1]["drift"]["state"], "in_sync");
+        assert_eq!(json["groups"][1]["stable_handle"], "pr:hotfix");
+
+        git(&repo, ["checkout", "-q", "release"].as_slice());
+        commit_file(&repo, "release.txt", "r\n", "chore: release notes");
+        git(&repo, ["checkout", "-q", "stack"].as_slice());
+        let data = status_data_from_list("main", "dank-spr/", "ignore", list).unwrap();
+        assert_eq!(data.segments[1].behind_base, 1);
+        assert_eq!(
+            render_status_lines(&data, ListOrder::RecentOnTop)[1],
+            "Segment from LPR #2 onto release is 1 commit(s) behind; `spr update` replays it onto the new base"
+        );
+    }
+}
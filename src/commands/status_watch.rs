@@ -0,0 +1,209 @@
+//! Polling mode for `spr status`.
+//!
+//! `spr status --watch` reprints the PR list every interval until every open PR's CI is green or
+//! any check fails. With `--notify`, reaching that point also raises a desktop notification
+//! (`notify-send` on Linux, `osascript` on macOS) and falls back to a terminal bell.
+
+use anyhow::Result;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+use tracing::info;
+
+use crate::commands::{PrListData, RemotePrState};
+use crate::config::{ListOrder, LocalPrBranchSyncPolicy};
+use crate::github::{PrCiState, PrState};
+
+/// Where the stack's CI stands after one poll.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum StackCiOutcome {
+    /// Every open PR reports successful checks.
+    Green,
+    /// At least one open PR has failing checks.
+    Failed(Vec<u64>),
+    /// Some checks are still running or unknown.
+    Pending,
+}
+
+fn stack_ci_outcome(data: &PrListData) -> StackCiOutcome {
+    let mut failed = Vec::new();
+    let mut pending = false;
+    for group in &data.groups {
+        match &group.remote.state {
+            RemotePrState::RemoteWithCiReview {
+                pr_number,
+                state: PrState::Open,
+                ci_review_status,
+                ..
+            } => match ci_review_status.ci_state {
+                PrCiState::Success => {}
+                PrCiState::Failure | PrCiState::Error => failed.push(*pr_number),
+                PrCiState::Pending | PrCiState::Expected | PrCiState::Unknown => pending = true,
+            },
+            RemotePrState::RemoteWithoutCiReview {
+                state: PrState::Open,
+                ..
+            } => pending = true,
+            _ => {}
+        }
+    }
+    if !failed.is_empty() {
+        StackCiOutcome::Failed(failed)
+    } else if pending {
+        StackCiOutcome::Pending
+    } else {
+        StackCiOutcome::Green
+    }
+}
+
+fn notify(summary: &str) {
+    let delivered = if cfg!(target_os = "macos") {
+        Command::new("osascript")
+            .args([
+                "-e",
+                &format!(
+                    "display notification \"{}\" with title \"spr\"",
+                    summary.replace('"', "'")
+                ),
+            ])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(false)
+    } else {
+        Command::new("notify-send")
+            .args(["spr", summary])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(false)
+    };
+    if !delivered {
+        let mut stderr = std::io::stderr();
+        let _ = stderr.write_all(b"\x07");
+        let _ = stderr.flush();
+    }
+}
+
+/// Poll the stack's PR status until CI settles, printing the list after each poll.
+///
+/// Returns once every open PR is green or any PR's checks fail; with `notify`, a desktop
+/// notification (or terminal bell) announces which.
+///
+/// # Errors
+///
+/// Returns errors from stack discovery or GitHub queries.
+pub fn watch_status(
+    base: &str,
+    prefix: &str,
+    ignore_tag: &str,
+    list_order: ListOrder,
+    local_pr_branch_policy: LocalPrBranchSyncPolicy,
+    interval: Duration,
+    notify_on_finish: bool,
+) -> Result<()> {
+    loop {
+        let data = crate::commands::collect_pr_list_data(
+            base,
+            prefix,
+            ignore_tag,
+            local_pr_branch_policy,
+        )?;
+        crate::commands::print_pr_list(&data, list_order);
+        let summary = match stack_ci_outcome(&data) {
+            StackCiOutcome::Pending => {
+                info!(
+                    "Checks still running; polling again in {}s.",
+                    interval.as_secs()
+                );
+                std::thread::sleep(interval);
+                continue;
+            }
+            StackCiOutcome::Green => "All stack PR checks passed.".to_string(),
+            StackCiOutcome::Failed(numbers) => format!(
+                "Checks failed on {}.",
+                numbers
+                    .iter()
+                    .map(|number| format!("#{number}"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        };
+        info!("{summary}");
+        if notify_on_finish {
+            notify(&summary);
+        }
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{stack_ci_outcome, StackCiOutcome};
+    use crate::commands::{PrGroupData, PrListData, RemotePrMetadata, RemotePrState};
+    use crate::github::{PrCiReviewStatus, PrCiState, PrReviewDecision, PrState};
+
+    fn open_pr(number: u64, ci_state: PrCiState) -> PrGroupData {
+        PrGroupData {
+            local_pr_number: number as usize,
+            stable_handle: format!("pr:g{number}"),
+            head_branch: format!("dank-spr/g{number}"),
+            first_commit_sha: "abc".to_string(),
+            commit_count: 1,
+            first_subject: "feat".to_string(),
+            planned_push: None,
+            remote: RemotePrMetadata {
+                state: RemotePrState::RemoteWithCiReview {
+                    pr_number: number,
+                    url: String::new(),
+                    base_branch: "main".to_string(),
+                    state: PrState::Open,
+                    ci_review_status: PrCiReviewStatus {
+                        ci_state,
+                        checks_state: ci_state,
+                        status_contexts: Vec::new(),
+                        review_decision: PrReviewDecision::ReviewRequired,
+                        is_draft: false,
+                        awaiting_review_since: None,
+                        requested_reviewers: Vec::new(),
+                        head_oid: String::new(),
+                    },
+                },
+            },
+        }
+    }
+
+    fn data(groups: Vec<PrGroupData>) -> PrListData {
+        PrListData {
+            groups,
+            local_pr_branch_drift: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn outcome_prefers_failures_then_pending() {
+        assert_eq!(
+            stack_ci_outcome(&data(vec![
+                open_pr(1, PrCiState::Success),
+                open_pr(2, PrCiState::Success)
+            ])),
+            StackCiOutcome::Green
+        );
+        assert_eq!(
+            stack_ci_outcome(&data(vec![
+                open_pr(1, PrCiState::Pending),
+                open_pr(2, PrCiState::Success)
+            ])),
+            StackCiOutcome::Pending
+        );
+        assert_eq!(
+            stack_ci_outcome(&data(vec![
+                open_pr(1, PrCiState::Pending),
+                open_pr(2, PrCiState::Failure)
+            ])),
+            StackCiOutcome::Failed(vec![2])
+        );
+    }
+}
@@ -0,0 +1,326 @@
+//! Maintain a GitHub issue with a checklist of every PR in the current stack.
+//!
+//! The issue number is remembered per stack branch in Git config
+//! (`branch.<name>.spr-tracking-issue`), so `spr update` and `spr land` can refresh the checklist
+//! without extra flags. PRs that drop out of the local stack (typically because they landed) stay
+//! in the checklist, checked off, so the issue keeps the full history of the feature.
+
+use anyhow::{bail, Result};
+use std::collections::HashSet;
+use tracing::{info, warn};
+
+use crate::commands::{PrGroupData, RemotePrState};
+use crate::config::LocalPrBranchSyncPolicy;
+use crate::execution::ExecutionMode;
+use crate::git::{git_config_get, git_current_branch, git_rw};
+use crate::github::{PrCiState, PrReviewDecision, PrState};
+use crate::maintenance_output::{
+    MaintenanceOptions, MaintenanceRepoContext, TrackIssueAction, TrackIssueSummaryData,
+};
+
+const TRACKING_ISSUE_MARKER: &str = "<!-- spr-tracking-issue -->";
+const STATUS_SEPARATOR: &str = " — ";
+
+fn tracking_issue_config_key(branch: &str) -> String {
+    format!("branch.{branch}.spr-tracking-issue")
+}
+
+fn ci_label(state: PrCiState) -> &'static str {
+    match state {
+        PrCiState::Success => "passing",
+        PrCiState::Failure | PrCiState::Error => "failing",
+        PrCiState::Pending | PrCiState::Expected => "pending",
+        PrCiState::Unknown => "unknown",
+    }
+}
+
+fn review_label(decision: PrReviewDecision) -> &'static str {
+    match decision {
+        PrReviewDecision::Approved => "approved",
+        PrReviewDecision::ChangesRequested => "changes requested",
+        PrReviewDecision::ReviewRequired => "review required",
+        PrReviewDecision::Unknown => "unknown",
+    }
+}
+
+fn checklist_line(group: &PrGroupData) -> (Option<u64>, String) {
+    match &group.remote.state {
+        RemotePrState::NoRemote => (
+            None,
+            format!(
+                "- [ ] {} {}{}not published yet",
+                group.stable_handle, group.first_subject, STATUS_SEPARATOR
+            ),
+        ),
+        RemotePrState::RemoteWithoutCiReview {
+            pr_number, state, ..
+        }
+        | RemotePrState::RemoteWithCiReview {
+            pr_number, state, ..
+        } if *state == PrState::Merged => (
+            Some(*pr_number),
+            format!(
+                "- [x] #{} {}{}merged",
+                pr_number, group.first_subject, STATUS_SEPARATOR
+            ),
+        ),
+        RemotePrState::RemoteWithoutCiReview { pr_number, .. } => (
+            Some(*pr_number),
+            format!(
+                "- [ ] #{} {}{}open",
+                pr_number, group.first_subject, STATUS_SEPARATOR
+            ),
+        ),
+        RemotePrState::RemoteWithCiReview {
+            pr_number,
+            ci_review_status,
+            ..
+        } => (
+            Some(*pr_number),
+            format!(
+                "- [ ] #{} {}{}open, CI {}, {}",
+                pr_number,
+                group.first_subject,
+                STATUS_SEPARATOR,
+                ci_label(ci_review_status.ci_state),
+                review_label(ci_review_status.review_decision)
+            ),
+        ),
+    }
+}
+
+/// Parse `- [ ] #N subject — status` lines from a previous tracking body.
+fn previous_checklist_entries(body: &str) -> Vec<(u64, String)> {
+    body.lines()
+        .filter_map(|line| {
+            let rest = line
+                .strip_prefix("- [ ] #")
+                .or_else(|| line.strip_prefix("- [x] #"))?;
+            let (number, rest) = rest.split_once(' ')?;
+            let subject = rest
+                .split_once(STATUS_SEPARATOR)
+                .map_or(rest, |(subject, _)| subject);
+            Some((number.parse().ok()?, subject.to_string()))
+        })
+        .collect()
+}
+
+fn render_tracking_issue_body(
+    stack_branch: &str,
+    groups: &[PrGroupData],
+    previous_body: Option<&str>,
+) -> String {
+    let mut current_numbers = HashSet::new();
+    let mut current_lines = Vec::new();
+    for group in groups {
+        let (number, line) = checklist_line(group);
+        current_numbers.extend(number);
+        current_lines.push(line);
+    }
+    let mut lines = vec![
+        TRACKING_ISSUE_MARKER.to_string(),
+        format!("Tracking the `{stack_branch}` stack (bottom → top). Updated by `spr`."),
+        String::new(),
+    ];
+    // Entries that left the local stack were landed (or dropped) earlier; keep them at the
+    // bottom, checked off, so the issue still lists every PR of the feature.
+    for (number, subject) in previous_body
+        .map(previous_checklist_entries)
+        .unwrap_or_default()
+    {
+        if current_numbers.insert(number) {
+            lines.push(format!("- [x] #{number} {subject}{STATUS_SEPARATOR}landed"));
+        }
+    }
+    lines.extend(current_lines);
+    lines.join("\n") + "\n"
+}
+
+fn stored_tracking_issue(stack_branch: &str) -> Result<Option<u64>> {
+    Ok(git_config_get(&tracking_issue_config_key(stack_branch))?
+        .and_then(|value| value.parse().ok()))
+}
+
+fn current_stack_branch() -> Result<String> {
+    let branch = git_current_branch()?;
+    if branch == "HEAD" {
+        bail!("HEAD is detached; check out the stack branch before running `spr track-issue`.");
+    }
+    Ok(branch)
+}
+
+/// Create the stack's tracking issue, or refresh it when one is already recorded.
+///
+/// # Errors
+///
+/// Returns errors when `HEAD` is detached, the stack cannot be read, or GitHub rejects the
+/// issue create/edit.
+pub fn track_issue(
+    base: &str,
+    prefix: &str,
+    ignore_tag: &str,
+    title: Option<&str>,
+    execution_mode: ExecutionMode,
+) -> Result<TrackIssueSummaryData> {
+    let stack_branch = current_stack_branch()?;
+    let data = crate::commands::collect_pr_list_data(
+        base,
+        prefix,
+        ignore_tag,
+        LocalPrBranchSyncPolicy::Off,
+    )?;
+    let dry_run = execution_mode == ExecutionMode::DryRun;
+    let (issue_number, action) = match stored_tracking_issue(&stack_branch)? {
+        Some(number) => {
+            let previous = crate::github::fetch_issue_body(number)?;
+            let body = render_tracking_issue_body(&stack_branch, &data.groups, Some(&previous));
+            crate::github::edit_issue_body(number, &body, execution_mode)?;
+            let action = if dry_run {
+                TrackIssueAction::DryRunUpdate
+            } else {
+                TrackIssueAction::Updated
+            };
+            (Some(number), action)
+        }
+        None => {
+            let body = render_tracking_issue_body(&stack_branch, &data.groups, None);
+            let default_title = format!("Stack: {stack_branch}");
+            let created = crate::github::create_issue(
+                title.unwrap_or(&default_title),
+                &body,
+                execution_mode,
+            )?;
+            if let Some(number) = created {
+                git_rw(
+                    execution_mode,
+                    [
+                        "config",
+                        &tracking_issue_config_key(&stack_branch),
+                        &number.to_string(),
+                    ]
+                    .as_slice(),
+                )?;
+            }
+            let action = if dry_run {
+                TrackIssueAction::DryRunCreate
+            } else {
+                TrackIssueAction::Created
+            };
+            (created, action)
+        }
+    };
+    Ok(TrackIssueSummaryData {
+        repo: MaintenanceRepoContext {
+            base: base.to_string(),
+            prefix: prefix.to_string(),
+        },
+        options: MaintenanceOptions { dry_run },
+        stack_branch,
+        issue_number,
+        action,
+        tracked_pr_count: data.groups.len(),
+    })
+}
+
+/// Refresh the tracking issue after `spr update`/`spr land` when the stack has one.
+///
+/// Best-effort: failures are logged as warnings because the calling command already succeeded.
+pub fn refresh_tracking_issue_if_configured(
+    base: &str,
+    prefix: &str,
+    ignore_tag: &str,
+    execution_mode: ExecutionMode,
+) {
+    let Ok(stack_branch) = git_current_branch() else {
+        return;
+    };
+    if !matches!(stored_tracking_issue(&stack_branch), Ok(Some(_))) {
+        return;
+    }
+    if let Err(err) = track_issue(base, prefix, ignore_tag, None, execution_mode) {
+        warn!("Failed to refresh the stack tracking issue: {err:#}");
+    }
+}
+
+pub fn print_track_issue_summary(summary: &TrackIssueSummaryData) {
+    match (summary.action, summary.issue_number) {
+        (TrackIssueAction::Created, Some(number)) => info!(
+            "Created tracking issue #{} for `{}` ({} PR(s)).",
+            number, summary.stack_branch, summary.tracked_pr_count
+        ),
+        (TrackIssueAction::Updated, Some(number)) => info!(
+            "Updated tracking issue #{} for `{}` ({} PR(s)).",
+            number, summary.stack_branch, summary.tracked_pr_count
+        ),
+        (TrackIssueAction::DryRunUpdate, Some(number)) => info!(
+            "DRY-RUN: would update tracking issue #{} for `{}`.",
+            number, summary.stack_branch
+        ),
+        _ => info!(
+            "DRY-RUN: would create a tracking issue for `{}`.",
+            summary.stack_branch
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{previous_checklist_entries, render_tracking_issue_body};
+    use crate::commands::{PrGroupData, RemotePrMetadata, RemotePrState};
+    use crate::github::{PrCiReviewStatus, PrCiState, PrReviewDecision, PrState};
+
+    fn group(n: usize, tag: &str, state: RemotePrState) -> PrGroupData {
+        PrGroupData {
+            local_pr_number: n,
+            stable_handle: format!("pr:{tag}"),
+            head_branch: format!("dank-spr/{tag}"),
+            first_commit_sha: format!("{tag}1"),
+            commit_count: 1,
+            first_subject: format!("feat: {tag}"),
+            planned_push: None,
+            remote: RemotePrMetadata { state },
+        }
+    }
+
+    #[test]
+    fn body_lists_stack_and_keeps_landed_entries_checked() {
+        let groups = vec![
+            group(
+                1,
+                "beta",
+                RemotePrState::RemoteWithCiReview {
+                    pr_number: 12,
+                    url: "https://github.com/o/r/pull/12".to_string(),
+                    base_branch: "main".to_string(),
+                    state: PrState::Open,
+                    ci_review_status: PrCiReviewStatus {
+                        ci_state: PrCiState::Success,
+                        checks_state: PrCiState::Success,
+                        status_contexts: Vec::new(),
+                        review_decision: PrReviewDecision::ReviewRequired,
+                        is_draft: false,
+                        awaiting_review_since: None,
+                        requested_reviewers: Vec::new(),
+                        head_oid: String::new(),
+                    },
+                },
+            ),
+            group(2, "gamma", RemotePrState::NoRemote),
+        ];
+        let previous = "<!-- spr-tracking-issue -->\n\n- [ ] #11 feat: alpha — open, CI passing, approved\n- [ ] #12 feat: beta — open\n";
+
+        let body = render_tracking_issue_body("stack", &groups, Some(previous));
+
+        assert_eq!(
+            body,
+            "<!-- spr-tracking-issue -->\nTracking the `stack` stack (bottom → top). Updated by `spr`.\n\n- [x] #11 feat: alpha — landed\n- [ ] #12 feat: beta — open, CI passing, review required\n- [ ] pr:gamma feat: gamma — not published yet\n"
+        );
+        assert_eq!(
+            previous_checklist_entries(&body),
+            vec![
+                (11, "feat: alpha".to_string()),
+                (12, "feat: beta".to_string())
+            ]
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use anyhow::{anyhow, Context, Result};
 use indicatif::{ProgressBar, ProgressStyle};
 use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use std::time::Duration;
 use time::{format_description::well_known::Rfc3339, Duration as TimeDuration, OffsetDateTime};
 use tracing::{info, warn};
@@ -11,12 +12,12 @@ use crate::branch_names::{
 use crate::commands::common;
 use crate::config::{ListOrder, LocalPrBranchSyncPolicy, PrDescriptionMode};
 use crate::execution::ExecutionMode;
-use crate::git::{get_remote_branches_sha, gh_rw, git_is_ancestor, git_rw, sanitize_gh_base_ref};
+use crate::git::{get_remote_branches_sha, git_is_ancestor, git_rw, remote, sanitize_gh_base_ref};
 use crate::github::{
     convert_pull_requests_to_draft, fetch_pr_bodies_graphql, fetch_pr_stage_info_graphql,
-    get_repo_owner_name, graphql_escape, is_resource_limit_error,
-    list_recent_terminal_prs_for_heads, mark_pull_requests_ready_for_review, upsert_pr_cached,
-    PrStageInfo, TerminalPrState,
+    fetch_pr_states_graphql, is_resource_limit_error, list_recent_terminal_prs_for_heads,
+    mark_pull_requests_ready_for_review, upsert_pr_cached, GraphqlMutation, PrStageInfo,
+    TerminalPrState,
 };
 use crate::limit::{apply_limit_groups, Limit};
 use crate::parsing::Group;
@@ -24,22 +25,33 @@ use crate::pr_base_chain::{
     build_desired_pr_base_chain, plan_base_reconciliation, verify_base_edits_converged,
     BaseReconciliationAction, BaseReconciliationDecision, ObservedPrBaseChain,
 };
+use crate::reviewers::{collect_group_reviewers, configured_reviewers, request_missing_reviewers};
+use crate::stack_segments::{has_segment_bases, replay_segment_onto_base, split_segments};
+use crate::title_transforms::TitleTransformConfig;
 use crate::update_output::{
     SkippedUpdateGroupData, UpdateEditAction, UpdateExecutionData, UpdateGroupData, UpdatePrAction,
     UpdatePushAction, UpdateSkippedReason,
 };
+use crate::update_progress::{clear_progress, load_progress, save_progress, UpdateProgress};
 
 #[cfg(test)]
 use crate::parsing::{derive_groups_between_with_ignored, split_groups_for_update};
+#[cfg(test)]
+use crate::stack_segments::isolate_active_stack;
+
+/// Opening marker of the stack block spr maintains at the end of each PR body.
+pub(crate) const STACK_BLOCK_START: &str = "<!-- spr-stack:start -->";
+/// Closing marker of the stack block spr maintains at the end of each PR body.
+pub(crate) const STACK_BLOCK_END: &str = "<!-- spr-stack:end -->";
 
 /// Replace the existing spr stack block with `new_block`, or append it if missing.
 ///
-/// The stack block is delimited by `<!-- spr-stack:start -->` and
-/// `<!-- spr-stack:end -->`. If the markers are absent, the block is appended
-/// with a blank line separator (or becomes the whole body when empty).
+/// The stack block is delimited by [`STACK_BLOCK_START`] and [`STACK_BLOCK_END`]. If the markers
+/// are absent, the block is appended with a blank line separator (or becomes the whole body when
+/// empty).
 fn update_stack_block(body: &str, new_block: &str) -> String {
-    let start = "<!-- spr-stack:start -->";
-    let end = "<!-- spr-stack:end -->";
+    let start = STACK_BLOCK_START;
+    let end = STACK_BLOCK_END;
     if let (Some(s), Some(e)) = (body.find(start), body.find(end)) {
         if e >= s {
             let e = e + end.len();
@@ -57,6 +69,22 @@ fn update_stack_block(body: &str, new_block: &str) -> String {
     }
 }
 
+/// Description `update` publishes for `group`: its commit-message body, merged into the
+/// repository's PR template with `body_template`. The commit message itself never carries the
+/// template, so `spr describe` edits only what the author wrote.
+fn templated_pr_body_base(group: &Group) -> Result<String> {
+    Ok(crate::pr_template::apply(&group.pr_body_base()?))
+}
+
+/// Body of a newly created PR: its description followed by a placeholder stack block.
+fn new_pr_body(group: &Group) -> Result<String> {
+    let cleaned = templated_pr_body_base(group)?;
+    let sep = if cleaned.is_empty() { "" } else { "\n\n" };
+    Ok(format!(
+        "{cleaned}{sep}{STACK_BLOCK_START}\n(placeholder; will be filled by spr)\n{STACK_BLOCK_END}",
+    ))
+}
+
 /// Parse a GitHub GraphQL RFC3339 timestamp string.
 fn parse_github_timestamp_rfc3339(s: &str) -> Result<OffsetDateTime> {
     OffsetDateTime::parse(s, &Rfc3339)
@@ -197,13 +225,33 @@ const MAX_BASE_MUTATION_CHARS: usize = 20_000;
 const MAX_BODY_UPDATES_PER_MUTATION: usize = 1;
 const MAX_BODY_MUTATION_CHARS: usize = 100_000;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum PushKind {
+/// What `spr update` does with a group's head branch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PushKind {
+    /// The remote branch already points at the group tip.
     Skip,
+    /// The branch is new or the remote tip is an ancestor of the group tip.
     FastForward,
+    /// The group was rewritten, so the remote tip is replaced.
     Force,
 }
 
+impl PushKind {
+    /// Classify the push of `target_sha` over the remote branch tip, if the branch exists.
+    ///
+    /// # Errors
+    ///
+    /// Returns errors when the ancestry check fails, e.g. the remote tip was never fetched.
+    pub fn classify(remote_head: Option<&str>, target_sha: &str) -> Result<Self> {
+        Ok(match remote_head {
+            Some(remote_sha) if remote_sha == target_sha => Self::Skip,
+            Some(remote_sha) if !git_is_ancestor(remote_sha, target_sha)? => Self::Force,
+            _ => Self::FastForward,
+        })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct PlannedPush {
     branch: String,
@@ -340,17 +388,15 @@ fn ready_pull_request_ids_requiring_temporary_draft(
 fn draft_protected_base_update_inputs(
     transitions: &[DraftProtectedBaseTransition],
     stage_info_by_number: &HashMap<u64, PrStageInfo>,
-) -> Result<Vec<String>> {
+) -> Result<Vec<PrUpdateInput>> {
     transitions
         .iter()
         .map(|transition| {
             let stage_info = pr_stage_info_for_transition(stage_info_by_number, transition)?;
-            let desired_base_ref = sanitize_gh_base_ref(&transition.desired_base_ref);
-            let fields = [
-                format!("pullRequestId:\"{}\"", stage_info.id),
-                format!("baseRefName:\"{}\"", graphql_escape(&desired_base_ref)),
-            ];
-            Ok(fields.join(", "))
+            Ok(PrUpdateInput::base_ref(
+                &stage_info.id,
+                &sanitize_gh_base_ref(&transition.desired_base_ref),
+            ))
         })
         .collect()
 }
@@ -369,45 +415,68 @@ impl UpdatePushAction {
     }
 }
 
-fn mutation_len_for_inputs(update_inputs: &[String]) -> usize {
-    let mut current_len = "mutation {".len() + 1;
+/// One `updatePullRequest` input: the PR's node ID plus the single field being changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PrUpdateInput {
+    pull_request_id: String,
+    field: &'static str,
+    value: String,
+}
+
+impl PrUpdateInput {
+    fn base_ref(pull_request_id: &str, base_ref: &str) -> Self {
+        Self {
+            pull_request_id: pull_request_id.to_string(),
+            field: "baseRefName",
+            value: base_ref.to_string(),
+        }
+    }
+
+    fn body(pull_request_id: &str, body: &str) -> Self {
+        Self {
+            pull_request_id: pull_request_id.to_string(),
+            field: "body",
+            value: body.to_string(),
+        }
+    }
+}
+
+fn update_mutation(update_inputs: &[PrUpdateInput]) -> GraphqlMutation {
+    let mut mutation = GraphqlMutation::new();
     for (i, input) in update_inputs.iter().enumerate() {
-        let alias = format!("m{}: ", i);
-        let frag = format!(
-            "updatePullRequest(input:{{{}}}){{ clientMutationId }} ",
-            input
-        );
-        current_len += alias.len() + frag.len();
+        let id = mutation.var("ID!", &input.pull_request_id);
+        let value = mutation.var("String!", &input.value);
+        mutation.field(format!(
+            "m{i}: updatePullRequest(input:{{pullRequestId:{id}, {}:{value}}}){{ clientMutationId }}",
+            input.field
+        ));
     }
-    current_len + 1
+    mutation
+}
+
+fn mutation_len_for_inputs(update_inputs: &[PrUpdateInput]) -> usize {
+    update_mutation(update_inputs).request_len()
 }
 
 fn chunk_update_inputs(
-    update_inputs: &[String],
+    update_inputs: &[PrUpdateInput],
     max_ops: usize,
     max_chars: usize,
-) -> Vec<Vec<String>> {
-    let mut chunks: Vec<Vec<String>> = Vec::new();
-    let mut current: Vec<String> = Vec::new();
-    let mut current_len = "mutation {".len() + 1;
+) -> Vec<Vec<PrUpdateInput>> {
+    let empty_len = mutation_len_for_inputs(&[]);
+    let mut chunks: Vec<Vec<PrUpdateInput>> = Vec::new();
+    let mut current: Vec<PrUpdateInput> = Vec::new();
+    let mut current_len = empty_len;
     for input in update_inputs {
-        let alias = format!("m{}: ", current.len());
-        let frag = format!(
-            "updatePullRequest(input:{{{}}}){{ clientMutationId }} ",
-            input
-        );
-        let next_len = current_len + alias.len() + frag.len();
-        if !current.is_empty() && (current.len() + 1 > max_ops || next_len > max_chars) {
+        let input_len = mutation_len_for_inputs(std::slice::from_ref(input)) - empty_len;
+        if !current.is_empty()
+            && (current.len() + 1 > max_ops || current_len + input_len > max_chars)
+        {
             chunks.push(current);
             current = Vec::new();
-            current_len = "mutation {".len() + 1;
+            current_len = empty_len;
         }
-        let alias = format!("m{}: ", current.len());
-        let frag = format!(
-            "updatePullRequest(input:{{{}}}){{ clientMutationId }} ",
-            input
-        );
-        current_len += alias.len() + frag.len();
+        current_len += input_len;
         current.push(input.clone());
     }
     if !current.is_empty() {
@@ -417,7 +486,7 @@ fn chunk_update_inputs(
 }
 
 fn should_use_single_update_mutation(
-    update_inputs: &[String],
+    update_inputs: &[PrUpdateInput],
     max_ops: usize,
     max_chars: usize,
     prefer_single: bool,
@@ -427,28 +496,28 @@ fn should_use_single_update_mutation(
         && mutation_len_for_inputs(update_inputs) <= max_chars
 }
 
-fn run_update_chunk(execution_mode: ExecutionMode, update_inputs: &[String]) -> Result<()> {
+fn run_update_chunk(execution_mode: ExecutionMode, update_inputs: &[PrUpdateInput]) -> Result<()> {
     if update_inputs.is_empty() {
         return Ok(());
     }
-    let mut m = String::from("mutation {");
-    for (i, input) in update_inputs.iter().enumerate() {
-        m.push_str(&format!(
-            "m{}: updatePullRequest(input:{{{}}}){{ clientMutationId }} ",
-            i, input
-        ));
+    if crate::forge::is_gitlab() {
+        for input in update_inputs {
+            crate::gitlab::update_mr(
+                &input.pull_request_id,
+                input.field,
+                &input.value,
+                execution_mode,
+            )?;
+        }
+        return Ok(());
     }
-    m.push('}');
-    gh_rw(
-        execution_mode,
-        ["api", "graphql", "-f", &format!("query={}", m)].as_slice(),
-    )?;
+    update_mutation(update_inputs).run(execution_mode)?;
     Ok(())
 }
 
 fn run_update_chunk_with_retry(
     execution_mode: ExecutionMode,
-    update_inputs: &[String],
+    update_inputs: &[PrUpdateInput],
     progress_bar: Option<&ProgressBar>,
 ) -> Result<()> {
     if update_inputs.is_empty() {
@@ -478,7 +547,7 @@ fn run_update_chunk_with_retry(
 
 fn run_update_mutations(
     execution_mode: ExecutionMode,
-    update_inputs: Vec<String>,
+    update_inputs: Vec<PrUpdateInput>,
     label: &str,
     max_ops: usize,
     max_chars: usize,
@@ -528,6 +597,62 @@ fn ignored_boundary_warning(skipped_handles: &[String]) -> String {
     )
 }
 
+/// Remote branches `<someone>-spr/<tag>` under another prefix for each head `<prefix><tag>`, in
+/// head order. They usually mean someone else stacks the same logical change.
+fn other_prefix_duplicates<'a>(
+    prefix: &str,
+    heads: &[String],
+    remote_branches: impl Iterator<Item = &'a String> + Clone,
+) -> Vec<(String, Vec<String>)> {
+    let mut duplicates = Vec::new();
+    for head in heads {
+        let Some(tag) = head.strip_prefix(prefix) else {
+            continue;
+        };
+        let suffix = format!("-spr/{tag}");
+        let mut others: Vec<String> = remote_branches
+            .clone()
+            .filter(|name| !name.starts_with(prefix))
+            .filter(|name| {
+                name.strip_suffix(&suffix)
+                    .is_some_and(|owner| !owner.is_empty() && !owner.contains('/'))
+            })
+            .cloned()
+            .collect();
+        if !others.is_empty() {
+            others.sort();
+            duplicates.push((head.clone(), others));
+        }
+    }
+    duplicates
+}
+
+/// PRs among `numbers` that are no longer open, with their lowercase GitHub state, in order.
+///
+/// PRs GitHub did not report a state for are left in the plan; the mutation reports them.
+fn prs_no_longer_open(numbers: &[u64], states: &HashMap<u64, String>) -> Vec<(u64, String)> {
+    numbers
+        .iter()
+        .filter_map(|number| {
+            states
+                .get(number)
+                .filter(|state| state.as_str() != "OPEN")
+                .map(|state| (*number, state.to_ascii_lowercase()))
+        })
+        .collect()
+}
+
+fn changed_underneath_warning(number: u64, head: &str, state: &str) -> String {
+    let hint = if state == "merged" {
+        "; `spr drop-merged-prefix` drops merged bottom groups from the local stack"
+    } else {
+        ""
+    };
+    format!(
+        "PR #{number} ({head}) was {state} on GitHub while spr was running; dropped its base and description edits{hint}"
+    )
+}
+
 fn skipped_group_data(skipped_handles: &[String]) -> Vec<SkippedUpdateGroupData> {
     skipped_handles
         .iter()
@@ -555,10 +680,23 @@ fn empty_update_execution(skipped_handles: &[String]) -> UpdateExecutionData {
     }
 }
 
+/// Apply `record` to the run's progress journal and persist it; a no-op under `--dry-run`.
+fn record_update_progress(
+    progress: &mut Option<(PathBuf, UpdateProgress)>,
+    record: impl FnOnce(&mut UpdateProgress),
+) -> Result<()> {
+    if let Some((common_dir, progress)) = progress {
+        record(progress);
+        save_progress(common_dir, progress)?;
+    }
+    Ok(())
+}
+
 #[allow(clippy::too_many_arguments)]
 fn build_from_groups_internal(
     base: &str,
     prefix: &str,
+    stack_branch: &str,
     skipped_handles: &[String],
     no_pr: bool,
     execution_mode: ExecutionMode,
@@ -569,6 +707,8 @@ fn build_from_groups_internal(
     allow_branch_reuse: bool,
     branch_reuse_guard_days: u32,
     local_pr_branch_policy: LocalPrBranchSyncPolicy,
+    title_transforms: &TitleTransformConfig,
+    draft_new_prs: bool,
     render_progress: bool,
 ) -> Result<UpdateExecutionData> {
     let dry_run = execution_mode == ExecutionMode::DryRun;
@@ -596,7 +736,53 @@ fn build_from_groups_internal(
     }
     let total_groups = groups.len();
     let branch_identities = group_branch_identities(&groups, prefix)?;
-    let desired_chain = build_desired_pr_base_chain(base, &groups, prefix)?;
+    let heads: Vec<String> = branch_identities
+        .iter()
+        .map(|identity| identity.exact.clone())
+        .collect();
+    let base_ref_for_remote = sanitize_gh_base_ref(base);
+    let mut branch_names = heads.clone();
+    if !branch_names.contains(&base_ref_for_remote) {
+        branch_names.push(base_ref_for_remote);
+    }
+    // `ls-remote` matches patterns against trailing ref components, so the bare tags also find
+    // `<someone>-spr/<tag>` branches of other prefixes in the same round trip.
+    let ls_remote_patterns: Vec<String> = branch_names
+        .iter()
+        .cloned()
+        .chain(
+            heads
+                .iter()
+                .filter_map(|head| head.strip_prefix(prefix))
+                .map(str::to_string),
+        )
+        .collect();
+    // Listing PRs, reading remote branch tips, and planning the desired base chain are
+    // independent, so they run side by side instead of paying three round trips in a row.
+    let ((observed_pr_bases, remote_map), desired_chain) = crate::concurrency::join(
+        || {
+            crate::concurrency::join(
+                || {
+                    if no_pr {
+                        Ok(ObservedPrBaseChain::default())
+                    } else {
+                        ObservedPrBaseChain::observe_for_heads(&heads)
+                    }
+                },
+                || get_remote_branches_sha(&ls_remote_patterns),
+            )
+        },
+        || build_desired_pr_base_chain(base, &groups, prefix),
+    );
+    let (mut observed_pr_bases, mut remote_map, desired_chain) =
+        (observed_pr_bases?, remote_map?, desired_chain?);
+    for (head, others) in other_prefix_duplicates(prefix, &heads, remote_map.keys()) {
+        warn!(
+            "{} shares its tag with {}; someone else may already be stacking the same change",
+            head,
+            others.join(", ")
+        );
+    }
     let desired_base_by_head: HashMap<String, String> = desired_chain
         .iter()
         .map(|desired| {
@@ -609,15 +795,6 @@ fn build_from_groups_internal(
 
     info!("Preparing {} group(s)…", groups.len());
 
-    let heads: Vec<String> = branch_identities
-        .iter()
-        .map(|identity| identity.exact.clone())
-        .collect();
-    let mut observed_pr_bases = if no_pr {
-        ObservedPrBaseChain::default()
-    } else {
-        ObservedPrBaseChain::observe_for_heads(&heads)?
-    };
     let mut prs_by_head = observed_pr_bases.pr_numbers_by_head();
     enforce_branch_reuse_guard(
         no_pr,
@@ -632,21 +809,19 @@ fn build_from_groups_internal(
     } else {
         plan_base_reconciliation(&desired_chain, &observed_pr_bases)
     };
-    let mut branch_names = heads.clone();
-    let base_ref_for_remote = sanitize_gh_base_ref(base);
-    if !branch_names.contains(&base_ref_for_remote) {
-        branch_names.push(base_ref_for_remote);
-    }
+    let mut current_base_refs: Vec<String> = Vec::new();
     for current_base_ref in initial_base_reconciliation
         .iter()
         .filter_map(|decision| decision.current_base_ref.as_deref())
         .map(sanitize_gh_base_ref)
     {
-        if !branch_names.contains(&current_base_ref) {
-            branch_names.push(current_base_ref);
+        if !branch_names.contains(&current_base_ref)
+            && !current_base_refs.contains(&current_base_ref)
+        {
+            current_base_refs.push(current_base_ref);
         }
     }
-    let remote_map = get_remote_branches_sha(&branch_names)?;
+    remote_map.extend(get_remote_branches_sha(&current_base_refs)?);
 
     let display_indices = list_order.display_indices(groups.len());
     for (display_idx, group_idx) in display_indices.iter().enumerate() {
@@ -668,17 +843,7 @@ fn build_from_groups_internal(
             .last()
             .cloned()
             .ok_or_else(|| anyhow!("Group {} has no commits", group.selector_text()))?;
-        let kind = if remote_head.as_deref() == Some(target_sha.as_str()) {
-            PushKind::Skip
-        } else if let Some(ref remote_sha) = remote_head {
-            if git_is_ancestor(remote_sha, &target_sha)? {
-                PushKind::FastForward
-            } else {
-                PushKind::Force
-            }
-        } else {
-            PushKind::FastForward
-        };
+        let kind = PushKind::classify(remote_head.as_deref(), &target_sha)?;
         planned.push(PlannedPush {
             branch,
             target_sha,
@@ -687,7 +852,41 @@ fn build_from_groups_internal(
         });
     }
 
-    let draft_protected_transitions = if no_pr {
+    let tips_to_check: Vec<crate::pre_push_check::GroupTip> = groups
+        .iter()
+        .zip(&planned)
+        .filter(|(_, planned_push)| planned_push.kind != PushKind::Skip)
+        .map(|(group, planned_push)| crate::pre_push_check::GroupTip {
+            selector: group.selector_text(),
+            sha: planned_push.target_sha.clone(),
+        })
+        .collect();
+    crate::pre_push_check::check_group_tips(&tips_to_check, execution_mode)?;
+
+    let mut progress = if execution_mode == ExecutionMode::Apply {
+        let tip = planned
+            .last()
+            .map(|planned_push| planned_push.target_sha.clone())
+            .unwrap_or_default();
+        let common_dir = crate::git::git_common_dir()?;
+        let progress = load_progress(&common_dir, stack_branch, base, &tip)?;
+        if !progress.is_empty() {
+            info!(
+                "Resuming interrupted update: {} PR(s) already created",
+                progress.created_prs.len()
+            );
+        }
+        for (head, number) in &progress.created_prs {
+            prs_by_head.entry(head_key(head)).or_insert(*number);
+        }
+        Some((common_dir, progress))
+    } else {
+        None
+    };
+
+    // The temporary-draft guard relies on GitHub's draft and base mutations; GitLab merge requests
+    // are retargeted directly.
+    let draft_protected_transitions = if no_pr || crate::forge::is_gitlab() {
         Vec::new()
     } else {
         draft_protected_base_transitions(&initial_base_reconciliation, &planned)?
@@ -702,11 +901,11 @@ fn build_from_groups_internal(
         .iter()
         .map(|transition| transition.head_branch.clone())
         .collect::<Vec<_>>();
-    let draft_protected_head_branches = draft_protected_transitions
+    let mut draft_protected_head_branches = draft_protected_transitions
         .iter()
         .map(|transition| transition.head_branch.clone())
         .collect::<Vec<_>>();
-    let temporarily_drafted_pull_request_ids = if draft_protected_transitions.is_empty() {
+    let mut temporarily_drafted_pull_request_ids = if draft_protected_transitions.is_empty() {
         Vec::new()
     } else {
         let protected_pr_numbers = draft_protected_transitions
@@ -758,7 +957,7 @@ fn build_from_groups_internal(
         })
         .collect();
     if !ff_refspecs.is_empty() {
-        let mut argv: Vec<String> = vec!["push".into(), "origin".into()];
+        let mut argv: Vec<String> = vec!["push".into(), remote()];
         argv.extend(ff_refspecs.clone());
         let args: Vec<&str> = argv.iter().map(|item| item.as_str()).collect();
         if render_progress {
@@ -801,7 +1000,7 @@ fn build_from_groups_internal(
                 })
             })
             .collect();
-        let mut argv: Vec<String> = vec!["push".into(), "origin".into()];
+        let mut argv: Vec<String> = vec!["push".into(), remote()];
         if force_leases.is_empty() {
             argv.push("--force-with-lease".into());
         } else {
@@ -851,6 +1050,7 @@ fn build_from_groups_internal(
         groups.len()
     ];
     let mut created_without_number: HashSet<usize> = HashSet::new();
+    let mut changed_underneath: Vec<String> = Vec::new();
     let mut parent_branch = base.to_string();
     for (group_idx, (group, identity)) in groups.iter().zip(branch_identities.iter()).enumerate() {
         let branch = identity.exact.clone();
@@ -863,8 +1063,9 @@ fn build_from_groups_internal(
                 let number = upsert_pr_cached(
                     &branch,
                     &sanitize_gh_base_ref(&parent_branch),
-                    &group.pr_title()?,
-                    &group.pr_body()?,
+                    &group.pr_title_with(title_transforms)?,
+                    &new_pr_body(group)?,
+                    draft_new_prs || group.requests_draft(),
                     execution_mode,
                     &mut prs_by_head,
                 )?;
@@ -872,6 +1073,9 @@ fn build_from_groups_internal(
                 pr_actions_by_group[group_idx] = if was_known {
                     UpdatePrAction::Existing
                 } else {
+                    record_update_progress(&mut progress, |progress| {
+                        progress.created_prs.insert(branch.clone(), number);
+                    })?;
                     UpdatePrAction::Created
                 };
             }
@@ -889,6 +1093,7 @@ fn build_from_groups_internal(
         let mut desired_stack_by_number: HashMap<u64, String> = HashMap::new();
         let mut base_body_by_number: HashMap<u64, String> = HashMap::new();
         let mut desired_base_by_number: HashMap<u64, String> = HashMap::new();
+        let mut review_guide_by_number: HashMap<u64, String> = HashMap::new();
         let numbers_rev: Vec<u64> = numbers_full.iter().cloned().rev().collect();
         for (group_idx, identity) in branch_identities.iter().enumerate() {
             if let Some(number) = pr_numbers_by_group[group_idx] {
@@ -898,7 +1103,7 @@ fn build_from_groups_internal(
                     .ok_or_else(|| anyhow!("Missing desired base ref for {}", identity.exact))?;
                 desired_base_by_number.insert(number, want_base_ref);
                 let group = &groups[group_idx];
-                let base_body = group.pr_body_base()?;
+                let base_body = templated_pr_body_base(group)?;
                 base_body_by_number.insert(number, base_body);
                 let mut lines = String::new();
                 for pr_number in &numbers_rev {
@@ -907,13 +1112,30 @@ fn build_from_groups_internal(
                     } else {
                         crate::format::EM_SPACE
                     };
-                    lines.push_str(&format!("- {} #{}\n", marker, pr_number));
+                    lines.push_str(&format!(
+                        "- {} {}\n",
+                        marker,
+                        crate::forge::pr_reference(*pr_number)
+                    ));
                 }
                 let stack_block = format!(
-                    "<!-- spr-stack:start -->\n**Stack**:\n{}\n\n⚠️ *Part of a stack created by [spr-multicommit](https://github.com/mattskl-openai/spr-multicommit). Do not merge manually using the UI - doing so may have unexpected results.*\n<!-- spr-stack:end -->",
+                    "{STACK_BLOCK_START}\n**Stack**:\n{}\n\n⚠️ *Part of a stack created by [spr-multicommit](https://github.com/mattskl-openai/spr-multicommit). Do not merge manually using the UI - doing so may have unexpected results.*\n{STACK_BLOCK_END}",
                     lines.trim_end(),
                 );
                 desired_stack_by_number.insert(number, stack_block);
+                if crate::review_guide::enabled() {
+                    let position = crate::review_guide::StackPosition {
+                        index: group_idx + 1,
+                        total: groups.len(),
+                        parent: group_idx
+                            .checked_sub(1)
+                            .and_then(|parent_idx| pr_numbers_by_group[parent_idx]),
+                        child: pr_numbers_by_group.get(group_idx + 1).copied().flatten(),
+                        base: base.to_string(),
+                    };
+                    review_guide_by_number
+                        .insert(number, crate::review_guide::review_guide(group, &position)?);
+                }
             }
         }
 
@@ -935,8 +1157,8 @@ fn build_from_groups_internal(
             .enumerate()
             .filter_map(|(group_idx, maybe_number)| maybe_number.map(|number| (number, group_idx)))
             .collect();
-        let mut body_updates: Vec<String> = Vec::new();
-        let mut base_updates: Vec<String> = Vec::new();
+        let mut body_updates: Vec<PrUpdateInput> = Vec::new();
+        let mut base_updates: Vec<PrUpdateInput> = Vec::new();
         if dry_run && !created_without_number.is_empty() {
             for group_idx in group_index_by_number.values().copied() {
                 description_actions_by_group[group_idx] = UpdateEditAction::Updated;
@@ -957,21 +1179,23 @@ fn build_from_groups_internal(
                     } else {
                         update_stack_block(&info.body, stack_block)
                     };
+                    let desired_body = match review_guide_by_number.get(&number) {
+                        Some(guide) => {
+                            crate::review_guide::update_review_guide(&desired_body, guide)
+                        }
+                        None => desired_body,
+                    };
                     if desired_body != info.body {
                         if let Some(&group_idx) = group_index_by_number.get(&number) {
                             description_actions_by_group[group_idx] = UpdateEditAction::Updated;
                         }
-                        let fields = [
-                            format!("pullRequestId:\"{}\"", info.id),
-                            format!("body:\"{}\"", graphql_escape(&desired_body)),
-                        ];
-                        body_updates.push(fields.join(", "));
+                        body_updates.push(PrUpdateInput::body(&info.id, &desired_body));
                     }
                 }
             }
         }
         let base_reconciliation = plan_base_reconciliation(&desired_chain, &observed_pr_bases);
-        let edited_head_branches = base_reconciliation
+        let mut edited_head_branches = base_reconciliation
             .iter()
             .filter(|decision| decision.action == BaseReconciliationAction::NeedsEdit)
             .map(|decision| decision.desired.head_branch.clone())
@@ -993,17 +1217,61 @@ fn build_from_groups_internal(
                     if let Some(&group_idx) = group_index_by_number.get(&number) {
                         base_actions_by_group[group_idx] = UpdateEditAction::Updated;
                     }
-                    let fields = [
-                        format!("pullRequestId:\"{}\"", info.id),
-                        format!("baseRefName:\"{}\"", graphql_escape(&desired_base_ref)),
-                    ];
-                    base_updates.push(fields.join(", "));
+                    base_updates.push(PrUpdateInput::base_ref(&info.id, &desired_base_ref));
                 }
             }
         }
         for group_idx in created_without_number {
             description_actions_by_group[group_idx] = UpdateEditAction::Updated;
         }
+        if execution_mode == ExecutionMode::Apply
+            && !(base_updates.is_empty()
+                && body_updates.is_empty()
+                && temporarily_drafted_pull_request_ids.is_empty())
+        {
+            // A reviewer can merge or close a PR in the UI after it was read above; editing it now
+            // would fail the whole batch, so re-check right before mutating and leave it out.
+            let number_by_id: HashMap<&str, u64> = bodies_by_number
+                .iter()
+                .map(|(number, info)| (info.id.as_str(), *number))
+                .collect();
+            let mut to_mutate: Vec<u64> = base_updates
+                .iter()
+                .chain(&body_updates)
+                .map(|input| input.pull_request_id.as_str())
+                .chain(
+                    temporarily_drafted_pull_request_ids
+                        .iter()
+                        .map(String::as_str),
+                )
+                .filter_map(|id| number_by_id.get(id).copied())
+                .collect();
+            to_mutate.sort_unstable();
+            to_mutate.dedup();
+            let states = fetch_pr_states_graphql(&to_mutate)?;
+            let mut gone_ids: HashSet<String> = HashSet::new();
+            let mut gone_heads: HashSet<String> = HashSet::new();
+            for (number, state) in prs_no_longer_open(&to_mutate, &states) {
+                let Some(&group_idx) = group_index_by_number.get(&number) else {
+                    continue;
+                };
+                let head = branch_identities[group_idx].exact.clone();
+                let message = changed_underneath_warning(number, &head, &state);
+                warn!("{}", message);
+                changed_underneath.push(message);
+                base_actions_by_group[group_idx] = UpdateEditAction::Unchanged;
+                description_actions_by_group[group_idx] = UpdateEditAction::Unchanged;
+                if let Some(info) = bodies_by_number.get(&number) {
+                    gone_ids.insert(info.id.clone());
+                }
+                gone_heads.insert(head);
+            }
+            base_updates.retain(|input| !gone_ids.contains(&input.pull_request_id));
+            body_updates.retain(|input| !gone_ids.contains(&input.pull_request_id));
+            temporarily_drafted_pull_request_ids.retain(|id| !gone_ids.contains(id));
+            edited_head_branches.retain(|head| !gone_heads.contains(head));
+            draft_protected_head_branches.retain(|head| !gone_heads.contains(head));
+        }
         let should_verify_base_updates = !edited_head_branches.is_empty();
         if !base_updates.is_empty() || !body_updates.is_empty() {
             if !base_updates.is_empty() {
@@ -1052,24 +1320,23 @@ fn build_from_groups_internal(
             let group = &groups[group_idx];
             let head_branch = &branch_identities[group_idx].exact;
             if let Some(number) = pr_number_for_head(&prs_by_head, head_branch) {
-                let title = group.pr_title().unwrap_or_else(|_| String::new());
+                let title = group
+                    .pr_title_with(title_transforms)
+                    .unwrap_or_else(|_| String::new());
                 ordered.push((number, title));
             }
         }
         if !ordered.is_empty() {
-            if let Ok((owner, name)) = get_repo_owner_name() {
+            if let Ok(url_prefix) = crate::forge::pr_url_prefix() {
                 info!("PRs:");
                 for (number, title) in ordered {
-                    let url = format!("https://github.com/{}/{}/pull/{}", owner, name, number);
-                    info!("  {} - {}", url, title);
+                    info!("  {}{} - {}", url_prefix, number, title);
                 }
             }
         }
     }
 
-    let remote_url_prefix = get_repo_owner_name()
-        .ok()
-        .map(|(owner, name)| format!("https://github.com/{owner}/{name}/pull/"));
+    let remote_url_prefix = crate::forge::pr_url_prefix().ok();
     let local_pr_targets = planned
         .iter()
         .enumerate()
@@ -1086,7 +1353,6 @@ fn build_from_groups_internal(
         execution_mode,
         &local_pr_targets,
     )?;
-
     let groups = groups
         .iter()
         .zip(branch_identities.iter())
@@ -1101,7 +1367,9 @@ fn build_from_groups_internal(
                     .get(&identity.exact)
                     .cloned()
                     .unwrap_or_else(|| base.to_string()),
-                title: group.pr_title().unwrap_or_else(|_| String::new()),
+                title: group
+                    .pr_title_with(title_transforms)
+                    .unwrap_or_else(|_| String::new()),
                 target_sha: planned_push.target_sha.clone(),
                 push_action: UpdatePushAction::from_planned_push(planned_push),
                 pr_action: pr_actions_by_group[group_idx],
@@ -1115,18 +1383,225 @@ fn build_from_groups_internal(
             },
         )
         .collect();
+    let mut warnings = update_warnings(skipped_handles);
+    warnings.extend(changed_underneath);
     Ok(UpdateExecutionData {
-        warnings: update_warnings(skipped_handles),
+        warnings,
         skipped_groups: skipped_group_data(skipped_handles),
         groups,
         local_pr_branch_actions,
     })
 }
 
+/// Publish `groups` with [`publish_segments`], then request the declared reviewers that are
+/// still missing on the published PRs, apply the `protected_paths` reviewers and labels, attach them to the configured milestone and project, and
+/// record the published groups of `stack_branch` in the remote state ref.
+#[allow(clippy::too_many_arguments)]
+fn build_from_segments(
+    base: &str,
+    prefix: &str,
+    stack_branch: &str,
+    skipped_handles: &[String],
+    no_pr: bool,
+    execution_mode: ExecutionMode,
+    pr_description_mode: PrDescriptionMode,
+    limit: Option<Limit>,
+    groups: Vec<Group>,
+    list_order: ListOrder,
+    allow_branch_reuse: bool,
+    branch_reuse_guard_days: u32,
+    local_pr_branch_policy: LocalPrBranchSyncPolicy,
+    title_transforms: &TitleTransformConfig,
+    draft_new_prs: bool,
+    render_progress: bool,
+) -> Result<UpdateExecutionData> {
+    // Review requests go through GitHub's reviewer API only
+    let group_reviewers = if no_pr || crate::forge::is_gitlab() {
+        Vec::new()
+    } else {
+        collect_group_reviewers(&groups, &configured_reviewers())?
+    };
+    let protected_matches =
+        crate::protected_paths::check_groups(&groups, &crate::protected_paths::configured_rules())?;
+    let mut execution = publish_segments(
+        base,
+        prefix,
+        stack_branch,
+        skipped_handles,
+        no_pr,
+        execution_mode,
+        pr_description_mode,
+        limit,
+        groups,
+        list_order,
+        allow_branch_reuse,
+        branch_reuse_guard_days,
+        local_pr_branch_policy,
+        title_transforms,
+        draft_new_prs,
+        render_progress,
+    )?;
+    let reviewer_requests: Vec<(u64, Vec<String>)> = group_reviewers
+        .into_iter()
+        .filter_map(|declared| {
+            execution
+                .groups
+                .iter()
+                .find(|group| group.stable_handle == declared.stable_handle)
+                .and_then(|group| group.remote_pr_number)
+                .map(|number| (number, declared.reviewers))
+        })
+        .collect();
+    request_missing_reviewers(&reviewer_requests, execution_mode);
+    for found in &protected_matches {
+        let Some(group) = execution
+            .groups
+            .iter()
+            .find(|group| group.stable_handle == found.stable_handle)
+        else {
+            continue;
+        };
+        if render_progress {
+            warn!("{}", found.warning());
+        }
+        execution.warnings.push(found.warning());
+        // Protected-path reviewers and labels are applied through GitHub only
+        if let Some(number) = group.remote_pr_number {
+            if !no_pr && !crate::forge::is_gitlab() {
+                crate::protected_paths::apply_to_pr(number, found, execution_mode);
+            }
+        }
+    }
+    // Milestones and projects are GitHub-only
+    if !no_pr && !crate::forge::is_gitlab() {
+        let pr_numbers: Vec<u64> = execution
+            .groups
+            .iter()
+            .filter_map(|group| group.remote_pr_number)
+            .collect();
+        crate::commands::attach_stack_prs_if_configured(&pr_numbers, execution_mode);
+    }
+    // Best effort: the remote state ref is informational and must not fail a successful
+    // publication.
+    if let Err(err) = crate::remote_state::publish_update_state(
+        prefix,
+        stack_branch,
+        &execution.groups,
+        execution_mode,
+    ) {
+        let warning = format!(
+            "Failed to update {}: {err:#}",
+            crate::remote_state::state_ref(prefix)
+        );
+        if render_progress {
+            warn!("{warning}");
+        }
+        execution.warnings.push(warning);
+    }
+    Ok(execution)
+}
+
+/// Publish `groups`, splitting them into one PR chain per `pr:base:` segment.
+///
+/// Without segment directives this is [`build_from_groups_internal`] unchanged. Otherwise `limit`
+/// applies to the whole stack, each segment above the first is replayed onto its own base, and
+/// the per-segment results are concatenated bottom-up. The resume journal keeps one entry per
+/// segment of `stack_branch` and is cleared only once every segment is published.
+#[allow(clippy::too_many_arguments)]
+fn publish_segments(
+    base: &str,
+    prefix: &str,
+    stack_branch: &str,
+    skipped_handles: &[String],
+    no_pr: bool,
+    execution_mode: ExecutionMode,
+    pr_description_mode: PrDescriptionMode,
+    limit: Option<Limit>,
+    groups: Vec<Group>,
+    list_order: ListOrder,
+    allow_branch_reuse: bool,
+    branch_reuse_guard_days: u32,
+    local_pr_branch_policy: LocalPrBranchSyncPolicy,
+    title_transforms: &TitleTransformConfig,
+    draft_new_prs: bool,
+    render_progress: bool,
+) -> Result<UpdateExecutionData> {
+    let execution = if !has_segment_bases(&groups) {
+        build_from_groups_internal(
+            base,
+            prefix,
+            stack_branch,
+            skipped_handles,
+            no_pr,
+            execution_mode,
+            pr_description_mode,
+            limit,
+            groups,
+            list_order,
+            allow_branch_reuse,
+            branch_reuse_guard_days,
+            local_pr_branch_policy,
+            title_transforms,
+            draft_new_prs,
+            render_progress,
+        )?
+    } else {
+        let segments = split_segments(base, apply_limit_groups(groups, limit)?);
+        let last_segment_idx = segments.len() - 1;
+        let mut combined = empty_update_execution(&[]);
+        for (segment_idx, mut segment) in segments.into_iter().enumerate() {
+            if segment.base != base {
+                info!(
+                    "Replaying {} group(s) from {} onto segment base {}",
+                    segment.groups.len(),
+                    segment.groups[0].selector_text(),
+                    segment.base
+                );
+                replay_segment_onto_base(&mut segment)?;
+            }
+            let execution = build_from_groups_internal(
+                &segment.base,
+                prefix,
+                stack_branch,
+                if segment_idx == last_segment_idx {
+                    skipped_handles
+                } else {
+                    &[]
+                },
+                no_pr,
+                execution_mode,
+                pr_description_mode,
+                None,
+                segment.groups,
+                list_order,
+                allow_branch_reuse,
+                branch_reuse_guard_days,
+                local_pr_branch_policy,
+                title_transforms,
+                draft_new_prs,
+                render_progress,
+            )?;
+            combined.warnings.extend(execution.warnings);
+            combined.skipped_groups.extend(execution.skipped_groups);
+            combined.groups.extend(execution.groups);
+            combined
+                .local_pr_branch_actions
+                .extend(execution.local_pr_branch_actions);
+        }
+        combined
+    };
+    // Every segment of the stack is published; a rerun no longer needs the created PRs
+    if execution_mode == ExecutionMode::Apply {
+        clear_progress(&crate::git::git_common_dir()?, stack_branch)?;
+    }
+    Ok(execution)
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn build_from_groups_with_summary(
     base: &str,
     prefix: &str,
+    stack_branch: &str,
     skipped_handles: &[String],
     no_pr: bool,
     execution_mode: ExecutionMode,
@@ -1137,10 +1612,13 @@ pub fn build_from_groups_with_summary(
     allow_branch_reuse: bool,
     branch_reuse_guard_days: u32,
     local_pr_branch_policy: LocalPrBranchSyncPolicy,
+    title_transforms: &TitleTransformConfig,
+    draft_new_prs: bool,
 ) -> Result<UpdateExecutionData> {
-    build_from_groups_internal(
+    build_from_segments(
         base,
         prefix,
+        stack_branch,
         skipped_handles,
         no_pr,
         execution_mode,
@@ -1151,6 +1629,8 @@ pub fn build_from_groups_with_summary(
         allow_branch_reuse,
         branch_reuse_guard_days,
         local_pr_branch_policy,
+        title_transforms,
+        draft_new_prs,
         false,
     )
 }
@@ -1159,6 +1639,7 @@ pub fn build_from_groups_with_summary(
 pub fn build_from_groups(
     base: &str,
     prefix: &str,
+    stack_branch: &str,
     skipped_handles: &[String],
     no_pr: bool,
     execution_mode: ExecutionMode,
@@ -1169,10 +1650,13 @@ pub fn build_from_groups(
     allow_branch_reuse: bool,
     branch_reuse_guard_days: u32,
     local_pr_branch_policy: LocalPrBranchSyncPolicy,
-) -> Result<()> {
-    build_from_groups_internal(
+    title_transforms: &TitleTransformConfig,
+    draft_new_prs: bool,
+) -> Result<UpdateExecutionData> {
+    build_from_segments(
         base,
         prefix,
+        stack_branch,
         skipped_handles,
         no_pr,
         execution_mode,
@@ -1183,9 +1667,10 @@ pub fn build_from_groups(
         allow_branch_reuse,
         branch_reuse_guard_days,
         local_pr_branch_policy,
+        title_transforms,
+        draft_new_prs,
         true,
-    )?;
-    Ok(())
+    )
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -1201,13 +1686,20 @@ pub fn build_from_tags(
     limit: Option<Limit>,
     list_order: ListOrder,
 ) -> Result<()> {
-    let (_merge_base, leading_ignored, groups): (String, Vec<String>, Vec<Group>) =
+    let (merge_base, mut leading_ignored, mut groups): (String, Vec<String>, Vec<Group>) =
         derive_groups_between_with_ignored(base, from, ignore_tag)?;
+    isolate_active_stack(
+        &merge_base,
+        &format!("{merge_base}..{from}"),
+        &mut leading_ignored,
+        &mut groups,
+    )?;
     let (groups, skipped_handles) = split_groups_for_update(&leading_ignored, groups);
     group_branch_identities(&groups, prefix)?;
     build_from_groups(
         base,
         prefix,
+        &crate::git::stack_ref_name(from)?,
         &skipped_handles,
         no_pr,
         execution_mode,
@@ -1218,20 +1710,26 @@ pub fn build_from_tags(
         true,
         0,
         LocalPrBranchSyncPolicy::Off,
-    )
+        &TitleTransformConfig::default(),
+        false,
+    )?;
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::{
         ancestry_collapse_risk_transitions, branch_reuse_guard_window, build_from_groups,
-        build_from_tags, draft_protected_base_transitions, head_key, heads_without_open_prs,
-        ignored_boundary_warning, parse_github_timestamp_rfc3339, pr_number_for_head,
+        build_from_tags, chunk_update_inputs, draft_protected_base_transitions, head_key,
+        heads_without_open_prs, ignored_boundary_warning, other_prefix_duplicates,
+        parse_github_timestamp_rfc3339, pr_number_for_head, prs_no_longer_open,
         ready_pull_request_ids_requiring_temporary_draft, recent_pr_age,
-        recent_pr_age_blocks_recreation, should_use_single_update_mutation, terminal_pr_action,
-        DraftProtectedBaseTransition, PlannedPush, PushKind,
+        recent_pr_age_blocks_recreation, should_use_single_update_mutation, templated_pr_body_base,
+        terminal_pr_action, update_mutation, DraftProtectedBaseTransition, PlannedPush,
+        PrUpdateInput, PushKind,
     };
     use crate::branch_names::group_branch_identities;
+    use crate::commands::reword::described_message;
     use crate::config::{ListOrder, LocalPrBranchSyncPolicy, PrDescriptionMode};
     use crate::execution::ExecutionMode;
     use crate::github::{PrStageInfo, TerminalPrState};
@@ -1240,8 +1738,10 @@ mod tests {
         BaseReconciliationAction, BaseReconciliationDecision, DesiredPrBase,
     };
     use crate::test_support::{
-        commit_file, init_case_conflicting_stack_repo, init_repo, lock_cwd, DirGuard,
+        commit_file, git, init_case_conflicting_stack_repo, init_repo, lock_cwd, write_file,
+        DirGuard,
     };
+    use crate::title_transforms::TitleTransformConfig;
     use std::collections::HashMap;
     use time::{Duration as TimeDuration, OffsetDateTime};
 
@@ -1249,6 +1749,46 @@ mod tests {
         OffsetDateTime::from_unix_timestamp(1_800_000_000).unwrap()
     }
 
+    #[test]
+    fn pr_template_is_applied_on_publish_and_survives_a_describe_round_trip() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path().to_path_buf();
+        std::fs::create_dir_all(repo.join(".github")).unwrap();
+        write_file(
+            &repo,
+            ".github/PULL_REQUEST_TEMPLATE.md",
+            "<!-- Thanks! -->\n\n## Summary\n\n<!-- What? -->\n\n## Testing\n\n- [ ] Tests\n",
+        );
+        git(&repo, ["add", "."].as_slice());
+        git(&repo, ["commit", "-m", "add template"].as_slice());
+        let _guard = DirGuard::change_to(&repo);
+        let _template = crate::pr_template::BODY_TEMPLATE.scoped(true);
+        let message = "feat: alpha pr:alpha\n\nWhy alpha.";
+        let group = |message: &str| Group {
+            marker: crate::group_markers::GroupMarker::PrLabel("alpha".to_string()),
+            subjects: vec!["feat: alpha pr:alpha".to_string()],
+            commits: vec!["a1".to_string()],
+            first_message: Some(message.to_string()),
+            ignored_after: Vec::new(),
+        };
+
+        // `spr describe` edits only the commit-message body
+        assert_eq!(group(message).pr_body_base().unwrap(), "Why alpha.");
+        let published = templated_pr_body_base(&group(message)).unwrap();
+        assert_eq!(
+            published,
+            "<!-- Thanks! -->\n\n## Summary\n\nWhy alpha.\n\n## Testing\n\n- [ ] Tests"
+        );
+
+        // Saving the published description back into the commit publishes the same body again
+        let described = described_message(message, &published);
+        assert_eq!(
+            templated_pr_body_base(&group(&described)).unwrap(),
+            published
+        );
+    }
+
     #[test]
     fn ignored_boundary_warning_explains_skipped_groups() {
         let warning = ignored_boundary_warning(&["pr:beta".to_string(), "pr:gamma".to_string()]);
@@ -1257,9 +1797,69 @@ mod tests {
         assert!(warning.contains("pr:beta, pr:gamma"));
     }
 
+    #[test]
+    fn prs_no_longer_open_reports_prs_merged_or_closed_since_the_read() {
+        let states = HashMap::from([
+            (10, "MERGED".to_string()),
+            (11, "OPEN".to_string()),
+            (12, "CLOSED".to_string()),
+        ]);
+        assert_eq!(
+            prs_no_longer_open(&[10, 11, 12, 13], &states),
+            vec![(10, "merged".to_string()), (12, "closed".to_string())]
+        );
+    }
+
+    #[test]
+    fn other_prefix_duplicates_finds_the_same_tag_under_other_spr_prefixes() {
+        let heads = vec!["dank-spr/alpha".to_string(), "dank-spr/beta".to_string()];
+        let remote: Vec<String> = [
+            "dank-spr/alpha",
+            "jane-spr/alpha",
+            "bob-spr/alpha",
+            "team/bob-spr/alpha",
+            "jane-spr/alphabet",
+            "alpha",
+            "main",
+        ]
+        .map(str::to_string)
+        .to_vec();
+
+        assert_eq!(
+            other_prefix_duplicates("dank-spr/", &heads, remote.iter()),
+            vec![(
+                "dank-spr/alpha".to_string(),
+                vec!["bob-spr/alpha".to_string(), "jane-spr/alpha".to_string()]
+            )]
+        );
+    }
+
+    #[test]
+    fn update_mutation_sends_bodies_as_variables_and_chunks_by_request_size() {
+        let body = "He said \"hi\" \\o/\n<!-- spr-stack:start -->";
+        let inputs = vec![
+            PrUpdateInput::body("PR_a", body),
+            PrUpdateInput::base_ref("PR_b", "dank-spr/alpha"),
+        ];
+
+        let mutation = update_mutation(&inputs);
+        assert_eq!(
+            mutation.query(),
+            "mutation($v0:ID!,$v1:String!,$v2:ID!,$v3:String!) { m0: updatePullRequest(input:{pullRequestId:$v0, body:$v1}){ clientMutationId } m1: updatePullRequest(input:{pullRequestId:$v2, baseRefName:$v3}){ clientMutationId } }"
+        );
+        assert!(mutation.gh_args().contains(&format!("v1={body}")));
+
+        assert_eq!(chunk_update_inputs(&inputs, 2, usize::MAX).len(), 1);
+        assert_eq!(chunk_update_inputs(&inputs, 1, usize::MAX).len(), 2);
+        assert_eq!(chunk_update_inputs(&inputs, 2, 200).len(), 2);
+    }
+
     #[test]
     fn preferred_single_update_mutation_still_respects_max_operations() {
-        let update_inputs = vec!["a".to_string(), "b".to_string()];
+        let update_inputs = vec![
+            PrUpdateInput::base_ref("PR_a", "main"),
+            PrUpdateInput::base_ref("PR_b", "main"),
+        ];
 
         assert!(!should_use_single_update_mutation(
             &update_inputs,
@@ -1531,6 +2131,7 @@ mod tests {
         build_from_groups(
             "main",
             "dank-spr/",
+            "HEAD",
             &skipped_handles,
             false,
             ExecutionMode::Apply,
@@ -1541,6 +2142,8 @@ mod tests {
             false,
             180,
             LocalPrBranchSyncPolicy::Off,
+            &TitleTransformConfig::default(),
+            false,
         )
         .unwrap();
     }
@@ -0,0 +1,407 @@
+//! Consistency check for a published stack, meant to run as a CI job on stack PRs.
+//!
+//! `spr verify --ci` reads the PR number from the GitHub Actions event payload, loads every PR
+//! listed in that PR's stack block through `gh` (a `GITHUB_TOKEN` is enough), and checks that:
+//! each open PR targets the head branch of the open PR below it, every listed PR carries the same
+//! stack block with its own entry marked, and each PR's first commit carries exactly one group
+//! marker while no later commit carries any. Problems are printed as workflow error annotations
+//! and fail the job. PRs without a stack block are not spr stack PRs and pass.
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use tracing::{info, warn};
+
+use crate::commands::update::{STACK_BLOCK_END, STACK_BLOCK_START};
+use crate::github::PrVerifyInfo;
+
+const CURRENT_PR_MARKER: char = '➡';
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VerifyProblem {
+    pub pr_number: u64,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VerifyData {
+    pub pr_number: u64,
+    /// PRs listed in the verified PR's stack block, bottom to top; empty for non-stack PRs.
+    pub stack: Vec<u64>,
+    pub problems: Vec<VerifyProblem>,
+}
+
+/// PR numbers of a rendered stack block, as listed (top of stack first).
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct StackBlock {
+    numbers: Vec<u64>,
+    current: Option<u64>,
+}
+
+fn parse_stack_block(body: &str) -> Option<StackBlock> {
+    let start = body.find(STACK_BLOCK_START)? + STACK_BLOCK_START.len();
+    let end = body[start..]
+        .find(STACK_BLOCK_END)
+        .map_or(body.len(), |end| start + end);
+    let mut block = StackBlock {
+        numbers: Vec::new(),
+        current: None,
+    };
+    for line in body[start..end].lines() {
+        let Some(entry) = line.trim().strip_prefix("- ") else {
+            continue;
+        };
+        let Some((marker, number)) = entry.split_once('#') else {
+            continue;
+        };
+        let Ok(number) = number.trim().parse::<u64>() else {
+            continue;
+        };
+        if marker.contains(CURRENT_PR_MARKER) {
+            block.current = Some(number);
+        }
+        block.numbers.push(number);
+    }
+    Some(block)
+}
+
+fn format_numbers(numbers: &[u64]) -> String {
+    numbers
+        .iter()
+        .map(|number| format!("#{number}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn marker_problems(info: &PrVerifyInfo) -> Vec<String> {
+    let mut problems = Vec::new();
+    for (idx, message) in info.commit_messages.iter().enumerate() {
+        let subject = message.lines().next().unwrap_or("");
+        let markers = crate::group_markers::candidate_group_markers(message);
+        if idx == 0 {
+            let valid = markers
+                .into_iter()
+                .filter(|marker| marker.clone().validate().is_ok())
+                .count();
+            if valid != 1 {
+                problems.push(format!(
+                    "first commit `{subject}` carries {valid} valid group marker(s); expected exactly one"
+                ));
+            }
+        } else if let Some(marker) = markers.first() {
+            problems.push(format!(
+                "commit `{subject}` carries group marker `{}`; only a PR's first commit may",
+                marker.display_text()
+            ));
+        }
+    }
+    problems
+}
+
+/// Check the stack that `block` (from PR `pr_number`) describes against the fetched PRs.
+fn verify_stack(
+    pr_number: u64,
+    block: &StackBlock,
+    infos: &HashMap<u64, PrVerifyInfo>,
+) -> Vec<VerifyProblem> {
+    let mut problems = Vec::new();
+    let mut problem =
+        |pr_number: u64, message: String| problems.push(VerifyProblem { pr_number, message });
+    match block.current {
+        Some(current) if current == pr_number => {}
+        Some(current) => problem(
+            pr_number,
+            format!("stack block marks #{current} as the current PR"),
+        ),
+        None => problem(pr_number, "stack block marks no PR as current".to_string()),
+    }
+    if !block.numbers.contains(&pr_number) {
+        problem(pr_number, "stack block does not list this PR".to_string());
+    }
+    let mut seen = HashSet::new();
+    for number in &block.numbers {
+        if !seen.insert(*number) {
+            problem(pr_number, format!("stack block lists #{number} twice"));
+        }
+    }
+
+    let open = |number: &u64| {
+        infos
+            .get(number)
+            .filter(|info| info.state == "OPEN")
+            .cloned()
+    };
+    for number in &block.numbers {
+        let Some(info) = infos.get(number) else {
+            problem(
+                pr_number,
+                format!("stack block lists #{number}, which does not exist"),
+            );
+            continue;
+        };
+        if info.state != "OPEN" {
+            continue;
+        }
+        match parse_stack_block(&info.body) {
+            None => problem(*number, "PR has no spr stack block".to_string()),
+            Some(other) => {
+                if other.numbers != block.numbers {
+                    problem(
+                        *number,
+                        format!(
+                            "stack block lists {} but #{pr_number} lists {}",
+                            format_numbers(&other.numbers),
+                            format_numbers(&block.numbers)
+                        ),
+                    );
+                }
+                if other.current != Some(*number) && *number != pr_number {
+                    problem(
+                        *number,
+                        "stack block does not mark this PR as current".to_string(),
+                    );
+                }
+            }
+        }
+        for message in marker_problems(info) {
+            problem(*number, message);
+        }
+    }
+
+    let bottom_to_top: Vec<u64> = block.numbers.iter().rev().copied().collect();
+    for pair in bottom_to_top.windows(2) {
+        let (Some(lower), Some(upper)) = (open(&pair[0]), open(&pair[1])) else {
+            continue;
+        };
+        if upper.base != lower.head {
+            problem(
+                upper.number,
+                format!(
+                    "base is `{}` but the PR below it (#{}) has head `{}`",
+                    upper.base, lower.number, lower.head
+                ),
+            );
+        }
+    }
+    problems
+}
+
+/// Collect verification results for the stack containing PR `pr_number`.
+///
+/// # Errors
+///
+/// Returns errors when the PR does not exist or GitHub queries fail.
+pub fn collect_verify_data(pr_number: u64) -> Result<VerifyData> {
+    let own = crate::github::fetch_pr_verify_info(&[pr_number])?
+        .remove(&pr_number)
+        .ok_or_else(|| anyhow!("PR #{pr_number} was not found"))?;
+    let Some(block) = parse_stack_block(&own.body) else {
+        return Ok(VerifyData {
+            pr_number,
+            stack: Vec::new(),
+            problems: Vec::new(),
+        });
+    };
+    let others: Vec<u64> = block
+        .numbers
+        .iter()
+        .copied()
+        .filter(|number| *number != pr_number)
+        .collect();
+    let mut infos = crate::github::fetch_pr_verify_info(&others)?;
+    infos.insert(pr_number, own);
+    Ok(VerifyData {
+        pr_number,
+        stack: block.numbers.iter().rev().copied().collect(),
+        problems: verify_stack(pr_number, &block, &infos),
+    })
+}
+
+fn pr_number_from_event(path: &Path) -> Result<u64> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read GitHub event payload: {}", path.display()))?;
+    let event: serde_json::Value = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse GitHub event payload: {}", path.display()))?;
+    event["pull_request"]["number"]
+        .as_u64()
+        .or_else(|| event["number"].as_u64())
+        .ok_or_else(|| {
+            anyhow!(
+                "GitHub event payload {} is not a pull_request event; pass --pr <N>",
+                path.display()
+            )
+        })
+}
+
+/// The PR to verify: `--pr`, or with `--ci` the PR of the triggering workflow event.
+///
+/// # Errors
+///
+/// Returns errors when neither source yields a PR number.
+pub fn resolve_verify_pr(pr: Option<u64>, ci: bool) -> Result<u64> {
+    if let Some(pr) = pr {
+        return Ok(pr);
+    }
+    if !ci {
+        bail!("pass --pr <N>, or --ci inside a pull_request workflow");
+    }
+    let path = std::env::var_os("GITHUB_EVENT_PATH")
+        .ok_or_else(|| anyhow!("GITHUB_EVENT_PATH is not set; pass --pr <N>"))?;
+    pr_number_from_event(Path::new(&path))
+}
+
+/// GitHub Actions `::error` workflow command for `problem`.
+fn error_annotation(problem: &VerifyProblem) -> String {
+    let message = format!("#{}: {}", problem.pr_number, problem.message)
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A");
+    format!("::error title=spr verify::{message}")
+}
+
+/// Report `data`; with `ci`, problems are printed as workflow error annotations.
+///
+/// # Errors
+///
+/// Returns an error when the stack has problems, so the command (and CI job) fails.
+pub fn report_verify(data: &VerifyData, ci: bool) -> Result<()> {
+    if data.stack.is_empty() {
+        info!(
+            "PR #{} has no spr stack block; nothing to verify.",
+            data.pr_number
+        );
+        return Ok(());
+    }
+    if data.problems.is_empty() {
+        info!("Stack {} is consistent.", format_numbers(&data.stack));
+        return Ok(());
+    }
+    for problem in &data.problems {
+        if ci {
+            println!("{}", error_annotation(problem));
+        } else {
+            warn!("#{}: {}", problem.pr_number, problem.message);
+        }
+    }
+    bail!(
+        "Stack of PR #{} is inconsistent ({} problem(s)); run `spr update` from the stack branch to repair it",
+        data.pr_number,
+        data.problems.len()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        error_annotation, parse_stack_block, pr_number_from_event, verify_stack, StackBlock,
+        VerifyProblem,
+    };
+    use crate::github::PrVerifyInfo;
+    use std::collections::HashMap;
+
+    fn block_body(numbers: &[u64], current: u64) -> String {
+        let lines = numbers
+            .iter()
+            .map(|number| {
+                let marker = if *number == current {
+                    "➡"
+                } else {
+                    crate::format::EM_SPACE
+                };
+                format!("- {marker} #{number}")
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        format!("Body.\n\n<!-- spr-stack:start -->\n**Stack**:\n{lines}\n<!-- spr-stack:end -->")
+    }
+
+    fn pr(number: u64, base: &str, head: &str, stack: &[u64], messages: &[&str]) -> PrVerifyInfo {
+        PrVerifyInfo {
+            number,
+            state: "OPEN".to_string(),
+            base: base.to_string(),
+            head: head.to_string(),
+            body: block_body(stack, number),
+            commit_messages: messages.iter().map(|message| message.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn parse_stack_block_reads_listed_numbers_and_current_entry() {
+        assert_eq!(
+            parse_stack_block(&block_body(&[12, 11], 11)),
+            Some(StackBlock {
+                numbers: vec![12, 11],
+                current: Some(11),
+            })
+        );
+        assert_eq!(parse_stack_block("No stack here."), None);
+    }
+
+    #[test]
+    fn consistent_stack_has_no_problems_and_drift_is_reported() {
+        let stack = [12, 11];
+        let mut infos = HashMap::from([
+            (
+                11,
+                pr(
+                    11,
+                    "main",
+                    "dank-spr/a",
+                    &stack,
+                    &["feat: a\n\npr:a", "fix: a"],
+                ),
+            ),
+            (
+                12,
+                pr(12, "dank-spr/a", "dank-spr/b", &stack, &["feat: b\n\npr:b"]),
+            ),
+        ]);
+        let block = parse_stack_block(&infos[&12].body).unwrap();
+        assert!(verify_stack(12, &block, &infos).is_empty());
+
+        infos.get_mut(&12).unwrap().base = "main".to_string();
+        infos.get_mut(&11).unwrap().commit_messages[1] = "fix: a\n\npr:c".to_string();
+        infos.get_mut(&11).unwrap().body = block_body(&[11], 11);
+        let problems = verify_stack(12, &block, &infos);
+        assert_eq!(
+            problems,
+            vec![
+                VerifyProblem {
+                    pr_number: 11,
+                    message: "stack block lists #11 but #12 lists #12, #11".to_string(),
+                },
+                VerifyProblem {
+                    pr_number: 11,
+                    message:
+                        "commit `fix: a` carries group marker `pr:c`; only a PR's first commit may"
+                            .to_string(),
+                },
+                VerifyProblem {
+                    pr_number: 12,
+                    message: "base is `main` but the PR below it (#11) has head `dank-spr/a`"
+                        .to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn event_payload_and_annotations() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("event.json");
+        std::fs::write(&path, r#"{"pull_request":{"number":42}}"#).unwrap();
+        assert_eq!(pr_number_from_event(&path).unwrap(), 42);
+        std::fs::write(&path, r#"{"ref":"refs/heads/main"}"#).unwrap();
+        assert!(pr_number_from_event(&path).is_err());
+
+        assert_eq!(
+            error_annotation(&VerifyProblem {
+                pr_number: 7,
+                message: "100% broken\nreally".to_string(),
+            }),
+            "::error title=spr verify::#7: 100%25 broken%0Areally"
+        );
+    }
+}
@@ -0,0 +1,224 @@
+//! Configurable lint rules for each group's first commit message.
+//!
+//! `spr update` derives PR titles and bodies from the first commit of every group, so these rules
+//! run against that commit before anything is pushed. Rules are opt-in; the default config checks
+//! nothing.
+
+use anyhow::{bail, Result};
+use regex::Regex;
+use serde::Deserialize;
+use std::sync::OnceLock;
+
+use crate::parsing::Group;
+
+/// Whether lint violations stop `spr update` or are only reported.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CommitLintLevel {
+    /// Report violations as warnings and continue publishing.
+    #[default]
+    Warn,
+    /// Refuse to publish while any group violates a rule.
+    Error,
+}
+
+/// The `commit_lint` config block.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct CommitLintConfig {
+    pub level: CommitLintLevel,
+    /// Require a conventional-commit prefix such as `feat:` or `fix(parser)!:`.
+    pub conventional_commit: bool,
+    /// Restrict conventional-commit types to this list. Empty accepts any lowercase type.
+    pub conventional_types: Vec<String>,
+    /// Maximum PR title length, measured after group markers and directives are stripped.
+    pub max_subject_length: Option<usize>,
+    /// Trailer keys (for example `Signed-off-by`) that must appear in the first commit.
+    pub required_trailers: Vec<String>,
+}
+
+impl CommitLintConfig {
+    fn is_enabled(&self) -> bool {
+        self.conventional_commit
+            || self.max_subject_length.is_some()
+            || !self.required_trailers.is_empty()
+    }
+}
+
+static CONVENTIONAL_PREFIX_REGEX: OnceLock<Regex> = OnceLock::new();
+
+fn conventional_prefix_regex() -> &'static Regex {
+    CONVENTIONAL_PREFIX_REGEX.get_or_init(|| {
+        Regex::new(r"^([a-z]+)(\([^()\s]+\))?!?: \S")
+            .expect("conventional commit regex should compile")
+    })
+}
+
+/// Trailer keys found in the final paragraph of `message`, in order.
+fn trailer_keys(message: &str) -> Vec<String> {
+    let trimmed = message.trim_end();
+    let Some((_, last_paragraph)) = trimmed.rsplit_once("\n\n") else {
+        return Vec::new();
+    };
+    last_paragraph
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, _)| key.trim())
+        .filter(|key| !key.is_empty() && !key.contains(char::is_whitespace))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Returns one human-readable violation per failed rule for `group`.
+pub fn lint_group(group: &Group, config: &CommitLintConfig) -> Result<Vec<String>> {
+    let mut violations = Vec::new();
+    let subject = group.pr_title()?;
+    if config.conventional_commit {
+        match conventional_prefix_regex().captures(&subject) {
+            None => violations.push(format!(
+                "title `{subject}` does not start with a conventional-commit prefix like `feat: `"
+            )),
+            Some(capture) => {
+                let commit_type = &capture[1];
+                if !config.conventional_types.is_empty()
+                    && !config.conventional_types.iter().any(|t| t == commit_type)
+                {
+                    violations.push(format!(
+                        "conventional-commit type `{commit_type}` is not one of: {}",
+                        config.conventional_types.join(", ")
+                    ));
+                }
+            }
+        }
+    }
+    if let Some(max) = config.max_subject_length {
+        let length = subject.chars().count();
+        if length > max {
+            violations.push(format!("title is {length} characters; the limit is {max}"));
+        }
+    }
+    if !config.required_trailers.is_empty() {
+        let present = trailer_keys(group.first_message.as_deref().unwrap_or(""));
+        for required in &config.required_trailers {
+            if !present.iter().any(|key| key.eq_ignore_ascii_case(required)) {
+                violations.push(format!("missing required trailer `{required}:`"));
+            }
+        }
+    }
+    Ok(violations)
+}
+
+/// Lint every group and either return the violations as warnings or fail on them.
+///
+/// With [`CommitLintLevel::Error`], any violation aborts before the caller pushes or edits PRs.
+pub fn check_groups(groups: &[Group], config: &CommitLintConfig) -> Result<Vec<String>> {
+    if !config.is_enabled() {
+        return Ok(Vec::new());
+    }
+    let mut messages = Vec::new();
+    for group in groups {
+        for violation in lint_group(group, config)? {
+            messages.push(format!("{}: {violation}", group.selector_text()));
+        }
+    }
+    if config.level == CommitLintLevel::Error && !messages.is_empty() {
+        bail!(
+            "Commit lint failed; fix the first commit of each group before publishing:\n  {}",
+            messages.join("\n  ")
+        );
+    }
+    Ok(messages
+        .into_iter()
+        .map(|message| format!("Commit lint: {message}"))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_groups, lint_group, CommitLintConfig, CommitLintLevel};
+    use crate::group_markers::GroupMarker;
+    use crate::parsing::Group;
+
+    fn group(tag: &str, message: &str) -> Group {
+        Group {
+            marker: GroupMarker::PrLabel(tag.to_string()),
+            subjects: vec![message.lines().next().unwrap_or("").to_string()],
+            commits: vec![format!("{tag}1")],
+            first_message: Some(message.to_string()),
+            ignored_after: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn default_config_checks_nothing() {
+        let groups = vec![group("alpha", "whatever pr:alpha")];
+
+        assert!(check_groups(&groups, &CommitLintConfig::default())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn conventional_prefix_and_type_list_are_enforced() {
+        let config = CommitLintConfig {
+            conventional_commit: true,
+            conventional_types: vec!["feat".to_string(), "fix".to_string()],
+            ..CommitLintConfig::default()
+        };
+
+        assert!(
+            lint_group(&group("a", "feat(parser)!: alpha pr:a"), &config)
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            lint_group(&group("b", "chore: beta pr:b"), &config)
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(
+            lint_group(&group("c", "Add gamma pr:c"), &config).unwrap()[0]
+                .contains("conventional-commit prefix")
+        );
+    }
+
+    #[test]
+    fn subject_length_ignores_markers_and_trailers_use_last_paragraph() {
+        let config = CommitLintConfig {
+            max_subject_length: Some(11),
+            required_trailers: vec!["Signed-off-by".to_string()],
+            ..CommitLintConfig::default()
+        };
+
+        let ok = group(
+            "alpha",
+            "feat: alpha pr:alpha\n\nBody.\n\nsigned-off-by: Dev <dev@example.com>",
+        );
+        assert!(lint_group(&ok, &config).unwrap().is_empty());
+
+        let bad = group("beta", "feat: beta-long pr:beta\n\nSigned-off-by mentioned");
+        let violations = lint_group(&bad, &config).unwrap();
+        assert_eq!(violations.len(), 2);
+        assert!(violations[0].contains("limit is 11"));
+        assert!(violations[1].contains("Signed-off-by"));
+    }
+
+    #[test]
+    fn error_level_fails_with_every_violation() {
+        let config = CommitLintConfig {
+            level: CommitLintLevel::Error,
+            conventional_commit: true,
+            ..CommitLintConfig::default()
+        };
+        let groups = vec![
+            group("alpha", "alpha pr:alpha"),
+            group("beta", "beta pr:beta"),
+        ];
+
+        let err = check_groups(&groups, &config).unwrap_err().to_string();
+
+        assert!(err.contains("pr:alpha"));
+        assert!(err.contains("pr:beta"));
+    }
+}
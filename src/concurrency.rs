@@ -0,0 +1,61 @@
+//! Run independent `git`/`gh` queries at the same time.
+//!
+//! Startup of `spr update` is dominated by round trips to GitHub and the remote: listing PRs,
+//! `git ls-remote`, and reading PR bodies. The queries are independent subprocesses, so scoped
+//! threads overlap them without any shared state beyond what the callers already synchronize.
+
+use std::thread;
+
+/// Run `a` and `b` concurrently and return both results. A panic in either is re-raised.
+pub fn join<A, B, RA, RB>(a: A, b: B) -> (RA, RB)
+where
+    A: FnOnce() -> RA + Send,
+    B: FnOnce() -> RB + Send,
+    RA: Send,
+    RB: Send,
+{
+    thread::scope(|scope| {
+        let b = scope.spawn(b);
+        let a = a();
+        (
+            a,
+            b.join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic)),
+        )
+    })
+}
+
+/// Apply `run` to every item concurrently, returning the results in item order.
+pub fn map<T, R, F>(items: &[T], run: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    if items.len() < 2 {
+        return items.iter().map(&run).collect();
+    }
+    thread::scope(|scope| {
+        let handles: Vec<_> = items.iter().map(|item| scope.spawn(|| run(item))).collect();
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{join, map};
+
+    #[test]
+    fn join_and_map_keep_results_in_order() {
+        assert_eq!(join(|| 1, || "two"), (1, "two"));
+        assert_eq!(map(&[3, 1, 2], |n| n * 10), vec![30, 10, 20]);
+        assert_eq!(map(&[] as &[u32], |n| n + 1), Vec::<u32>::new());
+    }
+}
@@ -6,6 +6,7 @@
 use anyhow::{anyhow, Context, Result};
 use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 
@@ -93,7 +94,7 @@ impl ListOrder {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct FileConfig {
     /// Root base branch for the stack, e.g. `origin/main`.
@@ -130,6 +131,60 @@ pub struct FileConfig {
     ///
     /// `0` effectively disables the guard for past terminal PRs.
     pub branch_reuse_guard_days: Option<u32>,
+    /// Lint rules checked against each group's first commit before `spr update` publishes.
+    pub commit_lint: Option<crate::commit_lint::CommitLintConfig>,
+    /// Rewrites applied to PR titles derived from each group's first commit subject.
+    pub title_transforms: Option<crate::title_transforms::TitleTransformConfig>,
+    /// GitHub Projects (v2) status applied to items of PRs landed by `spr land`.
+    pub projects: Option<crate::project_status::ProjectsConfig>,
+    /// Path globs limiting group derivation; commits touching none of them act as ignore blocks.
+    pub path_scope: Option<Vec<String>>,
+    /// Derive groups from the first-parent chain, skipping commits of merged side branches.
+    pub first_parent: Option<bool>,
+    /// Explicit branch names for `pr:<tag>` groups, keyed by tag, replacing `<prefix><tag>`.
+    pub branch_overrides: Option<BTreeMap<String, String>>,
+    /// Reviewers and labels required on PRs whose groups touch sensitive path globs.
+    pub protected_paths: Option<Vec<crate::protected_paths::ProtectedPathRule>>,
+    /// Base branches a stack may target; `*` matches any run of characters.
+    pub allowed_bases: Option<Vec<String>>,
+    /// Render a collapsed "Review guide" section beneath each PR's stack block.
+    pub review_guide: Option<bool>,
+    /// Path globs treated as generated by `spr files`, review guides, and protected-path rules.
+    pub generated_paths: Option<Vec<String>>,
+    /// Merge commit-message bodies into the repository's PR template.
+    pub body_template: Option<bool>,
+    /// Warn in `list`/`update` when the remote-tracking base was last fetched longer ago than
+    /// this many hours; `0` disables the warning.
+    pub fetch_stale_after_hours: Option<u32>,
+    /// Git remote that hosts the stack's PR branches and base.
+    pub remote: Option<String>,
+    /// Hours a ready PR may await review before `spr update` pings its requested reviewers;
+    /// `0` disables pinging.
+    pub review_ping_after_hours: Option<u32>,
+    /// Users or `org/team` entries to request review from on every stack PR.
+    pub reviewers: Option<Vec<String>>,
+    /// Open milestone title for stack PRs without a milestone.
+    pub milestone: Option<String>,
+    /// Branch that PRs target when `base` is a tag or commit.
+    pub landing_branch: Option<String>,
+    /// Title of the owner's project (v2) board for every stack PR.
+    pub project: Option<String>,
+    /// Shell command run against each group tip before `spr update` pushes it.
+    pub pre_push_check: Option<String>,
+    /// Open new PRs as drafts in `spr update`, as if `--draft` were given.
+    pub draft: Option<bool>,
+    /// Merge method `spr automerge` uses when `--method` is not given.
+    pub merge_method: Option<crate::commands::MergeMethod>,
+    /// Most PR groups `spr update` publishes from one stack; `0` means no limit.
+    pub max_stack_depth: Option<u32>,
+    /// Ask for confirmation from a terminal before `spr land` merges on GitHub.
+    pub confirm_land: Option<bool>,
+    /// Code-review host of the stack's PRs: `github` (default) or `gitlab`.
+    pub forge: Option<crate::forge::Forge>,
+    /// How revisions and commits are read: `batch` (default) or `subprocess`.
+    pub git_backend: Option<crate::git::GitBackendKind>,
+    /// How GitHub is reached: `auto` (default), `gh`, or `token`.
+    pub github_client: Option<crate::github_api::GithubClient>,
 }
 
 #[derive(Debug, Clone)]
@@ -154,6 +209,69 @@ pub struct Config {
     ///
     /// `0` effectively disables the guard for past terminal PRs.
     pub branch_reuse_guard_days: u32,
+    /// Lint rules checked against each group's first commit before `spr update` publishes.
+    pub commit_lint: crate::commit_lint::CommitLintConfig,
+    /// Rewrites applied to PR titles derived from each group's first commit subject.
+    pub title_transforms: crate::title_transforms::TitleTransformConfig,
+    /// GitHub Projects (v2) status applied to items of PRs landed by `spr land`.
+    pub projects: crate::project_status::ProjectsConfig,
+    /// Path globs limiting group derivation; empty means the whole repository.
+    pub path_scope: Vec<String>,
+    /// Derive groups from the first-parent chain, skipping commits of merged side branches.
+    pub first_parent: bool,
+    /// Explicit branch names for `pr:<tag>` groups, keyed by tag, replacing `<prefix><tag>`.
+    pub branch_overrides: BTreeMap<String, String>,
+    /// Reviewers and labels required on PRs whose groups touch sensitive path globs.
+    pub protected_paths: Vec<crate::protected_paths::ProtectedPathRule>,
+    /// Base branches a stack may target; empty allows any base.
+    pub allowed_bases: Vec<String>,
+    /// Render a collapsed "Review guide" section beneath each PR's stack block.
+    pub review_guide: bool,
+    /// Path globs treated as generated by `spr files`, review guides, and protected-path rules.
+    pub generated_paths: Vec<String>,
+    /// Merge commit-message bodies into the sections of `.github/PULL_REQUEST_TEMPLATE.md`.
+    pub body_template: bool,
+    /// Age in hours after which `list`/`update` warn about a stale remote-tracking base.
+    ///
+    /// `0` disables the warning.
+    pub fetch_stale_after_hours: u32,
+    /// Git remote that hosts the stack's PR branches and base; `--remote` overrides it.
+    pub remote: String,
+    /// Hours a ready PR may await review before `spr update` comments to ping its requested
+    /// reviewers.
+    ///
+    /// `0` disables pinging.
+    pub review_ping_after_hours: u32,
+    /// Users (`alice`) or teams (`org/team`) that `spr update` requests review from on every
+    /// stack PR, in addition to each group's `Reviewers:` trailers.
+    pub reviewers: Vec<String>,
+    /// Title of the open milestone that `spr update` puts stack PRs without a milestone on.
+    pub milestone: Option<String>,
+    /// Branch that PRs target when `base` is a tag or commit, such as a release branch.
+    pub landing_branch: Option<String>,
+    /// Title of the repository owner's project (v2) board that `spr update` adds every stack PR
+    /// to.
+    pub project: Option<String>,
+    /// Shell command `spr update` runs in a temporary worktree of each group tip it is about to
+    /// push; any failure aborts the update before pushing.
+    pub pre_push_check: Option<String>,
+    /// Whether `spr update` opens new PRs as drafts without `--draft`.
+    pub draft: bool,
+    /// Default `spr automerge --method`.
+    pub merge_method: crate::commands::MergeMethod,
+    /// Upper bound on the PR groups `spr update` publishes; `0` disables the check.
+    pub max_stack_depth: u32,
+    /// Whether `spr land` lists the PRs it is about to merge and asks before merging.
+    pub confirm_land: bool,
+    /// Where stack PRs live; `gitlab` publishes merge requests through `glab` for `update`,
+    /// `list`, and `status`.
+    pub forge: crate::forge::Forge,
+    /// Whether revision and commit reads share one long-lived `git cat-file` process per
+    /// repository (`batch`) or start a `git` process each (`subprocess`).
+    pub git_backend: crate::git::GitBackendKind,
+    /// Whether GitHub calls go through `gh` or straight to the API with `GH_TOKEN`/`GITHUB_TOKEN`;
+    /// `auto` uses `gh` when it is installed.
+    pub github_client: crate::github_api::GithubClient,
 }
 
 /// Normalize a configured branch prefix and reject values outside the ASCII-only conflict domain.
@@ -169,6 +287,66 @@ pub fn normalize_prefix(prefix: &str) -> Result<String> {
     Ok(normalized)
 }
 
+/// Normalize an ignore tag from config or `--ignore-tag`: an optional `pr:` is dropped, and an
+/// empty value means the default `ignore`.
+pub fn normalize_ignore_tag(tag: &str) -> Result<String> {
+    let tag = tag.trim();
+    let tag = tag.strip_prefix("pr:").unwrap_or(tag);
+    if tag.is_empty() {
+        return Ok("ignore".to_string());
+    }
+    if !tag.starts_with(|ch: char| ch.is_ascii_alphabetic()) {
+        return Err(anyhow!(
+            "Ignore tag must start with an ASCII letter: {:?}",
+            tag
+        ));
+    }
+    Ok(tag.to_string())
+}
+
+fn wildcard_matches(pattern: &str, value: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = value.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts = parts.peekable();
+    while let Some(part) = parts.next() {
+        if parts.peek().is_none() {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(idx) => rest = &rest[idx + part.len()..],
+            None => return false,
+        }
+    }
+    rest.is_empty()
+}
+
+/// Refuse a resolved `base` outside `allowed_bases` (no-op when the list is empty).
+///
+/// A base tracking `remote`, such as `origin/main`, also matches an entry for `main`. Other
+/// slashes are part of the branch name, so a local `team/main` does not match `main`.
+pub fn ensure_allowed_base(base: &str, remote: &str, allowed_bases: &[String]) -> Result<()> {
+    if allowed_bases.is_empty() {
+        return Ok(());
+    }
+    let short = base.strip_prefix(&format!("{remote}/"));
+    let allowed = allowed_bases.iter().any(|pattern| {
+        wildcard_matches(pattern, base)
+            || short.is_some_and(|short| wildcard_matches(pattern, short))
+    });
+    if allowed {
+        return Ok(());
+    }
+    Err(anyhow!(
+        "Base `{}` is not in allowed_bases ({}). Pass the intended --base, or --allow-base if targeting `{}` is deliberate.",
+        base,
+        allowed_bases.join(", "),
+        base
+    ))
+}
+
 fn read_config_file(path: &PathBuf) -> Result<Option<FileConfig>> {
     if !path.exists() {
         return Ok(None);
@@ -180,6 +358,75 @@ fn read_config_file(path: &PathBuf) -> Result<Option<FileConfig>> {
     Ok(Some(cfg))
 }
 
+/// Set the top-level `base` key in the config file at `path`, keeping every other line.
+///
+/// The file is edited as text rather than re-serialized so comments and key order survive.
+fn write_base_to_config_file(path: &PathBuf, base: &str) -> Result<()> {
+    write_key_to_config_file(path, "base", base)
+}
+
+/// Replace the first top-level `key:` line of the config file at `path` with `value`, or append
+/// one, keeping every other line and comment.
+fn write_key_to_config_file(path: &PathBuf, key: &str, value: &str) -> Result<()> {
+    let content = if path.exists() {
+        fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file: {}", path.display()))?
+    } else {
+        String::new()
+    };
+    let key_line = format!("{key}: {}", serde_yaml::to_string(value)?.trim_end());
+    let mut replaced = false;
+    let mut lines: Vec<String> = content
+        .lines()
+        .map(|line| {
+            if !replaced && line.starts_with(&format!("{key}:")) {
+                replaced = true;
+                key_line.clone()
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+    if !replaced {
+        lines.push(key_line);
+    }
+    let updated = lines.join("\n") + "\n";
+    serde_yaml::from_str::<FileConfig>(&updated)
+        .with_context(|| format!("Refusing to write an unparsable {}", path.display()))?;
+    fs::write(path, updated)
+        .with_context(|| format!("Failed to write config file: {}", path.display()))
+}
+
+/// Persist `base` in the repository config (`<git-main-worktree-root>/.spr_multicommit_cfg.yml`).
+///
+/// # Errors
+///
+/// Returns an error outside a Git worktree or when the existing file cannot be read or parsed.
+pub fn set_repo_config_base(base: &str) -> Result<PathBuf> {
+    let mut path = PathBuf::from(
+        crate::git::main_worktree_root()?
+            .ok_or_else(|| anyhow!("`spr` must run inside a git worktree"))?,
+    );
+    path.push(".spr_multicommit_cfg.yml");
+    write_base_to_config_file(&path, base)?;
+    Ok(path)
+}
+
+/// Persist `prefix` in the repository config (`<git-main-worktree-root>/.spr_multicommit_cfg.yml`).
+///
+/// # Errors
+///
+/// Returns an error outside a Git worktree or when the existing file cannot be read or parsed.
+pub fn set_repo_config_prefix(prefix: &str) -> Result<PathBuf> {
+    let mut path = PathBuf::from(
+        crate::git::main_worktree_root()?
+            .ok_or_else(|| anyhow!("`spr` must run inside a git worktree"))?,
+    );
+    path.push(".spr_multicommit_cfg.yml");
+    write_key_to_config_file(&path, "prefix", prefix)?;
+    Ok(path)
+}
+
 fn default_config() -> Config {
     let user = std::env::var("USER").unwrap_or_else(|_| "".to_string());
     Config {
@@ -193,6 +440,32 @@ fn default_config() -> Config {
         restack_conflict: RestackConflictPolicy::Halt,
         dirty_worktree: DirtyWorktreePolicy::Halt,
         branch_reuse_guard_days: 180,
+        commit_lint: crate::commit_lint::CommitLintConfig::default(),
+        title_transforms: crate::title_transforms::TitleTransformConfig::default(),
+        projects: crate::project_status::ProjectsConfig::default(),
+        path_scope: Vec::new(),
+        first_parent: false,
+        branch_overrides: BTreeMap::new(),
+        protected_paths: Vec::new(),
+        allowed_bases: Vec::new(),
+        review_guide: false,
+        generated_paths: Vec::new(),
+        body_template: false,
+        fetch_stale_after_hours: 24,
+        remote: "origin".to_string(),
+        review_ping_after_hours: 0,
+        reviewers: Vec::new(),
+        milestone: None,
+        landing_branch: None,
+        project: None,
+        pre_push_check: None,
+        draft: false,
+        merge_method: crate::commands::MergeMethod::Squash,
+        max_stack_depth: 0,
+        confirm_land: false,
+        forge: crate::forge::Forge::Github,
+        git_backend: crate::git::GitBackendKind::Batch,
+        github_client: crate::github_api::GithubClient::Auto,
     }
 }
 
@@ -228,13 +501,106 @@ fn apply_overrides(config: &Config, overrides: FileConfig) -> Config {
     if let Some(branch_reuse_guard_days) = overrides.branch_reuse_guard_days {
         merged.branch_reuse_guard_days = branch_reuse_guard_days;
     }
+    if let Some(commit_lint) = overrides.commit_lint {
+        merged.commit_lint = commit_lint;
+    }
+    if let Some(title_transforms) = overrides.title_transforms {
+        merged.title_transforms = title_transforms;
+    }
+    if let Some(projects) = overrides.projects {
+        merged.projects = projects;
+    }
+    if let Some(path_scope) = overrides.path_scope {
+        merged.path_scope = path_scope;
+    }
+    if let Some(first_parent) = overrides.first_parent {
+        merged.first_parent = first_parent;
+    }
+    if let Some(branch_overrides) = overrides.branch_overrides {
+        merged.branch_overrides = branch_overrides;
+    }
+    if let Some(protected_paths) = overrides.protected_paths {
+        merged.protected_paths = protected_paths;
+    }
+    if let Some(allowed_bases) = overrides.allowed_bases {
+        merged.allowed_bases = allowed_bases;
+    }
+    if let Some(review_guide) = overrides.review_guide {
+        merged.review_guide = review_guide;
+    }
+    if let Some(generated_paths) = overrides.generated_paths {
+        merged.generated_paths = generated_paths;
+    }
+    if let Some(body_template) = overrides.body_template {
+        merged.body_template = body_template;
+    }
+    if let Some(fetch_stale_after_hours) = overrides.fetch_stale_after_hours {
+        merged.fetch_stale_after_hours = fetch_stale_after_hours;
+    }
+    if let Some(remote) = overrides.remote {
+        merged.remote = remote;
+    }
+    if let Some(review_ping_after_hours) = overrides.review_ping_after_hours {
+        merged.review_ping_after_hours = review_ping_after_hours;
+    }
+    if let Some(reviewers) = overrides.reviewers {
+        merged.reviewers = reviewers;
+    }
+    if let Some(milestone) = overrides.milestone {
+        merged.milestone = Some(milestone);
+    }
+    if let Some(landing_branch) = overrides.landing_branch {
+        merged.landing_branch = Some(landing_branch);
+    }
+    if let Some(project) = overrides.project {
+        merged.project = Some(project);
+    }
+    if let Some(pre_push_check) = overrides.pre_push_check {
+        merged.pre_push_check = Some(pre_push_check);
+    }
+    if let Some(draft) = overrides.draft {
+        merged.draft = draft;
+    }
+    if let Some(merge_method) = overrides.merge_method {
+        merged.merge_method = merge_method;
+    }
+    if let Some(max_stack_depth) = overrides.max_stack_depth {
+        merged.max_stack_depth = max_stack_depth;
+    }
+    if let Some(confirm_land) = overrides.confirm_land {
+        merged.confirm_land = confirm_land;
+    }
+    if let Some(forge) = overrides.forge {
+        merged.forge = forge;
+    }
+    if let Some(git_backend) = overrides.git_backend {
+        merged.git_backend = git_backend;
+    }
+    if let Some(github_client) = overrides.github_client {
+        merged.github_client = github_client;
+    }
     merged
 }
 
+/// The `spr land` mode the `land` config value names. Checked only when `spr land` needs it, so a
+/// typo does not break every other command.
+pub fn land_mode(land: &str) -> Result<crate::cli::LandCmd> {
+    match land {
+        "flatten" => Ok(crate::cli::LandCmd::Flatten),
+        "per-pr" | "perpr" | "per_pr" => Ok(crate::cli::LandCmd::PerPr),
+        other => Err(anyhow!("land must be `flatten` or `per-pr`, not {other:?}")),
+    }
+}
+
 fn normalize_config(config: &mut Config) -> Result<()> {
     config.prefix = normalize_prefix(&config.prefix)?;
-    if config.ignore_tag.trim().is_empty() {
-        config.ignore_tag = "ignore".to_string();
+    config.ignore_tag = normalize_ignore_tag(&config.ignore_tag)?;
+    config.title_transforms.validate()?;
+    for (label, branch) in &config.branch_overrides {
+        crate::pr_labels::validate_label(label)
+            .map_err(|err| anyhow!("invalid branch_overrides tag `{label}`: {err}"))?;
+        crate::git::validate_branch_name(branch)
+            .with_context(|| format!("invalid branch_overrides branch for `{label}`"))?;
     }
     Ok(())
 }
@@ -266,11 +632,13 @@ pub fn load_config() -> Result<Config> {
 #[cfg(test)]
 mod tests {
     use super::{
-        apply_overrides, default_config, load_config, normalize_config, normalize_prefix,
-        read_config_file, DirtyWorktreePolicy, FileConfig, LocalPrBranchSyncPolicy,
-        PrDescriptionMode, RestackConflictPolicy,
+        apply_overrides, default_config, ensure_allowed_base, land_mode, load_config,
+        normalize_config, normalize_ignore_tag, normalize_prefix, read_config_file,
+        write_base_to_config_file, write_key_to_config_file, DirtyWorktreePolicy, FileConfig,
+        LocalPrBranchSyncPolicy, PrDescriptionMode, RestackConflictPolicy,
     };
     use crate::test_support::{git, init_repo, lock_cwd, DirGuard};
+    use std::collections::BTreeMap;
     use std::env;
     use std::fs;
     use std::path::{Path, PathBuf};
@@ -338,6 +706,14 @@ pr_description_mode: stack_only
         assert_eq!(cfg.pr_description_mode, Some(PrDescriptionMode::StackOnly));
     }
 
+    #[test]
+    fn normalize_ignore_tag_drops_pr_prefix_and_defaults_when_empty() {
+        assert_eq!(normalize_ignore_tag("pr:skip").unwrap(), "skip");
+        assert_eq!(normalize_ignore_tag(" wip ").unwrap(), "wip");
+        assert_eq!(normalize_ignore_tag("").unwrap(), "ignore");
+        assert!(normalize_ignore_tag("1st").is_err());
+    }
+
     #[test]
     fn read_config_file_rejects_unknown_key() {
         let dir = tempdir().expect("tempdir");
@@ -447,6 +823,47 @@ restack_conflict: rollback
         assert_eq!(cfg.base, "origin/release-candidate");
     }
 
+    #[test]
+    fn load_config_layers_repo_behavior_keys_over_home() {
+        let _lock = lock_cwd();
+        let home = tempdir().unwrap();
+        let _home_guard = EnvVarGuard::set("HOME", home.path().display().to_string());
+        fs::write(
+            home.path().join(".spr_multicommit_cfg.yml"),
+            "draft: true\nmerge_method: merge\nmax_stack_depth: 5\nconfirm_land: true\nland: per-pr\n",
+        )
+        .unwrap();
+        let repo_dir = init_repo();
+        fs::write(
+            repo_dir.path().join(".spr_multicommit_cfg.yml"),
+            "merge_method: rebase\nmax_stack_depth: 0\nforge: gitlab\ngithub_client: token\n",
+        )
+        .unwrap();
+        let _guard = DirGuard::change_to(repo_dir.path());
+
+        let cfg = load_config().unwrap();
+
+        assert!(cfg.draft);
+        assert_eq!(cfg.merge_method, crate::commands::MergeMethod::Rebase);
+        assert_eq!(cfg.max_stack_depth, 0);
+        assert!(cfg.confirm_land);
+        assert_eq!(cfg.land, "per-pr");
+        assert_eq!(cfg.forge, crate::forge::Forge::Gitlab);
+        assert_eq!(cfg.github_client, crate::github_api::GithubClient::Token);
+
+        fs::write(
+            repo_dir.path().join(".spr_multicommit_cfg.yml"),
+            "land: squash\n",
+        )
+        .unwrap();
+        let cfg = load_config().unwrap();
+        assert_eq!(cfg.land, "squash");
+        let err = land_mode(&cfg.land).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("land must be `flatten` or `per-pr`"));
+    }
+
     #[test]
     fn load_config_ignores_linked_worktree_repo_config_without_main_repo_config() {
         let _lock = lock_cwd();
@@ -502,37 +919,77 @@ restack_conflict: rollback
         let merged = apply_overrides(
             &base,
             FileConfig {
-                base: None,
-                prefix: None,
-                land: None,
-                ignore_tag: None,
-                pr_description_mode: None,
-                list_order: None,
-                local_pr_branches: None,
-                restack_conflict: None,
-                dirty_worktree: None,
                 branch_reuse_guard_days: Some(30),
+                ..FileConfig::default()
             },
         );
 
         assert_eq!(merged.branch_reuse_guard_days, 30);
     }
 
+    #[test]
+    fn apply_overrides_updates_fetch_stale_after_hours() {
+        assert_eq!(default_config().fetch_stale_after_hours, 24);
+        let merged = apply_overrides(
+            &default_config(),
+            FileConfig {
+                fetch_stale_after_hours: Some(0),
+                ..FileConfig::default()
+            },
+        );
+
+        assert_eq!(merged.fetch_stale_after_hours, 0);
+    }
+
+    #[test]
+    fn apply_overrides_updates_remote() {
+        assert_eq!(default_config().remote, "origin");
+        let merged = apply_overrides(
+            &default_config(),
+            FileConfig {
+                remote: Some("upstream".to_string()),
+                ..FileConfig::default()
+            },
+        );
+
+        assert_eq!(merged.remote, "upstream");
+    }
+
+    #[test]
+    fn apply_overrides_updates_reviewers() {
+        let merged = apply_overrides(
+            &default_config(),
+            FileConfig {
+                reviewers: Some(vec!["alice".to_string(), "acme/infra".to_string()]),
+                ..FileConfig::default()
+            },
+        );
+
+        assert_eq!(merged.reviewers, vec!["alice", "acme/infra"]);
+    }
+
+    #[test]
+    fn apply_overrides_updates_milestone_and_project() {
+        let merged = apply_overrides(
+            &default_config(),
+            FileConfig {
+                milestone: Some("v2.3".to_string()),
+                project: Some("Release train".to_string()),
+                ..FileConfig::default()
+            },
+        );
+
+        assert_eq!(merged.milestone.as_deref(), Some("v2.3"));
+        assert_eq!(merged.project.as_deref(), Some("Release train"));
+    }
+
     #[test]
     fn apply_overrides_updates_local_pr_branch_sync_policy() {
         let merged = apply_overrides(
             &default_config(),
             FileConfig {
-                base: None,
-                prefix: None,
-                land: None,
-                ignore_tag: None,
-                pr_description_mode: None,
-                list_order: None,
                 local_pr_branches: Some(LocalPrBranchSyncPolicy::UpdateExisting),
-                restack_conflict: None,
-                dirty_worktree: None,
-                branch_reuse_guard_days: None,
+                ..FileConfig::default()
             },
         );
 
@@ -542,6 +999,140 @@ restack_conflict: rollback
         );
     }
 
+    #[test]
+    fn read_config_file_parses_commit_lint_block() {
+        let dir = tempdir().expect("tempdir");
+        let path = dir.path().join(".spr_multicommit_cfg.yml");
+        fs::write(
+            &path,
+            "commit_lint:\n  level: error\n  conventional_commit: true\n  max_subject_length: 72\n  required_trailers: [Signed-off-by]\n",
+        )
+        .unwrap();
+
+        let cfg = read_config_file(&path).unwrap().expect("config exists");
+        let merged = apply_overrides(&default_config(), cfg);
+
+        assert_eq!(
+            merged.commit_lint,
+            crate::commit_lint::CommitLintConfig {
+                level: crate::commit_lint::CommitLintLevel::Error,
+                conventional_commit: true,
+                conventional_types: Vec::new(),
+                max_subject_length: Some(72),
+                required_trailers: vec!["Signed-off-by".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn read_config_file_parses_title_transforms_block() {
+        let dir = tempdir().expect("tempdir");
+        let path = dir.path().join(".spr_multicommit_cfg.yml");
+        fs::write(
+            &path,
+            "title_transforms:\n  strip_prefixes: [\"WIP:\"]\n  capitalize: upper\n  ticket_pattern: '[a-z]+-[0-9]+'\n",
+        )
+        .unwrap();
+
+        let cfg = read_config_file(&path).unwrap().expect("config exists");
+        let mut merged = apply_overrides(&default_config(), cfg);
+        normalize_config(&mut merged).unwrap();
+
+        assert_eq!(
+            merged.title_transforms,
+            crate::title_transforms::TitleTransformConfig {
+                strip_prefixes: vec!["WIP:".to_string()],
+                capitalize: crate::title_transforms::TitleCapitalization::Upper,
+                ticket_pattern: Some("[a-z]+-[0-9]+".to_string()),
+                ticket_format: None,
+            }
+        );
+    }
+
+    #[test]
+    fn read_config_file_parses_projects_block() {
+        let dir = tempdir().expect("tempdir");
+        let path = dir.path().join(".spr_multicommit_cfg.yml");
+        fs::write(&path, "projects:\n  landed_status: Done\n").unwrap();
+
+        let cfg = read_config_file(&path).unwrap().expect("config exists");
+        let merged = apply_overrides(&default_config(), cfg);
+
+        assert_eq!(merged.projects.landed_status.as_deref(), Some("Done"));
+        assert_eq!(merged.projects.status_field(), "Status");
+    }
+
+    #[test]
+    fn ensure_allowed_base_matches_names_wildcards_and_remote_tracking_bases() {
+        let allowed = vec!["main".to_string(), "release/*".to_string()];
+
+        ensure_allowed_base("main", "origin", &allowed).unwrap();
+        ensure_allowed_base("origin/main", "origin", &allowed).unwrap();
+        ensure_allowed_base("origin/release/1.2", "origin", &allowed).unwrap();
+        ensure_allowed_base("upstream/main", "upstream", &allowed).unwrap();
+        ensure_allowed_base("feature/login", "origin", &[]).unwrap();
+        let err = ensure_allowed_base("origin/feature/login", "origin", &allowed).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Base `origin/feature/login` is not in allowed_bases (main, release/*)"),
+            "{err}"
+        );
+        assert!(ensure_allowed_base("mainline", "origin", &allowed).is_err());
+    }
+
+    #[test]
+    fn ensure_allowed_base_keeps_slashes_that_are_part_of_the_branch_name() {
+        let allowed = vec!["main".to_string()];
+
+        assert!(ensure_allowed_base("team/main", "origin", &allowed).is_err());
+        assert!(ensure_allowed_base("origin/team/main", "origin", &allowed).is_err());
+        ensure_allowed_base("team/main", "origin", &["team/main".to_string()]).unwrap();
+        ensure_allowed_base("origin/team/main", "origin", &["team/*".to_string()]).unwrap();
+    }
+
+    #[test]
+    fn write_base_to_config_file_replaces_base_and_keeps_comments() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(".spr_multicommit_cfg.yml");
+        fs::write(
+            &path,
+            "# team defaults\nbase: origin/main\nprefix: dank-spr/\n",
+        )
+        .unwrap();
+
+        write_base_to_config_file(&path, "origin/release-2.0").unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "# team defaults\nbase: origin/release-2.0\nprefix: dank-spr/\n"
+        );
+        let missing = dir.path().join("missing.yml");
+        write_base_to_config_file(&missing, "origin/main").unwrap();
+        assert_eq!(fs::read_to_string(&missing).unwrap(), "base: origin/main\n");
+
+        write_key_to_config_file(&path, "prefix", "team/dank/").unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "# team defaults\nbase: origin/release-2.0\nprefix: team/dank/\n"
+        );
+    }
+
+    #[test]
+    fn normalize_config_validates_branch_overrides() {
+        let mut cfg = default_config();
+        cfg.branch_overrides =
+            BTreeMap::from([("integ".to_string(), "integration/main".to_string())]);
+        normalize_config(&mut cfg).unwrap();
+
+        cfg.branch_overrides = BTreeMap::from([("integ".to_string(), "bad..name".to_string())]);
+        assert!(normalize_config(&mut cfg).is_err());
+
+        cfg.branch_overrides =
+            BTreeMap::from([("1integ".to_string(), "integration/main".to_string())]);
+        let err = normalize_config(&mut cfg).unwrap_err();
+        assert!(err.to_string().contains("invalid branch_overrides tag"));
+    }
+
     #[test]
     fn normalize_config_rejects_non_ascii_prefix() {
         let mut cfg = default_config();
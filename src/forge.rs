@@ -0,0 +1,89 @@
+//! Which code-review host the stack's PRs live on.
+//!
+//! GitHub is the default and the only forge every command supports. With `forge: gitlab`, the
+//! publish and status paths (`spr update`, rewrites with `--update`, `spr list`, and
+//! `spr status`) read and write merge requests through `glab` instead of `gh`; see
+//! [`crate::gitlab`]. The read and write helpers in [`crate::github`] dispatch on [`forge`] so
+//! those commands need no forge-specific code of their own.
+
+use anyhow::Result;
+use serde::Deserialize;
+
+use crate::run_settings::Setting;
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Forge {
+    /// Pull requests on GitHub, through `gh`.
+    #[default]
+    Github,
+    /// Merge requests on GitLab (gitlab.com or self-hosted), through `glab`.
+    Gitlab,
+}
+
+impl Forge {
+    /// The CLI spr drives for this forge.
+    pub fn cli_tool(self) -> &'static str {
+        match self {
+            Self::Github => "gh",
+            Self::Gitlab => "glab",
+        }
+    }
+
+    /// How PR `number` is referenced in a PR body: `#12` on GitHub, `!12` on GitLab.
+    pub fn pr_reference(self, number: u64) -> String {
+        match self {
+            Self::Github => format!("#{number}"),
+            Self::Gitlab => format!("!{number}"),
+        }
+    }
+}
+
+static FORGE: Setting<Forge> = Setting::new();
+
+/// Talk to `forge` for the rest of this process.
+pub fn set_forge(forge: Forge) {
+    FORGE.set(forge);
+}
+
+/// The configured forge; GitHub until `main` sets one.
+pub fn forge() -> Forge {
+    FORGE.get().unwrap_or_default()
+}
+
+pub fn is_gitlab() -> bool {
+    forge() == Forge::Gitlab
+}
+
+/// Web URL prefix that a PR number is appended to, e.g. `https://github.com/o/n/pull/`.
+pub fn pr_url_prefix() -> Result<String> {
+    match forge() {
+        Forge::Github => {
+            let (owner, name) = crate::github::get_repo_owner_name()?;
+            Ok(format!("https://github.com/{owner}/{name}/pull/"))
+        }
+        Forge::Gitlab => {
+            let project = crate::gitlab::base_project()?;
+            Ok(format!(
+                "https://{}/{}/-/merge_requests/",
+                project.host, project.path
+            ))
+        }
+    }
+}
+
+/// How PR `number` is referenced in a PR body on the configured forge.
+pub fn pr_reference(number: u64) -> String {
+    forge().pr_reference(number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pr_reference_uses_the_forge_sigil() {
+        assert_eq!(Forge::Github.pr_reference(12), "#12");
+        assert_eq!(Forge::Gitlab.pr_reference(12), "!12");
+    }
+}
@@ -2,17 +2,21 @@
 //!
 //! This module centralizes command execution, dry-run logging, and small
 //! normalization utilities used across commands. When no base branch is
-//! configured, callers rely on [`discover_origin_head_base`] to resolve the
-//! default base via `origin/HEAD`.
+//! configured, callers first try [`discover_upstream_base`] (the current
+//! branch's `@{upstream}`) and then rely on [`discover_remote_head_base`] to
+//! resolve the default base via `origin/HEAD` (or the configured remote's `HEAD`).
 
 use anyhow::{bail, Context, Result};
+use serde::Deserialize;
 use std::collections::{HashMap, HashSet};
-use std::io::Write;
+use std::io::{BufRead, BufReader, Read, Write};
 use std::path::{Path, PathBuf};
-use std::process::{Command, Stdio};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::sync::{Mutex, OnceLock};
 use tracing::{error, info};
 
 use crate::execution::ExecutionMode;
+use crate::run_settings::Setting;
 
 pub fn ensure_tool(name: &str) -> Result<()> {
     let status = Command::new(name)
@@ -77,18 +81,58 @@ pub fn git_rw(execution_mode: ExecutionMode, args: &[&str]) -> Result<String> {
     }
 }
 
+/// `gh pr ...` arguments with `--repo <owner>/<name>` of the base remote appended, so PR
+/// subcommands reach the same repository as the GraphQL and REST calls instead of gh's default
+/// (which differs in fork layouts). Other arguments, and remotes that are not GitHub URLs, are
+/// left alone.
+fn with_gh_repo(args: &[&str]) -> Vec<String> {
+    let mut out: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+    if args.first() == Some(&"pr") && !args.iter().any(|arg| *arg == "--repo" || *arg == "-R") {
+        if let Ok((owner, name)) = crate::github::get_repo_owner_name() {
+            out.push("--repo".to_string());
+            out.push(format!("{owner}/{name}"));
+        }
+    }
+    out
+}
+
+/// Under test, reject any `gh` call the token GitHub client could not serve, so a new call site
+/// cannot quietly break `github_client: token`.
+#[cfg(test)]
+fn assert_token_client_serves(args: &[&str]) {
+    if let Err(err) = crate::github_api::check_supported(args) {
+        panic!("gh {args:?}: {err:#}");
+    }
+}
+
 pub fn gh_ro(args: &[&str]) -> Result<String> {
+    let args = with_gh_repo(args);
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let args = args.as_slice();
+    #[cfg(test)]
+    assert_token_client_serves(args);
     if std::env::var_os("SPR_DRY_RUN").is_some() {
         info!("DRY-RUN: gh {}", shellish(args));
     }
     verbose_log_cmd("gh", args);
+    if crate::github_api::enabled() {
+        return crate::github_api::run(args);
+    }
     run("gh", args)
 }
 
 pub fn gh_rw(execution_mode: ExecutionMode, args: &[&str]) -> Result<String> {
+    let args = with_gh_repo(args);
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let args = args.as_slice();
+    #[cfg(test)]
+    assert_token_client_serves(args);
     match execution_mode {
         ExecutionMode::Apply => {
             verbose_log_cmd("gh", args);
+            if crate::github_api::enabled() {
+                return crate::github_api::run(args);
+            }
             run("gh", args)
         }
         ExecutionMode::DryRun => {
@@ -109,11 +153,63 @@ pub fn gh_rw(execution_mode: ExecutionMode, args: &[&str]) -> Result<String> {
     }
 }
 
+pub fn glab_ro(args: &[&str]) -> Result<String> {
+    if std::env::var_os("SPR_DRY_RUN").is_some() {
+        info!("DRY-RUN: glab {}", shellish(args));
+    }
+    verbose_log_cmd("glab", args);
+    run("glab", args)
+}
+
+pub fn glab_rw(execution_mode: ExecutionMode, args: &[&str]) -> Result<String> {
+    match execution_mode {
+        ExecutionMode::Apply => {
+            verbose_log_cmd("glab", args);
+            run("glab", args)
+        }
+        ExecutionMode::DryRun => {
+            info!("DRY-RUN: glab {}", shellish(args));
+            Ok(String::new())
+        }
+    }
+}
+
+/// Run `git args` with `input` on stdin, e.g. `hash-object -w --stdin` or `mktree`.
+pub fn git_with_stdin(args: &[&str], input: &str) -> Result<String> {
+    verbose_log_cmd("git", args);
+    let mut child = Command::new("git")
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("failed to spawn git {}", shellish(args)))?;
+    child
+        .stdin
+        .take()
+        .with_context(|| format!("failed to open git {} stdin", shellish(args)))?
+        .write_all(input.as_bytes())
+        .with_context(|| format!("failed to write git {} stdin", shellish(args)))?;
+    let out = child
+        .wait_with_output()
+        .with_context(|| format!("failed to collect git {} output", shellish(args)))?;
+    if !out.status.success() {
+        bail!(
+            "command failed: git {}\nstderr:\n{}",
+            shellish(args),
+            String::from_utf8_lossy(&out.stderr)
+        );
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).to_string())
+}
+
 pub fn run(bin: &str, args: &[&str]) -> Result<String> {
+    let started = std::time::Instant::now();
     let out = Command::new(bin)
         .args(args)
         .output()
         .with_context(|| format!("failed to spawn {}", bin))?;
+    crate::timing::record(bin, args, started.elapsed());
     if !out.status.success() {
         let stderr = String::from_utf8_lossy(&out.stderr).to_string();
         let stderr = dedupe_prefixed_lines(bin, &stderr);
@@ -182,15 +278,21 @@ pub fn shellish(args: &[&str]) -> String {
 }
 
 pub fn sanitize_gh_base_ref(base: &str) -> String {
-    if let Some(stripped) = base.strip_prefix("origin/") {
-        return stripped.to_string();
+    if let Some(landing_branch) = pinned_base_landing_branch(base) {
+        return sanitize_gh_base_ref(&landing_branch);
+    }
+    for remote in [remote(), base_remote()] {
+        if let Some(stripped) = base.strip_prefix(&format!("{remote}/")) {
+            return stripped.to_string();
+        }
     }
     base.to_string()
 }
 
 pub fn normalize_branch_name(name: &str) -> String {
     let mut out = name.strip_prefix("refs/heads/").unwrap_or(name);
-    out = out.strip_prefix("origin/").unwrap_or(out);
+    let remote_prefix = format!("{}/", remote());
+    out = out.strip_prefix(&remote_prefix).unwrap_or(out);
     out.to_string()
 }
 
@@ -317,6 +419,19 @@ pub fn git_current_branch() -> Result<String> {
         .to_string())
 }
 
+/// The name a stack whose tip is `from` is published under: the checked-out branch for `HEAD`
+/// (the commit itself when `HEAD` is detached), or the `--from` ref otherwise.
+pub fn stack_ref_name(from: &str) -> Result<String> {
+    if from != "HEAD" {
+        return Ok(from.strip_prefix("refs/heads/").unwrap_or(from).to_string());
+    }
+    let branch = git_current_branch()?;
+    if branch != "HEAD" {
+        return Ok(branch);
+    }
+    git_rev_parse("HEAD")
+}
+
 pub fn git_current_branch_at(path: &str) -> Result<String> {
     Ok(
         git_ro_in(path, ["rev-parse", "--abbrev-ref", "HEAD"].as_slice())?
@@ -335,10 +450,65 @@ pub fn git_ref_exists_at(path: &str, reference: &str) -> Result<bool> {
     Ok(status.success())
 }
 
-/// Discover the repository's default branch via `origin/HEAD`.
+/// Discover a stack base from the current branch's `@{upstream}`.
 ///
-/// This runs `git symbolic-ref --short refs/remotes/origin/HEAD` and expects
-/// output like `origin/main`. If `origin/HEAD` is unset or the command fails,
+/// A stack branch created with `git checkout -b stack origin/release-1.2` tracks the branch it
+/// targets, so its upstream is a better default than `origin/HEAD`. Returns `None` when `HEAD`
+/// is detached, has no upstream, or tracks its own published copy or an spr PR branch
+/// (`prefix`), since neither of those is a meaningful base.
+pub fn discover_upstream_base(prefix: &str) -> Result<Option<String>> {
+    let quiet_git = |args: &[&str]| -> Result<Option<String>> {
+        verbose_log_cmd("git", args);
+        let out = Command::new("git")
+            .args(args)
+            .stderr(Stdio::null())
+            .output()
+            .context("failed to spawn git")?;
+        let value = String::from_utf8_lossy(&out.stdout).trim().to_string();
+        Ok((out.status.success() && !value.is_empty()).then_some(value))
+    };
+    let Some(branch) = quiet_git(["symbolic-ref", "--quiet", "--short", "HEAD"].as_slice())? else {
+        return Ok(None);
+    };
+    let Some(upstream) = quiet_git(
+        [
+            "rev-parse",
+            "--abbrev-ref",
+            "--symbolic-full-name",
+            "@{upstream}",
+        ]
+        .as_slice(),
+    )?
+    else {
+        return Ok(None);
+    };
+    let upstream_branch =
+        quiet_git(["config", "--get", &format!("branch.{branch}.merge")].as_slice())?
+            .map(|merge| merge.trim_start_matches("refs/heads/").to_string())
+            .unwrap_or_default();
+    if upstream_branch == branch || upstream_branch.starts_with(prefix) {
+        return Ok(None);
+    }
+    Ok(Some(upstream))
+}
+
+/// Read a single Git config value, returning `None` when the key is unset.
+pub fn git_config_get(key: &str) -> Result<Option<String>> {
+    let args = ["config", "--get", key];
+    verbose_log_cmd("git", &args);
+    let out = Command::new("git")
+        .args(args)
+        .stderr(Stdio::null())
+        .output()
+        .context("failed to spawn git")?;
+    let value = String::from_utf8_lossy(&out.stdout).trim().to_string();
+    Ok((out.status.success() && !value.is_empty()).then_some(value))
+}
+
+/// Discover the repository's default branch via `<remote>/HEAD` (normally `origin/HEAD`).
+///
+/// This runs `git symbolic-ref --short refs/remotes/<remote>/HEAD` and expects
+/// output like `origin/main`. If `<remote>/HEAD` is unset or the command fails,
 /// callers should surface the error loudly and instruct users to set `base`
 /// explicitly in `.spr_multicommit_cfg.yml`.
 ///
@@ -346,20 +516,187 @@ pub fn git_ref_exists_at(path: &str, reference: &str) -> Result<bool> {
 /// local `origin/HEAD` symbolic ref is up to date. In repositories that use a
 /// different remote name or do not track `origin/HEAD`, explicit configuration
 /// is more reliable than discovery.
-pub fn discover_origin_head_base() -> Result<String> {
-    let out = git_ro(["symbolic-ref", "--short", "refs/remotes/origin/HEAD"].as_slice())
+pub fn discover_remote_head_base() -> Result<String> {
+    let remote = remote();
+    let out = git_ro(
+        [
+            "symbolic-ref",
+            "--short",
+            &format!("refs/remotes/{remote}/HEAD"),
+        ]
+        .as_slice(),
+    )
         .with_context(|| {
-            "failed to discover default branch from origin/HEAD; set `base` in .spr_multicommit_cfg.yml or run `git remote set-head origin -a`"
+            "failed to discover default branch from {remote}/HEAD; set `base` in .spr_multicommit_cfg.yml or run `git remote set-head {remote} -a`"
         })?;
     let base = out.trim();
     if base.is_empty() {
         bail!(
-            "origin/HEAD resolved to an empty ref; set `base` in .spr_multicommit_cfg.yml or run `git remote set-head origin -a`"
+            "{remote}/HEAD resolved to an empty ref; set `base` in .spr_multicommit_cfg.yml or run `git remote set-head {remote} -a`"
         );
     }
     Ok(base.to_string())
 }
 
+static REMOTE: Setting<String> = Setting::new();
+
+/// Use `name` instead of `origin` for fetches, pushes, and remote-tracking refs for the rest of
+/// this process.
+pub fn set_remote(name: &str) {
+    let name = name.trim();
+    if !name.is_empty() {
+        REMOTE.set(name.to_string());
+    }
+}
+
+/// The git remote `spr` talks to; `origin` unless `--remote` or config `remote` says otherwise.
+pub fn remote() -> String {
+    REMOTE.get().unwrap_or_else(|| "origin".to_string())
+}
+
+static BASE_REMOTE: Setting<String> = Setting::new();
+
+/// Route the base and the pull requests to remote `name` while PR branches keep pushing to
+/// [`remote`], for triangular setups such as a fork (`origin`) with PRs against `upstream`.
+pub fn set_base_remote(name: &str) {
+    let name = name.trim();
+    if !name.is_empty() {
+        BASE_REMOTE.set(name.to_string());
+    }
+}
+
+/// The git remote that hosts the base branch and the pull requests; [`remote`] unless the base
+/// lives on another remote.
+pub fn base_remote() -> String {
+    BASE_REMOTE.get().unwrap_or_else(remote)
+}
+
+/// The remote named by the first segment of `base` (`upstream/main` -> `upstream`) when it is a
+/// configured git remote other than [`remote`].
+pub fn foreign_base_remote(base: &str) -> Result<Option<String>> {
+    let Some((name, _branch)) = base.split_once('/') else {
+        return Ok(None);
+    };
+    if name == remote() || git_config_get(&format!("remote.{name}.url"))?.is_none() {
+        return Ok(None);
+    }
+    Ok(Some(name.to_string()))
+}
+
+/// The pinned base and the landing branch its PRs target.
+static PINNED_BASE: Setting<(String, String)> = Setting::new();
+
+/// Whether `base` names a tag or a commit rather than a local or remote-tracking branch.
+///
+/// A base that does not resolve at all is not pinned; later git calls report it.
+pub fn is_pinned_base(base: &str) -> Result<bool> {
+    if git_ref_exists_at(".", &format!("refs/heads/{base}"))?
+        || git_ref_exists_at(".", &format!("refs/remotes/{base}"))?
+    {
+        return Ok(false);
+    }
+    git_ref_exists_at(".", &format!("{base}^{{commit}}"))
+}
+
+/// Open and retarget PRs of a stack rooted at the tag or commit `base` against `landing_branch`
+/// in this process, while merge-bases and diffs keep using `base`.
+pub fn set_pinned_base(base: &str, landing_branch: &str) {
+    PINNED_BASE.set((base.to_string(), landing_branch.to_string()));
+}
+
+fn pinned_base_landing_branch(base: &str) -> Option<String> {
+    let (pinned, landing_branch) = PINNED_BASE.get()?;
+    (pinned == base && !landing_branch.is_empty()).then_some(landing_branch)
+}
+
+static NO_FETCH: Setting<bool> = Setting::new();
+
+/// Skip `git fetch <remote>` for the rest of this process, trusting the local remote-tracking refs.
+pub fn set_no_fetch(enabled: bool) {
+    if enabled {
+        NO_FETCH.set(true);
+    }
+}
+
+pub fn no_fetch() -> bool {
+    NO_FETCH.get().unwrap_or(false)
+}
+
+/// Refresh the remote's (and a separate base remote's) remote-tracking refs, unless
+/// `--no-fetch` was given.
+pub fn fetch_remote(execution_mode: ExecutionMode) -> Result<()> {
+    let remote = remote();
+    if no_fetch() {
+        info!("Skipping `git fetch {}` (--no-fetch)", remote);
+        return Ok(());
+    }
+    git_rw(execution_mode, ["fetch", &remote].as_slice())?;
+    let base_remote = base_remote();
+    if base_remote != remote {
+        git_rw(execution_mode, ["fetch", &base_remote].as_slice())?;
+    }
+    Ok(())
+}
+
+/// Time since the last `git fetch` in this repository, from `FETCH_HEAD`'s mtime.
+///
+/// Returns `None` when the repository has never been fetched.
+pub fn last_fetch_age() -> Result<Option<std::time::Duration>> {
+    let fetch_head = git_common_dir()?.join("FETCH_HEAD");
+    let Ok(metadata) = std::fs::metadata(&fetch_head) else {
+        return Ok(None);
+    };
+    let modified = metadata
+        .modified()
+        .with_context(|| format!("Failed to read mtime of {}", fetch_head.display()))?;
+    Ok(Some(modified.elapsed().unwrap_or_default()))
+}
+
+/// Warning for a remote-tracking `base` whose last fetch is older than `stale_after_hours`.
+///
+/// `age` is `None` when the repository has never been fetched. A threshold of `0` disables
+/// the check.
+pub fn stale_fetch_warning(
+    base: &str,
+    age: Option<std::time::Duration>,
+    stale_after_hours: u32,
+) -> Option<String> {
+    if stale_after_hours == 0 {
+        return None;
+    }
+    let Some(age) = age else {
+        return Some(format!(
+            "{base} has never been fetched; run `git fetch {}` for an up-to-date base",
+            remote()
+        ));
+    };
+    let hours = age.as_secs() / 3600;
+    if hours < u64::from(stale_after_hours) {
+        return None;
+    }
+    let ago = if hours >= 48 {
+        format!("{} days", hours / 24)
+    } else {
+        format!("{hours} hours")
+    };
+    Some(format!(
+        "{base} was last fetched {ago} ago; run `git fetch {}` for an up-to-date base",
+        remote()
+    ))
+}
+
+/// [`stale_fetch_warning`] for `base` when it is a remote-tracking branch.
+pub fn base_fetch_warning(base: &str, stale_after_hours: u32) -> Result<Option<String>> {
+    if stale_after_hours == 0 || !git_ref_exists_at(".", &format!("refs/remotes/{base}"))? {
+        return Ok(None);
+    }
+    Ok(stale_fetch_warning(
+        base,
+        last_fetch_age()?,
+        stale_after_hours,
+    ))
+}
+
 pub fn verbose_log_cmd(tool: &str, args: &[&str]) {
     if std::env::var_os("SPR_VERBOSE").is_some() {
         info!("{} {}", tool, shellish(args));
@@ -367,9 +704,13 @@ pub fn verbose_log_cmd(tool: &str, args: &[&str]) {
 }
 
 pub fn to_remote_ref(name: &str) -> String {
+    let remote = remote();
     let name = name.strip_prefix("refs/heads/").unwrap_or(name);
-    let name = name.strip_prefix("origin/").unwrap_or(name);
-    format!("origin/{}", name)
+    if base_remote() != remote && name.starts_with(&format!("{}/", base_remote())) {
+        return name.to_string();
+    }
+    let name = name.strip_prefix(&format!("{remote}/")).unwrap_or(name);
+    format!("{remote}/{name}")
 }
 
 pub fn get_remote_branches_sha(branches: &[String]) -> Result<HashMap<String, String>> {
@@ -377,7 +718,8 @@ pub fn get_remote_branches_sha(branches: &[String]) -> Result<HashMap<String, St
     if branches.is_empty() {
         return Ok(out_map);
     }
-    let mut args: Vec<&str> = vec!["ls-remote", "--heads", "origin"];
+    let remote = remote();
+    let mut args: Vec<&str> = vec!["ls-remote", "--heads", &remote];
     let owned: Vec<String> = branches.iter().map(|b| b.to_string()).collect();
     let refs: Vec<&str> = owned.iter().map(|s| s.as_str()).collect();
     args.extend(refs);
@@ -415,11 +757,200 @@ pub fn git_is_ancestor(ancestor: &str, descendant: &str) -> Result<bool> {
     }
 }
 
+/* ------------------ object reads ------------------ */
+
+/// How revisions are resolved and commit objects read.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GitBackendKind {
+    /// One long-lived `git cat-file --batch-command` per repository serves every lookup.
+    #[default]
+    Batch,
+    /// Every lookup starts its own `git` process.
+    Subprocess,
+}
+
+/// Revision and object reads that spr repeats many times per run.
+pub trait GitBackend: Send + Sync {
+    /// Full object id that `revision` names, or `None` when it names no object.
+    fn resolve(&self, revision: &str) -> Result<Option<String>>;
+    /// Raw commit object `revision` peels to: headers, a blank line, then the message.
+    fn read_commit(&self, revision: &str) -> Result<String>;
+}
+
+struct SubprocessBackend;
+
+impl GitBackend for SubprocessBackend {
+    fn resolve(&self, revision: &str) -> Result<Option<String>> {
+        let started = std::time::Instant::now();
+        let args = ["rev-parse", "--verify", "--quiet", revision];
+        verbose_log_cmd("git", &args);
+        let out = Command::new("git")
+            .args(args)
+            .output()
+            .with_context(|| format!("failed to resolve {}", revision))?;
+        crate::timing::record("git", &args, started.elapsed());
+        if out.status.success() {
+            Ok(Some(
+                String::from_utf8_lossy(&out.stdout).trim().to_string(),
+            ))
+        } else if out.status.code() == Some(1) {
+            Ok(None)
+        } else {
+            bail!(
+                "failed to resolve {}: {}",
+                revision,
+                String::from_utf8_lossy(&out.stderr).trim()
+            );
+        }
+    }
+
+    fn read_commit(&self, revision: &str) -> Result<String> {
+        git_ro(["cat-file", "commit", revision].as_slice())
+    }
+}
+
+/// A `git cat-file --batch-command` process answering `info` and `contents` requests.
+struct CatFileProcess {
+    _child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl CatFileProcess {
+    fn spawn() -> Result<Self> {
+        let mut child = Command::new("git")
+            .args(["cat-file", "--batch-command"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .context("failed to spawn git cat-file --batch-command")?;
+        let stdin = child.stdin.take().context("git cat-file stdin missing")?;
+        let stdout = child.stdout.take().context("git cat-file stdout missing")?;
+        Ok(Self {
+            _child: child,
+            stdin,
+            stdout: BufReader::new(stdout),
+        })
+    }
+
+    /// Object id, type, and (for `contents`) the raw object `revision` names; `None` when it is
+    /// missing or ambiguous.
+    fn request(
+        &mut self,
+        command: &str,
+        revision: &str,
+    ) -> Result<Option<(String, String, Vec<u8>)>> {
+        writeln!(self.stdin, "{command} {revision}")?;
+        self.stdin.flush()?;
+        let mut header = String::new();
+        if self.stdout.read_line(&mut header)? == 0 {
+            bail!("git cat-file exited while reading {}", revision);
+        }
+        let header = header.trim_end_matches('\n');
+        let Some((object, last)) = header.rsplit_once(' ') else {
+            bail!("unexpected git cat-file reply for {}: {}", revision, header);
+        };
+        if matches!(last, "missing" | "ambiguous") {
+            return Ok(None);
+        }
+        let Some((oid, kind)) = object.split_once(' ') else {
+            bail!("unexpected git cat-file reply for {}: {}", revision, header);
+        };
+        let mut contents = Vec::new();
+        if command == "contents" {
+            let size: usize = last
+                .parse()
+                .with_context(|| format!("unexpected git cat-file object size {}", last))?;
+            // The object is followed by a newline
+            contents.resize(size + 1, 0);
+            self.stdout.read_exact(&mut contents)?;
+            contents.truncate(size);
+        }
+        Ok(Some((oid.to_string(), kind.to_string(), contents)))
+    }
+}
+
+/// Reads served by one [`CatFileProcess`] per working directory, so a run that resolves hundreds
+/// of revisions starts one process per repository instead of one per lookup.
+///
+/// `cat-file` reads refs and objects afresh on every request, so refs moved and objects written
+/// after it started are seen. Where the process cannot be started (for example a git older than
+/// 2.36) or a request fails, lookups fall back to [`SubprocessBackend`].
+#[derive(Default)]
+struct BatchBackend {
+    processes: Mutex<HashMap<PathBuf, Option<CatFileProcess>>>,
+}
+
+impl BatchBackend {
+    /// Answer `command revision` from this directory's process; `Err` means fall back.
+    fn request(&self, command: &str, revision: &str) -> Result<Option<(String, String, Vec<u8>)>> {
+        if revision.contains('\n') {
+            bail!("revision contains a newline");
+        }
+        let dir = std::env::current_dir()?;
+        let mut processes = self
+            .processes
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let process = processes
+            .entry(dir.clone())
+            .or_insert_with(|| CatFileProcess::spawn().ok());
+        let Some(process) = process.as_mut() else {
+            bail!("git cat-file --batch-command is unavailable");
+        };
+        let started = std::time::Instant::now();
+        let reply = process.request(command, revision);
+        crate::timing::record("git", &["cat-file", command], started.elapsed());
+        if reply.is_err() {
+            processes.insert(dir, None);
+        }
+        reply
+    }
+}
+
+impl GitBackend for BatchBackend {
+    fn resolve(&self, revision: &str) -> Result<Option<String>> {
+        match self.request("info", revision) {
+            Ok(reply) => Ok(reply.map(|(oid, _, _)| oid)),
+            Err(_) => SubprocessBackend.resolve(revision),
+        }
+    }
+
+    fn read_commit(&self, revision: &str) -> Result<String> {
+        match self.request("contents", &format!("{revision}^{{commit}}")) {
+            Ok(Some((_, kind, contents))) if kind == "commit" => {
+                Ok(String::from_utf8_lossy(&contents).into_owned())
+            }
+            _ => SubprocessBackend.read_commit(revision),
+        }
+    }
+}
+
+static GIT_BACKEND: Setting<GitBackendKind> = Setting::new();
+
+/// Serve revision and commit reads from `kind` for the rest of this process.
+pub fn set_git_backend(kind: GitBackendKind) {
+    GIT_BACKEND.set(kind);
+}
+
+fn git_backend() -> &'static dyn GitBackend {
+    static BATCH: OnceLock<BatchBackend> = OnceLock::new();
+    match GIT_BACKEND.get().unwrap_or_default() {
+        GitBackendKind::Batch => BATCH.get_or_init(BatchBackend::default),
+        GitBackendKind::Subprocess => &SubprocessBackend,
+    }
+}
+
+/// Full object id that `revision` names, or `None` when it names no object.
+pub fn git_resolve(revision: &str) -> Result<Option<String>> {
+    git_backend().resolve(revision)
+}
+
 /// Resolves a revision to its full object id.
 pub fn git_rev_parse(revision: &str) -> Result<String> {
-    Ok(git_ro(["rev-parse", revision].as_slice())?
-        .trim()
-        .to_string())
+    git_resolve(revision)?.with_context(|| format!("unknown revision {}", revision))
 }
 
 pub fn git_rev_parse_at(path: &str, revision: &str) -> Result<String> {
@@ -444,24 +975,7 @@ pub fn git_merge_base(left: &str, right: &str) -> Result<String> {
 /// Returns the tip SHA of an exact local branch name, if it exists.
 pub fn git_local_branch_tip(branch: &str) -> Result<Option<String>> {
     let reference = format!("refs/heads/{branch}^{{commit}}");
-    let out = Command::new("git")
-        .args(["rev-parse", "--verify", "--quiet", &reference])
-        .output()
-        .with_context(|| format!("failed to inspect local branch {}", branch))?;
-    if out.status.success() {
-        let sha = String::from_utf8_lossy(&out.stdout).trim().to_string();
-        Ok(Some(sha))
-    } else if out.status.code() == Some(1) {
-        Ok(None)
-    } else {
-        let stderr = String::from_utf8_lossy(&out.stderr).to_string();
-        bail!(
-            "failed to inspect local branch {} via {}: {}",
-            branch,
-            reference,
-            stderr
-        );
-    }
+    git_resolve(&reference).with_context(|| format!("failed to inspect local branch {}", branch))
 }
 
 /// Returns the commits in `from_exclusive..to_inclusive`, oldest first.
@@ -476,16 +990,31 @@ pub fn git_rev_list_range(from_exclusive: &str, to_inclusive: &str) -> Result<Ve
         .collect())
 }
 
+/// Header lines and message of a raw commit object.
+fn split_commit_object(raw: &str) -> (&str, &str) {
+    raw.split_once("\n\n")
+        .unwrap_or((raw.trim_end_matches('\n'), ""))
+}
+
 /// Returns the number of parents on the given commit.
 pub fn git_commit_parent_count(sha: &str) -> Result<usize> {
-    let out = git_ro(["rev-list", "--parents", "-n", "1", sha].as_slice())?;
-    let count = out.split_whitespace().count().saturating_sub(1);
-    Ok(count)
+    let raw = git_backend().read_commit(sha)?;
+    let (headers, _) = split_commit_object(&raw);
+    Ok(headers
+        .lines()
+        .filter(|line| line.starts_with("parent "))
+        .count())
 }
 
-/// Returns the full commit message for `sha`.
+/// Returns the full commit message for `sha`, as `git log --format=%B` prints it.
 pub fn git_commit_message(sha: &str) -> Result<String> {
-    git_ro(["log", "-n", "1", "--format=%B", sha].as_slice())
+    let raw = git_backend().read_commit(sha)?;
+    let (headers, message) = split_commit_object(&raw);
+    // Only `git log` re-encodes messages stored in another encoding
+    if headers.lines().any(|line| line.starts_with("encoding ")) {
+        return git_ro(["log", "-n", "1", "--format=%B", sha].as_slice());
+    }
+    Ok(format!("{message}\n"))
 }
 
 /// Returns a verbatim patch fingerprint for each commit, keyed by commit SHA.
@@ -580,7 +1109,7 @@ pub fn git_patch_ids_for_commits(commits: &[String]) -> Result<HashMap<String, S
 
 pub fn list_remote_branches_with_prefix(prefix: &str) -> Result<Vec<String>> {
     // List all remote heads and filter by prefix
-    let out = git_ro(["ls-remote", "--heads", "origin"].as_slice())?;
+    let out = git_ro(["ls-remote", "--heads", &remote()].as_slice())?;
     let mut names: Vec<String> = vec![];
     for line in out.lines() {
         let mut parts = line.split_whitespace();
@@ -599,7 +1128,91 @@ pub fn list_remote_branches_with_prefix(prefix: &str) -> Result<Vec<String>> {
 
 #[cfg(test)]
 mod tests {
-    use super::parse_worktree_list_porcelain;
+    use super::{
+        discover_upstream_base, foreign_base_remote, git_commit_message, git_commit_parent_count,
+        is_pinned_base, parse_worktree_list_porcelain, sanitize_gh_base_ref, stale_fetch_warning,
+        with_gh_repo, BatchBackend, GitBackend, SubprocessBackend, PINNED_BASE,
+    };
+    use crate::test_support::{commit_file, git, init_repo, lock_cwd, DirGuard};
+
+    #[test]
+    fn batch_backend_matches_subprocess_reads_and_sees_later_commits() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path();
+        let first = commit_file(repo, "a.txt", "a\n", "feat: a\n\nBody line\n\nTrailer: x");
+        let _guard = DirGuard::change_to(repo);
+        let batch = BatchBackend::default();
+
+        assert_eq!(
+            batch.resolve("HEAD").unwrap().as_deref(),
+            Some(first.as_str())
+        );
+        assert_eq!(
+            batch.resolve("HEAD^{tree}").unwrap(),
+            SubprocessBackend.resolve("HEAD^{tree}").unwrap()
+        );
+        assert_eq!(batch.resolve("no-such-ref").unwrap(), None);
+        assert_eq!(
+            batch.read_commit("HEAD").unwrap(),
+            SubprocessBackend.read_commit("HEAD").unwrap()
+        );
+
+        let second = commit_file(repo, "b.txt", "b\n", "feat: b");
+        assert_eq!(batch.resolve("HEAD").unwrap(), Some(second.clone()));
+        assert_eq!(batch.resolve("HEAD~1").unwrap(), Some(first.clone()));
+        assert_eq!(
+            git_commit_message(&first).unwrap(),
+            git(repo, ["log", "-n", "1", "--format=%B", &first].as_slice())
+        );
+        assert_eq!(git_commit_parent_count(&second).unwrap(), 1);
+    }
+
+    #[test]
+    fn pinned_tag_or_commit_base_targets_the_landing_branch() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path();
+        git(repo, ["tag", "v1.0"].as_slice());
+        let sha = git(repo, ["rev-parse", "HEAD"].as_slice())
+            .trim()
+            .to_string();
+        let _guard = DirGuard::change_to(repo);
+
+        assert!(is_pinned_base("v1.0").unwrap());
+        assert!(is_pinned_base(&sha).unwrap());
+        assert!(!is_pinned_base("main").unwrap());
+        assert!(!is_pinned_base("no-such-ref").unwrap());
+
+        let _pinned = PINNED_BASE.scoped(("v1.0".to_string(), "origin/release-1.x".to_string()));
+        assert_eq!(sanitize_gh_base_ref("v1.0"), "release-1.x");
+        assert_eq!(sanitize_gh_base_ref("origin/main"), "main");
+    }
+
+    #[test]
+    fn gh_pr_subcommands_target_the_base_remote_repository() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path();
+        git(
+            repo,
+            ["remote", "add", "origin", "git@github.com:acme/widgets.git"].as_slice(),
+        );
+        let _guard = DirGuard::change_to(repo);
+
+        assert_eq!(
+            with_gh_repo(&["pr", "close", "#3"]),
+            vec!["pr", "close", "#3", "--repo", "acme/widgets"]
+        );
+        assert_eq!(
+            with_gh_repo(&["pr", "list", "--repo", "other/repo"]),
+            vec!["pr", "list", "--repo", "other/repo"]
+        );
+        assert_eq!(
+            with_gh_repo(&["api", "graphql", "-f", "query=q"]),
+            vec!["api", "graphql", "-f", "query=q"]
+        );
+    }
 
     #[test]
     fn parse_worktree_list_porcelain_preserves_main_worktree_first() {
@@ -626,4 +1239,91 @@ mod tests {
         assert_eq!(entries[1].path, "/tmp/repo-stack");
         assert_eq!(entries[1].branch.as_deref(), Some("stack"));
     }
+
+    #[test]
+    fn stale_fetch_warning_reports_old_or_missing_fetches() {
+        let hours = |count: u64| Some(std::time::Duration::from_secs(count * 3600));
+        assert_eq!(stale_fetch_warning("origin/main", hours(3), 24), None);
+        assert_eq!(
+            stale_fetch_warning("origin/main", hours(30), 24).as_deref(),
+            Some("origin/main was last fetched 30 hours ago; run `git fetch origin` for an up-to-date base")
+        );
+        assert!(stale_fetch_warning("origin/main", hours(72), 24)
+            .unwrap()
+            .contains("last fetched 3 days ago"));
+        assert!(stale_fetch_warning("origin/main", None, 24)
+            .unwrap()
+            .contains("never been fetched"));
+        assert_eq!(stale_fetch_warning("origin/main", None, 0), None);
+    }
+
+    #[test]
+    fn foreign_base_remote_names_only_other_configured_remotes() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path();
+        let _guard = DirGuard::change_to(repo);
+        git(
+            repo,
+            [
+                "remote",
+                "add",
+                "upstream",
+                "https://github.com/acme/widgets.git",
+            ]
+            .as_slice(),
+        );
+        git(
+            repo,
+            [
+                "remote",
+                "add",
+                "origin",
+                "https://github.com/me/widgets.git",
+            ]
+            .as_slice(),
+        );
+
+        assert_eq!(
+            foreign_base_remote("upstream/main").unwrap().as_deref(),
+            Some("upstream")
+        );
+        assert_eq!(foreign_base_remote("origin/main").unwrap(), None);
+        assert_eq!(foreign_base_remote("release/1.2").unwrap(), None);
+        assert_eq!(foreign_base_remote("main").unwrap(), None);
+    }
+
+    #[test]
+    fn discover_upstream_base_uses_tracked_branch_but_not_own_or_pr_branches() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path();
+        let _guard = DirGuard::change_to(repo);
+        git(repo, ["branch", "release-1.2"].as_slice());
+        git(
+            repo,
+            ["checkout", "-b", "stack", "--track", "release-1.2"].as_slice(),
+        );
+
+        assert_eq!(
+            discover_upstream_base("dank-spr/").unwrap().as_deref(),
+            Some("release-1.2")
+        );
+
+        git(
+            repo,
+            ["config", "branch.stack.merge", "refs/heads/stack"].as_slice(),
+        );
+        assert_eq!(discover_upstream_base("dank-spr/").unwrap(), None);
+
+        git(repo, ["branch", "dank-spr/alpha"].as_slice());
+        git(
+            repo,
+            ["checkout", "-b", "scratch", "--track", "dank-spr/alpha"].as_slice(),
+        );
+        assert_eq!(discover_upstream_base("dank-spr/").unwrap(), None);
+
+        git(repo, ["checkout", "--detach"].as_slice());
+        assert_eq!(discover_upstream_base("dank-spr/").unwrap(), None);
+    }
 }
@@ -7,10 +7,14 @@
 //! remote head variants as explicit conflicts. The update preflight separately looks up the
 //! latest terminal PR event for a canonical head ref so branch-name reuse can be blocked after
 //! recent merges or closes.
+//!
+//! With `forge: gitlab`, the head lookups, body and state reads, CI status, and PR creation used
+//! by `spr update`, `spr list`, and `spr status` dispatch to [`crate::gitlab`] instead.
 
 use anyhow::{anyhow, bail, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, OnceLock};
 use time::{format_description::well_known::Rfc3339, OffsetDateTime};
 use tracing::info;
 
@@ -97,7 +101,7 @@ const MAX_CONFLICT_HEADS_PER_QUERY: usize = 10;
 const MAX_TERMINAL_HEADS_PER_QUERY: usize = 10;
 const MAX_PR_BODIES_PER_QUERY: usize = 10;
 const MAX_PR_STATUS_PER_QUERY: usize = 20;
-const HEAD_SEARCH_FIELDS: &str =
+pub(crate) const HEAD_SEARCH_FIELDS: &str =
     "number,headRefName,baseRefName,state,mergedAt,closedAt,url,autoMergeRequest";
 const EXACT_HEAD_QUERY_LIMIT: usize = 10;
 const EXACT_PR_GRAPHQL_FIELDS: &str =
@@ -660,25 +664,120 @@ fn select_latest_terminal_pr_match(
 #[derive(Clone)]
 pub struct PrBodyInfo {
     pub id: String,
+    pub title: String,
     pub body: String,
 }
 
 pub fn fetch_pr_bodies_graphql(numbers: &[u64]) -> Result<HashMap<u64, PrBodyInfo>> {
+    if crate::forge::is_gitlab() {
+        return crate::gitlab::fetch_mr_bodies(numbers);
+    }
+    let chunks: Vec<&[u64]> = numbers.chunks(MAX_PR_BODIES_PER_QUERY).collect();
     let mut out = HashMap::new();
-    for chunk in numbers.chunks(MAX_PR_BODIES_PER_QUERY) {
-        let chunk_out = run_read_chunk_with_retry(
-            chunk,
-            &fetch_pr_bodies_graphql_chunk,
-            &|mut left, right| {
-                left.extend(right);
-                left
-            },
-        )?;
-        out.extend(chunk_out);
+    for chunk_out in crate::concurrency::map(&chunks, |chunk| {
+        run_read_chunk_with_retry(chunk, &fetch_pr_bodies_graphql_chunk, &|mut left, right| {
+            left.extend(right);
+            left
+        })
+    }) {
+        out.extend(chunk_out?);
+    }
+    Ok(out)
+}
+
+/// GraphQL node IDs already read during this run, keyed by `owner/name` and PR number.
+///
+/// A PR's node ID never changes, so mutations that only need the ID reuse what an earlier body
+/// or draft-stage query returned instead of asking GitHub again.
+static PR_NODE_IDS: OnceLock<Mutex<HashMap<(String, u64), String>>> = OnceLock::new();
+
+fn pr_node_id_cache() -> std::sync::MutexGuard<'static, HashMap<(String, u64), String>> {
+    PR_NODE_IDS
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn remember_pr_node_ids<'a>(
+    owner: &str,
+    name: &str,
+    ids: impl IntoIterator<Item = (u64, &'a str)>,
+) {
+    let repo = format!("{owner}/{name}");
+    let mut cache = pr_node_id_cache();
+    for (number, id) in ids {
+        if !id.is_empty() {
+            cache.insert((repo.clone(), number), id.to_string());
+        }
+    }
+}
+
+/// GraphQL node IDs for `numbers`, querying GitHub only for PRs not seen earlier in this run.
+///
+/// # Errors
+///
+/// Returns errors when the repository cannot be resolved or a missing PR cannot be read.
+pub fn fetch_pr_node_ids_graphql(numbers: &[u64]) -> Result<HashMap<u64, String>> {
+    if numbers.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let (mut out, missing) = cached_pr_node_ids(numbers)?;
+    for chunk in missing.chunks(MAX_PR_BODIES_PER_QUERY) {
+        out.extend(
+            fetch_pr_stage_info_graphql(chunk)?
+                .into_iter()
+                .map(|(number, info)| (number, info.id)),
+        );
     }
     Ok(out)
 }
 
+/// Node IDs of `numbers` already read during this run, and the numbers still unknown.
+fn cached_pr_node_ids(numbers: &[u64]) -> Result<(HashMap<u64, String>, Vec<u64>)> {
+    let (owner, name) = get_repo_owner_name()?;
+    let repo = format!("{owner}/{name}");
+    let cache = pr_node_id_cache();
+    let mut known = HashMap::new();
+    let mut missing = Vec::new();
+    for number in numbers {
+        match cache.get(&(repo.clone(), *number)) {
+            Some(id) => {
+                known.insert(*number, id.clone());
+            }
+            None => missing.push(*number),
+        }
+    }
+    Ok((known, missing))
+}
+
+/// Titles and bodies of `body_numbers` plus node IDs of `id_numbers`, in one read: IDs seen
+/// earlier in the run come from the cache, and the rest ride along with the body query.
+///
+/// # Errors
+///
+/// Returns errors when the repository cannot be resolved or the query fails.
+pub fn fetch_pr_bodies_with_node_ids_graphql(
+    body_numbers: &[u64],
+    id_numbers: &[u64],
+) -> Result<(HashMap<u64, PrBodyInfo>, HashMap<u64, String>)> {
+    let (mut ids, missing) = cached_pr_node_ids(id_numbers)?;
+    let mut numbers = body_numbers.to_vec();
+    numbers.extend(
+        missing
+            .into_iter()
+            .filter(|number| !body_numbers.contains(number)),
+    );
+    let mut bodies = fetch_pr_bodies_graphql(&numbers)?;
+    ids.extend(
+        bodies
+            .iter()
+            .filter(|(number, _)| id_numbers.contains(number))
+            .map(|(number, info)| (*number, info.id.clone())),
+    );
+    bodies.retain(|number, _| body_numbers.contains(number));
+    Ok((bodies, ids))
+}
+
 pub fn fetch_pr_issue_comment_bodies_graphql(number: u64) -> Result<Vec<String>> {
     let (owner, name) = get_repo_owner_name()?;
     let query = "query($owner:String!,$name:String!,$number:Int!,$cursor:String){ repository(owner:$owner,name:$name){ pullRequest(number:$number){ comments(first:100,after:$cursor){ pageInfo { hasNextPage endCursor } nodes { body } } } } }";
@@ -738,7 +837,7 @@ fn fetch_pr_bodies_graphql_chunk(numbers: &[u64]) -> Result<HashMap<u64, PrBodyI
         String::from("query($owner:String!,$name:String!){ repository(owner:$owner,name:$name){ ");
     for (i, n) in numbers.iter().enumerate() {
         q.push_str(&format!(
-            "pr{}: pullRequest(number: {}) {{ id body }} ",
+            "pr{}: pullRequest(number: {}) {{ id title body }} ",
             i, n
         ));
     }
@@ -761,8 +860,98 @@ fn fetch_pr_bodies_graphql_chunk(numbers: &[u64]) -> Result<HashMap<u64, PrBodyI
     for (i, n) in numbers.iter().enumerate() {
         let key = format!("pr{}", i);
         let id = repo[&key]["id"].as_str().unwrap_or("").to_string();
+        let title = repo[&key]["title"].as_str().unwrap_or("").to_string();
         let body = repo[&key]["body"].as_str().unwrap_or("").to_string();
-        out.insert(*n, PrBodyInfo { id, body });
+        out.insert(*n, PrBodyInfo { id, title, body });
+    }
+    remember_pr_node_ids(
+        &owner,
+        &name,
+        out.iter().map(|(number, info)| (*number, info.id.as_str())),
+    );
+    Ok(out)
+}
+
+/// A PR's chain position, stack block, and commit messages, as checked by `spr verify`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrVerifyInfo {
+    pub number: u64,
+    /// GitHub state (`OPEN`, `CLOSED`, `MERGED`).
+    pub state: String,
+    pub base: String,
+    pub head: String,
+    pub body: String,
+    /// Commit messages, oldest first (at most the first 100 commits).
+    pub commit_messages: Vec<String>,
+}
+
+fn parse_pr_verify_node(number: u64, node: &serde_json::Value) -> Option<PrVerifyInfo> {
+    if node.is_null() {
+        return None;
+    }
+    Some(PrVerifyInfo {
+        number,
+        state: node["state"].as_str().unwrap_or("").to_string(),
+        base: node["baseRefName"].as_str().unwrap_or("").to_string(),
+        head: node["headRefName"].as_str().unwrap_or("").to_string(),
+        body: node["body"].as_str().unwrap_or("").to_string(),
+        commit_messages: node["commits"]["nodes"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|commit| commit["commit"]["message"].as_str().map(str::to_string))
+            .collect(),
+    })
+}
+
+/// Fetch verification data for `numbers`; PRs that do not exist are omitted.
+pub fn fetch_pr_verify_info(numbers: &[u64]) -> Result<HashMap<u64, PrVerifyInfo>> {
+    let mut out = HashMap::new();
+    for chunk in numbers.chunks(MAX_PR_BODIES_PER_QUERY) {
+        let chunk_out =
+            run_read_chunk_with_retry(chunk, &fetch_pr_verify_info_chunk, &|mut left, right| {
+                left.extend(right);
+                left
+            })?;
+        out.extend(chunk_out);
+    }
+    Ok(out)
+}
+
+fn fetch_pr_verify_info_chunk(numbers: &[u64]) -> Result<HashMap<u64, PrVerifyInfo>> {
+    let mut out = HashMap::new();
+    if numbers.is_empty() {
+        return Ok(out);
+    }
+    let (owner, name) = get_repo_owner_name()?;
+    let mut q =
+        String::from("query($owner:String!,$name:String!){ repository(owner:$owner,name:$name){ ");
+    for (i, n) in numbers.iter().enumerate() {
+        q.push_str(&format!(
+            "pr{}: pullRequest(number: {}) {{ state baseRefName headRefName body commits(first: 100) {{ nodes {{ commit {{ message }} }} }} }} ",
+            i, n
+        ));
+    }
+    q.push_str("} }");
+    let json = gh_ro(
+        [
+            "api",
+            "graphql",
+            "-f",
+            &format!("query={}", q),
+            "-F",
+            &format!("owner={}", owner),
+            "-F",
+            &format!("name={}", name),
+        ]
+        .as_slice(),
+    )?;
+    let v: serde_json::Value = serde_json::from_str(&json)?;
+    let repo = &v["data"]["repository"];
+    for (i, n) in numbers.iter().enumerate() {
+        if let Some(info) = parse_pr_verify_node(*n, &repo[format!("pr{}", i)]) {
+            out.insert(*n, info);
+        }
     }
     Ok(out)
 }
@@ -829,10 +1018,60 @@ pub fn fetch_pr_stage_info_graphql(numbers: &[u64]) -> Result<HashMap<u64, PrSta
                 },
             );
         }
+        remember_pr_node_ids(
+            &owner,
+            &name,
+            out.iter().map(|(number, info)| (*number, info.id.as_str())),
+        );
         Ok(out)
     }
 }
 
+/// Current GitHub state (`OPEN`, `MERGED`, or `CLOSED`) of each PR in `numbers`.
+///
+/// Used to re-check PRs right before mutating them, since a reviewer can merge or close one in
+/// the UI after the run read it. PRs GitHub cannot find are omitted.
+pub fn fetch_pr_states_graphql(numbers: &[u64]) -> Result<HashMap<u64, String>> {
+    if numbers.is_empty() {
+        return Ok(HashMap::new());
+    }
+    if crate::forge::is_gitlab() {
+        return crate::gitlab::fetch_mr_states(numbers);
+    }
+    let (owner, name) = get_repo_owner_name()?;
+    let mut out = HashMap::new();
+    for chunk in numbers.chunks(MAX_PR_BODIES_PER_QUERY) {
+        let mut q = String::from(
+            "query($owner:String!,$name:String!){ repository(owner:$owner,name:$name){ ",
+        );
+        for (i, n) in chunk.iter().enumerate() {
+            q.push_str(&format!("pr{}: pullRequest(number: {}) {{ state }} ", i, n));
+        }
+        q.push_str("} }");
+        let json = gh_ro(
+            [
+                "api",
+                "graphql",
+                "-f",
+                &format!("query={}", q),
+                "-F",
+                &format!("owner={}", owner),
+                "-F",
+                &format!("name={}", name),
+            ]
+            .as_slice(),
+        )?;
+        let v: serde_json::Value = serde_json::from_str(&json)?;
+        let repo = &v["data"]["repository"];
+        for (i, number) in chunk.iter().enumerate() {
+            if let Some(state) = repo[format!("pr{}", i)]["state"].as_str() {
+                out.insert(*number, state.to_string());
+            }
+        }
+    }
+    Ok(out)
+}
+
 const MAX_PR_STAGE_MUTATIONS_PER_REQUEST: usize = 50;
 
 fn mutate_pull_request_stage(
@@ -883,6 +1122,161 @@ pub fn mark_pull_requests_ready_for_review(
     )
 }
 
+/// One option of a Projects (v2) single-select field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectSingleSelectOption {
+    pub id: String,
+    pub name: String,
+}
+
+/// A Projects (v2) single-select field looked up by name on one project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectSingleSelectField {
+    pub id: String,
+    pub options: Vec<ProjectSingleSelectOption>,
+}
+
+/// A Projects (v2) item that tracks a pull request.
+///
+/// `status_field` is `None` when the project has no single-select field with the requested name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrProjectItem {
+    pub pr_number: u64,
+    pub item_id: String,
+    pub project_id: String,
+    pub project_title: String,
+    pub status_field: Option<ProjectSingleSelectField>,
+}
+
+/// A resolved single-select value change for one project item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectStatusUpdate {
+    pub pr_number: u64,
+    pub project_id: String,
+    pub item_id: String,
+    pub field_id: String,
+    pub option_id: String,
+}
+
+const MAX_PROJECT_ITEMS_PER_PR: usize = 20;
+const MAX_PROJECT_MUTATIONS_PER_REQUEST: usize = 50;
+
+fn parse_pr_project_items(pr_number: u64, node: &serde_json::Value) -> Vec<PrProjectItem> {
+    node["projectItems"]["nodes"]
+        .as_array()
+        .map(|nodes| {
+            nodes
+                .iter()
+                .filter_map(|item| {
+                    let project = &item["project"];
+                    let status_field =
+                        project["field"]["id"]
+                            .as_str()
+                            .map(|id| ProjectSingleSelectField {
+                                id: id.to_string(),
+                                options: project["field"]["options"]
+                                    .as_array()
+                                    .map(|options| {
+                                        options
+                                            .iter()
+                                            .filter_map(|option| {
+                                                Some(ProjectSingleSelectOption {
+                                                    id: option["id"].as_str()?.to_string(),
+                                                    name: option["name"].as_str()?.to_string(),
+                                                })
+                                            })
+                                            .collect()
+                                    })
+                                    .unwrap_or_default(),
+                            });
+                    Some(PrProjectItem {
+                        pr_number,
+                        item_id: item["id"].as_str()?.to_string(),
+                        project_id: project["id"].as_str()?.to_string(),
+                        project_title: project["title"].as_str().unwrap_or_default().to_string(),
+                        status_field,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Fetch the Projects (v2) items attached to each PR, with the named single-select field.
+///
+/// Requires a `gh` token with the `read:project` (or `project`) scope.
+pub fn fetch_pr_project_items(numbers: &[u64], field_name: &str) -> Result<Vec<PrProjectItem>> {
+    if numbers.is_empty() {
+        return Ok(Vec::new());
+    }
+    let (owner, name) = get_repo_owner_name()?;
+    let mut out = Vec::new();
+    for chunk in numbers.chunks(MAX_PR_BODIES_PER_QUERY) {
+        let mut q = String::from(
+            "query($owner:String!,$name:String!){ repository(owner:$owner,name:$name){ ",
+        );
+        for (i, n) in chunk.iter().enumerate() {
+            q.push_str(&format!(
+                "pr{}: pullRequest(number: {}) {{ projectItems(first: {}) {{ nodes {{ id project {{ id title field(name: \"{}\") {{ ... on ProjectV2SingleSelectField {{ id options {{ id name }} }} }} }} }} }} }} ",
+                i,
+                n,
+                MAX_PROJECT_ITEMS_PER_PR,
+                graphql_escape(field_name)
+            ));
+        }
+        q.push_str("} }");
+        let json = gh_ro(
+            [
+                "api",
+                "graphql",
+                "-f",
+                &format!("query={}", q),
+                "-F",
+                &format!("owner={}", owner),
+                "-F",
+                &format!("name={}", name),
+            ]
+            .as_slice(),
+        )?;
+        let v: serde_json::Value = serde_json::from_str(&json)?;
+        for (i, number) in chunk.iter().enumerate() {
+            out.extend(parse_pr_project_items(
+                *number,
+                &v["data"]["repository"][format!("pr{}", i)],
+            ));
+        }
+    }
+    Ok(out)
+}
+
+/// Apply single-select value changes to Projects (v2) items in bounded batches.
+///
+/// Requires a `gh` token with the `project` scope.
+pub fn update_project_item_statuses(
+    updates: &[ProjectStatusUpdate],
+    execution_mode: ExecutionMode,
+) -> Result<()> {
+    for chunk in updates.chunks(MAX_PROJECT_MUTATIONS_PER_REQUEST) {
+        let mut mutation = String::from("mutation {");
+        for (i, update) in chunk.iter().enumerate() {
+            mutation.push_str(&format!(
+                "m{}: updateProjectV2ItemFieldValue(input:{{projectId:\"{}\", itemId:\"{}\", fieldId:\"{}\", value:{{singleSelectOptionId:\"{}\"}}}}){{ clientMutationId }} ",
+                i,
+                graphql_escape(&update.project_id),
+                graphql_escape(&update.item_id),
+                graphql_escape(&update.field_id),
+                graphql_escape(&update.option_id)
+            ));
+        }
+        mutation.push('}');
+        gh_rw(
+            execution_mode,
+            ["api", "graphql", "-f", &format!("query={}", mutation)].as_slice(),
+        )?;
+    }
+    Ok(())
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum PrCiState {
@@ -905,6 +1299,42 @@ impl PrCiState {
             _ => Self::Unknown,
         }
     }
+
+    /// State of a check run from its `status` and, once completed, its `conclusion`.
+    fn from_check_run(status: &str, conclusion: Option<&str>) -> Self {
+        match (status, conclusion) {
+            ("COMPLETED", Some("SUCCESS" | "NEUTRAL" | "SKIPPED")) => Self::Success,
+            ("COMPLETED", Some(_)) => Self::Failure,
+            ("COMPLETED", None) => Self::Unknown,
+            _ => Self::Pending,
+        }
+    }
+
+    fn severity(self) -> u8 {
+        match self {
+            Self::Success => 0,
+            Self::Unknown => 1,
+            Self::Pending | Self::Expected => 2,
+            Self::Error | Self::Failure => 3,
+        }
+    }
+
+    /// The less healthy of two states.
+    fn worst(self, other: Self) -> Self {
+        if other.severity() > self.severity() {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+/// One commit status (as opposed to a check run) on a PR's head commit, such as a deployment or
+/// an external CI reporting through the statuses API.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PrStatusContext {
+    pub context: String,
+    pub state: PrCiState,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
@@ -929,11 +1359,117 @@ impl PrReviewDecision {
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct PrCiReviewStatus {
+    /// Combined state of check runs and commit statuses.
     pub ci_state: PrCiState,
+    /// State of the check runs alone.
+    pub checks_state: PrCiState,
+    /// Commit statuses on the head commit; repositories can require these for merging too.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub status_contexts: Vec<PrStatusContext>,
     pub review_decision: PrReviewDecision,
+    pub is_draft: bool,
+    /// RFC 3339 time since which a ready PR has been waiting for review: its last push or
+    /// ready-for-review event, else its creation. `None` for drafts and PRs not awaiting review.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub awaiting_review_since: Option<String>,
+    /// Users (`login`) and teams (`org/team`) whose review is still requested.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub requested_reviewers: Vec<String>,
+    /// Head commit these checks and reviews belong to; landing pins its merge to it.
+    #[serde(skip)]
+    pub head_oid: String,
+}
+
+impl PrCiReviewStatus {
+    /// How long the PR has been awaiting review as of `now`.
+    pub fn awaiting_review_for(&self, now: OffsetDateTime) -> Option<time::Duration> {
+        let since = OffsetDateTime::parse(self.awaiting_review_since.as_deref()?, &Rfc3339).ok()?;
+        Some((now - since).max(time::Duration::ZERO))
+    }
+}
+
+/// Start of the current review wait for one `pullRequest` node of the CI/review query.
+fn parse_awaiting_review_since(pr: &serde_json::Value) -> Option<String> {
+    if pr["isDraft"].as_bool().unwrap_or(false) {
+        return None;
+    }
+    let event = pr["timelineItems"]["nodes"]
+        .as_array()
+        .and_then(|nodes| nodes.last())
+        .and_then(|node| {
+            node["createdAt"]
+                .as_str()
+                .or_else(|| node["commit"]["committedDate"].as_str())
+        });
+    event
+        .or_else(|| pr["createdAt"].as_str())
+        .map(str::to_string)
+}
+
+/// Check-run state and commit statuses of one head `commit` node of the CI/review query.
+///
+/// Without commit statuses the rollup is the check-run state. With them, the check runs listed
+/// in the rollup are combined instead, falling back to the rollup when none are listed. A commit
+/// with neither counts as passing (no CI configured).
+fn parse_commit_ci(commit: &serde_json::Value) -> (PrCiState, Vec<PrStatusContext>) {
+    let rollup = &commit["statusCheckRollup"];
+    let rollup_state = rollup["state"]
+        .as_str()
+        .map_or(PrCiState::Success, PrCiState::from_graphql_state);
+    let status_contexts: Vec<PrStatusContext> = commit["status"]["contexts"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|node| {
+            Some(PrStatusContext {
+                context: node["context"].as_str()?.to_string(),
+                state: PrCiState::from_graphql_state(node["state"].as_str().unwrap_or("")),
+            })
+        })
+        .collect();
+    if status_contexts.is_empty() {
+        return (rollup_state, status_contexts);
+    }
+    let check_runs: Vec<PrCiState> = rollup["contexts"]["nodes"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|node| {
+            Some(PrCiState::from_check_run(
+                node["status"].as_str()?,
+                node["conclusion"].as_str(),
+            ))
+        })
+        .collect();
+    let checks_state = if check_runs.is_empty() {
+        rollup_state
+    } else {
+        check_runs
+            .into_iter()
+            .fold(PrCiState::Success, PrCiState::worst)
+    };
+    (checks_state, status_contexts)
+}
+
+fn parse_requested_reviewers(pr: &serde_json::Value) -> Vec<String> {
+    pr["reviewRequests"]["nodes"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|node| {
+            let reviewer = &node["requestedReviewer"];
+            reviewer["login"]
+                .as_str()
+                .or_else(|| reviewer["combinedSlug"].as_str())
+                .map(str::to_string)
+        })
+        .collect()
 }
 
 pub fn fetch_pr_ci_review_status(numbers: &[u64]) -> Result<HashMap<u64, PrCiReviewStatus>> {
+    if crate::forge::is_gitlab() {
+        return crate::gitlab::fetch_mr_ci_review_status(numbers);
+    }
     let mut out = HashMap::new();
     for chunk in numbers.chunks(MAX_PR_STATUS_PER_QUERY) {
         let chunk_out = run_read_chunk_with_retry(
@@ -959,7 +1495,7 @@ fn fetch_pr_ci_review_status_chunk(numbers: &[u64]) -> Result<HashMap<u64, PrCiR
         String::from("query($owner:String!,$name:String!){ repository(owner:$owner,name:$name){ ");
     for (i, n) in numbers.iter().enumerate() {
         q.push_str(&format!(
-            "pr{}: pullRequest(number: {}) {{ reviewDecision isDraft reviewRequests(first:1){{ totalCount }} reviews(last:50, states:[APPROVED,CHANGES_REQUESTED]){{ nodes {{ state }} }} commits(last:1) {{ nodes {{ commit {{ statusCheckRollup {{ state }} }} }} }} }} ",
+            "pr{}: pullRequest(number: {}) {{ headRefOid reviewDecision isDraft createdAt reviewRequests(first:20){{ nodes {{ requestedReviewer {{ ... on User {{ login }} ... on Team {{ combinedSlug }} }} }} }} timelineItems(last:1, itemTypes:[READY_FOR_REVIEW_EVENT, HEAD_REF_FORCE_PUSHED_EVENT, PULL_REQUEST_COMMIT]){{ nodes {{ ... on ReadyForReviewEvent {{ createdAt }} ... on HeadRefForcePushedEvent {{ createdAt }} ... on PullRequestCommit {{ commit {{ committedDate }} }} }} }} reviews(last:50, states:[APPROVED,CHANGES_REQUESTED]){{ nodes {{ state }} }} commits(last:1) {{ nodes {{ commit {{ statusCheckRollup {{ state contexts(first:100) {{ nodes {{ ... on CheckRun {{ status conclusion }} }} }} }} status {{ contexts {{ context state }} }} }} }} }} }} ",
             i, n
         ));
     }
@@ -985,15 +1521,12 @@ fn fetch_pr_ci_review_status_chunk(numbers: &[u64]) -> Result<HashMap<u64, PrCiR
             .as_str()
             .map(PrReviewDecision::from_graphql_state)
             .unwrap_or(PrReviewDecision::Unknown);
-        // Default when missing (no CI configured) → treat as passing
-        let mut ci = PrCiState::Success;
-        if let Some(nodes) = repo[&key]["commits"]["nodes"].as_array() {
-            if let Some(node) = nodes.first() {
-                if let Some(state) = node["commit"]["statusCheckRollup"]["state"].as_str() {
-                    ci = PrCiState::from_graphql_state(state);
-                }
-            }
-        }
+        let (checks_state, status_contexts) =
+            parse_commit_ci(&repo[&key]["commits"]["nodes"][0]["commit"]);
+        let ci = status_contexts
+            .iter()
+            .map(|status| status.state)
+            .fold(checks_state, PrCiState::worst);
         if review == PrReviewDecision::Unknown {
             // Fallback heuristic when reviewDecision is not available (e.g., no protected branch rules)
             let mut has_changes_requested = false;
@@ -1016,11 +1549,25 @@ fn fetch_pr_ci_review_status_chunk(numbers: &[u64]) -> Result<HashMap<u64, PrCiR
             }
         }
 
+        let awaiting_review_since = if review == PrReviewDecision::ReviewRequired {
+            parse_awaiting_review_since(&repo[&key])
+        } else {
+            None
+        };
         out.insert(
             *n,
             PrCiReviewStatus {
                 ci_state: ci,
+                checks_state,
+                status_contexts,
                 review_decision: review,
+                is_draft: repo[&key]["isDraft"].as_bool().unwrap_or(false),
+                awaiting_review_since,
+                requested_reviewers: parse_requested_reviewers(&repo[&key]),
+                head_oid: repo[&key]["headRefOid"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
             },
         );
     }
@@ -1081,18 +1628,466 @@ pub fn fetch_merged_pr_merge_commit_oids(numbers: &[u64]) -> Result<HashMap<u64,
     Ok(out)
 }
 
-pub fn get_repo_owner_name() -> Result<(String, String)> {
-    let url = git_ro(["config", "--get", "remote.origin.url"].as_slice())?
-        .trim()
-        .to_string();
-    if let Some(idx) = url.find("://") {
-        let rest = &url[idx + 3..];
-        let parts: Vec<&str> = rest.split('/').collect();
-        if parts.len() >= 3 {
-            let owner = parts[1].to_string();
-            let mut name = parts[2].to_string();
-            if let Some(s) = name.strip_suffix(".git") {
-                name = s.to_string();
+/// An existing PR's identity and commits, as needed by `spr import-pr`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrImportInfo {
+    pub number: u64,
+    pub title: String,
+    /// GitHub state (`OPEN`, `CLOSED`, `MERGED`).
+    pub state: String,
+    pub head: String,
+    /// Whether the head branch lives in a fork rather than this repository.
+    pub is_cross_repository: bool,
+    /// PR commits, oldest first.
+    pub commit_oids: Vec<String>,
+}
+
+fn parse_pr_import_info(json: &str) -> Result<PrImportInfo> {
+    #[derive(Deserialize)]
+    struct RawCommit {
+        oid: String,
+    }
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Raw {
+        number: u64,
+        title: String,
+        state: String,
+        head_ref_name: String,
+        #[serde(default)]
+        is_cross_repository: bool,
+        commits: Vec<RawCommit>,
+    }
+    let raw: Raw = serde_json::from_str(json)?;
+    Ok(PrImportInfo {
+        number: raw.number,
+        title: raw.title,
+        state: raw.state,
+        head: raw.head_ref_name,
+        is_cross_repository: raw.is_cross_repository,
+        commit_oids: raw.commits.into_iter().map(|commit| commit.oid).collect(),
+    })
+}
+
+/// Fetch head, state, and commit list for PR `number`.
+pub fn fetch_pr_import_info(number: u64) -> Result<PrImportInfo> {
+    let json = gh_ro(
+        [
+            "pr",
+            "view",
+            &number.to_string(),
+            "--json",
+            "number,title,state,headRefName,isCrossRepository,commits",
+        ]
+        .as_slice(),
+    )?;
+    parse_pr_import_info(&json)
+}
+
+/// Review-relevant timeline events used by `spr stats`, oldest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrTimelineEvent {
+    /// A submitted (non-pending) review.
+    Review,
+    /// A force push to the PR head, which is how `spr update` publishes new revisions.
+    ForcePush,
+}
+
+/// PR lifecycle and timeline data used by `spr stats`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrHistoryInfo {
+    pub number: u64,
+    pub title: String,
+    pub head: String,
+    pub created_at: String,
+    pub merged_at: Option<String>,
+    pub events: Vec<PrTimelineEvent>,
+}
+
+impl PrHistoryInfo {
+    /// Seconds from PR creation to merge, or `None` while unmerged.
+    ///
+    /// `spr` pushes a group's branch and opens its PR in the same run, so creation time stands in
+    /// for the first push.
+    pub fn time_to_merge_secs(&self) -> Result<Option<i64>> {
+        let Some(merged_at) = &self.merged_at else {
+            return Ok(None);
+        };
+        let created = parse_github_datetime_rfc3339(&self.created_at, "createdAt")?;
+        let merged = parse_github_datetime_rfc3339(merged_at, "mergedAt")?;
+        Ok(Some((merged - created).whole_seconds()))
+    }
+}
+
+const PR_HISTORY_TIMELINE_LIMIT: usize = 100;
+
+fn parse_pr_history_node(node: &serde_json::Value) -> Option<PrHistoryInfo> {
+    let events = node["timelineItems"]["nodes"]
+        .as_array()
+        .map(|nodes| {
+            nodes
+                .iter()
+                .filter_map(|item| match item["__typename"].as_str() {
+                    Some("HeadRefForcePushedEvent") => Some(PrTimelineEvent::ForcePush),
+                    Some("PullRequestReview") if item["state"].as_str() != Some("PENDING") => {
+                        Some(PrTimelineEvent::Review)
+                    }
+                    _ => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Some(PrHistoryInfo {
+        number: node["number"].as_u64()?,
+        title: node["title"].as_str().unwrap_or_default().to_string(),
+        head: node["headRefName"].as_str().unwrap_or_default().to_string(),
+        created_at: node["createdAt"].as_str()?.to_string(),
+        merged_at: node["mergedAt"].as_str().map(str::to_string),
+        events,
+    })
+}
+
+/// Fetch creation/merge times plus review and force-push timeline events for `numbers`.
+///
+/// Only the first 100 such timeline events per PR are considered. PRs GitHub cannot find are
+/// omitted.
+pub fn fetch_pr_history(numbers: &[u64]) -> Result<Vec<PrHistoryInfo>> {
+    let mut out = Vec::new();
+    for chunk in numbers.chunks(MAX_PR_STATUS_PER_QUERY) {
+        let chunk_out =
+            run_read_chunk_with_retry(chunk, &fetch_pr_history_chunk, &|mut left, right| {
+                left.extend(right);
+                left
+            })?;
+        out.extend(chunk_out);
+    }
+    Ok(out)
+}
+
+fn fetch_pr_history_chunk(numbers: &[u64]) -> Result<Vec<PrHistoryInfo>> {
+    if numbers.is_empty() {
+        return Ok(Vec::new());
+    }
+    let (owner, name) = get_repo_owner_name()?;
+    let mut query =
+        String::from("query($owner:String!,$name:String!){ repository(owner:$owner,name:$name){ ");
+    for (i, number) in numbers.iter().enumerate() {
+        query.push_str(&format!(
+            "pr{}: pullRequest(number: {}) {{ number title headRefName createdAt mergedAt timelineItems(first:{}, itemTypes:[PULL_REQUEST_REVIEW, HEAD_REF_FORCE_PUSHED_EVENT]) {{ nodes {{ __typename ... on PullRequestReview {{ state }} }} }} }} ",
+            i, number, PR_HISTORY_TIMELINE_LIMIT
+        ));
+    }
+    query.push_str("} }");
+    let json = gh_ro(
+        [
+            "api",
+            "graphql",
+            "-f",
+            &format!("query={}", query),
+            "-F",
+            &format!("owner={}", owner),
+            "-F",
+            &format!("name={}", name),
+        ]
+        .as_slice(),
+    )?;
+    let v: serde_json::Value = serde_json::from_str(&json)?;
+    let repo = &v["data"]["repository"];
+    Ok((0..numbers.len())
+        .filter_map(|i| parse_pr_history_node(&repo[&format!("pr{}", i)]))
+        .collect())
+}
+
+/// List the most recently merged PRs (newest first) whose head branch starts with `prefix`.
+///
+/// `limit` bounds how many merged PRs are scanned before filtering by prefix.
+pub fn list_merged_pr_numbers_with_prefix(prefix: &str, limit: usize) -> Result<Vec<u64>> {
+    let json = gh_ro(
+        [
+            "pr",
+            "list",
+            "--state",
+            "merged",
+            "--limit",
+            &limit.to_string(),
+            "--json",
+            "number,headRefName",
+        ]
+        .as_slice(),
+    )?;
+    #[derive(Deserialize)]
+    struct Raw {
+        number: u64,
+        #[serde(rename = "headRefName")]
+        head_ref_name: String,
+    }
+    let raws: Vec<Raw> = serde_json::from_str(&json)?;
+    Ok(raws
+        .into_iter()
+        .filter(|raw| raw.head_ref_name.starts_with(prefix))
+        .map(|raw| raw.number)
+        .collect())
+}
+
+/// Merge methods the repository settings allow for pull requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RepoMergeMethods {
+    pub merge_commit: bool,
+    pub squash: bool,
+    pub rebase: bool,
+}
+
+/// Read which merge methods the repository allows, so landing can pick one GitHub accepts.
+pub fn fetch_repo_merge_methods() -> Result<RepoMergeMethods> {
+    let (owner, name) = get_repo_owner_name()?;
+    let json = gh_ro(
+        [
+            "api",
+            "graphql",
+            "-f",
+            "query=query($owner:String!,$name:String!){ repository(owner:$owner,name:$name){ mergeCommitAllowed squashMergeAllowed rebaseMergeAllowed } }",
+            "-F",
+            &format!("owner={}", owner),
+            "-F",
+            &format!("name={}", name),
+        ]
+        .as_slice(),
+    )?;
+    let v: serde_json::Value = serde_json::from_str(&json)?;
+    let repo = &v["data"]["repository"];
+    let allowed = |field: &str| {
+        repo[field]
+            .as_bool()
+            .ok_or_else(|| anyhow!("GitHub repository result missing {}", field))
+    };
+    Ok(RepoMergeMethods {
+        merge_commit: allowed("mergeCommitAllowed")?,
+        squash: allowed("squashMergeAllowed")?,
+        rebase: allowed("rebaseMergeAllowed")?,
+    })
+}
+
+/// GraphQL node IDs of the repository labels among `names`, keyed by name.
+///
+/// Labels that do not exist in the repository are absent from the result.
+pub fn fetch_label_ids(names: &[String]) -> Result<HashMap<String, String>> {
+    if names.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let (owner, name) = get_repo_owner_name()?;
+    let mut q = String::from("query($owner:String!,$name:String!");
+    for i in 0..names.len() {
+        q.push_str(&format!(",$l{i}:String!"));
+    }
+    q.push_str("){ repository(owner:$owner,name:$name){ ");
+    for i in 0..names.len() {
+        q.push_str(&format!("l{i}: label(name:$l{i}){{ id }} "));
+    }
+    q.push_str("} }");
+    let mut args = vec![
+        "api".to_string(),
+        "graphql".to_string(),
+        "-f".to_string(),
+        format!("query={q}"),
+        "-F".to_string(),
+        format!("owner={owner}"),
+        "-F".to_string(),
+        format!("name={name}"),
+    ];
+    for (i, label) in names.iter().enumerate() {
+        args.push("-f".to_string());
+        args.push(format!("l{i}={label}"));
+    }
+    let arg_refs = args.iter().map(String::as_str).collect::<Vec<_>>();
+    let v: serde_json::Value = serde_json::from_str(&gh_ro(&arg_refs)?)?;
+    let repo = &v["data"]["repository"];
+    Ok(names
+        .iter()
+        .enumerate()
+        .filter_map(|(i, label)| {
+            repo[&format!("l{i}")]["id"]
+                .as_str()
+                .map(|id| (label.clone(), id.to_string()))
+        })
+        .collect())
+}
+
+/// GraphQL node IDs of the users with the given `logins`, keyed by login.
+///
+/// GitHub rejects the whole query when a login does not resolve to a user.
+pub fn fetch_user_ids(logins: &[String]) -> Result<HashMap<String, String>> {
+    if logins.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let vars = (0..logins.len())
+        .map(|i| format!("$u{i}:String!"))
+        .collect::<Vec<_>>()
+        .join(",");
+    let mut q = format!("query({vars}){{ ");
+    for i in 0..logins.len() {
+        q.push_str(&format!("u{i}: user(login:$u{i}){{ id }} "));
+    }
+    q.push('}');
+    let mut args = vec![
+        "api".to_string(),
+        "graphql".to_string(),
+        "-f".to_string(),
+        format!("query={q}"),
+    ];
+    for (i, login) in logins.iter().enumerate() {
+        args.push("-f".to_string());
+        args.push(format!("u{i}={login}"));
+    }
+    let arg_refs = args.iter().map(String::as_str).collect::<Vec<_>>();
+    let v: serde_json::Value = serde_json::from_str(&gh_ro(&arg_refs)?)?;
+    logins
+        .iter()
+        .enumerate()
+        .map(|(i, login)| {
+            v["data"][&format!("u{i}")]["id"]
+                .as_str()
+                .map(|id| (login.clone(), id.to_string()))
+                .ok_or_else(|| anyhow!("GitHub user `{}` was not found", login))
+        })
+        .collect()
+}
+
+/// GraphQL node ID of the open milestone titled exactly `title`, if there is one.
+pub fn fetch_open_milestone_id(title: &str) -> Result<Option<String>> {
+    let (owner, name) = get_repo_owner_name()?;
+    let json = gh_ro(
+        [
+            "api",
+            "graphql",
+            "-f",
+            "query=query($owner:String!,$name:String!,$title:String!){ repository(owner:$owner,name:$name){ milestones(first:100, states:[OPEN], query:$title){ nodes { id title } } } }",
+            "-F",
+            &format!("owner={}", owner),
+            "-F",
+            &format!("name={}", name),
+            "-f",
+            &format!("title={}", title),
+        ]
+        .as_slice(),
+    )?;
+    let v: serde_json::Value = serde_json::from_str(&json)?;
+    Ok(v["data"]["repository"]["milestones"]["nodes"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|node| node["title"].as_str() == Some(title))
+        .and_then(|node| node["id"].as_str())
+        .map(str::to_string))
+}
+
+/// GraphQL node ID of the repository owner's project (v2) titled exactly `title`, if there is one.
+pub fn fetch_owner_project_id(title: &str) -> Result<Option<String>> {
+    let (owner, name) = get_repo_owner_name()?;
+    let json = gh_ro(
+        [
+            "api",
+            "graphql",
+            "-f",
+            "query=query($owner:String!,$name:String!,$title:String!){ repository(owner:$owner,name:$name){ owner { ... on ProjectV2Owner { projectsV2(first:100, query:$title){ nodes { id title } } } } } }",
+            "-F",
+            &format!("owner={}", owner),
+            "-F",
+            &format!("name={}", name),
+            "-f",
+            &format!("title={}", title),
+        ]
+        .as_slice(),
+    )?;
+    let v: serde_json::Value = serde_json::from_str(&json)?;
+    Ok(v["data"]["repository"]["owner"]["projectsV2"]["nodes"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|node| node["title"].as_str() == Some(title))
+        .and_then(|node| node["id"].as_str())
+        .map(str::to_string))
+}
+
+/// Node ID, milestone, and project (v2) boards of one PR.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrTracking {
+    pub id: String,
+    pub milestone_id: Option<String>,
+    pub project_ids: Vec<String>,
+}
+
+/// Node ID, milestone, and project boards of each PR of `numbers`, in one query.
+pub fn fetch_pr_tracking(numbers: &[u64]) -> Result<HashMap<u64, PrTracking>> {
+    let mut out = HashMap::new();
+    if numbers.is_empty() {
+        return Ok(out);
+    }
+    let (owner, name) = get_repo_owner_name()?;
+    let mut q =
+        String::from("query($owner:String!,$name:String!){ repository(owner:$owner,name:$name){ ");
+    for (i, n) in numbers.iter().enumerate() {
+        q.push_str(&format!(
+            "pr{}: pullRequest(number: {}) {{ id milestone {{ id }} projectItems(first:50){{ nodes {{ project {{ id }} }} }} }} ",
+            i, n
+        ));
+    }
+    q.push_str("} }");
+    let json = gh_ro(
+        [
+            "api",
+            "graphql",
+            "-f",
+            &format!("query={}", q),
+            "-F",
+            &format!("owner={}", owner),
+            "-F",
+            &format!("name={}", name),
+        ]
+        .as_slice(),
+    )?;
+    let v: serde_json::Value = serde_json::from_str(&json)?;
+    let repo = &v["data"]["repository"];
+    for (i, n) in numbers.iter().enumerate() {
+        let pr = &repo[&format!("pr{}", i)];
+        let Some(id) = pr["id"].as_str() else {
+            continue;
+        };
+        out.insert(
+            *n,
+            PrTracking {
+                id: id.to_string(),
+                milestone_id: pr["milestone"]["id"].as_str().map(str::to_string),
+                project_ids: pr["projectItems"]["nodes"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|node| node["project"]["id"].as_str())
+                    .map(str::to_string)
+                    .collect(),
+            },
+        );
+    }
+    Ok(out)
+}
+
+/// Owner and name of the repository that hosts the pull requests (the base remote's).
+pub fn get_repo_owner_name() -> Result<(String, String)> {
+    remote_owner_name(&crate::git::base_remote())
+}
+
+/// Owner and name of the GitHub repository behind git remote `remote`.
+pub fn remote_owner_name(remote: &str) -> Result<(String, String)> {
+    let url = git_ro(["config", "--get", &format!("remote.{remote}.url")].as_slice())?
+        .trim()
+        .to_string();
+    if let Some(idx) = url.find("://") {
+        let rest = &url[idx + 3..];
+        let parts: Vec<&str> = rest.split('/').collect();
+        if parts.len() >= 3 {
+            let owner = parts[1].to_string();
+            let mut name = parts[2].to_string();
+            if let Some(s) = name.strip_suffix(".git") {
+                name = s.to_string();
             }
             return Ok((owner, name));
         }
@@ -1109,7 +2104,7 @@ pub fn get_repo_owner_name() -> Result<(String, String)> {
             return Ok((owner, name));
         }
     }
-    anyhow::bail!("Unable to parse remote.origin.url: {}", url)
+    anyhow::bail!("Unable to parse remote.{}.url: {}", remote, url)
 }
 
 pub fn resolve_pr_url_head_ref(pr_url: &str) -> Result<String> {
@@ -1136,6 +2131,78 @@ pub fn graphql_escape(s: &str) -> String {
     out
 }
 
+/// A batched GraphQL mutation whose inputs travel as typed variables.
+///
+/// Fields reference variables declared with [`GraphqlMutation::var`], so PR bodies, commit
+/// messages, and branch names are sent verbatim as `-f` fields instead of being escaped into the
+/// query text.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GraphqlMutation {
+    declarations: Vec<String>,
+    values: Vec<String>,
+    fields: Vec<String>,
+}
+
+impl GraphqlMutation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declare a variable of GraphQL type `type_name` (e.g. `ID!`) holding `value`.
+    ///
+    /// Returns the `$name` reference to use in a field.
+    pub fn var(&mut self, type_name: &str, value: &str) -> String {
+        let name = format!("v{}", self.values.len());
+        self.declarations.push(format!("${name}:{type_name}"));
+        self.values.push(value.to_string());
+        format!("${name}")
+    }
+
+    /// Add one aliased mutation field, e.g. `m0: closePullRequest(...){ clientMutationId }`.
+    pub fn field(&mut self, field: String) {
+        self.fields.push(field);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    pub fn query(&self) -> String {
+        let declarations = if self.declarations.is_empty() {
+            String::new()
+        } else {
+            format!("({})", self.declarations.join(","))
+        };
+        format!("mutation{} {{ {} }}", declarations, self.fields.join(" "))
+    }
+
+    /// `gh api graphql` arguments: the query, then one raw field per variable.
+    pub fn gh_args(&self) -> Vec<String> {
+        let mut args = vec![
+            "api".to_string(),
+            "graphql".to_string(),
+            "-f".to_string(),
+            format!("query={}", self.query()),
+        ];
+        for (i, value) in self.values.iter().enumerate() {
+            args.push("-f".to_string());
+            args.push(format!("v{i}={value}"));
+        }
+        args
+    }
+
+    /// Size of the request arguments, used to keep batches below GitHub's request limits.
+    pub fn request_len(&self) -> usize {
+        self.gh_args().iter().map(String::len).sum()
+    }
+
+    pub fn run(&self, execution_mode: ExecutionMode) -> Result<String> {
+        let args = self.gh_args();
+        let arg_refs = args.iter().map(String::as_str).collect::<Vec<_>>();
+        gh_rw(execution_mode, &arg_refs)
+    }
+}
+
 /// Fetch open PRs for a specific set of head branches.
 ///
 /// Matching requires an exact `headRefName` for reuse and treats case-only variants as conflicts
@@ -1152,12 +2219,19 @@ pub fn graphql_escape(s: &str) -> String {
 /// Callers that skip the search probe can silently reuse `Foo` for local branch `foo`, which is
 /// exactly the ambiguity this path is meant to reject.
 pub fn list_open_prs_for_heads(heads: &[String]) -> Result<Vec<PrInfo>> {
+    if crate::forge::is_gitlab() {
+        return crate::gitlab::list_open_mrs_for_heads(heads);
+    }
     let mut out: Vec<PrInfo> = Vec::new();
     if heads.is_empty() {
         return Ok(out);
     }
-    let exact_matches_by_head = list_exact_prs_for_heads(heads, &["OPEN"], EXACT_HEAD_QUERY_LIMIT)?;
-    let conflict_matches_by_head = list_open_conflicting_prs_for_heads_search(heads)?;
+    let (exact_matches_by_head, conflict_matches_by_head) = crate::concurrency::join(
+        || list_exact_prs_for_heads(heads, &["OPEN"], EXACT_HEAD_QUERY_LIMIT),
+        || list_open_conflicting_prs_for_heads_search(heads),
+    );
+    let (exact_matches_by_head, conflict_matches_by_head) =
+        (exact_matches_by_head?, conflict_matches_by_head?);
     for head in heads {
         if let Some(pr) =
             select_resolved_open_pr_match(head, &exact_matches_by_head, &conflict_matches_by_head)?
@@ -1231,6 +2305,9 @@ pub fn list_recent_terminal_prs_for_heads(
     heads: &[String],
     closed_since: OffsetDateTime,
 ) -> Result<Vec<TerminalPrInfo>> {
+    if crate::forge::is_gitlab() {
+        return crate::gitlab::list_recent_terminal_mrs_for_heads(heads, closed_since);
+    }
     let mut out = Vec::new();
     for chunk in heads.chunks(MAX_TERMINAL_HEADS_PER_QUERY) {
         let mut chunk_out = run_read_chunk_with_retry(
@@ -1315,6 +2392,9 @@ fn list_recent_terminal_prs_for_heads_chunk(
 ///
 /// Returns an error when `gh pr list` fails or when the JSON response cannot be parsed.
 pub fn list_open_or_merged_prs_for_heads(heads: &[String]) -> Result<Vec<PrInfoWithState>> {
+    if crate::forge::is_gitlab() {
+        return crate::gitlab::list_open_or_merged_mrs_for_heads(heads);
+    }
     let mut out: Vec<PrInfoWithState> = Vec::new();
     if heads.is_empty() {
         return Ok(out);
@@ -1407,32 +2487,142 @@ pub fn list_open_pr_heads() -> Result<HashSet<String>> {
             "--limit",
             "200",
             "--json",
-            "headRefName",
+            "headRefName",
+        ]
+        .as_slice(),
+    )?;
+    #[derive(Deserialize)]
+    struct Raw {
+        #[serde(rename = "headRefName")]
+        head_ref_name: String,
+    }
+    let raws: Vec<Raw> = serde_json::from_str(&json)?;
+    let mut set = HashSet::new();
+    for r in raws {
+        set.insert(r.head_ref_name);
+    }
+    Ok(set)
+}
+
+/// List open PR numbers keyed by exact head ref name.
+///
+/// Like [`list_open_pr_heads`], this is bounded by the same 200-PR page and is intended for
+/// maintenance sweeps rather than exact per-branch lookups.
+pub fn list_open_pr_numbers_by_head() -> Result<HashMap<String, u64>> {
+    let json = gh_ro(
+        [
+            "pr",
+            "list",
+            "--state",
+            "open",
+            "--limit",
+            "200",
+            "--json",
+            "number,headRefName",
         ]
         .as_slice(),
     )?;
     #[derive(Deserialize)]
     struct Raw {
+        number: u64,
         #[serde(rename = "headRefName")]
         head_ref_name: String,
     }
     let raws: Vec<Raw> = serde_json::from_str(&json)?;
-    let mut set = HashSet::new();
-    for r in raws {
-        set.insert(r.head_ref_name);
-    }
-    Ok(set)
+    Ok(raws
+        .into_iter()
+        .map(|raw| (raw.head_ref_name, raw.number))
+        .collect())
+}
+
+/// An open PR's head, base, and the content needed to open it again under another head.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct OpenPrSnapshot {
+    pub number: u64,
+    pub title: String,
+    pub body: String,
+    #[serde(rename = "headRefName")]
+    pub head: String,
+    #[serde(rename = "baseRefName")]
+    pub base: String,
+    #[serde(rename = "isDraft")]
+    pub is_draft: bool,
+}
+
+/// List open PRs with their title, body, head, base, and draft state.
+///
+/// Bounded by the same 200-PR page as [`list_open_pr_heads`].
+pub fn list_open_pr_snapshots() -> Result<Vec<OpenPrSnapshot>> {
+    let json = gh_ro(
+        [
+            "pr",
+            "list",
+            "--state",
+            "open",
+            "--limit",
+            "200",
+            "--json",
+            "number,title,body,headRefName,baseRefName,isDraft",
+        ]
+        .as_slice(),
+    )?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+/// One PR conversation comment, as `gh pr view --json comments` reports it.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct PrComment {
+    pub body: String,
+    /// `OWNER`, `MEMBER`, `COLLABORATOR`, `CONTRIBUTOR`, `NONE`, ...
+    #[serde(rename = "authorAssociation", default)]
+    pub author_association: String,
+    #[serde(rename = "createdAt", default)]
+    pub created_at: String,
+}
+
+#[derive(Deserialize)]
+struct PrLabelName {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct PrCommentsAndLabels {
+    #[serde(default)]
+    comments: Vec<PrComment>,
+    #[serde(default)]
+    labels: Vec<PrLabelName>,
+}
+
+/// Conversation comments (oldest first) and label names of PR `number`.
+pub fn fetch_pr_comments_and_labels(number: u64) -> Result<(Vec<PrComment>, Vec<String>)> {
+    let json = gh_ro(
+        [
+            "pr",
+            "view",
+            &number.to_string(),
+            "--json",
+            "comments,labels",
+        ]
+        .as_slice(),
+    )?;
+    let parsed: PrCommentsAndLabels = serde_json::from_str(&json)?;
+    Ok((
+        parsed.comments,
+        parsed.labels.into_iter().map(|label| label.name).collect(),
+    ))
 }
 
 /// Creates a new pull request for the given branch and parent if one does not already exist,
 /// and returns the PR number. If a PR for the branch already exists (as tracked in `prs_by_head`),
 /// returns its number without making any changes. The function updates the `prs_by_head` map as needed.
+/// `draft` only affects newly created PRs; existing PRs keep their current stage.
 /// In [`ExecutionMode::DryRun`], no actual changes are made on GitHub.
 pub fn upsert_pr_cached(
     branch: &str,
     parent: &str,
     title: &str,
     body: &str,
+    draft: bool,
     execution_mode: ExecutionMode,
     prs_by_head: &mut HashMap<CanonicalBranchConflictKey, u64>,
 ) -> Result<u64> {
@@ -1441,29 +2631,34 @@ pub fn upsert_pr_cached(
         // Defer edits to the final pass
         return Ok(num);
     }
+    if crate::forge::is_gitlab() {
+        let num = crate::gitlab::create_mr(branch, parent, title, body, draft, execution_mode)?;
+        prs_by_head.insert(branch_key, num);
+        return Ok(num);
+    }
     // Create PR and retrieve number in a single API call
     let (owner, name) = get_repo_owner_name()?;
     let path = format!("repos/{}/{}/pulls", owner, name);
-    let created_number = gh_rw(
-        execution_mode,
-        [
-            "api",
-            &path,
-            "-X",
-            "POST",
-            "-f",
-            &format!("head={}", branch),
-            "-f",
-            &format!("base={}", parent),
-            "-f",
-            &format!("title={}", title),
-            "-f",
-            &format!("body={}", body),
-            "--jq",
-            ".number",
-        ]
-        .as_slice(),
-    )?;
+    // With the base on another remote (a fork setup), the head branch lives in the push remote's
+    // repository and GitHub needs it qualified as `owner:branch`.
+    let head_arg = if crate::git::base_remote() == crate::git::remote() {
+        format!("head={}", branch)
+    } else {
+        let (head_owner, _) = remote_owner_name(&crate::git::remote())?;
+        format!("head={}:{}", head_owner, branch)
+    };
+    let base_arg = format!("base={}", parent);
+    let title_arg = format!("title={}", title);
+    let body_arg = format!("body={}", body);
+    let mut args = vec![
+        "api", &path, "-X", "POST", "-f", &head_arg, "-f", &base_arg, "-f", &title_arg, "-f",
+        &body_arg,
+    ];
+    if draft {
+        args.extend(["-F", "draft=true"]);
+    }
+    args.extend(["--jq", ".number"]);
+    let created_number = gh_rw(execution_mode, &args)?;
     let mut num: u64 = created_number.trim().parse().unwrap_or(0);
     if num == 0 && execution_mode == ExecutionMode::Apply {
         if let Some(existing) = get_resolved_open_pr_match(branch)? {
@@ -1477,6 +2672,178 @@ pub fn upsert_pr_cached(
     Ok(num)
 }
 
+/// Create an issue and return its number; `None` in dry-run, where nothing is created.
+pub fn create_issue(title: &str, body: &str, execution_mode: ExecutionMode) -> Result<Option<u64>> {
+    let (owner, name) = get_repo_owner_name()?;
+    let path = format!("repos/{}/{}/issues", owner, name);
+    let title_arg = format!("title={}", title);
+    let body_arg = format!("body={}", body);
+    let created = gh_rw(
+        execution_mode,
+        [
+            "api", &path, "-X", "POST", "-f", &title_arg, "-f", &body_arg, "--jq", ".number",
+        ]
+        .as_slice(),
+    )?;
+    if execution_mode == ExecutionMode::DryRun {
+        return Ok(None);
+    }
+    created
+        .trim()
+        .parse()
+        .map(Some)
+        .map_err(|_| anyhow!("Failed to determine the number of the created issue"))
+}
+
+pub fn fetch_issue_body(number: u64) -> Result<String> {
+    let (owner, name) = get_repo_owner_name()?;
+    let path = format!("repos/{}/{}/issues/{}", owner, name, number);
+    gh_ro(["api", &path, "--jq", ".body // \"\""].as_slice())
+}
+
+pub fn edit_issue_body(number: u64, body: &str, execution_mode: ExecutionMode) -> Result<()> {
+    let (owner, name) = get_repo_owner_name()?;
+    let path = format!("repos/{}/{}/issues/{}", owner, name, number);
+    let body_arg = format!("body={}", body);
+    gh_rw(
+        execution_mode,
+        ["api", &path, "-X", "PATCH", "-f", &body_arg].as_slice(),
+    )?;
+    Ok(())
+}
+
+/// Replace the title of PR `number`.
+pub fn edit_pr_title(number: u64, title: &str, execution_mode: ExecutionMode) -> Result<()> {
+    let (owner, name) = get_repo_owner_name()?;
+    let path = format!("repos/{}/{}/pulls/{}", owner, name, number);
+    let title_arg = format!("title={}", title);
+    gh_rw(
+        execution_mode,
+        ["api", &path, "-X", "PATCH", "-f", &title_arg].as_slice(),
+    )?;
+    Ok(())
+}
+
+/// Request review on PR `number` from `reviewers`; `org/team` entries are team requests.
+pub fn request_pr_reviewers(
+    number: u64,
+    reviewers: &[String],
+    execution_mode: ExecutionMode,
+) -> Result<()> {
+    let (owner, name) = get_repo_owner_name()?;
+    let path = format!(
+        "repos/{}/{}/pulls/{}/requested_reviewers",
+        owner, name, number
+    );
+    let fields: Vec<String> = reviewers
+        .iter()
+        .map(|reviewer| match reviewer.split_once('/') {
+            Some((_, team)) => format!("team_reviewers[]={}", team),
+            None => format!("reviewers[]={}", reviewer),
+        })
+        .collect();
+    let mut args = vec!["api", &path, "-X", "POST"];
+    for field in &fields {
+        args.extend(["-f", field.as_str()]);
+    }
+    gh_rw(execution_mode, &args)?;
+    Ok(())
+}
+
+/// Who is already involved in reviewing a PR.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrReviewParticipants {
+    pub author: String,
+    /// Users (`login`) and teams (`org/team`) whose review is currently requested.
+    pub requested: Vec<String>,
+    /// Users who have submitted a review.
+    pub reviewed: Vec<String>,
+}
+
+/// Author, requested reviewers, and review authors of each PR in `numbers`.
+pub fn fetch_pr_review_participants(numbers: &[u64]) -> Result<HashMap<u64, PrReviewParticipants>> {
+    let mut out = HashMap::new();
+    if numbers.is_empty() {
+        return Ok(out);
+    }
+    let (owner, name) = get_repo_owner_name()?;
+    let mut q =
+        String::from("query($owner:String!,$name:String!){ repository(owner:$owner,name:$name){ ");
+    for (i, n) in numbers.iter().enumerate() {
+        q.push_str(&format!(
+            "pr{}: pullRequest(number: {}) {{ author {{ login }} reviewRequests(first:50){{ nodes {{ requestedReviewer {{ ... on User {{ login }} ... on Team {{ combinedSlug }} }} }} }} reviews(first:100){{ nodes {{ author {{ login }} }} }} }} ",
+            i, n
+        ));
+    }
+    q.push_str("} }");
+    let json = gh_ro(
+        [
+            "api",
+            "graphql",
+            "-f",
+            &format!("query={}", q),
+            "-F",
+            &format!("owner={}", owner),
+            "-F",
+            &format!("name={}", name),
+        ]
+        .as_slice(),
+    )?;
+    let v: serde_json::Value = serde_json::from_str(&json)?;
+    let repo = &v["data"]["repository"];
+    for (i, n) in numbers.iter().enumerate() {
+        let pr = &repo[&format!("pr{}", i)];
+        let mut reviewed: Vec<String> = Vec::new();
+        for login in pr["reviews"]["nodes"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|node| node["author"]["login"].as_str())
+        {
+            if !reviewed.iter().any(|seen| seen == login) {
+                reviewed.push(login.to_string());
+            }
+        }
+        out.insert(
+            *n,
+            PrReviewParticipants {
+                author: pr["author"]["login"].as_str().unwrap_or("").to_string(),
+                requested: parse_requested_reviewers(pr),
+                reviewed,
+            },
+        );
+    }
+    Ok(out)
+}
+
+/// Add `labels` to PR `number`, keeping its existing labels.
+pub fn add_pr_labels(number: u64, labels: &[String], execution_mode: ExecutionMode) -> Result<()> {
+    let (owner, name) = get_repo_owner_name()?;
+    let path = format!("repos/{}/{}/issues/{}/labels", owner, name, number);
+    let fields: Vec<String> = labels
+        .iter()
+        .map(|label| format!("labels[]={}", label))
+        .collect();
+    let mut args = vec!["api", &path, "-X", "POST"];
+    for field in &fields {
+        args.extend(["-f", field.as_str()]);
+    }
+    gh_rw(execution_mode, &args)?;
+    Ok(())
+}
+
+/// Replace the body of PR `number`.
+pub fn edit_pr_body(number: u64, body: &str, execution_mode: ExecutionMode) -> Result<()> {
+    let (owner, name) = get_repo_owner_name()?;
+    let path = format!("repos/{}/{}/pulls/{}", owner, name, number);
+    let body_arg = format!("body={}", body);
+    gh_rw(
+        execution_mode,
+        ["api", &path, "-X", "PATCH", "-f", &body_arg].as_slice(),
+    )?;
+    Ok(())
+}
+
 /// Append a warning line to a specific PR body (idempotent). Returns Ok(()) whether updated or skipped.
 pub fn append_warning_to_pr(
     number: u64,
@@ -1496,17 +2863,13 @@ pub fn append_warning_to_pr(
             format!("{}\n\n{}", warning, body)
         };
         info!("Appending warning to PR #{} on GitHub...", number);
-        let mut m = String::from("mutation {");
-        m.push_str(&format!(
-            "u: updatePullRequest(input:{{pullRequestId:\"{}\", body:\"{}\"}}){{ clientMutationId }} ",
-            info.id,
-            graphql_escape(&new_body)
+        let mut mutation = GraphqlMutation::new();
+        let id = mutation.var("ID!", &info.id);
+        let body = mutation.var("String!", &new_body);
+        mutation.field(format!(
+            "u: updatePullRequest(input:{{pullRequestId:{id}, body:{body}}}){{ clientMutationId }}"
         ));
-        m.push('}');
-        gh_rw(
-            execution_mode,
-            ["api", "graphql", "-f", &format!("query={}", m)].as_slice(),
-        )?;
+        mutation.run(execution_mode)?;
         info!("Appended warning to PR #{}", number);
     }
     Ok(())
@@ -1516,13 +2879,16 @@ pub fn append_warning_to_pr(
 mod tests {
     use super::{
         fetch_merged_pr_merge_commit_oids, fetch_pr_bodies_graphql,
-        fetch_pr_issue_comment_bodies_graphql, filter_case_variant_head_search_matches,
+        fetch_pr_bodies_with_node_ids_graphql, fetch_pr_issue_comment_bodies_graphql,
+        fetch_pr_node_ids_graphql, filter_case_variant_head_search_matches,
         filter_head_search_matches, is_resource_limit_error,
         list_conflicting_prs_for_heads_search_exhaustive, list_exact_prs_for_heads,
         list_open_or_merged_prs_for_heads, list_open_prs_for_heads,
-        list_recent_terminal_prs_for_heads, parse_open_pr_automerge_node, resolve_pr_url_head_ref,
-        run_read_chunk_with_retry, select_latest_merged_pr_match, select_single_open_pr_match,
-        HeadSearchPr, PrState, TerminalPrState, EXACT_HEAD_QUERY_LIMIT,
+        list_recent_terminal_prs_for_heads, parse_commit_ci, parse_open_pr_automerge_node,
+        parse_pr_history_node, parse_pr_import_info, parse_pr_project_items,
+        resolve_pr_url_head_ref, run_read_chunk_with_retry, select_latest_merged_pr_match,
+        select_single_open_pr_match, GraphqlMutation, HeadSearchPr, PrCiState, PrState,
+        PrStatusContext, PrTimelineEvent, TerminalPrState, EXACT_HEAD_QUERY_LIMIT,
     };
     use crate::test_support::{init_repo, lock_cwd, DirGuard};
     use anyhow::anyhow;
@@ -1740,6 +3106,99 @@ mod tests {
         assert_eq!(fs::read_to_string(log_path).unwrap().lines().count(), 2);
     }
 
+    #[test]
+    fn graphql_mutation_passes_inputs_verbatim_as_variables() {
+        let body = "Quote \" backslash \\ newline\n tab\t $v0 @file {braces} v1=x";
+        let mut mutation = GraphqlMutation::new();
+        let id = mutation.var("ID!", "PR_1");
+        let body_var = mutation.var("String!", body);
+        mutation.field(format!(
+            "m0: updatePullRequest(input:{{pullRequestId:{id}, body:{body_var}}}){{ clientMutationId }}"
+        ));
+
+        assert_eq!(
+            mutation.query(),
+            "mutation($v0:ID!,$v1:String!) { m0: updatePullRequest(input:{pullRequestId:$v0, body:$v1}){ clientMutationId } }"
+        );
+        let args = mutation.gh_args();
+        assert_eq!(args[..3], ["api", "graphql", "-f"]);
+        assert_eq!(args[4..], ["-f", "v0=PR_1", "-f", &format!("v1={body}")]);
+        assert!(!mutation.is_empty());
+        assert_eq!(GraphqlMutation::new().query(), "mutation {  }");
+    }
+
+    #[test]
+    fn fetch_pr_node_ids_graphql_reuses_ids_from_earlier_queries() {
+        let _lock = lock_cwd();
+        let repo = init_repo();
+        crate::test_support::git(
+            repo.path(),
+            [
+                "remote",
+                "add",
+                "origin",
+                "https://github.com/example/spr-node-ids.git",
+            ]
+            .as_slice(),
+        );
+        let _guard = DirGuard::change_to(repo.path());
+        let data_dir = tempfile::tempdir().unwrap();
+        let log_path = data_dir.path().join("gh.log");
+        let script = format!(
+            "#!/bin/sh\nprintf '%s\\n' \"$*\" >> \"{}\"\ncase \"$*\" in\n  *isDraft*) echo '{{\"data\":{{\"repository\":{{\"pr0\":{{\"id\":\"PR_3\",\"isDraft\":false}}}}}}}}' ;;\n  *) echo '{{\"data\":{{\"repository\":{{\"pr0\":{{\"id\":\"PR_1\",\"body\":\"\"}},\"pr1\":{{\"id\":\"PR_2\",\"body\":\"\"}}}}}}}}' ;;\nesac\n",
+            log_path.display()
+        );
+        let (_wrapper_dir, _path_guard) = install_gh_wrapper(&script);
+
+        fetch_pr_bodies_graphql(&[1, 2]).unwrap();
+        let ids = fetch_pr_node_ids_graphql(&[1, 2, 3]).unwrap();
+
+        assert_eq!(ids[&1], "PR_1");
+        assert_eq!(ids[&2], "PR_2");
+        assert_eq!(ids[&3], "PR_3");
+        let log = fs::read_to_string(log_path).unwrap();
+        assert_eq!(log.lines().count(), 2);
+        assert!(
+            log.contains("pullRequest(number: 3) { id isDraft }"),
+            "{log}"
+        );
+    }
+
+    #[test]
+    fn fetch_pr_bodies_with_node_ids_graphql_reads_once_with_a_cold_cache() {
+        let _lock = lock_cwd();
+        let repo = init_repo();
+        crate::test_support::git(
+            repo.path(),
+            [
+                "remote",
+                "add",
+                "origin",
+                "https://github.com/example/spr-land-ids.git",
+            ]
+            .as_slice(),
+        );
+        let _guard = DirGuard::change_to(repo.path());
+        let data_dir = tempfile::tempdir().unwrap();
+        let log_path = data_dir.path().join("gh.log");
+        let script = format!(
+            "#!/bin/sh\nprintf '%s\\n' \"$*\" >> \"{}\"\necho '{{\"data\":{{\"repository\":{{\"pr0\":{{\"id\":\"PR_3\",\"title\":\"Top\",\"body\":\"\"}},\"pr1\":{{\"id\":\"PR_1\",\"body\":\"\"}},\"pr2\":{{\"id\":\"PR_2\",\"body\":\"\"}}}}}}}}'\n",
+            log_path.display()
+        );
+        let (_wrapper_dir, _path_guard) = install_gh_wrapper(&script);
+
+        let (bodies, ids) = fetch_pr_bodies_with_node_ids_graphql(&[3], &[3, 1, 2]).unwrap();
+
+        assert_eq!(bodies.keys().copied().collect::<Vec<_>>(), vec![3]);
+        assert_eq!(bodies[&3].title, "Top");
+        assert_eq!(
+            (ids[&1].as_str(), ids[&2].as_str(), ids[&3].as_str()),
+            ("PR_1", "PR_2", "PR_3")
+        );
+        let log = fs::read_to_string(log_path).unwrap();
+        assert_eq!(log.lines().count(), 1, "{log}");
+    }
+
     #[test]
     fn fetch_pr_issue_comment_bodies_graphql_reads_all_pages() {
         let _lock = lock_cwd();
@@ -2122,15 +3581,22 @@ mod tests {
         assert_eq!(prs[0].number, 17);
         assert_eq!(prs[1].number, 18);
 
+        // The exact-head query and the conflict search run concurrently, in either order.
         let log = fs::read_to_string(log_path).unwrap();
         let lines: Vec<&str> = log.lines().collect();
         assert_eq!(lines.len(), 2);
-        assert!(lines[0].contains("api graphql"));
-        assert!(lines[0].contains("states:[OPEN]"));
-        assert!(lines[1].contains("api graphql"));
-        assert!(lines[1].contains("is:pr is:open head:skilltest/alpha"));
-        assert!(lines[1].contains("is:pr is:open head:skilltest/beta"));
-        assert!(lines[1].contains("first:2"));
+        assert!(lines.iter().all(|line| line.contains("api graphql")));
+        let exact = lines
+            .iter()
+            .find(|line| line.contains("states:[OPEN]"))
+            .unwrap();
+        assert!(!exact.contains("is:pr is:open head:"));
+        let search = lines
+            .iter()
+            .find(|line| line.contains("is:pr is:open head:skilltest/alpha"))
+            .unwrap();
+        assert!(search.contains("is:pr is:open head:skilltest/beta"));
+        assert!(search.contains("first:2"));
     }
 
     #[test]
@@ -2368,4 +3834,116 @@ mod tests {
         assert!(lines[1]
             .contains("is:pr is:closed head:skilltest/alpha closed:>=2026-02-01 sort:closed-desc"));
     }
+
+    #[test]
+    fn parse_pr_project_items_reads_field_options_and_missing_fields() {
+        let node = json!({
+            "projectItems": {"nodes": [
+                {"id": "ITEM_1", "project": {"id": "PROJ_1", "title": "Roadmap", "field": {
+                    "id": "FIELD_1",
+                    "options": [{"id": "OPT_1", "name": "Done"}]
+                }}},
+                {"id": "ITEM_2", "project": {"id": "PROJ_2", "title": "Triage", "field": null}}
+            ]}
+        });
+
+        let items = parse_pr_project_items(9, &node);
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].pr_number, 9);
+        assert_eq!(items[0].project_title, "Roadmap");
+        let field = items[0].status_field.as_ref().unwrap();
+        assert_eq!(field.id, "FIELD_1");
+        assert_eq!(field.options[0].name, "Done");
+        assert_eq!(items[1].item_id, "ITEM_2");
+        assert!(items[1].status_field.is_none());
+    }
+
+    #[test]
+    fn parse_pr_history_node_keeps_submitted_reviews_and_force_pushes() {
+        let node = json!({
+            "number": 21,
+            "title": "feat: alpha",
+            "headRefName": "dank-spr/alpha",
+            "createdAt": "2026-03-01T10:00:00Z",
+            "mergedAt": "2026-03-02T12:30:00Z",
+            "timelineItems": {"nodes": [
+                {"__typename": "PullRequestReview", "state": "CHANGES_REQUESTED"},
+                {"__typename": "HeadRefForcePushedEvent"},
+                {"__typename": "PullRequestReview", "state": "PENDING"},
+                {"__typename": "PullRequestReview", "state": "APPROVED"}
+            ]}
+        });
+
+        let info = parse_pr_history_node(&node).unwrap();
+
+        assert_eq!(info.number, 21);
+        assert_eq!(info.head, "dank-spr/alpha");
+        assert_eq!(
+            info.events,
+            vec![
+                PrTimelineEvent::Review,
+                PrTimelineEvent::ForcePush,
+                PrTimelineEvent::Review
+            ]
+        );
+        assert_eq!(info.time_to_merge_secs().unwrap(), Some(26 * 3600 + 1800));
+        assert!(parse_pr_history_node(&Value::Null).is_none());
+    }
+
+    #[test]
+    fn parse_pr_import_info_reads_head_and_commit_order() {
+        let info = parse_pr_import_info(
+            r#"{"number":42,"title":"Fix login","state":"OPEN","headRefName":"alice/login","isCrossRepository":false,"commits":[{"oid":"aaa"},{"oid":"bbb"}]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(info.number, 42);
+        assert_eq!(info.head, "alice/login");
+        assert!(!info.is_cross_repository);
+        assert_eq!(info.commit_oids, vec!["aaa".to_string(), "bbb".to_string()]);
+    }
+
+    #[test]
+    fn parse_commit_ci_separates_check_runs_from_commit_statuses() {
+        assert_eq!(
+            parse_commit_ci(&Value::Null),
+            (PrCiState::Success, Vec::new())
+        );
+        assert_eq!(
+            parse_commit_ci(&json!({ "statusCheckRollup": { "state": "PENDING" } })),
+            (PrCiState::Pending, Vec::new())
+        );
+
+        let commit = json!({
+            "statusCheckRollup": {
+                "state": "FAILURE",
+                "contexts": { "nodes": [
+                    { "status": "COMPLETED", "conclusion": "SUCCESS" },
+                    { "status": "COMPLETED", "conclusion": "SKIPPED" },
+                    {}
+                ] }
+            },
+            "status": { "contexts": [
+                { "context": "deploy/staging", "state": "FAILURE" },
+                { "context": "ci/jenkins", "state": "SUCCESS" }
+            ] }
+        });
+        assert_eq!(
+            parse_commit_ci(&commit),
+            (
+                PrCiState::Success,
+                vec![
+                    PrStatusContext {
+                        context: "deploy/staging".to_string(),
+                        state: PrCiState::Failure,
+                    },
+                    PrStatusContext {
+                        context: "ci/jenkins".to_string(),
+                        state: PrCiState::Success,
+                    },
+                ]
+            )
+        );
+    }
 }
@@ -0,0 +1,1131 @@
+//! Token-based GitHub client for machines where `gh` is not installed.
+//!
+//! Every GitHub call spr makes is a `gh` argument vector handed to [`crate::git::gh_ro`] or
+//! [`crate::git::gh_rw`]. When this client is selected, those vectors are served here instead:
+//! `gh api` calls become the same REST or GraphQL request, and the handful of `gh pr` and
+//! `gh repo` subcommands spr uses are translated to their API equivalents. Requests go through
+//! `curl` with the token from `GH_TOKEN` or `GITHUB_TOKEN`; the token is handed to curl on stdin
+//! so it never shows up in a process listing. `GITHUB_API_URL` and `GITHUB_GRAPHQL_URL` point the
+//! client at GitHub Enterprise Server, as they do in GitHub Actions.
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+use tracing::info;
+
+use crate::run_settings::Setting;
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GithubClient {
+    /// `gh` when it is installed; otherwise the token client when a token is set.
+    #[default]
+    Auto,
+    /// Always `gh`.
+    Gh,
+    /// Always the token client, even when `gh` is installed.
+    Token,
+}
+
+static CLIENT: Setting<GithubClient> = Setting::new();
+static AUTO_USES_TOKEN: OnceLock<bool> = OnceLock::new();
+
+/// Reach GitHub through `client` for the rest of this process.
+pub fn set_github_client(client: GithubClient) {
+    CLIENT.set(client);
+}
+
+/// Whether GitHub calls are served by this client rather than `gh`.
+pub fn enabled() -> bool {
+    match CLIENT.get().unwrap_or_default() {
+        GithubClient::Gh => false,
+        GithubClient::Token => true,
+        GithubClient::Auto => *AUTO_USES_TOKEN
+            .get_or_init(|| crate::git::ensure_tool("gh").is_err() && token().is_some()),
+    }
+}
+
+/// Check that the token client can run: `curl` is installed and a token is set.
+pub fn ensure_ready() -> Result<()> {
+    crate::git::ensure_tool("curl")?;
+    if token().is_none() {
+        bail!("The token GitHub client needs GH_TOKEN or GITHUB_TOKEN to be set");
+    }
+    Ok(())
+}
+
+fn token() -> Option<String> {
+    ["GH_TOKEN", "GITHUB_TOKEN"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .map(|value| value.trim().to_string())
+        .find(|value| !value.is_empty())
+}
+
+fn api_url() -> String {
+    std::env::var("GITHUB_API_URL")
+        .ok()
+        .filter(|url| !url.trim().is_empty())
+        .map(|url| url.trim().trim_end_matches('/').to_string())
+        .unwrap_or_else(|| "https://api.github.com".to_string())
+}
+
+fn graphql_url() -> String {
+    std::env::var("GITHUB_GRAPHQL_URL")
+        .ok()
+        .filter(|url| !url.trim().is_empty())
+        .map(|url| url.trim().to_string())
+        .unwrap_or_else(|| format!("{}/graphql", api_url()))
+}
+
+/// Serve a `gh` argument vector and return what `gh` would have printed.
+pub fn run(args: &[&str]) -> Result<String> {
+    match parse_call(args)? {
+        GhCall::Api(call) => run_api(args, call),
+        GhCall::PrList(list) => pr_list(args, &list),
+        GhCall::PrView { pr, fields } => pr_view(args, &pr, &fields),
+        GhCall::PrOpen { pr } => pr_open(&pr),
+        GhCall::PrEdit { pr, base } => pr_edit(args, &pr, base),
+        GhCall::PrClose { pr, comment } => pr_close(args, &pr, comment),
+        GhCall::PrComment { pr, body } => pr_comment(args, &pr, body),
+        GhCall::RepoDefaultBranch { jq } => repo_default_branch(args, jq.as_ref()),
+    }
+}
+
+/// Fail, without contacting GitHub, when this client cannot serve `args`.
+#[cfg(test)]
+pub fn check_supported(args: &[&str]) -> Result<()> {
+    parse_call(args).map(|_| ())
+}
+
+/// A `gh` invocation this client serves, parsed and checked up front.
+#[derive(Debug, PartialEq)]
+enum GhCall<'a> {
+    Api(ApiCall),
+    PrList(PrList<'a>),
+    PrView {
+        pr: PrTarget,
+        fields: Vec<&'a str>,
+    },
+    /// `gh pr view --web`.
+    PrOpen {
+        pr: PrTarget,
+    },
+    PrEdit {
+        pr: PrTarget,
+        base: &'a str,
+    },
+    PrClose {
+        pr: PrTarget,
+        comment: Option<&'a str>,
+    },
+    PrComment {
+        pr: PrTarget,
+        body: &'a str,
+    },
+    RepoDefaultBranch {
+        jq: Option<JqPath>,
+    },
+}
+
+fn parse_call<'a>(args: &[&'a str]) -> Result<GhCall<'a>> {
+    match args {
+        ["api", rest @ ..] => parse_api_args(rest).map(GhCall::Api),
+        ["pr", "list", rest @ ..] => parse_pr_list(rest).map(GhCall::PrList),
+        ["pr", "view", rest @ ..] => parse_pr_view(rest),
+        ["pr", "edit", rest @ ..] => parse_pr_edit(rest),
+        ["pr", "close", rest @ ..] => parse_pr_close(rest),
+        ["pr", "comment", rest @ ..] => parse_pr_comment(rest),
+        ["repo", "view", rest @ ..] => parse_repo_view(rest),
+        _ => bail!(
+            "`gh {}` is not supported by the token GitHub client; install gh or set `github_client: gh`",
+            args.join(" ")
+        ),
+    }
+}
+
+/* ------------------ transport ------------------ */
+
+/// A curl config value, quoted so curl reads it back verbatim.
+fn curl_quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// The curl config (read from stdin with `-K -`) for one request.
+fn curl_config(method: &str, url: &str, token: &str, body: Option<&str>) -> String {
+    let mut lines = vec![
+        format!("url = {}", curl_quote(url)),
+        format!("request = {}", curl_quote(method)),
+        format!(
+            "header = {}",
+            curl_quote(&format!("Authorization: Bearer {token}"))
+        ),
+        format!(
+            "header = {}",
+            curl_quote("Accept: application/vnd.github+json")
+        ),
+        format!("header = {}", curl_quote("User-Agent: spr")),
+        "silent".to_string(),
+        "show-error".to_string(),
+        format!("write-out = {}", curl_quote("\\n%{http_code}")),
+    ];
+    if let Some(body) = body {
+        lines.push(format!(
+            "header = {}",
+            curl_quote("Content-Type: application/json")
+        ));
+        lines.push(format!("data-binary = {}", curl_quote(body)));
+    }
+    lines.join("\n") + "\n"
+}
+
+/// Send one request and return the response body. `gh_args` is the argument vector being
+/// served; it names the request in `--timing` and in errors.
+fn send(gh_args: &[&str], method: &str, url: &str, body: Option<&Value>) -> Result<String> {
+    let token = token()
+        .ok_or_else(|| anyhow!("No GitHub token: set GH_TOKEN or GITHUB_TOKEN, or install gh"))?;
+    let body = body.map(serde_json::to_string).transpose()?;
+    let config = curl_config(method, url, &token, body.as_deref());
+    let started = std::time::Instant::now();
+    let mut child = Command::new("curl")
+        .args(["-K", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("failed to spawn curl")?;
+    child
+        .stdin
+        .take()
+        .expect("curl stdin is piped")
+        .write_all(config.as_bytes())?;
+    let out = child.wait_with_output()?;
+    crate::timing::record("gh", gh_args, started.elapsed());
+    if !out.status.success() {
+        bail!(
+            "{} {} failed: {}",
+            method,
+            url,
+            String::from_utf8_lossy(&out.stderr).trim()
+        );
+    }
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let (response, status) = stdout.rsplit_once('\n').unwrap_or(("", &stdout));
+    let status: u16 = status
+        .trim()
+        .parse()
+        .with_context(|| format!("curl did not report an HTTP status for {method} {url}"))?;
+    if !(200..300).contains(&status) {
+        bail!(
+            "command failed: gh {:?}: HTTP {}: {}",
+            gh_args,
+            status,
+            response.trim()
+        );
+    }
+    Ok(response.to_string())
+}
+
+/// Run a GraphQL request, failing like `gh api graphql` when the response carries errors.
+fn graphql(gh_args: &[&str], query: &str, variables: Map<String, Value>) -> Result<Value> {
+    let request = json!({ "query": query, "variables": variables });
+    let response = send(gh_args, "POST", &graphql_url(), Some(&request))?;
+    let value: Value =
+        serde_json::from_str(&response).context("Failed to parse GitHub GraphQL response")?;
+    if let Some(errors) = value
+        .get("errors")
+        .filter(|errors| errors.as_array().is_some_and(|errors| !errors.is_empty()))
+    {
+        bail!("command failed: gh {:?}: GraphQL: {}", gh_args, errors);
+    }
+    Ok(value)
+}
+
+fn rest(gh_args: &[&str], method: &str, path: &str, body: Option<&Value>) -> Result<String> {
+    let url = format!("{}/{}", api_url(), path.trim_start_matches('/'));
+    send(gh_args, method, &url, body)
+}
+
+/* ------------------ gh api ------------------ */
+
+/// A parsed `gh api` invocation. `query` is set for `gh api graphql` and holds the document
+/// taken out of `fields`; the remaining fields are its variables.
+#[derive(Debug, Default, PartialEq)]
+struct ApiCall {
+    endpoint: String,
+    method: String,
+    query: Option<String>,
+    fields: Map<String, Value>,
+    jq: Option<JqPath>,
+}
+
+fn parse_api_args(args: &[&str]) -> Result<ApiCall> {
+    let mut call = ApiCall::default();
+    let mut method = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .copied()
+                .ok_or_else(|| anyhow!("gh api {arg} needs a value"))
+        };
+        match *arg {
+            "-X" | "--method" => method = Some(value()?.to_string()),
+            "-f" | "--raw-field" => add_field(&mut call.fields, value()?, false)?,
+            "-F" | "--field" => add_field(&mut call.fields, value()?, true)?,
+            "-q" | "--jq" => call.jq = Some(JqPath::parse(value()?)?),
+            flag if flag.starts_with('-') => {
+                bail!("gh api {flag} is not supported by the token GitHub client")
+            }
+            endpoint if call.endpoint.is_empty() => call.endpoint = endpoint.to_string(),
+            extra => bail!("gh api got an unexpected argument {extra}"),
+        }
+    }
+    if call.endpoint.is_empty() {
+        bail!("gh api needs an endpoint");
+    }
+    if call.endpoint == "graphql" {
+        match call.fields.remove("query") {
+            Some(Value::String(query)) => call.query = Some(query),
+            _ => bail!("gh api graphql needs a query field"),
+        }
+        if method.as_deref().is_some_and(|method| method != "POST") {
+            bail!("gh api graphql only supports POST");
+        }
+    }
+    call.method = method.unwrap_or_else(|| {
+        if call.fields.is_empty() && call.query.is_none() {
+            "GET".to_string()
+        } else {
+            "POST".to_string()
+        }
+    });
+    if call.method == "GET" && !call.fields.is_empty() {
+        bail!("gh api GET with fields is not supported by the token GitHub client");
+    }
+    Ok(call)
+}
+
+/// Add `key=value` the way `gh api` does: `-F` values become booleans, null, or integers when
+/// they parse as one, and `key[]` keys collect into an array.
+fn add_field(fields: &mut Map<String, Value>, raw: &str, typed: bool) -> Result<()> {
+    let (key, value) = raw
+        .split_once('=')
+        .ok_or_else(|| anyhow!("gh api field {raw} is not key=value"))?;
+    let value = if typed {
+        match value {
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            "null" => Value::Null,
+            _ => value
+                .parse::<i64>()
+                .map(Value::from)
+                .unwrap_or_else(|_| Value::String(value.to_string())),
+        }
+    } else {
+        Value::String(value.to_string())
+    };
+    match key.strip_suffix("[]") {
+        Some(key) => match fields
+            .entry(key)
+            .or_insert_with(|| Value::Array(Vec::new()))
+        {
+            Value::Array(items) => items.push(value),
+            _ => bail!("gh api field {key} is given both as a value and as an array"),
+        },
+        None => {
+            fields.insert(key.to_string(), value);
+        }
+    }
+    Ok(())
+}
+
+fn run_api(gh_args: &[&str], call: ApiCall) -> Result<String> {
+    let output = match call.query {
+        Some(query) => serde_json::to_string(&graphql(gh_args, &query, call.fields)?)?,
+        None => {
+            let body = (!call.fields.is_empty()).then_some(Value::Object(call.fields));
+            rest(gh_args, &call.method, &call.endpoint, body.as_ref())?
+        }
+    };
+    match call.jq {
+        Some(jq) => jq.apply(&output),
+        None => Ok(output),
+    }
+}
+
+/// The `--jq` expressions spr uses: a field path such as `.data.viewer.login`, optionally
+/// followed by `// <json default>`.
+#[derive(Debug, PartialEq)]
+struct JqPath {
+    segments: Vec<String>,
+    default: Option<Value>,
+}
+
+impl JqPath {
+    fn parse(expr: &str) -> Result<Self> {
+        let (path, default) = match expr.split_once("//") {
+            Some((path, default)) => (
+                path.trim(),
+                Some(
+                    serde_json::from_str::<Value>(default.trim())
+                        .with_context(|| format!("Unsupported --jq default in {expr}"))?,
+                ),
+            ),
+            None => (expr.trim(), None),
+        };
+        let segments = path
+            .strip_prefix('.')
+            .ok_or_else(|| anyhow!("Unsupported --jq expression {expr}"))?
+            .split('.')
+            .filter(|segment| !segment.is_empty())
+            .map(|segment| {
+                if segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_')
+                {
+                    Ok(segment.to_string())
+                } else {
+                    Err(anyhow!("Unsupported --jq expression {expr}"))
+                }
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { segments, default })
+    }
+
+    /// Evaluate against `json`; strings print raw, like `gh --jq`.
+    fn apply(&self, json: &str) -> Result<String> {
+        let parsed: Value = if json.trim().is_empty() {
+            Value::Null
+        } else {
+            serde_json::from_str(json)?
+        };
+        let value = self
+            .segments
+            .iter()
+            .fold(&parsed, |value, segment| &value[segment.as_str()]);
+        let value = match (value, &self.default) {
+            (Value::Null | Value::Bool(false), Some(default)) => default,
+            _ => value,
+        };
+        Ok(match value {
+            Value::String(s) => format!("{s}\n"),
+            other => format!("{other}\n"),
+        })
+    }
+}
+
+/* ------------------ gh pr / gh repo ------------------ */
+
+/// `gh` flags that take no value.
+const BOOLEAN_FLAGS: &[&str] = &["--web", "-w"];
+
+/// Positional arguments and `--flag value` pairs of a `gh pr` or `gh repo` subcommand. Flags in
+/// [`BOOLEAN_FLAGS`] map to an empty value.
+fn split_flags<'a>(args: &[&'a str]) -> Result<(Vec<&'a str>, HashMap<&'a str, &'a str>)> {
+    let mut positional = Vec::new();
+    let mut flags = HashMap::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if !arg.starts_with('-') {
+            positional.push(*arg);
+            continue;
+        }
+        let flag = match *arg {
+            "-R" => "--repo",
+            "-q" => "--jq",
+            "-w" => "--web",
+            flag => flag,
+        };
+        let value = if BOOLEAN_FLAGS.contains(arg) {
+            ""
+        } else {
+            args.next().ok_or_else(|| {
+                anyhow!("{arg} is not supported by the token GitHub client; install gh")
+            })?
+        };
+        flags.insert(flag, value);
+    }
+    Ok((positional, flags))
+}
+
+fn unsupported_flags(flags: &HashMap<&str, &str>, allowed: &[&str], command: &str) -> Result<()> {
+    if let Some(flag) = flags.keys().find(|flag| !allowed.contains(flag)) {
+        bail!("gh {command} {flag} is not supported by the token GitHub client; install gh");
+    }
+    Ok(())
+}
+
+type Repo = (String, String);
+
+/// The `--repo owner/name` flag; `None` means the current checkout's repository.
+fn repo_flag(flags: &HashMap<&str, &str>) -> Result<Option<Repo>> {
+    flags
+        .get("--repo")
+        .map(|repo| {
+            repo.split_once('/')
+                .map(|(owner, name)| (owner.to_string(), name.to_string()))
+                .ok_or_else(|| anyhow!("--repo {repo} is not owner/name"))
+        })
+        .transpose()
+}
+
+fn resolve_repo(repo: Option<&Repo>) -> Result<Repo> {
+    match repo {
+        Some(repo) => Ok(repo.clone()),
+        None => crate::github::get_repo_owner_name(),
+    }
+}
+
+/// The pull request a `gh pr` target names.
+#[derive(Debug, PartialEq)]
+struct PrTarget {
+    repo: Option<Repo>,
+    number: u64,
+}
+
+impl PrTarget {
+    fn resolve(&self) -> Result<(String, String, u64)> {
+        let (owner, name) = resolve_repo(self.repo.as_ref())?;
+        Ok((owner, name, self.number))
+    }
+}
+
+/// Parse a `gh pr` target: `12`, `#12`, or a PR URL.
+fn pr_target(target: &str, flags: &HashMap<&str, &str>) -> Result<PrTarget> {
+    if let Some((_, path)) = target.split_once("://") {
+        let parts: Vec<&str> = path.trim_end_matches('/').split('/').collect();
+        if let [_, owner, name, "pull", number, ..] = parts.as_slice() {
+            let number = number
+                .parse()
+                .with_context(|| format!("Unable to parse a PR number from {target}"))?;
+            return Ok(PrTarget {
+                repo: Some((owner.to_string(), name.to_string())),
+                number,
+            });
+        }
+        bail!("Unable to parse a PR URL from {target}");
+    }
+    let number = target
+        .trim_start_matches('#')
+        .parse()
+        .with_context(|| format!("Unable to parse a PR number from {target}"))?;
+    Ok(PrTarget {
+        repo: repo_flag(flags)?,
+        number,
+    })
+}
+
+/// The single target of a `gh pr` subcommand.
+fn single_pr_target(
+    positional: &[&str],
+    flags: &HashMap<&str, &str>,
+    usage: &str,
+) -> Result<PrTarget> {
+    let [target] = positional else {
+        bail!("The token GitHub client needs gh {usage}");
+    };
+    pr_target(target, flags)
+}
+
+/// GraphQL selection for one `gh pr --json` field.
+fn pr_field_selection(field: &str) -> Result<&'static str> {
+    Ok(match field {
+        "number" => "number",
+        "title" => "title",
+        "body" => "body",
+        "state" => "state",
+        "url" => "url",
+        "headRefName" => "headRefName",
+        "baseRefName" => "baseRefName",
+        "isDraft" => "isDraft",
+        "isCrossRepository" => "isCrossRepository",
+        "mergedAt" => "mergedAt",
+        "closedAt" => "closedAt",
+        "createdAt" => "createdAt",
+        "autoMergeRequest" => "autoMergeRequest { enabledAt mergeMethod }",
+        "commits" => "commits(first: 250) { nodes { commit { oid } } }",
+        "comments" => "comments(first: 100) { nodes { body authorAssociation createdAt } }",
+        "labels" => "labels(first: 100) { nodes { name } }",
+        _ => bail!("gh --json {field} is not supported by the token GitHub client"),
+    })
+}
+
+fn pr_selection(fields: &[&str]) -> Result<String> {
+    Ok(fields
+        .iter()
+        .map(|field| pr_field_selection(field))
+        .collect::<Result<Vec<_>>>()?
+        .join(" "))
+}
+
+/// Reshape a GraphQL pull request node into `gh pr --json` output.
+fn pr_json(node: &Value, fields: &[&str]) -> Value {
+    let mut out = Map::new();
+    for field in fields {
+        let value = match *field {
+            "commits" => Value::Array(
+                node["commits"]["nodes"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(|item| item["commit"].clone())
+                    .collect(),
+            ),
+            "comments" | "labels" => node[*field]["nodes"].clone(),
+            _ => node[*field].clone(),
+        };
+        out.insert(field.to_string(), value);
+    }
+    Value::Object(out)
+}
+
+/// The `--json` fields, checked against the ones this client can select.
+fn json_fields<'a>(flags: &HashMap<&str, &'a str>) -> Result<Vec<&'a str>> {
+    let fields: &'a str = flags
+        .get("--json")
+        .copied()
+        .ok_or_else(|| anyhow!("The token GitHub client only supports gh pr with --json"))?;
+    let fields: Vec<&str> = fields.split(',').map(str::trim).collect();
+    pr_selection(&fields)?;
+    Ok(fields)
+}
+
+/// A parsed `gh pr list`.
+#[derive(Debug, PartialEq)]
+struct PrList<'a> {
+    repo: Option<Repo>,
+    state: &'a str,
+    search: Option<&'a str>,
+    limit: usize,
+    fields: Vec<&'a str>,
+}
+
+fn parse_pr_list<'a>(args: &[&'a str]) -> Result<PrList<'a>> {
+    let (_, flags) = split_flags(args)?;
+    unsupported_flags(
+        &flags,
+        &["--state", "--search", "--limit", "--json", "--repo"],
+        "pr list",
+    )?;
+    Ok(PrList {
+        repo: repo_flag(&flags)?,
+        state: flags.get("--state").copied().unwrap_or("open"),
+        search: flags.get("--search").copied(),
+        limit: match flags.get("--limit") {
+            Some(limit) => limit.parse().context("--limit is not a number")?,
+            None => 30,
+        },
+        fields: json_fields(&flags)?,
+    })
+}
+
+/// The GraphQL query for one page of `gh pr list`.
+fn pr_list_query(
+    owner: &str,
+    name: &str,
+    state: &str,
+    search: Option<&str>,
+    selection: &str,
+) -> (String, Map<String, Value>) {
+    let mut variables = Map::new();
+    match search {
+        Some(search) => {
+            let qualifier = match state {
+                "open" => " is:open",
+                "closed" => " is:closed",
+                "merged" => " is:merged",
+                _ => "",
+            };
+            variables.insert(
+                "q".to_string(),
+                Value::String(format!("repo:{owner}/{name} is:pr{qualifier} {search}")),
+            );
+            let query = format!(
+                "query($q:String!,$first:Int!,$after:String){{ search(query:$q,type:ISSUE,first:$first,after:$after){{ pageInfo{{ hasNextPage endCursor }} nodes{{ ... on PullRequest {{ {selection} }} }} }} }}"
+            );
+            (query, variables)
+        }
+        None => {
+            let states = match state {
+                "open" => "[OPEN]",
+                "closed" => "[CLOSED,MERGED]",
+                "merged" => "[MERGED]",
+                _ => "[OPEN,CLOSED,MERGED]",
+            };
+            variables.insert("owner".to_string(), Value::String(owner.to_string()));
+            variables.insert("name".to_string(), Value::String(name.to_string()));
+            let query = format!(
+                "query($owner:String!,$name:String!,$first:Int!,$after:String){{ repository(owner:$owner,name:$name){{ pullRequests(states:{states},first:$first,after:$after,orderBy:{{field:CREATED_AT,direction:DESC}}){{ pageInfo{{ hasNextPage endCursor }} nodes{{ {selection} }} }} }} }}"
+            );
+            (query, variables)
+        }
+    }
+}
+
+fn pr_list(gh_args: &[&str], list: &PrList) -> Result<String> {
+    let (owner, name) = resolve_repo(list.repo.as_ref())?;
+    let (query, base_variables) = pr_list_query(
+        &owner,
+        &name,
+        list.state,
+        list.search,
+        &pr_selection(&list.fields)?,
+    );
+    let mut out = Vec::new();
+    let mut after = Value::Null;
+    while out.len() < list.limit {
+        let mut variables = base_variables.clone();
+        variables.insert(
+            "first".to_string(),
+            Value::from((list.limit - out.len()).min(100)),
+        );
+        variables.insert("after".to_string(), after);
+        let value = graphql(gh_args, &query, variables)?;
+        let connection = if list.search.is_some() {
+            &value["data"]["search"]
+        } else {
+            &value["data"]["repository"]["pullRequests"]
+        };
+        for node in connection["nodes"].as_array().into_iter().flatten() {
+            if node.as_object().is_some_and(|node| !node.is_empty()) {
+                out.push(pr_json(node, &list.fields));
+            }
+        }
+        if connection["pageInfo"]["hasNextPage"].as_bool() != Some(true) {
+            break;
+        }
+        after = connection["pageInfo"]["endCursor"].clone();
+    }
+    out.truncate(list.limit);
+    Ok(serde_json::to_string(&out)?)
+}
+
+fn parse_pr_view<'a>(args: &[&'a str]) -> Result<GhCall<'a>> {
+    let (positional, flags) = split_flags(args)?;
+    unsupported_flags(&flags, &["--json", "--repo", "--web"], "pr view")?;
+    let pr = single_pr_target(&positional, &flags, "pr view <number|url>")?;
+    if flags.contains_key("--web") {
+        if flags.contains_key("--json") {
+            bail!("gh pr view takes --web or --json, not both");
+        }
+        return Ok(GhCall::PrOpen { pr });
+    }
+    Ok(GhCall::PrView {
+        pr,
+        fields: json_fields(&flags)?,
+    })
+}
+
+fn pr_view(gh_args: &[&str], pr: &PrTarget, fields: &[&str]) -> Result<String> {
+    let (owner, name, number) = pr.resolve()?;
+    let query = format!(
+        "query($owner:String!,$name:String!,$number:Int!){{ repository(owner:$owner,name:$name){{ pullRequest(number:$number){{ {} }} }} }}",
+        pr_selection(fields)?
+    );
+    let mut variables = Map::new();
+    variables.insert("owner".to_string(), Value::String(owner.clone()));
+    variables.insert("name".to_string(), Value::String(name.clone()));
+    variables.insert("number".to_string(), Value::from(number));
+    let value = graphql(gh_args, &query, variables)?;
+    let node = &value["data"]["repository"]["pullRequest"];
+    if node.is_null() {
+        bail!("No pull request #{number} in {owner}/{name}");
+    }
+    Ok(serde_json::to_string(&pr_json(node, fields))?)
+}
+
+/// The web address of the GitHub instance: `server_url` (`GITHUB_SERVER_URL`) when set,
+/// otherwise derived from the API address, which GitHub Enterprise Server serves under
+/// `/api/v3`.
+fn web_url(server_url: Option<&str>, api_url: &str) -> String {
+    match server_url.map(str::trim).filter(|url| !url.is_empty()) {
+        Some(url) => url.trim_end_matches('/').to_string(),
+        None => api_url
+            .strip_suffix("/api/v3")
+            .unwrap_or("https://github.com")
+            .to_string(),
+    }
+}
+
+fn pr_web_url(pr: &PrTarget) -> Result<String> {
+    let (owner, name, number) = pr.resolve()?;
+    let server_url = std::env::var("GITHUB_SERVER_URL").ok();
+    Ok(format!(
+        "{}/{owner}/{name}/pull/{number}",
+        web_url(server_url.as_deref(), &api_url())
+    ))
+}
+
+/// `gh pr view --web`: open the PR with the platform's opener, or print its address when there
+/// is none (a container or a CI job).
+fn pr_open(pr: &PrTarget) -> Result<String> {
+    let url = pr_web_url(pr)?;
+    let opener = if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(windows) {
+        "explorer"
+    } else {
+        "xdg-open"
+    };
+    let opened = Command::new(opener)
+        .arg(&url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success());
+    if !opened {
+        info!("Open {url} in your browser");
+    }
+    Ok(String::new())
+}
+
+fn parse_pr_edit<'a>(args: &[&'a str]) -> Result<GhCall<'a>> {
+    let (positional, flags) = split_flags(args)?;
+    unsupported_flags(&flags, &["--base", "--repo"], "pr edit")?;
+    let usage = "pr edit <number> --base <branch>";
+    let Some(base) = flags.get("--base").copied() else {
+        bail!("The token GitHub client needs gh {usage}");
+    };
+    Ok(GhCall::PrEdit {
+        pr: single_pr_target(&positional, &flags, usage)?,
+        base,
+    })
+}
+
+fn pr_edit(gh_args: &[&str], pr: &PrTarget, base: &str) -> Result<String> {
+    let (owner, name, number) = pr.resolve()?;
+    rest(
+        gh_args,
+        "PATCH",
+        &format!("repos/{owner}/{name}/pulls/{number}"),
+        Some(&json!({ "base": base })),
+    )?;
+    Ok(String::new())
+}
+
+fn parse_pr_close<'a>(args: &[&'a str]) -> Result<GhCall<'a>> {
+    let (positional, flags) = split_flags(args)?;
+    unsupported_flags(&flags, &["--comment", "--repo"], "pr close")?;
+    Ok(GhCall::PrClose {
+        pr: single_pr_target(&positional, &flags, "pr close <number>")?,
+        comment: flags.get("--comment").copied(),
+    })
+}
+
+fn pr_close(gh_args: &[&str], pr: &PrTarget, comment: Option<&str>) -> Result<String> {
+    let (owner, name, number) = pr.resolve()?;
+    if let Some(comment) = comment {
+        rest(
+            gh_args,
+            "POST",
+            &format!("repos/{owner}/{name}/issues/{number}/comments"),
+            Some(&json!({ "body": comment })),
+        )?;
+    }
+    rest(
+        gh_args,
+        "PATCH",
+        &format!("repos/{owner}/{name}/pulls/{number}"),
+        Some(&json!({ "state": "closed" })),
+    )?;
+    Ok(String::new())
+}
+
+fn parse_pr_comment<'a>(args: &[&'a str]) -> Result<GhCall<'a>> {
+    let (positional, flags) = split_flags(args)?;
+    unsupported_flags(&flags, &["--body", "--repo"], "pr comment")?;
+    let usage = "pr comment <number> --body <text>";
+    let Some(body) = flags.get("--body").copied() else {
+        bail!("The token GitHub client needs gh {usage}");
+    };
+    Ok(GhCall::PrComment {
+        pr: single_pr_target(&positional, &flags, usage)?,
+        body,
+    })
+}
+
+fn pr_comment(gh_args: &[&str], pr: &PrTarget, body: &str) -> Result<String> {
+    let (owner, name, number) = pr.resolve()?;
+    rest(
+        gh_args,
+        "POST",
+        &format!("repos/{owner}/{name}/issues/{number}/comments"),
+        Some(&json!({ "body": body })),
+    )?;
+    Ok(String::new())
+}
+
+fn parse_repo_view<'a>(args: &[&'a str]) -> Result<GhCall<'a>> {
+    let (positional, flags) = split_flags(args)?;
+    unsupported_flags(&flags, &["--json", "--jq"], "repo view")?;
+    if !positional.is_empty() || flags.get("--json") != Some(&"defaultBranchRef") {
+        bail!("The token GitHub client only supports gh repo view --json defaultBranchRef");
+    }
+    Ok(GhCall::RepoDefaultBranch {
+        jq: flags
+            .get("--jq")
+            .map(|expr| JqPath::parse(expr))
+            .transpose()?,
+    })
+}
+
+fn repo_default_branch(gh_args: &[&str], jq: Option<&JqPath>) -> Result<String> {
+    let (owner, name) = crate::github::get_repo_owner_name()?;
+    let mut variables = Map::new();
+    variables.insert("owner".to_string(), Value::String(owner));
+    variables.insert("name".to_string(), Value::String(name));
+    let value = graphql(
+        gh_args,
+        "query($owner:String!,$name:String!){ repository(owner:$owner,name:$name){ defaultBranchRef { name } } }",
+        variables,
+    )?;
+    let output = serde_json::to_string(
+        &json!({ "defaultBranchRef": value["data"]["repository"]["defaultBranchRef"] }),
+    )?;
+    match jq {
+        Some(jq) => jq.apply(&output),
+        None => Ok(output),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_api_args_types_fields_like_gh() {
+        let call = parse_api_args(&[
+            "repos/o/n/pulls",
+            "-X",
+            "POST",
+            "-f",
+            "title=123",
+            "-F",
+            "draft=true",
+            "-F",
+            "owner=42",
+            "-f",
+            "reviewers[]=alice",
+            "-f",
+            "reviewers[]=bob",
+            "--jq",
+            ".number",
+        ])
+        .unwrap();
+        assert_eq!(call.endpoint, "repos/o/n/pulls");
+        assert_eq!(call.method, "POST");
+        assert_eq!(call.jq, Some(JqPath::parse(".number").unwrap()));
+        assert_eq!(
+            Value::Object(call.fields),
+            json!({
+                "title": "123",
+                "draft": true,
+                "owner": 42,
+                "reviewers": ["alice", "bob"],
+            })
+        );
+        assert!(parse_api_args(&["graphql", "--paginate"]).is_err());
+        assert!(parse_api_args(&["graphql", "-F", "owner=o"]).is_err());
+    }
+
+    #[test]
+    fn jq_paths_read_fields_with_defaults_and_print_strings_raw() {
+        let json = r#"{"number":12,"data":{"viewer":{"login":"octo"}},"body":null}"#;
+        let apply = |expr: &str| JqPath::parse(expr).unwrap().apply(json).unwrap();
+        assert_eq!(apply(".number"), "12\n");
+        assert_eq!(apply(".data.viewer.login"), "octo\n");
+        assert_eq!(apply(r#".body // """#), "\n");
+        assert_eq!(apply(".missing"), "null\n");
+        assert!(JqPath::parse(".items[0]").is_err());
+    }
+
+    #[test]
+    fn pr_json_reshapes_connections_into_gh_output() {
+        let node = json!({
+            "number": 7,
+            "headRefName": "dank-spr/a",
+            "commits": { "nodes": [{ "commit": { "oid": "abc" } }] },
+            "labels": { "nodes": [{ "name": "bug" }] },
+        });
+        assert_eq!(
+            pr_json(&node, &["number", "headRefName", "commits", "labels"]),
+            json!({
+                "number": 7,
+                "headRefName": "dank-spr/a",
+                "commits": [{ "oid": "abc" }],
+                "labels": [{ "name": "bug" }],
+            })
+        );
+        assert!(pr_selection(&["number", "reviewRequests"]).is_err());
+    }
+
+    #[test]
+    fn pr_target_accepts_numbers_and_urls() {
+        let flags = HashMap::from([("--repo", "acme/widgets")]);
+        assert_eq!(
+            pr_target("#3", &flags).unwrap(),
+            PrTarget {
+                repo: Some(("acme".to_string(), "widgets".to_string())),
+                number: 3,
+            }
+        );
+        assert_eq!(
+            pr_target("https://github.com/other/repo/pull/9", &flags).unwrap(),
+            PrTarget {
+                repo: Some(("other".to_string(), "repo".to_string())),
+                number: 9,
+            }
+        );
+    }
+
+    #[test]
+    fn pr_view_web_opens_the_pr_page() {
+        assert_eq!(
+            parse_call(&["pr", "view", "5", "--web", "--repo", "o/r"]).unwrap(),
+            GhCall::PrOpen {
+                pr: PrTarget {
+                    repo: Some(("o".to_string(), "r".to_string())),
+                    number: 5,
+                },
+            }
+        );
+        assert!(parse_call(&["pr", "view", "5", "--web", "--json", "url"]).is_err());
+        assert_eq!(
+            web_url(None, "https://api.github.com"),
+            "https://github.com"
+        );
+        assert_eq!(
+            web_url(None, "https://ghe.example.com/api/v3"),
+            "https://ghe.example.com"
+        );
+        assert_eq!(
+            web_url(Some("https://ghe.example.com/"), "https://api.github.com"),
+            "https://ghe.example.com"
+        );
+    }
+
+    /// The argument vector of every `gh_ro`/`gh_rw` call site, with the `--repo` that
+    /// `with_gh_repo` appends to `gh pr` calls.
+    #[test]
+    fn every_gh_call_spr_makes_is_served() {
+        let mut mutation = crate::github::GraphqlMutation::new();
+        let id = mutation.var("ID!", "PR_1");
+        mutation.field(format!(
+            "m0: markPullRequestReadyForReview(input:{{pullRequestId:{id}}}){{ clientMutationId }}"
+        ));
+        let mutation = mutation.gh_args();
+        let calls: Vec<Vec<&str>> = vec![
+            vec![
+                "pr",
+                "list",
+                "--state",
+                "all",
+                "--search",
+                "head:dank-spr/a",
+                "--limit",
+                "10",
+                "--json",
+                crate::github::HEAD_SEARCH_FIELDS,
+            ],
+            vec!["pr", "list", "--state", "merged", "--limit", "100", "--json", "number,headRefName"],
+            vec!["pr", "list", "--state", "open", "--limit", "200", "--json", "headRefName"],
+            vec![
+                "pr",
+                "list",
+                "--state",
+                "open",
+                "--limit",
+                "200",
+                "--json",
+                "number,title,body,headRefName,baseRefName,isDraft",
+            ],
+            vec![
+                "pr",
+                "view",
+                "7",
+                "--json",
+                "number,title,state,headRefName,isCrossRepository,commits",
+            ],
+            vec!["pr", "view", "7", "--json", "comments,labels"],
+            vec!["pr", "view", "https://github.com/o/r/pull/7", "--json", "headRefName"],
+            vec!["pr", "view", "7", "--web"],
+            vec!["pr", "close", "#7", "--comment", "Folded into #6."],
+            vec!["pr", "edit", "#7", "--base", "dank-spr/a"],
+            vec!["pr", "comment", "7", "--body", "Ping"],
+            vec!["repo", "view", "--json", "defaultBranchRef", "-q", ".defaultBranchRef.name"],
+            vec![
+                "api",
+                "graphql",
+                "-f",
+                "query=query($owner:String!,$name:String!){ repository(owner:$owner,name:$name){ id } }",
+                "-F",
+                "owner=o",
+                "-F",
+                "name=r",
+                "-F",
+                "number=7",
+                "-f",
+                "l0=bug",
+            ],
+            mutation.iter().map(String::as_str).collect(),
+            vec![
+                "api",
+                "repos/o/r/pulls",
+                "-X",
+                "POST",
+                "-f",
+                "head=dank-spr/a",
+                "-f",
+                "base=main",
+                "-f",
+                "title=A",
+                "-f",
+                "body=B",
+                "-F",
+                "draft=true",
+                "--jq",
+                ".number",
+            ],
+            vec!["api", "repos/o/r/issues", "-X", "POST", "-f", "title=A", "-f", "body=B", "--jq", ".number"],
+            vec!["api", "repos/o/r/issues/7", "--jq", ".body // \"\""],
+            vec!["api", "repos/o/r/pulls/7", "-X", "PATCH", "-f", "body=B"],
+            vec![
+                "api",
+                "repos/o/r/pulls/7/requested_reviewers",
+                "-X",
+                "POST",
+                "-f",
+                "reviewers[]=alice",
+                "-f",
+                "team_reviewers[]=core",
+            ],
+            vec!["api", "repos/o/r/issues/7/labels", "-X", "POST", "-f", "labels[]=bug"],
+        ];
+        for mut call in calls {
+            if call[0] == "pr" {
+                call.extend(["--repo", "o/r"]);
+            }
+            if let Err(err) = check_supported(&call) {
+                panic!("gh {call:?}: {err:#}");
+            }
+        }
+    }
+
+    #[test]
+    fn curl_config_quotes_bodies_so_escapes_survive() {
+        let config = curl_config(
+            "POST",
+            "https://api.github.com/graphql",
+            "t0k",
+            Some(r#"{"body":"a \"q\"\nb"}"#),
+        );
+        assert!(config.contains(r#"header = "Authorization: Bearer t0k""#));
+        assert!(config.contains(r#"data-binary = "{\"body\":\"a \\\"q\\\"\\nb\"}""#));
+    }
+}
@@ -0,0 +1,99 @@
+//! Per-group directives carried in a group's first commit message.
+//!
+//! Directives are standalone tokens (`spr-draft`, `pr:base:<ref>`) that adjust how `spr update`
+//! publishes one group without changing the group's identity. Unlike group markers they never
+//! select a group; they are stripped from PR titles and bodies but kept in commit messages so they
+//! survive rewrites.
+
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// Create this group's PR as a draft.
+pub const DRAFT_DIRECTIVE: &str = "spr-draft";
+
+const DIRECTIVE_PATTERN: &str = r"(?i)(^|\s)(spr-draft)(\s|$)";
+
+/// Start a stack segment whose PR chain targets `<ref>` (see [`crate::stack_segments`]).
+const SEGMENT_BASE_PATTERN: &str = r"(?i)(^|\s)pr:base:(\S+)(\s|$)";
+
+static DIRECTIVE_REGEX: OnceLock<Regex> = OnceLock::new();
+static SEGMENT_BASE_REGEX: OnceLock<Regex> = OnceLock::new();
+
+fn directive_regex() -> &'static Regex {
+    DIRECTIVE_REGEX.get_or_init(|| {
+        Regex::new(DIRECTIVE_PATTERN).expect("group directive regex should compile")
+    })
+}
+
+fn segment_base_regex() -> &'static Regex {
+    SEGMENT_BASE_REGEX.get_or_init(|| {
+        Regex::new(SEGMENT_BASE_PATTERN).expect("segment base directive regex should compile")
+    })
+}
+
+/// Returns whether `token` (a `pr:` marker candidate payload) is a `pr:base:` directive.
+pub fn is_segment_base_payload(payload: &str) -> bool {
+    payload.len() > "base:".len() && payload[.."base:".len()].eq_ignore_ascii_case("base:")
+}
+
+/// Returns the `<ref>` of the first `pr:base:<ref>` directive in `text`.
+pub fn segment_base_directive(text: &str) -> Option<String> {
+    segment_base_regex()
+        .captures(text)
+        .and_then(|capture| capture.get(2))
+        .map(|value| value.as_str().to_string())
+}
+
+/// Returns whether `text` contains the standalone `spr-draft` directive.
+pub fn has_draft_directive(text: &str) -> bool {
+    directive_regex().captures_iter(text).any(|capture| {
+        capture
+            .get(2)
+            .is_some_and(|token| token.as_str().eq_ignore_ascii_case(DRAFT_DIRECTIVE))
+    })
+}
+
+/// Removes standalone directives from `text`, keeping the whitespace that surrounded them.
+pub fn strip_group_directives(text: &str) -> String {
+    let text = directive_regex().replace_all(text, "${1}${3}");
+    segment_base_regex()
+        .replace_all(&text, "${1}${3}")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{has_draft_directive, segment_base_directive, strip_group_directives};
+
+    #[test]
+    fn draft_directive_requires_a_standalone_token() {
+        assert!(has_draft_directive("feat: alpha pr:alpha spr-draft"));
+        assert!(has_draft_directive("feat: alpha\n\nSPR-DRAFT\nmore"));
+        assert!(!has_draft_directive("feat: alpha pr:alpha spr-drafty"));
+        assert!(!has_draft_directive("feat: mention-spr-draft"));
+    }
+
+    #[test]
+    fn strip_group_directives_removes_only_directive_tokens() {
+        assert_eq!(
+            strip_group_directives("feat: alpha spr-draft"),
+            "feat: alpha "
+        );
+        assert_eq!(
+            strip_group_directives("body\nspr-draft\ntail"),
+            "body\n\ntail"
+        );
+        assert_eq!(strip_group_directives("keep spr-drafty"), "keep spr-drafty");
+    }
+
+    #[test]
+    fn segment_base_directive_reads_and_strips_the_ref() {
+        let message = "fix: hotfix pr:hotfix pr:base:origin/release-2.0";
+        assert_eq!(
+            segment_base_directive(message),
+            Some("origin/release-2.0".to_string())
+        );
+        assert_eq!(segment_base_directive("fix: pr:hotfix"), None);
+        assert_eq!(strip_group_directives(message), "fix: hotfix pr:hotfix ");
+    }
+}
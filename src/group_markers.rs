@@ -7,12 +7,32 @@
 
 use anyhow::{bail, Result};
 use regex::{Captures, Regex};
+use std::collections::BTreeMap;
 use std::sync::OnceLock;
 
+use crate::run_settings::Setting;
+
 const CANDIDATE_MARKER_PATTERN: &str = r"(?i)(^|[^A-Za-z0-9_])(pr|branch):(\S*)";
 
 static CANDIDATE_MARKER_REGEX: OnceLock<Regex> = OnceLock::new();
 
+/// The `branch_overrides` config: `pr:<label>` to branch.
+pub(crate) static BRANCH_OVERRIDES: Setting<BTreeMap<String, String>> = Setting::new();
+
+/// Publish `pr:<label>` groups in this process to the mapped branch instead of `<prefix><label>`.
+///
+/// Lets a tagged group target a pre-existing branch (e.g. a long-lived integration branch) while
+/// keeping its `pr:<label>` selector; the rest of the stack keeps the prefix scheme.
+pub fn set_branch_overrides(overrides: &BTreeMap<String, String>) {
+    if !overrides.is_empty() {
+        BRANCH_OVERRIDES.set(overrides.clone());
+    }
+}
+
+fn branch_override(label: &str) -> Option<String> {
+    BRANCH_OVERRIDES.get()?.remove(label)
+}
+
 /// The exact one-of marker stored on a PR-group seed commit.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum GroupMarker {
@@ -37,11 +57,21 @@ impl GroupMarker {
 
     pub fn concrete_branch_name(&self, prefix: &str) -> String {
         match self {
-            Self::PrLabel(label) => format!("{prefix}{label}"),
+            Self::PrLabel(label) => {
+                branch_override(label).unwrap_or_else(|| format!("{prefix}{label}"))
+            }
             Self::BranchName(branch_name) => branch_name.clone(),
         }
     }
 
+    /// The named stack of a `pr:<stack>/<tag>` marker; other markers belong to no named stack.
+    pub fn stack_name(&self) -> Option<&str> {
+        match self {
+            Self::PrLabel(label) => crate::pr_labels::label_stack(label),
+            Self::BranchName(_) => None,
+        }
+    }
+
     pub fn is_ignore_pr_label(&self, ignore_tag: &str) -> bool {
         matches!(self, Self::PrLabel(label) if label == ignore_tag)
     }
@@ -115,6 +145,8 @@ fn marker_payload<'a>(capture: &'a Captures<'a>) -> &'a str {
 }
 
 /// Returns every `pr:` or `branch:` token candidate from `text`.
+///
+/// `pr:base:<ref>` tokens are group directives, not markers, and are skipped.
 pub fn candidate_group_markers(text: &str) -> Vec<CandidateGroupMarker> {
     candidate_marker_regex()
         .captures_iter(text)
@@ -122,6 +154,10 @@ pub fn candidate_group_markers(text: &str) -> Vec<CandidateGroupMarker> {
             kind: marker_kind(&capture),
             payload: marker_payload(&capture).to_string(),
         })
+        .filter(|candidate| {
+            candidate.kind != CandidateGroupMarkerKind::Pr
+                || !crate::group_directives::is_segment_base_payload(&candidate.payload)
+        })
         .collect()
 }
 
@@ -158,8 +194,26 @@ pub fn strip_valid_group_markers(text: &str) -> String {
 mod tests {
     use super::{
         candidate_group_markers, first_valid_group_marker, strip_valid_group_markers,
-        CandidateGroupMarkerKind, GroupMarker,
+        CandidateGroupMarkerKind, GroupMarker, BRANCH_OVERRIDES,
     };
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn branch_overrides_replace_prefixed_name_for_mapped_labels_only() {
+        let _overrides = BRANCH_OVERRIDES.scoped(BTreeMap::from([(
+            "overrideprobe".to_string(),
+            "integration/main".to_string(),
+        )]));
+
+        assert_eq!(
+            GroupMarker::PrLabel("overrideprobe".to_string()).concrete_branch_name("dank-spr/"),
+            "integration/main"
+        );
+        assert_eq!(
+            GroupMarker::PrLabel("overrideprobe2".to_string()).concrete_branch_name("dank-spr/"),
+            "dank-spr/overrideprobe2"
+        );
+    }
 
     #[test]
     fn candidate_markers_capture_pr_and_branch_tokens() {
@@ -174,6 +228,7 @@ mod tests {
         assert_eq!(markers[1].payload, "feature/login");
         assert_eq!(markers[2].kind, CandidateGroupMarkerKind::Branch);
         assert_eq!(markers[2].payload, "");
+        assert!(candidate_group_markers("fix: pr:base:origin/release").is_empty());
     }
 
     #[test]
@@ -7,6 +7,7 @@ pub const JSON_OUTPUT_SCHEMA_VERSION: u32 = 1;
 pub const EXIT_SUCCESS: i32 = 0;
 pub const EXIT_FAILURE: i32 = 1;
 pub const EXIT_SUSPENDED: i32 = 2;
+pub const EXIT_CANCELLED: i32 = 3;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "kebab-case")]
@@ -15,23 +16,61 @@ pub enum JsonCommand {
     Help,
     Version,
     Restack,
+    BaseSet,
+    Sync,
     AdoptPrefix,
     DropMergedPrefix,
+    RenamePrefix,
     Absorb,
     Move,
+    ApplyRemoteDirectives,
     FixPr,
+    ImportPr,
+    Fixup,
+    Amend,
+    SquashRange,
+    Pull,
+    Edit,
+    Split,
+    Fold,
+    RollbackPr,
+    Cherry,
+    Title,
+    Describe,
     ResolveStack,
     Resume,
+    Abort,
+    Init,
     Land,
     List,
     ListPr,
     ListCommit,
+    Files,
+    Diff,
+    RangeDiff,
+    Checkout,
+    Freeze,
+    Thaw,
+    Landed,
+    Backup,
+    Parse,
+    Stats,
     Status,
     SyncLocalBranches,
+    Verify,
     Update,
+    Flush,
     Prep,
     RelinkPrs,
+    TrackIssue,
     Cleanup,
+    Ready,
+    Draft,
+    Open,
+    Label,
+    Milestone,
+    Assign,
+    Automerge,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -295,6 +334,7 @@ pub fn command_for_raw_args(args: &[OsString]) -> JsonCommand {
                 || arg == "--local-pr-branches"
                 || arg == "--until"
                 || arg == "--exact"
+                || arg == "--remote"
                 || arg == "-b"
             {
                 skip_value = true;
@@ -304,36 +344,112 @@ pub fn command_for_raw_args(args: &[OsString]) -> JsonCommand {
                 return JsonCommand::ListCommit;
             } else if arg == "restack" {
                 return JsonCommand::Restack;
+            } else if arg == "base" {
+                return JsonCommand::BaseSet;
+            } else if arg == "sync" {
+                return JsonCommand::Sync;
             } else if arg == "adopt-prefix" {
                 return JsonCommand::AdoptPrefix;
             } else if arg == "drop-merged-prefix" {
                 return JsonCommand::DropMergedPrefix;
+            } else if arg == "rename-prefix" {
+                return JsonCommand::RenamePrefix;
             } else if arg == "absorb" {
                 return JsonCommand::Absorb;
             } else if arg == "move" || arg == "mv" {
                 return JsonCommand::Move;
+            } else if arg == "apply-remote-directives" {
+                return JsonCommand::ApplyRemoteDirectives;
             } else if arg == "fix-pr" || arg == "fix" {
                 return JsonCommand::FixPr;
+            } else if arg == "import-pr" {
+                return JsonCommand::ImportPr;
+            } else if arg == "fixup" {
+                return JsonCommand::Fixup;
+            } else if arg == "amend" {
+                return JsonCommand::Amend;
+            } else if arg == "squash-range" {
+                return JsonCommand::SquashRange;
+            } else if arg == "pull" {
+                return JsonCommand::Pull;
+            } else if arg == "edit" {
+                return JsonCommand::Edit;
+            } else if arg == "split" {
+                return JsonCommand::Split;
+            } else if arg == "fold" {
+                return JsonCommand::Fold;
+            } else if arg == "rollback-pr" {
+                return JsonCommand::RollbackPr;
+            } else if arg == "cherry" {
+                return JsonCommand::Cherry;
+            } else if arg == "title" {
+                return JsonCommand::Title;
+            } else if arg == "describe" {
+                return JsonCommand::Describe;
             } else if arg == "resolve-stack" {
                 return JsonCommand::ResolveStack;
-            } else if arg == "resume" {
+            } else if arg == "resume" || arg == "continue" {
                 return JsonCommand::Resume;
+            } else if arg == "abort" {
+                return JsonCommand::Abort;
+            } else if arg == "init" {
+                return JsonCommand::Init;
             } else if arg == "land" {
                 return JsonCommand::Land;
             } else if arg == "list" || arg == "ls" {
                 saw_list = true;
+            } else if arg == "files" {
+                return JsonCommand::Files;
+            } else if arg == "diff" {
+                return JsonCommand::Diff;
+            } else if arg == "checkout" {
+                return JsonCommand::Checkout;
+            } else if arg == "freeze" {
+                return JsonCommand::Freeze;
+            } else if arg == "thaw" {
+                return JsonCommand::Thaw;
+            } else if arg == "range-diff" {
+                return JsonCommand::RangeDiff;
+            } else if arg == "landed" {
+                return JsonCommand::Landed;
+            } else if arg == "backup" {
+                return JsonCommand::Backup;
+            } else if arg == "parse" {
+                return JsonCommand::Parse;
+            } else if arg == "stats" {
+                return JsonCommand::Stats;
             } else if arg == "status" || arg == "stat" {
                 return JsonCommand::Status;
             } else if arg == "sync-local-branches" {
                 return JsonCommand::SyncLocalBranches;
+            } else if arg == "verify" {
+                return JsonCommand::Verify;
             } else if arg == "update" || arg == "u" {
                 return JsonCommand::Update;
+            } else if arg == "flush" {
+                return JsonCommand::Flush;
             } else if arg == "prep" {
                 return JsonCommand::Prep;
             } else if arg == "relink-prs" {
                 return JsonCommand::RelinkPrs;
+            } else if arg == "track-issue" {
+                return JsonCommand::TrackIssue;
             } else if arg == "cleanup" || arg == "clean" {
                 return JsonCommand::Cleanup;
+            } else if arg == "ready" {
+                return JsonCommand::Ready;
+            } else if arg == "draft" {
+                return JsonCommand::Draft;
+            } else if arg == "open" {
+                return JsonCommand::Open;
+            } else if arg == "label" {
+                return JsonCommand::Label;
+            } else if arg == "milestone" {
+                return JsonCommand::Milestone;
+            } else if arg == "assign" {
+                return JsonCommand::Assign;
+            } else if arg == "automerge" {
+                return JsonCommand::Automerge;
             } else if !arg.starts_with('-') {
                 if saw_list {
                     return JsonCommand::List;
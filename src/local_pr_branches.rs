@@ -254,9 +254,10 @@ fn backup_tag_for_non_fast_forward(
         branch_name,
         short_sha(old_tip)
     );
+    let message = format!("spr backup (local-pr-branches) of {branch_name}");
     git_rw(
         execution_mode,
-        ["tag", "-f", &backup_tag, old_tip].as_slice(),
+        ["tag", "-f", "-a", "-m", &message, &backup_tag, old_tip].as_slice(),
     )
     .with_context(|| {
         format!(
@@ -558,7 +559,7 @@ mod tests {
         assert_eq!(actions[0].action, LocalPrBranchActionKind::Updated);
         let backup_tag = actions[0].backup_tag.as_deref().unwrap();
         assert_eq!(
-            rev_parse(&repo, &format!("refs/tags/{backup_tag}")),
+            rev_parse(&repo, &format!("refs/tags/{backup_tag}^{{commit}}")),
             old_tip
         );
         assert_eq!(branch_tip(&repo, "dank-spr/alpha"), Some(target_tip));
@@ -9,7 +9,7 @@ use serde::Serialize;
 
 use crate::commands::{RewriteCommandKind, RewriteSuspendedState};
 pub use crate::json_output::{
-    JsonCommand as MachineCommand, EXIT_SUSPENDED, JSON_OUTPUT_SCHEMA_VERSION,
+    JsonCommand as MachineCommand, EXIT_CANCELLED, EXIT_SUSPENDED, JSON_OUTPUT_SCHEMA_VERSION,
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
@@ -20,6 +20,15 @@ pub enum MachineRewriteCommandKind {
     Move,
     FixPr,
     AdoptPrefix,
+    ImportPr,
+    Edit,
+    Title,
+    Describe,
+    Split,
+    Fold,
+    Amend,
+    SquashRange,
+    Pull,
 }
 
 impl From<RewriteCommandKind> for MachineRewriteCommandKind {
@@ -30,6 +39,15 @@ impl From<RewriteCommandKind> for MachineRewriteCommandKind {
             RewriteCommandKind::Move => Self::Move,
             RewriteCommandKind::FixPr => Self::FixPr,
             RewriteCommandKind::AdoptPrefix => Self::AdoptPrefix,
+            RewriteCommandKind::ImportPr => Self::ImportPr,
+            RewriteCommandKind::Edit => Self::Edit,
+            RewriteCommandKind::Title => Self::Title,
+            RewriteCommandKind::Describe => Self::Describe,
+            RewriteCommandKind::Split => Self::Split,
+            RewriteCommandKind::Fold => Self::Fold,
+            RewriteCommandKind::Amend => Self::Amend,
+            RewriteCommandKind::SquashRange => Self::SquashRange,
+            RewriteCommandKind::Pull => Self::Pull,
         }
     }
 }
@@ -68,6 +86,8 @@ pub enum MachinePayload {
         #[serde(flatten)]
         details: Box<MachineSuspendedPayload>,
     },
+    /// The user declined a confirmation prompt; nothing changed.
+    Cancelled,
 }
 
 impl MachineOutput {
@@ -93,6 +113,14 @@ impl MachineOutput {
         }
     }
 
+    pub fn cancelled(command: MachineCommand) -> Self {
+        Self {
+            schema_version: JSON_OUTPUT_SCHEMA_VERSION,
+            command,
+            payload: MachinePayload::Cancelled,
+        }
+    }
+
     pub fn suspended(
         command: MachineCommand,
         suspended: RewriteSuspendedState,
@@ -144,10 +172,10 @@ impl MachineOutput {
     }
 
     pub fn exit_code(&self) -> i32 {
-        if matches!(self.payload, MachinePayload::Suspended { .. }) {
-            EXIT_SUSPENDED
-        } else {
-            crate::json_output::EXIT_SUCCESS
+        match self.payload {
+            MachinePayload::Suspended { .. } => EXIT_SUSPENDED,
+            MachinePayload::Cancelled => EXIT_CANCELLED,
+            MachinePayload::Completed { .. } => crate::json_output::EXIT_SUCCESS,
         }
     }
 }
@@ -222,6 +250,16 @@ mod tests {
         assert_eq!(json["local_pr_branch_actions"], serde_json::json!([]));
     }
 
+    #[test]
+    fn cancelled_output_reports_cancelled_with_its_own_exit_code() {
+        let output = MachineOutput::cancelled(MachineCommand::Land);
+        let json = serde_json::to_value(&output).unwrap();
+
+        assert_eq!(json["command"], "land");
+        assert_eq!(json["result"], "cancelled");
+        assert_eq!(output.exit_code(), crate::json_output::EXIT_CANCELLED);
+    }
+
     #[test]
     fn completed_output_omits_destination_branch_when_absent() {
         let output = MachineOutput::completed_with_local_pr_branch_actions(
@@ -11,28 +11,48 @@ mod adopt_prefix_output;
 mod branch_names;
 mod cli;
 mod commands;
+mod commit_lint;
+mod concurrency;
 mod config;
 mod execution;
+mod forge;
 mod format;
 mod git;
 mod github;
+mod github_api;
+mod gitlab;
+mod group_directives;
 mod group_markers;
 mod json_output;
 mod limit;
 mod local_pr_branches;
 mod machine_output;
 mod maintenance_output;
+mod offline_queue;
 mod parsing;
+mod path_attributes;
 mod pr_base_chain;
 mod pr_labels;
+mod pr_template;
+mod pre_push_check;
+mod project_status;
+mod protected_paths;
 mod read_only_output;
+mod remote_state;
 mod restack_output;
+mod review_guide;
+mod reviewers;
+mod run_settings;
 mod selectors;
 mod stack_metadata;
+mod stack_segments;
 mod summary_output;
 #[cfg(test)]
 mod test_support;
+mod timing;
+mod title_transforms;
 mod update_output;
+mod update_progress;
 
 fn resolve_update_pr_limit(
     groups: &[crate::parsing::Group],
@@ -72,25 +92,63 @@ fn resolve_update_pr_limit(
 
 fn command_requires_gh(cmd: &crate::cli::Cmd) -> bool {
     match cmd {
-        crate::cli::Cmd::Restack { .. }
-        | crate::cli::Cmd::AdoptPrefix { .. }
+        crate::cli::Cmd::AdoptPrefix { .. }
         | crate::cli::Cmd::Absorb { .. }
         | crate::cli::Cmd::Resume { .. }
+        | crate::cli::Cmd::Abort { .. }
+        | crate::cli::Cmd::Init { .. }
         | crate::cli::Cmd::SyncLocalBranches
-        | crate::cli::Cmd::FixPr { .. } => false,
+        | crate::cli::Cmd::FixPr { .. }
+        | crate::cli::Cmd::Files { .. }
+        | crate::cli::Cmd::Diff { .. }
+        | crate::cli::Cmd::RangeDiff { .. }
+        | crate::cli::Cmd::Checkout { .. }
+        | crate::cli::Cmd::Freeze { .. }
+        | crate::cli::Cmd::Thaw { .. }
+        | crate::cli::Cmd::Landed { .. }
+        | crate::cli::Cmd::Parse
+        | crate::cli::Cmd::Backup { .. } => false,
         crate::cli::Cmd::ResolveStack { target } => target
             .as_deref()
             .map(crate::commands::looks_like_pr_url)
             .unwrap_or(false),
-        crate::cli::Cmd::Update { no_pr, .. } => !*no_pr,
+        crate::cli::Cmd::Update { no_pr, offline, .. } => !*no_pr && !*offline,
+        crate::cli::Cmd::Restack { update, .. }
+        | crate::cli::Cmd::Fixup { update, .. }
+        | crate::cli::Cmd::Amend { update, .. }
+        | crate::cli::Cmd::SquashRange { update, .. }
+        | crate::cli::Cmd::Pull { update, .. }
+        | crate::cli::Cmd::Edit { update, .. }
+        | crate::cli::Cmd::Split { update, .. } => *update,
+        crate::cli::Cmd::Fold { close, update, .. } => *close || *update,
+        crate::cli::Cmd::Land { local_only, .. } => !*local_only,
         crate::cli::Cmd::List { .. }
-        | crate::cli::Cmd::Status
+        | crate::cli::Cmd::Status { .. }
+        | crate::cli::Cmd::Base { .. }
+        | crate::cli::Cmd::Sync { .. }
         | crate::cli::Cmd::Prep { .. }
         | crate::cli::Cmd::DropMergedPrefix { .. }
-        | crate::cli::Cmd::Land { .. }
+        | crate::cli::Cmd::RenamePrefix { .. }
         | crate::cli::Cmd::RelinkPrs { .. }
+        | crate::cli::Cmd::TrackIssue { .. }
+        | crate::cli::Cmd::Flush { .. }
+        | crate::cli::Cmd::Stats { .. }
+        | crate::cli::Cmd::Verify { .. }
+        | crate::cli::Cmd::ImportPr { .. }
+        | crate::cli::Cmd::RollbackPr { .. }
+        | crate::cli::Cmd::Cherry { .. }
+        | crate::cli::Cmd::Title { .. }
+        | crate::cli::Cmd::Describe { .. }
         | crate::cli::Cmd::Cleanup { .. }
-        | crate::cli::Cmd::Move { .. } => true,
+        | crate::cli::Cmd::Move { .. }
+        | crate::cli::Cmd::ApplyRemoteDirectives { .. }
+        | crate::cli::Cmd::Ready { .. }
+        | crate::cli::Cmd::Draft { .. }
+        | crate::cli::Cmd::Open { .. }
+        | crate::cli::Cmd::Label { .. }
+        | crate::cli::Cmd::Milestone { .. }
+        | crate::cli::Cmd::Assign { .. }
+        | crate::cli::Cmd::Automerge { .. } => true,
     }
 }
 
@@ -102,6 +160,7 @@ enum CommandOutput {
     Machine(crate::machine_output::MachineOutput),
     ReadOnly(crate::read_only_output::ReadOnlyOutput),
     RestackPreview(crate::restack_output::RestackPreviewOutput),
+    RestackCheck(crate::restack_output::RestackCheckOutput),
     ResolveStack(crate::commands::ResolveStackOutput),
     Update(crate::update_output::UpdateOutput),
     Maintenance(Box<crate::maintenance_output::MaintenanceOutput>),
@@ -111,11 +170,56 @@ enum CommandOutput {
 fn init_tools(needs_gh: bool) -> Result<()> {
     crate::git::ensure_tool("git")?;
     if needs_gh {
-        crate::git::ensure_tool("gh")?;
+        init_github_client()?;
     }
     Ok(())
 }
 
+/// Check that whichever GitHub client this run uses, `gh` or the token client, can run.
+fn init_github_client() -> Result<()> {
+    if crate::github_api::enabled() {
+        crate::github_api::ensure_ready()
+    } else {
+        crate::git::ensure_tool("gh")
+    }
+}
+
+/// Whether `cmd` only reaches the forge through the publish and status paths that
+/// [`crate::gitlab`] implements.
+fn command_supports_gitlab(cmd: &crate::cli::Cmd) -> bool {
+    !command_requires_gh(cmd)
+        || matches!(
+            cmd,
+            crate::cli::Cmd::Update { .. }
+                | crate::cli::Cmd::List { .. }
+                | crate::cli::Cmd::Status { .. }
+                | crate::cli::Cmd::Restack { .. }
+                | crate::cli::Cmd::Fixup { .. }
+                | crate::cli::Cmd::Amend { .. }
+                | crate::cli::Cmd::SquashRange { .. }
+                | crate::cli::Cmd::Pull { .. }
+                | crate::cli::Cmd::Edit { .. }
+                | crate::cli::Cmd::Split { .. }
+        )
+}
+
+/// Check the configured forge's CLI once the config has selected the forge.
+fn init_forge_tool(cmd: &crate::cli::Cmd) -> Result<()> {
+    if !command_requires_gh(cmd) {
+        return Ok(());
+    }
+    let forge = crate::forge::forge();
+    if forge == crate::forge::Forge::Gitlab && !command_supports_gitlab(cmd) {
+        anyhow::bail!(
+            "This command is GitHub-only; with `forge: gitlab`, spr supports `update`, `list`, `status`, and `--update` on rewrite commands."
+        );
+    }
+    match forge {
+        crate::forge::Forge::Github => init_github_client(),
+        crate::forge::Forge::Gitlab => crate::git::ensure_tool(forge.cli_tool()),
+    }
+}
+
 fn set_dry_run_env(execution_mode: ExecutionMode, assume_existing_prs: bool) {
     if execution_mode == ExecutionMode::DryRun {
         std::env::set_var("SPR_DRY_RUN", "1");
@@ -146,6 +250,46 @@ fn refresh_metadata_after_update(
     Ok(MetadataRefreshOutcome::Refreshed)
 }
 
+/// Warn about groups whose PR branch stack metadata records as published from another stack.
+///
+/// This catches switching to a second stack under the same prefix and running `spr update`/`spr land`
+/// there, which would otherwise overwrite the first stack's PR branches. `from` is the stack being
+/// published; `HEAD` resolves to the current branch, and detached checkouts skip the check.
+fn source_branch_mismatch_warnings(
+    from: &str,
+    prefix: &str,
+    groups: &[crate::parsing::Group],
+) -> Result<Vec<String>> {
+    let Some(repo_path) = crate::git::repo_root()? else {
+        return Ok(Vec::new());
+    };
+    let stack_branch = if from == "HEAD" {
+        match crate::stack_metadata::current_branch_or_none(&repo_path)? {
+            Some(branch) => branch,
+            None => return Ok(Vec::new()),
+        }
+    } else {
+        from.to_string()
+    };
+    let Some(metadata) = crate::stack_metadata::load_metadata_for_repo_path(&repo_path)? else {
+        return Ok(Vec::new());
+    };
+    let pr_branches: Vec<String> = crate::branch_names::group_branch_identities(groups, prefix)?
+        .into_iter()
+        .map(|identity| identity.exact)
+        .collect();
+    Ok(
+        crate::stack_metadata::foreign_stack_owners(&metadata, &stack_branch, &pr_branches)
+            .into_iter()
+            .map(|(pr_branch, owner)| {
+                format!(
+                    "{pr_branch} was last published from stack branch `{owner}`, not `{stack_branch}`; publishing it from here overwrites that stack's PR"
+                )
+            })
+            .collect(),
+    )
+}
+
 /// Change to the requested working directory before config discovery or repo-scoped commands.
 fn apply_working_directory_override(path: Option<&Path>) -> Result<()> {
     if let Some(path) = path {
@@ -157,33 +301,245 @@ fn apply_working_directory_override(path: Option<&Path>) -> Result<()> {
 
 /// Resolve the base branch, branch prefix, and ignore tag with explicit precedence.
 ///
-/// Base resolution follows: CLI `--base` → merged config `base` → discovery
-/// via `origin/HEAD`. Unlike other defaults, base discovery is not optional:
-/// if `origin/HEAD` cannot be resolved, this returns an error so the failure is
-/// visible and actionable to the user.
+/// The prefix and ignore tag come from their CLI flags, else from the merged config.
+///
+/// Base resolution follows: CLI `--base` → merged config `base` → the current
+/// branch's `@{upstream}` (see [`crate::git::discover_upstream_base`]) →
+/// discovery via `origin/HEAD`. Unlike other defaults, base discovery is not
+/// optional: if `origin/HEAD` cannot be resolved, this returns an error so the
+/// failure is visible and actionable to the user.
 fn resolve_base_prefix(
     cfg: &crate::config::Config,
     base: Option<String>,
     prefix: Option<String>,
+    ignore_tag: Option<String>,
 ) -> Result<(String, String, String)> {
+    let prefix = crate::config::normalize_prefix(&prefix.unwrap_or_else(|| cfg.prefix.clone()))?;
     let base = match base {
         Some(base) => base,
         None => {
-            if cfg.base.trim().is_empty() {
-                crate::git::discover_origin_head_base()?
-            } else {
+            if !cfg.base.trim().is_empty() {
                 cfg.base.clone()
+            } else if let Some(upstream) = crate::git::discover_upstream_base(&prefix)? {
+                upstream
+            } else {
+                crate::git::discover_remote_head_base()?
             }
         }
     };
-    let prefix = crate::config::normalize_prefix(&prefix.unwrap_or_else(|| cfg.prefix.clone()))?;
-    let mut ignore_tag = cfg.ignore_tag.clone();
-    if ignore_tag.trim().is_empty() {
-        ignore_tag = "ignore".to_string();
+    // A base such as `upstream/main` on another remote makes that remote host the PRs, while
+    // PR branches still push to the configured remote
+    if let Some(base_remote) = crate::git::foreign_base_remote(&base)? {
+        crate::git::set_base_remote(&base_remote);
     }
+    let ignore_tag =
+        crate::config::normalize_ignore_tag(ignore_tag.as_deref().unwrap_or(&cfg.ignore_tag))?;
     Ok((base, prefix, ignore_tag))
 }
 
+/// `--from` ref of the commands that read the local stack from another branch on request.
+fn stack_from_arg(command: &crate::cli::Cmd) -> Option<&str> {
+    match command {
+        crate::cli::Cmd::List { from, .. }
+        | crate::cli::Cmd::Land { from, .. }
+        | crate::cli::Cmd::Restack { from, .. } => from.as_deref(),
+        crate::cli::Cmd::Prep { from_ref, .. } => from_ref.as_deref(),
+        _ => None,
+    }
+}
+
+/// Apply `--path-scope`/`--first-parent` (falling back to config), `--stack`, and `--from` to
+/// group derivation, and the configured `branch_overrides` to group branch naming.
+fn apply_group_derivation_options(cli: &crate::cli::Cli, cfg: &crate::config::Config) {
+    crate::parsing::set_path_scope(if cli.path_scope.is_empty() {
+        &cfg.path_scope
+    } else {
+        &cli.path_scope
+    });
+    crate::parsing::set_first_parent(cli.first_parent || cfg.first_parent);
+    crate::parsing::set_active_stack(cli.stack.as_deref());
+    crate::parsing::set_stack_from(stack_from_arg(&cli.cmd));
+    crate::group_markers::set_branch_overrides(&cfg.branch_overrides);
+    crate::review_guide::set_enabled(cfg.review_guide);
+    crate::path_attributes::set_generated_paths(&cfg.generated_paths);
+    crate::reviewers::set_configured_reviewers(&cfg.reviewers);
+    crate::protected_paths::set_configured_rules(&cfg.protected_paths);
+    crate::commands::set_configured_attachments(cfg.milestone.as_deref(), cfg.project.as_deref());
+    crate::pr_template::set_enabled(cfg.body_template);
+    crate::pre_push_check::set_command(cfg.pre_push_check.as_deref());
+    crate::forge::set_forge(cfg.forge);
+    crate::git::set_git_backend(cfg.git_backend);
+    crate::github_api::set_github_client(cfg.github_client);
+}
+
+/// Publish the current stack (`HEAD`, or the `--from` ref) after a completed local rewrite, as
+/// `spr update` would.
+///
+/// Local PR branch sync is left to the rewrite command itself, which already reconciled it.
+/// `limit` scopes publication to the bottom groups like `spr update pr --to`.
+#[allow(clippy::too_many_arguments)]
+fn publish_current_stack_after_rewrite(
+    context: &crate::stack_metadata::RefreshMetadataContext,
+    limit: Option<crate::limit::Limit>,
+    execution_mode: ExecutionMode,
+    pr_description_mode: crate::config::PrDescriptionMode,
+    list_order: crate::config::ListOrder,
+    branch_reuse_guard_days: u32,
+    commit_lint: &crate::commit_lint::CommitLintConfig,
+    title_transforms: &crate::title_transforms::TitleTransformConfig,
+) -> Result<()> {
+    let from = crate::parsing::stack_from();
+    publish_stack_at(
+        context,
+        &from,
+        &crate::git::stack_ref_name(&from)?,
+        limit,
+        execution_mode,
+        pr_description_mode,
+        list_order,
+        branch_reuse_guard_days,
+        commit_lint,
+        title_transforms,
+        false,
+    )
+}
+
+/// Publish the stack whose tip is `from`, recorded as `stack_branch`, through the same publish
+/// routine as `spr update`; see [`publish_current_stack_after_rewrite`]. New PRs open as drafts
+/// when `draft_new_prs` is set.
+#[allow(clippy::too_many_arguments)]
+fn publish_stack_at(
+    context: &crate::stack_metadata::RefreshMetadataContext,
+    from: &str,
+    stack_branch: &str,
+    limit: Option<crate::limit::Limit>,
+    execution_mode: ExecutionMode,
+    pr_description_mode: crate::config::PrDescriptionMode,
+    list_order: crate::config::ListOrder,
+    branch_reuse_guard_days: u32,
+    commit_lint: &crate::commit_lint::CommitLintConfig,
+    title_transforms: &crate::title_transforms::TitleTransformConfig,
+    draft_new_prs: bool,
+) -> Result<()> {
+    let (merge_base, mut leading_ignored, mut all_groups) =
+        crate::parsing::derive_groups_between_with_ignored(
+            &context.base,
+            from,
+            &context.ignore_tag,
+        )?;
+    if all_groups.is_empty() {
+        return Ok(());
+    }
+    crate::stack_segments::isolate_active_stack(
+        &merge_base,
+        &format!("{merge_base}..{from}"),
+        &mut leading_ignored,
+        &mut all_groups,
+    )?;
+    crate::commands::ensure_not_frozen(&context.prefix, false, "spr update", execution_mode)?;
+    let (groups, skipped_handles) =
+        crate::parsing::split_groups_for_update(&leading_ignored, all_groups);
+    crate::branch_names::group_branch_identities(&groups, &context.prefix)?;
+    for warning in crate::commit_lint::check_groups(&groups, commit_lint)? {
+        tracing::warn!("{warning}");
+    }
+    crate::commands::build_from_groups(
+        &context.base,
+        &context.prefix,
+        stack_branch,
+        &skipped_handles,
+        false,
+        execution_mode,
+        pr_description_mode,
+        limit,
+        groups,
+        list_order,
+        false,
+        branch_reuse_guard_days,
+        crate::config::LocalPrBranchSyncPolicy::Off,
+        title_transforms,
+        draft_new_prs,
+    )?;
+    if execution_mode == ExecutionMode::Apply
+        && refresh_metadata_after_update(context)? == MetadataRefreshOutcome::SkippedDetached
+    {
+        tracing::warn!("{DETACHED_METADATA_REFRESH_WARNING}");
+    }
+    Ok(())
+}
+
+/// Record an `spr update --offline` run for `spr flush` instead of contacting GitHub.
+///
+/// Only the current branch's `HEAD` stack can be queued. The entry journals the branch pushes and
+/// PR edits planned for `groups` (the publishable prefix), which `spr flush` later publishes.
+#[allow(clippy::too_many_arguments)]
+fn queue_offline_update(
+    from: &str,
+    base: &str,
+    prefix: &str,
+    groups: Vec<crate::parsing::Group>,
+    limit: Option<crate::limit::Limit>,
+    pr_description_mode: crate::config::PrDescriptionMode,
+    title_transforms: &crate::title_transforms::TitleTransformConfig,
+    draft: bool,
+    execution_mode: ExecutionMode,
+) -> Result<()> {
+    if from != "HEAD" {
+        return Err(anyhow::anyhow!(
+            "`spr update --offline` only supports `--from HEAD`; check out `{}` first.",
+            from
+        ));
+    }
+    let stack_branch = crate::git::git_current_branch()?;
+    if stack_branch == "HEAD" {
+        return Err(anyhow::anyhow!(
+            "HEAD is detached; check out the stack branch before running `spr update --offline`."
+        ));
+    }
+    let head = crate::git::git_ro(["rev-parse", "HEAD"].as_slice())?;
+    let pr_limit = limit.map(|crate::limit::Limit::ByPr(count)| count);
+    let plan = crate::offline_queue::plan_offline_update(
+        base,
+        prefix,
+        &crate::limit::apply_limit_groups(groups, limit)?,
+        title_transforms,
+    )?;
+    let dry_prefix = if execution_mode == ExecutionMode::DryRun {
+        "DRY-RUN: would queue"
+    } else {
+        "Queued"
+    };
+    for push in &plan.0 {
+        tracing::info!("{dry_prefix} push {} -> {}", push.branch, push.sha);
+    }
+    for edit in &plan.1 {
+        tracing::info!(
+            "{dry_prefix} PR {} -> {}: {}",
+            edit.head,
+            edit.base,
+            edit.title
+        );
+    }
+    if execution_mode == ExecutionMode::DryRun {
+        return Ok(());
+    }
+    crate::offline_queue::enqueue_update(
+        &crate::git::git_common_dir()?,
+        crate::offline_queue::queued_update(
+            &stack_branch,
+            head.trim(),
+            pr_limit,
+            pr_description_mode,
+            draft,
+            plan,
+        )?,
+    )?;
+    tracing::info!(
+        "Queued PR updates for `{stack_branch}`; run `spr flush` once GitHub is reachable."
+    );
+    Ok(())
+}
+
 fn ensure_rewrite_completed(
     output_format: crate::cli::OutputFormat,
     command_name: &str,
@@ -267,7 +623,14 @@ fn sync_local_pr_branches_for_current_stack(
     prefix: &str,
     ignore_tag: &str,
 ) -> Result<Vec<crate::local_pr_branches::LocalPrBranchAction>> {
-    sync_local_pr_branches_for_branch(policy, execution_mode, base, prefix, ignore_tag, "HEAD")
+    sync_local_pr_branches_for_branch(
+        policy,
+        execution_mode,
+        base,
+        prefix,
+        ignore_tag,
+        &crate::parsing::stack_from(),
+    )
 }
 
 fn sync_local_pr_branches_for_branch(
@@ -313,12 +676,14 @@ fn read_only_pr_list_output(
     prefix: &str,
     ignore_tag: &str,
     local_pr_branch_policy: crate::config::LocalPrBranchSyncPolicy,
+    since: Option<&str>,
 ) -> std::result::Result<crate::read_only_output::ReadOnlyOutput, crate::json_output::ErrorOutput> {
     match crate::commands::collect_pr_list_data_for_json(
         base,
         prefix,
         ignore_tag,
         local_pr_branch_policy,
+        since,
     ) {
         Ok(data) => Ok(crate::read_only_output::pr_list(command, data)),
         Err(crate::commands::ReadOnlyQueryError::SyntheticBranchNameCollision(collision)) => Err(
@@ -336,12 +701,14 @@ fn read_only_commit_list_output(
     prefix: &str,
     ignore_tag: &str,
     local_pr_branch_policy: crate::config::LocalPrBranchSyncPolicy,
+    since: Option<&str>,
 ) -> std::result::Result<crate::read_only_output::ReadOnlyOutput, crate::json_output::ErrorOutput> {
     match crate::commands::collect_commit_list_data_for_json(
         base,
         prefix,
         ignore_tag,
         local_pr_branch_policy,
+        since,
     ) {
         Ok(data) => Ok(crate::read_only_output::commit_list(command, data)),
         Err(crate::commands::ReadOnlyQueryError::SyntheticBranchNameCollision(collision)) => Err(
@@ -355,12 +722,38 @@ fn read_only_commit_list_output(
 
 fn run_cli(cli: crate::cli::Cli, output_format: crate::cli::OutputFormat) -> Result<CommandOutput> {
     apply_working_directory_override(cli.cd.as_deref())?;
-    init_tools(command_requires_gh(&cli.cmd))?;
+    // Verify talks to GitHub before the config is read; other commands check the configured
+    // forge's CLI below
+    init_tools(matches!(cli.cmd, crate::cli::Cmd::Verify { .. }))?;
+    if let crate::cli::Cmd::Abort { path } = &cli.cmd {
+        let resume_path = match path {
+            Some(path) => path.clone(),
+            None => crate::commands::pending_resume_path()?,
+        };
+        crate::commands::abort_rewrite(&resume_path)?;
+        return Ok(CommandOutput::Machine(
+            crate::machine_output::MachineOutput::completed_with_local_pr_branch_actions(
+                crate::machine_output::MachineCommand::Abort,
+                Vec::new(),
+            ),
+        ));
+    }
+
+    if let crate::cli::Cmd::Init { global, yes, force } = &cli.cmd {
+        crate::commands::init_config(*global, *yes, *force)?;
+        return Ok(CommandOutput::Machine(
+            crate::machine_output::MachineOutput::completed_with_local_pr_branch_actions(
+                crate::machine_output::MachineCommand::Init,
+                Vec::new(),
+            ),
+        ));
+    }
+
     if let crate::cli::Cmd::Resume { path, .. } = &cli.cmd {
-        let resume_path = if path.is_absolute() {
-            path.clone()
-        } else {
-            std::env::current_dir()?.join(path)
+        let resume_path = match path {
+            Some(path) if path.is_absolute() => path.clone(),
+            Some(path) => std::env::current_dir()?.join(path),
+            None => crate::commands::pending_resume_path()?,
         };
         let resume_context = crate::commands::resume_context(&resume_path)?;
         std::env::set_current_dir(&resume_context.original_worktree_root).with_context(|| {
@@ -377,11 +770,17 @@ fn run_cli(cli: crate::cli::Cli, output_format: crate::cli::OutputFormat) -> Res
         } else {
             match crate::config::load_config() {
                 Ok(cfg) => {
+                    apply_group_derivation_options(&cli, &cfg);
                     let policy = explicit_local_pr_branch_policy.unwrap_or(cfg.local_pr_branches);
                     if policy == crate::config::LocalPrBranchSyncPolicy::Off {
                         None
                     } else {
-                        match resolve_base_prefix(&cfg, cli.base.clone(), cli.prefix.clone()) {
+                        match resolve_base_prefix(
+                            &cfg,
+                            cli.base.clone(),
+                            cli.prefix.clone(),
+                            cli.ignore_tag.clone(),
+                        ) {
                             Ok(context) => Some((policy, context)),
                             Err(err) if explicit_local_pr_branch_policy.is_none() => {
                                 tracing::warn!(
@@ -440,9 +839,79 @@ fn run_cli(cli: crate::cli::Cli, output_format: crate::cli::OutputFormat) -> Res
         )?));
     }
 
+    // Verification only reads PRs, so it runs without a stack base (CI checkouts often lack one).
+    if let crate::cli::Cmd::Verify { ci, pr } = cli.cmd {
+        let data =
+            crate::commands::collect_verify_data(crate::commands::resolve_verify_pr(pr, ci)?)?;
+        if output_format == crate::cli::OutputFormat::Json {
+            return Ok(CommandOutput::ReadOnly(crate::read_only_output::verify(
+                crate::json_output::JsonCommand::Verify,
+                data,
+            )));
+        }
+        crate::commands::report_verify(&data, ci)?;
+        return Ok(CommandOutput::None);
+    }
+
     let cfg = crate::config::load_config()?;
-    let (base, prefix, ignore_tag) =
-        resolve_base_prefix(&cfg, cli.base.clone(), cli.prefix.clone())?;
+    // Base discovery reads the remote's HEAD, so the remote must be set first
+    crate::git::set_remote(cli.remote.as_deref().unwrap_or(&cfg.remote));
+    let (base, prefix, ignore_tag) = resolve_base_prefix(
+        &cfg,
+        cli.base.clone(),
+        cli.prefix.clone(),
+        cli.ignore_tag.clone(),
+    )?;
+    if let Some(stack) = cli.stack.as_deref() {
+        if let Err(err) = crate::pr_labels::validate_label(stack) {
+            anyhow::bail!("invalid --stack `{stack}`: {err}");
+        }
+        if stack.contains('/') {
+            anyhow::bail!("invalid --stack `{stack}`: a stack name cannot contain `/`");
+        }
+    }
+    apply_group_derivation_options(&cli, &cfg);
+    init_forge_tool(&cli.cmd)?;
+    crate::git::set_no_fetch(cli.no_fetch);
+    // These read the base without fetching it, so point out a remote-tracking base gone stale
+    if matches!(
+        cli.cmd,
+        crate::cli::Cmd::Update { .. }
+            | crate::cli::Cmd::List { .. }
+            | crate::cli::Cmd::Status { .. }
+    ) {
+        if let Some(warning) = crate::git::base_fetch_warning(&base, cfg.fetch_stale_after_hours)? {
+            tracing::warn!("{warning}");
+        }
+    }
+    // A stack rooted at a release tag or pinned commit diffs against that root, but its PRs
+    // need a real branch to target
+    let landing_branch = if crate::git::is_pinned_base(&base)? {
+        let landing_branch = cli
+            .landing_branch
+            .clone()
+            .or_else(|| cfg.landing_branch.clone());
+        match &landing_branch {
+            Some(landing_branch) => crate::git::set_pinned_base(&base, landing_branch),
+            None if command_requires_gh(&cli.cmd) => anyhow::bail!(
+                "Base `{base}` is a tag or commit, not a branch; set `landing_branch` in config or pass --landing-branch <BRANCH> to choose the branch its PRs target"
+            ),
+            None => {}
+        }
+        landing_branch
+    } else {
+        None
+    };
+    if !cli.allow_base {
+        crate::config::ensure_allowed_base(
+            landing_branch.as_deref().unwrap_or(&base),
+            &crate::git::remote(),
+            &cfg.allowed_bases,
+        )?;
+    }
+    if cli.linearize {
+        crate::commands::linearize_stack(&base, cli.cmd.execution_mode(), cfg.dirty_worktree)?;
+    }
     let metadata_refresh_context = crate::stack_metadata::RefreshMetadataContext {
         base: base.clone(),
         prefix: prefix.clone(),
@@ -453,6 +922,10 @@ fn run_cli(cli: crate::cli::Cli, output_format: crate::cli::OutputFormat) -> Res
     let dirty_worktree_policy = cfg.dirty_worktree;
     let list_order = cfg.list_order;
     let branch_reuse_guard_days = cfg.branch_reuse_guard_days;
+    let commit_lint = cfg.commit_lint.clone();
+    let title_transforms = cfg.title_transforms.clone();
+    let projects = cfg.projects.clone();
+    let review_ping_after_hours = cfg.review_ping_after_hours;
     let local_pr_branch_policy = cli.local_pr_branches.unwrap_or(cfg.local_pr_branches);
     match cli.cmd {
         crate::cli::Cmd::Update {
@@ -462,18 +935,51 @@ fn run_cli(cli: crate::cli::Cli, output_format: crate::cli::OutputFormat) -> Res
             assume_existing_prs,
             pr_description_mode: pr_description_mode_override,
             allow_branch_reuse,
+            draft,
+            offline,
+            plan_out,
+            thaw,
             dry_run,
             extent,
         } => {
             let execution_mode = ExecutionMode::from(dry_run);
             set_dry_run_env(execution_mode, assume_existing_prs);
             let pr_description_mode = pr_description_mode_override.unwrap_or(pr_description_mode);
+            let draft = draft || (cfg.draft && !no_pr);
             if restack {
                 Err(anyhow::anyhow!(
                     "`spr update --restack` is deprecated. Use `spr restack --after N` instead."
                 ))
             } else {
-                let (_merge_base, leading_ignored, all_groups) =
+                crate::commands::ensure_not_frozen(&prefix, thaw, "spr update", execution_mode)?;
+                if !no_pr && !offline && from == "HEAD" {
+                    let retargeted =
+                        crate::commands::collect_auto_retargeted_prs(&base, &prefix, &ignore_tag)?;
+                    if !retargeted.is_empty() {
+                        for line in crate::commands::auto_retarget_warnings(&retargeted) {
+                            tracing::warn!("{line}");
+                        }
+                        tracing::info!("Dropping the merged PR group(s) before publishing so each PR shows only its own changes again.");
+                        let outcome = crate::commands::drop_merged_prefix(
+                            &metadata_refresh_context,
+                            false,
+                            execution_mode,
+                            restack_conflict_policy,
+                            dirty_worktree_policy,
+                        )?;
+                        if outcome != crate::commands::RewriteCommandOutcome::Completed {
+                            return Ok(CommandOutput::Machine(ensure_rewrite_completed(
+                                output_format,
+                                "spr update",
+                                crate::machine_output::MachineCommand::Update,
+                                outcome,
+                                None,
+                                Vec::new(),
+                            )?));
+                        }
+                    }
+                }
+                let (merge_base, mut leading_ignored, mut all_groups) =
                     crate::parsing::derive_groups_between_with_ignored(&base, &from, &ignore_tag)?;
                 if all_groups.is_empty() {
                     return Err(anyhow::anyhow!(
@@ -482,9 +988,25 @@ fn run_cli(cli: crate::cli::Cli, output_format: crate::cli::OutputFormat) -> Res
                         from
                     ));
                 }
+                crate::stack_segments::isolate_active_stack(
+                    &merge_base,
+                    &format!("{merge_base}..{from}"),
+                    &mut leading_ignored,
+                    &mut all_groups,
+                )?;
                 let (groups, skipped_handles) =
                     crate::parsing::split_groups_for_update(&leading_ignored, all_groups);
                 crate::branch_names::group_branch_identities(&groups, &prefix)?;
+                let lint_warnings = crate::commit_lint::check_groups(&groups, &commit_lint)?;
+                let mut source_warnings = source_branch_mismatch_warnings(&from, &prefix, &groups)?;
+                source_warnings.extend(crate::commands::pushed_ahead_warnings(
+                    &base, &prefix, &groups,
+                )?);
+                if output_format != crate::cli::OutputFormat::Json {
+                    for warning in &source_warnings {
+                        tracing::warn!("{warning}");
+                    }
+                }
                 let (limit, resolved_extent) = if let Some(extent) = extent {
                     match extent {
                         crate::cli::Extent::Pr { to, n, legacy_n } => {
@@ -499,10 +1021,45 @@ fn run_cli(cli: crate::cli::Cli, output_format: crate::cli::OutputFormat) -> Res
                 } else {
                     (None, crate::update_output::ResolvedUpdateLimit::All)
                 };
-                if output_format == crate::cli::OutputFormat::Json {
-                    let execution = crate::commands::build_from_groups_with_summary(
+                let published = match limit {
+                    Some(crate::limit::Limit::ByPr(count)) => count.min(groups.len()),
+                    None => groups.len(),
+                };
+                if cfg.max_stack_depth > 0 && published > cfg.max_stack_depth as usize {
+                    return Err(anyhow::anyhow!(
+                        "Refusing to publish {} PR groups; max_stack_depth is {}. Land the bottom of the stack first or publish part of it with `spr update pr --to <N>`.",
+                        published,
+                        cfg.max_stack_depth
+                    ));
+                }
+                if offline {
+                    for warning in &lint_warnings {
+                        tracing::warn!("{warning}");
+                    }
+                    queue_offline_update(
+                        &from,
+                        &base,
+                        &prefix,
+                        groups,
+                        limit,
+                        pr_description_mode,
+                        &title_transforms,
+                        draft,
+                        execution_mode,
+                    )?;
+                    return Ok(CommandOutput::Machine(
+                        crate::machine_output::MachineOutput::completed_with_local_pr_branch_actions(
+                            crate::machine_output::MachineCommand::Update,
+                            Vec::new(),
+                        ),
+                    ));
+                }
+                let stack_branch = crate::git::stack_ref_name(&from)?;
+                let execution = if output_format == crate::cli::OutputFormat::Json {
+                    crate::commands::build_from_groups_with_summary(
                         &base,
                         &prefix,
+                        &stack_branch,
                         &skipped_handles,
                         no_pr,
                         execution_mode,
@@ -513,38 +1070,17 @@ fn run_cli(cli: crate::cli::Cli, output_format: crate::cli::OutputFormat) -> Res
                         allow_branch_reuse,
                         branch_reuse_guard_days,
                         local_pr_branch_policy,
-                    )?;
-                    let mut summary = crate::update_output::UpdateSummaryData::from_execution(
-                        crate::update_output::UpdateRepoContext {
-                            base: base.clone(),
-                            from,
-                            prefix: prefix.clone(),
-                            ignore_tag: ignore_tag.clone(),
-                        },
-                        crate::update_output::UpdateOptions {
-                            dry_run: execution_mode == ExecutionMode::DryRun,
-                            no_pr,
-                            pr_description_mode,
-                            local_pr_branches: local_pr_branch_policy,
-                        },
-                        resolved_extent,
-                        execution,
-                    );
-                    if execution_mode == ExecutionMode::Apply
-                        && refresh_metadata_after_update(&metadata_refresh_context)?
-                            == MetadataRefreshOutcome::SkippedDetached
-                    {
-                        summary
-                            .warnings
-                            .push(DETACHED_METADATA_REFRESH_WARNING.to_string());
-                    }
-                    Ok(CommandOutput::Update(crate::update_output::summary(
-                        summary,
-                    )))
+                        &title_transforms,
+                        draft,
+                    )?
                 } else {
+                    for warning in &lint_warnings {
+                        tracing::warn!("{warning}");
+                    }
                     crate::commands::build_from_groups(
                         &base,
                         &prefix,
+                        &stack_branch,
                         &skipped_handles,
                         no_pr,
                         execution_mode,
@@ -555,26 +1091,208 @@ fn run_cli(cli: crate::cli::Cli, output_format: crate::cli::OutputFormat) -> Res
                         allow_branch_reuse,
                         branch_reuse_guard_days,
                         local_pr_branch_policy,
-                    )?;
-                    if execution_mode == ExecutionMode::Apply
-                        && refresh_metadata_after_update(&metadata_refresh_context)?
-                            == MetadataRefreshOutcome::SkippedDetached
-                    {
+                        &title_transforms,
+                        draft,
+                    )?
+                };
+                let mut summary = crate::update_output::UpdateSummaryData::from_execution(
+                    crate::update_output::UpdateRepoContext {
+                        base: base.clone(),
+                        from,
+                        prefix: prefix.clone(),
+                        ignore_tag: ignore_tag.clone(),
+                    },
+                    crate::update_output::UpdateOptions {
+                        dry_run: execution_mode == ExecutionMode::DryRun,
+                        no_pr,
+                        pr_description_mode,
+                        local_pr_branches: local_pr_branch_policy,
+                    },
+                    resolved_extent,
+                    execution,
+                );
+                summary.warnings.extend(source_warnings);
+                summary.warnings.extend(lint_warnings);
+                // Tracking issues and review pings are GitHub-only
+                if !no_pr && !crate::forge::is_gitlab() {
+                    crate::commands::refresh_tracking_issue_if_configured(
+                        &base,
+                        &prefix,
+                        &ignore_tag,
+                        execution_mode,
+                    );
+                    let pr_numbers: Vec<u64> = summary
+                        .groups
+                        .iter()
+                        .filter_map(|group| group.remote_pr_number)
+                        .collect();
+                    crate::commands::ping_stale_reviews_if_configured(
+                        &pr_numbers,
+                        review_ping_after_hours,
+                        execution_mode,
+                    );
+                }
+                if execution_mode == ExecutionMode::Apply
+                    && refresh_metadata_after_update(&metadata_refresh_context)?
+                        == MetadataRefreshOutcome::SkippedDetached
+                {
+                    if output_format != crate::cli::OutputFormat::Json {
                         eprintln!("{DETACHED_METADATA_REFRESH_WARNING}");
                     }
+                    summary
+                        .warnings
+                        .push(DETACHED_METADATA_REFRESH_WARNING.to_string());
+                }
+                if let Some(path) = &plan_out {
+                    std::fs::write(path, crate::update_output::render_plan_markdown(&summary))
+                        .with_context(|| format!("Failed to write plan to {}", path.display()))?;
+                    tracing::info!("Wrote the update plan to {}.", path.display());
+                }
+                if output_format == crate::cli::OutputFormat::Json {
+                    Ok(CommandOutput::Update(crate::update_output::summary(
+                        summary,
+                    )))
+                } else {
+                    for line in crate::update_output::render_summary_lines(&summary) {
+                        tracing::info!("{line}");
+                    }
                     Ok(CommandOutput::None)
                 }
             }
         }
+        crate::cli::Cmd::Flush { dry_run } => {
+            let execution_mode = ExecutionMode::from(dry_run);
+            set_dry_run_env(execution_mode, false);
+            let stack_branch = crate::git::git_current_branch()?;
+            let common_dir = crate::git::git_common_dir()?;
+            let Some(queued) =
+                crate::offline_queue::queued_update_for_branch(&common_dir, &stack_branch)?
+            else {
+                return Err(anyhow::anyhow!(
+                    "No offline update is queued for `{}`; run `spr update` instead.",
+                    stack_branch
+                ));
+            };
+            let head = crate::git::git_ro(["rev-parse", "HEAD"].as_slice())?;
+            if head.trim() != queued.head {
+                tracing::warn!(
+                    "`{}` moved since the offline update was queued at {}; flushing the queued stack at {}. Run `spr update` afterwards to publish newer commits.",
+                    stack_branch,
+                    queued.queued_at,
+                    queued.head
+                );
+            }
+            let limit = queued.pr_limit.map(crate::limit::Limit::ByPr);
+            let (merge_base, mut leading_ignored, mut all_groups) =
+                crate::parsing::derive_groups_between_with_ignored(
+                    &base,
+                    &queued.head,
+                    &ignore_tag,
+                )
+                .with_context(|| {
+                    format!("The queued stack at {} no longer resolves", queued.head)
+                })?;
+            crate::stack_segments::isolate_active_stack(
+                &merge_base,
+                &format!("{merge_base}..{}", queued.head),
+                &mut leading_ignored,
+                &mut all_groups,
+            )?;
+            let (groups, _skipped) =
+                crate::parsing::split_groups_for_update(&leading_ignored, all_groups);
+            let plan = crate::offline_queue::plan_offline_update(
+                &base,
+                &prefix,
+                &crate::limit::apply_limit_groups(groups, limit)?,
+                &title_transforms,
+            )?;
+            for drift in crate::offline_queue::plan_drift(&queued, &plan) {
+                tracing::warn!("Since the update was queued, {drift}");
+            }
+            tracing::info!(
+                "Flushing {} push(es) and {} PR update(s) queued at {}",
+                plan.0.len(),
+                plan.1.len(),
+                queued.queued_at
+            );
+            publish_stack_at(
+                &metadata_refresh_context,
+                &queued.head,
+                &stack_branch,
+                limit,
+                execution_mode,
+                queued.pr_description_mode,
+                list_order,
+                branch_reuse_guard_days,
+                &commit_lint,
+                &title_transforms,
+                queued.draft,
+            )?;
+            crate::commands::refresh_tracking_issue_if_configured(
+                &base,
+                &prefix,
+                &ignore_tag,
+                execution_mode,
+            );
+            if execution_mode == ExecutionMode::Apply {
+                crate::offline_queue::remove_queued_update(&common_dir, &stack_branch)?;
+            }
+            let pending = crate::offline_queue::queued_branches(&common_dir)?
+                .into_iter()
+                .filter(|branch| *branch != stack_branch)
+                .collect::<Vec<_>>();
+            if !pending.is_empty() {
+                tracing::info!(
+                    "Offline updates are still queued for: {}. Check out each branch and run `spr flush`.",
+                    pending.join(", ")
+                );
+            }
+            Ok(CommandOutput::Machine(
+                crate::machine_output::MachineOutput::completed_with_local_pr_branch_actions(
+                    crate::machine_output::MachineCommand::Flush,
+                    Vec::new(),
+                ),
+            ))
+        }
         crate::cli::Cmd::Restack {
             after,
+            interactive: _,
             safe,
             preview,
+            update,
+            check,
+            from: _,
             dry_run,
         } => {
             let execution_mode = ExecutionMode::from(dry_run);
             set_dry_run_env(execution_mode, false);
-            if preview {
+            let after = match after {
+                Some(after) => after,
+                // clap requires `--after` unless `--interactive` is passed.
+                None => match crate::commands::choose_restack_after_interactively(
+                    &metadata_refresh_context,
+                    safe,
+                )? {
+                    Some(after) => after,
+                    None => {
+                        return Ok(CommandOutput::Machine(
+                            crate::machine_output::MachineOutput::completed_with_local_pr_branch_actions(
+                                crate::machine_output::MachineCommand::Restack,
+                                Vec::new(),
+                            ),
+                        ))
+                    }
+                },
+            };
+            if check {
+                Ok(CommandOutput::RestackCheck(crate::restack_output::check(
+                    crate::commands::check_restack_after(
+                        &metadata_refresh_context,
+                        &after,
+                        execution_mode,
+                    )?,
+                )))
+            } else if preview {
                 Ok(CommandOutput::RestackPreview(
                     crate::restack_output::preview(crate::commands::preview_restack_after(
                         &metadata_refresh_context,
@@ -599,6 +1317,18 @@ fn run_cli(cli: crate::cli::Cli, output_format: crate::cli::OutputFormat) -> Res
                     &prefix,
                     &ignore_tag,
                 )?;
+                if update && outcome == crate::commands::RewriteCommandOutcome::Completed {
+                    publish_current_stack_after_rewrite(
+                        &metadata_refresh_context,
+                        None,
+                        execution_mode,
+                        pr_description_mode,
+                        list_order,
+                        branch_reuse_guard_days,
+                        &commit_lint,
+                        &title_transforms,
+                    )?;
+                }
                 Ok(CommandOutput::Machine(ensure_rewrite_completed(
                     output_format,
                     "spr restack",
@@ -609,40 +1339,149 @@ fn run_cli(cli: crate::cli::Cli, output_format: crate::cli::OutputFormat) -> Res
                 )?))
             }
         }
-        crate::cli::Cmd::AdoptPrefix {
-            safe,
-            preview,
-            dry_run,
-        } => {
+        crate::cli::Cmd::Sync { safe, dry_run } => {
             let execution_mode = ExecutionMode::from(dry_run);
             set_dry_run_env(execution_mode, false);
-            if preview {
-                Ok(CommandOutput::AdoptPrefixPreview(
-                    crate::adopt_prefix_output::preview(crate::commands::preview_adopt_prefix(
-                        &metadata_refresh_context,
-                        safe,
-                    )?),
-                ))
-            } else {
-                let outcome = crate::commands::adopt_prefix(
+            let outcome = crate::commands::restack_after(
+                &metadata_refresh_context,
+                &crate::selectors::AfterSelector::Bottom,
+                safe,
+                execution_mode,
+                restack_conflict_policy,
+                dirty_worktree_policy,
+            )?;
+            let local_pr_branch_actions = sync_actions_after_completed_rewrite(
+                &outcome,
+                local_pr_branch_policy,
+                execution_mode,
+                &base,
+                &prefix,
+                &ignore_tag,
+            )?;
+            if outcome == crate::commands::RewriteCommandOutcome::Completed {
+                publish_current_stack_after_rewrite(
                     &metadata_refresh_context,
-                    safe,
+                    None,
                     execution_mode,
-                    dirty_worktree_policy,
+                    pr_description_mode,
+                    list_order,
+                    branch_reuse_guard_days,
+                    &commit_lint,
+                    &title_transforms,
                 )?;
-                let (destination_branch, local_pr_branch_actions) = if outcome.rewrite_outcome
-                    == crate::commands::RewriteCommandOutcome::Completed
-                    && execution_mode == ExecutionMode::Apply
-                {
-                    let stack_ref = format!("refs/heads/{}", outcome.owning_stack_branch);
-                    let local_pr_branch_actions = sync_local_pr_branches_for_branch(
-                        local_pr_branch_policy,
-                        execution_mode,
-                        &base,
-                        &prefix,
-                        &ignore_tag,
-                        &stack_ref,
-                    )?;
+            }
+            Ok(CommandOutput::Machine(ensure_rewrite_completed(
+                output_format,
+                "spr sync",
+                crate::machine_output::MachineCommand::Sync,
+                outcome,
+                None,
+                local_pr_branch_actions,
+            )?))
+        }
+        crate::cli::Cmd::Base {
+            action:
+                crate::cli::BaseCmd::Set {
+                    base: new_base,
+                    safe,
+                    dry_run,
+                },
+        } => {
+            let execution_mode = ExecutionMode::from(dry_run);
+            set_dry_run_env(execution_mode, false);
+            if !cli.allow_base {
+                crate::config::ensure_allowed_base(
+                    &new_base,
+                    &crate::git::remote(),
+                    &cfg.allowed_bases,
+                )?;
+            }
+            let outcome = crate::commands::restack_onto_base(
+                &metadata_refresh_context,
+                &new_base,
+                safe,
+                execution_mode,
+                restack_conflict_policy,
+                dirty_worktree_policy,
+            )?;
+            let new_context = crate::stack_metadata::RefreshMetadataContext {
+                base: new_base.clone(),
+                ..metadata_refresh_context.clone()
+            };
+            let local_pr_branch_actions = sync_actions_after_completed_rewrite(
+                &outcome,
+                local_pr_branch_policy,
+                execution_mode,
+                &new_base,
+                &prefix,
+                &ignore_tag,
+            )?;
+            if execution_mode == ExecutionMode::DryRun {
+                tracing::info!(
+                    "DRY-RUN: would set base: {} in the repo config and republish the stack",
+                    new_base
+                );
+            } else {
+                // A suspended replay still finishes on the new base via `spr resume`, so the
+                // config must already point there.
+                let path = crate::config::set_repo_config_base(&new_base)?;
+                tracing::info!("Set base: {} in {}", new_base, path.display());
+                if outcome == crate::commands::RewriteCommandOutcome::Completed {
+                    publish_current_stack_after_rewrite(
+                        &new_context,
+                        None,
+                        execution_mode,
+                        pr_description_mode,
+                        list_order,
+                        branch_reuse_guard_days,
+                        &commit_lint,
+                        &title_transforms,
+                    )?;
+                }
+            }
+            Ok(CommandOutput::Machine(ensure_rewrite_completed(
+                output_format,
+                "spr base set",
+                crate::machine_output::MachineCommand::BaseSet,
+                outcome,
+                None,
+                local_pr_branch_actions,
+            )?))
+        }
+        crate::cli::Cmd::AdoptPrefix {
+            safe,
+            preview,
+            dry_run,
+        } => {
+            let execution_mode = ExecutionMode::from(dry_run);
+            set_dry_run_env(execution_mode, false);
+            if preview {
+                Ok(CommandOutput::AdoptPrefixPreview(
+                    crate::adopt_prefix_output::preview(crate::commands::preview_adopt_prefix(
+                        &metadata_refresh_context,
+                        safe,
+                    )?),
+                ))
+            } else {
+                let outcome = crate::commands::adopt_prefix(
+                    &metadata_refresh_context,
+                    safe,
+                    execution_mode,
+                    dirty_worktree_policy,
+                )?;
+                let (destination_branch, local_pr_branch_actions) = if outcome.rewrite_outcome
+                    == crate::commands::RewriteCommandOutcome::Completed
+                    && execution_mode == ExecutionMode::Apply
+                {
+                    let stack_ref = format!("refs/heads/{}", outcome.owning_stack_branch);
+                    let local_pr_branch_actions = sync_local_pr_branches_for_branch(
+                        local_pr_branch_policy,
+                        execution_mode,
+                        &base,
+                        &prefix,
+                        &ignore_tag,
+                        &stack_ref,
+                    )?;
                     (
                         Some(outcome.owning_stack_branch.clone()),
                         local_pr_branch_actions,
@@ -660,6 +1499,21 @@ fn run_cli(cli: crate::cli::Cli, output_format: crate::cli::OutputFormat) -> Res
                 )?))
             }
         }
+        crate::cli::Cmd::RenamePrefix {
+            new_prefix,
+            keep_config,
+            dry_run,
+        } => {
+            let execution_mode = ExecutionMode::from(dry_run);
+            set_dry_run_env(execution_mode, false);
+            crate::commands::rename_prefix(&prefix, &new_prefix, keep_config, execution_mode)?;
+            Ok(CommandOutput::Machine(
+                crate::machine_output::MachineOutput::completed_with_local_pr_branch_actions(
+                    crate::machine_output::MachineCommand::RenamePrefix,
+                    Vec::new(),
+                ),
+            ))
+        }
         crate::cli::Cmd::DropMergedPrefix { safe, dry_run } => {
             let execution_mode = ExecutionMode::from(dry_run);
             set_dry_run_env(execution_mode, false);
@@ -691,8 +1545,36 @@ fn run_cli(cli: crate::cli::Cli, output_format: crate::cli::OutputFormat) -> Res
             from,
             allow_replayed_duplicates,
             query_changed_branches,
+            staged,
+            safe,
             dry_run,
         } => {
+            if staged {
+                let execution_mode = ExecutionMode::from(dry_run);
+                set_dry_run_env(execution_mode, false);
+                let outcome = crate::commands::absorb_staged(
+                    &metadata_refresh_context,
+                    safe,
+                    execution_mode,
+                    dirty_worktree_policy,
+                )?;
+                let local_pr_branch_actions = sync_actions_after_completed_rewrite(
+                    &outcome,
+                    local_pr_branch_policy,
+                    execution_mode,
+                    &base,
+                    &prefix,
+                    &ignore_tag,
+                )?;
+                return Ok(CommandOutput::Machine(ensure_rewrite_completed(
+                    output_format,
+                    "spr absorb --staged",
+                    crate::machine_output::MachineCommand::Absorb,
+                    outcome,
+                    None,
+                    local_pr_branch_actions,
+                )?));
+            }
             let options = crate::commands::AbsorbOptions {
                 copied_later_stack_commit_policy: if allow_replayed_duplicates {
                     crate::commands::CopiedLaterStackCommitPolicy::AllowKeepNonSeedDuplicates
@@ -749,7 +1631,12 @@ fn run_cli(cli: crate::cli::Cli, output_format: crate::cli::OutputFormat) -> Res
                 local_pr_branch_actions,
             )?))
         }
-        crate::cli::Cmd::Prep { from, dry_run } => {
+        crate::cli::Cmd::Prep {
+            from,
+            from_ref: _,
+            allow_empty,
+            dry_run,
+        } => {
             let execution_mode = ExecutionMode::from(dry_run);
             set_dry_run_env(execution_mode, false);
             let selection = match (cli.until, cli.exact, from) {
@@ -779,6 +1666,8 @@ fn run_cli(cli: crate::cli::Cli, output_format: crate::cli::OutputFormat) -> Res
                     local_pr_branch_policy,
                     selection,
                     execution_mode,
+                    allow_empty,
+                    title_transforms: title_transforms.clone(),
                 },
             )?;
             if output_format == crate::cli::OutputFormat::Json {
@@ -790,7 +1679,7 @@ fn run_cli(cli: crate::cli::Cli, output_format: crate::cli::OutputFormat) -> Res
                 Ok(CommandOutput::None)
             }
         }
-        crate::cli::Cmd::List { what, .. } => {
+        crate::cli::Cmd::List { what, since, .. } => {
             if output_format == crate::cli::OutputFormat::Json {
                 match what {
                     crate::cli::ListWhat::Pr => match read_only_pr_list_output(
@@ -799,6 +1688,7 @@ fn run_cli(cli: crate::cli::Cli, output_format: crate::cli::OutputFormat) -> Res
                         &prefix,
                         &ignore_tag,
                         local_pr_branch_policy,
+                        since.as_deref(),
                     ) {
                         Ok(output) => Ok(CommandOutput::ReadOnly(output)),
                         Err(output) => Ok(CommandOutput::Error(output)),
@@ -809,6 +1699,7 @@ fn run_cli(cli: crate::cli::Cli, output_format: crate::cli::OutputFormat) -> Res
                         &prefix,
                         &ignore_tag,
                         local_pr_branch_policy,
+                        since.as_deref(),
                     ) {
                         Ok(output) => Ok(CommandOutput::ReadOnly(output)),
                         Err(output) => Ok(CommandOutput::Error(output)),
@@ -822,6 +1713,7 @@ fn run_cli(cli: crate::cli::Cli, output_format: crate::cli::OutputFormat) -> Res
                         &ignore_tag,
                         list_order,
                         local_pr_branch_policy,
+                        since.as_deref(),
                     )?,
                     crate::cli::ListWhat::Commit => crate::commands::list_commits_display(
                         &base,
@@ -829,25 +1721,228 @@ fn run_cli(cli: crate::cli::Cli, output_format: crate::cli::OutputFormat) -> Res
                         &ignore_tag,
                         list_order,
                         local_pr_branch_policy,
+                        since.as_deref(),
                     )?,
                 }
                 Ok(CommandOutput::None)
             }
         }
-        crate::cli::Cmd::Status => {
+        crate::cli::Cmd::Freeze { reason, dry_run } => {
+            crate::commands::freeze_stack(
+                &base,
+                &prefix,
+                &ignore_tag,
+                reason.as_deref(),
+                ExecutionMode::from(dry_run),
+            )?;
+            Ok(CommandOutput::Machine(
+                crate::machine_output::MachineOutput::completed_with_local_pr_branch_actions(
+                    crate::machine_output::MachineCommand::Freeze,
+                    Vec::new(),
+                ),
+            ))
+        }
+        crate::cli::Cmd::Thaw { dry_run } => {
+            crate::commands::thaw_stack(&prefix, ExecutionMode::from(dry_run))?;
+            Ok(CommandOutput::Machine(
+                crate::machine_output::MachineOutput::completed_with_local_pr_branch_actions(
+                    crate::machine_output::MachineCommand::Thaw,
+                    Vec::new(),
+                ),
+            ))
+        }
+        crate::cli::Cmd::Checkout {
+            target,
+            branch,
+            force,
+            dry_run,
+        } => {
+            let execution_mode = ExecutionMode::from(dry_run);
+            let branch = crate::commands::checkout_group(
+                &base,
+                &prefix,
+                &ignore_tag,
+                &target,
+                branch.as_deref(),
+                force,
+                execution_mode,
+            )?;
+            Ok(CommandOutput::Machine(
+                crate::machine_output::MachineOutput::completed_with_destination_branch(
+                    crate::machine_output::MachineCommand::Checkout,
+                    Some(branch),
+                    Vec::new(),
+                ),
+            ))
+        }
+        crate::cli::Cmd::RangeDiff { target } => {
+            if output_format == crate::cli::OutputFormat::Json {
+                let data =
+                    crate::commands::collect_range_diff_data(&base, &prefix, &ignore_tag, &target)?;
+                Ok(CommandOutput::ReadOnly(
+                    crate::read_only_output::range_diff(
+                        crate::json_output::JsonCommand::RangeDiff,
+                        data,
+                    ),
+                ))
+            } else {
+                crate::commands::range_diff_display(&base, &prefix, &ignore_tag, &target)?;
+                Ok(CommandOutput::None)
+            }
+        }
+        crate::cli::Cmd::Files { target, stat } => {
+            if output_format == crate::cli::OutputFormat::Json {
+                let data =
+                    crate::commands::collect_files_data(&base, &ignore_tag, target.as_ref(), stat)?;
+                Ok(CommandOutput::ReadOnly(crate::read_only_output::files(
+                    crate::json_output::JsonCommand::Files,
+                    data,
+                )))
+            } else {
+                crate::commands::list_files_display(&base, &ignore_tag, target.as_ref(), stat)?;
+                Ok(CommandOutput::None)
+            }
+        }
+        crate::cli::Cmd::Diff { target, stat } => {
+            if output_format == crate::cli::OutputFormat::Json {
+                let data = crate::commands::collect_diff_data(&base, &ignore_tag, &target, stat)?;
+                Ok(CommandOutput::ReadOnly(crate::read_only_output::diff(
+                    crate::json_output::JsonCommand::Diff,
+                    data,
+                )))
+            } else {
+                crate::commands::diff_display(&base, &ignore_tag, &target, stat)?;
+                Ok(CommandOutput::None)
+            }
+        }
+        crate::cli::Cmd::Parse => {
+            if output_format == crate::cli::OutputFormat::Json {
+                let data = crate::commands::collect_parse_data(&base, &prefix, &ignore_tag)?;
+                Ok(CommandOutput::ReadOnly(crate::read_only_output::parse(
+                    crate::json_output::JsonCommand::Parse,
+                    data,
+                )))
+            } else {
+                crate::commands::parse_display(&base, &prefix, &ignore_tag)?;
+                Ok(CommandOutput::None)
+            }
+        }
+        crate::cli::Cmd::Landed { sha } => {
+            if output_format == crate::cli::OutputFormat::Json {
+                let data = crate::commands::collect_landed_data(&sha)?;
+                Ok(CommandOutput::ReadOnly(crate::read_only_output::landed(
+                    crate::json_output::JsonCommand::Landed,
+                    data,
+                )))
+            } else {
+                crate::commands::landed_display(&sha)?;
+                Ok(CommandOutput::None)
+            }
+        }
+        crate::cli::Cmd::Backup {
+            action: crate::cli::BackupCmd::List,
+        } => {
+            if output_format == crate::cli::OutputFormat::Json {
+                Ok(CommandOutput::ReadOnly(crate::read_only_output::backups(
+                    crate::json_output::JsonCommand::Backup,
+                    crate::commands::collect_backups_data()?,
+                )))
+            } else {
+                crate::commands::backup_list_display()?;
+                Ok(CommandOutput::None)
+            }
+        }
+        crate::cli::Cmd::Backup {
+            action:
+                crate::cli::BackupCmd::Prune {
+                    older_than,
+                    in_head,
+                    dry_run,
+                },
+        } => {
+            let execution_mode = ExecutionMode::from(dry_run);
+            set_dry_run_env(execution_mode, false);
+            let pruned = crate::commands::prune_backups(older_than, in_head, execution_mode)?;
+            if pruned > 0 {
+                tracing::info!("Pruned {pruned} backup ref(s)");
+            }
+            Ok(CommandOutput::Machine(
+                crate::machine_output::MachineOutput::completed_with_local_pr_branch_actions(
+                    crate::machine_output::MachineCommand::Backup,
+                    Vec::new(),
+                ),
+            ))
+        }
+        crate::cli::Cmd::Stats {
+            history,
+            limit,
+            since,
+        } => {
+            let data = crate::commands::collect_stats_data(
+                &base,
+                &prefix,
+                &ignore_tag,
+                history,
+                limit,
+                since.as_deref(),
+            )?;
             if output_format == crate::cli::OutputFormat::Json {
-                match read_only_pr_list_output(
-                    crate::json_output::JsonCommand::Status,
+                Ok(CommandOutput::ReadOnly(crate::read_only_output::stats(
+                    crate::json_output::JsonCommand::Stats,
+                    data,
+                )))
+            } else {
+                crate::commands::print_stats(&data);
+                Ok(CommandOutput::None)
+            }
+        }
+        crate::cli::Cmd::Status {
+            watch,
+            interval,
+            notify,
+        } => {
+            if watch {
+                if output_format == crate::cli::OutputFormat::Json {
+                    return Err(anyhow::anyhow!(
+                        "`spr status --watch` prints human output only; drop `--json`."
+                    ));
+                }
+                crate::commands::watch_status(
+                    &base,
+                    &prefix,
+                    &ignore_tag,
+                    list_order,
+                    local_pr_branch_policy,
+                    std::time::Duration::from_secs(interval.max(1)),
+                    notify,
+                )?;
+                Ok(CommandOutput::None)
+            } else if output_format == crate::cli::OutputFormat::Json {
+                let command = crate::json_output::JsonCommand::Status;
+                match crate::commands::collect_status_data_for_json(
                     &base,
                     &prefix,
                     &ignore_tag,
                     local_pr_branch_policy,
                 ) {
-                    Ok(output) => Ok(CommandOutput::ReadOnly(output)),
-                    Err(output) => Ok(CommandOutput::Error(output)),
+                    Ok(data) => Ok(CommandOutput::ReadOnly(crate::read_only_output::status(
+                        command, data,
+                    ))),
+                    Err(crate::commands::ReadOnlyQueryError::SyntheticBranchNameCollision(
+                        collision,
+                    )) => Ok(CommandOutput::Error(
+                        crate::json_output::ErrorOutput::synthetic_branch_name_collision(
+                            command, &collision,
+                        ),
+                    )),
+                    Err(crate::commands::ReadOnlyQueryError::Internal(err)) => {
+                        Ok(CommandOutput::Error(
+                            crate::json_output::ErrorOutput::internal(command, format!("{err:#}")),
+                        ))
+                    }
                 }
             } else {
-                crate::commands::list_prs_display(
+                crate::commands::status_display(
                     &base,
                     &prefix,
                     &ignore_tag,
@@ -883,135 +1978,928 @@ fn run_cli(cli: crate::cli::Cli, output_format: crate::cli::OutputFormat) -> Res
                 Ok(CommandOutput::None)
             }
         }
-        crate::cli::Cmd::ResolveStack { target } => Ok(CommandOutput::ResolveStack(
-            crate::commands::resolve_stack(target, &ignore_tag)?,
-        )),
-        crate::cli::Cmd::Resume { .. } => unreachable!("handled before config loading"),
-        crate::cli::Cmd::Land {
-            which,
-            r#unsafe,
-            no_restack,
+        crate::cli::Cmd::ResolveStack { target } => Ok(CommandOutput::ResolveStack(
+            crate::commands::resolve_stack(target, &ignore_tag)?,
+        )),
+        crate::cli::Cmd::Resume { .. }
+        | crate::cli::Cmd::Abort { .. }
+        | crate::cli::Cmd::Init { .. }
+        | crate::cli::Cmd::Verify { .. } => {
+            unreachable!("handled before config loading")
+        }
+        crate::cli::Cmd::Land {
+            which,
+            r#unsafe,
+            no_restack,
+            update,
+            except,
+            from: _,
+            local_only,
+            wait,
+            timeout,
+            yes,
+            thaw,
+            dry_run,
+        } => {
+            let execution_mode = ExecutionMode::from(dry_run);
+            set_dry_run_env(execution_mode, false);
+            if !local_only {
+                crate::commands::ensure_not_frozen(&prefix, thaw, "spr land", execution_mode)?;
+            }
+            if local_only {
+                crate::commands::land_local_only(
+                    &base,
+                    &ignore_tag,
+                    &cli.until
+                        .unwrap_or(crate::selectors::InclusiveSelector::All),
+                    execution_mode,
+                )?;
+                return Ok(CommandOutput::Machine(
+                    crate::machine_output::MachineOutput::completed_with_local_pr_branch_actions(
+                        crate::machine_output::MachineCommand::Land,
+                        Vec::new(),
+                    ),
+                ));
+            }
+            let mode = match which {
+                Some(mode) => mode,
+                None => crate::config::land_mode(&cfg.land)?,
+            };
+            let mut until = cli
+                .until
+                .unwrap_or(crate::selectors::InclusiveSelector::All);
+            if !except.is_empty() {
+                if crate::commands::common::unchecked_out_stack_branch()?.is_some() {
+                    anyhow::bail!(
+                        "spr land --except reorders the checked-out stack; check out the --from branch first"
+                    );
+                }
+                let (landable, outcome) = crate::commands::move_excepted_groups_to_top(
+                    &base,
+                    &prefix,
+                    &ignore_tag,
+                    &until,
+                    &except,
+                    crate::commands::MoveExecutionOptions {
+                        safe: false,
+                        execution_mode,
+                        dirty_worktree_policy,
+                    },
+                )?;
+                if execution_mode == ExecutionMode::DryRun {
+                    return Ok(CommandOutput::Machine(
+                        crate::machine_output::MachineOutput::completed_with_local_pr_branch_actions(
+                            crate::machine_output::MachineCommand::Land,
+                            Vec::new(),
+                        ),
+                    ));
+                }
+                if outcome != crate::commands::RewriteCommandOutcome::Completed {
+                    return Ok(CommandOutput::Machine(ensure_rewrite_completed(
+                        output_format,
+                        "spr land --except",
+                        crate::machine_output::MachineCommand::Land,
+                        outcome,
+                        None,
+                        Vec::new(),
+                    )?));
+                }
+                // Retarget the PRs to the reordered stack before merging the bottom ones
+                publish_current_stack_after_rewrite(
+                    &metadata_refresh_context,
+                    None,
+                    execution_mode,
+                    pr_description_mode,
+                    list_order,
+                    branch_reuse_guard_days,
+                    &commit_lint,
+                    &title_transforms,
+                )?;
+                until = crate::selectors::InclusiveSelector::Group(
+                    crate::selectors::GroupSelector::LocalPr(landable),
+                );
+            }
+            let stack_from = crate::parsing::stack_from();
+            let (_merge_base, land_groups) =
+                crate::parsing::derive_groups_between(&base, &stack_from, &ignore_tag)?;
+            for warning in source_branch_mismatch_warnings(&stack_from, &prefix, &land_groups)? {
+                tracing::warn!("{warning}");
+            }
+            if cfg.confirm_land
+                && !yes
+                && execution_mode == ExecutionMode::Apply
+                && !crate::commands::confirm_landing(&land_groups, &until, &base)?
+            {
+                tracing::info!("Landing cancelled.");
+                return Ok(CommandOutput::Machine(
+                    crate::machine_output::MachineOutput::cancelled(
+                        crate::machine_output::MachineCommand::Land,
+                    ),
+                ));
+            }
+            let landed_count = match mode {
+                crate::cli::LandCmd::Flatten => crate::commands::land_flatten_until(
+                    &base,
+                    &prefix,
+                    &ignore_tag,
+                    &until,
+                    execution_mode,
+                    r#unsafe,
+                    wait.then(|| std::time::Duration::from_secs(timeout * 60)),
+                    &projects,
+                )?,
+                crate::cli::LandCmd::PerPr => crate::commands::land_per_pr_until(
+                    &base,
+                    &prefix,
+                    &ignore_tag,
+                    &until,
+                    execution_mode,
+                    r#unsafe,
+                    wait.then(|| std::time::Duration::from_secs(timeout * 60)),
+                    &projects,
+                )?,
+            };
+            let local_pr_branch_actions = if !no_restack {
+                // After landing the first N PRs, restack the remaining commits onto the latest base
+                let outcome = crate::commands::restack_after_count(
+                    &metadata_refresh_context,
+                    landed_count,
+                    false,
+                    execution_mode,
+                    restack_conflict_policy,
+                    dirty_worktree_policy,
+                )?;
+                if let crate::commands::RewriteCommandOutcome::Suspended(state) = &outcome {
+                    let post_success_hint = Some(
+                        "GitHub landing already succeeded; resolve the local restack conflict and run the printed `spr resume <path>` command instead of rerunning `spr land`."
+                            .to_string(),
+                    );
+                    if output_format == crate::cli::OutputFormat::Json {
+                        return Ok(CommandOutput::Machine(
+                            crate::machine_output::MachineOutput::suspended(
+                                crate::machine_output::MachineCommand::Land,
+                                (**state).clone(),
+                                post_success_hint,
+                            ),
+                        ));
+                    } else {
+                        return Err(anyhow::anyhow!(
+                            "GitHub landing already succeeded, but the follow-on restack suspended due to a cherry-pick conflict. Resolve the conflict in the temp worktree, stage the resolution, and run `spr resume {}` instead of rerunning `spr land`.",
+                            state.resume_path.display()
+                        ));
+                    }
+                }
+                let actions = sync_actions_after_completed_rewrite(
+                    &outcome,
+                    local_pr_branch_policy,
+                    execution_mode,
+                    &base,
+                    &prefix,
+                    &ignore_tag,
+                )?;
+                if update && outcome == crate::commands::RewriteCommandOutcome::Completed {
+                    publish_current_stack_after_rewrite(
+                        &metadata_refresh_context,
+                        None,
+                        execution_mode,
+                        pr_description_mode,
+                        list_order,
+                        branch_reuse_guard_days,
+                        &commit_lint,
+                        &title_transforms,
+                    )?;
+                }
+                actions
+            } else {
+                Vec::new()
+            };
+            crate::commands::refresh_tracking_issue_if_configured(
+                &base,
+                &prefix,
+                &ignore_tag,
+                execution_mode,
+            );
+            Ok(CommandOutput::Machine(
+                crate::machine_output::MachineOutput::completed_with_local_pr_branch_actions(
+                    crate::machine_output::MachineCommand::Land,
+                    local_pr_branch_actions,
+                ),
+            ))
+        }
+        crate::cli::Cmd::TrackIssue { title, dry_run } => {
+            let execution_mode = ExecutionMode::from(dry_run);
+            set_dry_run_env(execution_mode, false);
+            let summary = crate::commands::track_issue(
+                &base,
+                &prefix,
+                &ignore_tag,
+                title.as_deref(),
+                execution_mode,
+            )?;
+            if output_format == crate::cli::OutputFormat::Json {
+                Ok(CommandOutput::Maintenance(Box::new(
+                    crate::maintenance_output::track_issue_summary(summary),
+                )))
+            } else {
+                crate::commands::print_track_issue_summary(&summary);
+                Ok(CommandOutput::None)
+            }
+        }
+        crate::cli::Cmd::RelinkPrs { dry_run } => {
+            let execution_mode = ExecutionMode::from(dry_run);
+            set_dry_run_env(execution_mode, false);
+            let summary = crate::commands::relink_prs(&base, &prefix, &ignore_tag, execution_mode)?;
+            if output_format == crate::cli::OutputFormat::Json {
+                Ok(CommandOutput::Maintenance(Box::new(
+                    crate::maintenance_output::relink_prs_summary(summary),
+                )))
+            } else {
+                crate::commands::print_relink_prs_summary(&summary);
+                Ok(CommandOutput::None)
+            }
+        }
+        crate::cli::Cmd::Cleanup {
+            close_orphaned_prs,
+            yes,
+            dry_run,
+        } => {
+            let execution_mode = ExecutionMode::from(dry_run);
+            set_dry_run_env(execution_mode, false);
+            let orphaned_pr_scope =
+                close_orphaned_prs.then_some(crate::commands::OrphanedPrScope {
+                    base: &base,
+                    ignore_tag: &ignore_tag,
+                    assume_yes: yes,
+                });
+            let summary = crate::commands::cleanup_remote_branches(
+                &prefix,
+                orphaned_pr_scope,
+                execution_mode,
+            )?;
+            if output_format == crate::cli::OutputFormat::Json {
+                Ok(CommandOutput::Maintenance(Box::new(
+                    crate::maintenance_output::cleanup_summary(summary),
+                )))
+            } else {
+                crate::commands::print_cleanup_summary(&summary);
+                Ok(CommandOutput::None)
+            }
+        }
+        crate::cli::Cmd::Ready { target, dry_run } => run_pr_stage_command(
+            crate::maintenance_output::PrStage::Ready,
+            &target,
+            dry_run,
+            output_format,
+            &base,
+            &prefix,
+            &ignore_tag,
+        ),
+        crate::cli::Cmd::Draft { target, dry_run } => run_pr_stage_command(
+            crate::maintenance_output::PrStage::Draft,
+            &target,
+            dry_run,
+            output_format,
+            &base,
+            &prefix,
+            &ignore_tag,
+        ),
+        crate::cli::Cmd::Open { target, dry_run } => {
+            let execution_mode = ExecutionMode::from(dry_run);
+            set_dry_run_env(execution_mode, false);
+            crate::commands::open_prs(
+                &base,
+                &prefix,
+                &ignore_tag,
+                target.target.as_ref(),
+                execution_mode,
+            )?;
+            Ok(CommandOutput::Machine(
+                crate::machine_output::MachineOutput::completed_with_local_pr_branch_actions(
+                    crate::machine_output::MachineCommand::Open,
+                    Vec::new(),
+                ),
+            ))
+        }
+        crate::cli::Cmd::Label {
+            add,
+            remove,
+            dry_run,
+        } => {
+            let execution_mode = ExecutionMode::from(dry_run);
+            set_dry_run_env(execution_mode, false);
+            crate::commands::label_stack(
+                &base,
+                &prefix,
+                &ignore_tag,
+                &add,
+                &remove,
+                execution_mode,
+            )?;
+            Ok(CommandOutput::Machine(
+                crate::machine_output::MachineOutput::completed_with_local_pr_branch_actions(
+                    crate::machine_output::MachineCommand::Label,
+                    Vec::new(),
+                ),
+            ))
+        }
+        crate::cli::Cmd::Milestone {
+            name,
+            clear: _,
+            dry_run,
+        } => {
+            let execution_mode = ExecutionMode::from(dry_run);
+            set_dry_run_env(execution_mode, false);
+            crate::commands::set_stack_milestone(
+                &base,
+                &prefix,
+                &ignore_tag,
+                name.as_deref(),
+                execution_mode,
+            )?;
+            Ok(CommandOutput::Machine(
+                crate::machine_output::MachineOutput::completed_with_local_pr_branch_actions(
+                    crate::machine_output::MachineCommand::Milestone,
+                    Vec::new(),
+                ),
+            ))
+        }
+        crate::cli::Cmd::Assign {
+            target,
+            users,
+            remove,
+            dry_run,
+        } => {
+            let execution_mode = ExecutionMode::from(dry_run);
+            set_dry_run_env(execution_mode, false);
+            let target = match &target {
+                crate::selectors::InclusiveSelector::All => None,
+                crate::selectors::InclusiveSelector::Group(selector) => Some(selector),
+            };
+            crate::commands::assign_stack(
+                &base,
+                &prefix,
+                &ignore_tag,
+                target,
+                &users,
+                &remove,
+                execution_mode,
+            )?;
+            Ok(CommandOutput::Machine(
+                crate::machine_output::MachineOutput::completed_with_local_pr_branch_actions(
+                    crate::machine_output::MachineCommand::Assign,
+                    Vec::new(),
+                ),
+            ))
+        }
+        crate::cli::Cmd::Automerge {
+            target,
+            method,
+            dry_run,
+        } => {
+            let execution_mode = ExecutionMode::from(dry_run);
+            set_dry_run_env(execution_mode, false);
+            let method = method.unwrap_or(cfg.merge_method);
+            crate::commands::enable_automerge(
+                &base,
+                &prefix,
+                &ignore_tag,
+                target.as_ref(),
+                method,
+                execution_mode,
+            )?;
+            Ok(CommandOutput::Machine(
+                crate::machine_output::MachineOutput::completed_with_local_pr_branch_actions(
+                    crate::machine_output::MachineCommand::Automerge,
+                    Vec::new(),
+                ),
+            ))
+        }
+        crate::cli::Cmd::FixPr {
+            target,
+            after,
+            before,
+            tail,
+            safe,
+            dry_run,
+        } => {
+            let execution_mode = ExecutionMode::from(dry_run);
+            set_dry_run_env(execution_mode, false);
+            let target = match (target.or(after), before) {
+                (Some(target), _) => target,
+                (None, Some(before)) => {
+                    crate::commands::resolve_fix_pr_before(&metadata_refresh_context, &before)?
+                }
+                (None, None) => {
+                    return Err(anyhow::anyhow!(
+                        "spr fix-pr requires a target, --after, or --before"
+                    ))
+                }
+            };
+            let outcome = crate::commands::fix_pr_tail(
+                &metadata_refresh_context,
+                &target,
+                tail,
+                safe,
+                execution_mode,
+                dirty_worktree_policy,
+            )?;
+            let local_pr_branch_actions = sync_actions_after_completed_rewrite(
+                &outcome,
+                local_pr_branch_policy,
+                execution_mode,
+                &base,
+                &prefix,
+                &ignore_tag,
+            )?;
+            Ok(CommandOutput::Machine(ensure_rewrite_completed(
+                output_format,
+                "spr fix-pr",
+                crate::machine_output::MachineCommand::FixPr,
+                outcome,
+                None,
+                local_pr_branch_actions,
+            )?))
+        }
+        crate::cli::Cmd::ImportPr {
+            number,
+            after,
+            tag,
+            safe,
+            dry_run,
+        } => {
+            let execution_mode = ExecutionMode::from(dry_run);
+            set_dry_run_env(execution_mode, false);
+            let outcome = crate::commands::import_pr(
+                &metadata_refresh_context,
+                number,
+                &after.unwrap_or(crate::selectors::AfterSelector::Top),
+                tag.as_deref(),
+                safe,
+                execution_mode,
+                dirty_worktree_policy,
+            )?;
+            let local_pr_branch_actions = sync_actions_after_completed_rewrite(
+                &outcome,
+                local_pr_branch_policy,
+                execution_mode,
+                &base,
+                &prefix,
+                &ignore_tag,
+            )?;
+            Ok(CommandOutput::Machine(ensure_rewrite_completed(
+                output_format,
+                "spr import-pr",
+                crate::machine_output::MachineCommand::ImportPr,
+                outcome,
+                None,
+                local_pr_branch_actions,
+            )?))
+        }
+        crate::cli::Cmd::Fixup {
+            target,
+            message,
+            update,
+            safe,
+            dry_run,
+        } => {
+            let execution_mode = ExecutionMode::from(dry_run);
+            set_dry_run_env(execution_mode, false);
+            let outcome = crate::commands::fixup_staged(
+                &metadata_refresh_context,
+                &target,
+                message.as_deref(),
+                safe,
+                execution_mode,
+                dirty_worktree_policy,
+            )?;
+            let local_pr_branch_actions = sync_actions_after_completed_rewrite(
+                &outcome.rewrite,
+                local_pr_branch_policy,
+                execution_mode,
+                &base,
+                &prefix,
+                &ignore_tag,
+            )?;
+            if update && outcome.rewrite == crate::commands::RewriteCommandOutcome::Completed {
+                publish_current_stack_after_rewrite(
+                    &metadata_refresh_context,
+                    Some(crate::limit::Limit::ByPr(outcome.local_pr_number)),
+                    execution_mode,
+                    pr_description_mode,
+                    list_order,
+                    branch_reuse_guard_days,
+                    &commit_lint,
+                    &title_transforms,
+                )?;
+            }
+            Ok(CommandOutput::Machine(ensure_rewrite_completed(
+                output_format,
+                "spr fixup",
+                crate::machine_output::MachineCommand::Fixup,
+                outcome.rewrite,
+                None,
+                local_pr_branch_actions,
+            )?))
+        }
+        crate::cli::Cmd::Amend {
+            target,
+            commit,
+            update,
+            safe,
+            dry_run,
+        } => {
+            let execution_mode = ExecutionMode::from(dry_run);
+            set_dry_run_env(execution_mode, false);
+            let outcome = crate::commands::amend_group(
+                &metadata_refresh_context,
+                &target,
+                commit.as_deref(),
+                safe,
+                execution_mode,
+                dirty_worktree_policy,
+            )?;
+            let local_pr_branch_actions = sync_actions_after_completed_rewrite(
+                &outcome.rewrite,
+                local_pr_branch_policy,
+                execution_mode,
+                &base,
+                &prefix,
+                &ignore_tag,
+            )?;
+            if update && outcome.rewrite == crate::commands::RewriteCommandOutcome::Completed {
+                publish_current_stack_after_rewrite(
+                    &metadata_refresh_context,
+                    Some(crate::limit::Limit::ByPr(outcome.local_pr_number)),
+                    execution_mode,
+                    pr_description_mode,
+                    list_order,
+                    branch_reuse_guard_days,
+                    &commit_lint,
+                    &title_transforms,
+                )?;
+            }
+            Ok(CommandOutput::Machine(ensure_rewrite_completed(
+                output_format,
+                "spr amend",
+                crate::machine_output::MachineCommand::Amend,
+                outcome.rewrite,
+                None,
+                local_pr_branch_actions,
+            )?))
+        }
+        crate::cli::Cmd::SquashRange {
+            range,
+            update,
+            safe,
+            dry_run,
+        } => {
+            let execution_mode = ExecutionMode::from(dry_run);
+            set_dry_run_env(execution_mode, false);
+            let outcome = crate::commands::squash_commit_range(
+                &metadata_refresh_context,
+                range,
+                safe,
+                execution_mode,
+                dirty_worktree_policy,
+            )?;
+            let local_pr_branch_actions = sync_actions_after_completed_rewrite(
+                &outcome.rewrite,
+                local_pr_branch_policy,
+                execution_mode,
+                &base,
+                &prefix,
+                &ignore_tag,
+            )?;
+            if update && outcome.rewrite == crate::commands::RewriteCommandOutcome::Completed {
+                publish_current_stack_after_rewrite(
+                    &metadata_refresh_context,
+                    Some(crate::limit::Limit::ByPr(outcome.local_pr_number)),
+                    execution_mode,
+                    pr_description_mode,
+                    list_order,
+                    branch_reuse_guard_days,
+                    &commit_lint,
+                    &title_transforms,
+                )?;
+            }
+            Ok(CommandOutput::Machine(ensure_rewrite_completed(
+                output_format,
+                "spr squash-range",
+                crate::machine_output::MachineCommand::SquashRange,
+                outcome.rewrite,
+                None,
+                local_pr_branch_actions,
+            )?))
+        }
+        crate::cli::Cmd::Pull {
+            stack: _,
+            update,
+            safe,
+            dry_run,
+        } => {
+            let execution_mode = ExecutionMode::from(dry_run);
+            set_dry_run_env(execution_mode, false);
+            let outcome = crate::commands::pull_stack(
+                &metadata_refresh_context,
+                safe,
+                execution_mode,
+                dirty_worktree_policy,
+            )?;
+            let local_pr_branch_actions = sync_actions_after_completed_rewrite(
+                &outcome,
+                local_pr_branch_policy,
+                execution_mode,
+                &base,
+                &prefix,
+                &ignore_tag,
+            )?;
+            if update && outcome == crate::commands::RewriteCommandOutcome::Completed {
+                publish_current_stack_after_rewrite(
+                    &metadata_refresh_context,
+                    None,
+                    execution_mode,
+                    pr_description_mode,
+                    list_order,
+                    branch_reuse_guard_days,
+                    &commit_lint,
+                    &title_transforms,
+                )?;
+            }
+            Ok(CommandOutput::Machine(ensure_rewrite_completed(
+                output_format,
+                "spr pull",
+                crate::machine_output::MachineCommand::Pull,
+                outcome,
+                None,
+                local_pr_branch_actions,
+            )?))
+        }
+        crate::cli::Cmd::Edit {
+            target,
+            update,
+            safe,
+            dry_run,
+        } => {
+            let execution_mode = ExecutionMode::from(dry_run);
+            set_dry_run_env(execution_mode, false);
+            let outcome = crate::commands::edit_group_message(
+                &metadata_refresh_context,
+                &target,
+                safe,
+                execution_mode,
+                dirty_worktree_policy,
+            )?;
+            let local_pr_branch_actions = sync_actions_after_completed_rewrite(
+                &outcome.rewrite,
+                local_pr_branch_policy,
+                execution_mode,
+                &base,
+                &prefix,
+                &ignore_tag,
+            )?;
+            if update && outcome.rewrite == crate::commands::RewriteCommandOutcome::Completed {
+                publish_current_stack_after_rewrite(
+                    &metadata_refresh_context,
+                    Some(crate::limit::Limit::ByPr(outcome.local_pr_number)),
+                    execution_mode,
+                    pr_description_mode,
+                    list_order,
+                    branch_reuse_guard_days,
+                    &commit_lint,
+                    &title_transforms,
+                )?;
+                crate::commands::sync_pr_title(
+                    &metadata_refresh_context,
+                    outcome.local_pr_number,
+                    None,
+                    &title_transforms,
+                    execution_mode,
+                )?;
+            }
+            Ok(CommandOutput::Machine(ensure_rewrite_completed(
+                output_format,
+                "spr edit",
+                crate::machine_output::MachineCommand::Edit,
+                outcome.rewrite,
+                None,
+                local_pr_branch_actions,
+            )?))
+        }
+        crate::cli::Cmd::Split {
+            target,
+            after,
+            tag,
+            update,
+            safe,
+            dry_run,
+        } => {
+            let execution_mode = ExecutionMode::from(dry_run);
+            set_dry_run_env(execution_mode, false);
+            let outcome = crate::commands::split_group(
+                &metadata_refresh_context,
+                &target,
+                after,
+                tag.as_deref(),
+                safe,
+                execution_mode,
+                dirty_worktree_policy,
+            )?;
+            let local_pr_branch_actions = sync_actions_after_completed_rewrite(
+                &outcome.rewrite,
+                local_pr_branch_policy,
+                execution_mode,
+                &base,
+                &prefix,
+                &ignore_tag,
+            )?;
+            if update && outcome.rewrite == crate::commands::RewriteCommandOutcome::Completed {
+                // Every PR above the split moves up one position, so publish the whole stack.
+                publish_current_stack_after_rewrite(
+                    &metadata_refresh_context,
+                    None,
+                    execution_mode,
+                    pr_description_mode,
+                    list_order,
+                    branch_reuse_guard_days,
+                    &commit_lint,
+                    &title_transforms,
+                )?;
+            }
+            Ok(CommandOutput::Machine(ensure_rewrite_completed(
+                output_format,
+                "spr split",
+                crate::machine_output::MachineCommand::Split,
+                outcome.rewrite,
+                None,
+                local_pr_branch_actions,
+            )?))
+        }
+        crate::cli::Cmd::Fold {
+            target,
+            close,
+            update,
+            safe,
+            dry_run,
+        } => {
+            let execution_mode = ExecutionMode::from(dry_run);
+            set_dry_run_env(execution_mode, false);
+            let outcome = crate::commands::fold_groups(
+                &metadata_refresh_context,
+                &target,
+                close,
+                safe,
+                execution_mode,
+                dirty_worktree_policy,
+            )?;
+            let local_pr_branch_actions = sync_actions_after_completed_rewrite(
+                &outcome.rewrite,
+                local_pr_branch_policy,
+                execution_mode,
+                &base,
+                &prefix,
+                &ignore_tag,
+            )?;
+            if update && outcome.rewrite == crate::commands::RewriteCommandOutcome::Completed {
+                // Every PR above the fold moves down one position, so publish the whole stack.
+                publish_current_stack_after_rewrite(
+                    &metadata_refresh_context,
+                    None,
+                    execution_mode,
+                    pr_description_mode,
+                    list_order,
+                    branch_reuse_guard_days,
+                    &commit_lint,
+                    &title_transforms,
+                )?;
+            }
+            Ok(CommandOutput::Machine(ensure_rewrite_completed(
+                output_format,
+                "spr fold",
+                crate::machine_output::MachineCommand::Fold,
+                outcome.rewrite,
+                None,
+                local_pr_branch_actions,
+            )?))
+        }
+        crate::cli::Cmd::RollbackPr {
+            number,
+            tag,
+            no_update,
             dry_run,
         } => {
             let execution_mode = ExecutionMode::from(dry_run);
             set_dry_run_env(execution_mode, false);
-            let mode = which.unwrap_or(match cfg.land.as_str() {
-                "per-pr" | "perpr" | "per_pr" => crate::cli::LandCmd::PerPr,
-                _ => crate::cli::LandCmd::Flatten,
-            });
-            let until = cli
-                .until
-                .unwrap_or(crate::selectors::InclusiveSelector::All);
-            let landed_count = match mode {
-                crate::cli::LandCmd::Flatten => crate::commands::land_flatten_until(
-                    &base,
-                    &prefix,
-                    &ignore_tag,
-                    &until,
-                    execution_mode,
-                    r#unsafe,
-                )?,
-                crate::cli::LandCmd::PerPr => crate::commands::land_per_pr_until(
-                    &base,
-                    &prefix,
-                    &ignore_tag,
-                    &until,
-                    execution_mode,
-                    r#unsafe,
-                )?,
-            };
-            let local_pr_branch_actions = if !no_restack {
-                // After landing the first N PRs, restack the remaining commits onto the latest base
-                let outcome = crate::commands::restack_after_count(
+            let tag = crate::commands::rollback_pr(
+                &metadata_refresh_context,
+                number,
+                tag.as_deref(),
+                execution_mode,
+            )?;
+            if no_update {
+                tracing::info!("Created pr:{tag}; run `spr update` to open its PR.");
+            } else if execution_mode == ExecutionMode::Apply {
+                publish_current_stack_after_rewrite(
                     &metadata_refresh_context,
-                    landed_count,
-                    false,
+                    None,
                     execution_mode,
-                    restack_conflict_policy,
-                    dirty_worktree_policy,
+                    pr_description_mode,
+                    list_order,
+                    branch_reuse_guard_days,
+                    &commit_lint,
+                    &title_transforms,
                 )?;
-                if let crate::commands::RewriteCommandOutcome::Suspended(state) = &outcome {
-                    let post_success_hint = Some(
-                        "GitHub landing already succeeded; resolve the local restack conflict and run the printed `spr resume <path>` command instead of rerunning `spr land`."
-                            .to_string(),
-                    );
-                    if output_format == crate::cli::OutputFormat::Json {
-                        return Ok(CommandOutput::Machine(
-                            crate::machine_output::MachineOutput::suspended(
-                                crate::machine_output::MachineCommand::Land,
-                                (**state).clone(),
-                                post_success_hint,
-                            ),
-                        ));
-                    } else {
-                        return Err(anyhow::anyhow!(
-                            "GitHub landing already succeeded, but the follow-on restack suspended due to a cherry-pick conflict. Resolve the conflict in the temp worktree, stage the resolution, and run `spr resume {}` instead of rerunning `spr land`.",
-                            state.resume_path.display()
-                        ));
-                    }
-                }
-                sync_actions_after_completed_rewrite(
-                    &outcome,
-                    local_pr_branch_policy,
-                    execution_mode,
-                    &base,
-                    &prefix,
-                    &ignore_tag,
-                )?
             } else {
-                Vec::new()
-            };
+                tracing::info!("DRY-RUN: would publish the stack to open the PR for pr:{tag}.");
+            }
             Ok(CommandOutput::Machine(
                 crate::machine_output::MachineOutput::completed_with_local_pr_branch_actions(
-                    crate::machine_output::MachineCommand::Land,
-                    local_pr_branch_actions,
+                    crate::machine_output::MachineCommand::RollbackPr,
+                    Vec::new(),
                 ),
             ))
         }
-        crate::cli::Cmd::RelinkPrs { dry_run } => {
+        crate::cli::Cmd::Cherry {
+            target,
+            onto,
+            draft,
+            dry_run,
+        } => {
             let execution_mode = ExecutionMode::from(dry_run);
             set_dry_run_env(execution_mode, false);
-            let summary = crate::commands::relink_prs(&base, &prefix, &ignore_tag, execution_mode)?;
-            if output_format == crate::cli::OutputFormat::Json {
-                Ok(CommandOutput::Maintenance(Box::new(
-                    crate::maintenance_output::relink_prs_summary(summary),
-                )))
-            } else {
-                crate::commands::print_relink_prs_summary(&summary);
-                Ok(CommandOutput::None)
-            }
+            crate::commands::cherry_group(
+                &base,
+                &prefix,
+                &ignore_tag,
+                &target,
+                &onto,
+                draft,
+                execution_mode,
+            )?;
+            Ok(CommandOutput::Machine(
+                crate::machine_output::MachineOutput::completed_with_local_pr_branch_actions(
+                    crate::machine_output::MachineCommand::Cherry,
+                    Vec::new(),
+                ),
+            ))
         }
-        crate::cli::Cmd::Cleanup { dry_run } => {
+        crate::cli::Cmd::Title {
+            target,
+            title,
+            safe,
+            dry_run,
+        } => {
             let execution_mode = ExecutionMode::from(dry_run);
             set_dry_run_env(execution_mode, false);
-            let summary = crate::commands::cleanup_remote_branches(&prefix, execution_mode)?;
-            if output_format == crate::cli::OutputFormat::Json {
-                Ok(CommandOutput::Maintenance(Box::new(
-                    crate::maintenance_output::cleanup_summary(summary),
-                )))
-            } else {
-                crate::commands::print_cleanup_summary(&summary);
-                Ok(CommandOutput::None)
+            let outcome = crate::commands::retitle_group(
+                &metadata_refresh_context,
+                &target,
+                &title,
+                safe,
+                execution_mode,
+                dirty_worktree_policy,
+            )?;
+            let local_pr_branch_actions = sync_actions_after_completed_rewrite(
+                &outcome.rewrite,
+                local_pr_branch_policy,
+                execution_mode,
+                &base,
+                &prefix,
+                &ignore_tag,
+            )?;
+            if outcome.rewrite == crate::commands::RewriteCommandOutcome::Completed {
+                crate::commands::sync_pr_title(
+                    &metadata_refresh_context,
+                    outcome.local_pr_number,
+                    Some(&title),
+                    &title_transforms,
+                    execution_mode,
+                )?;
             }
+            Ok(CommandOutput::Machine(ensure_rewrite_completed(
+                output_format,
+                "spr title",
+                crate::machine_output::MachineCommand::Title,
+                outcome.rewrite,
+                None,
+                local_pr_branch_actions,
+            )?))
         }
-        crate::cli::Cmd::FixPr {
+        crate::cli::Cmd::Describe {
             target,
-            tail,
             safe,
             dry_run,
         } => {
             let execution_mode = ExecutionMode::from(dry_run);
             set_dry_run_env(execution_mode, false);
-            let outcome = crate::commands::fix_pr_tail(
+            let outcome = crate::commands::describe_group(
                 &metadata_refresh_context,
                 &target,
-                tail,
                 safe,
                 execution_mode,
                 dirty_worktree_policy,
             )?;
             let local_pr_branch_actions = sync_actions_after_completed_rewrite(
-                &outcome,
+                &outcome.rewrite,
                 local_pr_branch_policy,
                 execution_mode,
                 &base,
@@ -1020,9 +2908,9 @@ fn run_cli(cli: crate::cli::Cli, output_format: crate::cli::OutputFormat) -> Res
             )?;
             Ok(CommandOutput::Machine(ensure_rewrite_completed(
                 output_format,
-                "spr fix-pr",
-                crate::machine_output::MachineCommand::FixPr,
-                outcome,
+                "spr describe",
+                crate::machine_output::MachineCommand::Describe,
+                outcome.rewrite,
                 None,
                 local_pr_branch_actions,
             )?))
@@ -1030,17 +2918,25 @@ fn run_cli(cli: crate::cli::Cli, output_format: crate::cli::OutputFormat) -> Res
         crate::cli::Cmd::Move {
             range,
             after,
+            before,
             safe,
             dry_run,
         } => {
             let execution_mode = ExecutionMode::from(dry_run);
             set_dry_run_env(execution_mode, false);
+            let destination = match (after, before) {
+                (Some(after), _) => crate::commands::MoveDestination::After(after),
+                (None, Some(before)) => crate::commands::MoveDestination::Before(before),
+                (None, None) => {
+                    return Err(anyhow::anyhow!("spr move requires --after or --before"))
+                }
+            };
             let outcome = crate::commands::move_groups_after(
                 &base,
                 &prefix,
                 &ignore_tag,
                 &range,
-                &after,
+                &destination,
                 crate::commands::MoveExecutionOptions {
                     safe,
                     execution_mode,
@@ -1064,6 +2960,36 @@ fn run_cli(cli: crate::cli::Cli, output_format: crate::cli::OutputFormat) -> Res
                 local_pr_branch_actions,
             )?))
         }
+        crate::cli::Cmd::ApplyRemoteDirectives { safe, dry_run } => {
+            let execution_mode = ExecutionMode::from(dry_run);
+            set_dry_run_env(execution_mode, false);
+            let outcome = crate::commands::apply_remote_directives(
+                &base,
+                &prefix,
+                &ignore_tag,
+                crate::commands::MoveExecutionOptions {
+                    safe,
+                    execution_mode,
+                    dirty_worktree_policy,
+                },
+            )?;
+            let local_pr_branch_actions = sync_actions_after_completed_rewrite(
+                &outcome,
+                local_pr_branch_policy,
+                execution_mode,
+                &base,
+                &prefix,
+                &ignore_tag,
+            )?;
+            Ok(CommandOutput::Machine(ensure_rewrite_completed(
+                output_format,
+                "spr apply-remote-directives",
+                crate::machine_output::MachineCommand::ApplyRemoteDirectives,
+                outcome,
+                None,
+                local_pr_branch_actions,
+            )?))
+        }
     }
 }
 
@@ -1155,8 +3081,16 @@ fn main() {
         crate::cli::OutputFormat::Human
     };
     init_logging(cli.verbose, output_format);
+    let timing = cli.timing;
+    if timing {
+        crate::timing::enable();
+    }
     let command = json_command_for_cli(&cli.cmd);
-    match run_cli(cli, output_format) {
+    let result = run_cli(cli, output_format);
+    if timing {
+        crate::timing::print_summary();
+    }
+    match result {
         Ok(output) => match output {
             CommandOutput::None => {}
             CommandOutput::AbsorbQuery(output) => {
@@ -1176,6 +3110,8 @@ fn main() {
             CommandOutput::Machine(output) => {
                 if output_format == crate::cli::OutputFormat::Json {
                     exit_with_json(&output, output.exit_code());
+                } else if output.exit_code() != crate::json_output::EXIT_SUCCESS {
+                    std::process::exit(output.exit_code());
                 }
             }
             CommandOutput::ReadOnly(output) => {
@@ -1190,6 +3126,16 @@ fn main() {
                     println!("{}", output.render_human());
                 }
             }
+            CommandOutput::RestackCheck(output) => {
+                if output_format == crate::cli::OutputFormat::Json {
+                    exit_with_json(&output, output.exit_code());
+                } else {
+                    println!("{}", output.render_human());
+                    if output.exit_code() != crate::json_output::EXIT_SUCCESS {
+                        std::process::exit(output.exit_code());
+                    }
+                }
+            }
             CommandOutput::Update(output) => {
                 if output_format == crate::cli::OutputFormat::Json {
                     exit_with_json(&output, output.exit_code());
@@ -1225,40 +3171,109 @@ fn main() {
     }
 }
 
+fn run_pr_stage_command(
+    stage: crate::maintenance_output::PrStage,
+    target: &crate::cli::PrStageTargetArgs,
+    dry_run: crate::cli::DryRunArgs,
+    output_format: crate::cli::OutputFormat,
+    base: &str,
+    prefix: &str,
+    ignore_tag: &str,
+) -> Result<CommandOutput> {
+    let execution_mode = ExecutionMode::from(dry_run);
+    set_dry_run_env(execution_mode, false);
+    let summary = crate::commands::set_pr_stage(
+        base,
+        prefix,
+        ignore_tag,
+        target.target.as_ref(),
+        stage,
+        execution_mode,
+    )?;
+    if output_format == crate::cli::OutputFormat::Json {
+        Ok(CommandOutput::Maintenance(Box::new(
+            crate::maintenance_output::pr_stage_summary(summary),
+        )))
+    } else {
+        crate::commands::print_pr_stage_summary(&summary);
+        Ok(CommandOutput::None)
+    }
+}
+
 fn json_command_for_cli(cmd: &crate::cli::Cmd) -> crate::json_output::JsonCommand {
     match cmd {
         crate::cli::Cmd::Restack { .. } => crate::machine_output::MachineCommand::Restack,
+        crate::cli::Cmd::Base { .. } => crate::machine_output::MachineCommand::BaseSet,
+        crate::cli::Cmd::Sync { .. } => crate::machine_output::MachineCommand::Sync,
         crate::cli::Cmd::AdoptPrefix { .. } => crate::machine_output::MachineCommand::AdoptPrefix,
         crate::cli::Cmd::DropMergedPrefix { .. } => {
             crate::machine_output::MachineCommand::DropMergedPrefix
         }
+        crate::cli::Cmd::RenamePrefix { .. } => crate::machine_output::MachineCommand::RenamePrefix,
         crate::cli::Cmd::Absorb { .. } => crate::machine_output::MachineCommand::Absorb,
         crate::cli::Cmd::ResolveStack { .. } => crate::machine_output::MachineCommand::ResolveStack,
         crate::cli::Cmd::Resume { .. } => crate::machine_output::MachineCommand::Resume,
+        crate::cli::Cmd::Abort { .. } => crate::machine_output::MachineCommand::Abort,
+        crate::cli::Cmd::Init { .. } => crate::machine_output::MachineCommand::Init,
         crate::cli::Cmd::Land { .. } => crate::machine_output::MachineCommand::Land,
         crate::cli::Cmd::FixPr { .. } => crate::machine_output::MachineCommand::FixPr,
+        crate::cli::Cmd::ImportPr { .. } => crate::machine_output::MachineCommand::ImportPr,
+        crate::cli::Cmd::Fixup { .. } => crate::machine_output::MachineCommand::Fixup,
+        crate::cli::Cmd::Amend { .. } => crate::machine_output::MachineCommand::Amend,
+        crate::cli::Cmd::SquashRange { .. } => crate::machine_output::MachineCommand::SquashRange,
+        crate::cli::Cmd::Pull { .. } => crate::machine_output::MachineCommand::Pull,
+        crate::cli::Cmd::Edit { .. } => crate::machine_output::MachineCommand::Edit,
+        crate::cli::Cmd::Split { .. } => crate::machine_output::MachineCommand::Split,
+        crate::cli::Cmd::Fold { .. } => crate::machine_output::MachineCommand::Fold,
+        crate::cli::Cmd::RollbackPr { .. } => crate::machine_output::MachineCommand::RollbackPr,
+        crate::cli::Cmd::Cherry { .. } => crate::machine_output::MachineCommand::Cherry,
+        crate::cli::Cmd::Title { .. } => crate::machine_output::MachineCommand::Title,
+        crate::cli::Cmd::Describe { .. } => crate::machine_output::MachineCommand::Describe,
         crate::cli::Cmd::Move { .. } => crate::machine_output::MachineCommand::Move,
+        crate::cli::Cmd::ApplyRemoteDirectives { .. } => {
+            crate::machine_output::MachineCommand::ApplyRemoteDirectives
+        }
         crate::cli::Cmd::Update { .. } => crate::machine_output::MachineCommand::Update,
+        crate::cli::Cmd::Flush { .. } => crate::machine_output::MachineCommand::Flush,
         crate::cli::Cmd::Prep { .. } => crate::machine_output::MachineCommand::Prep,
         crate::cli::Cmd::List { what, .. } => match what {
             crate::cli::ListWhat::Pr => crate::machine_output::MachineCommand::ListPr,
             crate::cli::ListWhat::Commit => crate::machine_output::MachineCommand::ListCommit,
         },
-        crate::cli::Cmd::Status => crate::machine_output::MachineCommand::Status,
+        crate::cli::Cmd::Files { .. } => crate::machine_output::MachineCommand::Files,
+        crate::cli::Cmd::Diff { .. } => crate::machine_output::MachineCommand::Diff,
+        crate::cli::Cmd::RangeDiff { .. } => crate::machine_output::MachineCommand::RangeDiff,
+        crate::cli::Cmd::Checkout { .. } => crate::machine_output::MachineCommand::Checkout,
+        crate::cli::Cmd::Freeze { .. } => crate::machine_output::MachineCommand::Freeze,
+        crate::cli::Cmd::Thaw { .. } => crate::machine_output::MachineCommand::Thaw,
+        crate::cli::Cmd::Landed { .. } => crate::machine_output::MachineCommand::Landed,
+        crate::cli::Cmd::Parse => crate::machine_output::MachineCommand::Parse,
+        crate::cli::Cmd::Backup { .. } => crate::machine_output::MachineCommand::Backup,
+        crate::cli::Cmd::Stats { .. } => crate::machine_output::MachineCommand::Stats,
+        crate::cli::Cmd::Verify { .. } => crate::machine_output::MachineCommand::Verify,
+        crate::cli::Cmd::Status { .. } => crate::machine_output::MachineCommand::Status,
         crate::cli::Cmd::SyncLocalBranches => {
             crate::machine_output::MachineCommand::SyncLocalBranches
         }
         crate::cli::Cmd::RelinkPrs { .. } => crate::machine_output::MachineCommand::RelinkPrs,
+        crate::cli::Cmd::TrackIssue { .. } => crate::machine_output::MachineCommand::TrackIssue,
         crate::cli::Cmd::Cleanup { .. } => crate::machine_output::MachineCommand::Cleanup,
+        crate::cli::Cmd::Ready { .. } => crate::machine_output::MachineCommand::Ready,
+        crate::cli::Cmd::Draft { .. } => crate::machine_output::MachineCommand::Draft,
+        crate::cli::Cmd::Open { .. } => crate::machine_output::MachineCommand::Open,
+        crate::cli::Cmd::Label { .. } => crate::machine_output::MachineCommand::Label,
+        crate::cli::Cmd::Milestone { .. } => crate::machine_output::MachineCommand::Milestone,
+        crate::cli::Cmd::Assign { .. } => crate::machine_output::MachineCommand::Assign,
+        crate::cli::Cmd::Automerge { .. } => crate::machine_output::MachineCommand::Automerge,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::{
-        apply_working_directory_override, command_requires_gh, ensure_rewrite_completed,
-        json_command_for_raw_args, parse_failure_as_json_output, resolve_update_pr_limit, run_cli,
-        CommandOutput,
+        apply_working_directory_override, command_requires_gh, command_supports_gitlab,
+        ensure_rewrite_completed, json_command_for_raw_args, parse_failure_as_json_output,
+        resolve_update_pr_limit, run_cli, CommandOutput,
     };
     use crate::cli::{DryRunArgs, OutputFormat};
     use crate::json_output::{ErrorPayload, JsonCommand, JsonError, EXIT_FAILURE};
@@ -1423,6 +3438,8 @@ mod tests {
             from: None,
             allow_replayed_duplicates: false,
             query_changed_branches: false,
+            staged: false,
+            safe: false,
             dry_run: DryRunArgs::default(),
         }));
     }
@@ -1447,13 +3464,49 @@ mod tests {
     #[test]
     fn resume_is_local_only_for_tool_checks() {
         assert!(!command_requires_gh(&crate::cli::Cmd::Resume {
-            path: std::path::PathBuf::from(".git/spr/resume/example.json"),
+            path: Some(std::path::PathBuf::from(".git/spr/resume/example.json")),
         }));
+        assert!(!command_requires_gh(&crate::cli::Cmd::Abort { path: None }));
+    }
+
+    #[test]
+    fn restack_requires_github_cli_only_with_update() {
+        let restack = |update| crate::cli::Cmd::Restack {
+            after: Some("1".parse().unwrap()),
+            interactive: false,
+            safe: false,
+            preview: false,
+            update,
+            check: false,
+            from: None,
+            dry_run: DryRunArgs::default(),
+        };
+        assert!(!command_requires_gh(&restack(false)));
+        assert!(command_requires_gh(&restack(true)));
     }
 
     #[test]
     fn status_requires_github_cli() {
-        assert!(command_requires_gh(&crate::cli::Cmd::Status));
+        assert!(command_requires_gh(&crate::cli::Cmd::Status {
+            watch: false,
+            interval: 30,
+            notify: false,
+        }));
+    }
+
+    #[test]
+    fn gitlab_forge_supports_status_and_local_commands_but_not_github_only_ones() {
+        assert!(command_supports_gitlab(&crate::cli::Cmd::Status {
+            watch: false,
+            interval: 30,
+            notify: false,
+        }));
+        assert!(command_supports_gitlab(&crate::cli::Cmd::SyncLocalBranches));
+        assert!(!command_supports_gitlab(&crate::cli::Cmd::Stats {
+            history: false,
+            limit: 50,
+            since: None,
+        }));
     }
 
     #[test]
@@ -1470,6 +3523,10 @@ mod tests {
             assume_existing_prs: false,
             pr_description_mode: None,
             allow_branch_reuse: false,
+            draft: false,
+            offline: false,
+            plan_out: None,
+            thaw: false,
             dry_run: DryRunArgs::default(),
             extent: None,
         }));
@@ -1484,6 +3541,28 @@ mod tests {
             assume_existing_prs: false,
             pr_description_mode: None,
             allow_branch_reuse: false,
+            draft: false,
+            offline: false,
+            plan_out: None,
+            thaw: false,
+            dry_run: DryRunArgs::default(),
+            extent: None,
+        }));
+    }
+
+    #[test]
+    fn update_offline_stays_git_only_for_tool_checks() {
+        assert!(!command_requires_gh(&crate::cli::Cmd::Update {
+            from: "HEAD".to_string(),
+            no_pr: false,
+            restack: false,
+            assume_existing_prs: false,
+            pr_description_mode: None,
+            allow_branch_reuse: false,
+            draft: false,
+            offline: true,
+            plan_out: None,
+            thaw: false,
             dry_run: DryRunArgs::default(),
             extent: None,
         }));
@@ -1748,6 +3827,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn json_command_for_raw_args_detects_sync() {
+        let args = vec![
+            OsString::from("spr"),
+            OsString::from("--json"),
+            OsString::from("sync"),
+            OsString::from("--safe"),
+        ];
+
+        assert_eq!(json_command_for_raw_args(&args), JsonCommand::Sync);
+    }
+
     #[test]
     fn json_command_for_raw_args_detects_list_alias() {
         let args = vec![
@@ -2017,6 +4108,75 @@ mod tests {
         }
     }
 
+    #[test]
+    fn run_cli_prep_allow_empty_preserves_empty_group() {
+        let _lock = lock_cwd();
+        let dir = init_update_stack_repo();
+        let repo = dir.path().join("repo");
+        let _guard = DirGuard::change_to(&repo);
+        let _home_guard = EnvVarGuard::set("HOME", dir.path().display().to_string());
+        let origin_url = format!("file://{}", dir.path().join("origin.git").display());
+        git(
+            &repo,
+            ["remote", "set-url", "origin", origin_url.as_str()].as_slice(),
+        );
+        git(
+            &repo,
+            [
+                "commit",
+                "--allow-empty",
+                "-m",
+                "chore: placeholder pr:gamma",
+            ]
+            .as_slice(),
+        );
+        let log_path = repo.join("gh.log");
+        let script = prep_json_gh_script(&log_path);
+        let (_wrapper_dir, _path_guard) = install_gh_wrapper(&script);
+        let run = |allow_empty: bool| {
+            let mut args = vec![
+                "spr",
+                "--cd",
+                repo.to_str().unwrap(),
+                "--base",
+                "main",
+                "--prefix",
+                "dank-spr/",
+                "--exact",
+                "3",
+                "prep",
+                "--dry-run",
+                "--json",
+            ];
+            if allow_empty {
+                args.push("--allow-empty");
+            }
+            let cli = crate::cli::Cli::try_parse_from(args).unwrap();
+            match run_cli(cli, OutputFormat::Json).unwrap() {
+                CommandOutput::Maintenance(output) => match output.data {
+                    MaintenancePayload::Prep { data } => data,
+                    other => panic!("unexpected maintenance payload: {:?}", other),
+                },
+                other => panic!("unexpected command output: {:?}", other),
+            }
+        };
+
+        let skipped = run(false);
+        assert_eq!(
+            skipped.selected_groups[0].action,
+            crate::maintenance_output::PreparedGroupAction::SkippedEmpty
+        );
+
+        let preserved = run(true);
+        assert!(preserved.options.allow_empty);
+        assert_eq!(preserved.selected_groups[0].stable_handle, "pr:gamma");
+        assert_eq!(
+            preserved.selected_groups[0].action,
+            crate::maintenance_output::PreparedGroupAction::PreservedEmpty
+        );
+        assert!(preserved.selected_groups[0].target_sha.is_some());
+    }
+
     #[test]
     fn run_cli_prep_from_json_returns_suffix_summary() {
         let _lock = lock_cwd();
@@ -2460,7 +4620,7 @@ mod tests {
     }
 
     #[test]
-    fn run_cli_status_json_matches_list_pr_payload() {
+    fn run_cli_status_json_extends_list_pr_payload() {
         let _lock = lock_cwd();
         let _restore = CurrentDirGuard::capture();
         let repo = init_local_stack_repo();
@@ -2574,7 +4734,24 @@ mod tests {
             (CommandOutput::ReadOnly(list_output), CommandOutput::ReadOnly(status_output)) => {
                 assert_eq!(list_output.command, JsonCommand::ListPr);
                 assert_eq!(status_output.command, JsonCommand::Status);
-                assert_eq!(list_output.data, status_output.data);
+                let (
+                    crate::read_only_output::ReadOnlyPayload::PrList { data: list },
+                    crate::read_only_output::ReadOnlyPayload::Status { data: status },
+                ) = (list_output.data, status_output.data)
+                else {
+                    panic!("expected a pr list and a status payload");
+                };
+                assert_eq!(status.list, list);
+                assert_eq!(status.segments.len(), 1);
+                assert_eq!(status.segments[0].base, "main");
+                assert_eq!(status.segments[0].group_count, 2);
+                assert_eq!(
+                    status.groups[1].base_chain,
+                    crate::commands::status::BaseChainState::Misrouted {
+                        current: "main".to_string(),
+                        expected: "dank-spr/alpha".to_string(),
+                    }
+                );
             }
             other => panic!("unexpected command outputs: {:?}", other),
         }
@@ -2955,7 +5132,8 @@ mod tests {
             "GitHub PR base chain did not converge after update: dank-spr/beta: main -> dank-spr/alpha"
         );
         let log = fs::read_to_string(log_path).unwrap();
-        assert!(log.contains("baseRefName:\"dank-spr/alpha\""), "{log}");
+        assert!(log.contains("baseRefName:$v1"), "{log}");
+        assert!(log.contains("-f v1=dank-spr/alpha"), "{log}");
     }
 
     #[test]
@@ -3274,7 +5452,7 @@ mod tests {
         assert_eq!(
             rev_parse(
                 &repo,
-                &format!("refs/tags/backup/absorb/stack-{original_stack_short}")
+                &format!("refs/tags/backup/absorb/stack-{original_stack_short}^{{commit}}")
             ),
             beta_tip
         );
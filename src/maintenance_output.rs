@@ -26,6 +26,14 @@ pub enum MaintenancePayload {
         #[serde(flatten)]
         data: Box<LocalPrBranchSyncSummaryData>,
     },
+    PrStage {
+        #[serde(flatten)]
+        data: Box<PrStageSummaryData>,
+    },
+    TrackIssue {
+        #[serde(flatten)]
+        data: Box<TrackIssueSummaryData>,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
@@ -52,6 +60,7 @@ pub struct PrepRepoContext {
 pub struct PrepOptions {
     pub dry_run: bool,
     pub pr_description_mode: PrDescriptionMode,
+    pub allow_empty: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
@@ -77,6 +86,7 @@ pub enum ResolvedPrepSelection {
 pub enum PreparedGroupAction {
     Squashed,
     PreservedSingleCommit,
+    PreservedEmpty,
     SkippedEmpty,
 }
 
@@ -162,6 +172,7 @@ pub struct CleanupSummaryData {
     pub open_pr_heads: Vec<String>,
     pub decisions: Vec<CleanupDecisionData>,
     pub delete_batch: Vec<String>,
+    pub orphaned_prs: Vec<CleanupOrphanedPrData>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
@@ -197,6 +208,64 @@ pub struct CleanupDecisionData {
     pub action: CleanupAction,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CleanupOrphanedPrAction {
+    Close,
+    DryRunClose,
+}
+
+/// An open PR under the prefix whose head no longer matches any group in the local stack.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CleanupOrphanedPrData {
+    pub number: u64,
+    pub head_branch: String,
+    pub action: CleanupOrphanedPrAction,
+}
+
+/// Review stage requested by `spr ready` or `spr draft`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PrStage {
+    Ready,
+    Draft,
+}
+
+impl PrStage {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Ready => "ready",
+            Self::Draft => "draft",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PrStageSummaryData {
+    pub repo: MaintenanceRepoContext,
+    pub options: MaintenanceOptions,
+    pub stage: PrStage,
+    pub decisions: Vec<PrStageDecisionData>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PrStageAction {
+    Converted,
+    DryRunConvert,
+    AlreadyInStage,
+    MissingOpenPr,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PrStageDecisionData {
+    pub local_pr_number: usize,
+    pub stable_handle: String,
+    pub head_branch: String,
+    pub remote_pr_number: Option<u64>,
+    pub action: PrStageAction,
+}
+
 pub fn prep_summary(data: PrepSummaryData) -> MaintenanceOutput {
     SummaryOutput::new(
         JsonCommand::Prep,
@@ -206,6 +275,26 @@ pub fn prep_summary(data: PrepSummaryData) -> MaintenanceOutput {
     )
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrackIssueAction {
+    Created,
+    Updated,
+    DryRunCreate,
+    DryRunUpdate,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TrackIssueSummaryData {
+    pub repo: MaintenanceRepoContext,
+    pub options: MaintenanceOptions,
+    pub stack_branch: String,
+    /// `None` only for a dry-run create, where no issue exists yet.
+    pub issue_number: Option<u64>,
+    pub action: TrackIssueAction,
+    pub tracked_pr_count: usize,
+}
+
 pub fn relink_prs_summary(data: RelinkPrsSummaryData) -> MaintenanceOutput {
     SummaryOutput::new(
         JsonCommand::RelinkPrs,
@@ -224,6 +313,28 @@ pub fn cleanup_summary(data: CleanupSummaryData) -> MaintenanceOutput {
     )
 }
 
+pub fn track_issue_summary(data: TrackIssueSummaryData) -> MaintenanceOutput {
+    SummaryOutput::new(
+        JsonCommand::TrackIssue,
+        MaintenancePayload::TrackIssue {
+            data: Box::new(data),
+        },
+    )
+}
+
+pub fn pr_stage_summary(data: PrStageSummaryData) -> MaintenanceOutput {
+    let command = match data.stage {
+        PrStage::Ready => JsonCommand::Ready,
+        PrStage::Draft => JsonCommand::Draft,
+    };
+    SummaryOutput::new(
+        command,
+        MaintenancePayload::PrStage {
+            data: Box::new(data),
+        },
+    )
+}
+
 pub fn local_pr_branch_sync_summary(data: LocalPrBranchSyncSummaryData) -> MaintenanceOutput {
     SummaryOutput::new(
         JsonCommand::SyncLocalBranches,
@@ -257,6 +368,7 @@ mod tests {
             options: PrepOptions {
                 dry_run: true,
                 pr_description_mode: PrDescriptionMode::Overwrite,
+                allow_empty: false,
             },
             selection: ResolvedPrepSelection::All,
             selected_groups: vec![PreparedGroupData {
@@ -325,6 +437,7 @@ mod tests {
                 action: CleanupAction::SkipOpenPr,
             }],
             delete_batch: Vec::new(),
+            orphaned_prs: Vec::new(),
         });
 
         assert_eq!(output.command, JsonCommand::Cleanup);
@@ -344,6 +457,7 @@ mod tests {
             options: PrepOptions {
                 dry_run: false,
                 pr_description_mode: PrDescriptionMode::Overwrite,
+                allow_empty: false,
             },
             selection: ResolvedPrepSelection::All,
             selected_groups: Vec::new(),
@@ -366,6 +480,7 @@ mod tests {
                     options: PrepOptions {
                         dry_run: false,
                         pr_description_mode: PrDescriptionMode::Overwrite,
+                        allow_empty: false,
                     },
                     selection: ResolvedPrepSelection::All,
                     selected_groups: Vec::new(),
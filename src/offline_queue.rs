@@ -0,0 +1,397 @@
+//! Queue of `spr update --offline` runs waiting for `spr flush`.
+//!
+//! The queue lives under the repository common Git directory (`spr/offline_queue.json`) so every
+//! linked worktree sees it. Each entry journals what the offline run prepared: the stack `HEAD`,
+//! the branch pushes (branch and tip SHA), and the PR creations or edits (head, base, title) it
+//! would have made. `spr flush` publishes exactly that snapshot, even if the branch moved since,
+//! and reports where the recorded plan no longer matches what it publishes.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+
+use crate::branch_names::group_branch_identities;
+use crate::config::PrDescriptionMode;
+use crate::git::sanitize_gh_base_ref;
+use crate::parsing::Group;
+use crate::stack_segments::{replay_segment_onto_base, split_segments};
+use crate::title_transforms::TitleTransformConfig;
+
+const OFFLINE_QUEUE_SCHEMA_VERSION: u32 = 2;
+const OFFLINE_QUEUE_FILE_NAME: &str = "offline_queue.json";
+
+/// One deferred `spr update` for a stack branch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueuedUpdate {
+    pub stack_branch: String,
+    /// `HEAD` when the update was queued; flush warns when the stack moved since.
+    pub head: String,
+    /// `spr update pr --to/--n` count, or `None` for the whole stack.
+    pub pr_limit: Option<usize>,
+    pub pr_description_mode: PrDescriptionMode,
+    /// Whether PRs created by the flush open as drafts (`--draft` or the `draft` config).
+    #[serde(default)]
+    pub draft: bool,
+    pub queued_at: String,
+    /// Branch pushes planned offline, bottom first.
+    #[serde(default)]
+    pub pushes: Vec<QueuedPush>,
+    /// PR creations or edits planned offline, bottom first.
+    #[serde(default)]
+    pub pr_edits: Vec<QueuedPrEdit>,
+}
+
+/// A planned push of `sha` to `branch`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueuedPush {
+    pub branch: String,
+    pub sha: String,
+}
+
+/// A planned PR for `head`, created or edited to target `base` with `title`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueuedPrEdit {
+    pub head: String,
+    pub base: String,
+    pub title: String,
+}
+
+/// The pushes and PR edits `spr update` would make for `groups` (the publishable prefix, already
+/// limited), computed without contacting GitHub.
+///
+/// Groups of a `pr:base:` segment are replayed onto the segment base first, as publishing does, so
+/// the recorded SHAs are the ones `spr flush` pushes. Replays keep the original dates, so replaying
+/// the same commits again at flush time yields the same SHAs.
+///
+/// # Errors
+///
+/// Returns errors when branch names collide, a group has no title, or a segment does not replay
+/// onto its base.
+pub fn plan_offline_update(
+    base: &str,
+    prefix: &str,
+    groups: &[Group],
+    title_transforms: &TitleTransformConfig,
+) -> Result<(Vec<QueuedPush>, Vec<QueuedPrEdit>)> {
+    let mut identities = group_branch_identities(groups, prefix)?.into_iter();
+    let mut pushes = Vec::with_capacity(groups.len());
+    let mut pr_edits = Vec::with_capacity(groups.len());
+    for mut segment in split_segments(base, groups.to_vec()) {
+        if segment.base != base {
+            replay_segment_onto_base(&mut segment)?;
+        }
+        let mut parent = sanitize_gh_base_ref(&segment.base);
+        for (group, identity) in segment.groups.iter().zip(identities.by_ref()) {
+            if let Some(sha) = group.commits.last() {
+                pushes.push(QueuedPush {
+                    branch: identity.exact.clone(),
+                    sha: sha.clone(),
+                });
+            }
+            pr_edits.push(QueuedPrEdit {
+                head: identity.exact.clone(),
+                base: parent.clone(),
+                title: group.pr_title_with(title_transforms)?,
+            });
+            parent = identity.exact;
+        }
+    }
+    Ok((pushes, pr_edits))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct OfflineQueueFile {
+    schema_version: u32,
+    updates: Vec<QueuedUpdate>,
+}
+
+fn queue_path(git_common_dir: &Path) -> PathBuf {
+    git_common_dir.join("spr").join(OFFLINE_QUEUE_FILE_NAME)
+}
+
+fn read_queue(path: &Path) -> Result<Vec<QueuedUpdate>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read offline queue: {}", path.display()))?;
+    let file: OfflineQueueFile = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse offline queue: {}", path.display()))?;
+    Ok(file.updates)
+}
+
+fn write_queue(path: &Path, updates: Vec<QueuedUpdate>) -> Result<()> {
+    if updates.is_empty() {
+        if path.exists() {
+            fs::remove_file(path)
+                .with_context(|| format!("Failed to remove offline queue: {}", path.display()))?;
+        }
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let file = OfflineQueueFile {
+        schema_version: OFFLINE_QUEUE_SCHEMA_VERSION,
+        updates,
+    };
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_string_pretty(&file)? + "\n")
+        .with_context(|| format!("Failed to write offline queue: {}", tmp.display()))?;
+    fs::rename(&tmp, path)
+        .with_context(|| format!("Failed to replace offline queue: {}", path.display()))?;
+    Ok(())
+}
+
+/// Build a queue entry stamped with the current time.
+pub fn queued_update(
+    stack_branch: &str,
+    head: &str,
+    pr_limit: Option<usize>,
+    pr_description_mode: PrDescriptionMode,
+    draft: bool,
+    (pushes, pr_edits): (Vec<QueuedPush>, Vec<QueuedPrEdit>),
+) -> Result<QueuedUpdate> {
+    Ok(QueuedUpdate {
+        stack_branch: stack_branch.to_string(),
+        head: head.to_string(),
+        pr_limit,
+        pr_description_mode,
+        draft,
+        queued_at: OffsetDateTime::now_utc().format(&Rfc3339)?,
+        pushes,
+        pr_edits,
+    })
+}
+
+/// Where the plan recorded in `queued` differs from `current`, one line per difference.
+pub fn plan_drift(
+    queued: &QueuedUpdate,
+    (pushes, pr_edits): &(Vec<QueuedPush>, Vec<QueuedPrEdit>),
+) -> Vec<String> {
+    let mut drift = Vec::new();
+    for push in &queued.pushes {
+        match pushes.iter().find(|current| current.branch == push.branch) {
+            Some(current) if current.sha == push.sha => {}
+            Some(current) => drift.push(format!(
+                "{} now pushes {} instead of the queued {}",
+                push.branch, current.sha, push.sha
+            )),
+            None => drift.push(format!("{} is no longer published", push.branch)),
+        }
+    }
+    for edit in &queued.pr_edits {
+        if let Some(current) = pr_edits.iter().find(|current| current.head == edit.head) {
+            if current.base != edit.base {
+                drift.push(format!(
+                    "the PR for {} now targets {} instead of the queued {}",
+                    edit.head, current.base, edit.base
+                ));
+            }
+            if current.title != edit.title {
+                drift.push(format!(
+                    "the PR for {} is now titled `{}` instead of the queued `{}`",
+                    edit.head, current.title, edit.title
+                ));
+            }
+        }
+    }
+    drift
+}
+
+/// Queue `update`, replacing any earlier entry for the same stack branch.
+pub fn enqueue_update(git_common_dir: &Path, update: QueuedUpdate) -> Result<()> {
+    let path = queue_path(git_common_dir);
+    let mut updates = read_queue(&path)?;
+    updates.retain(|queued| queued.stack_branch != update.stack_branch);
+    updates.push(update);
+    write_queue(&path, updates)
+}
+
+/// Return the queued update for `stack_branch` without removing it.
+pub fn queued_update_for_branch(
+    git_common_dir: &Path,
+    stack_branch: &str,
+) -> Result<Option<QueuedUpdate>> {
+    Ok(read_queue(&queue_path(git_common_dir))?
+        .into_iter()
+        .find(|queued| queued.stack_branch == stack_branch))
+}
+
+/// Drop the queued update for `stack_branch` after it was flushed.
+pub fn remove_queued_update(git_common_dir: &Path, stack_branch: &str) -> Result<()> {
+    let path = queue_path(git_common_dir);
+    let mut updates = read_queue(&path)?;
+    updates.retain(|queued| queued.stack_branch != stack_branch);
+    write_queue(&path, updates)
+}
+
+/// Stack branches that still have queued updates.
+pub fn queued_branches(git_common_dir: &Path) -> Result<Vec<String>> {
+    Ok(read_queue(&queue_path(git_common_dir))?
+        .into_iter()
+        .map(|queued| queued.stack_branch)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        enqueue_update, plan_drift, plan_offline_update, queue_path, queued_branches,
+        queued_update, queued_update_for_branch, remove_queued_update,
+    };
+    use crate::config::PrDescriptionMode;
+    use crate::parsing::derive_groups_between;
+    use crate::stack_segments::{replay_segment_onto_base, split_segments};
+    use crate::test_support::{commit_file, git, init_repo, lock_cwd, DirGuard};
+    use crate::title_transforms::TitleTransformConfig;
+
+    #[test]
+    fn queue_replaces_per_branch_and_removes_file_when_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let common = dir.path();
+
+        enqueue_update(
+            common,
+            queued_update(
+                "stack",
+                "aaa",
+                None,
+                PrDescriptionMode::Overwrite,
+                false,
+                Default::default(),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        enqueue_update(
+            common,
+            queued_update(
+                "other",
+                "bbb",
+                Some(2),
+                PrDescriptionMode::Overwrite,
+                false,
+                Default::default(),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        enqueue_update(
+            common,
+            queued_update(
+                "stack",
+                "ccc",
+                Some(1),
+                PrDescriptionMode::Overwrite,
+                false,
+                Default::default(),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            queued_branches(common).unwrap(),
+            vec!["other".to_string(), "stack".to_string()]
+        );
+        let stack = queued_update_for_branch(common, "stack").unwrap().unwrap();
+        assert_eq!(stack.head, "ccc");
+        assert_eq!(stack.pr_limit, Some(1));
+
+        remove_queued_update(common, "stack").unwrap();
+        remove_queued_update(common, "other").unwrap();
+        assert!(queued_branches(common).unwrap().is_empty());
+        assert!(!queue_path(common).exists());
+    }
+
+    #[test]
+    fn offline_plan_records_replayed_tips_and_chained_bases_and_reports_drift() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path().to_path_buf();
+        git(&repo, ["branch", "release"].as_slice());
+        git(&repo, ["checkout", "-b", "stack"].as_slice());
+        let alpha = commit_file(&repo, "alpha.txt", "alpha\n", "feat: alpha pr:alpha");
+        let beta = commit_file(&repo, "beta.txt", "beta\n", "feat: beta pr:beta");
+        let local_fix = commit_file(
+            &repo,
+            "fix.txt",
+            "fix\n",
+            "fix: crash pr:fix pr:base:release",
+        );
+        let _guard = DirGuard::change_to(&repo);
+        let (_merge_base, groups) = derive_groups_between("main", "HEAD", "ignore").unwrap();
+
+        let plan = plan_offline_update(
+            "main",
+            "dank-spr/",
+            &groups,
+            &TitleTransformConfig::default(),
+        )
+        .unwrap();
+
+        let mut segments = split_segments("main", groups.clone());
+        replay_segment_onto_base(&mut segments[1]).unwrap();
+        let replayed_fix = segments[1].groups[0].commits.last().cloned().unwrap();
+        assert_ne!(replayed_fix, local_fix);
+        let pushes: Vec<(&str, &str)> = plan
+            .0
+            .iter()
+            .map(|push| (push.branch.as_str(), push.sha.as_str()))
+            .collect();
+        assert_eq!(
+            pushes,
+            vec![
+                ("dank-spr/alpha", alpha.as_str()),
+                ("dank-spr/beta", beta.as_str()),
+                ("dank-spr/fix", replayed_fix.as_str())
+            ]
+        );
+        let bases: Vec<(&str, &str)> = plan
+            .1
+            .iter()
+            .map(|edit| (edit.head.as_str(), edit.base.as_str()))
+            .collect();
+        assert_eq!(
+            bases,
+            vec![
+                ("dank-spr/alpha", "main"),
+                ("dank-spr/beta", "dank-spr/alpha"),
+                ("dank-spr/fix", "release")
+            ]
+        );
+
+        let queued = queued_update(
+            "stack",
+            &local_fix,
+            None,
+            PrDescriptionMode::Overwrite,
+            false,
+            plan.clone(),
+        )
+        .unwrap();
+        // Planning the same stack again at flush time reproduces the replayed SHAs
+        let replanned = plan_offline_update(
+            "main",
+            "dank-spr/",
+            &groups,
+            &TitleTransformConfig::default(),
+        )
+        .unwrap();
+        assert!(plan_drift(&queued, &replanned).is_empty());
+        let mut moved = replanned;
+        moved.0[1].sha = "b2".to_string();
+        moved.0.pop();
+        assert_eq!(
+            plan_drift(&queued, &moved),
+            vec![
+                format!("dank-spr/beta now pushes b2 instead of the queued {beta}"),
+                "dank-spr/fix is no longer published".to_string()
+            ]
+        );
+    }
+}
@@ -3,9 +3,14 @@
 //! The parser treats `pr:ignore` (or a configured ignore tag) as a local-only block:
 //! ignored commits are preserved in local history, but they are not part of any PR
 //! grouping and are attached to the preceding group for rewrite operations.
+//!
+//! A path scope (`path_scope` config or `--path-scope`) narrows a stack to one area of a
+//! monorepo: commits touching none of the scoped globs are treated exactly like an ignore block.
+//! An active named stack (`--stack`) does the same for commits of groups outside that stack.
 
-use crate::git::git_ro;
+use crate::git::{git_ro, git_ro_in};
 use crate::group_markers::{candidate_group_markers, first_valid_group_marker, GroupMarker};
+use crate::run_settings::Setting;
 use anyhow::{bail, Result};
 use std::collections::HashSet;
 use tracing::warn;
@@ -55,15 +60,25 @@ impl Group {
 
     pub fn pr_title(&self) -> Result<String> {
         if let Some(s) = self.subjects.first() {
-            let t = crate::group_markers::strip_valid_group_markers(s)
-                .trim()
-                .to_string();
+            let t = crate::group_directives::strip_group_directives(
+                &crate::group_markers::strip_valid_group_markers(s),
+            )
+            .trim()
+            .to_string();
             if !t.is_empty() {
                 return Ok(t);
             }
         }
         Ok(self.bare_selector_text().to_string())
     }
+
+    /// [`Group::pr_title`] with the configured `title_transforms` applied.
+    pub fn pr_title_with(
+        &self,
+        transforms: &crate::title_transforms::TitleTransformConfig,
+    ) -> Result<String> {
+        transforms.apply(&self.pr_title()?, self.bare_selector_text())
+    }
     pub fn squash_commit_message(&self) -> Result<String> {
         if let Some(full) = &self.first_message {
             if let Some(found) = first_valid_group_marker(full) {
@@ -89,25 +104,6 @@ impl Group {
             self.selector_text()
         )
     }
-    pub fn pr_body(&self) -> Result<String> {
-        // Use only the body (drop the subject/title line); remove group markers.
-        let base_body = if let Some(full) = &self.first_message {
-            let mut it = full.lines();
-            let _ = it.next();
-            it.collect::<Vec<_>>().join("\n")
-        } else {
-            String::new()
-        };
-        let cleaned = crate::group_markers::strip_valid_group_markers(&base_body)
-            .trim()
-            .to_string();
-        let sep = if cleaned.is_empty() { "" } else { "\n\n" };
-        Ok(format!(
-            "{}{}<!-- spr-stack:start -->\n(placeholder; will be filled by spr)\n<!-- spr-stack:end -->",
-            cleaned, sep,
-        ))
-    }
-
     /// Body derived from the first commit message (without the title line) and with group markers removed.
     /// Does not include any stack markers. Trimmed.
     pub fn pr_body_base(&self) -> Result<String> {
@@ -118,9 +114,17 @@ impl Group {
         } else {
             String::new()
         };
-        Ok(crate::group_markers::strip_valid_group_markers(&base_body)
-            .trim()
-            .to_string())
+        let cleaned = crate::group_directives::strip_group_directives(
+            &crate::group_markers::strip_valid_group_markers(&base_body),
+        );
+        Ok(cleaned.trim().to_string())
+    }
+
+    /// Whether the group's first commit asks for its PR to be created as a draft (`spr-draft`).
+    pub fn requests_draft(&self) -> bool {
+        self.first_message
+            .as_deref()
+            .is_some_and(crate::group_directives::has_draft_directive)
     }
 }
 
@@ -155,6 +159,215 @@ fn ensure_unique_group_markers(groups: &[Group]) -> Result<()> {
     Ok(())
 }
 
+/// Path globs that scope group derivation in this process.
+static PATH_SCOPE: Setting<Vec<String>> = Setting::new();
+
+/// Restrict group derivation in this process to commits touching `globs`.
+///
+/// An empty list leaves derivation unscoped.
+pub fn set_path_scope(globs: &[String]) {
+    if !globs.is_empty() {
+        PATH_SCOPE.set(globs.to_vec());
+    }
+}
+
+fn path_scope() -> Vec<String> {
+    PATH_SCOPE.get().unwrap_or_default()
+}
+
+static FIRST_PARENT: Setting<bool> = Setting::new();
+
+/// Derive groups in this process from the first-parent chain only.
+///
+/// Merge commits from the base then count as single stack commits instead of pulling the
+/// merged side branch's commits into whatever group precedes them.
+pub fn set_first_parent(enabled: bool) {
+    if enabled {
+        FIRST_PARENT.set(true);
+    }
+}
+
+fn first_parent() -> bool {
+    FIRST_PARENT.get().unwrap_or(false)
+}
+
+pub(crate) static ACTIVE_STACK: Setting<String> = Setting::new();
+
+/// Restrict group derivation in this process to the `pr:<stack>/<tag>` groups of `stack`.
+pub fn set_active_stack(stack: Option<&str>) {
+    if let Some(stack) = stack.map(str::trim).filter(|stack| !stack.is_empty()) {
+        ACTIVE_STACK.set(stack.to_string());
+    }
+}
+
+fn active_stack() -> Option<String> {
+    ACTIVE_STACK.get()
+}
+
+pub(crate) static STACK_FROM: Setting<String> = Setting::new();
+
+/// Read the local stack in this process from `from` instead of `HEAD`.
+///
+/// This lets `--from <ref>` manage a stack kept on a branch other than the checked-out one.
+pub fn set_stack_from(from: Option<&str>) {
+    if let Some(from) = from.map(str::trim).filter(|from| !from.is_empty()) {
+        if from != "HEAD" {
+            STACK_FROM.set(from.to_string());
+        }
+    }
+}
+
+/// Tip of the local stack: the ref given to [`set_stack_from`], or `HEAD`.
+pub fn stack_from() -> String {
+    STACK_FROM.get().unwrap_or_else(|| "HEAD".to_string())
+}
+
+/// Merge commits in `range`, oldest first.
+pub fn merge_commits_in_range(range: &str) -> Result<Vec<String>> {
+    Ok(
+        git_ro(["rev-list", "--merges", "--reverse", range].as_slice())?
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect(),
+    )
+}
+
+/// Refuse to derive groups across merge commits unless first-parent derivation is enabled.
+///
+/// Without this check the merged side branch's commits are silently folded into whichever group
+/// precedes them, and rewrites later fail to cherry-pick the merge itself.
+fn ensure_linear_stack(range: &str) -> Result<()> {
+    if first_parent() {
+        return Ok(());
+    }
+    let merges = merge_commits_in_range(range)?;
+    let Some(first) = merges.first() else {
+        return Ok(());
+    };
+    bail!(
+        "The stack contains {} merge commit(s) (first: {}). Run `spr --linearize <command>` to rebase the stack into a linear history first, or pass `--first-parent` to group only the first-parent chain.",
+        merges.len(),
+        &first[..first.len().min(8)]
+    )
+}
+
+/// Raw `git log --format=%H%x00%B%x1e --reverse <range>` for group parsing.
+fn stack_log(range: &str, first_parent: bool) -> Result<String> {
+    stack_log_in(None, range, first_parent)
+}
+
+fn stack_log_in(repo_path: Option<&str>, range: &str, first_parent: bool) -> Result<String> {
+    let mut args = vec!["log", "--format=%H%x00%B%x1e", "--reverse"];
+    if first_parent {
+        args.push("--first-parent");
+    }
+    args.push(range);
+    match repo_path {
+        Some(path) => git_ro_in(path, &args),
+        None => git_ro(&args),
+    }
+}
+
+fn log_shas(repo_path: Option<&str>, args: &[&str]) -> Result<HashSet<String>> {
+    let output = match repo_path {
+        Some(path) => git_ro_in(path, args)?,
+        None => git_ro(args)?,
+    };
+    Ok(output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Return the commits in `range` that touch none of `globs` (git `:(glob)` pathspecs).
+///
+/// Returns an empty set when `globs` is empty.
+///
+/// # Errors
+///
+/// Returns errors from `git log`.
+pub fn out_of_scope_commits(
+    repo_path: Option<&str>,
+    range: &str,
+    globs: &[String],
+) -> Result<HashSet<String>> {
+    if globs.is_empty() {
+        return Ok(HashSet::new());
+    }
+    let all = log_shas(repo_path, ["log", "--format=%H", range].as_slice())?;
+    let pathspecs: Vec<String> = globs.iter().map(|glob| format!(":(glob){glob}")).collect();
+    let mut args = vec!["log", "--format=%H", range, "--"];
+    args.extend(pathspecs.iter().map(String::as_str));
+    let in_scope = log_shas(repo_path, &args)?;
+    Ok(all.difference(&in_scope).cloned().collect())
+}
+
+/// Commits of `raw` (a [`stack_log`] stream) that belong to groups outside `stack`.
+///
+/// A commit belongs to the group of the nearest marker at or before it. Commits before the first
+/// marker belong to no group and are kept; commits with an unparsable marker are left for the
+/// group parser to report.
+pub fn other_stack_commits(raw: &str, stack: &str) -> HashSet<String> {
+    let mut out = HashSet::new();
+    let mut in_stack: Option<bool> = None;
+    for chunk in raw.split('\u{001e}') {
+        let chunk = chunk.trim_end_matches('\n');
+        if chunk.trim().is_empty() {
+            continue;
+        }
+        let (sha, message) = chunk.split_once('\0').unwrap_or((chunk, ""));
+        let markers = candidate_group_markers(message);
+        if let [candidate] = markers.as_slice() {
+            if let Ok(marker) = candidate.clone().validate() {
+                in_stack = Some(marker.stack_name() == Some(stack));
+            }
+        }
+        if in_stack == Some(false) {
+            out.insert(sha.trim().to_string());
+        }
+    }
+    out
+}
+
+/// Commits in `range` outside the process path scope set by [`set_path_scope`] or outside the
+/// stack set by [`set_active_stack`].
+///
+/// # Errors
+///
+/// Returns errors from `git log`.
+pub fn out_of_scope_commits_for_current_scope(
+    repo_path: Option<&str>,
+    range: &str,
+) -> Result<HashSet<String>> {
+    let mut out = out_of_scope_commits(repo_path, range, &path_scope())?;
+    if let Some(stack) = active_stack() {
+        let raw = stack_log_in(repo_path, range, first_parent())?;
+        out.extend(other_stack_commits(&raw, &stack));
+    }
+    Ok(out)
+}
+
+/// Commits in `range` that belong to named stacks other than the one set by
+/// [`set_active_stack`]; empty when no stack is active.
+///
+/// # Errors
+///
+/// Returns errors from `git log`.
+pub fn other_stack_commits_in(range: &str) -> Result<HashSet<String>> {
+    match active_stack() {
+        Some(stack) => Ok(other_stack_commits(
+            &stack_log(range, first_parent())?,
+            &stack,
+        )),
+        None => Ok(HashSet::new()),
+    }
+}
+
+#[cfg(test)]
 /// Parse a reversed git log stream into PR groups, honoring an ignore tag.
 ///
 /// The input must be the raw output of `git log --format=%H%x00%B%x1e --reverse <range>`.
@@ -172,6 +385,7 @@ pub fn parse_groups(raw: &str, ignore_tag: &str) -> Result<Vec<Group>> {
     Ok(parse_groups_with_leading_commits(raw, ignore_tag)?.groups)
 }
 
+#[cfg(test)]
 /// Parse a reversed git log stream into PR groups while retaining ignored commits.
 ///
 /// Ignored commits are attached to the preceding group as `ignored_after`. If an ignore
@@ -186,6 +400,7 @@ pub fn parse_groups_with_ignored(raw: &str, ignore_tag: &str) -> Result<(Vec<Str
     Ok((parsed.leading_ignored, parsed.groups))
 }
 
+#[cfg(test)]
 /// Parse a reversed git log stream into PR groups while retaining pre-group commits.
 ///
 /// Ordinary ungrouped commits and explicit ignore-block commits that appear before the first group
@@ -195,6 +410,20 @@ pub fn parse_groups_with_ignored(raw: &str, ignore_tag: &str) -> Result<(Vec<Str
 ///
 /// Returns an error if any commit message contains more than one group marker.
 pub fn parse_groups_with_leading_commits(raw: &str, ignore_tag: &str) -> Result<ParsedGroups> {
+    parse_groups_with_leading_commits_scoped(raw, ignore_tag, &HashSet::new())
+}
+
+/// Like [`parse_groups_with_leading_commits`], but treats every commit in `out_of_scope` as part
+/// of an ignore block, whatever its message says.
+///
+/// # Errors
+///
+/// Returns an error if any in-scope commit message contains more than one group marker.
+pub fn parse_groups_with_leading_commits_scoped(
+    raw: &str,
+    ignore_tag: &str,
+    out_of_scope: &HashSet<String>,
+) -> Result<ParsedGroups> {
     let mut groups: Vec<Group> = vec![];
     let mut current: Option<Group> = None;
     let mut ignoring = false;
@@ -232,6 +461,13 @@ pub fn parse_groups_with_leading_commits(raw: &str, ignore_tag: &str) -> Result<
         let message = parts.next().unwrap_or_default().to_string();
         let subj = message.lines().next().unwrap_or_default().to_string();
 
+        if out_of_scope.contains(&sha) {
+            flush_current(&mut current, &mut groups);
+            ignoring = true;
+            ignored_block.push(sha);
+            continue;
+        }
+
         let markers = candidate_group_markers(&message);
         if markers.len() > 1 {
             bail!("Multiple group markers found in commit {sha}");
@@ -328,22 +564,18 @@ pub fn derive_groups_between(
     let merge_base = git_ro(["merge-base", base, to].as_slice())?
         .trim()
         .to_string();
-    let lines = git_ro(
-        [
-            "log",
-            "--format=%H%x00%B%x1e",
-            "--reverse",
-            &format!("{merge_base}..{to}"),
-        ]
-        .as_slice(),
-    )?;
-    let groups = parse_groups(&lines, ignore_tag)?;
+    ensure_linear_stack(&format!("{merge_base}..{to}"))?;
+    let lines = stack_log(&format!("{merge_base}..{to}"), first_parent())?;
+    let out_of_scope =
+        out_of_scope_commits_for_current_scope(None, &format!("{merge_base}..{to}"))?;
+    let groups =
+        parse_groups_with_leading_commits_scoped(&lines, ignore_tag, &out_of_scope)?.groups;
     Ok((merge_base, groups))
 }
 
-/// Convenience: derive PR groups from merge-base(base, HEAD)..HEAD.
+/// Convenience: derive PR groups from merge-base(base, tip)..tip, where tip is [`stack_from`].
 pub fn derive_local_groups(base: &str, ignore_tag: &str) -> Result<(String, Vec<Group>)> {
-    derive_groups_between(base, "HEAD", ignore_tag)
+    derive_groups_between(base, &stack_from(), ignore_tag)
 }
 
 /// Derive PR groups and leading ignored commits from `merge-base(base, to)..to`.
@@ -376,20 +608,15 @@ pub fn derive_groups_between_with_leading_commits(
     let merge_base = git_ro(["merge-base", base, to].as_slice())?
         .trim()
         .to_string();
-    let lines = git_ro(
-        [
-            "log",
-            "--format=%H%x00%B%x1e",
-            "--reverse",
-            &format!("{merge_base}..{to}"),
-        ]
-        .as_slice(),
-    )?;
-    let parsed = parse_groups_with_leading_commits(&lines, ignore_tag)?;
+    ensure_linear_stack(&format!("{merge_base}..{to}"))?;
+    let lines = stack_log(&format!("{merge_base}..{to}"), first_parent())?;
+    let out_of_scope =
+        out_of_scope_commits_for_current_scope(None, &format!("{merge_base}..{to}"))?;
+    let parsed = parse_groups_with_leading_commits_scoped(&lines, ignore_tag, &out_of_scope)?;
     Ok((merge_base, parsed))
 }
 
-/// Convenience: derive PR groups and leading ignored commits from merge-base(base, HEAD)..HEAD.
+/// Convenience: derive PR groups and leading ignored commits from the [`stack_from`] tip.
 ///
 /// # Errors
 ///
@@ -398,10 +625,10 @@ pub fn derive_local_groups_with_ignored(
     base: &str,
     ignore_tag: &str,
 ) -> Result<(String, Vec<String>, Vec<Group>)> {
-    derive_groups_between_with_ignored(base, "HEAD", ignore_tag)
+    derive_groups_between_with_ignored(base, &stack_from(), ignore_tag)
 }
 
-/// Convenience: derive PR groups plus pre-group commits from merge-base(base, HEAD)..HEAD.
+/// Convenience: derive PR groups plus pre-group commits from the [`stack_from`] tip.
 ///
 /// # Errors
 ///
@@ -410,15 +637,18 @@ pub fn derive_local_groups_with_leading_commits(
     base: &str,
     ignore_tag: &str,
 ) -> Result<(String, ParsedGroups)> {
-    derive_groups_between_with_leading_commits(base, "HEAD", ignore_tag)
+    derive_groups_between_with_leading_commits(base, &stack_from(), ignore_tag)
 }
 
 #[cfg(test)]
 mod tests {
     use super::{
-        parse_groups, parse_groups_with_ignored, parse_groups_with_leading_commits,
-        split_groups_for_update,
+        other_stack_commits, out_of_scope_commits, parse_groups, parse_groups_with_ignored,
+        parse_groups_with_leading_commits, parse_groups_with_leading_commits_scoped,
+        split_groups_for_update, stack_log,
     };
+    use crate::test_support::{commit_file, git, init_repo, lock_cwd, DirGuard};
+    use std::collections::HashSet;
 
     fn make_log(entries: &[(&str, &str)]) -> String {
         let mut out = String::new();
@@ -475,6 +705,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn draft_directive_marks_group_and_is_stripped_from_pr_text() {
+        let raw = make_log(&[
+            ("a1", "feat: alpha pr:alpha spr-draft\n\nWhy alpha."),
+            ("b1", "feat: beta pr:beta"),
+        ]);
+
+        let groups = parse_groups(&raw, "ignore").expect("parse_groups ok");
+
+        assert!(groups[0].requests_draft());
+        assert!(!groups[1].requests_draft());
+        assert_eq!(groups[0].pr_title().unwrap(), "feat: alpha");
+        assert_eq!(groups[0].pr_body_base().unwrap(), "Why alpha.");
+        assert!(groups[0]
+            .squash_commit_message()
+            .unwrap()
+            .contains("spr-draft"));
+    }
+
     #[test]
     fn parse_groups_with_ignored_attaches_to_previous() {
         let raw = make_log(&[
@@ -678,6 +927,56 @@ mod tests {
         assert_eq!(groups[1].pr_title().unwrap(), "feat: beta start");
     }
 
+    #[test]
+    fn pr_title_with_applies_transforms_after_stripping_markers() {
+        let raw = make_log(&[("a1", "WIP: add alpha pr:proj-7-alpha spr-draft")]);
+        let transforms = crate::title_transforms::TitleTransformConfig {
+            strip_prefixes: vec!["WIP:".to_string()],
+            capitalize: crate::title_transforms::TitleCapitalization::Upper,
+            ticket_pattern: Some(r"proj-\d+".to_string()),
+            ticket_format: None,
+        };
+
+        let groups = parse_groups(&raw, "ignore").unwrap();
+
+        assert_eq!(groups[0].pr_title().unwrap(), "WIP: add alpha");
+        assert_eq!(
+            groups[0].pr_title_with(&transforms).unwrap(),
+            "Add alpha (PROJ-7)"
+        );
+    }
+
+    #[test]
+    fn other_stack_commits_scope_parsing_to_one_named_stack() {
+        let raw = make_log(&[
+            ("s1", "chore: setup"),
+            ("a1", "feat: api alpha pr:api/alpha"),
+            ("w1", "feat: web login pr:web/login"),
+            ("w2", "feat: web login follow-up"),
+            ("a2", "feat: api beta pr:api/beta"),
+            ("p1", "feat: plain pr:plain"),
+        ]);
+
+        let out_of_scope = other_stack_commits(&raw, "api");
+        let mut sorted: Vec<&str> = out_of_scope.iter().map(String::as_str).collect();
+        sorted.sort();
+        assert_eq!(sorted, vec!["p1", "w1", "w2"]);
+
+        let parsed =
+            parse_groups_with_leading_commits_scoped(&raw, "ignore", &out_of_scope).unwrap();
+        assert_eq!(parsed.leading_ungrouped, vec!["s1".to_string()]);
+        let handles: Vec<String> = parsed.groups.iter().map(|g| g.selector_text()).collect();
+        assert_eq!(handles, vec!["pr:api/alpha", "pr:api/beta"]);
+        assert_eq!(
+            parsed.groups[0].ignored_after,
+            vec!["w1".to_string(), "w2".to_string()]
+        );
+        assert_eq!(
+            parsed.groups[0].concrete_branch_name("dank-spr/"),
+            "dank-spr/api/alpha"
+        );
+    }
+
     #[test]
     fn parse_groups_rejects_invalid_trailing_characters() {
         let raw = make_log(&[("a1", "feat: invalid punctuation pr:alpha!oops")]);
@@ -711,4 +1010,71 @@ mod tests {
             "unexpected error: {message}"
         );
     }
+
+    #[test]
+    fn parse_groups_scoped_treats_out_of_scope_commits_as_ignore_blocks() {
+        let raw = make_log(&[
+            ("a1", "feat: alpha pr:alpha"),
+            ("x1", "feat: other area pr:other"),
+            ("x2", "fix: other area"),
+            ("b1", "feat: beta pr:beta"),
+            ("b2", "feat: beta follow-up"),
+        ]);
+        let out_of_scope: HashSet<String> = ["x1", "x2"].iter().map(|s| s.to_string()).collect();
+
+        let parsed =
+            parse_groups_with_leading_commits_scoped(&raw, "ignore", &out_of_scope).unwrap();
+
+        assert_eq!(parsed.groups.len(), 2);
+        assert_eq!(parsed.groups[0].commits, vec!["a1"]);
+        assert_eq!(parsed.groups[0].ignored_after, vec!["x1", "x2"]);
+        assert_eq!(parsed.groups[1].commits, vec!["b1", "b2"]);
+    }
+
+    #[test]
+    fn out_of_scope_commits_matches_path_globs() {
+        let dir = init_repo();
+        let repo = dir.path().to_path_buf();
+        commit_file(&repo, "api-a.rs", "a\n", "feat: api pr:api");
+        let web = commit_file(&repo, "web.ts", "w\n", "feat: web");
+        commit_file(&repo, "api-b.rs", "b\n", "feat: api follow-up");
+        let repo_path = repo.to_str().unwrap();
+
+        let out_of_scope =
+            out_of_scope_commits(Some(repo_path), "HEAD~3..HEAD", &["api-*".to_string()]).unwrap();
+        assert_eq!(out_of_scope, HashSet::from([web]));
+        assert!(out_of_scope_commits(Some(repo_path), "HEAD~3..HEAD", &[])
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn first_parent_stack_log_skips_merged_side_branch_commits() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path().to_path_buf();
+        let base = git(&repo, ["rev-parse", "HEAD"].as_slice())
+            .trim()
+            .to_string();
+        commit_file(&repo, "a.txt", "a\n", "feat: a pr:alpha");
+        git(&repo, ["checkout", "-q", "-b", "side"].as_slice());
+        let side = commit_file(&repo, "side.txt", "s\n", "side work");
+        git(&repo, ["checkout", "-q", "-"].as_slice());
+        commit_file(&repo, "a2.txt", "a\n", "feat: a follow-up");
+        git(
+            &repo,
+            ["merge", "-q", "--no-ff", "-m", "merge side", "side"].as_slice(),
+        );
+        commit_file(&repo, "b.txt", "b\n", "feat: b pr:beta");
+        let _guard = DirGuard::change_to(&repo);
+        let range = format!("{base}..HEAD");
+
+        let full = parse_groups(&stack_log(&range, false).unwrap(), "ignore").unwrap();
+        assert!(full[0].commits.contains(&side));
+
+        let first_parent = parse_groups(&stack_log(&range, true).unwrap(), "ignore").unwrap();
+        assert_eq!(first_parent.len(), 2);
+        assert_eq!(first_parent[0].commits.len(), 3);
+        assert!(!first_parent[0].commits.contains(&side));
+    }
 }
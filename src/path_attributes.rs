@@ -0,0 +1,233 @@
+//! Git attributes that change how a group's files are presented.
+//!
+//! Paths marked `linguist-generated` in `.gitattributes` count as generated, and paths marked
+//! `binary` or `-diff` count as binary. `spr files` labels both kinds, and the review guide lists
+//! them apart from the files a reviewer should read. Attributes are read from the checked-out
+//! tree, like `git check-attr` does.
+//!
+//! The `generated_paths` config adds path globs (git `:(glob)` pathspecs) that count as generated
+//! without editing `.gitattributes`, for lockfiles and vendored code. Protected-path rules ignore
+//! these paths too; the changes themselves stay in the PRs.
+
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+
+use crate::git::git_ro;
+use crate::run_settings::Setting;
+
+/// Presentation attributes of one path.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PathAttributes {
+    pub generated: bool,
+    pub binary: bool,
+}
+
+static GENERATED_PATHS: Setting<Vec<String>> = Setting::new();
+
+/// Treat paths matching `globs` as generated in this process.
+pub fn set_generated_paths(globs: &[String]) {
+    if !globs.is_empty() {
+        GENERATED_PATHS.set(globs.to_vec());
+    }
+}
+
+/// The configured `generated_paths` globs.
+pub fn generated_paths() -> Vec<String> {
+    GENERATED_PATHS.get().unwrap_or_default()
+}
+
+/// Whether a `git check-attr` value turns a boolean attribute on.
+fn attribute_set(value: &str) -> bool {
+    value == "set" || value == "true"
+}
+
+/// Parse `git check-attr -z` output: NUL-separated `path`, `attribute`, `value` triples.
+fn parse_check_attr(output: &str) -> HashMap<String, PathAttributes> {
+    let mut attributes: HashMap<String, PathAttributes> = HashMap::new();
+    let fields: Vec<&str> = output.split('\0').collect();
+    for triple in fields.chunks_exact(3) {
+        let [path, attribute, value] = [triple[0], triple[1], triple[2]];
+        let entry = attributes.entry(path.to_string()).or_default();
+        match attribute {
+            "linguist-generated" => entry.generated |= attribute_set(value),
+            "binary" => entry.binary |= attribute_set(value),
+            "diff" => entry.binary |= value == "unset",
+            _ => {}
+        }
+    }
+    attributes
+}
+
+/// Mark every path of `paths` changed between `from` and `to` that matches one of `globs` as
+/// generated. Git does the matching through `:(glob)` pathspecs, exactly as `protected_paths` does.
+fn mark_generated(
+    attributes: &mut HashMap<String, PathAttributes>,
+    paths: &[String],
+    globs: &[String],
+    from: &str,
+    to: &str,
+) -> Result<()> {
+    if globs.is_empty() {
+        return Ok(());
+    }
+    let pathspecs: Vec<String> = globs.iter().map(|glob| format!(":(glob){glob}")).collect();
+    let mut args = vec!["diff", "--name-only", "-z", from, to, "--"];
+    args.extend(pathspecs.iter().map(String::as_str));
+    let output = git_ro(&args)?;
+    let matched: HashSet<&str> = output.split('\0').filter(|path| !path.is_empty()).collect();
+    for path in paths {
+        if matched.contains(path.as_str()) {
+            attributes.entry(path.clone()).or_default().generated = true;
+        }
+    }
+    Ok(())
+}
+
+/// Attributes of `paths`, the files changed between `from` and `to`, with `generated_paths`
+/// matches counted as generated; paths without any of them are absent from the map.
+///
+/// # Errors
+///
+/// Returns errors from `git check-attr` and `git diff`.
+pub fn path_attributes(
+    from: &str,
+    to: &str,
+    paths: &[String],
+) -> Result<HashMap<String, PathAttributes>> {
+    if paths.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let mut args = vec![
+        "check-attr",
+        "-z",
+        "linguist-generated",
+        "binary",
+        "diff",
+        "--",
+    ];
+    args.extend(paths.iter().map(String::as_str));
+    let mut attributes = parse_check_attr(&git_ro(&args)?);
+    mark_generated(&mut attributes, paths, &generated_paths(), from, to)?;
+    attributes.retain(|_, attributes| *attributes != PathAttributes::default());
+    Ok(attributes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{mark_generated, path_attributes, PathAttributes};
+    use crate::test_support::{commit_file, git, init_repo, lock_cwd, write_file, DirGuard};
+    use std::collections::HashMap;
+    use std::fs;
+
+    #[test]
+    fn path_attributes_reads_generated_and_binary_markers() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        commit_file(
+            dir.path(),
+            ".gitattributes",
+            "gen/** linguist-generated\n*.png binary\nCargo.lock linguist-generated=true -diff\n",
+            "chore: attributes",
+        );
+        let _guard = DirGuard::change_to(dir.path());
+
+        let attributes = path_attributes(
+            "HEAD",
+            "HEAD",
+            &[
+                "gen/api.rs".to_string(),
+                "logo.png".to_string(),
+                "Cargo.lock".to_string(),
+                "src/main.rs".to_string(),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            attributes.get("gen/api.rs"),
+            Some(&PathAttributes {
+                generated: true,
+                binary: false,
+            })
+        );
+        assert_eq!(
+            attributes.get("logo.png"),
+            Some(&PathAttributes {
+                generated: false,
+                binary: true,
+            })
+        );
+        assert_eq!(
+            attributes.get("Cargo.lock"),
+            Some(&PathAttributes {
+                generated: true,
+                binary: true,
+            })
+        );
+        assert_eq!(attributes.get("src/main.rs"), None);
+    }
+
+    #[test]
+    fn generated_path_globs_match_as_glob_pathspecs() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path();
+        let base = git(repo, ["rev-parse", "HEAD"].as_slice())
+            .trim()
+            .to_string();
+        let paths: Vec<String> = [
+            "Cargo.lock",
+            "web/package-lock.json",
+            "vendor/lib/a.c",
+            "src/vendor.rs",
+            "proto/v1/api.pb.go",
+            "proto/api.pb.go",
+            "docs/Cargo.lock",
+        ]
+        .map(str::to_string)
+        .to_vec();
+        for path in &paths {
+            if let Some((parent, _)) = path.rsplit_once('/') {
+                fs::create_dir_all(repo.join(parent)).unwrap();
+            }
+            write_file(repo, path, "content\n");
+        }
+        git(repo, ["add", "."].as_slice());
+        git(repo, ["commit", "-m", "feat: files"].as_slice());
+        let _guard = DirGuard::change_to(repo);
+        let globs: Vec<String> = [
+            "Cargo.lock",
+            "**/package-lock.json",
+            "vendor/**",
+            "proto/*/*.pb.go",
+        ]
+        .map(str::to_string)
+        .to_vec();
+        let mut attributes = HashMap::from([(
+            "Cargo.lock".to_string(),
+            PathAttributes {
+                generated: false,
+                binary: true,
+            },
+        )]);
+
+        mark_generated(&mut attributes, &paths, &globs, &base, "HEAD").unwrap();
+
+        let mut generated: Vec<&str> = attributes
+            .iter()
+            .filter(|(_, attributes)| attributes.generated)
+            .map(|(path, _)| path.as_str())
+            .collect();
+        generated.sort_unstable();
+        assert_eq!(
+            generated,
+            vec![
+                "Cargo.lock",
+                "proto/v1/api.pb.go",
+                "vendor/lib/a.c",
+                "web/package-lock.json"
+            ]
+        );
+        assert!(attributes["Cargo.lock"].binary);
+    }
+}
@@ -2,7 +2,8 @@
 //!
 //! Labels are the immutable payload in `pr:<label>` commit markers and in
 //! stable selector inputs. They must start with an ASCII letter and may then
-//! use ASCII letters, digits, `.`, `_`, or `-`.
+//! use ASCII letters, digits, `.`, `_`, or `-`. A label may name its stack as
+//! `<stack>/<tag>`, where both parts follow the same rules.
 
 /// A validation failure for a PR-group label.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -17,7 +18,7 @@ impl std::fmt::Display for LabelValidationError {
             Self::MustStartWithLetter => write!(f, "must start with an ASCII letter"),
             Self::InvalidCharacters => write!(
                 f,
-                "must use only ASCII letters, digits, `.`, `_`, or `-` after the first letter"
+                "must use only ASCII letters, digits, `.`, `_`, or `-` after the first letter, with at most one `/` after a stack name"
             ),
         }
     }
@@ -27,6 +28,21 @@ impl std::error::Error for LabelValidationError {}
 
 /// Validates one PR-group label against the shared commit-marker and selector grammar.
 pub fn validate_label(label: &str) -> std::result::Result<(), LabelValidationError> {
+    match label.split_once('/') {
+        Some((stack, tag)) => {
+            validate_segment(stack)?;
+            validate_segment(tag)
+        }
+        None => validate_segment(label),
+    }
+}
+
+/// The stack a `<stack>/<tag>` label belongs to, or `None` for a label without one.
+pub fn label_stack(label: &str) -> Option<&str> {
+    label.split_once('/').map(|(stack, _)| stack)
+}
+
+fn validate_segment(label: &str) -> std::result::Result<(), LabelValidationError> {
     let mut chars = label.chars();
     if let Some(first) = chars.next() {
         if !first.is_ascii_alphabetic() {
@@ -43,7 +59,7 @@ pub fn validate_label(label: &str) -> std::result::Result<(), LabelValidationErr
 
 #[cfg(test)]
 mod tests {
-    use super::{validate_label, LabelValidationError};
+    use super::{label_stack, validate_label, LabelValidationError};
 
     #[test]
     fn validate_label_rejects_empty_string() {
@@ -52,4 +68,19 @@ mod tests {
             LabelValidationError::MustStartWithLetter
         );
     }
+
+    #[test]
+    fn validate_label_accepts_one_stack_segment() {
+        assert_eq!(validate_label("api/alpha"), Ok(()));
+        assert_eq!(label_stack("api/alpha"), Some("api"));
+        assert_eq!(label_stack("alpha"), None);
+        assert_eq!(
+            validate_label("api/2nd").unwrap_err(),
+            LabelValidationError::MustStartWithLetter
+        );
+        assert_eq!(
+            validate_label("api/alpha/beta").unwrap_err(),
+            LabelValidationError::InvalidCharacters
+        );
+    }
 }
@@ -0,0 +1,178 @@
+//! Merge commit-message bodies into the repository's pull request template.
+//!
+//! With `body_template: true`, PR bodies keep the sections of `.github/PULL_REQUEST_TEMPLATE.md`
+//! instead of replacing the whole template with the commit-message body. The template and the
+//! body are split at markdown headings: a body section whose heading matches a template heading
+//! (ignoring case and `#` level) replaces that section's content, body text before its first
+//! heading fills the first template section, and body sections the template does not have are
+//! appended. Template sections the body does not fill keep their placeholder text.
+
+use std::path::PathBuf;
+
+use crate::git::git_ro;
+use crate::run_settings::Setting;
+
+pub(crate) static BODY_TEMPLATE: Setting<bool> = Setting::new();
+
+/// Template paths GitHub reads for a single default PR template, in lookup order.
+const TEMPLATE_PATHS: &[&str] = &[
+    ".github/PULL_REQUEST_TEMPLATE.md",
+    ".github/pull_request_template.md",
+    "PULL_REQUEST_TEMPLATE.md",
+    "docs/PULL_REQUEST_TEMPLATE.md",
+];
+
+/// Merge PR bodies composed by this process into the repository's PR template.
+pub fn set_enabled(enabled: bool) {
+    if enabled {
+        BODY_TEMPLATE.set(true);
+    }
+}
+
+pub fn enabled() -> bool {
+    BODY_TEMPLATE.get().unwrap_or(false)
+}
+
+/// The repository's PR template, if it has one.
+fn load_template() -> Option<String> {
+    let root = git_ro(["rev-parse", "--show-toplevel"].as_slice()).ok()?;
+    let root = PathBuf::from(root.trim());
+    TEMPLATE_PATHS
+        .iter()
+        .find_map(|path| std::fs::read_to_string(root.join(path)).ok())
+        .filter(|template| !template.trim().is_empty())
+}
+
+/// `body` merged into the repository's PR template when `body_template` is enabled and the
+/// repository has a template; otherwise `body` unchanged.
+pub fn apply(body: &str) -> String {
+    if !enabled() {
+        return body.to_string();
+    }
+    match load_template() {
+        Some(template) => merge_into_template(&template, body),
+        None => body.to_string(),
+    }
+}
+
+struct Section<'a> {
+    heading: &'a str,
+    content: String,
+}
+
+fn heading_key(heading: &str) -> String {
+    heading.trim_start_matches('#').trim().to_lowercase()
+}
+
+fn is_heading(line: &str) -> bool {
+    let hashes = line.len() - line.trim_start_matches('#').len();
+    (1..=6).contains(&hashes) && line[hashes..].starts_with(' ')
+}
+
+/// Text before the first heading, then each heading with the text beneath it.
+fn split_sections(text: &str) -> (String, Vec<Section<'_>>) {
+    let mut prelude = Vec::new();
+    let mut sections: Vec<(&str, Vec<&str>)> = Vec::new();
+    let mut in_fence = false;
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        }
+        if !in_fence && is_heading(line) {
+            sections.push((line, Vec::new()));
+        } else if let Some((_, content)) = sections.last_mut() {
+            content.push(line);
+        } else {
+            prelude.push(line);
+        }
+    }
+    (
+        prelude.join("\n").trim().to_string(),
+        sections
+            .into_iter()
+            .map(|(heading, content)| Section {
+                heading,
+                content: content.join("\n").trim().to_string(),
+            })
+            .collect(),
+    )
+}
+
+/// Fill the sections of `template` from `body`.
+pub fn merge_into_template(template: &str, body: &str) -> String {
+    let (template_prelude, mut template_sections) = split_sections(template);
+    let (body_prelude, body_sections) = split_sections(body);
+    // A body that already went through the template (an edited PR description) repeats the
+    // template's prelude; keep it once so merging again changes nothing
+    let mut body_prelude =
+        Some(body_prelude).filter(|prelude| !prelude.is_empty() && *prelude != template_prelude);
+    let mut first_filled = false;
+    let mut extra_sections = Vec::new();
+    for body_section in body_sections {
+        let key = heading_key(body_section.heading);
+        match template_sections
+            .iter()
+            .position(|section| heading_key(section.heading) == key)
+        {
+            Some(idx) => {
+                template_sections[idx].content = body_section.content;
+                first_filled |= idx == 0;
+            }
+            None => extra_sections.push(body_section),
+        }
+    }
+    if let Some(first) = template_sections.first_mut().filter(|_| !first_filled) {
+        if let Some(prelude) = body_prelude.take() {
+            first.content = prelude;
+        }
+    }
+    let mut blocks: Vec<String> = Vec::new();
+    blocks.extend(body_prelude);
+    if !template_prelude.is_empty() {
+        blocks.push(template_prelude);
+    }
+    for section in template_sections.iter().chain(&extra_sections) {
+        if section.content.is_empty() {
+            blocks.push(section.heading.to_string());
+        } else {
+            blocks.push(format!("{}\n\n{}", section.heading, section.content));
+        }
+    }
+    blocks.join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::merge_into_template;
+
+    const TEMPLATE: &str = "<!-- Thanks for contributing! -->\n\n## Summary\n\n<!-- What does this change? -->\n\n## Testing\n\n- [ ] Unit tests\n\n## Risk\n\nLow / Medium / High\n";
+
+    #[test]
+    fn merge_fills_first_section_with_plain_body_and_keeps_the_rest() {
+        assert_eq!(
+            merge_into_template(TEMPLATE, "Adds retries to the uploader."),
+            "<!-- Thanks for contributing! -->\n\n## Summary\n\nAdds retries to the uploader.\n\n## Testing\n\n- [ ] Unit tests\n\n## Risk\n\nLow / Medium / High"
+        );
+    }
+
+    #[test]
+    fn merge_matches_sections_by_heading_and_appends_unknown_ones() {
+        assert_eq!(
+            merge_into_template(
+                TEMPLATE,
+                "Context first.\n\n### testing\n\nRan `cargo test`.\n\n## Follow-ups\n\nDocs.",
+            ),
+            "<!-- Thanks for contributing! -->\n\n## Summary\n\nContext first.\n\n## Testing\n\nRan `cargo test`.\n\n## Risk\n\nLow / Medium / High\n\n## Follow-ups\n\nDocs."
+        );
+        assert_eq!(
+            merge_into_template(TEMPLATE, "Lead.\n\n## Summary\n\nWhat changed."),
+            "Lead.\n\n<!-- Thanks for contributing! -->\n\n## Summary\n\nWhat changed.\n\n## Testing\n\n- [ ] Unit tests\n\n## Risk\n\nLow / Medium / High"
+        );
+    }
+
+    #[test]
+    fn merging_an_already_merged_body_changes_nothing() {
+        let merged = merge_into_template(TEMPLATE, "Adds retries.\n\n## Testing\n\nRan it.");
+        assert_eq!(merge_into_template(TEMPLATE, &merged), merged);
+    }
+}
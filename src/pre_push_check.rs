@@ -0,0 +1,171 @@
+//! Run a configured check against each group tip before `spr update` pushes it.
+//!
+//! With `pre_push_check` set, `spr update` checks out every group whose branch it is about to push
+//! into a detached temporary worktree and runs the command there with `sh -c`. The group's
+//! selector and tip are exported as `SPR_GROUP` and `SPR_GROUP_SHA`. If the command fails for any
+//! group, the update stops before pushing anything, so a broken mid-stack PR never reaches
+//! reviewers. Groups whose branch is already up to date are not checked again.
+
+use anyhow::{bail, Context, Result};
+use std::process::Command;
+use tracing::{info, warn};
+
+use crate::execution::ExecutionMode;
+use crate::git::{git_common_dir, git_rw};
+use crate::run_settings::Setting;
+
+static PRE_PUSH_CHECK: Setting<String> = Setting::new();
+
+/// Lines of a failing check's output shown in the warning.
+const FAILURE_OUTPUT_LINES: usize = 20;
+
+/// Run `command` before pushes made by this process.
+pub fn set_command(command: Option<&str>) {
+    if let Some(command) = command.filter(|command| !command.trim().is_empty()) {
+        PRE_PUSH_CHECK.set(command.to_string());
+    }
+}
+
+fn command() -> Option<String> {
+    PRE_PUSH_CHECK.get()
+}
+
+/// A group about to be pushed: its selector and tip commit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupTip {
+    pub selector: String,
+    pub sha: String,
+}
+
+/// Run the configured check on every group in `tips`; a no-op without `pre_push_check`.
+///
+/// # Errors
+///
+/// Returns an error naming the groups whose check failed, or when a worktree cannot be created.
+pub fn check_group_tips(tips: &[GroupTip], execution_mode: ExecutionMode) -> Result<()> {
+    let Some(command) = command() else {
+        return Ok(());
+    };
+    if tips.is_empty() {
+        return Ok(());
+    }
+    if execution_mode == ExecutionMode::DryRun {
+        info!(
+            "Would run pre-push check `{}` on {} group(s)",
+            command,
+            tips.len()
+        );
+        return Ok(());
+    }
+    run_checks(&command, tips)
+}
+
+fn run_checks(command: &str, tips: &[GroupTip]) -> Result<()> {
+    let mut failed = Vec::new();
+    for (idx, tip) in tips.iter().enumerate() {
+        info!(
+            "({}/{}) Running pre-push check on {}",
+            idx + 1,
+            tips.len(),
+            tip.selector
+        );
+        if !check_one(command, tip)? {
+            failed.push(tip.selector.clone());
+        }
+    }
+    if !failed.is_empty() {
+        bail!(
+            "Pre-push check `{}` failed for {}; nothing was pushed",
+            command,
+            failed.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Whether `command` passes on `tip`, run in a throwaway detached worktree.
+fn check_one(command: &str, tip: &GroupTip) -> Result<bool> {
+    let path = git_common_dir()?
+        .join("spr")
+        .join(format!("pre-push-{}", &tip.sha[..tip.sha.len().min(12)]));
+    let path_text = path.to_string_lossy().to_string();
+    if path.exists() {
+        let _ = git_rw(
+            ExecutionMode::Apply,
+            ["worktree", "remove", "--force", &path_text].as_slice(),
+        );
+    }
+    git_rw(
+        ExecutionMode::Apply,
+        ["worktree", "add", "--detach", &path_text, &tip.sha].as_slice(),
+    )?;
+    let output = Command::new("sh")
+        .args(["-c", command])
+        .current_dir(&path)
+        .env("SPR_GROUP", &tip.selector)
+        .env("SPR_GROUP_SHA", &tip.sha)
+        .output()
+        .with_context(|| format!("Failed to run pre-push check `{command}`"));
+    let removed = git_rw(
+        ExecutionMode::Apply,
+        ["worktree", "remove", "--force", &path_text].as_slice(),
+    );
+    let output = output?;
+    removed?;
+    if output.status.success() {
+        return Ok(true);
+    }
+    let combined = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let lines: Vec<&str> = combined.lines().collect();
+    warn!(
+        "Pre-push check failed on {} ({}):\n{}",
+        tip.selector,
+        output.status,
+        lines[lines.len().saturating_sub(FAILURE_OUTPUT_LINES)..].join("\n")
+    );
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run_checks, GroupTip};
+    use crate::test_support::{commit_file, git, init_repo, lock_cwd, DirGuard};
+
+    #[test]
+    fn run_checks_names_failing_groups_and_removes_worktrees() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path();
+        let alpha = commit_file(repo, "alpha.txt", "a\n", "feat: alpha pr:alpha");
+        let beta = commit_file(repo, "beta.txt", "b\n", "feat: beta pr:beta");
+        let _guard = DirGuard::change_to(repo);
+        let tips = [
+            GroupTip {
+                selector: "pr:alpha".to_string(),
+                sha: alpha,
+            },
+            GroupTip {
+                selector: "pr:beta".to_string(),
+                sha: beta,
+            },
+        ];
+
+        run_checks("test -f alpha.txt", &tips).unwrap();
+        let err =
+            run_checks("test -f beta.txt && test \"$SPR_GROUP\" = pr:beta", &tips).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Pre-push check `test -f beta.txt && test \"$SPR_GROUP\" = pr:beta` failed for pr:alpha; nothing was pushed"
+        );
+        assert_eq!(
+            git(repo, ["worktree", "list", "--porcelain"].as_slice())
+                .matches("worktree ")
+                .count(),
+            1
+        );
+    }
+}
@@ -0,0 +1,153 @@
+//! Optional GitHub Projects (v2) status updates for PRs landed by `spr land`.
+//!
+//! When `projects.landed_status` is configured, every project item attached to a landed PR has
+//! its single-select status field set to that option. Items on projects without the field or the
+//! option are skipped with a warning rather than failing the already-completed land.
+
+use anyhow::Result;
+use serde::Deserialize;
+use tracing::{info, warn};
+
+use crate::execution::ExecutionMode;
+use crate::github::{PrProjectItem, ProjectStatusUpdate};
+
+const DEFAULT_STATUS_FIELD: &str = "Status";
+
+/// The `projects` config block.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProjectsConfig {
+    /// Single-select field to update; defaults to `Status`.
+    pub status_field: Option<String>,
+    /// Option name (matched case-insensitively) to set after landing; unset disables updates.
+    pub landed_status: Option<String>,
+}
+
+impl ProjectsConfig {
+    pub fn status_field(&self) -> &str {
+        self.status_field.as_deref().unwrap_or(DEFAULT_STATUS_FIELD)
+    }
+}
+
+/// Choose one update per project item, returning warnings for items that cannot be updated.
+fn plan_status_updates(
+    items: &[PrProjectItem],
+    status_field: &str,
+    landed_status: &str,
+) -> (Vec<ProjectStatusUpdate>, Vec<String>) {
+    let mut updates = Vec::new();
+    let mut warnings = Vec::new();
+    for item in items {
+        let Some(field) = &item.status_field else {
+            warnings.push(format!(
+                "PR #{}: project \"{}\" has no single-select field named \"{}\"",
+                item.pr_number, item.project_title, status_field
+            ));
+            continue;
+        };
+        let Some(option) = field
+            .options
+            .iter()
+            .find(|option| option.name.eq_ignore_ascii_case(landed_status))
+        else {
+            warnings.push(format!(
+                "PR #{}: project \"{}\" field \"{}\" has no option \"{}\"",
+                item.pr_number, item.project_title, status_field, landed_status
+            ));
+            continue;
+        };
+        updates.push(ProjectStatusUpdate {
+            pr_number: item.pr_number,
+            project_id: item.project_id.clone(),
+            item_id: item.item_id.clone(),
+            field_id: field.id.clone(),
+            option_id: option.id.clone(),
+        });
+    }
+    (updates, warnings)
+}
+
+/// Move project items for `pr_numbers` to the configured landed status, if any.
+///
+/// Unresolvable items are logged as warnings. Errors are returned only for GitHub failures so the
+/// caller can decide whether they should fail the command.
+pub fn mark_landed_prs(
+    config: &ProjectsConfig,
+    pr_numbers: &[u64],
+    execution_mode: ExecutionMode,
+) -> Result<()> {
+    let Some(landed_status) = config.landed_status.as_deref() else {
+        return Ok(());
+    };
+    if pr_numbers.is_empty() {
+        return Ok(());
+    }
+    let items = crate::github::fetch_pr_project_items(pr_numbers, config.status_field())?;
+    let (updates, warnings) = plan_status_updates(&items, config.status_field(), landed_status);
+    for warning in warnings {
+        warn!("Skipping project status update: {}", warning);
+    }
+    if updates.is_empty() {
+        return Ok(());
+    }
+    crate::github::update_project_item_statuses(&updates, execution_mode)?;
+    info!(
+        "Set {} project item(s) to \"{}\" for landed PR(s).",
+        updates.len(),
+        landed_status
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{plan_status_updates, ProjectsConfig};
+    use crate::github::{PrProjectItem, ProjectSingleSelectField, ProjectSingleSelectOption};
+
+    fn item(pr_number: u64, field: Option<ProjectSingleSelectField>) -> PrProjectItem {
+        PrProjectItem {
+            pr_number,
+            item_id: format!("ITEM_{pr_number}"),
+            project_id: "PROJ_1".to_string(),
+            project_title: "Roadmap".to_string(),
+            status_field: field,
+        }
+    }
+
+    #[test]
+    fn plans_updates_for_matching_option_and_warns_otherwise() {
+        let field = ProjectSingleSelectField {
+            id: "FIELD_1".to_string(),
+            options: vec![
+                ProjectSingleSelectOption {
+                    id: "OPT_TODO".to_string(),
+                    name: "Todo".to_string(),
+                },
+                ProjectSingleSelectOption {
+                    id: "OPT_DONE".to_string(),
+                    name: "Done".to_string(),
+                },
+            ],
+        };
+        let items = vec![item(7, Some(field.clone())), item(8, None)];
+
+        let (updates, warnings) = plan_status_updates(&items, "Status", "done");
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].pr_number, 7);
+        assert_eq!(updates[0].option_id, "OPT_DONE");
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("PR #8"), "{warnings:?}");
+
+        let (updates, warnings) = plan_status_updates(&[item(7, Some(field))], "Status", "Shipped");
+        assert!(updates.is_empty());
+        assert!(
+            warnings[0].contains("no option \"Shipped\""),
+            "{warnings:?}"
+        );
+    }
+
+    #[test]
+    fn status_field_defaults_to_status() {
+        assert_eq!(ProjectsConfig::default().status_field(), "Status");
+    }
+}
@@ -0,0 +1,208 @@
+//! Review policies for sensitive paths.
+//!
+//! The `protected_paths` config maps path globs (git `:(glob)` pathspecs, as in `path_scope`) to
+//! reviewers and labels. Every publish path (`spr update`, rewrite commands run with `--update`,
+//! `spr sync`, and `spr flush`) warns about every published group whose commits touch a
+//! protected path, then requests the mandated reviewers and applies the labels on that group's PR.
+//! Requests and labels are additive, so re-running `spr update` is harmless. Paths matching the
+//! `generated_paths` config never trigger a rule.
+
+use anyhow::Result;
+use serde::Deserialize;
+use tracing::warn;
+
+use crate::execution::ExecutionMode;
+use crate::git::git_ro;
+use crate::parsing::Group;
+use crate::run_settings::Setting;
+
+static CONFIGURED_RULES: Setting<Vec<ProtectedPathRule>> = Setting::new();
+
+/// Apply `rules` to every stack published in this process.
+pub fn set_configured_rules(rules: &[ProtectedPathRule]) {
+    if !rules.is_empty() {
+        CONFIGURED_RULES.set(rules.to_vec());
+    }
+}
+
+/// The `protected_paths` config.
+pub fn configured_rules() -> Vec<ProtectedPathRule> {
+    CONFIGURED_RULES.get().unwrap_or_default()
+}
+
+/// One `protected_paths` entry.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProtectedPathRule {
+    /// Path globs that trigger the rule.
+    pub paths: Vec<String>,
+    /// Users (`alice`) or teams (`org/team`) to request review from.
+    pub reviewers: Vec<String>,
+    /// Labels to apply to the PR.
+    pub labels: Vec<String>,
+}
+
+/// Reviewers and labels a group's PR needs because of the protected paths it touches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProtectedGroupMatch {
+    pub stable_handle: String,
+    pub files: Vec<String>,
+    pub reviewers: Vec<String>,
+    pub labels: Vec<String>,
+}
+
+impl ProtectedGroupMatch {
+    pub fn warning(&self) -> String {
+        let mut required = Vec::new();
+        if !self.reviewers.is_empty() {
+            required.push(format!("review from {}", self.reviewers.join(", ")));
+        }
+        if !self.labels.is_empty() {
+            required.push(format!("labels {}", self.labels.join(", ")));
+        }
+        format!(
+            "Protected paths: {} touches {}; requires {}",
+            self.stable_handle,
+            self.files.join(", "),
+            required.join(" and ")
+        )
+    }
+}
+
+fn push_unique(into: &mut Vec<String>, values: &[String]) {
+    for value in values {
+        if !into.contains(value) {
+            into.push(value.clone());
+        }
+    }
+}
+
+/// Files changed by `group` that match any of `globs` and none of the `generated_paths` globs.
+fn protected_files(group: &Group, globs: &[String]) -> Result<Vec<String>> {
+    let (Some(first), Some(last)) = (group.commits.first(), group.commits.last()) else {
+        return Ok(Vec::new());
+    };
+    let range = format!("{first}^..{last}");
+    let mut pathspecs: Vec<String> = globs.iter().map(|glob| format!(":(glob){glob}")).collect();
+    pathspecs.extend(
+        crate::path_attributes::generated_paths()
+            .iter()
+            .map(|glob| format!(":(exclude,glob){glob}")),
+    );
+    let mut args = vec!["log", "--format=", "--name-only", &range, "--"];
+    args.extend(pathspecs.iter().map(String::as_str));
+    let mut files: Vec<String> = Vec::new();
+    for line in git_ro(&args)?.lines().map(str::trim) {
+        if !line.is_empty() && !files.iter().any(|file| file == line) {
+            files.push(line.to_string());
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Match every group against `rules`, merging reviewers and labels of all triggered rules.
+///
+/// # Errors
+///
+/// Returns errors from `git log`.
+pub fn check_groups(
+    groups: &[Group],
+    rules: &[ProtectedPathRule],
+) -> Result<Vec<ProtectedGroupMatch>> {
+    let mut matches = Vec::new();
+    if rules.is_empty() {
+        return Ok(matches);
+    }
+    for group in groups {
+        let mut found = ProtectedGroupMatch {
+            stable_handle: group.selector_text(),
+            files: Vec::new(),
+            reviewers: Vec::new(),
+            labels: Vec::new(),
+        };
+        for rule in rules.iter().filter(|rule| !rule.paths.is_empty()) {
+            let files = protected_files(group, &rule.paths)?;
+            if files.is_empty() {
+                continue;
+            }
+            push_unique(&mut found.files, &files);
+            push_unique(&mut found.reviewers, &rule.reviewers);
+            push_unique(&mut found.labels, &rule.labels);
+        }
+        if !found.files.is_empty() {
+            found.files.sort();
+            matches.push(found);
+        }
+    }
+    Ok(matches)
+}
+
+/// Request reviewers and apply labels on PR `number` for `found`.
+///
+/// Failures (for example requesting review from the PR author) are reported as warnings so one
+/// rejected request does not fail an otherwise successful update.
+pub fn apply_to_pr(number: u64, found: &ProtectedGroupMatch, execution_mode: ExecutionMode) {
+    if !found.reviewers.is_empty() {
+        if let Err(err) =
+            crate::github::request_pr_reviewers(number, &found.reviewers, execution_mode)
+        {
+            warn!("Failed to request protected-path reviewers on PR #{number}: {err:#}");
+        }
+    }
+    if !found.labels.is_empty() {
+        if let Err(err) = crate::github::add_pr_labels(number, &found.labels, execution_mode) {
+            warn!("Failed to apply protected-path labels on PR #{number}: {err:#}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_groups, ProtectedPathRule};
+    use crate::group_markers::GroupMarker;
+    use crate::parsing::Group;
+    use crate::test_support::{commit_file, init_repo, lock_cwd, DirGuard};
+
+    #[test]
+    fn check_groups_merges_triggered_rules_per_group() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path().to_path_buf();
+        let a1 = commit_file(&repo, "migrations.sql", "alter\n", "feat: a\n\npr:a");
+        let a2 = commit_file(&repo, "auth.rs", "fn auth() {}\n", "fix: a");
+        let b1 = commit_file(&repo, "readme.txt", "docs\n", "docs: b\n\npr:b");
+        let _guard = DirGuard::change_to(&repo);
+        let group = |label: &str, commits: Vec<String>| Group {
+            marker: GroupMarker::PrLabel(label.to_string()),
+            subjects: Vec::new(),
+            commits,
+            first_message: None,
+            ignored_after: Vec::new(),
+        };
+        let rules = vec![
+            ProtectedPathRule {
+                paths: vec!["*.sql".to_string()],
+                reviewers: vec!["db-owner".to_string(), "org/security".to_string()],
+                labels: vec!["needs-db-review".to_string()],
+            },
+            ProtectedPathRule {
+                paths: vec!["auth.rs".to_string()],
+                reviewers: vec!["org/security".to_string()],
+                labels: Vec::new(),
+            },
+        ];
+
+        let matches =
+            check_groups(&[group("a", vec![a1, a2]), group("b", vec![b1])], &rules).unwrap();
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].stable_handle, "pr:a");
+        assert_eq!(matches[0].files, vec!["auth.rs", "migrations.sql"]);
+        assert_eq!(matches[0].reviewers, vec!["db-owner", "org/security"]);
+        assert_eq!(
+            matches[0].warning(),
+            "Protected paths: pr:a touches auth.rs, migrations.sql; requires review from db-owner, org/security and labels needs-db-review"
+        );
+    }
+}
@@ -18,6 +18,42 @@ pub enum ReadOnlyPayload {
         #[serde(flatten)]
         data: crate::commands::CommitListData,
     },
+    Files {
+        #[serde(flatten)]
+        data: crate::commands::FilesData,
+    },
+    Stats {
+        #[serde(flatten)]
+        data: crate::commands::StatsData,
+    },
+    Verify {
+        #[serde(flatten)]
+        data: crate::commands::VerifyData,
+    },
+    RangeDiff {
+        #[serde(flatten)]
+        data: crate::commands::RangeDiffData,
+    },
+    Diff {
+        #[serde(flatten)]
+        data: crate::commands::DiffData,
+    },
+    Landed {
+        #[serde(flatten)]
+        data: crate::commands::LandedData,
+    },
+    Backups {
+        #[serde(flatten)]
+        data: crate::commands::BackupsData,
+    },
+    Parse {
+        #[serde(flatten)]
+        data: crate::commands::ParseData,
+    },
+    Status {
+        #[serde(flatten)]
+        data: crate::commands::StatusData,
+    },
 }
 
 pub fn pr_list(command: JsonCommand, data: crate::commands::PrListData) -> ReadOnlyOutput {
@@ -28,6 +64,42 @@ pub fn commit_list(command: JsonCommand, data: crate::commands::CommitListData)
     SummaryOutput::new(command, ReadOnlyPayload::CommitList { data })
 }
 
+pub fn files(command: JsonCommand, data: crate::commands::FilesData) -> ReadOnlyOutput {
+    SummaryOutput::new(command, ReadOnlyPayload::Files { data })
+}
+
+pub fn stats(command: JsonCommand, data: crate::commands::StatsData) -> ReadOnlyOutput {
+    SummaryOutput::new(command, ReadOnlyPayload::Stats { data })
+}
+
+pub fn verify(command: JsonCommand, data: crate::commands::VerifyData) -> ReadOnlyOutput {
+    SummaryOutput::new(command, ReadOnlyPayload::Verify { data })
+}
+
+pub fn range_diff(command: JsonCommand, data: crate::commands::RangeDiffData) -> ReadOnlyOutput {
+    SummaryOutput::new(command, ReadOnlyPayload::RangeDiff { data })
+}
+
+pub fn diff(command: JsonCommand, data: crate::commands::DiffData) -> ReadOnlyOutput {
+    SummaryOutput::new(command, ReadOnlyPayload::Diff { data })
+}
+
+pub fn landed(command: JsonCommand, data: crate::commands::LandedData) -> ReadOnlyOutput {
+    SummaryOutput::new(command, ReadOnlyPayload::Landed { data })
+}
+
+pub fn backups(command: JsonCommand, data: crate::commands::BackupsData) -> ReadOnlyOutput {
+    SummaryOutput::new(command, ReadOnlyPayload::Backups { data })
+}
+
+pub fn parse(command: JsonCommand, data: crate::commands::ParseData) -> ReadOnlyOutput {
+    SummaryOutput::new(command, ReadOnlyPayload::Parse { data })
+}
+
+pub fn status(command: JsonCommand, data: crate::commands::StatusData) -> ReadOnlyOutput {
+    SummaryOutput::new(command, ReadOnlyPayload::Status { data })
+}
+
 #[cfg(test)]
 mod tests {
     use super::{commit_list, pr_list, ReadOnlyPayload};
@@ -51,6 +123,7 @@ mod tests {
                     first_commit_sha: "aaaaaaaa1".to_string(),
                     commit_count: 2,
                     first_subject: "feat: alpha".to_string(),
+                    planned_push: None,
                     remote: RemotePrMetadata {
                         state: RemotePrState::RemoteWithCiReview {
                             pr_number: 17,
@@ -59,7 +132,13 @@ mod tests {
                             state: PrState::Open,
                             ci_review_status: PrCiReviewStatus {
                                 ci_state: PrCiState::Success,
+                                checks_state: PrCiState::Success,
+                                status_contexts: Vec::new(),
                                 review_decision: PrReviewDecision::Approved,
+                                is_draft: false,
+                                awaiting_review_since: None,
+                                requested_reviewers: Vec::new(),
+                                head_oid: String::new(),
                             },
                         },
                     },
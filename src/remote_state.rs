@@ -0,0 +1,390 @@
+//! Shared stack state published to `refs/spr/state/<prefix>` on the remote.
+//!
+//! After each applied publish (`spr update`, rewrite commands run with `--update`, `spr sync`, and
+//! `spr flush`), the ref gets a new commit whose `state.json` maps every group branch ever
+//! published under the prefix to its selector, PR number, last-pushed SHA, and the stack ref that
+//! published it. Entries are merged by head branch rather than replaced wholesale, so groups that were since landed,
+//! closed, or renamed stay discoverable by collaborators and by tools that only see the remote.
+//! Each commit has the previous state as its parent, so pushes are fast-forwards and the ref keeps
+//! a history of stack changes. `spr freeze` records a freeze in the same state, so every
+//! collaborator's `spr update` and `spr land` see it. `spr cleanup` reads which stack branch last
+//! published each group branch, and `spr checkout` reads the last-pushed SHAs.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+use tracing::info;
+
+use crate::execution::ExecutionMode;
+use crate::git::{git_ro, git_rw, git_with_stdin, remote};
+use crate::update_output::UpdateGroupData;
+
+const REMOTE_STATE_SCHEMA_VERSION: u32 = 1;
+const REMOTE_STATE_FILE_NAME: &str = "state.json";
+
+/// One published group branch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemoteStateEntry {
+    pub head_branch: String,
+    /// Group selector such as `pr:alpha` or `branch:team/fix`.
+    pub selector: String,
+    pub pr_number: Option<u64>,
+    pub sha: String,
+    /// Local stack branch that last published the group.
+    pub stack_branch: String,
+    pub updated_at: String,
+}
+
+/// A `spr freeze` of the branches under the prefix.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FreezeRecord {
+    pub frozen_at: String,
+    /// Local stack branch that was frozen.
+    pub stack_branch: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Remote SHA of each group branch when the stack was frozen.
+    pub shas: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemoteStackState {
+    pub schema_version: u32,
+    pub prefix: String,
+    /// Entries sorted by head branch.
+    pub entries: Vec<RemoteStateEntry>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frozen: Option<FreezeRecord>,
+}
+
+/// The remote ref holding the state for branches under `prefix`.
+pub fn state_ref(prefix: &str) -> String {
+    format!("refs/spr/state/{}", prefix.trim_end_matches('/'))
+}
+
+/// Fetch the state ref into the same local ref; returns its commit, or `None` if absent remotely.
+fn fetch_state_commit(state_ref: &str) -> Result<Option<String>> {
+    let remote = remote();
+    let advertised = git_ro(["ls-remote", &remote, state_ref].as_slice())?;
+    let Some(sha) = advertised.split_whitespace().next() else {
+        return Ok(None);
+    };
+    git_ro(
+        [
+            "fetch",
+            "--no-tags",
+            &remote,
+            &format!("+{state_ref}:{state_ref}"),
+        ]
+        .as_slice(),
+    )?;
+    Ok(Some(sha.to_string()))
+}
+
+fn read_state_at(commit: &str) -> Result<RemoteStackState> {
+    let content = git_ro(
+        [
+            "cat-file",
+            "blob",
+            &format!("{commit}:{REMOTE_STATE_FILE_NAME}"),
+        ]
+        .as_slice(),
+    )?;
+    serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse {REMOTE_STATE_FILE_NAME} at {commit}"))
+}
+
+fn merge_entries(state: &mut RemoteStackState, entries: Vec<RemoteStateEntry>) {
+    for entry in entries {
+        state
+            .entries
+            .retain(|existing| existing.head_branch != entry.head_branch);
+        state.entries.push(entry);
+    }
+    state
+        .entries
+        .sort_by(|a, b| a.head_branch.cmp(&b.head_branch));
+}
+
+fn entries_for_update(
+    stack_branch: &str,
+    groups: &[UpdateGroupData],
+    updated_at: &str,
+) -> Vec<RemoteStateEntry> {
+    groups
+        .iter()
+        .map(|group| RemoteStateEntry {
+            head_branch: group.head_branch.clone(),
+            selector: group.stable_handle.clone(),
+            pr_number: group.remote_pr_number,
+            sha: group.target_sha.clone(),
+            stack_branch: stack_branch.to_string(),
+            updated_at: updated_at.to_string(),
+        })
+        .collect()
+}
+
+/// SHA last recorded for `head_branch` in the local copy of the state ref for `prefix`.
+///
+/// Reads only the local ref, which `spr update` refreshes after each publish; `None` when the ref
+/// or the entry does not exist.
+pub fn recorded_push_sha(prefix: &str, head_branch: &str) -> Result<Option<String>> {
+    let state_ref = state_ref(prefix);
+    if !crate::git::git_ref_exists_at(".", &state_ref)? {
+        return Ok(None);
+    }
+    Ok(read_state_at(&state_ref)?
+        .entries
+        .into_iter()
+        .find(|entry| entry.head_branch == head_branch)
+        .map(|entry| entry.sha))
+}
+
+/// Merge the groups of a completed `spr update` into the remote state for `prefix` and push it.
+///
+/// # Errors
+///
+/// Returns errors from Git, including a rejected push when another collaborator published state
+/// concurrently; the next `spr update` merges on top of theirs.
+pub fn publish_update_state(
+    prefix: &str,
+    stack_branch: &str,
+    groups: &[UpdateGroupData],
+    execution_mode: ExecutionMode,
+) -> Result<()> {
+    if groups.is_empty() {
+        return Ok(());
+    }
+    let state_ref = state_ref(prefix);
+    if execution_mode == ExecutionMode::DryRun {
+        info!(
+            "DRY-RUN: would record {} group(s) in {state_ref}",
+            groups.len()
+        );
+        return Ok(());
+    }
+    let (parent, mut state) = fetch_state(prefix, &state_ref)?;
+    let updated_at = OffsetDateTime::now_utc().format(&Rfc3339)?;
+    merge_entries(
+        &mut state,
+        entries_for_update(stack_branch, groups, &updated_at),
+    );
+    push_state(
+        &state_ref,
+        parent.as_deref(),
+        &state,
+        &format!("spr state: update from {stack_branch}"),
+        execution_mode,
+    )
+}
+
+/// The remote state commit for `prefix` and its content, or a fresh state when there is none.
+fn fetch_state(prefix: &str, state_ref: &str) -> Result<(Option<String>, RemoteStackState)> {
+    let parent = fetch_state_commit(state_ref)?;
+    let mut state = match &parent {
+        Some(commit) => read_state_at(commit)?,
+        None => RemoteStackState {
+            schema_version: REMOTE_STATE_SCHEMA_VERSION,
+            prefix: prefix.to_string(),
+            entries: Vec::new(),
+            frozen: None,
+        },
+    };
+    state.schema_version = REMOTE_STATE_SCHEMA_VERSION;
+    Ok((parent, state))
+}
+
+/// Commit `state` on top of `parent`, push it to `state_ref`, and update the local copy.
+fn push_state(
+    state_ref: &str,
+    parent: Option<&str>,
+    state: &RemoteStackState,
+    message: &str,
+    execution_mode: ExecutionMode,
+) -> Result<()> {
+    let blob = git_with_stdin(
+        ["hash-object", "-w", "--stdin"].as_slice(),
+        &(serde_json::to_string_pretty(state)? + "\n"),
+    )?;
+    let tree = git_with_stdin(
+        ["mktree"].as_slice(),
+        &format!("100644 blob {}\t{REMOTE_STATE_FILE_NAME}\n", blob.trim()),
+    )?;
+    let mut args = vec!["commit-tree", tree.trim(), "-m", message];
+    if let Some(parent) = parent {
+        args.extend(["-p", parent]);
+    }
+    let commit = git_ro(&args)?;
+    git_rw(
+        execution_mode,
+        ["push", &remote(), &format!("{}:{state_ref}", commit.trim())].as_slice(),
+    )?;
+    git_ro(["update-ref", state_ref, commit.trim()].as_slice())?;
+    Ok(())
+}
+
+/// The state for `prefix` read from the remote, or from the local copy when the remote cannot be
+/// reached; `None` when neither exists.
+fn current_state(prefix: &str) -> Result<Option<RemoteStackState>> {
+    let state_ref = state_ref(prefix);
+    let commit = match fetch_state_commit(&state_ref) {
+        Ok(commit) => commit,
+        Err(_) => crate::git::git_ref_exists_at(".", &state_ref)?.then(|| state_ref.clone()),
+    };
+    commit.map(|commit| read_state_at(&commit)).transpose()
+}
+
+/// The freeze recorded for `prefix`, read from the remote state, or from the local copy when the
+/// remote cannot be reached.
+pub fn frozen_record(prefix: &str) -> Result<Option<FreezeRecord>> {
+    Ok(current_state(prefix)?.and_then(|state| state.frozen))
+}
+
+/// Group branches under `prefix` that `stack_branch` published last, according to the state.
+///
+/// Branches another stack branch published since are not included, so callers can tell this
+/// stack's abandoned branches from other stacks' live ones under the same prefix.
+pub fn branches_published_from(prefix: &str, stack_branch: &str) -> Result<HashSet<String>> {
+    Ok(current_state(prefix)?
+        .map(|state| {
+            state
+                .entries
+                .into_iter()
+                .filter(|entry| entry.stack_branch == stack_branch)
+                .map(|entry| entry.head_branch)
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Record `frozen` (or clear the freeze with `None`) in the remote state for `prefix`.
+///
+/// # Errors
+///
+/// Returns errors from Git, including a rejected push when the state moved concurrently.
+pub fn publish_freeze(
+    prefix: &str,
+    frozen: Option<FreezeRecord>,
+    execution_mode: ExecutionMode,
+) -> Result<()> {
+    let state_ref = state_ref(prefix);
+    let message = match &frozen {
+        Some(record) => format!("spr state: freeze from {}", record.stack_branch),
+        None => "spr state: thaw".to_string(),
+    };
+    if execution_mode == ExecutionMode::DryRun {
+        info!("DRY-RUN: would record `{message}` in {state_ref}");
+        return Ok(());
+    }
+    let (parent, mut state) = fetch_state(prefix, &state_ref)?;
+    state.frozen = frozen;
+    push_state(
+        &state_ref,
+        parent.as_deref(),
+        &state,
+        &message,
+        execution_mode,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        fetch_state_commit, publish_update_state, read_state_at, state_ref, RemoteStackState,
+    };
+    use crate::execution::ExecutionMode;
+    use crate::test_support::{git, init_repo, lock_cwd, DirGuard};
+    use crate::update_output::{
+        UpdateEditAction, UpdateGroupData, UpdatePrAction, UpdatePushAction,
+    };
+
+    fn read_remote_state(prefix: &str) -> Option<RemoteStackState> {
+        fetch_state_commit(&state_ref(prefix))
+            .unwrap()
+            .map(|commit| read_state_at(&commit).unwrap())
+    }
+
+    fn group(handle: &str, branch: &str, number: u64, sha: &str) -> UpdateGroupData {
+        UpdateGroupData {
+            local_pr_number: 1,
+            stable_handle: handle.to_string(),
+            head_branch: branch.to_string(),
+            base_ref: "main".to_string(),
+            title: "feat".to_string(),
+            target_sha: sha.to_string(),
+            push_action: UpdatePushAction::CreateBranch,
+            pr_action: UpdatePrAction::Created,
+            base_ref_action: UpdateEditAction::Unchanged,
+            description_action: UpdateEditAction::Updated,
+            remote_pr_number: Some(number),
+            remote_pr_url: None,
+        }
+    }
+
+    #[test]
+    fn publish_merges_entries_by_head_branch_across_runs() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path().to_path_buf();
+        let origin_dir = tempfile::tempdir().unwrap();
+        let origin = origin_dir.path().to_path_buf();
+        git(
+            &repo,
+            ["init", "--bare", origin.to_str().unwrap()].as_slice(),
+        );
+        git(
+            &repo,
+            ["remote", "add", "origin", origin.to_str().unwrap()].as_slice(),
+        );
+        let _guard = DirGuard::change_to(&repo);
+
+        assert_eq!(state_ref("dank-spr/"), "refs/spr/state/dank-spr");
+        assert!(read_remote_state("dank-spr/").is_none());
+        publish_update_state(
+            "dank-spr/",
+            "stack",
+            &[
+                group("pr:a", "dank-spr/a", 1, "aaa"),
+                group("pr:b", "dank-spr/b", 2, "bbb"),
+            ],
+            ExecutionMode::Apply,
+        )
+        .unwrap();
+        publish_update_state(
+            "dank-spr/",
+            "stack",
+            &[group("pr:b", "dank-spr/b", 2, "ccc")],
+            ExecutionMode::Apply,
+        )
+        .unwrap();
+
+        let state = read_remote_state("dank-spr/").unwrap();
+        let summary: Vec<(String, Option<u64>, String)> = state
+            .entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry.head_branch.clone(),
+                    entry.pr_number,
+                    entry.sha.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("dank-spr/a".to_string(), Some(1), "aaa".to_string()),
+                ("dank-spr/b".to_string(), Some(2), "ccc".to_string()),
+            ]
+        );
+        assert_eq!(
+            git(
+                &origin,
+                ["rev-list", "--count", "refs/spr/state/dank-spr"].as_slice()
+            )
+            .trim(),
+            "2"
+        );
+    }
+}
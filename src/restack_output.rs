@@ -80,6 +80,100 @@ impl RestackPreviewOutput {
     }
 }
 
+/// Outcome of a `spr restack --check` trial replay.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestackCheckResult {
+    Clean,
+    Conflict,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RestackCheckOutput {
+    pub schema_version: u32,
+    pub command: JsonCommand,
+    pub result: RestackCheckResult,
+    pub data: RestackCheckData,
+}
+
+/// The first commit that failed to replay during a trial restack.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RestackCheckConflict {
+    /// Group the commit belongs to, or the ignored block that carries it.
+    pub owner: String,
+    pub commit: String,
+    pub subject: String,
+    pub conflicted_paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RestackCheckData {
+    pub base_ref: String,
+    pub base_sha: Option<String>,
+    pub current_branch: String,
+    pub original_head: String,
+    pub after_selector: String,
+    pub resolved_after_count: usize,
+    pub planned_commit_count: usize,
+    pub replayed_commit_count: usize,
+    pub conflict: Option<RestackCheckConflict>,
+}
+
+pub fn check(data: RestackCheckData) -> RestackCheckOutput {
+    RestackCheckOutput {
+        schema_version: JSON_OUTPUT_SCHEMA_VERSION,
+        command: JsonCommand::Restack,
+        result: if data.conflict.is_some() {
+            RestackCheckResult::Conflict
+        } else {
+            RestackCheckResult::Clean
+        },
+        data,
+    }
+}
+
+impl RestackCheckOutput {
+    /// A predicted conflict exits non-zero so scripts can gate a real restack on the check.
+    pub fn exit_code(&self) -> i32 {
+        match self.result {
+            RestackCheckResult::Clean => crate::json_output::EXIT_SUCCESS,
+            RestackCheckResult::Conflict => crate::json_output::EXIT_FAILURE,
+        }
+    }
+
+    pub fn render_human(&self) -> String {
+        let data = &self.data;
+        let header = format!(
+            "Restack check:\n  current branch: {} @ {} (unchanged)\n  target base: {} @ {}\n  selector: --after {} -> keeps first {} PR group(s)",
+            data.current_branch,
+            data.original_head,
+            data.base_ref,
+            data.base_sha.as_deref().unwrap_or("<unresolved>"),
+            data.after_selector,
+            data.resolved_after_count,
+        );
+        match &data.conflict {
+            None => format!(
+                "{header}\n  result: clean; all {} commit(s) replay without conflicts",
+                data.planned_commit_count
+            ),
+            Some(conflict) => format!(
+                "{header}\n  result: conflict after replaying {} of {} commit(s)\n  conflicting commit: {} {} ({})\n  conflicted paths: {}",
+                data.replayed_commit_count,
+                data.planned_commit_count,
+                conflict.commit.chars().take(8).collect::<String>(),
+                conflict.subject,
+                conflict.owner,
+                if conflict.conflicted_paths.is_empty() {
+                    "<unknown>".to_string()
+                } else {
+                    conflict.conflicted_paths.join(", ")
+                },
+            ),
+        }
+    }
+}
+
 fn render_groups(groups: &[RestackPreviewGroup]) -> String {
     if groups.is_empty() {
         "<none>".to_string()
@@ -189,8 +283,9 @@ pub fn render_human_preview(title: &str, data: &RestackPreviewData) -> String {
 #[cfg(test)]
 mod tests {
     use super::{
-        preview, render_human_preview, RestackExecutorPlan, RestackPreviewData,
-        RestackPreviewGroup, RestackPreviewResult,
+        check, preview, render_human_preview, RestackCheckConflict, RestackCheckData,
+        RestackCheckResult, RestackExecutorPlan, RestackPreviewData, RestackPreviewGroup,
+        RestackPreviewResult,
     };
 
     fn sample_preview_data() -> RestackPreviewData {
@@ -304,4 +399,33 @@ mod tests {
         assert!(rendered.contains("not validated yet: cherry-pick conflicts; tests"));
         assert!(!rendered.contains("not validated by preview"));
     }
+
+    #[test]
+    fn restack_check_conflict_exits_non_zero_and_names_group_and_paths() {
+        let output = check(RestackCheckData {
+            base_ref: "origin/main".to_string(),
+            base_sha: Some("base123".to_string()),
+            current_branch: "stack".to_string(),
+            original_head: "head123".to_string(),
+            after_selector: "0".to_string(),
+            resolved_after_count: 0,
+            planned_commit_count: 2,
+            replayed_commit_count: 1,
+            conflict: Some(RestackCheckConflict {
+                owner: "pr:beta".to_string(),
+                commit: "abcdef0123456789".to_string(),
+                subject: "feat: beta".to_string(),
+                conflicted_paths: vec!["story.txt".to_string()],
+            }),
+        });
+        let json = serde_json::to_value(&output).unwrap();
+
+        assert_eq!(output.result, RestackCheckResult::Conflict);
+        assert_eq!(output.exit_code(), crate::json_output::EXIT_FAILURE);
+        assert_eq!(json["result"], "conflict");
+        assert_eq!(json["data"]["conflict"]["owner"], "pr:beta");
+        let rendered = output.render_human();
+        assert!(rendered.contains("conflicting commit: abcdef01 feat: beta (pr:beta)"));
+        assert!(rendered.contains("conflicted paths: story.txt"));
+    }
 }
@@ -0,0 +1,223 @@
+//! Reviewer-oriented "Review guide" section in PR bodies.
+//!
+//! With `review_guide: true`, `spr update` renders a collapsed section beneath each PR's stack
+//! block listing the PR's position in the stack, its parent and child PRs, its commits, and the
+//! files it touches grouped by directory. Files `.gitattributes` marks as generated or binary are
+//! named on a separate line instead, so they do not crowd out the files to read. The section sits
+//! between its own
+//! `<!-- spr-review-guide:start -->`/`<!-- spr-review-guide:end -->` markers, so it is refreshed on
+//! every update in both `pr_description_mode`s without disturbing hand-written text.
+
+use anyhow::Result;
+use std::collections::BTreeMap;
+
+use crate::git::git_ro;
+use crate::parsing::Group;
+use crate::path_attributes::path_attributes;
+use crate::run_settings::Setting;
+
+pub const REVIEW_GUIDE_START: &str = "<!-- spr-review-guide:start -->";
+pub const REVIEW_GUIDE_END: &str = "<!-- spr-review-guide:end -->";
+
+static REVIEW_GUIDE: Setting<bool> = Setting::new();
+
+/// Render review guides on PRs published by this process.
+pub fn set_enabled(enabled: bool) {
+    if enabled {
+        REVIEW_GUIDE.set(true);
+    }
+}
+
+pub fn enabled() -> bool {
+    REVIEW_GUIDE.get().unwrap_or(false)
+}
+
+/// Where a PR sits in its stack, as shown in the guide.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StackPosition {
+    /// 1-based position from the bottom of the stack.
+    pub index: usize,
+    pub total: usize,
+    /// PR number below this one, or `None` when the PR targets the base branch.
+    pub parent: Option<u64>,
+    pub child: Option<u64>,
+    pub base: String,
+}
+
+/// Files changed by `group`, sorted, split into files to review and generated or binary files.
+fn group_files(group: &Group) -> Result<(Vec<String>, Vec<String>)> {
+    let (Some(first), Some(last)) = (group.commits.first(), group.commits.last()) else {
+        return Ok((Vec::new(), Vec::new()));
+    };
+    let first_parent = format!("{first}^");
+    let mut files: Vec<String> = git_ro(["diff", "--name-only", &first_parent, last].as_slice())?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect();
+    files.sort();
+    let attributes = path_attributes(&first_parent, last, &files)?;
+    Ok(files
+        .into_iter()
+        .partition(|file| !attributes.contains_key(file)))
+}
+
+fn files_by_directory(files: &[String]) -> BTreeMap<String, Vec<String>> {
+    let mut by_dir: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for file in files {
+        let (dir, name) = match file.rsplit_once('/') {
+            Some((dir, name)) => (format!("{dir}/"), name.to_string()),
+            None => ("./".to_string(), file.clone()),
+        };
+        by_dir.entry(dir).or_default().push(name);
+    }
+    by_dir
+}
+
+fn render(
+    position: &StackPosition,
+    commits: &[(String, String)],
+    files: &[String],
+    skipped_files: &[String],
+) -> String {
+    let parent = match position.parent {
+        Some(number) => format!("on top of #{number}"),
+        None => format!("based on `{}`", position.base),
+    };
+    let child = match position.child {
+        Some(number) => format!("; #{number} builds on it"),
+        None => String::new(),
+    };
+    let mut out = format!(
+        "{REVIEW_GUIDE_START}\n<details>\n<summary>Review guide</summary>\n\n**Position**: {} of {}, {}{}\n\n**Commits** ({}):\n",
+        position.index,
+        position.total,
+        parent,
+        child,
+        commits.len()
+    );
+    for (sha, subject) in commits {
+        out.push_str(&format!("- `{}` {}\n", &sha[..sha.len().min(8)], subject));
+    }
+    out.push_str(&format!("\n**Files** ({}):\n", files.len()));
+    for (dir, names) in files_by_directory(files) {
+        let names = names
+            .iter()
+            .map(|name| format!("`{name}`"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        out.push_str(&format!("- `{dir}`: {names}\n"));
+    }
+    if !skipped_files.is_empty() {
+        let names = skipped_files
+            .iter()
+            .map(|name| format!("`{name}`"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        out.push_str(&format!(
+            "\n**Generated or binary** ({}): {names}\n",
+            skipped_files.len()
+        ));
+    }
+    out.push_str(&format!("</details>\n{REVIEW_GUIDE_END}"));
+    out
+}
+
+/// Render the review guide for `group` at `position`.
+///
+/// # Errors
+///
+/// Returns errors from `git diff`.
+pub fn review_guide(group: &Group, position: &StackPosition) -> Result<String> {
+    let commits: Vec<(String, String)> = group
+        .commits
+        .iter()
+        .cloned()
+        .zip(group.subjects.iter().cloned())
+        .collect();
+    let (files, skipped_files) = group_files(group)?;
+    Ok(render(position, &commits, &files, &skipped_files))
+}
+
+/// Replace the review guide in `body`, or append it after the rest of the body.
+pub fn update_review_guide(body: &str, guide: &str) -> String {
+    if let (Some(start), Some(end)) = (body.find(REVIEW_GUIDE_START), body.find(REVIEW_GUIDE_END)) {
+        if end >= start {
+            let end = end + REVIEW_GUIDE_END.len();
+            return format!("{}{}{}", &body[..start], guide, &body[end..]);
+        }
+    }
+    if body.trim().is_empty() {
+        guide.to_string()
+    } else {
+        format!("{}\n\n{}", body, guide)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render, update_review_guide, StackPosition};
+
+    #[test]
+    fn render_groups_files_by_directory_and_links_neighbours() {
+        let guide = render(
+            &StackPosition {
+                index: 2,
+                total: 3,
+                parent: Some(11),
+                child: Some(13),
+                base: "main".to_string(),
+            },
+            &[(
+                "0123456789abcdef".to_string(),
+                "feat: beta pr:beta".to_string(),
+            )],
+            &[
+                "README.md".to_string(),
+                "src/a.rs".to_string(),
+                "src/b.rs".to_string(),
+            ],
+            &[],
+        );
+        assert_eq!(
+            guide,
+            "<!-- spr-review-guide:start -->\n<details>\n<summary>Review guide</summary>\n\n**Position**: 2 of 3, on top of #11; #13 builds on it\n\n**Commits** (1):\n- `01234567` feat: beta pr:beta\n\n**Files** (3):\n- `./`: `README.md`\n- `src/`: `a.rs`, `b.rs`\n</details>\n<!-- spr-review-guide:end -->"
+        );
+    }
+
+    #[test]
+    fn render_names_generated_or_binary_files_apart() {
+        let guide = render(
+            &StackPosition {
+                index: 1,
+                total: 1,
+                parent: None,
+                child: None,
+                base: "main".to_string(),
+            },
+            &[],
+            &["src/a.rs".to_string()],
+            &["Cargo.lock".to_string(), "logo.png".to_string()],
+        );
+        assert!(
+            guide.contains("**Files** (1):\n- `src/`: `a.rs`\n\n**Generated or binary** (2): `Cargo.lock`, `logo.png`\n</details>"),
+            "unexpected guide: {guide}"
+        );
+    }
+
+    #[test]
+    fn update_review_guide_replaces_existing_section_only() {
+        let old =
+            "Intro\n\n<!-- spr-review-guide:start -->\nold\n<!-- spr-review-guide:end -->\n\nNotes";
+        let guide = "<!-- spr-review-guide:start -->\nnew\n<!-- spr-review-guide:end -->";
+        assert_eq!(
+            update_review_guide(old, guide),
+            "Intro\n\n<!-- spr-review-guide:start -->\nnew\n<!-- spr-review-guide:end -->\n\nNotes"
+        );
+        assert_eq!(
+            update_review_guide("Intro", guide),
+            format!("Intro\n\n{guide}")
+        );
+    }
+}
@@ -0,0 +1,198 @@
+//! Reviewers requested on every stack PR.
+//!
+//! Reviewers come from the `reviewers` config (for the whole stack) and from `Reviewers:` trailers
+//! in a group's commits (for that group's PR). After publishing, every publish path (`spr update`,
+//! `--update` rewrites, and `spr flush`) requests review from each declared reviewer who is not the
+//! PR author, is not already requested, and has not reviewed yet, so re-publishing does not
+//! re-request reviews that were already given.
+
+use anyhow::Result;
+use tracing::{info, warn};
+
+use crate::execution::ExecutionMode;
+use crate::git::git_ro;
+use crate::github::{fetch_pr_review_participants, request_pr_reviewers, PrReviewParticipants};
+use crate::parsing::Group;
+use crate::run_settings::Setting;
+
+static CONFIGURED_REVIEWERS: Setting<Vec<String>> = Setting::new();
+
+/// Request review from `reviewers` on every stack PR published in this process.
+pub fn set_configured_reviewers(reviewers: &[String]) {
+    if !reviewers.is_empty() {
+        CONFIGURED_REVIEWERS.set(reviewers.to_vec());
+    }
+}
+
+/// The `reviewers` config.
+pub fn configured_reviewers() -> Vec<String> {
+    CONFIGURED_REVIEWERS.get().unwrap_or_default()
+}
+
+/// Reviewers declared for one group's PR.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupReviewers {
+    pub stable_handle: String,
+    pub reviewers: Vec<String>,
+}
+
+/// Reviewer names from `Reviewers:` trailer values, separated by commas or whitespace, with any
+/// leading `@` removed.
+fn parse_trailer_reviewers(values: &str) -> Vec<String> {
+    values
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .map(|name| name.trim_start_matches('@'))
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn push_unique(into: &mut Vec<String>, values: impl IntoIterator<Item = String>) {
+    for value in values {
+        if !into.iter().any(|known| known.eq_ignore_ascii_case(&value)) {
+            into.push(value);
+        }
+    }
+}
+
+/// Reviewers for each group: `configured` plus the group's `Reviewers:` trailers.
+///
+/// # Errors
+///
+/// Returns errors from `git log`.
+pub fn collect_group_reviewers(
+    groups: &[Group],
+    configured: &[String],
+) -> Result<Vec<GroupReviewers>> {
+    let mut out = Vec::with_capacity(groups.len());
+    for group in groups {
+        let mut reviewers = Vec::new();
+        push_unique(&mut reviewers, configured.iter().cloned());
+        if let (Some(first), Some(last)) = (group.commits.first(), group.commits.last()) {
+            let trailers = git_ro(
+                [
+                    "log",
+                    "--reverse",
+                    "--format=%(trailers:key=Reviewers,valueonly)",
+                    &format!("{first}^..{last}"),
+                ]
+                .as_slice(),
+            )?;
+            push_unique(&mut reviewers, parse_trailer_reviewers(&trailers));
+        }
+        if !reviewers.is_empty() {
+            out.push(GroupReviewers {
+                stable_handle: group.selector_text(),
+                reviewers,
+            });
+        }
+    }
+    Ok(out)
+}
+
+/// Declared reviewers that still need a review request on a PR.
+fn missing_reviewers(declared: &[String], participants: &PrReviewParticipants) -> Vec<String> {
+    let involved = |name: &String| {
+        name.eq_ignore_ascii_case(&participants.author)
+            || participants
+                .requested
+                .iter()
+                .chain(&participants.reviewed)
+                .any(|known| known.eq_ignore_ascii_case(name))
+    };
+    declared
+        .iter()
+        .filter(|name| !involved(name))
+        .cloned()
+        .collect()
+}
+
+/// Request the missing declared reviewers on each `(PR number, reviewers)` pair.
+///
+/// Best-effort: failures are logged as warnings because the calling command already succeeded.
+pub fn request_missing_reviewers(prs: &[(u64, Vec<String>)], execution_mode: ExecutionMode) {
+    if prs.is_empty() {
+        return;
+    }
+    let numbers: Vec<u64> = prs.iter().map(|(number, _)| *number).collect();
+    let participants = match fetch_pr_review_participants(&numbers) {
+        Ok(participants) => participants,
+        Err(err) => {
+            warn!("Failed to read PR reviewers: {err:#}");
+            return;
+        }
+    };
+    for (number, declared) in prs {
+        let Some(participants) = participants.get(number) else {
+            continue;
+        };
+        let missing = missing_reviewers(declared, participants);
+        if missing.is_empty() {
+            continue;
+        }
+        info!(
+            "Requesting review from {} on PR #{}",
+            missing.join(", "),
+            number
+        );
+        if let Err(err) = request_pr_reviewers(*number, &missing, execution_mode) {
+            warn!("Failed to request reviewers on PR #{number}: {err:#}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{collect_group_reviewers, missing_reviewers, parse_trailer_reviewers};
+    use crate::github::PrReviewParticipants;
+    use crate::parsing::derive_local_groups;
+    use crate::test_support::{commit_file, git, init_repo, lock_cwd, DirGuard};
+
+    #[test]
+    fn trailer_reviewers_split_on_commas_and_strip_mentions() {
+        assert_eq!(
+            parse_trailer_reviewers("@bob, acme/infra\ncarol\n"),
+            vec!["bob", "acme/infra", "carol"]
+        );
+    }
+
+    #[test]
+    fn group_reviewers_merge_config_with_commit_trailers() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path().to_path_buf();
+        git(&repo, ["checkout", "-b", "stack"].as_slice());
+        commit_file(&repo, "a.txt", "a\n", "feat: a pr:alpha\n\nReviewers: @bob");
+        commit_file(
+            &repo,
+            "a.txt",
+            "a2\n",
+            "feat: a more\n\nReviewers: alice, carol",
+        );
+        commit_file(&repo, "b.txt", "b\n", "feat: b pr:beta");
+        let _guard = DirGuard::change_to(&repo);
+        let (_merge_base, groups) = derive_local_groups("main", "ignore").unwrap();
+
+        let reviewers = collect_group_reviewers(&groups, &["alice".to_string()]).unwrap();
+
+        assert_eq!(reviewers.len(), 2);
+        assert_eq!(reviewers[0].stable_handle, "pr:alpha");
+        assert_eq!(reviewers[0].reviewers, vec!["alice", "bob", "carol"]);
+        assert_eq!(reviewers[1].reviewers, vec!["alice"]);
+    }
+
+    #[test]
+    fn missing_reviewers_skip_author_requested_and_reviewed() {
+        let participants = PrReviewParticipants {
+            author: "dana".to_string(),
+            requested: vec!["acme/infra".to_string()],
+            reviewed: vec!["Bob".to_string()],
+        };
+        let declared: Vec<String> = ["dana", "acme/infra", "bob", "carol"]
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+
+        assert_eq!(missing_reviewers(&declared, &participants), vec!["carol"]);
+    }
+}
@@ -0,0 +1,55 @@
+//! Process-wide switches set once per run from the CLI and config.
+//!
+//! Options such as `--from` or `--stack` are read deep inside group derivation and git helpers,
+//! far from `main`. Keeping them in statics here, instead of `SPR_*` environment variables, keeps
+//! them out of the environment of every `git`, `gh`, hook, and editor subprocess spr starts.
+
+use std::sync::{PoisonError, RwLock};
+
+/// One switch: unset until `main` (or a test) sets it.
+pub struct Setting<T>(RwLock<Option<T>>);
+
+impl<T: Clone> Setting<T> {
+    pub const fn new() -> Self {
+        Self(RwLock::new(None))
+    }
+
+    pub fn set(&self, value: T) {
+        *self.0.write().unwrap_or_else(PoisonError::into_inner) = Some(value);
+    }
+
+    pub fn get(&self) -> Option<T> {
+        self.0
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Set the switch until the returned guard drops, then restore its previous value.
+    #[cfg(test)]
+    pub fn scoped(&'static self, value: T) -> SettingGuard<T> {
+        let previous = self.get();
+        self.set(value);
+        SettingGuard {
+            setting: self,
+            previous,
+        }
+    }
+}
+
+#[cfg(test)]
+pub struct SettingGuard<T: Clone + 'static> {
+    setting: &'static Setting<T>,
+    previous: Option<T>,
+}
+
+#[cfg(test)]
+impl<T: Clone + 'static> Drop for SettingGuard<T> {
+    fn drop(&mut self) {
+        *self
+            .setting
+            .0
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = self.previous.take();
+    }
+}
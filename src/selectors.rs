@@ -114,7 +114,7 @@ fn parse_pr_label(input: &str, whole: &str) -> std::result::Result<String, Strin
                 "explicit selector `{whole}` must start with an ASCII letter after `pr:`"
             )),
             crate::pr_labels::LabelValidationError::InvalidCharacters => Err(format!(
-                "explicit selector `{whole}` must use only ASCII letters, digits, `.`, `_`, or `-` after the first letter"
+                "explicit selector `{whole}` must use only ASCII letters, digits, `.`, `_`, or `-` after the first letter, with at most one `/` after a stack name"
             )),
         }
     } else {
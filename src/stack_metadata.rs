@@ -23,7 +23,10 @@ use crate::git::{
     git_common_dir, git_common_dir_at, git_current_branch, git_current_branch_at,
     git_ref_exists_at, git_rev_parse_at, git_ro_in, repo_root,
 };
-use crate::parsing::{parse_groups_with_ignored, split_groups_for_update, Group};
+use crate::parsing::{
+    out_of_scope_commits_for_current_scope, parse_groups_with_leading_commits_scoped,
+    split_groups_for_update, Group,
+};
 
 // TODO(2026-11-07): Drop schema v1 read compatibility after the six-month
 // migration window. Remove the `tag` serde aliases, legacy selector
@@ -616,7 +619,9 @@ pub fn build_snapshot_for_branch(
         ]
         .as_slice(),
     )?;
-    let (leading_ignored, parsed_groups) = parse_groups_with_ignored(&lines, ignore_tag)?;
+    let out_of_scope = out_of_scope_commits_for_current_scope(Some(repo_path), &range)?;
+    let parsed = parse_groups_with_leading_commits_scoped(&lines, ignore_tag, &out_of_scope)?;
+    let (leading_ignored, parsed_groups) = (parsed.leading_ignored, parsed.groups);
     let (groups, _skipped_handles) = split_groups_for_update(&leading_ignored, parsed_groups);
     build_snapshot_from_groups(stack_branch, &stack_head, base, prefix, &groups)
 }
@@ -751,6 +756,34 @@ pub fn stack_ids_for_branch(metadata: &StackMetadataFile, stack_branch: &str) ->
         .collect()
 }
 
+/// PR branches in `pr_branches` whose live record belongs to a stack built from another branch.
+///
+/// Returns `(pr_branch, owning_stack_branch)` pairs. A stack that ever published from
+/// `stack_branch` (it is among the owner's known branches) is not foreign, so renaming the
+/// stack branch does not warn.
+pub fn foreign_stack_owners(
+    metadata: &StackMetadataFile,
+    stack_branch: &str,
+    pr_branches: &[String],
+) -> Vec<(String, String)> {
+    pr_branches
+        .iter()
+        .filter_map(|pr_branch| {
+            let record = metadata
+                .pr_branches
+                .get(&PrBranchName(pr_branch.clone()))?
+                .as_live()?;
+            let owner = metadata.stacks.get(record.stack_id)?;
+            let owned_here = owner.preferred_branch.0 == stack_branch
+                || owner
+                    .known_branches
+                    .iter()
+                    .any(|candidate| candidate.0 == stack_branch);
+            (!owned_here).then(|| (pr_branch.clone(), owner.preferred_branch.0.clone()))
+        })
+        .collect()
+}
+
 pub fn verify_stack_branch_for_pr_record(
     repo_path: &str,
     candidate_branch: &StackBranchName,
@@ -845,12 +878,12 @@ pub fn current_branch_or_none(repo_path: &str) -> Result<Option<String>> {
 #[cfg(test)]
 mod tests {
     use super::{
-        acquire_lock, apply_snapshot, load_metadata_from_path, metadata_lock_path,
-        ordered_known_branches, refresh_metadata_for_current_checkout_if_attached,
-        write_metadata_atomically, GroupSelectorText, PrBranchName, PrBranchRecord,
-        StackBranchName, StackId, StackMetadataFile, StackRecord, StackSnapshot,
-        StackSnapshotGroup, TombstoneReason, LEGACY_STACK_METADATA_SCHEMA_VERSION,
-        STACK_METADATA_SCHEMA_VERSION,
+        acquire_lock, apply_snapshot, foreign_stack_owners, load_metadata_from_path,
+        metadata_lock_path, ordered_known_branches,
+        refresh_metadata_for_current_checkout_if_attached, write_metadata_atomically,
+        GroupSelectorText, PrBranchName, PrBranchRecord, StackBranchName, StackId,
+        StackMetadataFile, StackRecord, StackSnapshot, StackSnapshotGroup, TombstoneReason,
+        LEGACY_STACK_METADATA_SCHEMA_VERSION, STACK_METADATA_SCHEMA_VERSION,
     };
     use crate::test_support::{git, init_repo, lock_cwd, DirGuard};
     use std::collections::BTreeMap;
@@ -969,6 +1002,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn foreign_stack_owners_reports_branches_published_from_another_stack() {
+        let metadata = apply_snapshot(
+            StackMetadataFile::default(),
+            &sample_snapshot("dank/stack-a", &[("dank-spr/alpha", "a1", "a2")]),
+            "2026-03-05T00:00:00Z",
+        )
+        .unwrap();
+        let branches = vec!["dank-spr/alpha".to_string(), "dank-spr/gamma".to_string()];
+
+        assert_eq!(
+            foreign_stack_owners(&metadata, "dank/stack-b", &branches),
+            vec![("dank-spr/alpha".to_string(), "dank/stack-a".to_string())]
+        );
+        assert!(foreign_stack_owners(&metadata, "dank/stack-a", &branches).is_empty());
+    }
+
     #[test]
     fn apply_snapshot_reuses_empty_stack_by_known_branch() {
         let existing = StackMetadataFile {
@@ -0,0 +1,316 @@
+//! Stacks split into segments that target different bases.
+//!
+//! A group whose first commit carries a `pr:base:<ref>` directive starts a new segment: that group
+//! and every group above it, up to the next `pr:base:` directive, are published by `spr update` as
+//! an independent PR chain whose bottom PR targets `<ref>`. Locally the segment still sits on top
+//! of the groups below it, so before publishing, its commits are replayed onto `<ref>` with a
+//! private index (no worktree or checkout is touched). Replays keep each commit's author,
+//! committer, and message, so an unchanged segment replays to the same SHAs and is not
+//! force-pushed again. With `--stack`, the active named stack's groups are replayed the same way
+//! when other stacks' commits sit below or between them, so each stack gets a PR chain of its own.
+
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+use std::process::Command;
+
+use crate::commands::common::{apply_commit_diff, git_in_index, with_temp_index};
+use crate::git::git_ro;
+use crate::group_directives::segment_base_directive;
+use crate::parsing::{other_stack_commits_in, Group};
+
+/// A run of groups published as one PR chain onto `base`.
+#[derive(Debug, Clone)]
+pub struct StackSegment {
+    pub base: String,
+    pub groups: Vec<Group>,
+}
+
+/// The `pr:base:<ref>` directive on the group's first commit, if any.
+pub fn group_segment_base(group: &Group) -> Option<String> {
+    group
+        .first_message
+        .as_deref()
+        .and_then(segment_base_directive)
+}
+
+/// Whether any group starts a segment with its own base.
+pub fn has_segment_bases(groups: &[Group]) -> bool {
+    groups
+        .iter()
+        .any(|group| group_segment_base(group).is_some())
+}
+
+/// Split `groups` (oldest first) into segments; groups before the first directive use `base`.
+pub fn split_segments(base: &str, groups: Vec<Group>) -> Vec<StackSegment> {
+    let mut segments: Vec<StackSegment> = Vec::new();
+    for group in groups {
+        match (group_segment_base(&group), segments.last_mut()) {
+            (None, Some(current)) => current.groups.push(group),
+            (directive, _) => segments.push(StackSegment {
+                base: directive.unwrap_or_else(|| base.to_string()),
+                groups: vec![group],
+            }),
+        }
+    }
+    segments
+}
+
+/// Write a copy of `commit` on top of `parent`, keeping author, committer, and message.
+fn replay_commit(index: &Path, commit: &str, parent: &str) -> Result<String> {
+    git_in_index(index, ["read-tree", parent].as_slice(), None)?;
+    apply_commit_diff(index, commit)?;
+    let tree = git_in_index(index, ["write-tree"].as_slice(), None)?;
+    let identity = git_ro(
+        [
+            "log",
+            "-n",
+            "1",
+            "--format=%an%x00%ae%x00%ad%x00%cn%x00%ce%x00%cd%x00%B",
+            "--date=raw",
+            commit,
+        ]
+        .as_slice(),
+    )?;
+    let fields: Vec<&str> = identity.splitn(7, '\0').collect();
+    let [an, ae, ad, cn, ce, cd, message] = fields.as_slice() else {
+        bail!("Could not read the identity of commit {}", commit);
+    };
+    let out = Command::new("git")
+        .args(["commit-tree", &tree, "-p", parent, "-m", message.trim_end()])
+        .env("GIT_AUTHOR_NAME", an)
+        .env("GIT_AUTHOR_EMAIL", ae)
+        .env("GIT_AUTHOR_DATE", ad)
+        .env("GIT_COMMITTER_NAME", cn)
+        .env("GIT_COMMITTER_EMAIL", ce)
+        .env("GIT_COMMITTER_DATE", cd)
+        .output()
+        .context("failed to spawn git commit-tree")?;
+    if !out.status.success() {
+        bail!(
+            "git commit-tree failed for {}: {}",
+            commit,
+            String::from_utf8_lossy(&out.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).trim().to_string())
+}
+
+/// Replay the segment's commits onto its base, replacing each group's commits with the copies.
+///
+/// # Errors
+///
+/// Returns an error when the base does not resolve or a commit does not apply without the
+/// groups below the segment.
+pub fn replay_segment_onto_base(segment: &mut StackSegment) -> Result<()> {
+    let base = segment.base.clone();
+    replay_groups_onto(&base, &mut segment.groups, |commit, group| {
+        format!(
+            "Commit {} of {} does not apply onto {}; a `pr:base:` segment must not depend on the groups below it",
+            commit,
+            group.selector_text(),
+            base
+        )
+    })
+}
+
+/// With `--stack`, take the commits of other named stacks out of the parsed stack and, when any
+/// sat below or between the active stack's groups, replay those groups onto `merge_base`.
+///
+/// The foreign commits are dropped from `leading_ignored` and the groups' `ignored_after`, so
+/// they no longer end the publishable prefix the way an ignore block does, and the replayed
+/// groups form a PR chain of their own. Local history is left alone.
+///
+/// # Errors
+///
+/// Returns errors from `git log`, or when a group's commit does not apply without the other
+/// stacks' commits below it.
+pub fn isolate_active_stack(
+    merge_base: &str,
+    range: &str,
+    leading_ignored: &mut Vec<String>,
+    groups: &mut [Group],
+) -> Result<()> {
+    let foreign = other_stack_commits_in(range)?;
+    if foreign.is_empty() {
+        return Ok(());
+    }
+    leading_ignored.retain(|commit| !foreign.contains(commit));
+    for group in groups.iter_mut() {
+        group
+            .ignored_after
+            .retain(|commit| !foreign.contains(commit));
+    }
+    // Replay only the groups that are still publishable; a real ignore block ends the chain
+    let publishable = if leading_ignored.is_empty() {
+        groups
+            .iter()
+            .position(|group| !group.ignored_after.is_empty())
+            .map_or(groups.len(), |idx| idx + 1)
+    } else {
+        0
+    };
+    replay_groups_onto(merge_base, &mut groups[..publishable], |commit, group| {
+        format!(
+            "Commit {} of {} does not apply without the other stacks' commits below it; move the stack to the bottom of the branch or onto its own branch",
+            commit,
+            group.selector_text()
+        )
+    })
+}
+
+/// Replay the commits of `groups` in order onto `onto`, replacing each group's commits with the
+/// copies. `conflict` describes a commit that does not apply.
+fn replay_groups_onto(
+    onto: &str,
+    groups: &mut [Group],
+    conflict: impl Fn(&str, &Group) -> String,
+) -> Result<()> {
+    let mut parent = git_ro(["rev-parse", &format!("{onto}^{{commit}}")].as_slice())
+        .with_context(|| format!("segment base {onto} does not resolve"))?
+        .trim()
+        .to_string();
+    with_temp_index("segment", |index| {
+        for group in groups.iter_mut() {
+            let mut replayed = Vec::with_capacity(group.commits.len());
+            for commit in &group.commits {
+                parent = replay_commit(index, commit, &parent)
+                    .with_context(|| conflict(commit, group))?;
+                replayed.push(parent.clone());
+            }
+            group.commits = replayed;
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{isolate_active_stack, replay_segment_onto_base, split_segments};
+    use crate::parsing::{
+        derive_groups_between, derive_groups_between_with_ignored, split_groups_for_update,
+        ACTIVE_STACK,
+    };
+    use crate::test_support::{commit_file, git, init_repo, lock_cwd, DirGuard};
+
+    #[test]
+    fn segments_split_at_directives_and_replay_onto_their_base() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path().to_path_buf();
+        git(&repo, ["branch", "release"].as_slice());
+        git(&repo, ["checkout", "-b", "stack"].as_slice());
+        commit_file(&repo, "alpha.txt", "alpha\n", "feat: alpha pr:alpha");
+        commit_file(
+            &repo,
+            "hotfix.txt",
+            "fix\n",
+            "fix: hotfix pr:hotfix pr:base:release",
+        );
+        commit_file(&repo, "hotfix.txt", "fix 2\n", "fix: follow-up");
+        commit_file(&repo, "docs.txt", "docs\n", "docs: notes pr:docs");
+        let _guard = DirGuard::change_to(&repo);
+
+        let (_merge_base, groups) = derive_groups_between("main", "HEAD", "ignore").unwrap();
+        let mut segments = split_segments("main", groups);
+
+        let shape: Vec<(String, Vec<String>)> = segments
+            .iter()
+            .map(|segment| {
+                (
+                    segment.base.clone(),
+                    segment.groups.iter().map(|g| g.selector_text()).collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            shape,
+            vec![
+                ("main".to_string(), vec!["pr:alpha".to_string()]),
+                (
+                    "release".to_string(),
+                    vec!["pr:hotfix".to_string(), "pr:docs".to_string()]
+                ),
+            ]
+        );
+
+        replay_segment_onto_base(&mut segments[1]).unwrap();
+        let first_run = segments[1].groups[1].commits.last().cloned().unwrap();
+        assert_eq!(
+            git(
+                &repo,
+                ["log", "--format=%s", &format!("release..{first_run}")].as_slice()
+            ),
+            "docs: notes pr:docs\nfix: follow-up\nfix: hotfix pr:hotfix pr:base:release\n"
+        );
+        assert!(
+            git(&repo, ["ls-tree", "--name-only", &first_run].as_slice())
+                .lines()
+                .all(|file| file != "alpha.txt")
+        );
+
+        let (_merge_base, groups) = derive_groups_between("main", "HEAD", "ignore").unwrap();
+        let mut again = split_segments("main", groups);
+        replay_segment_onto_base(&mut again[1]).unwrap();
+        assert_eq!(again[1].groups[1].commits.last(), Some(&first_run));
+    }
+
+    #[test]
+    fn active_stack_gets_its_own_chain_past_other_stacks_commits() {
+        let _lock = lock_cwd();
+        let dir = init_repo();
+        let repo = dir.path().to_path_buf();
+        git(&repo, ["checkout", "-b", "stack"].as_slice());
+        commit_file(&repo, "web.txt", "login\n", "feat: login page pr:web/login");
+        commit_file(
+            &repo,
+            "api.txt",
+            "client\n",
+            "feat: api client pr:api/client",
+        );
+        commit_file(
+            &repo,
+            "web.txt",
+            "login 2\n",
+            "feat: signup page pr:web/signup",
+        );
+        commit_file(
+            &repo,
+            "api.txt",
+            "client 2\n",
+            "feat: api retries pr:api/retries",
+        );
+        let _guard = DirGuard::change_to(&repo);
+        let _stack = ACTIVE_STACK.scoped("api".to_string());
+
+        let (merge_base, mut leading_ignored, mut groups) =
+            derive_groups_between_with_ignored("main", "HEAD", "ignore").unwrap();
+        isolate_active_stack(
+            &merge_base,
+            &format!("{merge_base}..HEAD"),
+            &mut leading_ignored,
+            &mut groups,
+        )
+        .unwrap();
+        let (publishable, skipped) = split_groups_for_update(&leading_ignored, groups);
+
+        assert!(skipped.is_empty(), "{skipped:?}");
+        assert_eq!(
+            publishable
+                .iter()
+                .map(|group| group.selector_text())
+                .collect::<Vec<_>>(),
+            vec!["pr:api/client", "pr:api/retries"]
+        );
+        let tip = publishable[1].commits.last().unwrap();
+        assert_eq!(
+            git(
+                &repo,
+                ["log", "--format=%s", &format!("main..{tip}")].as_slice()
+            ),
+            "feat: api retries pr:api/retries\nfeat: api client pr:api/client\n"
+        );
+        assert!(git(&repo, ["ls-tree", "--name-only", tip].as_slice())
+            .lines()
+            .all(|file| file != "web.txt"));
+    }
+}
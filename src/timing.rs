@@ -0,0 +1,150 @@
+//! `--timing` breakdown of where a run spends its time.
+//!
+//! Every `git`/`gh` invocation through [`crate::git::run`] reports its duration here. With
+//! `--timing`, `spr` groups those durations into phases (stack parsing, `ls-remote`, pushes,
+//! GraphQL batches, ...) and prints a summary to stderr when the command finishes, so stdout stays
+//! machine-readable under `--json`.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+static STARTED_AT: Mutex<Option<Instant>> = Mutex::new(None);
+static RECORDS: Mutex<Vec<(&'static str, Duration)>> = Mutex::new(Vec::new());
+
+/// Start recording command durations for this process.
+pub fn enable() {
+    *STARTED_AT.lock().unwrap() = Some(Instant::now());
+}
+
+fn enabled() -> bool {
+    STARTED_AT
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .is_some()
+}
+
+/// Phase a `bin args` invocation is reported under.
+fn command_phase(bin: &str, args: &[&str]) -> &'static str {
+    let mut rest = args;
+    while let [flag, _, tail @ ..] = rest {
+        if *flag == "-C" || *flag == "-c" {
+            rest = tail;
+        } else {
+            break;
+        }
+    }
+    match (bin, rest) {
+        ("git", ["push", ..]) => "pushes",
+        ("git", ["ls-remote", ..]) => "ls-remote",
+        ("git", ["fetch", ..]) => "fetches",
+        ("git", ["log" | "rev-list" | "merge-base" | "diff-tree", ..]) => "parsing",
+        ("git", ["cherry-pick" | "rebase" | "worktree" | "commit-tree", ..]) => "rewrites",
+        ("git", _) => "other git",
+        ("gh" | "glab", ["api", "graphql", ..]) => "GraphQL batches",
+        ("gh" | "glab", ["api", ..]) => "REST calls",
+        _ => "other gh",
+    }
+}
+
+/// Record that `bin args` took `elapsed`; a no-op unless `--timing` is on.
+pub fn record(bin: &str, args: &[&str], elapsed: Duration) {
+    if enabled() {
+        RECORDS
+            .lock()
+            .unwrap()
+            .push((command_phase(bin, args), elapsed));
+    }
+}
+
+fn render_summary(records: &[(&'static str, Duration)], total: Duration) -> Vec<String> {
+    let mut phases: Vec<(&'static str, usize, Duration)> = Vec::new();
+    for (phase, elapsed) in records {
+        match phases.iter_mut().find(|(name, _, _)| name == phase) {
+            Some((_, count, sum)) => {
+                *count += 1;
+                *sum += *elapsed;
+            }
+            None => phases.push((phase, 1, *elapsed)),
+        }
+    }
+    phases.sort_by(|a, b| b.2.cmp(&a.2).then(a.0.cmp(b.0)));
+    let commands: Duration = phases.iter().map(|(_, _, sum)| *sum).sum();
+    let mut lines = vec!["Timing:".to_string()];
+    for (phase, count, sum) in &phases {
+        lines.push(format!(
+            "  {:<16} {:>9.1} ms  ({} call(s))",
+            phase,
+            sum.as_secs_f64() * 1000.0,
+            count
+        ));
+    }
+    lines.push(format!(
+        "  {:<16} {:>9.1} ms",
+        "in-process",
+        total.saturating_sub(commands).as_secs_f64() * 1000.0
+    ));
+    lines.push(format!(
+        "  {:<16} {:>9.1} ms",
+        "total",
+        total.as_secs_f64() * 1000.0
+    ));
+    lines
+}
+
+/// Print the phase summary to stderr if `--timing` is on.
+pub fn print_summary() {
+    let Some(started_at) = *STARTED_AT.lock().unwrap() else {
+        return;
+    };
+    for line in render_summary(&RECORDS.lock().unwrap(), started_at.elapsed()) {
+        eprintln!("{line}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{command_phase, render_summary};
+    use std::time::Duration;
+
+    #[test]
+    fn command_phase_skips_repo_flags_and_splits_gh_api() {
+        assert_eq!(
+            command_phase("git", &["-C", "/tmp/spr-x", "push", "origin"]),
+            "pushes"
+        );
+        assert_eq!(command_phase("git", &["ls-remote", "origin"]), "ls-remote");
+        assert_eq!(command_phase("git", &["log", "--reverse"]), "parsing");
+        assert_eq!(
+            command_phase("gh", &["api", "graphql", "-f"]),
+            "GraphQL batches"
+        );
+        assert_eq!(
+            command_phase("gh", &["api", "repos/o/n/pulls"]),
+            "REST calls"
+        );
+        assert_eq!(command_phase("gh", &["pr", "view"]), "other gh");
+    }
+
+    #[test]
+    fn summary_orders_phases_by_time_and_reports_in_process_remainder() {
+        let ms = Duration::from_millis;
+        let lines = render_summary(
+            &[
+                ("parsing", ms(10)),
+                ("pushes", ms(200)),
+                ("parsing", ms(15)),
+            ],
+            ms(300),
+        );
+        assert_eq!(
+            lines,
+            vec![
+                "Timing:".to_string(),
+                "  pushes               200.0 ms  (1 call(s))".to_string(),
+                "  parsing               25.0 ms  (2 call(s))".to_string(),
+                "  in-process            75.0 ms".to_string(),
+                "  total                300.0 ms".to_string(),
+            ]
+        );
+    }
+}
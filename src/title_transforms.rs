@@ -0,0 +1,183 @@
+//! Config-defined rewrites applied to PR titles derived from group subjects.
+//!
+//! Transforms run after group markers and directives are stripped, in a fixed order: configured
+//! prefixes are removed, capitalization is applied, and finally a ticket ID extracted from the
+//! group's tag is appended. The default config leaves titles untouched.
+
+use anyhow::{Context, Result};
+use regex::RegexBuilder;
+use serde::Deserialize;
+
+/// How `spr` adjusts the first character of a PR title.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TitleCapitalization {
+    /// Leave the title as written.
+    #[default]
+    Preserve,
+    /// Uppercase the first character of the title.
+    Upper,
+    /// Lowercase the first character of the title.
+    Lower,
+}
+
+/// The `title_transforms` config block.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct TitleTransformConfig {
+    /// Prefixes removed (case-insensitively, repeatedly) from the start of the title, e.g. `WIP:`.
+    pub strip_prefixes: Vec<String>,
+    pub capitalize: TitleCapitalization,
+    /// Case-insensitive regex matched against the group's tag; the first match (uppercased) is
+    /// appended to the title unless the title already mentions it.
+    pub ticket_pattern: Option<String>,
+    /// Template used to append the ticket; `{title}` and `{ticket}` are substituted.
+    /// Defaults to `{title} ({ticket})`.
+    pub ticket_format: Option<String>,
+}
+
+const DEFAULT_TICKET_FORMAT: &str = "{title} ({ticket})";
+
+impl TitleTransformConfig {
+    /// Rejects an invalid `ticket_pattern` up front instead of on first use.
+    pub fn validate(&self) -> Result<()> {
+        self.ticket_regex()?;
+        Ok(())
+    }
+
+    fn ticket_regex(&self) -> Result<Option<regex::Regex>> {
+        self.ticket_pattern
+            .as_deref()
+            .map(|pattern| {
+                RegexBuilder::new(pattern)
+                    .case_insensitive(true)
+                    .build()
+                    .with_context(|| format!("Invalid title_transforms.ticket_pattern `{pattern}`"))
+            })
+            .transpose()
+    }
+
+    /// Applies every configured transform to `title` for the group identified by `tag`.
+    pub fn apply(&self, title: &str, tag: &str) -> Result<String> {
+        let mut title = title.trim().to_string();
+        while let Some(rest) = self.strip_one_prefix(&title) {
+            title = rest;
+        }
+        title = match self.capitalize {
+            TitleCapitalization::Preserve => title,
+            TitleCapitalization::Upper => map_first_char(&title, char::to_uppercase),
+            TitleCapitalization::Lower => map_first_char(&title, char::to_lowercase),
+        };
+        if let Some(regex) = self.ticket_regex()? {
+            if let Some(found) = regex.find(tag) {
+                let ticket = found.as_str().to_uppercase();
+                if !title.to_uppercase().contains(&ticket) {
+                    title = self
+                        .ticket_format
+                        .as_deref()
+                        .unwrap_or(DEFAULT_TICKET_FORMAT)
+                        .replace("{title}", &title)
+                        .replace("{ticket}", &ticket);
+                }
+            }
+        }
+        Ok(title)
+    }
+
+    fn strip_one_prefix(&self, title: &str) -> Option<String> {
+        self.strip_prefixes.iter().find_map(|prefix| {
+            let prefix = prefix.trim();
+            if prefix.is_empty() {
+                return None;
+            }
+            let head = title.get(..prefix.len())?;
+            if !head.eq_ignore_ascii_case(prefix) {
+                return None;
+            }
+            let rest = title[prefix.len()..].trim_start();
+            (!rest.is_empty()).then(|| rest.to_string())
+        })
+    }
+}
+
+fn map_first_char<I>(text: &str, map: impl Fn(char) -> I) -> String
+where
+    I: Iterator<Item = char>,
+{
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => map(first).chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TitleCapitalization, TitleTransformConfig};
+
+    #[test]
+    fn default_config_keeps_titles() {
+        let config = TitleTransformConfig::default();
+
+        assert_eq!(
+            config.apply("wip: alpha", "proj-12-alpha").unwrap(),
+            "wip: alpha"
+        );
+    }
+
+    #[test]
+    fn strips_prefixes_then_capitalizes() {
+        let config = TitleTransformConfig {
+            strip_prefixes: vec!["WIP:".to_string(), "[draft]".to_string()],
+            capitalize: TitleCapitalization::Upper,
+            ..TitleTransformConfig::default()
+        };
+
+        assert_eq!(
+            config.apply("[Draft] wip: add alpha", "alpha").unwrap(),
+            "Add alpha"
+        );
+        assert_eq!(config.apply("WIP:", "alpha").unwrap(), "WIP:");
+    }
+
+    #[test]
+    fn appends_ticket_from_tag_once() {
+        let config = TitleTransformConfig {
+            ticket_pattern: Some(r"[a-z]+-\d+".to_string()),
+            ..TitleTransformConfig::default()
+        };
+
+        assert_eq!(
+            config.apply("Add alpha", "proj-12-alpha").unwrap(),
+            "Add alpha (PROJ-12)"
+        );
+        assert_eq!(
+            config.apply("PROJ-12: add alpha", "proj-12-alpha").unwrap(),
+            "PROJ-12: add alpha"
+        );
+        assert_eq!(config.apply("Add beta", "beta").unwrap(), "Add beta");
+
+        let bracketed = TitleTransformConfig {
+            ticket_format: Some("[{ticket}] {title}".to_string()),
+            ..config
+        };
+        assert_eq!(
+            bracketed.apply("Add alpha", "proj-12-alpha").unwrap(),
+            "[PROJ-12] Add alpha"
+        );
+    }
+
+    #[test]
+    fn invalid_ticket_pattern_is_reported() {
+        let config = TitleTransformConfig {
+            ticket_pattern: Some("(".to_string()),
+            ..TitleTransformConfig::default()
+        };
+
+        assert!(config
+            .validate()
+            .unwrap_err()
+            .to_string()
+            .contains("ticket_pattern"));
+    }
+}
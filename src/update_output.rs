@@ -15,9 +15,44 @@ pub struct UpdateSummaryData {
     pub warnings: Vec<String>,
     pub skipped_groups: Vec<SkippedUpdateGroupData>,
     pub groups: Vec<UpdateGroupData>,
+    pub metrics: UpdateMetrics,
     pub local_pr_branch_actions: Vec<LocalPrBranchAction>,
 }
 
+/// Per-action counts over an update's groups, for the end-of-run summary.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct UpdateMetrics {
+    pub branches_unchanged: usize,
+    pub branches_created: usize,
+    pub branches_fast_forwarded: usize,
+    pub branches_force_pushed: usize,
+    pub prs_created: usize,
+    /// Existing PRs whose base ref or description was edited.
+    pub prs_edited: usize,
+    pub prs_unchanged: usize,
+}
+
+impl UpdateMetrics {
+    pub fn from_groups(groups: &[UpdateGroupData]) -> Self {
+        let mut metrics = Self::default();
+        for group in groups {
+            match group.push_action {
+                UpdatePushAction::Unchanged => metrics.branches_unchanged += 1,
+                UpdatePushAction::CreateBranch => metrics.branches_created += 1,
+                UpdatePushAction::FastForwardBranch => metrics.branches_fast_forwarded += 1,
+                UpdatePushAction::ForcePushBranch => metrics.branches_force_pushed += 1,
+            }
+            match group.pr_action {
+                UpdatePrAction::NotRequested => {}
+                UpdatePrAction::Created => metrics.prs_created += 1,
+                UpdatePrAction::Existing if group_pr_edited(group) => metrics.prs_edited += 1,
+                UpdatePrAction::Existing => metrics.prs_unchanged += 1,
+            }
+        }
+        metrics
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct UpdateRepoContext {
     pub base: String,
@@ -115,12 +150,340 @@ impl UpdateSummaryData {
             extent,
             warnings: execution.warnings,
             skipped_groups: execution.skipped_groups,
+            metrics: UpdateMetrics::from_groups(&execution.groups),
             groups: execution.groups,
             local_pr_branch_actions: execution.local_pr_branch_actions,
         }
     }
 }
 
+fn push_action_text(action: UpdatePushAction) -> &'static str {
+    match action {
+        UpdatePushAction::Unchanged => "unchanged",
+        UpdatePushAction::CreateBranch => "create branch",
+        UpdatePushAction::FastForwardBranch => "fast-forward",
+        UpdatePushAction::ForcePushBranch => "force-push",
+    }
+}
+
+fn edit_action_text(action: UpdateEditAction) -> &'static str {
+    match action {
+        UpdateEditAction::NotRequested => "—",
+        UpdateEditAction::Unchanged => "unchanged",
+        UpdateEditAction::Updated => "update",
+    }
+}
+
+fn pr_text(group: &UpdateGroupData) -> String {
+    match (group.pr_action, group.remote_pr_number) {
+        (UpdatePrAction::NotRequested, _) => "—".to_string(),
+        (UpdatePrAction::Created, Some(number)) => format!("create #{number}"),
+        (UpdatePrAction::Created, None) => "create".to_string(),
+        (UpdatePrAction::Existing, Some(number)) => format!("#{number}"),
+        (UpdatePrAction::Existing, None) => "existing".to_string(),
+    }
+}
+
+fn group_pr_edited(group: &UpdateGroupData) -> bool {
+    group.base_ref_action == UpdateEditAction::Updated
+        || group.description_action == UpdateEditAction::Updated
+}
+
+/// Why the group's branch and PR ended up as they did, e.g. `force-pushed (history rewritten);
+/// PR #12 edited (base)`.
+fn group_reason(group: &UpdateGroupData) -> String {
+    let push = match group.push_action {
+        UpdatePushAction::Unchanged => "branch unchanged (remote already at the group tip)",
+        UpdatePushAction::CreateBranch => "branch created",
+        UpdatePushAction::FastForwardBranch => "fast-forwarded (new commits on top)",
+        UpdatePushAction::ForcePushBranch => "force-pushed (history rewritten)",
+    };
+    let number = group
+        .remote_pr_number
+        .map_or_else(|| "PR".to_string(), |number| format!("PR #{number}"));
+    let pr = match group.pr_action {
+        UpdatePrAction::NotRequested => "no PR requested".to_string(),
+        UpdatePrAction::Created => format!("{number} created"),
+        UpdatePrAction::Existing if group_pr_edited(group) => {
+            let mut edited = Vec::new();
+            if group.base_ref_action == UpdateEditAction::Updated {
+                edited.push("base");
+            }
+            if group.description_action == UpdateEditAction::Updated {
+                edited.push("description");
+            }
+            format!("{number} edited ({})", edited.join(", "))
+        }
+        UpdatePrAction::Existing => format!("{number} unchanged"),
+    };
+    format!("{push}; {pr}")
+}
+
+fn metrics_text(metrics: &UpdateMetrics, no_pr: bool) -> String {
+    let branches = format!(
+        "branches: {} force-pushed, {} fast-forwarded, {} created, {} unchanged",
+        metrics.branches_force_pushed,
+        metrics.branches_fast_forwarded,
+        metrics.branches_created,
+        metrics.branches_unchanged
+    );
+    if no_pr {
+        branches
+    } else {
+        format!(
+            "{branches}; PRs: {} created, {} edited, {} unchanged",
+            metrics.prs_created, metrics.prs_edited, metrics.prs_unchanged
+        )
+    }
+}
+
+/// Human end-of-run summary: counts, then one reason line per group.
+pub fn render_summary_lines(data: &UpdateSummaryData) -> Vec<String> {
+    if data.groups.is_empty() {
+        return Vec::new();
+    }
+    let mut lines = vec![format!(
+        "{}{}",
+        if data.options.dry_run {
+            "Update plan (dry run): "
+        } else {
+            "Update summary: "
+        },
+        metrics_text(&data.metrics, data.options.no_pr)
+    )];
+    for group in &data.groups {
+        lines.push(format!(
+            "  {:>2}. {}: {}",
+            group.local_pr_number,
+            group.stable_handle,
+            group_reason(group)
+        ));
+    }
+    lines
+}
+
+/// Render an update summary as a Markdown plan for change tickets and CI artifacts.
+pub fn render_plan_markdown(data: &UpdateSummaryData) -> String {
+    let mut lines = vec![
+        "# spr update plan".to_string(),
+        String::new(),
+        format!(
+            "- Base: `{}`, prefix: `{}`, from: `{}`",
+            data.repo.base, data.repo.prefix, data.repo.from
+        ),
+        format!(
+            "- Mode: {}",
+            if data.options.dry_run {
+                "dry run (nothing was pushed or edited)"
+            } else {
+                "applied"
+            }
+        ),
+        format!(
+            "- Extent: {}",
+            match data.extent {
+                ResolvedUpdateLimit::All => "all groups".to_string(),
+                ResolvedUpdateLimit::ByPr { count } => format!("bottom {count} group(s)"),
+            }
+        ),
+        format!(
+            "- Totals: {}",
+            metrics_text(&data.metrics, data.options.no_pr)
+        ),
+        String::new(),
+        "## Groups".to_string(),
+        String::new(),
+        "| # | Group | Title | Branch | Base | Push | PR | Base edit | Description |".to_string(),
+        "|---|---|---|---|---|---|---|---|---|".to_string(),
+    ];
+    for group in &data.groups {
+        lines.push(format!(
+            "| {} | `{}` | {} | `{}` | `{}` | {} | {} | {} | {} |",
+            group.local_pr_number,
+            group.stable_handle,
+            group.title.replace('|', "\\|"),
+            group.head_branch,
+            group.base_ref,
+            push_action_text(group.push_action),
+            pr_text(group),
+            edit_action_text(group.base_ref_action),
+            edit_action_text(group.description_action),
+        ));
+    }
+    if !data.skipped_groups.is_empty() {
+        lines.extend([String::new(), "## Skipped".to_string(), String::new()]);
+        for skipped in &data.skipped_groups {
+            lines.push(format!(
+                "- `{}`: held back by an ignore block below it",
+                skipped.stable_handle
+            ));
+        }
+    }
+    if !data.warnings.is_empty() {
+        lines.extend([String::new(), "## Warnings".to_string(), String::new()]);
+        lines.extend(data.warnings.iter().map(|warning| format!("- {warning}")));
+    }
+    lines.join("\n") + "\n"
+}
+
 pub fn summary(data: UpdateSummaryData) -> UpdateOutput {
     SummaryOutput::new(JsonCommand::Update, data)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        render_plan_markdown, render_summary_lines, ResolvedUpdateLimit, SkippedUpdateGroupData,
+        UpdateEditAction, UpdateGroupData, UpdateMetrics, UpdateOptions, UpdatePrAction,
+        UpdatePushAction, UpdateRepoContext, UpdateSkippedReason, UpdateSummaryData,
+    };
+    use crate::config::{LocalPrBranchSyncPolicy, PrDescriptionMode};
+
+    #[test]
+    fn plan_markdown_lists_groups_skips_and_warnings() {
+        let data = UpdateSummaryData {
+            repo: UpdateRepoContext {
+                base: "main".to_string(),
+                from: "HEAD".to_string(),
+                prefix: "dank-spr/".to_string(),
+                ignore_tag: "ignore".to_string(),
+            },
+            options: UpdateOptions {
+                dry_run: true,
+                no_pr: false,
+                pr_description_mode: PrDescriptionMode::Overwrite,
+                local_pr_branches: LocalPrBranchSyncPolicy::Off,
+            },
+            extent: ResolvedUpdateLimit::ByPr { count: 1 },
+            warnings: vec!["subject too long".to_string()],
+            skipped_groups: vec![SkippedUpdateGroupData {
+                stable_handle: "pr:beta".to_string(),
+                reason: UpdateSkippedReason::IgnoredBoundary,
+            }],
+            groups: vec![UpdateGroupData {
+                local_pr_number: 1,
+                stable_handle: "pr:alpha".to_string(),
+                head_branch: "dank-spr/alpha".to_string(),
+                base_ref: "main".to_string(),
+                title: "feat: a|b".to_string(),
+                target_sha: "abc".to_string(),
+                push_action: UpdatePushAction::CreateBranch,
+                pr_action: UpdatePrAction::Created,
+                base_ref_action: UpdateEditAction::Unchanged,
+                description_action: UpdateEditAction::Updated,
+                remote_pr_number: None,
+                remote_pr_url: None,
+            }],
+            metrics: UpdateMetrics {
+                branches_created: 1,
+                prs_created: 1,
+                ..UpdateMetrics::default()
+            },
+            local_pr_branch_actions: Vec::new(),
+        };
+
+        let markdown = render_plan_markdown(&data);
+
+        assert!(markdown.starts_with("# spr update plan\n"));
+        assert!(markdown.contains("- Extent: bottom 1 group(s)\n"));
+        assert!(markdown.contains(
+            "- Totals: branches: 0 force-pushed, 0 fast-forwarded, 1 created, 0 unchanged; PRs: 1 created, 0 edited, 0 unchanged\n"
+        ));
+        assert!(markdown.contains(
+            "| 1 | `pr:alpha` | feat: a\\|b | `dank-spr/alpha` | `main` | create branch | create | unchanged | update |\n"
+        ));
+        assert!(
+            markdown.contains("## Skipped\n\n- `pr:beta`: held back by an ignore block below it\n")
+        );
+        assert!(markdown.ends_with("## Warnings\n\n- subject too long\n"));
+    }
+
+    fn group(
+        local_pr_number: usize,
+        push_action: UpdatePushAction,
+        pr_action: UpdatePrAction,
+        base_ref_action: UpdateEditAction,
+    ) -> UpdateGroupData {
+        UpdateGroupData {
+            local_pr_number,
+            stable_handle: format!("pr:g{local_pr_number}"),
+            head_branch: format!("dank-spr/g{local_pr_number}"),
+            base_ref: "main".to_string(),
+            title: String::new(),
+            target_sha: "abc".to_string(),
+            push_action,
+            pr_action,
+            base_ref_action,
+            description_action: UpdateEditAction::Unchanged,
+            remote_pr_number: Some(10 + local_pr_number as u64),
+            remote_pr_url: None,
+        }
+    }
+
+    #[test]
+    fn summary_lines_count_actions_and_explain_each_group() {
+        let groups = vec![
+            group(
+                1,
+                UpdatePushAction::Unchanged,
+                UpdatePrAction::Existing,
+                UpdateEditAction::Unchanged,
+            ),
+            group(
+                2,
+                UpdatePushAction::ForcePushBranch,
+                UpdatePrAction::Existing,
+                UpdateEditAction::Updated,
+            ),
+            group(
+                3,
+                UpdatePushAction::CreateBranch,
+                UpdatePrAction::Created,
+                UpdateEditAction::NotRequested,
+            ),
+        ];
+        let metrics = UpdateMetrics::from_groups(&groups);
+        assert_eq!(
+            metrics,
+            UpdateMetrics {
+                branches_unchanged: 1,
+                branches_created: 1,
+                branches_fast_forwarded: 0,
+                branches_force_pushed: 1,
+                prs_created: 1,
+                prs_edited: 1,
+                prs_unchanged: 1,
+            }
+        );
+        let data = UpdateSummaryData {
+            repo: UpdateRepoContext {
+                base: "main".to_string(),
+                from: "HEAD".to_string(),
+                prefix: "dank-spr/".to_string(),
+                ignore_tag: "ignore".to_string(),
+            },
+            options: UpdateOptions {
+                dry_run: false,
+                no_pr: false,
+                pr_description_mode: PrDescriptionMode::Overwrite,
+                local_pr_branches: LocalPrBranchSyncPolicy::Off,
+            },
+            extent: ResolvedUpdateLimit::All,
+            warnings: Vec::new(),
+            skipped_groups: Vec::new(),
+            groups,
+            metrics,
+            local_pr_branch_actions: Vec::new(),
+        };
+
+        assert_eq!(
+            render_summary_lines(&data),
+            vec![
+                "Update summary: branches: 1 force-pushed, 0 fast-forwarded, 1 created, 1 unchanged; PRs: 1 created, 1 edited, 1 unchanged".to_string(),
+                "   1. pr:g1: branch unchanged (remote already at the group tip); PR #11 unchanged".to_string(),
+                "   2. pr:g2: force-pushed (history rewritten); PR #12 edited (base)".to_string(),
+                "   3. pr:g3: branch created; PR #13 created".to_string(),
+            ]
+        );
+    }
+}
@@ -0,0 +1,205 @@
+//! Progress journal for interrupted `spr update` runs.
+//!
+//! Each applied `spr update` records the PRs it created under the repository common Git directory
+//! (`spr/update_progress.json`) as it goes, one entry per published stack and segment base, and
+//! clears the stack's entries once every segment is published. When a run is interrupted (network
+//! drop, Ctrl-C), re-running `spr update` on the same stack tip reuses the recorded PR numbers instead of trusting a possibly lagging PR
+//! listing, so a PR created just before the interruption is not created a second time. Pushes and
+//! PR edits need no journal: both are re-planned against the remote branches and the PRs' current
+//! bases and bodies, so whatever already landed before the interruption is skipped.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const UPDATE_PROGRESS_SCHEMA_VERSION: u32 = 1;
+const UPDATE_PROGRESS_FILE_NAME: &str = "update_progress.json";
+
+/// What one in-flight `spr update` has already done for one segment of a stack.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpdateProgress {
+    /// The published stack ref: the checked-out branch, the `--from` ref, or the detached commit.
+    pub stack_branch: String,
+    /// Base of the segment (`base`, or a `pr:base:` ref) whose PR chain this entry covers.
+    #[serde(default)]
+    pub base: String,
+    /// Tip of the topmost published group; a run against a different tip starts fresh.
+    pub tip: String,
+    /// PRs created by the run, keyed by head branch.
+    pub created_prs: BTreeMap<String, u64>,
+}
+
+impl UpdateProgress {
+    pub fn new(stack_branch: &str, base: &str, tip: &str) -> Self {
+        Self {
+            stack_branch: stack_branch.to_string(),
+            base: base.to_string(),
+            tip: tip.to_string(),
+            created_prs: BTreeMap::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.created_prs.is_empty()
+    }
+
+    fn same_segment(&self, other: &UpdateProgress) -> bool {
+        self.stack_branch == other.stack_branch && self.base == other.base
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct UpdateProgressFile {
+    schema_version: u32,
+    runs: Vec<UpdateProgress>,
+}
+
+fn progress_path(git_common_dir: &Path) -> PathBuf {
+    git_common_dir.join("spr").join(UPDATE_PROGRESS_FILE_NAME)
+}
+
+fn read_runs(path: &Path) -> Result<Vec<UpdateProgress>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read update progress: {}", path.display()))?;
+    let file: UpdateProgressFile = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse update progress: {}", path.display()))?;
+    Ok(file.runs)
+}
+
+fn write_runs(path: &Path, runs: Vec<UpdateProgress>) -> Result<()> {
+    if runs.is_empty() {
+        if path.exists() {
+            fs::remove_file(path)
+                .with_context(|| format!("Failed to remove update progress: {}", path.display()))?;
+        }
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let file = UpdateProgressFile {
+        schema_version: UPDATE_PROGRESS_SCHEMA_VERSION,
+        runs,
+    };
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_string_pretty(&file)? + "\n")
+        .with_context(|| format!("Failed to write update progress: {}", tmp.display()))?;
+    fs::rename(&tmp, path)
+        .with_context(|| format!("Failed to replace update progress: {}", path.display()))?;
+    Ok(())
+}
+
+/// Return the recorded progress for the `base` segment of `stack_branch` at `tip`, or a fresh
+/// entry.
+///
+/// Progress recorded against another tip is stale and ignored; the next save replaces it.
+pub fn load_progress(
+    git_common_dir: &Path,
+    stack_branch: &str,
+    base: &str,
+    tip: &str,
+) -> Result<UpdateProgress> {
+    let fresh = UpdateProgress::new(stack_branch, base, tip);
+    Ok(read_runs(&progress_path(git_common_dir))?
+        .into_iter()
+        .find(|run| run.same_segment(&fresh) && run.tip == tip)
+        .unwrap_or(fresh))
+}
+
+/// Persist `progress`, replacing any earlier entry for the same stack segment.
+pub fn save_progress(git_common_dir: &Path, progress: &UpdateProgress) -> Result<()> {
+    let path = progress_path(git_common_dir);
+    let mut runs = read_runs(&path)?;
+    runs.retain(|run| !run.same_segment(progress));
+    runs.push(progress.clone());
+    write_runs(&path, runs)
+}
+
+/// Drop every segment entry for `stack_branch` after all of its segments were published.
+pub fn clear_progress(git_common_dir: &Path, stack_branch: &str) -> Result<()> {
+    let path = progress_path(git_common_dir);
+    let mut runs = read_runs(&path)?;
+    runs.retain(|run| run.stack_branch != stack_branch);
+    write_runs(&path, runs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{clear_progress, load_progress, progress_path, save_progress};
+
+    #[test]
+    fn progress_resumes_only_at_the_same_tip_and_clears_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let common = dir.path();
+
+        let mut progress = load_progress(common, "stack", "main", "aaa").unwrap();
+        assert!(progress.is_empty());
+        progress.created_prs.insert("dank-spr/one".to_string(), 12);
+        save_progress(common, &progress).unwrap();
+
+        assert_eq!(
+            load_progress(common, "stack", "main", "aaa").unwrap(),
+            progress
+        );
+        assert!(load_progress(common, "stack", "main", "bbb")
+            .unwrap()
+            .is_empty());
+        assert!(load_progress(common, "other", "main", "aaa")
+            .unwrap()
+            .is_empty());
+
+        clear_progress(common, "stack").unwrap();
+        assert!(load_progress(common, "stack", "main", "aaa")
+            .unwrap()
+            .is_empty());
+        assert!(!progress_path(common).exists());
+    }
+
+    #[test]
+    fn interrupted_second_segment_keeps_the_first_segments_created_prs() {
+        let dir = tempfile::tempdir().unwrap();
+        let common = dir.path();
+
+        // The first segment publishes fully, then the run is interrupted inside the second
+        let mut first = load_progress(common, "stack", "main", "aaa").unwrap();
+        first.created_prs.insert("dank-spr/alpha".to_string(), 10);
+        save_progress(common, &first).unwrap();
+        let mut second = load_progress(common, "stack", "release", "bbb").unwrap();
+        second.created_prs.insert("dank-spr/hotfix".to_string(), 11);
+        save_progress(common, &second).unwrap();
+        let mut unrelated = load_progress(common, "other", "main", "ccc").unwrap();
+        unrelated
+            .created_prs
+            .insert("dank-spr/beta".to_string(), 12);
+        save_progress(common, &unrelated).unwrap();
+
+        // The rerun resumes both segments
+        assert_eq!(
+            load_progress(common, "stack", "main", "aaa").unwrap(),
+            first
+        );
+        assert_eq!(
+            load_progress(common, "stack", "release", "bbb").unwrap(),
+            second
+        );
+
+        // Finishing every segment clears only this stack's entries
+        clear_progress(common, "stack").unwrap();
+        assert!(load_progress(common, "stack", "main", "aaa")
+            .unwrap()
+            .is_empty());
+        assert!(load_progress(common, "stack", "release", "bbb")
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            load_progress(common, "other", "main", "ccc").unwrap(),
+            unrelated
+        );
+    }
+}